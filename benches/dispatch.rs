@@ -0,0 +1,136 @@
+//! 事件交付延迟基准：内联回调 vs 无锁队列（ArrayQueue + 轮询线程）vs 通道（crossbeam channel）
+//!
+//! 每次迭代交付一个合成事件，并等待消费者确认处理完毕，测得的是"交付 → 消费者处理"的单事件延迟。
+//! 队列和通道模式需要消费者线程独占一个核，单核机器上的结果主要反映线程调度开销。
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use crossbeam_queue::ArrayQueue;
+use sol_parser_sdk::core::events::*;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn event() -> DexEvent {
+    DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+        metadata: EventMetadata {
+            signature: Signature::default(),
+            slot: 1,
+            tx_index: 0,
+            block_time_us: 0,
+            grpc_recv_us: 0,
+            handle_us: 0,
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        },
+        pool: Pubkey::new_unique(),
+        from: Pubkey::new_unique(),
+        start_bin_id: 0,
+        end_bin_id: 0,
+        amount_in: 1,
+        amount_out: 1,
+        swap_for_y: true,
+        fee: 0,
+        protocol_fee: 0,
+        fee_bps: 0,
+        host_fee: 0,
+    })
+}
+
+/// 等待消费者处理完第 `n` 个事件（让出 CPU，核数少时也不会饿死消费者线程）
+#[inline]
+fn wait_for(handled: &AtomicU64, n: u64) {
+    while handled.load(Ordering::Acquire) < n {
+        std::thread::yield_now();
+    }
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dispatch");
+    let template = event();
+
+    group.bench_function("inline", |b| {
+        let handled = AtomicU64::new(0);
+        let callback = |event: DexEvent| {
+            black_box(&event);
+            handled.fetch_add(1, Ordering::Release);
+        };
+        b.iter_custom(|iters| {
+            let start = Instant::now();
+            for _ in 0..iters {
+                callback(template.clone());
+            }
+            start.elapsed()
+        });
+    });
+
+    group.bench_function("queue", |b| {
+        b.iter_custom(|iters| {
+            let queue = Arc::new(ArrayQueue::<DexEvent>::new(1024));
+            let handled = Arc::new(AtomicU64::new(0));
+            let stop = Arc::new(AtomicBool::new(false));
+            let consumer = {
+                let (queue, handled, stop) = (queue.clone(), handled.clone(), stop.clone());
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        match queue.pop() {
+                            Some(event) => {
+                                black_box(&event);
+                                handled.fetch_add(1, Ordering::Release);
+                            }
+                            None => std::thread::yield_now(),
+                        }
+                    }
+                })
+            };
+            let mut total = Duration::ZERO;
+            for n in 1..=iters {
+                let start = Instant::now();
+                let _ = queue.push(template.clone());
+                wait_for(&handled, n);
+                total += start.elapsed();
+            }
+            stop.store(true, Ordering::Relaxed);
+            consumer.join().unwrap();
+            total
+        });
+    });
+
+    group.bench_function("channel", |b| {
+        b.iter_custom(|iters| {
+            let (tx, rx) = crossbeam_channel::unbounded::<DexEvent>();
+            let handled = Arc::new(AtomicU64::new(0));
+            let consumer = {
+                let handled = handled.clone();
+                std::thread::spawn(move || {
+                    for event in rx {
+                        black_box(&event);
+                        handled.fetch_add(1, Ordering::Release);
+                    }
+                })
+            };
+            let mut total = Duration::ZERO;
+            for n in 1..=iters {
+                let start = Instant::now();
+                tx.send(template.clone()).unwrap();
+                wait_for(&handled, n);
+                total += start.elapsed();
+            }
+            drop(tx);
+            consumer.join().unwrap();
+            total
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_dispatch);
+criterion_main!(benches);
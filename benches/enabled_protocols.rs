@@ -0,0 +1,68 @@
+//! 协议开关基准：同一笔多协议交易（合成指令 + Program data 日志），全部启用 vs 只启用 PumpFun
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion};
+use sol_parser_sdk::core::ParseOptions;
+use sol_parser_sdk::grpc::{EnabledProtocols, Protocol};
+use sol_parser_sdk::instr::{self, program_ids::*};
+use sol_parser_sdk::logs::{self, optimized_matcher::{detect_log_type, parse_log_with_type, program_id_strings}};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+
+fn invoke(logs: &mut Vec<String>, program_id: &str, discriminator: [u8; 8], body_len: usize) {
+    let mut data = discriminator.to_vec();
+    data.extend((0..body_len).map(|i| (i * 7 + 3) as u8));
+    logs.push(format!("Program {} invoke [1]", program_id));
+    logs.push(format!("Program data: {}", general_purpose::STANDARD.encode(&data)));
+    logs.push(format!("Program {} success", program_id));
+}
+
+fn instruction(discriminator: [u8; 8]) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    data.resize(64, 1);
+    data
+}
+
+fn bench_enabled_protocols(c: &mut Criterion) {
+    let mut tx_logs = Vec::new();
+    invoke(&mut tx_logs, program_id_strings::ORCA_WHIRLPOOL_ID, logs::orca_whirlpool::discriminators::TRADED_EVENT, 137);
+    invoke(&mut tx_logs, program_id_strings::METEORA_DAMM_V2_ID, logs::meteora_damm::discriminators::SWAP_EVENT, 129);
+    invoke(&mut tx_logs, program_id_strings::BONK_ID, logs::raydium_launchpad::discriminators::TRADE, 160);
+    invoke(&mut tx_logs, program_id_strings::RAYDIUM_CPMM_ID, logs::raydium_cpmm::discriminators::SWAP_BASE_IN, 89);
+    invoke(&mut tx_logs, program_id_strings::PUMPFUN_ID, logs::pumpfun::discriminators::TRADE_EVENT, 250);
+
+    let instructions = [
+        (ORCA_WHIRLPOOL_PROGRAM_ID, instruction(instr::orca_whirlpool::discriminators::SWAP)),
+        (METEORA_DAMM_V2_PROGRAM_ID, instruction(instr::meteora_damm::discriminators::SWAP)),
+        (BONK_PROGRAM_ID, instruction(instr::raydium_launchpad::discriminators::BUY_EXACT_IN)),
+        (RAYDIUM_CPMM_PROGRAM_ID, instruction(instr::raydium_cpmm::discriminators::SWAP_BASE_IN)),
+        (PUMPFUN_PROGRAM_ID, instruction(instr::pumpfun::discriminators::BUY)),
+    ];
+    let accounts: Vec<Pubkey> = (0..24).map(|_| Pubkey::new_unique()).collect();
+    let signature = Signature::default();
+
+    let parse_tx = |enabled_protocols: EnabledProtocols| {
+        let options = ParseOptions { enabled_protocols, ..Default::default() };
+        let mut count = black_box(&tx_logs)
+            .iter()
+            .filter_map(|log| {
+                parse_log_with_type(detect_log_type(log), log, signature, 1, 0, Some(0), 0, None, enabled_protocols, false)
+            })
+            .count();
+        for (program_id, data) in &instructions {
+            count += instr::parse_instruction_unified_with_recv(
+                black_box(data), &accounts, signature, 1, 0, Some(0), program_id, None, 0, options, None,
+            )
+            .is_some() as usize;
+        }
+        count
+    };
+
+    let mut group = c.benchmark_group("multi_protocol_tx");
+    group.bench_function("all_enabled", |b| b.iter(|| parse_tx(EnabledProtocols::all())));
+    group.bench_function("pumpfun_only", |b| b.iter(|| parse_tx(EnabledProtocols::only([Protocol::PumpFun]))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_enabled_protocols);
+criterion_main!(benches);
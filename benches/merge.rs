@@ -0,0 +1,134 @@
+//! 指令/日志事件合并基准：10k 混合事件，索引合并 vs 线性扫描
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use sol_parser_sdk::core::events::*;
+use sol_parser_sdk::merge_instruction_and_log_events;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+
+const EVENT_COUNT: usize = 10_000;
+
+fn metadata(i: usize) -> EventMetadata {
+    let mut sig = [0u8; 64];
+    sig[..8].copy_from_slice(&((i / 4) as u64).to_le_bytes());
+    EventMetadata {
+        signature: Signature::from(sig),
+        slot: i as u64,
+        tx_index: 0,
+        block_time_us: 0,
+        grpc_recv_us: 0,
+        handle_us: 0,
+        event_source: EventSource::Log,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
+    }
+}
+
+fn dlmm_swap(i: usize, pool: Pubkey, from: Pubkey) -> DexEvent {
+    DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+        metadata: metadata(i),
+        pool,
+        from,
+        start_bin_id: 0,
+        end_bin_id: 0,
+        amount_in: i as u64,
+        amount_out: 0,
+        swap_for_y: i.is_multiple_of(2),
+        fee: 0,
+        protocol_fee: 0,
+        fee_bps: 0,
+        host_fee: 0,
+    })
+}
+
+fn pumpswap_buy(i: usize, pool: Pubkey, user: Pubkey) -> DexEvent {
+    DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+        metadata: metadata(i),
+        pool_id: pool,
+        user,
+        token_mint: Pubkey::default(),
+        sol_amount: i as u64,
+        token_amount: 0,
+        price: 0,
+        slippage: 0,
+        pool_base_token_reserves: 0,
+        pool_quote_token_reserves: 0,
+        lp_fee: 0,
+        protocol_fee: 0,
+        coin_creator: Pubkey::default(),
+        coin_creator_fee: None,
+    })
+}
+
+/// 生成混合事件：一半日志事件、一半指令事件，指令事件约 3/4 能匹配上
+fn build_events() -> (Vec<DexEvent>, Vec<DexEvent>) {
+    let pools: Vec<Pubkey> = (0..64).map(|_| Pubkey::new_unique()).collect();
+    let user = Pubkey::new_unique();
+    let mut logs = Vec::with_capacity(EVENT_COUNT / 2);
+    let mut instrs = Vec::with_capacity(EVENT_COUNT / 2);
+    for i in 0..EVENT_COUNT / 2 {
+        let pool = pools[i % pools.len()];
+        if i % 2 == 0 {
+            logs.push(dlmm_swap(i, pool, Pubkey::default()));
+        } else {
+            logs.push(pumpswap_buy(i, pool, Pubkey::default()));
+        }
+        let instr_pool = if i % 4 == 3 { Pubkey::new_unique() } else { pool };
+        if i % 2 == 0 {
+            instrs.push(dlmm_swap(i, instr_pool, user));
+        } else {
+            instrs.push(pumpswap_buy(i, instr_pool, user));
+        }
+    }
+    (instrs, logs)
+}
+
+fn key(event: &DexEvent) -> Option<(std::mem::Discriminant<DexEvent>, Signature, Pubkey)> {
+    let pool = match event {
+        DexEvent::MeteoraDlmmSwap(e) => e.pool,
+        DexEvent::PumpSwapBuy(e) => e.pool_id,
+        _ => return None,
+    };
+    Some((std::mem::discriminant(event), event.metadata()?.signature, pool))
+}
+
+/// 对照组：每个指令事件反向线性扫描已合并事件
+fn merge_linear_scan(instruction_events: Vec<DexEvent>, log_events: Vec<DexEvent>) -> Vec<DexEvent> {
+    let mut merged = log_events;
+    let log_count = merged.len();
+    let mut used = vec![false; log_count];
+    for instr_event in instruction_events {
+        let k = key(&instr_event);
+        let found = (0..log_count).rev().find(|&i| !used[i] && key(&merged[i]) == k);
+        match found {
+            Some(i) => {
+                used[i] = true;
+                let _ = merged[i].clone();
+            }
+            None => merged.push(instr_event),
+        }
+    }
+    merged
+}
+
+fn bench_merge(c: &mut Criterion) {
+    let (instrs, logs) = build_events();
+
+    let mut group = c.benchmark_group("merge_10k_mixed");
+    group.sample_size(10);
+    group.bench_function("indexed", |b| {
+        b.iter(|| merge_instruction_and_log_events(black_box(instrs.clone()), black_box(logs.clone())))
+    });
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| merge_linear_scan(black_box(instrs.clone()), black_box(logs.clone())))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_merge);
+criterion_main!(benches);
@@ -0,0 +1,149 @@
+//! 解析基准：基于 `tests/fixtures/transactions` 中的夹具交易（目前均为合成交易），按协议测量单次操作耗时（ns/op）
+//!
+//! - `parse_log/<协议>`：单条日志走 [`parse_log_unified`]（只取能解析出事件的日志行；
+//!   没有 invoke 栈上下文时只有自带判别信息的日志能解析，其余协议没有该项）
+//! - `parse_instruction/<协议>`：单条外层指令走 [`parse_instruction_unified`]
+//! - `merge/<协议>`：整笔交易走 [`parse_transaction_events`]（日志 + 指令 + 合并）
+//! - `swap/<事件>`：产出该成交事件的交易走完整路径，覆盖 PumpFun 成交与 Raydium 各类 swap
+//!
+//! 每次迭代轮流取一个样本，criterion 报告的单次迭代时间即 ns/op。
+//! 运行：`cargo bench --features fixtures --bench parse_bench`
+
+use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use sol_parser_sdk::core::events::DexEvent;
+use sol_parser_sdk::fixtures::{load_dir, RecordedTransaction};
+use sol_parser_sdk::instr::parse_instruction_unified;
+use sol_parser_sdk::logs::parse_log_unified;
+use sol_parser_sdk::parse_transaction_events;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+use std::path::Path;
+
+/// 预先解码的交易，基准循环内不再做 hex / base58 解码
+struct Tx {
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: Pubkey,
+    data: Vec<u8>,
+    accounts: Vec<Pubkey>,
+    logs: Vec<String>,
+}
+
+impl Tx {
+    fn decode(tx: &RecordedTransaction) -> Self {
+        Self {
+            signature: tx.signature.parse().unwrap(),
+            slot: tx.slot,
+            tx_index: tx.tx_index,
+            block_time: tx.block_time,
+            program_id: tx.program_id.parse().unwrap(),
+            data: hex::decode(&tx.instruction_data).unwrap(),
+            accounts: tx.accounts.iter().map(|a| a.parse().unwrap()).collect(),
+            logs: tx.logs.clone(),
+        }
+    }
+
+    fn parse_instruction(&self) -> Option<DexEvent> {
+        parse_instruction_unified(
+            &self.data, &self.accounts, self.signature, self.slot, self.tx_index, self.block_time, &self.program_id,
+            None,
+        )
+    }
+
+    fn parse(&self) -> Vec<DexEvent> {
+        parse_transaction_events(
+            &self.data, &self.accounts, &self.logs, self.signature, self.slot, self.tx_index, self.block_time,
+            &self.program_id,
+        )
+    }
+}
+
+fn load() -> Vec<(String, Vec<Tx>)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/transactions");
+    load_dir(&dir)
+        .unwrap()
+        .into_iter()
+        .map(|(protocol, txs)| (protocol, txs.iter().map(Tx::decode).collect()))
+        .collect()
+}
+
+/// 成交事件基准：(名称, 是否为该成交事件)
+type SwapCase = (&'static str, fn(&DexEvent) -> bool);
+
+/// 每次迭代轮流处理一个样本
+fn cycle<T>(b: &mut Bencher, samples: &[T], mut op: impl FnMut(&T)) {
+    let mut i = 0;
+    b.iter(|| {
+        op(&samples[i]);
+        i = (i + 1) % samples.len();
+    });
+}
+
+fn bench_parsers(c: &mut Criterion) {
+    let fixtures = load();
+
+    let mut group = c.benchmark_group("parse_log");
+    for (protocol, txs) in &fixtures {
+        let lines: Vec<(&str, Signature, u64, Option<i64>)> = txs
+            .iter()
+            .flat_map(|tx| tx.logs.iter().map(move |log| (log.as_str(), tx.signature, tx.slot, tx.block_time)))
+            .filter(|&(log, signature, slot, block_time)| parse_log_unified(log, signature, slot, block_time).is_some())
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        group.bench_function(protocol.as_str(), |b| {
+            cycle(b, &lines, |&(log, signature, slot, block_time)| {
+                black_box(parse_log_unified(black_box(log), signature, slot, block_time));
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("parse_instruction");
+    for (protocol, txs) in &fixtures {
+        let txs: Vec<&Tx> = txs.iter().filter(|tx| tx.parse_instruction().is_some()).collect();
+        if txs.is_empty() {
+            continue;
+        }
+        group.bench_function(protocol.as_str(), |b| {
+            cycle(b, &txs, |tx| {
+                black_box(tx.parse_instruction());
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("merge");
+    for (protocol, txs) in &fixtures {
+        group.bench_function(protocol.as_str(), |b| {
+            cycle(b, txs, |tx| {
+                black_box(tx.parse());
+            })
+        });
+    }
+    group.finish();
+
+    let swaps: [SwapCase; 4] = [
+        ("pumpfun_trade", |e| matches!(e, DexEvent::PumpFunTrade(_))),
+        ("raydium_amm_v4_swap", |e| matches!(e, DexEvent::RaydiumAmmV4Swap(_))),
+        ("raydium_cpmm_swap", |e| matches!(e, DexEvent::RaydiumCpmmSwap(_))),
+        ("raydium_clmm_swap", |e| matches!(e, DexEvent::RaydiumClmmSwap(_))),
+    ];
+    let mut group = c.benchmark_group("swap");
+    for (name, is_swap) in swaps {
+        let txs: Vec<&Tx> = fixtures.iter().flat_map(|(_, txs)| txs).filter(|tx| tx.parse().iter().any(is_swap)).collect();
+        assert!(!txs.is_empty(), "no fixture produces {}", name);
+        group.bench_function(name, |b| {
+            cycle(b, &txs, |tx| {
+                black_box(tx.parse());
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parsers);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! 解析范围基准：合成的 PumpFun 成交区块（每笔交易一条 Buy 指令 + TradeEvent 日志），
+//! 对比 `ParseMode` 三种模式的单笔交易解析耗时，LogsOnly 与 Merged 的差值即跳过指令解析节省的时间
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use sol_parser_sdk::core::{parse_transaction_events_with_options, ParseMode, ParseOptions};
+use sol_parser_sdk::logs::optimized_matcher::program_id_strings::PUMPFUN_ID;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+use std::str::FromStr;
+
+const BLOCK_TXS: usize = 256;
+
+struct Tx {
+    data: Vec<u8>,
+    accounts: Vec<Pubkey>,
+    logs: Vec<String>,
+}
+
+fn pumpfun_block() -> Vec<Tx> {
+    (0..BLOCK_TXS)
+        .map(|i| {
+            let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+            let mut data = sol_parser_sdk::instr::pumpfun::discriminators::BUY.to_vec();
+            data.extend_from_slice(&(1_000_000 + i as u64).to_le_bytes());
+            data.extend_from_slice(&50_000_000u64.to_le_bytes());
+            let mut trade = sol_parser_sdk::logs::pumpfun::discriminators::TRADE_EVENT.to_vec();
+            trade.extend_from_slice(accounts[2].as_ref());
+            trade.extend((0..200).map(|j| (i + j) as u8));
+            let logs = vec![
+                "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+                "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+                format!("Program {} invoke [1]", PUMPFUN_ID),
+                "Program log: Instruction: Buy".to_string(),
+                format!("Program data: {}", general_purpose::STANDARD.encode(&trade)),
+                format!("Program {} consumed 31234 of 200000 compute units", PUMPFUN_ID),
+                format!("Program {} success", PUMPFUN_ID),
+            ];
+            Tx { data, accounts, logs }
+        })
+        .collect()
+}
+
+fn bench_parse_mode(c: &mut Criterion) {
+    let block = pumpfun_block();
+    let program_id = Pubkey::from_str(PUMPFUN_ID).unwrap();
+    let mut group = c.benchmark_group("parse_mode");
+    group.throughput(Throughput::Elements(BLOCK_TXS as u64));
+    for (name, mode) in [
+        ("merged", ParseMode::Merged),
+        ("logs_only", ParseMode::LogsOnly),
+        ("instructions_only", ParseMode::InstructionsOnly),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for tx in &block {
+                    black_box(parse_transaction_events_with_options(
+                        black_box(&tx.data),
+                        &tx.accounts,
+                        &tx.logs,
+                        Signature::default(),
+                        1,
+                        0,
+                        None,
+                        &program_id,
+                        ParseOptions { mode, ..Default::default() },
+                    ));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_mode);
+criterion_main!(benches);
@@ -0,0 +1,128 @@
+//! 解析线程池基准：500 笔交易同时到达时，交易从"可接收"到"事件入队"的 p99 延迟
+//!
+//! 模拟 `ClientConfig.parser_threads` 的两种模式：
+//! - `inline`：接收循环逐笔解析后入队，后面的交易要等前面的解析完成
+//! - `offload_N`：接收循环只把交易转交给 `ParserPool`，N 个解析线程并行解析
+//!
+//! 每次迭代返回该批交易的 p99 延迟，报告中的时间即平均 p99。
+//! 解析线程需要独占核心，单核机器上 offload 只能省去接收循环的排队，整体延迟不会下降。
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion};
+use crossbeam_queue::ArrayQueue;
+use sol_parser_sdk::core::events::DexEvent;
+use sol_parser_sdk::grpc::stream_health::StreamStats;
+use sol_parser_sdk::grpc::workers::ParserPool;
+use sol_parser_sdk::parse_logs_only;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use yellowstone_grpc_proto::prelude::{SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo, TransactionStatusMeta};
+
+const BURST: usize = 500;
+/// 每笔交易的 TradeEvent 数（模拟大交易）
+const EVENTS_PER_TX: usize = 20;
+const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const TRADE_EVENT: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+/// 合成 PumpFun 交易日志：每个 TradeEvent 一次 invoke
+fn burst() -> Vec<Vec<String>> {
+    (0..BURST)
+        .map(|_| {
+            let mut logs = Vec::new();
+            for _ in 0..EVENTS_PER_TX {
+                let mut data = TRADE_EVENT.to_vec();
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+                data.extend_from_slice(&5_000_000u64.to_le_bytes());
+                data.push(1);
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+                for v in [31_000_000_000u64, 1_000_000_000_000_000, 1_000_000_000, 793_000_000_000_000] {
+                    data.extend_from_slice(&v.to_le_bytes());
+                }
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.extend_from_slice(&100u64.to_le_bytes());
+                data.extend_from_slice(&10_000_000u64.to_le_bytes());
+                logs.push(format!("Program {} invoke [1]", PUMPFUN_PROGRAM_ID));
+                logs.push(format!("Program data: {}", general_purpose::STANDARD.encode(&data)));
+                logs.push(format!("Program {} success", PUMPFUN_PROGRAM_ID));
+            }
+            logs
+        })
+        .collect()
+}
+
+/// 解析一笔交易并入队，返回最后一个事件入队的时刻
+fn parse_into(logs: &[String], queue: &ArrayQueue<DexEvent>) -> Instant {
+    for event in parse_logs_only(logs, Signature::default(), 1, None, None) {
+        let _ = queue.push(event);
+    }
+    Instant::now()
+}
+
+/// 包装成 gRPC 推送的交易，日志放在 meta 中
+fn to_update(logs: &[String]) -> SubscribeUpdateTransaction {
+    let meta = TransactionStatusMeta { log_messages: logs.to_vec(), ..Default::default() };
+    SubscribeUpdateTransaction {
+        transaction: Some(SubscribeUpdateTransactionInfo { meta: Some(meta), ..Default::default() }),
+        slot: 1,
+    }
+}
+
+fn p99(mut latencies: Vec<Duration>) -> Duration {
+    latencies.sort_unstable();
+    latencies[latencies.len() * 99 / 100 - 1]
+}
+
+fn bench_offload(c: &mut Criterion) {
+    let transactions = Arc::new(burst());
+    let mut group = c.benchmark_group("burst_p99");
+    group.sample_size(20);
+
+    group.bench_function("inline", |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                let queue = ArrayQueue::new(BURST * EVENTS_PER_TX);
+                let start = Instant::now();
+                let latencies = transactions.iter().map(|logs| parse_into(logs, &queue) - start).collect();
+                total += p99(latencies);
+            }
+            total
+        });
+    });
+
+    let updates: Vec<_> = transactions.iter().map(|logs| to_update(logs)).collect();
+    for threads in [1, 4] {
+        group.bench_function(format!("offload_{}", threads), |b| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    let queue = Arc::new(ArrayQueue::new(BURST * EVENTS_PER_TX));
+                    let (done_tx, done_rx) = crossbeam_channel::bounded::<Duration>(BURST);
+                    let burst = updates.clone();
+                    let start = Instant::now();
+                    let pool = {
+                        let queue = queue.clone();
+                        ParserPool::spawn(threads, &[], BURST, true, Arc::new(StreamStats::default()), move |update, _| {
+                            let logs = update.transaction.as_ref().and_then(|tx| tx.meta.as_ref()).map(|meta| &meta.log_messages[..]);
+                            let _ = done_tx.send(parse_into(logs.unwrap_or_default(), &queue) - start);
+                        })
+                    };
+                    for update in burst {
+                        futures::executor::block_on(pool.submit(update, 0));
+                    }
+                    drop(pool);
+                    let latencies = done_rx.iter().take(BURST).collect();
+                    total += p99(latencies);
+                }
+                total
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_offload);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+//! 绑核基准：有其它线程争抢 CPU 时，单笔交易解析耗时的 p99.9（抖动）
+//!
+//! 模拟 `ClientConfig.receiver_core_id` / `parser_core_ids`：
+//! - `unpinned`：解析线程由内核调度，可能在核心间迁移
+//! - `pinned`：解析线程绑定到最后一个核心
+//!
+//! 每核各有一个忙等线程制造调度竞争。每次迭代解析 `BATCH` 笔交易并返回该批的 p99.9，
+//! 报告中的时间即平均 p99.9。绑核只在 Linux 上生效，其它平台两组结果相同；单核机器上没有可迁移的核心，
+//! 两组也不会有差别。
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion};
+use sol_parser_sdk::grpc::workers::pin_current_thread;
+use sol_parser_sdk::parse_logs_only;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const BATCH: usize = 5_000;
+const PUMPFUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const TRADE_EVENT: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+
+/// 合成 PumpFun 交易日志：一个 TradeEvent
+fn trade_logs() -> Vec<String> {
+    let mut data = TRADE_EVENT.to_vec();
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+    data.extend_from_slice(&5_000_000u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+    for v in [31_000_000_000u64, 1_000_000_000_000_000, 1_000_000_000, 793_000_000_000_000] {
+        data.extend_from_slice(&v.to_le_bytes());
+    }
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(&100u64.to_le_bytes());
+    data.extend_from_slice(&10_000_000u64.to_le_bytes());
+    vec![
+        format!("Program {} invoke [1]", PUMPFUN_PROGRAM_ID),
+        format!("Program data: {}", general_purpose::STANDARD.encode(&data)),
+        format!("Program {} success", PUMPFUN_PROGRAM_ID),
+    ]
+}
+
+fn p999(mut latencies: Vec<Duration>) -> Duration {
+    latencies.sort_unstable();
+    latencies[latencies.len() * 999 / 1000 - 1]
+}
+
+/// 在新线程上解析 `iters` 批交易，返回各批 p99.9 之和
+fn run(logs: &Arc<Vec<String>>, core_id: Option<usize>, iters: u64) -> Duration {
+    let logs = logs.clone();
+    std::thread::spawn(move || {
+        if let Some(core_id) = core_id {
+            pin_current_thread(core_id);
+        }
+        (0..iters)
+            .map(|_| {
+                let latencies = (0..BATCH)
+                    .map(|_| {
+                        let start = Instant::now();
+                        std::hint::black_box(parse_logs_only(&logs, Signature::default(), 1, None, None));
+                        start.elapsed()
+                    })
+                    .collect();
+                p999(latencies)
+            })
+            .sum()
+    })
+    .join()
+    .unwrap()
+}
+
+fn bench_pinning(c: &mut Criterion) {
+    let logs = Arc::new(trade_logs());
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let stop = Arc::new(AtomicBool::new(false));
+    let noise: Vec<_> = (0..cores)
+        .map(|_| {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    std::hint::spin_loop();
+                }
+            })
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("parse_p999");
+    group.sample_size(20);
+    group.bench_function("unpinned", |b| b.iter_custom(|iters| run(&logs, None, iters)));
+    group.bench_function("pinned", |b| b.iter_custom(|iters| run(&logs, Some(cores - 1), iters)));
+    group.finish();
+
+    stop.store(true, Ordering::Relaxed);
+    noise.into_iter().for_each(|t| t.join().unwrap());
+}
+
+criterion_group!(benches, bench_pinning);
+criterion_main!(benches);
@@ -0,0 +1,105 @@
+//! 交易预过滤基准：2000 笔与跟踪协议无关的合成交易（投票 / 转账）
+//!
+//! `decode_and_scan` 为预过滤之前每笔交易都要做的工作（账户列表和签名解码、日志扫描），
+//! `prefilter` 为 [`TrackedPrograms::matches`]。
+//! `wallet_prefilter` 为跟踪 10 万个钱包时的 [`WalletFilter::matches`]（同样 2000 笔、每笔 13 个账户，均未命中）。
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use memchr::memmem;
+use sol_parser_sdk::grpc::{Protocol, TrackedPrograms, TransactionFilter, WalletFilter};
+use sol_parser_sdk::logs::optimized_matcher::{detect_pumpfun_create, InvokeTracker};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::hint::black_box;
+use yellowstone_grpc_proto::prelude::{CompiledInstruction, Message, SubscribeUpdateTransactionInfo, Transaction};
+
+fn irrelevant_transactions(count: usize) -> Vec<(Transaction, Vec<String>)> {
+    let programs = ["Vote111111111111111111111111111111111111111", "11111111111111111111111111111111"];
+    (0..count)
+        .map(|i| {
+            let program: Pubkey = programs[i % programs.len()].parse().unwrap();
+            let mut account_keys: Vec<Vec<u8>> = (0..12).map(|_| Pubkey::new_unique().to_bytes().to_vec()).collect();
+            account_keys.push(program.to_bytes().to_vec());
+            let message = Message {
+                account_keys,
+                instructions: vec![CompiledInstruction { program_id_index: 12, accounts: vec![0, 1, 2], data: vec![2; 40] }],
+                ..Default::default()
+            };
+            let logs = vec![
+                format!("Program {} invoke [1]", program),
+                format!("Program {} consumed 2100 of 200000 compute units", program),
+                format!("Program {} success", program),
+            ];
+            (Transaction { signatures: vec![vec![i as u8; 64]], message: Some(message) }, logs)
+        })
+        .collect()
+}
+
+fn decode(tx: &Transaction) -> (Vec<Pubkey>, Signature) {
+    let message = tx.message.as_ref().unwrap();
+    let accounts = message
+        .account_keys
+        .iter()
+        .filter_map(|key| <[u8; 32]>::try_from(key.as_slice()).ok().map(Pubkey::new_from_array))
+        .collect();
+    let signature = tx.signatures.first().and_then(|sig| Signature::try_from(sig.as_slice()).ok()).unwrap_or_default();
+    (accounts, signature)
+}
+
+/// 解析器对日志的扫描：PumpFun create 检测 + invoke 栈 + Program data / ray_log 查找
+fn scan_logs(logs: &[String], program_data: &memmem::Finder, ray_log: &memmem::Finder) -> usize {
+    let has_create = detect_pumpfun_create(logs);
+    let mut invokes = InvokeTracker::new();
+    let mut candidates = has_create as usize;
+    for log in logs {
+        if invokes.observe(log) {
+            continue;
+        }
+        if program_data.find(log.as_bytes()).is_some() || ray_log.find(log.as_bytes()).is_some() {
+            candidates += 1;
+        }
+    }
+    candidates
+}
+
+fn bench_prefilter(c: &mut Criterion) {
+    let transactions = irrelevant_transactions(2000);
+    let tracked = TrackedPrograms::from_filters(&[TransactionFilter::for_protocols(&Protocol::ALL)], None);
+
+    let mut group = c.benchmark_group("irrelevant_block");
+    let (program_data, ray_log) = (memmem::Finder::new(b"Program data: "), memmem::Finder::new(b"ray_log: "));
+    group.bench_function("decode_and_scan", |b| {
+        b.iter(|| {
+            for (tx, logs) in &transactions {
+                black_box(decode(black_box(tx)));
+                black_box(scan_logs(logs, &program_data, &ray_log));
+            }
+        })
+    });
+    group.bench_function("prefilter", |b| {
+        b.iter(|| {
+            for (tx, logs) in &transactions {
+                black_box(tracked.matches(black_box(tx.message.as_ref().unwrap()), logs));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_wallet_prefilter(c: &mut Criterion) {
+    let infos: Vec<SubscribeUpdateTransactionInfo> = irrelevant_transactions(2000)
+        .into_iter()
+        .map(|(tx, _)| SubscribeUpdateTransactionInfo { transaction: Some(tx), ..Default::default() })
+        .collect();
+    let wallets = WalletFilter::new((0..100_000).map(|_| Pubkey::new_unique()));
+
+    c.bench_function("wallet_prefilter", |b| {
+        b.iter(|| {
+            for info in &infos {
+                black_box(wallets.matches(black_box(info)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_prefilter, bench_wallet_prefilter);
+criterion_main!(benches);
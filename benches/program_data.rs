@@ -0,0 +1,68 @@
+//! `Program data:` 解码基准：每次分配 Vec 的旧路径与复用缓冲区的新路径（合成日志），并统计每次解码的分配次数
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion};
+use sol_parser_sdk::logs::{self, utils};
+use solana_sdk::signature::Signature;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ROUNDS: usize = 10_000;
+
+fn allocations_per_call(mut f: impl FnMut()) -> f64 {
+    f();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for _ in 0..ROUNDS {
+        f();
+    }
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / ROUNDS as f64
+}
+
+fn bench_program_data(c: &mut Criterion) {
+    let mut data = logs::raydium_clmm::discriminators::SWAP.to_vec();
+    data.extend((0..200).map(|i| (i * 7 + 3) as u8));
+    let log = format!("Program data: {}", general_purpose::STANDARD.encode(&data));
+    let mut buf = Vec::new();
+
+    eprintln!(
+        "program_data allocations/call: extract_program_data={:.2} decode_program_data={:.2} parse_raydium_clmm_log={:.2}",
+        allocations_per_call(|| {
+            black_box(utils::extract_program_data(black_box(&log)));
+        }),
+        allocations_per_call(|| {
+            black_box(utils::decode_program_data(black_box(&log), &mut buf));
+        }),
+        allocations_per_call(|| {
+            black_box(logs::parse_raydium_clmm_log(black_box(&log), Signature::default(), 1, 0, Some(0), 0));
+        }),
+    );
+
+    let mut group = c.benchmark_group("program_data");
+    group.bench_function("extract_program_data", |b| b.iter(|| utils::extract_program_data(black_box(&log))));
+    group.bench_function("decode_program_data", |b| {
+        b.iter(|| utils::decode_program_data(black_box(&log), &mut buf).map(|bytes| bytes.len()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_program_data);
+criterion_main!(benches);
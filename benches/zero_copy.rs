@@ -0,0 +1,45 @@
+//! 零拷贝解析器基准：每种事件对比零拷贝解码与通用解析器（合成 Program data 日志）
+
+use base64::{engine::general_purpose, Engine as _};
+use criterion::{criterion_group, criterion_main, Criterion};
+use sol_parser_sdk::core::events::DexEvent;
+use sol_parser_sdk::logs::{self, zero_copy_parser};
+use solana_sdk::signature::Signature;
+use std::hint::black_box;
+
+type Parser = fn(&str, Signature, u64, u64, Option<i64>, i64) -> Option<DexEvent>;
+
+fn program_data_log(discriminator: [u8; 8], body_len: usize) -> String {
+    let mut data = discriminator.to_vec();
+    data.extend((0..body_len).map(|i| (i * 7 + 3) as u8));
+    format!("Program data: {}", general_purpose::STANDARD.encode(&data))
+}
+
+fn bench_pair(c: &mut Criterion, name: &str, log: &str, fast: Parser, generic: Parser) {
+    let signature = Signature::default();
+    let mut group = c.benchmark_group(name);
+    group.bench_function("zero_copy", |b| {
+        b.iter(|| fast(black_box(log), signature, 1, 0, Some(0), 0))
+    });
+    group.bench_function("generic", |b| {
+        b.iter(|| generic(black_box(log), signature, 1, 0, Some(0), 0))
+    });
+    group.finish();
+}
+
+fn bench_zero_copy(c: &mut Criterion) {
+    let buy = program_data_log(logs::pump_amm::discriminators::BUY, logs::pump_amm::layouts::TRADE_V2_LEN);
+    bench_pair(c, "pumpswap_buy", &buy, zero_copy_parser::parse_pump_amm_trade, logs::parse_pump_amm_log);
+
+    let sell = program_data_log(logs::pump_amm::discriminators::SELL, logs::pump_amm::layouts::TRADE_V2_LEN);
+    bench_pair(c, "pumpswap_sell", &sell, zero_copy_parser::parse_pump_amm_trade, logs::parse_pump_amm_log);
+
+    let bonk = program_data_log(logs::raydium_launchpad::discriminators::TRADE, 160);
+    bench_pair(c, "bonk_trade", &bonk, zero_copy_parser::parse_bonk_trade, logs::parse_raydium_launchpad_log);
+
+    let cpmm = program_data_log(logs::raydium_cpmm::discriminators::SWAP_BASE_IN, 89);
+    bench_pair(c, "raydium_cpmm_swap", &cpmm, zero_copy_parser::parse_raydium_cpmm_swap, logs::parse_raydium_cpmm_log);
+}
+
+criterion_group!(benches, bench_zero_copy);
+criterion_main!(benches);
@@ -1,6 +1,8 @@
 use sol_parser_sdk::grpc::{
     ClientConfig, Protocol, YellowstoneGrpc, TransactionFilter, AccountFilter, EventTypeFilter, EventType,
+    DexEventSource, MockYellowstone, ConsumerOptions,
 };
+use sol_parser_sdk::common::time::now_micros;
 use sol_parser_sdk::DexEvent;
 
 #[tokio::main]
@@ -13,14 +15,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn run_example() -> Result<(), Box<dyn std::error::Error>> {
+    // `cargo run --example basic -- --mock`：不连接节点，使用固定种子的合成事件流
+    if std::env::args().any(|arg| arg == "--mock") {
+        println!("🧪 Using simulated event stream (MockYellowstone)...");
+        return consume(MockYellowstone::generator(42).with_rate(20)).await;
+    }
+
     println!("🚀 Subscribing to Yellowstone gRPC events...");
 
-    // Create low-latency configuration
-    let mut config: ClientConfig = ClientConfig::default();
-    config.enable_metrics = true; // Enable performance monitoring
-    config.connection_timeout_ms = 10000;
-    config.request_timeout_ms = 30000;
-    config.enable_tls = true;
+    // Create low-latency configuration (validated by build())
+    let config: ClientConfig = ClientConfig::builder()
+        .enable_metrics(true) // Enable performance monitoring
+        .connection_timeout_ms(10000)
+        .request_timeout_ms(30000)
+        .enable_tls(true)
+        .build()?;
 
     let grpc = YellowstoneGrpc::new_with_config(
         "https://solana-yellowstone-grpc.publicnode.com:443".to_string(),
@@ -29,6 +38,10 @@ async fn run_example() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     println!("✅ gRPC client created successfully");
+    consume(grpc).await
+}
+
+async fn consume<S: DexEventSource + Clone + 'static>(grpc: S) -> Result<(), Box<dyn std::error::Error>> {
 
     // Monitor only PumpFun protocol for focused events
     let protocols = vec![
@@ -61,55 +74,39 @@ async fn run_example() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await?;
 
-    // 高性能消费事件（无锁队列）
+    // 开启延迟统计，定期打印各事件类型的 gRPC→消费 耗时分布
+    queue.set_latency_tracking(true);
+    let stats_queue = queue.clone();
     tokio::spawn(async move {
-        let mut spin_count = 0u32;
         loop {
-            // 使用 try-recv 非阻塞轮询，降低延迟
-            if let Some(event) = queue.pop() {
-                spin_count = 0; // 重置自旋计数
-
-                // 计算从gRPC接收到队列接收的耗时
-                let queue_recv_us = unsafe {
-                    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
-                    libc::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
-                    (ts.tv_sec as i64) * 1_000_000 + (ts.tv_nsec as i64) / 1_000
-                };
-
-                match &event {
-                    DexEvent::PumpFunTrade(e) => {
-                        let latency_us = queue_recv_us - e.metadata.grpc_recv_us;
-                        println!("\ngRPC接收时间: {} μs", e.metadata.grpc_recv_us);
-                        println!("事件接收时间: {} μs", queue_recv_us);
-                        println!("事件解析耗时: {} μs", latency_us);
-                        println!("================================================");
-                        println!("{:?}", event);
-                    },
-                    DexEvent::PumpFunCreate(e) => {
-                        let latency_us = queue_recv_us - e.metadata.grpc_recv_us;
-                        println!("\ngRPC接收时间: {} μs", e.metadata.grpc_recv_us);
-                        println!("事件接收时间: {} μs", queue_recv_us);
-                        println!("事件解析耗时: {} μs", latency_us);
-                        println!("================================================");
-                        println!("{:?}", event);
-                    },
-                    _ => {}
-                }
-            } else {
-                // 混合策略：先自旋等待，如果长时间没数据才 yield
-                spin_count += 1;
-                if spin_count < 1000 {
-                    // 短暂自旋，降低延迟
-                    std::hint::spin_loop();
-                } else {
-                    // 超过阈值后 yield CPU，避免 100% 占用
-                    tokio::task::yield_now().await;
-                    spin_count = 0;
-                }
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            for (event_type, s) in stats_queue.latency_snapshot() {
+                println!(
+                    "📈 {:?}: count={} p50={}μs p95={}μs p99={}μs max={}μs",
+                    event_type, s.count, s.p50_us, s.p95_us, s.p99_us, s.max_us
+                );
             }
         }
     });
 
+    // 高性能消费事件：独立线程轮询无锁队列（先自旋后 yield），按批次回调
+    let _consumer = queue.spawn_consumer(ConsumerOptions { max_wait_us: 0, ..Default::default() }, |events| {
+        // 计算从gRPC接收到队列接收的耗时
+        let queue_recv_us = now_micros();
+        for event in events {
+            let metadata = match event {
+                DexEvent::PumpFunTrade(e) => &e.metadata,
+                DexEvent::PumpFunCreate(e) => &e.metadata,
+                _ => continue,
+            };
+            println!("\ngRPC接收时间: {} μs", metadata.grpc_recv_us);
+            println!("事件接收时间: {} μs", queue_recv_us);
+            println!("事件解析耗时: {} μs", queue_recv_us - metadata.grpc_recv_us);
+            println!("================================================");
+            println!("{}", event);
+        }
+    });
+
     // Auto-stop after 1000 seconds for testing
     let grpc_clone = grpc.clone();
     tokio::spawn(async move {
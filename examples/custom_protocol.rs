@@ -0,0 +1,47 @@
+//! 运行时注册自定义协议
+//!
+//! 演示如何为布局与 PumpFun 相同、程序 ID 不同的分叉注册解析器。
+//! 日志为合成数据，仅用于展示注册表的用法。
+
+use base64::{engine::general_purpose, Engine as _};
+use sol_parser_sdk::core::events::CustomEvent;
+use sol_parser_sdk::core::{InstructionSpec, LogEventSpec, ParserRegistry};
+use sol_parser_sdk::DexEvent;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+
+fn main() {
+    // 替换为分叉程序的真实 ID
+    let fork = Pubkey::from_str("Fork111111111111111111111111111111111111111").unwrap();
+
+    let buy = InstructionSpec::new("buy", sol_parser_sdk::instr::pumpfun::discriminators::BUY, |data, accounts, ctx| {
+        sol_parser_sdk::instr::pumpfun::parse_instruction(data, accounts, ctx.signature, ctx.slot, ctx.tx_index, ctx.block_time)
+    });
+    let trade = LogEventSpec::new("TradeEvent", sol_parser_sdk::logs::pumpfun::discriminators::TRADE_EVENT, |log, _, ctx| {
+        sol_parser_sdk::logs::parse_pumpfun_trade(log, ctx.signature, ctx.slot, ctx.tx_index, ctx.block_time, ctx.grpc_recv_us, false)
+    });
+    // 分叉独有的事件：输出为 DexEvent::Custom
+    let fee = LogEventSpec::new("FeeEvent", [7; 8], move |_, data, ctx| {
+        let amount = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+        Some(DexEvent::Custom(CustomEvent {
+            metadata: ctx.metadata(),
+            protocol: "pumpfun-fork".into(),
+            name: "FeeEvent".into(),
+            program_id: fork,
+            data: serde_json::json!({ "amount": amount }),
+        }))
+    });
+    ParserRegistry::register("pumpfun-fork", fork, vec![buy], vec![trade, fee]);
+
+    let mut fee_data = vec![7u8; 8];
+    fee_data.extend_from_slice(&42u64.to_le_bytes());
+    let logs = vec![
+        format!("Program {} invoke [1]", fork),
+        format!("Program data: {}", general_purpose::STANDARD.encode(&fee_data)),
+        format!("Program {} success", fork),
+    ];
+
+    for event in sol_parser_sdk::parse_logs_only(&logs, Signature::default(), 1, None, None) {
+        println!("{}", event);
+    }
+}
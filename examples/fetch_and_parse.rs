@@ -0,0 +1,26 @@
+//! 按签名拉取单笔交易并打印解析出的事件
+//!
+//! `cargo run --example fetch_and_parse --features rpc -- <signature> [rpc_url]`
+
+use sol_parser_sdk::{fetch_and_parse, RpcFetchError};
+use solana_sdk::signature::Signature;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let signature: Signature = args.next().ok_or("usage: fetch_and_parse <signature> [rpc_url]")?.parse()?;
+    let rpc_url = args.next().unwrap_or_else(|| "https://api.mainnet-beta.solana.com".to_string());
+
+    match fetch_and_parse(&rpc_url, &signature).await {
+        Ok(events) => {
+            println!("{} events in {}", events.len(), signature);
+            for event in events {
+                println!("{:#?}", event);
+            }
+        }
+        Err(RpcFetchError::NotFinalized { slot, .. }) => println!("confirmed at slot {} but not finalized yet, retry later", slot),
+        Err(RpcFetchError::NotFound(_)) => println!("transaction not found"),
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
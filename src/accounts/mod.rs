@@ -0,0 +1,38 @@
+//! 账户数据解析器
+//!
+//! 把账户订阅收到的账户数据按所有者程序解码为 [`DexEvent`]，目前支持：
+//! - Raydium AMM V4 池子账户（[`raydium_amm_v4`]）
+
+pub mod raydium_amm_v4;
+
+use crate::core::events::{DexEvent, EventMetadata, EventSource};
+use crate::instr::program_ids::RAYDIUM_AMM_V4_PROGRAM_ID;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+/// 账户更新的元数据：`signature` 为触发此次更新的交易（没有时为默认值）
+pub fn account_metadata(signature: Signature, slot: u64, grpc_recv_us: i64) -> EventMetadata {
+    EventMetadata {
+        signature,
+        slot,
+        tx_index: 0,
+        block_time_us: 0,
+        grpc_recv_us,
+        handle_us: crate::common::time::handle_micros(grpc_recv_us),
+        event_source: EventSource::Account,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
+    }
+}
+
+/// 解码账户数据，不认识的所有者或布局返回 None
+pub fn parse_account(pubkey: Pubkey, owner: &Pubkey, data: &[u8], metadata: EventMetadata) -> Option<DexEvent> {
+    if *owner == RAYDIUM_AMM_V4_PROGRAM_ID {
+        return raydium_amm_v4::parse_pool_state(pubkey, data, metadata).map(DexEvent::RaydiumAmmV4PoolState);
+    }
+    None
+}
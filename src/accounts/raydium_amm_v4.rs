@@ -0,0 +1,99 @@
+//! Raydium AMM V4 池子账户（`AmmInfo`，752 字节，无 discriminator）
+//!
+//! 布局（小端）：
+//! - `0..128`：16 个 u64 参数（status、nonce、decimals、lot size 等，即 [`RaydiumAmmInfo`]）
+//! - `128..192`：Fees（8 个 u64）
+//! - `192..336`：StateData，开头为 `need_take_pnl_coin` / `need_take_pnl_pc`，`224` 为 `pool_open_time`
+//! - `336..624`：coin_vault、pc_vault、coin_mint、pc_mint、lp_mint、open_orders、market、market_program、target_orders
+//! - `624..688`：padding，`688` amm_owner，`720` lp_amount
+
+use crate::core::events::{EventMetadata, RaydiumAmmInfo, RaydiumAmmV4PoolStateEvent};
+use crate::instr::utils::{read_pubkey, read_u64_le};
+use solana_sdk::pubkey::Pubkey;
+
+/// `AmmInfo` 账户大小
+pub const AMM_INFO_SIZE: usize = 752;
+
+/// 解码池子账户，长度不符（其它账户类型，如 target orders）时返回 None
+pub fn parse_pool_state(amm: Pubkey, data: &[u8], metadata: EventMetadata) -> Option<RaydiumAmmV4PoolStateEvent> {
+    if data.len() != AMM_INFO_SIZE {
+        return None;
+    }
+    let u64_at = |index: usize| read_u64_le(data, index * 8);
+    let pubkey_at = |index: usize| read_pubkey(data, 336 + index * 32);
+
+    Some(RaydiumAmmV4PoolStateEvent {
+        metadata,
+        amm,
+        amm_info: RaydiumAmmInfo {
+            status: u64_at(0)?,
+            nonce: u64_at(1)?,
+            order_num: u64_at(2)?,
+            depth: u64_at(3)?,
+            coin_decimals: u64_at(4)?,
+            pc_decimals: u64_at(5)?,
+            state: u64_at(6)?,
+            reset_flag: u64_at(7)?,
+            min_size: u64_at(8)?,
+            vol_max_cut_ratio: u64_at(9)?,
+            amount_wave_ratio: u64_at(10)?,
+            coin_lot_size: u64_at(11)?,
+            pc_lot_size: u64_at(12)?,
+            min_price_multiplier: u64_at(13)?,
+            max_price_multiplier: u64_at(14)?,
+            sys_decimal_value: u64_at(15)?,
+        },
+        need_take_pnl_coin: u64_at(24)?,
+        need_take_pnl_pc: u64_at(25)?,
+        pool_open_time: u64_at(28)?,
+        coin_vault: pubkey_at(0)?,
+        pc_vault: pubkey_at(1)?,
+        coin_mint: pubkey_at(2)?,
+        pc_mint: pubkey_at(3)?,
+        lp_mint: pubkey_at(4)?,
+        open_orders: pubkey_at(5)?,
+        market: pubkey_at(6)?,
+        market_program: pubkey_at(7)?,
+        target_orders: pubkey_at(8)?,
+        lp_amount: read_u64_le(data, 720)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::{account_metadata, parse_account};
+    use crate::core::events::DexEvent;
+    use crate::instr::program_ids::RAYDIUM_AMM_V4_PROGRAM_ID;
+    use solana_sdk::signature::Signature;
+
+    #[test]
+    fn test_decode_pool_reserve_fields() {
+        // 按 AmmInfo 布局构造的 SOL/USDC 池子账户
+        let (coin_vault, pc_vault, open_orders) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; AMM_INFO_SIZE];
+        data[0..8].copy_from_slice(&6u64.to_le_bytes()); // status
+        data[32..40].copy_from_slice(&9u64.to_le_bytes()); // coin_decimals
+        data[40..48].copy_from_slice(&6u64.to_le_bytes()); // pc_decimals
+        data[192..200].copy_from_slice(&1_500u64.to_le_bytes()); // need_take_pnl_coin
+        data[200..208].copy_from_slice(&2_500u64.to_le_bytes()); // need_take_pnl_pc
+        data[336..368].copy_from_slice(coin_vault.as_ref());
+        data[368..400].copy_from_slice(pc_vault.as_ref());
+        data[496..528].copy_from_slice(open_orders.as_ref());
+        data[720..728].copy_from_slice(&42u64.to_le_bytes()); // lp_amount
+
+        let amm = Pubkey::new_unique();
+        let metadata = account_metadata(Signature::default(), 7, 0);
+        let Some(DexEvent::RaydiumAmmV4PoolState(pool)) = parse_account(amm, &RAYDIUM_AMM_V4_PROGRAM_ID, &data, metadata)
+        else {
+            panic!("expected pool state");
+        };
+        assert_eq!(pool.amm, amm);
+        assert_eq!((pool.amm_info.status, pool.amm_info.coin_decimals, pool.amm_info.pc_decimals), (6, 9, 6));
+        assert_eq!((pool.coin_vault, pool.pc_vault, pool.open_orders), (coin_vault, pc_vault, open_orders));
+        assert_eq!(pool.lp_amount, 42);
+        assert_eq!(pool.reserves(100_000, 200_000), (98_500, 197_500));
+
+        assert!(parse_pool_state(amm, &data[..AMM_INFO_SIZE - 1], account_metadata(Signature::default(), 7, 0)).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+//! 无锁延迟直方图
+//!
+//! HDR 风格的对数-线性分桶：每个 2 的幂区间再线性拆分为 16 个子桶，
+//! 相对误差约 6%。所有计数都是原子操作，多线程同时记录无需加锁。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 每个 2 的幂区间的子桶位数
+const SUB_BUCKET_BITS: u32 = 4;
+const SUB_BUCKET_COUNT: usize = 1 << SUB_BUCKET_BITS;
+/// 可记录的最大值位数（2^40 μs ≈ 12 天，足够覆盖延迟）
+const MAX_VALUE_BITS: u32 = 40;
+const MAX_VALUE: u64 = (1u64 << MAX_VALUE_BITS) - 1;
+const BUCKET_COUNT: usize =
+    SUB_BUCKET_COUNT + (MAX_VALUE_BITS - SUB_BUCKET_BITS) as usize * SUB_BUCKET_COUNT;
+
+/// 延迟统计摘要（单位：微秒）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+/// 原子延迟直方图
+pub struct LatencyHistogram {
+    buckets: Box<[AtomicU64]>,
+    count: AtomicU64,
+    max: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..BUCKET_COUNT).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    /// 记录一个延迟值（微秒）
+    #[inline]
+    pub fn record(&self, value_us: u64) {
+        let value = value_us.min(MAX_VALUE);
+        self.buckets[bucket_index(value)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    /// 已记录的样本数
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// 生成 p50/p95/p99/max 摘要
+    pub fn summary(&self) -> LatencySummary {
+        let counts: Vec<u64> = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return LatencySummary::default();
+        }
+        let max_us = self.max.load(Ordering::Relaxed);
+
+        let percentile = |p: f64| -> u64 {
+            let target = ((total as f64) * p).ceil().max(1.0) as u64;
+            let mut seen = 0u64;
+            for (idx, c) in counts.iter().enumerate() {
+                seen += c;
+                if seen >= target {
+                    return bucket_upper_bound(idx).min(max_us);
+                }
+            }
+            max_us
+        };
+
+        LatencySummary {
+            count: total,
+            p50_us: percentile(0.50),
+            p95_us: percentile(0.95),
+            p99_us: percentile(0.99),
+            max_us,
+        }
+    }
+
+    /// 清空所有统计
+    pub fn reset(&self) {
+        for b in self.buckets.iter() {
+            b.store(0, Ordering::Relaxed);
+        }
+        self.count.store(0, Ordering::Relaxed);
+        self.max.store(0, Ordering::Relaxed);
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline(always)]
+fn bucket_index(value: u64) -> usize {
+    if value < SUB_BUCKET_COUNT as u64 {
+        return value as usize;
+    }
+    let msb = 63 - value.leading_zeros();
+    let shift = msb - SUB_BUCKET_BITS;
+    let sub = ((value >> shift) as usize) & (SUB_BUCKET_COUNT - 1);
+    SUB_BUCKET_COUNT + shift as usize * SUB_BUCKET_COUNT + sub
+}
+
+#[inline]
+fn bucket_upper_bound(idx: usize) -> u64 {
+    if idx < SUB_BUCKET_COUNT {
+        return idx as u64;
+    }
+    let shift = ((idx - SUB_BUCKET_COUNT) / SUB_BUCKET_COUNT) as u32;
+    let sub = ((idx - SUB_BUCKET_COUNT) % SUB_BUCKET_COUNT) as u64;
+    let lower = (SUB_BUCKET_COUNT as u64 + sub) << shift;
+    lower + (1u64 << shift) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_within_precision() {
+        let h = LatencyHistogram::new();
+        for v in 1..=1000u64 {
+            h.record(v);
+        }
+        let s = h.summary();
+        assert_eq!(s.count, 1000);
+        assert_eq!(s.max_us, 1000);
+        assert!((470..=530).contains(&s.p50_us), "p50 = {}", s.p50_us);
+        assert!((930..=1000).contains(&s.p95_us), "p95 = {}", s.p95_us);
+        assert!((970..=1000).contains(&s.p99_us), "p99 = {}", s.p99_us);
+    }
+
+    #[test]
+    fn test_bucket_index_monotonic() {
+        let mut last = 0;
+        for v in [0u64, 1, 15, 16, 17, 31, 32, 1_000, 1_000_000, MAX_VALUE] {
+            let idx = bucket_index(v);
+            assert!(idx >= last && idx < BUCKET_COUNT);
+            assert!(bucket_upper_bound(idx) >= v);
+            last = idx;
+        }
+    }
+}
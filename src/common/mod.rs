@@ -1,14 +1,21 @@
 // 公用模块 - 简化的通用功能
+#[cfg(feature = "grpc")]
 pub mod metrics;
 pub mod constants;
+#[cfg(feature = "grpc")]
 pub mod subscription;
 pub mod simd_utils;
+pub mod latency;
+pub mod time;
 
 // 重新导出主要类型
+#[cfg(feature = "grpc")]
 pub use metrics::*;
 pub use constants::*;
+#[cfg(feature = "grpc")]
 pub use subscription::*;
 pub use simd_utils::*;
+pub use latency::*;
 
 // 常用类型别名
 pub type AnyResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
\ No newline at end of file
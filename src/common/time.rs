@@ -0,0 +1,111 @@
+//! 时间源抽象
+//!
+//! 所有 `grpc_recv_us` 与延迟统计都通过 [`now_micros`] 取时间：
+//! - unix 上直接调用 `clock_gettime(CLOCK_REALTIME)`，其它平台及只启用 `parse` feature（不链接 libc）时回退到 `SystemTime`
+//! - 需要可控时间的组件按实例注入 [`Clock`]（如 [`DexEventQueue::with_clock`](crate::grpc::DexEventQueue::with_clock)），
+//!   不存在进程级的时钟替换，多个客户端、并行测试互不影响
+
+/// 时间源（微秒级 unix 时间戳）
+pub trait Clock: Send + Sync {
+    fn now_micros(&self) -> i64;
+
+    /// 低精度时间，默认与 `now_micros` 相同
+    fn now_micros_coarse(&self) -> i64 {
+        self.now_micros()
+    }
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline(always)]
+    fn now_micros(&self) -> i64 {
+        system_now_micros(false)
+    }
+
+    #[inline(always)]
+    fn now_micros_coarse(&self) -> i64 {
+        system_now_micros(true)
+    }
+}
+
+#[cfg(all(unix, feature = "grpc"))]
+#[inline(always)]
+#[allow(clippy::unnecessary_cast)] // time_t 在部分平台上不是 i64
+fn system_now_micros(coarse: bool) -> i64 {
+    // Linux 上的 CLOCK_REALTIME_COARSE 走 vDSO 且更快，其它 unix 没有该时钟
+    #[cfg(target_os = "linux")]
+    let clock_id = if coarse { libc::CLOCK_REALTIME_COARSE } else { libc::CLOCK_REALTIME };
+    #[cfg(not(target_os = "linux"))]
+    let clock_id = {
+        let _ = coarse;
+        libc::CLOCK_REALTIME
+    };
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    unsafe {
+        libc::clock_gettime(clock_id, &mut ts);
+    }
+    (ts.tv_sec as i64) * 1_000_000 + (ts.tv_nsec as i64) / 1_000
+}
+
+#[cfg(not(all(unix, feature = "grpc")))]
+#[inline(always)]
+fn system_now_micros(_coarse: bool) -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_micros() as i64)
+}
+
+/// 当前时间（微秒）
+#[inline(always)]
+pub fn now_micros() -> i64 {
+    system_now_micros(false)
+}
+
+/// 当前时间（微秒，低精度，用于事件元数据）
+#[inline(always)]
+pub fn now_micros_coarse() -> i64 {
+    system_now_micros(true)
+}
+
+/// 事件构造时刻（写入 `EventMetadata::handle_us`），不早于 `grpc_recv_us`
+///
+/// 使用低精度时钟，它可能比精确取得的接收时间落后一个 tick，因此取两者较大值。
+#[inline(always)]
+pub fn handle_micros(grpc_recv_us: i64) -> i64 {
+    now_micros_coarse().max(grpc_recv_us)
+}
+
+/// 从 `start_us` 到现在经过的微秒数（时钟回拨时为 0）
+#[inline]
+pub fn elapsed_micros(start_us: i64) -> u64 {
+    (now_micros() - start_us).max(0) as u64
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    /// 手动推进的测试时钟
+    #[derive(Default)]
+    pub(crate) struct MockClock(pub AtomicI64);
+
+    impl Clock for MockClock {
+        fn now_micros(&self) -> i64 {
+            self.0.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_system_clock_is_unix_micros() {
+        let now = SystemClock.now_micros();
+        let expected = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_micros() as i64;
+        assert!((now - expected).abs() < 1_000_000);
+    }
+}
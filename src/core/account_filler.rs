@@ -400,7 +400,9 @@ pub mod meteora {
         swap_event: &mut MeteoraPoolsSwapEvent,
         get_account: &AccountGetter<'_>,
     ) {
-        // MeteoraPoolsSwapEvent只有IDL事件字段，无指令账户字段
+        if swap_event.pool == Pubkey::default() {
+            swap_event.pool = get_account(0);
+        }
     }
 
     /// 填充 Meteora DAMM V2 Swap 事件账户
@@ -483,12 +485,18 @@ pub mod bonk {
         trade_event: &mut BonkTradeEvent,
         get_account: &AccountGetter<'_>,
     ) {
-        // 基于Bonk IDL swap指令账户映射
+        // 基于 Raydium Launchpad buy/sell 指令账户映射：payer@0, pool_state@4, base_token_mint@9, quote_token_mint@10
         if trade_event.user == Pubkey::default() {
             trade_event.user = get_account(0);
         }
         if trade_event.pool_state == Pubkey::default() {
-            trade_event.pool_state = get_account(1);
+            trade_event.pool_state = get_account(4);
+        }
+        if trade_event.base_mint == Pubkey::default() {
+            trade_event.base_mint = get_account(9);
+        }
+        if trade_event.quote_mint == Pubkey::default() {
+            trade_event.quote_mint = get_account(10);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,475 @@
+//! 按 slot 汇总事件
+//!
+//! [`SlotAggregator`] 按 slot 累计各协议的成交笔数、SOL 成交额、独立交易者数和新建池子数，
+//! slot 结束时输出一份 [`SlotSummary`]，适合看板类场景，不需要逐条处理事件：
+//! - 收到该 slot 的 `SlotStatus::Finalized`：`finalized = true`，更早的未结束 slot 一并输出
+//! - 收到该 slot 的 `SlotStatus::Dead`：所在分叉被丢弃，丢弃该 slot 的统计，不输出
+//! - 收到该 slot 的 `BlockMeta`：区块已完整，输出该 slot（此前已收到 Finalized 时 `finalized = true`）
+//! - 看到更高的 slot（超过 `lag_slots` 个）：按"已进入后续 slot"推断结束，`finalized = false`；
+//!   用 [`SlotAggregator::wait_for_finalized`] 创建时不做推断，只在 Finalized 时输出
+//! - 已输出的 slot 再收到的事件不再计入，记入 [`SlotAggregator::late_events`]
+//!
+//! SOL 成交额按 WSOL 一侧统计，单位为 lamports：
+//! - PumpFun、PumpSwap：SOL / quote 侧（PumpSwap 池子已知 quote 不是 WSOL 时不计）
+//! - Bonk：quote 侧（池子已知 quote 不是 WSOL 时不计）
+//! - Raydium AMM V4、Orca Whirlpool、Meteora DAMM V2：池子 mint 已知（见过建池 / 增删流动性事件）且一侧为 WSOL 时
+//! - 其它交易事件不带 mint 或方向，不计入
+
+use crate::core::events::{DexEvent, SlotStatus, WSOL_MINT};
+use crate::grpc::types::{EventType, Protocol};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 记录的池子 mint 上限，超过后清空重新学习，避免长时间运行时无限增长
+const MAX_KNOWN_POOLS: usize = 100_000;
+
+/// 单个协议在一个 slot 内的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtocolStats {
+    /// 成交笔数
+    pub trades: u64,
+    /// SOL 成交额（lamports）
+    pub sol_volume: u64,
+    /// 独立交易者数（事件中带交易者地址时才统计）
+    pub unique_traders: u64,
+    /// 新建池子数（含 PumpFun / Bonk 的 bonding curve）
+    pub pools_created: u64,
+}
+
+/// 一个 slot 的汇总
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotSummary {
+    pub slot: u64,
+    pub per_protocol: HashMap<Protocol, ProtocolStats>,
+    /// 收到该 slot 的 `SlotStatus::Finalized` 时为 true，其余情况（推断、`BlockMeta`、`flush`）为 false
+    pub finalized: bool,
+}
+
+#[derive(Default)]
+struct SlotState {
+    stats: HashMap<Protocol, ProtocolStats>,
+    traders: HashMap<Protocol, HashSet<Pubkey>>,
+    finalized: bool,
+}
+
+/// 按 slot 汇总事件
+pub struct SlotAggregator {
+    /// None 表示只在 Finalized 时输出
+    lag_slots: Option<u64>,
+    open: BTreeMap<u64, SlotState>,
+    /// 已输出的最高 slot
+    emitted_up_to: Option<u64>,
+    late_events: u64,
+    /// 池子 -> (base mint, quote mint)，来自建池 / 增删流动性事件
+    pool_mints: HashMap<Pubkey, (Pubkey, Pubkey)>,
+}
+
+impl SlotAggregator {
+    /// `lag_slots` 为推断 slot 结束前等待的 slot 数：看到 `slot + lag_slots + 1` 时输出 `slot`，
+    /// 0 表示一进入下一个 slot 就输出（processed 级别下乱序较多时可适当调大）
+    pub fn new(lag_slots: u64) -> Self {
+        Self::with_lag(Some(lag_slots))
+    }
+
+    /// 不按更高 slot 推断结束，只在收到 `SlotStatus::Finalized` 时输出（需订阅 slot 更新）
+    pub fn wait_for_finalized() -> Self {
+        Self::with_lag(None)
+    }
+
+    fn with_lag(lag_slots: Option<u64>) -> Self {
+        Self { lag_slots, open: BTreeMap::new(), emitted_up_to: None, late_events: 0, pool_mints: HashMap::new() }
+    }
+
+    /// 加入一个事件，返回此刻已结束的 slot 汇总（按 slot 升序）
+    pub fn push(&mut self, event: &DexEvent) -> Vec<SlotSummary> {
+        let Some(slot) = event.metadata().map(|m| m.slot) else { return Vec::new() };
+        if self.emitted_up_to.is_some_and(|emitted| slot <= emitted) {
+            if !matches!(event, DexEvent::SlotStatus(_)) {
+                self.late_events += 1;
+            }
+            return Vec::new();
+        }
+
+        match event {
+            DexEvent::SlotStatus(e) => match e.status {
+                SlotStatus::Finalized => {
+                    let mut state = self.open.remove(&slot).unwrap_or_default();
+                    state.finalized = true;
+                    return self.close_through(slot, state);
+                }
+                SlotStatus::Dead => {
+                    self.open.remove(&slot);
+                    return Vec::new();
+                }
+                _ => return Vec::new(),
+            },
+            DexEvent::BlockMeta(_) => {
+                if self.lag_slots.is_none() {
+                    return Vec::new();
+                }
+                let state = self.open.remove(&slot).unwrap_or_default();
+                return self.close_through(slot, state);
+            }
+            _ => {}
+        }
+        self.learn_pool(event);
+        let sol_volume = self.sol_amount(event);
+        record(self.open.entry(slot).or_default(), event, sol_volume);
+
+        let Some(lag_slots) = self.lag_slots else { return Vec::new() };
+        let ready = self.close_before(slot.saturating_sub(lag_slots));
+        if let Some(last) = ready.last() {
+            self.emitted_up_to = Some(last.slot);
+        }
+        ready
+    }
+
+    /// 立即输出所有未结束的 slot（例如订阅结束时）
+    pub fn flush(&mut self) -> Vec<SlotSummary> {
+        let open = std::mem::take(&mut self.open);
+        let ready: Vec<SlotSummary> = open.into_iter().map(|(slot, state)| Self::summary(slot, state)).collect();
+        if let Some(last) = ready.last() {
+            self.emitted_up_to = Some(last.slot);
+        }
+        ready
+    }
+
+    /// 尚未结束的 slot 数
+    pub fn pending_slots(&self) -> usize {
+        self.open.len()
+    }
+
+    /// slot 已输出后才到达、未计入汇总的事件数
+    pub fn late_events(&self) -> u64 {
+        self.late_events
+    }
+
+    /// 输出 `slot`（状态已从 `open` 中取出）及更早的所有 slot，保证汇总按 slot 升序
+    fn close_through(&mut self, slot: u64, state: SlotState) -> Vec<SlotSummary> {
+        let mut ready = self.close_before(slot);
+        ready.push(Self::summary(slot, state));
+        self.emitted_up_to = Some(slot);
+        ready
+    }
+
+    /// 输出 `slot` 之前的所有 slot
+    fn close_before(&mut self, slot: u64) -> Vec<SlotSummary> {
+        let rest = self.open.split_off(&slot);
+        let closed = std::mem::replace(&mut self.open, rest);
+        closed.into_iter().map(|(slot, state)| Self::summary(slot, state)).collect()
+    }
+
+    fn summary(slot: u64, state: SlotState) -> SlotSummary {
+        SlotSummary { slot, per_protocol: state.stats, finalized: state.finalized }
+    }
+
+    /// 从带 mint 的事件中记录池子的 (base, quote)
+    fn learn_pool(&mut self, event: &DexEvent) {
+        let (pool, mints) = match event {
+            DexEvent::BonkPoolCreate(e) => (e.pool_state, (e.base_mint, e.quote_mint)),
+            DexEvent::PumpSwapDeposit(e) => (e.pool, (e.base_mint, e.quote_mint)),
+            DexEvent::PumpSwapWithdraw(e) => (e.pool, (e.base_mint, e.quote_mint)),
+            DexEvent::RaydiumAmmV4Initialize2(e) => (e.amm, (e.coin_mint, e.pc_mint)),
+            DexEvent::OrcaWhirlpoolPoolInitialized(e) => (e.whirlpool, (e.token_mint_a, e.token_mint_b)),
+            DexEvent::MeteoraDammV2InitializePool(e) => (e.lb_pair, (e.token_x, e.token_y)),
+            _ => return,
+        };
+        if mints.0 == Pubkey::default() || mints.1 == Pubkey::default() {
+            return;
+        }
+        if self.pool_mints.len() >= MAX_KNOWN_POOLS {
+            self.pool_mints.clear();
+        }
+        self.pool_mints.insert(pool, mints);
+    }
+
+    /// 成交中 WSOL 一侧的数量，无法确定时为 0
+    fn sol_amount(&self, event: &DexEvent) -> u64 {
+        let mints = |pool: &Pubkey| self.pool_mints.get(pool).copied();
+        // (base 是否为 WSOL, quote 是否为 WSOL)，池子未知时为 None
+        let sides = |pool: &Pubkey| mints(pool).map(|(base, quote)| (base == WSOL_MINT, quote == WSOL_MINT));
+        // 池子未知或 quote 为 WSOL 时计 quote 侧
+        let quote_is_sol = |pool: &Pubkey| sides(pool).is_none_or(|(_, quote)| quote);
+        match event {
+            DexEvent::PumpFunTrade(e) => e.sol_amount,
+            DexEvent::PumpSwapBuy(e) if quote_is_sol(&e.pool_id) => e.sol_amount,
+            DexEvent::PumpSwapSell(e) if quote_is_sol(&e.pool_id) => e.sol_amount,
+            DexEvent::BonkTrade(e) if quote_is_sol(&e.pool_state) => {
+                if e.is_buy { e.amount_in } else { e.amount_out }
+            }
+            // direction：1 = pc 换 coin，2 = coin 换 pc
+            DexEvent::RaydiumAmmV4Swap(e) => match (sides(&e.amm), e.direction) {
+                (Some((true, _)), 1) | (Some((_, true)), 2) => e.amount_out,
+                (Some((true, _)), 2) | (Some((_, true)), 1) => e.amount_in,
+                _ => 0,
+            },
+            DexEvent::OrcaWhirlpoolSwap(e) => {
+                side_amount(sides(&e.whirlpool), e.a_to_b, e.input_amount, e.output_amount)
+            }
+            DexEvent::MeteoraDammV2Swap(e) => {
+                side_amount(sides(&e.lb_pair), e.swap_for_y, e.amount_in, e.amount_out)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// base 换 quote（`base_to_quote`）时输入为 base，否则输入为 quote，取 WSOL 一侧的数量
+fn side_amount(sides: Option<(bool, bool)>, base_to_quote: bool, amount_in: u64, amount_out: u64) -> u64 {
+    match sides {
+        Some((true, _)) => if base_to_quote { amount_in } else { amount_out },
+        Some((_, true)) => if base_to_quote { amount_out } else { amount_in },
+        _ => 0,
+    }
+}
+
+fn record(state: &mut SlotState, event: &DexEvent, sol_volume: u64) {
+    let Some(protocol) = EventType::from_event(event).and_then(EventType::protocol) else { return };
+
+    let (is_trade, is_pool_create) = classify(event);
+    if !is_trade && !is_pool_create {
+        return;
+    }
+    let stats = state.stats.entry(protocol).or_default();
+    if is_pool_create {
+        stats.pools_created += 1;
+        return;
+    }
+    stats.trades += 1;
+    stats.sol_volume += sol_volume;
+    if let Some(trader) = trader(event) {
+        if state.traders.entry(protocol).or_default().insert(trader) {
+            stats.unique_traders += 1;
+        }
+    }
+}
+
+/// (是否成交, 是否新建池子)
+fn classify(event: &DexEvent) -> (bool, bool) {
+    match event {
+        DexEvent::PumpFunTrade(_)
+        | DexEvent::PumpSwapBuy(_)
+        | DexEvent::PumpSwapSell(_)
+        | DexEvent::BonkTrade(_)
+        | DexEvent::RaydiumClmmSwap(_)
+        | DexEvent::RaydiumCpmmSwap(_)
+        | DexEvent::RaydiumAmmV4Swap(_)
+        | DexEvent::OrcaWhirlpoolSwap(_)
+        | DexEvent::MeteoraPoolsSwap(_)
+        | DexEvent::MeteoraDammV2Swap(_)
+        | DexEvent::MeteoraDlmmSwap(_) => (true, false),
+        DexEvent::PumpFunCreate(_)
+        | DexEvent::BonkPoolCreate(_)
+        | DexEvent::PumpSwapCreatePool(_)
+        | DexEvent::RaydiumClmmCreatePool(_)
+        | DexEvent::RaydiumCpmmInitialize(_)
+        | DexEvent::RaydiumAmmV4Initialize2(_)
+        | DexEvent::OrcaWhirlpoolPoolInitialized(_)
+        | DexEvent::MeteoraPoolsPoolCreated(_)
+        | DexEvent::MeteoraDammV2InitializePool(_)
+        | DexEvent::MeteoraDlmmInitializePool(_) => (false, true),
+        _ => (false, false),
+    }
+}
+
+fn trader(event: &DexEvent) -> Option<Pubkey> {
+    let trader = match event {
+        DexEvent::PumpFunTrade(e) => e.user,
+        DexEvent::PumpSwapBuy(e) => e.user,
+        DexEvent::PumpSwapSell(e) => e.user,
+        DexEvent::BonkTrade(e) => e.user,
+        DexEvent::RaydiumClmmSwap(e) => e.sender,
+        DexEvent::RaydiumAmmV4Swap(e) => e.user_source_owner,
+        DexEvent::MeteoraDammV2Swap(e) => e.from,
+        DexEvent::MeteoraDlmmSwap(e) => e.from,
+        _ => return None,
+    };
+    (trader != Pubkey::default()).then_some(trader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{
+        BlockMetaEvent, EventMetadata, EventSource, OrcaWhirlpoolPoolInitializedEvent, OrcaWhirlpoolSwapEvent,
+        PumpSwapBuyEvent, PumpSwapSellEvent, SlotStatusEvent,
+    };
+    use solana_sdk::signature::Signature;
+
+    fn metadata(slot: u64) -> EventMetadata {
+        EventMetadata {
+            signature: Signature::default(),
+            slot,
+            tx_index: 0,
+            block_time_us: 0,
+            grpc_recv_us: 0,
+            handle_us: 0,
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    fn buy(slot: u64, user: Pubkey, sol_amount: u64) -> DexEvent {
+        DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+            metadata: metadata(slot),
+            pool_id: Pubkey::default(),
+            user,
+            token_mint: Pubkey::default(),
+            sol_amount,
+            token_amount: 0,
+            price: 0,
+            slippage: 0,
+            pool_base_token_reserves: 0,
+            pool_quote_token_reserves: 0,
+            lp_fee: 0,
+            protocol_fee: 0,
+            coin_creator: Pubkey::default(),
+            coin_creator_fee: None,
+        })
+    }
+
+    fn sell(slot: u64, user: Pubkey, sol_amount: u64) -> DexEvent {
+        DexEvent::PumpSwapSell(PumpSwapSellEvent {
+            metadata: metadata(slot),
+            pool_id: Pubkey::default(),
+            user,
+            token_mint: Pubkey::default(),
+            token_amount: 0,
+            sol_amount,
+            price: 0,
+            slippage: 0,
+            pool_base_token_reserves: 0,
+            pool_quote_token_reserves: 0,
+            lp_fee: 0,
+            protocol_fee: 0,
+            coin_creator: Pubkey::default(),
+            coin_creator_fee: None,
+        })
+    }
+
+    #[test]
+    fn test_summaries_across_two_slots() {
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut aggregator = SlotAggregator::new(0);
+
+        // 合成事件：slot 10 三笔成交（两个交易者），slot 11 一笔成交
+        assert!(aggregator.push(&buy(10, alice, 1_000)).is_empty());
+        assert!(aggregator.push(&sell(10, bob, 2_000)).is_empty());
+        assert!(aggregator.push(&buy(10, alice, 500)).is_empty());
+
+        let ready = aggregator.push(&buy(11, bob, 7_000));
+        assert_eq!(ready.len(), 1);
+        assert_eq!((ready[0].slot, ready[0].finalized), (10, false));
+        let stats = &ready[0].per_protocol[&Protocol::PumpSwap];
+        assert_eq!(*stats, ProtocolStats { trades: 3, sol_volume: 3_500, unique_traders: 2, pools_created: 0 });
+
+        // slot 10 已输出，迟到的事件不计入
+        assert!(aggregator.push(&buy(10, bob, 1)).is_empty());
+        assert_eq!(aggregator.late_events(), 1);
+
+        // BlockMeta 只说明区块完整，不代表已 finalized
+        let ready = aggregator.push(&DexEvent::BlockMeta(BlockMetaEvent { metadata: metadata(11) }));
+        assert_eq!(ready.len(), 1);
+        assert_eq!((ready[0].slot, ready[0].finalized), (11, false));
+        let stats = &ready[0].per_protocol[&Protocol::PumpSwap];
+        assert_eq!((stats.trades, stats.sol_volume, stats.unique_traders), (1, 7_000, 1));
+        assert_eq!(aggregator.pending_slots(), 0);
+    }
+
+    fn slot_status(slot: u64, status: SlotStatus) -> DexEvent {
+        DexEvent::SlotStatus(SlotStatusEvent { metadata: metadata(slot), parent: Some(slot - 1), status })
+    }
+
+    #[test]
+    fn test_finalized_comes_from_slot_status() {
+        let user = Pubkey::new_unique();
+        let mut aggregator = SlotAggregator::wait_for_finalized();
+
+        // 不按更高 slot 推断，BlockMeta 也不输出
+        assert!(aggregator.push(&buy(10, user, 1_000)).is_empty());
+        assert!(aggregator.push(&buy(11, user, 2_000)).is_empty());
+        assert!(aggregator.push(&buy(12, user, 4_000)).is_empty());
+        assert!(aggregator.push(&DexEvent::BlockMeta(BlockMetaEvent { metadata: metadata(10) })).is_empty());
+        assert!(aggregator.push(&slot_status(10, SlotStatus::Confirmed)).is_empty());
+
+        // slot 11 所在分叉被丢弃
+        assert!(aggregator.push(&slot_status(11, SlotStatus::Dead)).is_empty());
+
+        let ready = aggregator.push(&slot_status(10, SlotStatus::Finalized));
+        assert_eq!(ready.len(), 1);
+        assert_eq!((ready[0].slot, ready[0].finalized), (10, true));
+        assert_eq!(ready[0].per_protocol[&Protocol::PumpSwap].sol_volume, 1_000);
+
+        let ready = aggregator.push(&slot_status(12, SlotStatus::Finalized));
+        assert_eq!(ready.iter().map(|s| (s.slot, s.finalized)).collect::<Vec<_>>(), vec![(12, true)]);
+        assert_eq!(aggregator.pending_slots(), 0);
+        assert_eq!(aggregator.late_events(), 0);
+
+        // 推断模式下，Finalized 先于推断到达时同样标记
+        let mut aggregator = SlotAggregator::new(2);
+        aggregator.push(&buy(20, user, 1));
+        aggregator.push(&buy(21, user, 1));
+        let ready = aggregator.push(&slot_status(20, SlotStatus::Finalized));
+        assert_eq!((ready[0].slot, ready[0].finalized), (20, true));
+        let ready = aggregator.flush();
+        assert_eq!((ready[0].slot, ready[0].finalized), (21, false));
+    }
+
+    #[test]
+    fn test_wsol_side_counted_as_sol() {
+        let (whirlpool, token) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let swap = |slot, a_to_b, input_amount, output_amount| {
+            DexEvent::OrcaWhirlpoolSwap(OrcaWhirlpoolSwapEvent {
+                metadata: metadata(slot),
+                whirlpool,
+                a_to_b,
+                pre_sqrt_price: 0,
+                post_sqrt_price: 0,
+                input_amount,
+                output_amount,
+                input_transfer_fee: 0,
+                output_transfer_fee: 0,
+                lp_fee: 0,
+                protocol_fee: 0,
+            })
+        };
+        let mut aggregator = SlotAggregator::new(0);
+
+        // 池子 mint 未知时无法确定 SOL 一侧
+        aggregator.push(&swap(9, true, 5, 7));
+        let ready = aggregator.push(&DexEvent::OrcaWhirlpoolPoolInitialized(OrcaWhirlpoolPoolInitializedEvent {
+            metadata: metadata(10),
+            whirlpool,
+            whirlpools_config: Pubkey::default(),
+            token_mint_a: WSOL_MINT,
+            token_mint_b: token,
+            tick_spacing: 64,
+            token_program_a: Pubkey::default(),
+            token_program_b: Pubkey::default(),
+            decimals_a: 9,
+            decimals_b: 6,
+            initial_sqrt_price: 0,
+        }));
+        assert_eq!(ready[0].per_protocol[&Protocol::OrcaWhirlpool].sol_volume, 0);
+
+        // token A 为 WSOL：a_to_b 时 SOL 为输入，反向时为输出
+        aggregator.push(&swap(10, true, 3_000, 100));
+        aggregator.push(&swap(10, false, 200, 5_000));
+        let ready = aggregator.flush();
+        let stats = &ready[0].per_protocol[&Protocol::OrcaWhirlpool];
+        assert_eq!((stats.trades, stats.pools_created, stats.sol_volume), (2, 1, 8_000));
+
+        // PumpSwap 池子已知 quote 不是 WSOL 时不计
+        let mut aggregator = SlotAggregator::new(0);
+        aggregator.pool_mints.insert(Pubkey::default(), (token, Pubkey::new_unique()));
+        aggregator.push(&buy(30, token, 9_000));
+        let ready = aggregator.flush();
+        assert_eq!(ready[0].per_protocol[&Protocol::PumpSwap].sol_volume, 0);
+    }
+}
@@ -0,0 +1,90 @@
+//! 跨协议成交识别
+//!
+//! 同一笔交易在不同协议上成交同一个代币（例如在 PumpSwap 买入、在 Raydium CPMM 卖出）时，
+//! 在这些成交事件之后追加一个 [`DexEvent::CrossVenueTrade`]，原有成交事件不受影响。
+//!
+//! 只统计以 SOL 报价且能确定代币 mint 的成交：PumpFun、PumpSwap 使用 [`DexEvent::primary_mint`]；
+//! Raydium CPMM 事件不含 mint，由调用方从指令账户取出输入 / 输出 mint（见 [`swap_mints_from_accounts`]）。
+
+use crate::core::events::*;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+/// Raydium CPMM swap 指令中输入 / 输出 mint 的账户位置
+const CPMM_INPUT_MINT: usize = 10;
+const CPMM_OUTPUT_MINT: usize = 11;
+
+/// 从事件所在指令的账户列表取出 `(输入 mint, 输出 mint)`，只用于事件本身不含 mint 的 swap
+pub fn swap_mints_from_accounts(event: &DexEvent, accounts: &[Pubkey]) -> Option<(Pubkey, Pubkey)> {
+    match event {
+        DexEvent::RaydiumCpmmSwap(_) => Some((*accounts.get(CPMM_INPUT_MINT)?, *accounts.get(CPMM_OUTPUT_MINT)?)),
+        _ => None,
+    }
+}
+
+/// 识别跨协议成交，`events` 为同一笔交易按指令顺序排列的事件
+///
+/// 每个在两个及以上协议上成交过的 mint 追加一个 [`DexEvent::CrossVenueTrade`]，按各自最后一笔成交的顺序排列。
+/// `swap_mints` 为不含 mint 的 swap 事件提供 `(输入 mint, 输出 mint)`，取不到时返回 None（该笔成交不参与识别）。
+pub fn detect_cross_venue_trades(
+    events: &mut Vec<DexEvent>,
+    swap_mints: impl Fn(&DexEvent) -> Option<(Pubkey, Pubkey)>,
+) {
+    // (签名, mint, 最后一笔成交的下标, 各笔成交)
+    let mut groups: Vec<(Signature, Pubkey, usize, Vec<LegSummary>)> = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        let Some((mint, leg)) = leg(event, &swap_mints) else { continue };
+        let signature = event.metadata().map_or_else(Signature::default, |m| m.signature);
+        match groups.iter_mut().find(|(s, m, _, _)| *s == signature && *m == mint) {
+            Some(group) => {
+                group.2 = index;
+                group.3.push(leg);
+            }
+            None => groups.push((signature, mint, index, vec![leg])),
+        }
+    }
+
+    for (_, mint, last, legs) in groups {
+        if legs.iter().all(|leg| leg.protocol == legs[0].protocol) {
+            continue;
+        }
+        let Some(metadata) = events[last].metadata().cloned() else { continue };
+        let net_sol = legs.iter().fold(0i64, |net, leg| match leg.side {
+            TradeSide::Buy => net.saturating_sub_unsigned(leg.sol_amount),
+            TradeSide::Sell => net.saturating_add_unsigned(leg.sol_amount),
+        });
+        events.push(DexEvent::CrossVenueTrade(CrossVenueTradeEvent { metadata, mint, legs, net_sol }));
+    }
+}
+
+/// 把一笔以 SOL 报价的成交转换为 `(mint, 成交摘要)`
+fn leg(event: &DexEvent, swap_mints: &impl Fn(&DexEvent) -> Option<(Pubkey, Pubkey)>) -> Option<(Pubkey, LegSummary)> {
+    let (mint, pool, side, token_amount, sol_amount) = match event {
+        DexEvent::PumpFunTrade(e) => {
+            let side = if e.is_buy { TradeSide::Buy } else { TradeSide::Sell };
+            (event.primary_mint()?, Pubkey::default(), side, e.token_amount, e.sol_amount)
+        }
+        DexEvent::PumpSwapBuy(e) => (event.primary_mint()?, e.pool_id, TradeSide::Buy, e.token_amount, e.sol_amount),
+        DexEvent::PumpSwapSell(e) => (event.primary_mint()?, e.pool_id, TradeSide::Sell, e.token_amount, e.sol_amount),
+        DexEvent::RaydiumCpmmSwap(e) => match swap_mints(event)? {
+            (input, output) if input == WSOL_MINT => (output, e.pool_id, TradeSide::Buy, e.output_amount, e.input_amount),
+            (input, output) if output == WSOL_MINT => (input, e.pool_id, TradeSide::Sell, e.input_amount, e.output_amount),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if mint == Pubkey::default() {
+        return None;
+    }
+    let metadata = event.metadata()?;
+    let leg = LegSummary {
+        protocol: event.protocol()?,
+        pool,
+        side,
+        token_amount,
+        sol_amount,
+        outer_index: metadata.outer_index,
+        inner_index: metadata.inner_index,
+    };
+    Some((mint, leg))
+}
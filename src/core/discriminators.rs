@@ -0,0 +1,265 @@
+//! discriminator 校验
+//!
+//! 汇总内置解析器和 [`ParserRegistry`] 中注册的 discriminator，检查：
+//! - 同一程序、同一类别（指令 / 日志事件）中多个名称共用一个值，后者永远不会被匹配到
+//! - Anchor 程序中的疑似占位值：逐字节递增（`[2, 3, 4, ...]`）、全部相同、过半为 0
+//!
+//! 不同程序之间重复是正常的（按程序 ID 分发）；Raydium AMM V4 不是 Anchor 程序，不做占位值检查。
+//! 新增指令或事件时在 [`builtin_discriminators`] 中登记，由测试防止回归；
+//! 也可通过 `ClientConfig.validate_discriminators` 在客户端初始化时输出警告。
+
+use crate::core::registry::ParserRegistry;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// discriminator 类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiscriminatorKind {
+    Instruction,
+    LogEvent,
+}
+
+/// 一条 discriminator 记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscriminatorEntry {
+    pub program: String,
+    pub kind: DiscriminatorKind,
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    /// 是否为 Anchor 8 字节 discriminator（决定是否做占位值检查）
+    pub anchor: bool,
+}
+
+/// 校验发现的问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Collision {
+    /// 同一程序、同一类别中多个名称共用一个值
+    Duplicate { program: String, kind: DiscriminatorKind, discriminator: Vec<u8>, names: Vec<String> },
+    /// 疑似占位值
+    Suspicious { program: String, kind: DiscriminatorKind, name: String, discriminator: Vec<u8>, reason: &'static str },
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Collision::Duplicate { program, kind, discriminator, names } => {
+                write!(f, "{} {:?} discriminator {:?} shared by {}", program, kind, discriminator, names.join(", "))
+            }
+            Collision::Suspicious { program, kind, name, discriminator, reason } => {
+                write!(f, "{} {:?} {} = {:?} looks like a placeholder ({})", program, kind, name, discriminator, reason)
+            }
+        }
+    }
+}
+
+/// (名称, discriminator) 列表
+type NamedTable<'a> = &'a [(&'a str, &'a [u8])];
+
+macro_rules! named {
+    ($module:ident: $($name:ident),* $(,)?) => {
+        &[$((stringify!($name), &$module::$name[..])),*]
+    };
+}
+
+/// 内置解析器使用的 discriminator
+pub fn builtin_discriminators() -> Vec<DiscriminatorEntry> {
+    use crate::instr::{
+        meteora_amm::discriminators as pools_ix, meteora_damm::discriminators as damm_ix,
+        orca_whirlpool::discriminators as orca_ix, pump_amm::discriminators as pump_amm_ix,
+        pumpfun::discriminators as pumpfun_ix, raydium_amm::discriminators as amm_v4_ix,
+        raydium_clmm::discriminators as clmm_ix, raydium_cpmm::discriminators as cpmm_ix,
+        raydium_launchpad::discriminators as bonk_ix,
+    };
+    use crate::logs::{
+        meteora_amm::discriminators as pools_log, meteora_damm::discriminators as damm_log,
+        meteora_dlmm::discriminators as dlmm_log, orca_whirlpool::discriminators as orca_log,
+        pump_amm::discriminators as pump_amm_log, pumpfun::discriminators as pumpfun_log,
+        raydium_amm::discriminators as amm_v4_log, raydium_clmm::discriminators as clmm_log,
+        raydium_cpmm::discriminators as cpmm_log, raydium_launchpad::discriminators as bonk_log,
+    };
+    use DiscriminatorKind::{Instruction, LogEvent};
+
+    let amm_v4_ix: NamedTable<'_> = &[
+        ("SWAP_BASE_IN", &[amm_v4_ix::SWAP_BASE_IN]),
+        ("SWAP_BASE_OUT", &[amm_v4_ix::SWAP_BASE_OUT]),
+        ("DEPOSIT", &[amm_v4_ix::DEPOSIT]),
+        ("WITHDRAW", &[amm_v4_ix::WITHDRAW]),
+        ("INITIALIZE2", &[amm_v4_ix::INITIALIZE2]),
+        ("WITHDRAW_PNL", &[amm_v4_ix::WITHDRAW_PNL]),
+    ];
+    let tables: [(&str, DiscriminatorKind, bool, NamedTable<'_>); 19] = [
+        ("PumpFun", Instruction, true, named!(pumpfun_ix: CREATE, BUY, SELL, SET_PARAMS, MIGRATE)),
+        ("PumpFun", LogEvent, true, named!(pumpfun_log: CREATE_EVENT, TRADE_EVENT, MIGRATE_EVENT, SET_PARAMS_EVENT)),
+        ("PumpSwap", Instruction, true, named!(pump_amm_ix: BUY, SELL, CREATE_POOL, DEPOSIT, WITHDRAW)),
+        ("PumpSwap", LogEvent, true, named!(pump_amm_log: BUY, SELL, CREATE_POOL, DEPOSIT_EVENT, WITHDRAW_EVENT)),
+        ("Bonk", Instruction, true, named!(bonk_ix: BUY_EXACT_IN, BUY_EXACT_OUT, SELL_EXACT_IN, SELL_EXACT_OUT,
+            INITIALIZE, MIGRATE_TO_AMM, MIGRATE_TO_CPSWAP)),
+        ("Bonk", LogEvent, true, named!(bonk_log: TRADE, POOL_CREATE)),
+        ("RaydiumCpmm", Instruction, true, named!(cpmm_ix: SWAP_BASE_IN, SWAP_BASE_OUT, INITIALIZE, DEPOSIT, WITHDRAW,
+            CREATE_AMM_CONFIG, UPDATE_AMM_CONFIG, UPDATE_POOL_STATUS, COLLECT_PROTOCOL_FEE, COLLECT_FUND_FEE)),
+        ("RaydiumCpmm", LogEvent, true, named!(cpmm_log: SWAP_BASE_IN, SWAP_BASE_OUT, CREATE_POOL, DEPOSIT, WITHDRAW)),
+        ("RaydiumClmm", Instruction, true, named!(clmm_ix: SWAP, SWAP_V2, INCREASE_LIQUIDITY, INCREASE_LIQUIDITY_V2,
+            DECREASE_LIQUIDITY, DECREASE_LIQUIDITY_V2, CREATE_POOL, OPEN_POSITION, OPEN_POSITION_V2,
+            OPEN_POSITION_WITH_TOKEN22_NFT, CLOSE_POSITION)),
+        ("RaydiumClmm", LogEvent, true, named!(clmm_log: SWAP, INCREASE_LIQUIDITY_EVENT, DECREASE_LIQUIDITY_EVENT,
+            LIQUIDITY_CHANGE_EVENT, CREATE_PERSONAL_POSITION_EVENT, CREATE_POOL, COLLECT_FEE)),
+        ("RaydiumAmmV4", Instruction, false, amm_v4_ix),
+        ("RaydiumAmmV4", LogEvent, false, named!(amm_v4_log: SWAP_BASE_IN_EVENT, SWAP_BASE_OUT_EVENT, DEPOSIT_EVENT,
+            WITHDRAW_EVENT, INITIALIZE2_EVENT, WITHDRAW_PNL_EVENT)),
+        ("OrcaWhirlpool", Instruction, true, named!(orca_ix: INITIALIZE_CONFIG, INITIALIZE_POOL, INITIALIZE_TICK_ARRAY,
+            INITIALIZE_FEE_TIER, INITIALIZE_REWARD, SET_REWARD_EMISSIONS, OPEN_POSITION, OPEN_POSITION_WITH_METADATA,
+            INCREASE_LIQUIDITY, DECREASE_LIQUIDITY, UPDATE_FEES_AND_REWARDS, COLLECT_FEES, COLLECT_REWARD,
+            COLLECT_PROTOCOL_FEES, SWAP, CLOSE_POSITION, COLLECT_FEES_V2, COLLECT_REWARD_V2, SWAP_V2, TWO_HOP_SWAP,
+            TWO_HOP_SWAP_V2)),
+        ("OrcaWhirlpool", LogEvent, true, named!(orca_log: TRADED_EVENT, LIQUIDITY_INCREASED_EVENT,
+            LIQUIDITY_DECREASED_EVENT, POOL_INITIALIZED_EVENT)),
+        ("MeteoraPools", Instruction, true, named!(pools_ix: INITIALIZE, SWAP, ADD_LIQUIDITY, REMOVE_LIQUIDITY,
+            CREATE_CONFIG, CLOSE_CONFIG, CREATE_POOL)),
+        ("MeteoraPools", LogEvent, true, named!(pools_log: SWAP_EVENT, ADD_LIQUIDITY_EVENT, REMOVE_LIQUIDITY_EVENT,
+            BOOTSTRAP_LIQUIDITY_EVENT, POOL_CREATED_EVENT, SET_POOL_FEES_EVENT)),
+        ("MeteoraDammV2", Instruction, true, named!(damm_ix: INITIALIZE_LB_PAIR, INITIALIZE_REWARD, ADD_LIQUIDITY,
+            REMOVE_LIQUIDITY, INITIALIZE_POSITION, CLOSE_POSITION, SWAP, CLAIM_REWARD, CLAIM_POSITION_FEE, FUND_REWARD)),
+        ("MeteoraDammV2", LogEvent, true, named!(damm_log: SWAP_EVENT, ADD_LIQUIDITY_EVENT, REMOVE_LIQUIDITY_EVENT,
+            INITIALIZE_POOL_EVENT, CREATE_POSITION_EVENT, CLOSE_POSITION_EVENT, CLAIM_POSITION_FEE_EVENT,
+            INITIALIZE_REWARD_EVENT, FUND_REWARD_EVENT, CLAIM_REWARD_EVENT)),
+        ("MeteoraDlmm", LogEvent, true, named!(dlmm_log: SWAP_EVENT, ADD_LIQUIDITY_EVENT, REMOVE_LIQUIDITY_EVENT,
+            INITIALIZE_BIN_ARRAY_EVENT, INITIALIZE_POOL_EVENT, CREATE_POSITION_EVENT, CLOSE_POSITION_EVENT, CLAIM_FEE_EVENT)),
+    ];
+
+    tables
+        .into_iter()
+        .flat_map(|(program, kind, anchor, names)| {
+            names.iter().map(move |(name, discriminator)| DiscriminatorEntry {
+                program: program.to_string(),
+                kind,
+                name: name.to_string(),
+                discriminator: discriminator.to_vec(),
+                anchor,
+            })
+        })
+        .collect()
+}
+
+/// [`ParserRegistry`] 中注册的 discriminator（程序以注册名加程序 ID 区分）
+pub fn registered_discriminators() -> Vec<DiscriminatorEntry> {
+    let mut entries = Vec::new();
+    for protocol in ParserRegistry::protocols() {
+        let program = format!("{} ({})", protocol.name, protocol.program_id);
+        for spec in &protocol.instructions {
+            entries.push(DiscriminatorEntry {
+                program: program.clone(),
+                kind: DiscriminatorKind::Instruction,
+                name: spec.name.clone(),
+                discriminator: spec.discriminator.clone(),
+                anchor: spec.discriminator.len() == 8,
+            });
+        }
+        for spec in &protocol.log_events {
+            entries.push(DiscriminatorEntry {
+                program: program.clone(),
+                kind: DiscriminatorKind::LogEvent,
+                name: spec.name.clone(),
+                discriminator: spec.discriminator.to_vec(),
+                anchor: true,
+            });
+        }
+    }
+    entries
+}
+
+/// 校验内置和已注册的全部 discriminator
+pub fn validate_discriminators() -> Result<(), Vec<Collision>> {
+    let mut entries = builtin_discriminators();
+    entries.extend(registered_discriminators());
+    validate_entries(&entries)
+}
+
+/// 校验给定的 discriminator 集合
+pub fn validate_entries(entries: &[DiscriminatorEntry]) -> Result<(), Vec<Collision>> {
+    let mut groups: BTreeMap<(&str, DiscriminatorKind, &[u8]), Vec<String>> = BTreeMap::new();
+    let mut collisions = Vec::new();
+    for entry in entries {
+        groups.entry((&entry.program, entry.kind, &entry.discriminator)).or_default().push(entry.name.clone());
+        if let Some(reason) = entry.anchor.then(|| placeholder_reason(&entry.discriminator)).flatten() {
+            collisions.push(Collision::Suspicious {
+                program: entry.program.clone(),
+                kind: entry.kind,
+                name: entry.name.clone(),
+                discriminator: entry.discriminator.clone(),
+                reason,
+            });
+        }
+    }
+    for ((program, kind, discriminator), names) in groups {
+        if names.len() > 1 {
+            collisions.push(Collision::Duplicate {
+                program: program.to_string(),
+                kind,
+                discriminator: discriminator.to_vec(),
+                names,
+            });
+        }
+    }
+    if collisions.is_empty() {
+        Ok(())
+    } else {
+        Err(collisions)
+    }
+}
+
+/// Anchor discriminator 是哈希前缀，以下模式基本不可能出现
+fn placeholder_reason(discriminator: &[u8]) -> Option<&'static str> {
+    if discriminator.len() != 8 {
+        return Some("anchor discriminators are 8 bytes");
+    }
+    if discriminator.windows(2).all(|w| w[1] == w[0].wrapping_add(1)) {
+        return Some("ascending bytes");
+    }
+    if discriminator.iter().all(|&b| b == discriminator[0]) {
+        return Some("all bytes equal");
+    }
+    if discriminator.iter().filter(|&&b| b == 0).count() >= 4 {
+        return Some("mostly zero bytes");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(program: &str, name: &str, discriminator: [u8; 8]) -> DiscriminatorEntry {
+        DiscriminatorEntry {
+            program: program.into(),
+            kind: DiscriminatorKind::Instruction,
+            name: name.into(),
+            discriminator: discriminator.to_vec(),
+            anchor: true,
+        }
+    }
+
+    #[test]
+    fn test_builtin_and_registered_collisions_are_reported() {
+        let builtin = validate_entries(&builtin_discriminators());
+        assert!(builtin.is_ok(), "{:?}", builtin);
+
+        // 在内置列表后追加两个共用 discriminator 的指令和一个占位值，不改动全局注册表
+        let mut entries = builtin_discriminators();
+        entries.push(entry("collide", "buy", [7, 1, 9, 200, 13, 55, 81, 4]));
+        entries.push(entry("collide", "sell", [7, 1, 9, 200, 13, 55, 81, 4]));
+        entries.push(entry("collide", "todo", [1, 2, 3, 4, 5, 6, 7, 8]));
+        // 其它程序使用相同值不算冲突
+        entries.push(entry("other", "buy", [7, 1, 9, 200, 13, 55, 81, 4]));
+        let issues = validate_entries(&entries).unwrap_err();
+
+        let expected = Collision::Duplicate {
+            program: "collide".into(),
+            kind: DiscriminatorKind::Instruction,
+            discriminator: vec![7, 1, 9, 200, 13, 55, 81, 4],
+            names: vec!["buy".into(), "sell".into()],
+        };
+        assert!(issues.contains(&expected), "{:?}", issues);
+        assert!(issues.iter().any(|issue| matches!(issue, Collision::Suspicious { name, reason: "ascending bytes", .. } if name == "todo")));
+        assert_eq!(issues.len(), 2);
+    }
+}
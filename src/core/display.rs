@@ -0,0 +1,203 @@
+//! [`DexEvent`] 的单行可读摘要（`Display`），用于日志和示例输出
+//!
+//! 格式为 `<事件名> <摘要> @ slot <slot>`，例如 `PumpFunTrade buy 1.5 SOL for 1.2M tokens (mint Abcd…wxyz) @ slot 123`。
+//! 成交 / 流动性 / 建池事件的摘要取自 [`DexEvent::to_flat_rows`]：SOL 按 9 位精度换算，
+//! 其它代币数量为原始单位（事件不含 decimals），以 K / M / B 缩写；公钥只保留首尾各 4 位。
+//! 需要完整字段时仍使用 `{:?}`。
+
+use crate::core::events::*;
+use crate::core::flat::FlatRow;
+use std::fmt;
+
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+
+impl fmt::Display for DexEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.kind())?;
+        match self {
+            DexEvent::Error(message) => return write!(f, ": {}", message),
+            DexEvent::CrossVenueTrade(e) => write!(
+                f,
+                " {} legs, net {} SOL (mint {})",
+                e.legs.len(),
+                signed_sol(e.net_sol),
+                short(&e.mint.to_string())
+            )?,
+            _ => {
+                if let Some(row) = self.to_flat_rows().first() {
+                    summarize(f, row)?;
+                }
+            }
+        }
+        match self.metadata() {
+            Some(metadata) => write!(f, " @ slot {}", metadata.slot),
+            None => Ok(()),
+        }
+    }
+}
+
+fn summarize(f: &mut fmt::Formatter<'_>, row: &FlatRow) -> fmt::Result {
+    match row {
+        FlatRow::Trade(row) => match (row.mint_in.as_deref(), row.mint_out.as_deref()) {
+            (Some(WSOL), mint) => {
+                write!(f, " buy {} SOL for {} tokens", sol(row.amount_in), compact(row.amount_out))?;
+                mint_suffix(f, mint)
+            }
+            (mint, Some(WSOL)) => {
+                write!(f, " sell {} tokens for {} SOL", compact(row.amount_in), sol(row.amount_out))?;
+                mint_suffix(f, mint)
+            }
+            _ => {
+                write!(f, " {} in -> {} out", compact(row.amount_in), compact(row.amount_out))?;
+                pool_suffix(f, row.pool.as_deref())
+            }
+        },
+        FlatRow::Liquidity(row) => {
+            f.write_str(if row.is_add { " add" } else { " remove" })?;
+            if let (Some(a), Some(b)) = (row.amount_a, row.amount_b) {
+                write!(f, " {} / {}", compact(a), compact(b))?;
+            }
+            pool_suffix(f, row.pool.as_deref())
+        }
+        FlatRow::PoolCreate(row) => {
+            pool_suffix(f, row.pool.as_deref())?;
+            mint_suffix(f, row.mint_a.as_deref().filter(|&mint| mint != WSOL).or(row.mint_b.as_deref()))
+        }
+    }
+}
+
+fn mint_suffix(f: &mut fmt::Formatter<'_>, mint: Option<&str>) -> fmt::Result {
+    match mint {
+        Some(mint) => write!(f, " (mint {})", short(mint)),
+        None => Ok(()),
+    }
+}
+
+fn pool_suffix(f: &mut fmt::Formatter<'_>, pool: Option<&str>) -> fmt::Result {
+    match pool {
+        Some(pool) => write!(f, " (pool {})", short(pool)),
+        None => Ok(()),
+    }
+}
+
+/// base58 公钥缩写为首尾各 4 位
+fn short(key: &str) -> String {
+    match (key.get(..4), key.get(key.len().saturating_sub(4)..)) {
+        (Some(head), Some(tail)) if key.len() > 10 => format!("{}…{}", head, tail),
+        _ => key.to_string(),
+    }
+}
+
+/// lamports 换算为 SOL，最多保留 4 位小数
+fn sol(lamports: u64) -> String {
+    trim(format!("{:.4}", lamports as f64 / 1e9))
+}
+
+fn signed_sol(lamports: i64) -> String {
+    let abs = sol(lamports.unsigned_abs());
+    if lamports < 0 { format!("-{}", abs) } else { abs }
+}
+
+/// 原始数量缩写：1_200_000 -> 1.2M
+fn compact(amount: u64) -> String {
+    const UNITS: [(u64, &str); 4] = [(1_000_000_000_000, "T"), (1_000_000_000, "B"), (1_000_000, "M"), (1_000, "K")];
+    match UNITS.iter().find(|&&(unit, _)| amount >= unit) {
+        Some(&(unit, suffix)) => format!("{}{}", trim(format!("{:.1}", amount as f64 / unit as f64)), suffix),
+        None => amount.to_string(),
+    }
+}
+
+fn trim(number: String) -> String {
+    if !number.contains('.') {
+        return number;
+    }
+    number.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signature;
+
+    fn metadata(slot: u64) -> EventMetadata {
+        EventMetadata {
+            signature: Signature::default(),
+            slot,
+            tx_index: 0,
+            block_time_us: 0,
+            grpc_recv_us: 0,
+            handle_us: 0,
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    #[test]
+    fn test_trade_summary() {
+        let mint: Pubkey = "AbcdEFGH1111111111111111111111111111111wxyz".parse().unwrap();
+        let trade = DexEvent::PumpFunTrade(PumpFunTradeEvent {
+            metadata: metadata(123),
+            mint,
+            sol_amount: 1_500_000_000,
+            token_amount: 1_234_567,
+            is_buy: true,
+            is_created_buy: false,
+            is_dev_create_token_trade: false,
+            user: Pubkey::default(),
+            timestamp: 0,
+            virtual_sol_reserves: 0,
+            virtual_token_reserves: 0,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+            fee_recipient: Pubkey::default(),
+            fee_basis_points: 0,
+            fee: 0,
+            creator: Pubkey::default(),
+            creator_fee_basis_points: 0,
+            creator_fee: 0,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 0,
+            sol_limit: 0,
+            price_per_token_lamports: 0.0,
+            slippage_bps: None,
+            price_impact_bps: None,
+        });
+        assert_eq!(trade.to_string(), "PumpFunTrade buy 1.5 SOL for 1.2M tokens (mint Abcd…wxyz) @ slot 123");
+
+        let event = DexEvent::CrossVenueTrade(CrossVenueTradeEvent { metadata: metadata(7), mint, legs: Vec::new(), net_sol: -25_000_000 });
+        assert_eq!(event.to_string(), "CrossVenueTrade 0 legs, net -0.025 SOL (mint Abcd…wxyz) @ slot 7");
+
+        let swap = DexEvent::RaydiumCpmmSwap(RaydiumCpmmSwapEvent {
+            metadata: metadata(123),
+            pool_id: mint,
+            input_vault_before: 0,
+            output_vault_before: 0,
+            input_amount: 1_500,
+            output_amount: 2_345_678,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            base_input: true,
+        });
+        assert_eq!(swap.to_string(), "RaydiumCpmmSwap 1.5K in -> 2.3M out (pool Abcd…wxyz) @ slot 123");
+        assert_eq!(DexEvent::Error("boom".into()).to_string(), "Error: boom");
+    }
+
+    #[test]
+    fn test_number_formats() {
+        assert_eq!(sol(1_500_000_000), "1.5");
+        assert_eq!(sol(1_000_000_000), "1");
+        assert_eq!(compact(1_200_000), "1.2M");
+        assert_eq!(compact(999), "999");
+        assert_eq!(short("So11111111111111111111111111111111111111112"), "So11…1112");
+    }
+}
@@ -14,6 +14,101 @@ pub struct EventMetadata {
     pub tx_index: u64,  // 交易在slot中的索引，参考solana-streamer
     pub block_time_us: i64,
     pub grpc_recv_us: i64,
+    /// 事件结构体构造（解析完成）的时间戳（微秒），不早于 `grpc_recv_us`；
+    /// `handle_us - grpc_recv_us` 即该事件的解析耗时
+    #[serde(default)]
+    pub handle_us: i64,
+    /// 事件来源（合并后为 Merged）
+    #[serde(default)]
+    pub event_source: EventSource,
+    /// 所属外层指令在交易中的序号
+    #[serde(default)]
+    pub outer_index: u32,
+    /// 所属内层指令（CPI）在该外层指令中的序号，外层指令自身为 None
+    #[serde(default)]
+    pub inner_index: Option<u32>,
+    /// 事件数据布局版本（协议多次追加字段时由解析器按数据长度记录），不区分布局的事件为 None
+    #[serde(default)]
+    pub protocol_version: Option<u16>,
+    /// 订阅内的递增序号，由客户端在过滤之后、入队时分配（从 1 开始，0 表示未分配）；
+    /// 相邻事件序号不连续即有事件被丢弃，丢弃的区间见 `DexEventQueue::dropped_ranges`
+    #[serde(default)]
+    pub sequence: u64,
+    /// 指令原始数据（开启 `ClientConfig.retain_raw` 时由指令解析器填充，见 [`RawInstruction`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<Box<RawInstruction>>,
+    /// 交易的计算单元价格（ComputeBudget `SetComputeUnitPrice`，微 lamports / CU），交易未设置时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_fee_micro_lamports: Option<u64>,
+    /// 交易的计算单元上限（ComputeBudget `SetComputeUnitLimit`），交易未设置时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_unit_limit: Option<u32>,
+}
+
+impl EventMetadata {
+    /// 交易内的指令顺序键 `(outer_index, inner_index)`
+    #[inline]
+    pub fn instruction_order(&self) -> (u32, Option<u32>) {
+        (self.outer_index, self.inner_index)
+    }
+
+    /// 优先费（lamports）：单价 × 计算单元上限，向上取整；两者都已设置时才能计算
+    #[inline]
+    pub fn priority_fee_lamports(&self) -> Option<u64> {
+        let fee = self.priority_fee_micro_lamports? as u128 * self.compute_unit_limit? as u128;
+        u64::try_from(fee.div_ceil(1_000_000)).ok()
+    }
+
+    /// 金额字段的 Option 视图
+    ///
+    /// 事件结构体以 `0` 表示"未知/待补齐"（合并时按 `== 0` 从另一来源补齐），
+    /// 因此只有**仅来自指令**（`EventSource::Instruction`）且值为 0 时才视为未知并返回 None；
+    /// 日志或合并事件中的 0 是真实的零金额，返回 `Some(0)`。
+    #[inline]
+    pub fn amount_opt(&self, value: u64) -> Option<u64> {
+        (value != 0 || self.event_source != EventSource::Instruction).then_some(value)
+    }
+}
+
+/// 事件所在指令的原始数据，用于排查解码异常
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RawInstruction {
+    /// 指令数据前 [`RawInstruction::MAX_DATA_LEN`] 字节
+    pub data: Vec<u8>,
+    /// 指令数据的完整长度
+    pub data_len: usize,
+}
+
+impl RawInstruction {
+    /// 保留的最大字节数
+    pub const MAX_DATA_LEN: usize = 256;
+
+    pub fn new(instruction_data: &[u8]) -> Self {
+        let n = instruction_data.len().min(Self::MAX_DATA_LEN);
+        Self { data: instruction_data[..n].to_vec(), data_len: instruction_data.len() }
+    }
+
+    /// 指令数据前 8 字节（不足 8 字节时右侧补 0）
+    pub fn discriminator(&self) -> [u8; 8] {
+        let mut discriminator = [0u8; 8];
+        let n = self.data.len().min(8);
+        discriminator[..n].copy_from_slice(&self.data[..n]);
+        discriminator
+    }
+}
+
+/// 事件数据来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventSource {
+    /// 程序日志（执行结果）
+    #[default]
+    Log,
+    /// 指令数据（用户意图）
+    Instruction,
+    /// 指令与日志合并
+    Merged,
+    /// 账户数据（账户订阅）
+    Account,
 }
 
 /// Block Meta Event
@@ -22,13 +117,64 @@ pub struct BlockMetaEvent {
     pub metadata: EventMetadata,
 }
 
-/// Bonk Pool Create Event
+/// Slot 状态（对应 geyser 的 `SlotStatus`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SlotStatus {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+    /// 以下为 slot 内的中间状态，需开启 `SlotFilter::interslot_updates`
+    FirstShredReceived,
+    Completed,
+    CreatedBank,
+    /// 所在分叉被丢弃
+    Dead,
+}
+
+/// Slot 状态更新事件（slot 订阅，`metadata.slot` 即该 slot）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotStatusEvent {
+    pub metadata: EventMetadata,
+    pub parent: Option<u64>,
+    pub status: SlotStatus,
+}
+
+/// Bonk Pool Create Event - 对应 Raydium Launchpad `PoolCreateEvent` / `initialize` 指令
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BonkPoolCreateEvent {
     pub metadata: EventMetadata,
     pub base_mint_param: BaseMintParam,
     pub pool_state: Pubkey,
     pub creator: Pubkey,
+    /// 全局配置账户（GlobalConfig，含交易/卖出手续费率）
+    pub config: Pubkey,
+    /// 平台配置账户 [INSTRUCTION]
+    pub platform_config: Pubkey,
+    /// [INSTRUCTION]
+    pub base_mint: Pubkey,
+    /// [INSTRUCTION]
+    pub quote_mint: Pubkey,
+
+    // === 曲线参数 (CurveParams) ===
+    /// 曲线类型：0 = Constant, 1 = Fixed, 2 = Linear
+    pub curve_type: u8,
+    pub supply: u64,
+    /// 曲线上可售出的 base 数量（仅 Constant 曲线，其它曲线为 0）
+    pub total_base_sell: u64,
+    /// 迁移前需募集的 quote 数量
+    pub total_quote_fund_raising: u64,
+    /// 迁移目标：0 = AMM V4, 1 = CPSwap
+    pub migrate_type: u8,
+    /// 初始虚拟 base 储备（由 Constant 曲线参数推导，其它曲线为 0）
+    pub virtual_base: u64,
+    /// 初始虚拟 quote 储备（由 Constant 曲线参数推导，其它曲线为 0）
+    pub virtual_quote: u64,
+
+    // === 锁仓参数 (VestingParams) ===
+    pub total_locked_amount: u64,
+    pub cliff_period: u64,
+    pub unlock_period: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +197,40 @@ pub struct BonkTradeEvent {
     pub is_buy: bool,
     pub trade_direction: TradeDirection,
     pub exact_in: bool,
+    /// 代币 mint（来自指令账户，只有日志时为默认值）
+    #[serde(default)]
+    pub base_mint: Pubkey,
+    /// 报价 mint（WSOL、USD1 等，来自指令账户，只有日志时为默认值）
+    #[serde(default)]
+    pub quote_mint: Pubkey,
+    // === 用户意图（来自指令）===
+    /// exact-in 指令的最少输出
+    #[serde(default)]
+    pub minimum_amount_out: Option<u64>,
+    /// exact-out 指令的最多输入
+    #[serde(default)]
+    pub maximum_amount_in: Option<u64>,
+    // === 费用与成交后的曲线状态（来自 TradeEvent 日志）===
+    #[serde(default)]
+    pub protocol_fee: Option<u64>,
+    #[serde(default)]
+    pub platform_fee: Option<u64>,
+    #[serde(default)]
+    pub share_fee: Option<u64>,
+    #[serde(default)]
+    pub virtual_base: Option<u64>,
+    #[serde(default)]
+    pub virtual_quote: Option<u64>,
+    #[serde(default)]
+    pub real_base_after: Option<u64>,
+    #[serde(default)]
+    pub real_quote_after: Option<u64>,
+    /// 池状态：0 = Fund（募集中），1 = Migrate（待迁移），2 = Trade（已迁移）
+    #[serde(default)]
+    pub pool_status: Option<u8>,
+    /// 募集进度（万分比，合并时由 `real_quote_after` 与募集目标计算，上限 10000；募集目标未知时为 None）
+    #[serde(default)]
+    pub curve_progress_bps: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,14 +239,48 @@ pub enum TradeDirection {
     Sell,
 }
 
-/// Bonk Migrate AMM Event
+/// Bonk SOL 报价池默认的募集目标（85 SOL）
+///
+/// 同一笔交易中没有该池的 PoolCreate（其中的 `total_quote_fund_raising`）且报价为 WSOL 时，按此计算 `curve_progress_bps`。
+pub const BONK_DEFAULT_QUOTE_FUND_RAISING: u64 = 85_000_000_000;
+
+/// Wrapped SOL mint，SOL 报价统一使用该地址
+pub const WSOL_MINT: Pubkey = solana_sdk::pubkey!("So11111111111111111111111111111111111111112");
+
+/// Raydium CLMM 个人头寸账户（PDA），同一头寸从开仓到平仓的所有事件都相同
+///
+/// 种子为 `[b"position", position_nft_mint]`，头寸 NFT 全局唯一，池地址不在种子中。
+pub fn raydium_clmm_position_key(position_nft_mint: &Pubkey) -> Pubkey {
+    let program_id = crate::instr::program_ids::RAYDIUM_CLMM_PROGRAM_ID;
+    Pubkey::find_program_address(&[b"position", position_nft_mint.as_ref()], &program_id).0
+}
+
+/// 归一化的交易方向（相对 base/quote 约定）
+///
+/// Buy = 用 quote 买入 base，Sell = 卖出 base 换回 quote。
+/// 各 DEX 的 base/quote 约定见 [`DexEvent::trade_side`]。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// Bonk Migrate Event - 对应 `migrate_to_amm` / `migrate_to_cpswap` 指令
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BonkMigrateAmmEvent {
     pub metadata: EventMetadata,
+    /// Launchpad pool_state
     pub old_pool: Pubkey,
+    /// 迁移后的 AMM V4 / CPSwap 池
     pub new_pool: Pubkey,
+    /// 发起迁移的 payer
     pub user: Pubkey,
+    /// 迁移指令参数不含流动性数量，保留为 0
     pub liquidity_amount: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    /// 迁移目标：0 = AMM V4, 1 = CPSwap
+    pub migrate_type: u8,
 }
 
 /// PumpFun Trade Event - 基于官方IDL定义
@@ -83,7 +297,11 @@ pub struct PumpFunTradeEvent {
     pub sol_amount: u64,
     pub token_amount: u64,
     pub is_buy: bool,
+    /// 同笔交易中有 PumpFun create
     pub is_created_buy: bool,
+    /// 创建者在创建代币的同笔交易中的买入（dev buy）
+    #[serde(default)]
+    pub is_dev_create_token_trade: bool,
     pub user: Pubkey,
     pub timestamp: i64,
     pub virtual_sol_reserves: u64,
@@ -102,6 +320,22 @@ pub struct PumpFunTradeEvent {
     pub current_sol_volume: u64,
     pub last_update_timestamp: i64,
 
+    // === [INSTRUCTION] 用户限价 ===
+    /// 买入为 max_sol_cost，卖出为 min_sol_output（仅指令侧有值，0 表示未知）
+    #[serde(default)]
+    pub sol_limit: u64,
+
+    // === 合并时计算的派生字段（见 `core::merger`） ===
+    /// 成交均价：每个最小单位代币的 lamports
+    #[serde(default)]
+    pub price_per_token_lamports: f64,
+    /// 相对用户限价的余量（bps），负数表示超出限价；缺少指令侧时为 None
+    #[serde(default)]
+    pub slippage_bps: Option<i32>,
+    /// 成交均价相对交易前现货价的不利偏移（bps）
+    #[serde(default)]
+    pub price_impact_bps: Option<i32>,
+
     // === 指令参数字段 (暂时注释，以后可能会用到，AI不要删除) ===
     // pub amount: u64,                     // buy/sell.args.amount
     // pub max_sol_cost: u64,               // buy.args.maxSolCost
@@ -135,7 +369,12 @@ pub struct PumpFunMigrateEvent {
     pub pool_migration_fee: u64,
     pub bonding_curve: Pubkey,
     pub timestamp: i64,
+    /// 迁移创建的 PumpSwap 池
     pub pool: Pubkey,
+    /// 迁移后 PumpSwap 池的初始 base(token) 储备，由同笔交易的 PumpSwapCreatePool 关联填充
+    pub pool_base_amount: u64,
+    /// 迁移后 PumpSwap 池的初始 quote(SOL) 储备，由同笔交易的 PumpSwapCreatePool 关联填充
+    pub pool_quote_amount: u64,
     // === 额外账户信息（用于指令解析，暂时注释，以后可能会用到，AI不要删除） ===
     // pub global: Pubkey,
     // pub withdraw_authority: Pubkey,
@@ -160,6 +399,9 @@ pub struct PumpFunCreateTokenEvent {
     pub name: String,
     pub symbol: String,
     pub uri: String,
+    /// name / symbol / uri 中存在无效 UTF-8 并已替换为 U+FFFD（可用于识别仿冒代币）
+    #[serde(default)]
+    pub invalid_utf8: bool,
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub user: Pubkey,
@@ -171,6 +413,30 @@ pub struct PumpFunCreateTokenEvent {
     pub token_total_supply: u64,
 }
 
+/// PumpFun Set Params Event - 全局参数/手续费配置更新（基于IDL SetParamsEvent + set_params指令）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PumpFunSetParamsEvent {
+    pub metadata: EventMetadata,
+    // IDL SetParamsEvent 字段
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub final_real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    pub fee_basis_points: u64,
+    pub withdraw_authority: Pubkey,
+    pub enable_migrate: bool,
+    pub pool_migration_fee: u64,
+    pub creator_fee_basis_points: u64,
+    pub fee_recipients: Vec<Pubkey>,
+    pub timestamp: i64,
+    pub set_creator_authority: Pubkey,
+    pub admin_set_creator_authority: Pubkey,
+    // 指令账户字段
+    pub global: Pubkey,
+    pub authority: Pubkey,
+}
+
 /// PumpSwap Buy Event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PumpSwapBuyEvent {
@@ -178,10 +444,31 @@ pub struct PumpSwapBuyEvent {
     pub pool_id: Pubkey,
     pub user: Pubkey,
     pub token_mint: Pubkey,
+    /// 支付的 quote 数量（IDL 中的 quote_amount_in）
     pub sol_amount: u64,
+    /// 买到的 base 数量（IDL 中的 base_amount_out）
     pub token_amount: u64,
     pub price: u64,
     pub slippage: u16,
+    // === 成交明细（来自日志；旧布局没有时为默认值，见 `logs::pump_amm::layouts`） ===
+    /// 池子的 base（代币）储备（IDL 中的 pool_base_token_reserves）
+    #[serde(default)]
+    pub pool_base_token_reserves: u64,
+    /// 池子的 quote（SOL）储备（IDL 中的 pool_quote_token_reserves）
+    #[serde(default)]
+    pub pool_quote_token_reserves: u64,
+    /// LP 手续费（quote）
+    #[serde(default)]
+    pub lp_fee: u64,
+    /// 协议手续费（quote）
+    #[serde(default)]
+    pub protocol_fee: u64,
+    /// 代币创建者
+    #[serde(default)]
+    pub coin_creator: Pubkey,
+    /// 创建者手续费（quote，较新的日志布局才有）
+    #[serde(default)]
+    pub coin_creator_fee: Option<u64>,
 }
 
 /// PumpSwap Sell Event
@@ -191,10 +478,31 @@ pub struct PumpSwapSellEvent {
     pub pool_id: Pubkey,
     pub user: Pubkey,
     pub token_mint: Pubkey,
+    /// 卖出的 base 数量（IDL 中的 base_amount_in）
     pub token_amount: u64,
+    /// 得到的 quote 数量（IDL 中的 quote_amount_out）
     pub sol_amount: u64,
     pub price: u64,
     pub slippage: u16,
+    // === 成交明细（来自日志；旧布局没有时为默认值，见 `logs::pump_amm::layouts`） ===
+    /// 池子的 base（代币）储备（IDL 中的 pool_base_token_reserves）
+    #[serde(default)]
+    pub pool_base_token_reserves: u64,
+    /// 池子的 quote（SOL）储备（IDL 中的 pool_quote_token_reserves）
+    #[serde(default)]
+    pub pool_quote_token_reserves: u64,
+    /// LP 手续费（quote）
+    #[serde(default)]
+    pub lp_fee: u64,
+    /// 协议手续费（quote）
+    #[serde(default)]
+    pub protocol_fee: u64,
+    /// 代币创建者
+    #[serde(default)]
+    pub coin_creator: Pubkey,
+    /// 创建者手续费（quote，较新的日志布局才有）
+    #[serde(default)]
+    pub coin_creator_fee: Option<u64>,
 }
 
 /// PumpSwap Create Pool Event
@@ -209,75 +517,102 @@ pub struct PumpSwapCreatePoolEvent {
     pub fee_rate: u16,
 }
 
-/// PumpSwap Pool Created Event - 指令解析版本
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PumpSwapPoolCreated {
-    pub metadata: EventMetadata,
-    pub pool_account: Pubkey,
-    pub token_a_mint: Pubkey,
-    pub token_b_mint: Pubkey,
-    pub token_a_vault: Pubkey,
-    pub token_b_vault: Pubkey,
-    pub lp_mint: Pubkey,
-    pub creator: Pubkey,
-    pub authority: Pubkey,
-    pub initial_token_a_amount: u64,
-    pub initial_token_b_amount: u64,
-}
-
-/// PumpSwap Trade Event - 指令解析版本
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PumpSwapTrade {
-    pub metadata: EventMetadata,
-    pub pool_account: Pubkey,
-    pub user: Pubkey,
-    pub user_token_in_account: Pubkey,
-    pub user_token_out_account: Pubkey,
-    pub pool_token_in_vault: Pubkey,
-    pub pool_token_out_vault: Pubkey,
-    pub token_in_mint: Pubkey,
-    pub token_out_mint: Pubkey,
-    pub amount_in: u64,
-    pub minimum_amount_out: u64,
-    pub is_token_a_to_b: bool,
-}
-
-/// PumpSwap Liquidity Added Event - 指令解析版本
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PumpSwapLiquidityAdded {
-    pub metadata: EventMetadata,
-    pub pool_account: Pubkey,
-    pub user: Pubkey,
-    pub user_token_a_account: Pubkey,
-    pub user_token_b_account: Pubkey,
-    pub user_lp_token_account: Pubkey,
-    pub pool_token_a_vault: Pubkey,
-    pub pool_token_b_vault: Pubkey,
-    pub lp_mint: Pubkey,
-    pub token_a_mint: Pubkey,
-    pub token_b_mint: Pubkey,
-    pub max_token_a_amount: u64,
-    pub max_token_b_amount: u64,
-    pub min_lp_tokens: u64,
-}
-
-/// PumpSwap Liquidity Removed Event - 指令解析版本
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PumpSwapLiquidityRemoved {
-    pub metadata: EventMetadata,
-    pub pool_account: Pubkey,
-    pub user: Pubkey,
-    pub user_token_a_account: Pubkey,
-    pub user_token_b_account: Pubkey,
-    pub user_lp_token_account: Pubkey,
-    pub pool_token_a_vault: Pubkey,
-    pub pool_token_b_vault: Pubkey,
-    pub lp_mint: Pubkey,
-    pub token_a_mint: Pubkey,
-    pub token_b_mint: Pubkey,
-    pub lp_tokens_to_burn: u64,
-    pub min_token_a_amount: u64,
-    pub min_token_b_amount: u64,
+// 旧版 PumpSwap 指令事件，放在子模块中以便对 serde 派生代码统一关闭废弃警告
+#[allow(deprecated)]
+pub use legacy_pumpswap::*;
+
+#[allow(deprecated)]
+mod legacy_pumpswap {
+    use super::EventMetadata;
+    use serde::{Deserialize, Serialize};
+    use solana_sdk::pubkey::Pubkey;
+
+    /// PumpSwap Pool Created Event - 指令解析版本
+    ///
+    /// 已废弃：没有解析器产生该事件，改用 [`DexEvent::PumpSwapCreatePool`]，
+    /// 已有数据可通过 `PumpSwapCreatePoolEvent::from` 转换，下个版本移除。
+    #[deprecated(note = "use DexEvent::PumpSwapCreatePool")]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PumpSwapPoolCreated {
+        pub metadata: EventMetadata,
+        pub pool_account: Pubkey,
+        pub token_a_mint: Pubkey,
+        pub token_b_mint: Pubkey,
+        pub token_a_vault: Pubkey,
+        pub token_b_vault: Pubkey,
+        pub lp_mint: Pubkey,
+        pub creator: Pubkey,
+        pub authority: Pubkey,
+        pub initial_token_a_amount: u64,
+        pub initial_token_b_amount: u64,
+    }
+
+    /// PumpSwap Trade Event - 指令解析版本
+    ///
+    /// 已废弃：没有解析器产生该事件，改用 [`DexEvent::PumpSwapBuy`] / [`DexEvent::PumpSwapSell`]，
+    /// 已有数据可通过 `DexEvent::from` 按方向转换，下个版本移除。
+    #[deprecated(note = "use DexEvent::PumpSwapBuy / DexEvent::PumpSwapSell")]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PumpSwapTrade {
+        pub metadata: EventMetadata,
+        pub pool_account: Pubkey,
+        pub user: Pubkey,
+        pub user_token_in_account: Pubkey,
+        pub user_token_out_account: Pubkey,
+        pub pool_token_in_vault: Pubkey,
+        pub pool_token_out_vault: Pubkey,
+        pub token_in_mint: Pubkey,
+        pub token_out_mint: Pubkey,
+        pub amount_in: u64,
+        pub minimum_amount_out: u64,
+        pub is_token_a_to_b: bool,
+    }
+
+    /// PumpSwap Liquidity Added Event - 指令解析版本
+    ///
+    /// 已废弃：没有解析器产生该事件，改用 [`DexEvent::PumpSwapDeposit`]，
+    /// 已有数据可通过 `PumpSwapDepositEvent::from` 转换，下个版本移除。
+    #[deprecated(note = "use DexEvent::PumpSwapDeposit")]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PumpSwapLiquidityAdded {
+        pub metadata: EventMetadata,
+        pub pool_account: Pubkey,
+        pub user: Pubkey,
+        pub user_token_a_account: Pubkey,
+        pub user_token_b_account: Pubkey,
+        pub user_lp_token_account: Pubkey,
+        pub pool_token_a_vault: Pubkey,
+        pub pool_token_b_vault: Pubkey,
+        pub lp_mint: Pubkey,
+        pub token_a_mint: Pubkey,
+        pub token_b_mint: Pubkey,
+        pub max_token_a_amount: u64,
+        pub max_token_b_amount: u64,
+        pub min_lp_tokens: u64,
+    }
+
+    /// PumpSwap Liquidity Removed Event - 指令解析版本
+    ///
+    /// 已废弃：没有解析器产生该事件，改用 [`DexEvent::PumpSwapWithdraw`]，
+    /// 已有数据可通过 `PumpSwapWithdrawEvent::from` 转换，下个版本移除。
+    #[deprecated(note = "use DexEvent::PumpSwapWithdraw")]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PumpSwapLiquidityRemoved {
+        pub metadata: EventMetadata,
+        pub pool_account: Pubkey,
+        pub user: Pubkey,
+        pub user_token_a_account: Pubkey,
+        pub user_token_b_account: Pubkey,
+        pub user_lp_token_account: Pubkey,
+        pub pool_token_a_vault: Pubkey,
+        pub pool_token_b_vault: Pubkey,
+        pub lp_mint: Pubkey,
+        pub token_a_mint: Pubkey,
+        pub token_b_mint: Pubkey,
+        pub lp_tokens_to_burn: u64,
+        pub min_token_a_amount: u64,
+        pub min_token_b_amount: u64,
+    }
 }
 
 /// PumpSwap Pool Updated Event - 指令解析版本
@@ -302,22 +637,167 @@ pub struct PumpSwapFeesClaimed {
     pub pool_fee_vault: Pubkey,
 }
 
-/// PumpSwap Deposit Event
+/// PumpSwap Deposit Event（基于 IDL DepositEvent + deposit 指令）
+///
+/// 成交数量（`base_amount_in` / `quote_amount_in` / `lp_token_amount_out`）来自日志，
+/// `max_*` 上限和 mint 来自指令。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PumpSwapDepositEvent {
     pub metadata: EventMetadata,
+    pub timestamp: i64,
     pub pool: Pubkey,
     pub user: Pubkey,
-    pub amount: u64,
-}
-
-/// PumpSwap Withdraw Event
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_token_amount_out: u64,
+    pub max_base_amount_in: u64,
+    pub max_quote_amount_in: u64,
+    pub base_amount_in: u64,
+    pub quote_amount_in: u64,
+    pub pool_base_token_reserves: u64,
+    pub pool_quote_token_reserves: u64,
+    pub lp_mint_supply: u64,
+    pub user_base_token_account: Pubkey,
+    pub user_quote_token_account: Pubkey,
+    pub user_pool_token_account: Pubkey,
+}
+
+/// PumpSwap Withdraw Event（基于 IDL WithdrawEvent + withdraw 指令）
+///
+/// 成交数量（`base_amount_out` / `quote_amount_out`）来自日志，`min_*` 下限和 mint 来自指令。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PumpSwapWithdrawEvent {
     pub metadata: EventMetadata,
+    pub timestamp: i64,
     pub pool: Pubkey,
     pub user: Pubkey,
-    pub amount: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_token_amount_in: u64,
+    pub min_base_amount_out: u64,
+    pub min_quote_amount_out: u64,
+    pub base_amount_out: u64,
+    pub quote_amount_out: u64,
+    pub pool_base_token_reserves: u64,
+    pub pool_quote_token_reserves: u64,
+    pub lp_mint_supply: u64,
+    pub user_base_token_account: Pubkey,
+    pub user_quote_token_account: Pubkey,
+    pub user_pool_token_account: Pubkey,
+}
+
+// PumpSwap 事件迁移：
+// - PumpSwapPoolCreated      -> PumpSwapCreatePool
+// - PumpSwapTrade            -> PumpSwapBuy / PumpSwapSell（`is_token_a_to_b` 为卖出）
+// - PumpSwapLiquidityAdded   -> PumpSwapDeposit
+// - PumpSwapLiquidityRemoved -> PumpSwapWithdraw
+// 指令只带用户给出的数量，转换后成交数量字段为 0；PumpSwapTrade 的 `amount_in` 是实际输入数量，
+// `minimum_amount_out` 只是滑点下限，不作为成交数量，输出一侧为 0
+
+#[allow(deprecated)]
+impl From<PumpSwapPoolCreated> for PumpSwapCreatePoolEvent {
+    fn from(e: PumpSwapPoolCreated) -> Self {
+        Self {
+            metadata: e.metadata,
+            pool_id: e.pool_account,
+            creator: e.creator,
+            token_mint: e.token_a_mint,
+            initial_sol_amount: e.initial_token_b_amount,
+            initial_token_amount: e.initial_token_a_amount,
+            fee_rate: 0,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<PumpSwapTrade> for DexEvent {
+    fn from(e: PumpSwapTrade) -> Self {
+        if e.is_token_a_to_b {
+            DexEvent::PumpSwapSell(PumpSwapSellEvent {
+                metadata: e.metadata,
+                pool_id: e.pool_account,
+                user: e.user,
+                token_mint: e.token_in_mint,
+                token_amount: e.amount_in,
+                sol_amount: 0,
+                price: 0,
+                slippage: 0,
+                pool_base_token_reserves: 0,
+                pool_quote_token_reserves: 0,
+                lp_fee: 0,
+                protocol_fee: 0,
+                coin_creator: Pubkey::default(),
+                coin_creator_fee: None,
+            })
+        } else {
+            DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+                metadata: e.metadata,
+                pool_id: e.pool_account,
+                user: e.user,
+                token_mint: e.token_out_mint,
+                sol_amount: e.amount_in,
+                token_amount: 0,
+                price: 0,
+                slippage: 0,
+                pool_base_token_reserves: 0,
+                pool_quote_token_reserves: 0,
+                lp_fee: 0,
+                protocol_fee: 0,
+                coin_creator: Pubkey::default(),
+                coin_creator_fee: None,
+            })
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<PumpSwapLiquidityAdded> for PumpSwapDepositEvent {
+    fn from(e: PumpSwapLiquidityAdded) -> Self {
+        Self {
+            metadata: e.metadata,
+            timestamp: 0,
+            pool: e.pool_account,
+            user: e.user,
+            base_mint: e.token_a_mint,
+            quote_mint: e.token_b_mint,
+            lp_token_amount_out: e.min_lp_tokens,
+            max_base_amount_in: e.max_token_a_amount,
+            max_quote_amount_in: e.max_token_b_amount,
+            base_amount_in: 0,
+            quote_amount_in: 0,
+            pool_base_token_reserves: 0,
+            pool_quote_token_reserves: 0,
+            lp_mint_supply: 0,
+            user_base_token_account: e.user_token_a_account,
+            user_quote_token_account: e.user_token_b_account,
+            user_pool_token_account: e.user_lp_token_account,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<PumpSwapLiquidityRemoved> for PumpSwapWithdrawEvent {
+    fn from(e: PumpSwapLiquidityRemoved) -> Self {
+        Self {
+            metadata: e.metadata,
+            timestamp: 0,
+            pool: e.pool_account,
+            user: e.user,
+            base_mint: e.token_a_mint,
+            quote_mint: e.token_b_mint,
+            lp_token_amount_in: e.lp_tokens_to_burn,
+            min_base_amount_out: e.min_token_a_amount,
+            min_quote_amount_out: e.min_token_b_amount,
+            base_amount_out: 0,
+            quote_amount_out: 0,
+            pool_base_token_reserves: 0,
+            pool_quote_token_reserves: 0,
+            lp_mint_supply: 0,
+            user_base_token_account: e.user_token_a_account,
+            user_quote_token_account: e.user_token_b_account,
+            user_pool_token_account: e.user_lp_token_account,
+        }
+    }
 }
 
 /// Raydium CPMM Swap Event (基于IDL SwapEvent + swapBaseInput指令定义)
@@ -384,6 +864,17 @@ pub struct RaydiumCpmmWithdrawEvent {
     pub token1_amount: u64,
 }
 
+/// Raydium CPMM 池子状态更新事件（update_pool_status 指令，仅指令数据）
+///
+/// status 为位掩码：bit0 禁止存款，bit1 禁止取款，bit2 禁止交换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaydiumCpmmPoolStatusUpdatedEvent {
+    pub metadata: EventMetadata,
+    pub pool_state: Pubkey,
+    pub authority: Pubkey,
+    pub status: u8,
+}
+
 /// Raydium CLMM Swap Event (基于IDL SwapEvent + swap指令定义)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumClmmSwapEvent {
@@ -420,6 +911,9 @@ pub struct RaydiumClmmClosePositionEvent {
     pub pool: Pubkey,
     pub user: Pubkey,
     pub position_nft_mint: Pubkey,
+    /// 个人头寸账户（PDA），见 [`raydium_clmm_position_key`]
+    #[serde(default)]
+    pub position_key: Pubkey,
 }
 
 /// Raydium CLMM Decrease Liquidity Event
@@ -431,6 +925,21 @@ pub struct RaydiumClmmDecreaseLiquidityEvent {
     pub liquidity: u128,
     pub amount0_min: u64,
     pub amount1_min: u64,
+    #[serde(default)]
+    pub position_nft_mint: Pubkey,
+    /// 个人头寸账户（PDA），见 [`raydium_clmm_position_key`]
+    #[serde(default)]
+    pub position_key: Pubkey,
+    /// 头寸价格区间（来自 LiquidityChangeEvent）
+    #[serde(default)]
+    pub tick_lower_index: i32,
+    #[serde(default)]
+    pub tick_upper_index: i32,
+    /// 实际取出的 token0 / token1 数量（来自日志）
+    #[serde(default)]
+    pub amount0: u64,
+    #[serde(default)]
+    pub amount1: u64,
 }
 
 /// Raydium CLMM Collect Fee Event
@@ -462,6 +971,21 @@ pub struct RaydiumClmmIncreaseLiquidityEvent {
     pub liquidity: u128,
     pub amount0_max: u64,
     pub amount1_max: u64,
+    #[serde(default)]
+    pub position_nft_mint: Pubkey,
+    /// 个人头寸账户（PDA），见 [`raydium_clmm_position_key`]
+    #[serde(default)]
+    pub position_key: Pubkey,
+    /// 头寸价格区间（来自 LiquidityChangeEvent）
+    #[serde(default)]
+    pub tick_lower_index: i32,
+    #[serde(default)]
+    pub tick_upper_index: i32,
+    /// 实际存入的 token0 / token1 数量（来自日志）
+    #[serde(default)]
+    pub amount0: u64,
+    #[serde(default)]
+    pub amount1: u64,
 }
 
 /// Raydium CLMM Open Position with Token Extension NFT Event
@@ -486,6 +1010,14 @@ pub struct RaydiumClmmOpenPositionEvent {
     pub tick_lower_index: i32,
     pub tick_upper_index: i32,
     pub liquidity: u128,
+    /// 个人头寸账户（PDA），见 [`raydium_clmm_position_key`]
+    #[serde(default)]
+    pub position_key: Pubkey,
+    /// 实际存入的 token0 / token1 数量（来自 CreatePersonalPositionEvent）
+    #[serde(default)]
+    pub amount0: u64,
+    #[serde(default)]
+    pub amount1: u64,
 }
 
 /// Raydium AMM V4 Deposit Event (简化版)
@@ -538,6 +1070,12 @@ pub struct RaydiumAmmV4SwapEvent {
     pub max_amount_in: u64,
     pub amount_out: u64,
 
+    // ray_log 字段（交换前池子储备和方向）
+    pub pool_coin_amount: u64,
+    pub pool_pc_amount: u64,
+    /// 交换方向：1 = PC→Coin，2 = Coin→PC，0 = 未知
+    pub direction: u64,
+
     pub token_program: Pubkey,
     pub amm: Pubkey,
     pub amm_authority: Pubkey,
@@ -815,6 +1353,41 @@ pub struct RaydiumAmmInfo {
     pub sys_decimal_value: u64,
 }
 
+/// Raydium AMM V4 池子账户更新（由账户订阅解码，见 [`crate::accounts::raydium_amm_v4`]）
+///
+/// 账户里不直接存储储备：储备 = 金库代币余额 - 待提取的 PnL（`need_take_pnl_*`），
+/// 金库余额来自 `coin_vault` / `pc_vault` 代币账户，用 [`Self::reserves`] 换算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaydiumAmmV4PoolStateEvent {
+    pub metadata: EventMetadata,
+    /// AMM 账户地址
+    pub amm: Pubkey,
+    pub amm_info: RaydiumAmmInfo,
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    pub pool_open_time: u64,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub open_orders: Pubkey,
+    pub market: Pubkey,
+    pub market_program: Pubkey,
+    pub target_orders: Pubkey,
+    pub lp_amount: u64,
+}
+
+impl RaydiumAmmV4PoolStateEvent {
+    /// 由金库余额换算 `(coin, pc)` 储备
+    pub fn reserves(&self, coin_vault_amount: u64, pc_vault_amount: u64) -> (u64, u64) {
+        (
+            coin_vault_amount.saturating_sub(self.need_take_pnl_coin),
+            pc_vault_amount.saturating_sub(self.need_take_pnl_pc),
+        )
+    }
+}
+
 /// Raydium CLMM AMM Config Account Event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumClmmAmmConfigAccountEvent {
@@ -982,6 +1555,58 @@ pub struct TokenInfoEvent {
     pub supply: u64,
 }
 
+/// 已支持程序中无法识别的指令（discriminator 不在内置表中），需开启 `ClientConfig.emit_unknown_instructions`
+///
+/// 只说明该程序有新的 / 未解码的指令类型出现，不代表一定是 swap
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnknownSwapEvent {
+    pub metadata: EventMetadata,
+    pub program_id: Pubkey,
+    /// 指令数据前 8 字节（不足 8 字节时右侧补 0）
+    pub raw_discriminator: [u8; 8],
+    pub data_len: usize,
+}
+
+/// 跨协议成交中的一笔（见 [`CrossVenueTradeEvent`]）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegSummary {
+    pub protocol: crate::core::protocol::Protocol,
+    /// 成交的池子（PumpFun 事件不含 bonding curve 地址，为默认值）
+    pub pool: Pubkey,
+    pub side: TradeSide,
+    pub token_amount: u64,
+    /// 买入花费 / 卖出所得的 SOL（lamports）
+    pub sol_amount: u64,
+    pub outer_index: u32,
+    pub inner_index: Option<u32>,
+}
+
+/// 同一笔交易在不同协议上成交同一个代币，需开启 `ClientConfig.detect_cross_venue_trades`
+///
+/// 在各笔成交事件之后输出（成交事件本身照常输出），`metadata` 沿用最后一笔成交的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossVenueTradeEvent {
+    pub metadata: EventMetadata,
+    pub mint: Pubkey,
+    /// 按指令顺序排列的各笔成交
+    pub legs: Vec<LegSummary>,
+    /// 各笔成交的 SOL 净额（卖出所得减买入花费，lamports），不含交易费和 tip
+    pub net_sol: i64,
+}
+
+/// 通过 [`crate::core::registry::ParserRegistry`] 注册的自定义协议事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomEvent {
+    pub metadata: EventMetadata,
+    /// 注册时的协议名
+    pub protocol: String,
+    /// 事件 / 指令名
+    pub name: String,
+    pub program_id: Pubkey,
+    /// 解析函数输出的字段
+    pub data: serde_json::Value,
+}
+
 // ====================== Orca Whirlpool Events ======================
 
 /// Orca Whirlpool Swap Event (基于 TradedEvent，不是 SwapEvent)
@@ -1064,6 +1689,40 @@ pub struct OrcaWhirlpoolPoolInitializedEvent {
     pub initial_sqrt_price: u128,
 }
 
+/// Orca Whirlpool Collect Fees Event（LP 领取头寸累计的手续费）
+///
+/// 程序不为 collectFees 发出事件，由指令解析；`amount_a` / `amount_b` 为实际转出的手续费，
+/// 指令数据不携带金额，无法确定时为 0。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrcaWhirlpoolCollectFeesEvent {
+    pub metadata: EventMetadata,
+    pub whirlpool: Pubkey,
+    pub position: Pubkey,
+    /// positionAuthority
+    pub owner: Pubkey,
+    pub token_owner_account_a: Pubkey,
+    pub token_owner_account_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// Orca Whirlpool Collect Reward Event（LP 领取头寸累计的流动性挖矿奖励）
+///
+/// 与 collectFees 相同，程序不发出事件；`amount` 由内层 token 转账补齐，
+/// `reward_mint` 在 V2 指令账户中，V1 只能从 TransferChecked 转账得到，未知时为默认值。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrcaWhirlpoolCollectRewardEvent {
+    pub metadata: EventMetadata,
+    pub whirlpool: Pubkey,
+    pub position: Pubkey,
+    /// positionAuthority
+    pub owner: Pubkey,
+    pub reward_owner_account: Pubkey,
+    pub reward_index: u8,
+    pub amount: u64,
+    pub reward_mint: Pubkey,
+}
+
 // ====================== Meteora Pools Events ======================
 
 /// Meteora Pools Swap Event
@@ -1075,6 +1734,27 @@ pub struct MeteoraPoolsSwapEvent {
     pub trade_fee: u64,
     pub admin_fee: u64,  // IDL字段名: adminFee
     pub host_fee: u64,
+    /// 池子地址（来自指令账户，只有日志时为默认值）
+    #[serde(default)]
+    pub pool: Pubkey,
+    /// 本次成交使用的费率模式，需要池子的费率配置才能判断
+    #[serde(default)]
+    pub fee_mode: MeteoraFeeMode,
+    /// 有效费率 bps：`(trade_fee + admin_fee + host_fee) / in_amount`
+    #[serde(default)]
+    pub effective_fee_bps: u16,
+}
+
+/// Meteora Pools 手续费模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MeteoraFeeMode {
+    /// 不知道池子的费率配置（Swap 事件本身不带配置）
+    #[default]
+    Unknown,
+    /// 实收手续费等于池子配置的交易费率
+    Base,
+    /// 实收手续费偏离池子配置的交易费率
+    Dynamic,
 }
 
 /// Meteora Pools Add Liquidity Event
@@ -1129,22 +1809,56 @@ pub struct MeteoraPoolsSetPoolFeesEvent {
 
 // ====================== Meteora DAMM V2 Events ======================
 
-/// Meteora DAMM V2 Swap Event
+/// Meteora DAMM V2 Swap Event（日志为 IDL `EvtSwap`）
+///
+/// DAMM V2 是恒定乘积池，没有 bin：`start_bin_id` / `end_bin_id` / `fee_bps` 为 0。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeteoraDammV2SwapEvent {
     pub metadata: EventMetadata,
     // === 事件核心字段 ===
+    /// 池子（IDL `pool`）
     pub lb_pair: Pubkey,
+    /// 交易发起者（来自指令，日志不含）
     pub from: Pubkey,
     pub start_bin_id: i32,
     pub end_bin_id: i32,
+    /// 实际输入数量（IDL `actual_amount_in`）
     pub amount_in: u64,
     pub amount_out: u64,
+    /// A 换 B（IDL `trade_direction == 0`）
     pub swap_for_y: bool,
+    /// LP 手续费（IDL `lp_fee`）
     pub fee: u64,
     pub protocol_fee: u64,
     pub fee_bps: u128,
+    /// 推荐人手续费（IDL `referral_fee`）
     pub host_fee: u64,
+    /// 合作方手续费（IDL `partner_fee`）
+    #[serde(default)]
+    pub partner_fee: u64,
+    // === 价格字段（Q64.64 sqrt 价格） ===
+    /// 交易前价格，事件不含，需调用方（例如按池子记录的上一笔 `post_sqrt_price`）填入，否则为 0
+    #[serde(default)]
+    pub pre_sqrt_price: u128,
+    /// 交易后价格（IDL `next_sqrt_price`）
+    #[serde(default)]
+    pub post_sqrt_price: u128,
+}
+
+impl MeteoraDammV2SwapEvent {
+    /// 成交造成的价格变动（bps，按 sqrt 价格换算为现货价后取绝对值），缺少价格时返回 None
+    pub fn price_impact_bps(&self) -> Option<u32> {
+        sqrt_price_impact_bps(self.pre_sqrt_price, self.post_sqrt_price)
+    }
+}
+
+/// 由交易前后的 sqrt 价格计算价格变动（bps）：`|(post / pre)² - 1| × 10000`
+pub fn sqrt_price_impact_bps(pre_sqrt_price: u128, post_sqrt_price: u128) -> Option<u32> {
+    if pre_sqrt_price == 0 || post_sqrt_price == 0 {
+        return None;
+    }
+    let ratio = post_sqrt_price as f64 / pre_sqrt_price as f64;
+    Some(((ratio * ratio - 1.0).abs() * 10_000.0).round() as u32)
 }
 
 /// Meteora DAMM V2 Add Liquidity Event
@@ -1245,11 +1959,14 @@ pub struct MeteoraDlmmSwapEvent {
     pub metadata: EventMetadata,
     pub pool: Pubkey,  // lbPair in IDL
     pub from: Pubkey,
+    /// 交易前的活跃 bin
     pub start_bin_id: i32,
+    /// 交易后的活跃 bin
     pub end_bin_id: i32,
     pub amount_in: u64,
     pub amount_out: u64,
     pub swap_for_y: bool,
+    /// 手续费数量（输入代币）
     pub fee: u64,
     pub protocol_fee: u64,
     pub fee_bps: u128,  // IDL字段
@@ -1330,100 +2047,766 @@ pub struct MeteoraDlmmClaimFeeEvent {
 
 // ====================== 统一的 DEX 事件枚举 ======================
 
-/// 统一的 DEX 事件枚举 - 参考 sol-dex-shreds 的做法
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum DexEvent {
-    // PumpFun 事件
-    PumpFunCreate(PumpFunCreateTokenEvent),
-    PumpFunTrade(PumpFunTradeEvent),
-    PumpFunComplete(PumpFunCompleteTokenEvent),
-    PumpFunMigrate(PumpFunMigrateEvent),
-
-    // Bonk 事件
-    BonkTrade(BonkTradeEvent),
-    BonkPoolCreate(BonkPoolCreateEvent),
-    BonkMigrateAmm(BonkMigrateAmmEvent),
-
-    // PumpSwap 事件
-    PumpSwapBuy(PumpSwapBuyEvent),
-    PumpSwapSell(PumpSwapSellEvent),
-    PumpSwapCreatePool(PumpSwapCreatePoolEvent),
-    PumpSwapPoolCreated(PumpSwapPoolCreated),
-    PumpSwapTrade(PumpSwapTrade),
-    PumpSwapLiquidityAdded(PumpSwapLiquidityAdded),
-    PumpSwapLiquidityRemoved(PumpSwapLiquidityRemoved),
-    PumpSwapPoolUpdated(PumpSwapPoolUpdated),
-    PumpSwapFeesClaimed(PumpSwapFeesClaimed),
-
-    // Raydium CLMM 事件
-    RaydiumClmmSwap(RaydiumClmmSwapEvent),
-    RaydiumClmmCreatePool(RaydiumClmmCreatePoolEvent),
-    RaydiumClmmOpenPosition(RaydiumClmmOpenPositionEvent),
-    RaydiumClmmOpenPositionWithTokenExtNft(RaydiumClmmOpenPositionWithTokenExtNftEvent),
-    RaydiumClmmClosePosition(RaydiumClmmClosePositionEvent),
-    RaydiumClmmIncreaseLiquidity(RaydiumClmmIncreaseLiquidityEvent),
-    RaydiumClmmDecreaseLiquidity(RaydiumClmmDecreaseLiquidityEvent),
-    RaydiumClmmCollectFee(RaydiumClmmCollectFeeEvent),
-
-    // Raydium CPMM 事件
-    RaydiumCpmmSwap(RaydiumCpmmSwapEvent),
-    RaydiumCpmmDeposit(RaydiumCpmmDepositEvent),
-    RaydiumCpmmWithdraw(RaydiumCpmmWithdrawEvent),
-    RaydiumCpmmInitialize(RaydiumCpmmInitializeEvent),
-
-    // Raydium AMM V4 事件
-    RaydiumAmmV4Swap(RaydiumAmmV4SwapEvent),
-    RaydiumAmmV4Deposit(RaydiumAmmV4DepositEvent),
-    RaydiumAmmV4Initialize2(RaydiumAmmV4Initialize2Event),
-    RaydiumAmmV4Withdraw(RaydiumAmmV4WithdrawEvent),
-    RaydiumAmmV4WithdrawPnl(RaydiumAmmV4WithdrawPnlEvent),
-
-    // Orca Whirlpool 事件
-    OrcaWhirlpoolSwap(OrcaWhirlpoolSwapEvent),
-    OrcaWhirlpoolLiquidityIncreased(OrcaWhirlpoolLiquidityIncreasedEvent),
-    OrcaWhirlpoolLiquidityDecreased(OrcaWhirlpoolLiquidityDecreasedEvent),
-    OrcaWhirlpoolPoolInitialized(OrcaWhirlpoolPoolInitializedEvent),
-
-    // Meteora Pools 事件
-    MeteoraPoolsSwap(MeteoraPoolsSwapEvent),
-    MeteoraPoolsAddLiquidity(MeteoraPoolsAddLiquidityEvent),
-    MeteoraPoolsRemoveLiquidity(MeteoraPoolsRemoveLiquidityEvent),
-    MeteoraPoolsBootstrapLiquidity(MeteoraPoolsBootstrapLiquidityEvent),
-    MeteoraPoolsPoolCreated(MeteoraPoolsPoolCreatedEvent),
-    MeteoraPoolsSetPoolFees(MeteoraPoolsSetPoolFeesEvent),
-
-    // Meteora DAMM V2 事件
-    MeteoraDammV2Swap(MeteoraDammV2SwapEvent),
-    MeteoraDammV2AddLiquidity(MeteoraDammV2AddLiquidityEvent),
-    MeteoraDammV2RemoveLiquidity(MeteoraDammV2RemoveLiquidityEvent),
-    MeteoraDammV2InitializePool(MeteoraDammV2InitializePoolEvent),
-    MeteoraDammV2CreatePosition(MeteoraDammV2CreatePositionEvent),
-    MeteoraDammV2ClosePosition(MeteoraDammV2ClosePositionEvent),
-    MeteoraDammV2ClaimPositionFee(MeteoraDammV2ClaimPositionFeeEvent),
-    MeteoraDammV2InitializeReward(MeteoraDammV2InitializeRewardEvent),
-    MeteoraDammV2FundReward(MeteoraDammV2FundRewardEvent),
-    MeteoraDammV2ClaimReward(MeteoraDammV2ClaimRewardEvent),
-
-    // Meteora DLMM 事件
-    MeteoraDlmmSwap(MeteoraDlmmSwapEvent),
-    MeteoraDlmmAddLiquidity(MeteoraDlmmAddLiquidityEvent),
-    MeteoraDlmmRemoveLiquidity(MeteoraDlmmRemoveLiquidityEvent),
-    MeteoraDlmmInitializePool(MeteoraDlmmInitializePoolEvent),
-    MeteoraDlmmInitializeBinArray(MeteoraDlmmInitializeBinArrayEvent),
-    MeteoraDlmmCreatePosition(MeteoraDlmmCreatePositionEvent),
-    MeteoraDlmmClosePosition(MeteoraDlmmClosePositionEvent),
-    MeteoraDlmmClaimFee(MeteoraDlmmClaimFeeEvent),
-
-    // 账户事件
-    TokenAccount(TokenAccountEvent),
-    NonceAccount(NonceAccountEvent),
-
-    // 区块元数据事件
-    BlockMeta(BlockMetaEvent),
-
-    // Token 信息事件
-    TokenInfo(TokenInfoEvent),
-
-    // 错误事件
-    Error(String),
-}
\ No newline at end of file
+// 枚举放在子模块中，以便对 serde 派生代码中已废弃变体的引用统一关闭警告
+pub use dex_event::DexEvent;
+
+#[allow(deprecated)]
+mod dex_event {
+    use super::*;
+
+    /// 统一的 DEX 事件枚举 - 参考 sol-dex-shreds 的做法
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub enum DexEvent {
+        // PumpFun 事件
+        PumpFunCreate(PumpFunCreateTokenEvent),
+        PumpFunTrade(PumpFunTradeEvent),
+        PumpFunComplete(PumpFunCompleteTokenEvent),
+        PumpFunMigrate(PumpFunMigrateEvent),
+        PumpFunSetParams(PumpFunSetParamsEvent),
+
+        // Bonk 事件
+        BonkTrade(BonkTradeEvent),
+        BonkPoolCreate(BonkPoolCreateEvent),
+        BonkMigrateAmm(BonkMigrateAmmEvent),
+
+        // PumpSwap 事件
+        PumpSwapBuy(PumpSwapBuyEvent),
+        PumpSwapSell(PumpSwapSellEvent),
+        PumpSwapCreatePool(PumpSwapCreatePoolEvent),
+        /// 已废弃：没有解析器产生，改用 [`DexEvent::PumpSwapCreatePool`]，下个版本移除
+        #[deprecated(note = "use DexEvent::PumpSwapCreatePool")]
+        PumpSwapPoolCreated(PumpSwapPoolCreated),
+        /// 已废弃：没有解析器产生，改用 [`DexEvent::PumpSwapBuy`] / [`DexEvent::PumpSwapSell`]，下个版本移除
+        #[deprecated(note = "use DexEvent::PumpSwapBuy / DexEvent::PumpSwapSell")]
+        PumpSwapTrade(PumpSwapTrade),
+        /// 已废弃：没有解析器产生，改用 [`DexEvent::PumpSwapDeposit`]，下个版本移除
+        #[deprecated(note = "use DexEvent::PumpSwapDeposit")]
+        PumpSwapLiquidityAdded(PumpSwapLiquidityAdded),
+        /// 已废弃：没有解析器产生，改用 [`DexEvent::PumpSwapWithdraw`]，下个版本移除
+        #[deprecated(note = "use DexEvent::PumpSwapWithdraw")]
+        PumpSwapLiquidityRemoved(PumpSwapLiquidityRemoved),
+        PumpSwapPoolUpdated(PumpSwapPoolUpdated),
+        PumpSwapFeesClaimed(PumpSwapFeesClaimed),
+        PumpSwapDeposit(PumpSwapDepositEvent),
+        PumpSwapWithdraw(PumpSwapWithdrawEvent),
+
+        // Raydium CLMM 事件
+        RaydiumClmmSwap(RaydiumClmmSwapEvent),
+        RaydiumClmmCreatePool(RaydiumClmmCreatePoolEvent),
+        RaydiumClmmOpenPosition(RaydiumClmmOpenPositionEvent),
+        RaydiumClmmOpenPositionWithTokenExtNft(RaydiumClmmOpenPositionWithTokenExtNftEvent),
+        RaydiumClmmClosePosition(RaydiumClmmClosePositionEvent),
+        RaydiumClmmIncreaseLiquidity(RaydiumClmmIncreaseLiquidityEvent),
+        RaydiumClmmDecreaseLiquidity(RaydiumClmmDecreaseLiquidityEvent),
+        RaydiumClmmCollectFee(RaydiumClmmCollectFeeEvent),
+
+        // Raydium CPMM 事件
+        RaydiumCpmmSwap(RaydiumCpmmSwapEvent),
+        RaydiumCpmmDeposit(RaydiumCpmmDepositEvent),
+        RaydiumCpmmWithdraw(RaydiumCpmmWithdrawEvent),
+        RaydiumCpmmInitialize(RaydiumCpmmInitializeEvent),
+        RaydiumCpmmPoolStatusUpdated(RaydiumCpmmPoolStatusUpdatedEvent),
+
+        // Raydium AMM V4 事件
+        RaydiumAmmV4Swap(RaydiumAmmV4SwapEvent),
+        RaydiumAmmV4Deposit(RaydiumAmmV4DepositEvent),
+        RaydiumAmmV4Initialize2(RaydiumAmmV4Initialize2Event),
+        RaydiumAmmV4Withdraw(RaydiumAmmV4WithdrawEvent),
+        RaydiumAmmV4WithdrawPnl(RaydiumAmmV4WithdrawPnlEvent),
+        RaydiumAmmV4PoolState(RaydiumAmmV4PoolStateEvent),
+
+        // Orca Whirlpool 事件
+        OrcaWhirlpoolSwap(OrcaWhirlpoolSwapEvent),
+        OrcaWhirlpoolLiquidityIncreased(OrcaWhirlpoolLiquidityIncreasedEvent),
+        OrcaWhirlpoolLiquidityDecreased(OrcaWhirlpoolLiquidityDecreasedEvent),
+        OrcaWhirlpoolPoolInitialized(OrcaWhirlpoolPoolInitializedEvent),
+        OrcaWhirlpoolCollectFees(OrcaWhirlpoolCollectFeesEvent),
+        OrcaWhirlpoolCollectReward(OrcaWhirlpoolCollectRewardEvent),
+
+        // Meteora Pools 事件
+        MeteoraPoolsSwap(MeteoraPoolsSwapEvent),
+        MeteoraPoolsAddLiquidity(MeteoraPoolsAddLiquidityEvent),
+        MeteoraPoolsRemoveLiquidity(MeteoraPoolsRemoveLiquidityEvent),
+        MeteoraPoolsBootstrapLiquidity(MeteoraPoolsBootstrapLiquidityEvent),
+        MeteoraPoolsPoolCreated(MeteoraPoolsPoolCreatedEvent),
+        MeteoraPoolsSetPoolFees(MeteoraPoolsSetPoolFeesEvent),
+
+        // Meteora DAMM V2 事件
+        MeteoraDammV2Swap(MeteoraDammV2SwapEvent),
+        MeteoraDammV2AddLiquidity(MeteoraDammV2AddLiquidityEvent),
+        MeteoraDammV2RemoveLiquidity(MeteoraDammV2RemoveLiquidityEvent),
+        MeteoraDammV2InitializePool(MeteoraDammV2InitializePoolEvent),
+        MeteoraDammV2CreatePosition(MeteoraDammV2CreatePositionEvent),
+        MeteoraDammV2ClosePosition(MeteoraDammV2ClosePositionEvent),
+        MeteoraDammV2ClaimPositionFee(MeteoraDammV2ClaimPositionFeeEvent),
+        MeteoraDammV2InitializeReward(MeteoraDammV2InitializeRewardEvent),
+        MeteoraDammV2FundReward(MeteoraDammV2FundRewardEvent),
+        MeteoraDammV2ClaimReward(MeteoraDammV2ClaimRewardEvent),
+
+        // Meteora DLMM 事件
+        MeteoraDlmmSwap(MeteoraDlmmSwapEvent),
+        MeteoraDlmmAddLiquidity(MeteoraDlmmAddLiquidityEvent),
+        MeteoraDlmmRemoveLiquidity(MeteoraDlmmRemoveLiquidityEvent),
+        MeteoraDlmmInitializePool(MeteoraDlmmInitializePoolEvent),
+        MeteoraDlmmInitializeBinArray(MeteoraDlmmInitializeBinArrayEvent),
+        MeteoraDlmmCreatePosition(MeteoraDlmmCreatePositionEvent),
+        MeteoraDlmmClosePosition(MeteoraDlmmClosePositionEvent),
+        MeteoraDlmmClaimFee(MeteoraDlmmClaimFeeEvent),
+
+        // 账户事件
+        TokenAccount(TokenAccountEvent),
+        NonceAccount(NonceAccountEvent),
+
+        // 区块元数据事件
+        BlockMeta(BlockMetaEvent),
+        SlotStatus(SlotStatusEvent),
+
+        // Token 信息事件
+        TokenInfo(TokenInfoEvent),
+
+        // 自定义协议事件（运行时注册）
+        Custom(CustomEvent),
+
+        // 已支持程序中无法识别的指令
+        UnknownSwap(UnknownSwapEvent),
+
+        // 跨协议成交（解析后分析生成）
+        CrossVenueTrade(CrossVenueTradeEvent),
+
+        // 错误事件
+        Error(String),
+    }
+}
+/// 为所有带 metadata 的变体生成统一访问器
+macro_rules! dex_event_metadata {
+    ($self:expr, $e:ident => $body:expr, $none:expr) => {
+        match $self {
+            DexEvent::PumpFunCreate($e) => $body,
+            DexEvent::PumpFunTrade($e) => $body,
+            DexEvent::PumpFunComplete($e) => $body,
+            DexEvent::PumpFunMigrate($e) => $body,
+            DexEvent::PumpFunSetParams($e) => $body,
+            DexEvent::BonkTrade($e) => $body,
+            DexEvent::BonkPoolCreate($e) => $body,
+            DexEvent::BonkMigrateAmm($e) => $body,
+            DexEvent::PumpSwapBuy($e) => $body,
+            DexEvent::PumpSwapSell($e) => $body,
+            DexEvent::PumpSwapCreatePool($e) => $body,
+            DexEvent::PumpSwapPoolCreated($e) => $body,
+            DexEvent::PumpSwapTrade($e) => $body,
+            DexEvent::PumpSwapLiquidityAdded($e) => $body,
+            DexEvent::PumpSwapLiquidityRemoved($e) => $body,
+            DexEvent::PumpSwapPoolUpdated($e) => $body,
+            DexEvent::PumpSwapFeesClaimed($e) => $body,
+            DexEvent::PumpSwapDeposit($e) => $body,
+            DexEvent::PumpSwapWithdraw($e) => $body,
+            DexEvent::RaydiumClmmSwap($e) => $body,
+            DexEvent::RaydiumClmmCreatePool($e) => $body,
+            DexEvent::RaydiumClmmOpenPosition($e) => $body,
+            DexEvent::RaydiumClmmOpenPositionWithTokenExtNft($e) => $body,
+            DexEvent::RaydiumClmmClosePosition($e) => $body,
+            DexEvent::RaydiumClmmIncreaseLiquidity($e) => $body,
+            DexEvent::RaydiumClmmDecreaseLiquidity($e) => $body,
+            DexEvent::RaydiumClmmCollectFee($e) => $body,
+            DexEvent::RaydiumCpmmSwap($e) => $body,
+            DexEvent::RaydiumCpmmDeposit($e) => $body,
+            DexEvent::RaydiumCpmmWithdraw($e) => $body,
+            DexEvent::RaydiumCpmmInitialize($e) => $body,
+            DexEvent::RaydiumCpmmPoolStatusUpdated($e) => $body,
+            DexEvent::RaydiumAmmV4Swap($e) => $body,
+            DexEvent::RaydiumAmmV4Deposit($e) => $body,
+            DexEvent::RaydiumAmmV4Initialize2($e) => $body,
+            DexEvent::RaydiumAmmV4Withdraw($e) => $body,
+            DexEvent::RaydiumAmmV4WithdrawPnl($e) => $body,
+            DexEvent::RaydiumAmmV4PoolState($e) => $body,
+            DexEvent::OrcaWhirlpoolSwap($e) => $body,
+            DexEvent::OrcaWhirlpoolLiquidityIncreased($e) => $body,
+            DexEvent::OrcaWhirlpoolLiquidityDecreased($e) => $body,
+            DexEvent::OrcaWhirlpoolPoolInitialized($e) => $body,
+            DexEvent::OrcaWhirlpoolCollectFees($e) => $body,
+            DexEvent::OrcaWhirlpoolCollectReward($e) => $body,
+            DexEvent::MeteoraPoolsSwap($e) => $body,
+            DexEvent::MeteoraPoolsAddLiquidity($e) => $body,
+            DexEvent::MeteoraPoolsRemoveLiquidity($e) => $body,
+            DexEvent::MeteoraPoolsBootstrapLiquidity($e) => $body,
+            DexEvent::MeteoraPoolsPoolCreated($e) => $body,
+            DexEvent::MeteoraPoolsSetPoolFees($e) => $body,
+            DexEvent::MeteoraDammV2Swap($e) => $body,
+            DexEvent::MeteoraDammV2AddLiquidity($e) => $body,
+            DexEvent::MeteoraDammV2RemoveLiquidity($e) => $body,
+            DexEvent::MeteoraDammV2InitializePool($e) => $body,
+            DexEvent::MeteoraDammV2CreatePosition($e) => $body,
+            DexEvent::MeteoraDammV2ClosePosition($e) => $body,
+            DexEvent::MeteoraDammV2ClaimPositionFee($e) => $body,
+            DexEvent::MeteoraDammV2InitializeReward($e) => $body,
+            DexEvent::MeteoraDammV2FundReward($e) => $body,
+            DexEvent::MeteoraDammV2ClaimReward($e) => $body,
+            DexEvent::MeteoraDlmmSwap($e) => $body,
+            DexEvent::MeteoraDlmmAddLiquidity($e) => $body,
+            DexEvent::MeteoraDlmmRemoveLiquidity($e) => $body,
+            DexEvent::MeteoraDlmmInitializePool($e) => $body,
+            DexEvent::MeteoraDlmmInitializeBinArray($e) => $body,
+            DexEvent::MeteoraDlmmCreatePosition($e) => $body,
+            DexEvent::MeteoraDlmmClosePosition($e) => $body,
+            DexEvent::MeteoraDlmmClaimFee($e) => $body,
+            DexEvent::TokenAccount($e) => $body,
+            DexEvent::NonceAccount($e) => $body,
+            DexEvent::BlockMeta($e) => $body,
+            DexEvent::SlotStatus($e) => $body,
+            DexEvent::TokenInfo($e) => $body,
+            DexEvent::Custom($e) => $body,
+            DexEvent::UnknownSwap($e) => $body,
+            DexEvent::CrossVenueTrade($e) => $body,
+            DexEvent::Error(_) => $none,
+        }
+    };
+}
+
+#[allow(deprecated)]
+impl DexEvent {
+    /// 获取事件元数据（Error 事件没有元数据）
+    #[inline]
+    pub fn metadata(&self) -> Option<&EventMetadata> {
+        dex_event_metadata!(self, e => Some(&e.metadata), None)
+    }
+
+    /// 获取可变事件元数据
+    #[inline]
+    pub fn metadata_mut(&mut self) -> Option<&mut EventMetadata> {
+        dex_event_metadata!(self, e => Some(&mut e.metadata), None)
+    }
+
+    /// 事件变体名（与 serde 序列化的标签一致）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DexEvent::PumpFunCreate(_) => "PumpFunCreate",
+            DexEvent::PumpFunTrade(_) => "PumpFunTrade",
+            DexEvent::PumpFunComplete(_) => "PumpFunComplete",
+            DexEvent::PumpFunMigrate(_) => "PumpFunMigrate",
+            DexEvent::PumpFunSetParams(_) => "PumpFunSetParams",
+            DexEvent::BonkTrade(_) => "BonkTrade",
+            DexEvent::BonkPoolCreate(_) => "BonkPoolCreate",
+            DexEvent::BonkMigrateAmm(_) => "BonkMigrateAmm",
+            DexEvent::PumpSwapBuy(_) => "PumpSwapBuy",
+            DexEvent::PumpSwapSell(_) => "PumpSwapSell",
+            DexEvent::PumpSwapCreatePool(_) => "PumpSwapCreatePool",
+            DexEvent::PumpSwapPoolCreated(_) => "PumpSwapPoolCreated",
+            DexEvent::PumpSwapTrade(_) => "PumpSwapTrade",
+            DexEvent::PumpSwapLiquidityAdded(_) => "PumpSwapLiquidityAdded",
+            DexEvent::PumpSwapLiquidityRemoved(_) => "PumpSwapLiquidityRemoved",
+            DexEvent::PumpSwapPoolUpdated(_) => "PumpSwapPoolUpdated",
+            DexEvent::PumpSwapFeesClaimed(_) => "PumpSwapFeesClaimed",
+            DexEvent::PumpSwapDeposit(_) => "PumpSwapDeposit",
+            DexEvent::PumpSwapWithdraw(_) => "PumpSwapWithdraw",
+            DexEvent::RaydiumClmmSwap(_) => "RaydiumClmmSwap",
+            DexEvent::RaydiumClmmCreatePool(_) => "RaydiumClmmCreatePool",
+            DexEvent::RaydiumClmmOpenPosition(_) => "RaydiumClmmOpenPosition",
+            DexEvent::RaydiumClmmOpenPositionWithTokenExtNft(_) => "RaydiumClmmOpenPositionWithTokenExtNft",
+            DexEvent::RaydiumClmmClosePosition(_) => "RaydiumClmmClosePosition",
+            DexEvent::RaydiumClmmIncreaseLiquidity(_) => "RaydiumClmmIncreaseLiquidity",
+            DexEvent::RaydiumClmmDecreaseLiquidity(_) => "RaydiumClmmDecreaseLiquidity",
+            DexEvent::RaydiumClmmCollectFee(_) => "RaydiumClmmCollectFee",
+            DexEvent::RaydiumCpmmSwap(_) => "RaydiumCpmmSwap",
+            DexEvent::RaydiumCpmmDeposit(_) => "RaydiumCpmmDeposit",
+            DexEvent::RaydiumCpmmWithdraw(_) => "RaydiumCpmmWithdraw",
+            DexEvent::RaydiumCpmmInitialize(_) => "RaydiumCpmmInitialize",
+            DexEvent::RaydiumCpmmPoolStatusUpdated(_) => "RaydiumCpmmPoolStatusUpdated",
+            DexEvent::RaydiumAmmV4Swap(_) => "RaydiumAmmV4Swap",
+            DexEvent::RaydiumAmmV4Deposit(_) => "RaydiumAmmV4Deposit",
+            DexEvent::RaydiumAmmV4Initialize2(_) => "RaydiumAmmV4Initialize2",
+            DexEvent::RaydiumAmmV4Withdraw(_) => "RaydiumAmmV4Withdraw",
+            DexEvent::RaydiumAmmV4WithdrawPnl(_) => "RaydiumAmmV4WithdrawPnl",
+            DexEvent::RaydiumAmmV4PoolState(_) => "RaydiumAmmV4PoolState",
+            DexEvent::OrcaWhirlpoolSwap(_) => "OrcaWhirlpoolSwap",
+            DexEvent::OrcaWhirlpoolLiquidityIncreased(_) => "OrcaWhirlpoolLiquidityIncreased",
+            DexEvent::OrcaWhirlpoolLiquidityDecreased(_) => "OrcaWhirlpoolLiquidityDecreased",
+            DexEvent::OrcaWhirlpoolPoolInitialized(_) => "OrcaWhirlpoolPoolInitialized",
+            DexEvent::OrcaWhirlpoolCollectFees(_) => "OrcaWhirlpoolCollectFees",
+            DexEvent::OrcaWhirlpoolCollectReward(_) => "OrcaWhirlpoolCollectReward",
+            DexEvent::MeteoraPoolsSwap(_) => "MeteoraPoolsSwap",
+            DexEvent::MeteoraPoolsAddLiquidity(_) => "MeteoraPoolsAddLiquidity",
+            DexEvent::MeteoraPoolsRemoveLiquidity(_) => "MeteoraPoolsRemoveLiquidity",
+            DexEvent::MeteoraPoolsBootstrapLiquidity(_) => "MeteoraPoolsBootstrapLiquidity",
+            DexEvent::MeteoraPoolsPoolCreated(_) => "MeteoraPoolsPoolCreated",
+            DexEvent::MeteoraPoolsSetPoolFees(_) => "MeteoraPoolsSetPoolFees",
+            DexEvent::MeteoraDammV2Swap(_) => "MeteoraDammV2Swap",
+            DexEvent::MeteoraDammV2AddLiquidity(_) => "MeteoraDammV2AddLiquidity",
+            DexEvent::MeteoraDammV2RemoveLiquidity(_) => "MeteoraDammV2RemoveLiquidity",
+            DexEvent::MeteoraDammV2InitializePool(_) => "MeteoraDammV2InitializePool",
+            DexEvent::MeteoraDammV2CreatePosition(_) => "MeteoraDammV2CreatePosition",
+            DexEvent::MeteoraDammV2ClosePosition(_) => "MeteoraDammV2ClosePosition",
+            DexEvent::MeteoraDammV2ClaimPositionFee(_) => "MeteoraDammV2ClaimPositionFee",
+            DexEvent::MeteoraDammV2InitializeReward(_) => "MeteoraDammV2InitializeReward",
+            DexEvent::MeteoraDammV2FundReward(_) => "MeteoraDammV2FundReward",
+            DexEvent::MeteoraDammV2ClaimReward(_) => "MeteoraDammV2ClaimReward",
+            DexEvent::MeteoraDlmmSwap(_) => "MeteoraDlmmSwap",
+            DexEvent::MeteoraDlmmAddLiquidity(_) => "MeteoraDlmmAddLiquidity",
+            DexEvent::MeteoraDlmmRemoveLiquidity(_) => "MeteoraDlmmRemoveLiquidity",
+            DexEvent::MeteoraDlmmInitializePool(_) => "MeteoraDlmmInitializePool",
+            DexEvent::MeteoraDlmmInitializeBinArray(_) => "MeteoraDlmmInitializeBinArray",
+            DexEvent::MeteoraDlmmCreatePosition(_) => "MeteoraDlmmCreatePosition",
+            DexEvent::MeteoraDlmmClosePosition(_) => "MeteoraDlmmClosePosition",
+            DexEvent::MeteoraDlmmClaimFee(_) => "MeteoraDlmmClaimFee",
+            DexEvent::TokenAccount(_) => "TokenAccount",
+            DexEvent::NonceAccount(_) => "NonceAccount",
+            DexEvent::BlockMeta(_) => "BlockMeta",
+            DexEvent::SlotStatus(_) => "SlotStatus",
+            DexEvent::TokenInfo(_) => "TokenInfo",
+            DexEvent::Custom(_) => "Custom",
+            DexEvent::UnknownSwap(_) => "UnknownSwap",
+            DexEvent::CrossVenueTrade(_) => "CrossVenueTrade",
+            DexEvent::Error(_) => "Error",
+        }
+    }
+
+    /// 稳定的事件 ID，用作下游存储的幂等键
+    ///
+    /// 对 `(kind, signature, outer_index, inner_index)` 做 SHA-256，同一逻辑事件在重连回放、
+    /// 不同 commitment 或重复解析时得到相同 ID，与接收时间和解析路径无关。
+    /// 没有签名的事件（slot、区块、账户更新）额外纳入 `slot`。
+    /// 没有元数据的事件（`Custom`、`Error` 等）无法区分彼此，返回 None。
+    pub fn event_id(&self) -> Option<[u8; 32]> {
+        let metadata = self.metadata()?;
+        let inner_index = match metadata.inner_index {
+            Some(index) => [&[1u8][..], &index.to_le_bytes()].concat(),
+            None => vec![0],
+        };
+        let slot = if metadata.signature == Signature::default() { metadata.slot } else { 0 };
+        solana_program::hash::hashv(&[
+            self.kind().as_bytes(),
+            metadata.signature.as_ref(),
+            &metadata.outer_index.to_le_bytes(),
+            &inner_index,
+            &slot.to_le_bytes(),
+        ])
+        .to_bytes()
+        .into()
+    }
+
+    /// 事件所在指令的原始数据，需开启 `ClientConfig.retain_raw`；日志、账户事件返回 None
+    pub fn raw_instruction(&self) -> Option<&RawInstruction> {
+        self.metadata()?.raw.as_deref()
+    }
+
+    /// 事件所在指令数据的前 8 字节（见 [`RawInstruction::discriminator`]）
+    ///
+    /// [`DexEvent::UnknownSwap`] 总是返回其 `raw_discriminator`，其它事件需开启 `ClientConfig.retain_raw`。
+    pub fn raw_discriminator(&self) -> Option<[u8; 8]> {
+        match self {
+            DexEvent::UnknownSwap(e) => Some(e.raw_discriminator),
+            _ => self.raw_instruction().map(RawInstruction::discriminator),
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl DexEvent {
+    /// 归一化的交易方向，非交易事件或无法判断方向时返回 None
+    ///
+    /// 各 DEX 的 base/quote 约定：
+    /// - PumpFun / PumpSwap Buy/Sell：base = 代币，quote = SOL，方向取自 `is_buy` / 事件类型
+    /// - PumpSwap Trade（已废弃）：base = token A，`is_token_a_to_b` 为卖出
+    /// - Bonk：base = 代币，quote = SOL，方向取自 `is_buy`
+    /// - Raydium CLMM：base = token0，quote = token1，`zero_for_one` 为卖出
+    /// - Orca Whirlpool：base = token A，quote = token B，`a_to_b` 为卖出
+    /// - Meteora DLMM / DAMM V2：base = token X，quote = token Y，`swap_for_y` 为卖出
+    /// - Raydium CPMM / AMM V4：`base_input` 等字段表示 exact-in/exact-out，不代表方向，返回 None
+    /// - Meteora Pools：事件不携带方向，返回 None
+    pub fn trade_side(&self) -> Option<TradeSide> {
+        let is_sell = match self {
+            DexEvent::PumpFunTrade(e) => !e.is_buy,
+            DexEvent::PumpSwapBuy(_) => false,
+            DexEvent::PumpSwapSell(_) => true,
+            DexEvent::PumpSwapTrade(e) => e.is_token_a_to_b,
+            DexEvent::BonkTrade(e) => !e.is_buy,
+            DexEvent::RaydiumClmmSwap(e) => e.zero_for_one,
+            DexEvent::OrcaWhirlpoolSwap(e) => e.a_to_b,
+            DexEvent::MeteoraDlmmSwap(e) => e.swap_for_y,
+            DexEvent::MeteoraDammV2Swap(e) => e.swap_for_y,
+            _ => return None,
+        };
+        Some(if is_sell { TradeSide::Sell } else { TradeSide::Buy })
+    }
+
+    /// base mint（事件中可确定时）
+    pub fn base_mint(&self) -> Option<Pubkey> {
+        match self {
+            DexEvent::PumpFunTrade(e) => Some(e.mint),
+            DexEvent::PumpSwapBuy(e) => Some(e.token_mint),
+            DexEvent::PumpSwapSell(e) => Some(e.token_mint),
+            DexEvent::PumpSwapTrade(e) => {
+                Some(if e.is_token_a_to_b { e.token_in_mint } else { e.token_out_mint })
+            }
+            _ => None,
+        }
+    }
+
+    /// quote mint（事件中可确定时，SOL 报价统一为 WSOL）
+    pub fn quote_mint(&self) -> Option<Pubkey> {
+        match self {
+            DexEvent::PumpFunTrade(_) | DexEvent::PumpSwapBuy(_) | DexEvent::PumpSwapSell(_) => {
+                Some(WSOL_MINT)
+            }
+            DexEvent::PumpSwapTrade(e) => {
+                Some(if e.is_token_a_to_b { e.token_out_mint } else { e.token_in_mint })
+            }
+            _ => None,
+        }
+    }
+
+    /// 事件所属代币的 mint（非报价侧），用于按代币路由；无法确定时返回 None
+    pub fn primary_mint(&self) -> Option<Pubkey> {
+        let mint = match self {
+            DexEvent::PumpFunCreate(e) => e.mint,
+            DexEvent::PumpFunTrade(e) => e.mint,
+            DexEvent::PumpFunComplete(e) => e.mint,
+            DexEvent::PumpFunMigrate(e) => e.mint,
+            DexEvent::BonkPoolCreate(e) => e.base_mint,
+            DexEvent::BonkMigrateAmm(e) => e.base_mint,
+            DexEvent::PumpSwapBuy(e) => e.token_mint,
+            DexEvent::PumpSwapSell(e) => e.token_mint,
+            DexEvent::PumpSwapCreatePool(e) => e.token_mint,
+            DexEvent::PumpSwapPoolCreated(e) => e.token_a_mint,
+            DexEvent::PumpSwapTrade(_) => return self.base_mint(),
+            DexEvent::PumpSwapLiquidityAdded(e) => e.token_a_mint,
+            DexEvent::PumpSwapLiquidityRemoved(e) => e.token_a_mint,
+            DexEvent::PumpSwapDeposit(e) => e.base_mint,
+            DexEvent::PumpSwapWithdraw(e) => e.base_mint,
+            DexEvent::RaydiumAmmV4Initialize2(e) => e.coin_mint,
+            DexEvent::OrcaWhirlpoolPoolInitialized(e) => e.token_mint_a,
+            DexEvent::MeteoraPoolsPoolCreated(e) => e.token_a_mint,
+            DexEvent::MeteoraDammV2InitializePool(e) => e.token_x,
+            DexEvent::TokenAccount(e) => e.mint,
+            DexEvent::TokenInfo(e) => e.mint,
+            _ => return None,
+        };
+        (mint != Pubkey::default()).then_some(mint)
+    }
+
+    /// 事件所属协议（区块、账户、自定义及未知交换事件返回 None）
+    pub fn protocol(&self) -> Option<crate::core::protocol::Protocol> {
+        use crate::core::protocol::Protocol;
+        Some(match self {
+            DexEvent::PumpFunCreate(_) | DexEvent::PumpFunTrade(_) | DexEvent::PumpFunComplete(_)
+            | DexEvent::PumpFunMigrate(_) | DexEvent::PumpFunSetParams(_) => Protocol::PumpFun,
+            DexEvent::BonkTrade(_) | DexEvent::BonkPoolCreate(_) | DexEvent::BonkMigrateAmm(_) => Protocol::Bonk,
+            DexEvent::PumpSwapBuy(_) | DexEvent::PumpSwapSell(_) | DexEvent::PumpSwapCreatePool(_)
+            | DexEvent::PumpSwapPoolUpdated(_) | DexEvent::PumpSwapFeesClaimed(_) | DexEvent::PumpSwapDeposit(_)
+            | DexEvent::PumpSwapWithdraw(_) => Protocol::PumpSwap,
+            DexEvent::RaydiumClmmSwap(_) | DexEvent::RaydiumClmmCreatePool(_) | DexEvent::RaydiumClmmOpenPosition(_)
+            | DexEvent::RaydiumClmmOpenPositionWithTokenExtNft(_) | DexEvent::RaydiumClmmClosePosition(_)
+            | DexEvent::RaydiumClmmIncreaseLiquidity(_) | DexEvent::RaydiumClmmDecreaseLiquidity(_)
+            | DexEvent::RaydiumClmmCollectFee(_) => Protocol::RaydiumClmm,
+            DexEvent::RaydiumCpmmSwap(_) | DexEvent::RaydiumCpmmDeposit(_) | DexEvent::RaydiumCpmmWithdraw(_)
+            | DexEvent::RaydiumCpmmInitialize(_) | DexEvent::RaydiumCpmmPoolStatusUpdated(_) => Protocol::RaydiumCpmm,
+            DexEvent::RaydiumAmmV4Swap(_) | DexEvent::RaydiumAmmV4Deposit(_) | DexEvent::RaydiumAmmV4Initialize2(_)
+            | DexEvent::RaydiumAmmV4Withdraw(_) | DexEvent::RaydiumAmmV4WithdrawPnl(_)
+            | DexEvent::RaydiumAmmV4PoolState(_) => Protocol::RaydiumAmmV4,
+            DexEvent::OrcaWhirlpoolSwap(_) | DexEvent::OrcaWhirlpoolLiquidityIncreased(_)
+            | DexEvent::OrcaWhirlpoolLiquidityDecreased(_) | DexEvent::OrcaWhirlpoolPoolInitialized(_)
+            | DexEvent::OrcaWhirlpoolCollectFees(_) | DexEvent::OrcaWhirlpoolCollectReward(_) => Protocol::OrcaWhirlpool,
+            DexEvent::MeteoraPoolsSwap(_) | DexEvent::MeteoraPoolsAddLiquidity(_) | DexEvent::MeteoraPoolsRemoveLiquidity(_)
+            | DexEvent::MeteoraPoolsBootstrapLiquidity(_) | DexEvent::MeteoraPoolsPoolCreated(_)
+            | DexEvent::MeteoraPoolsSetPoolFees(_) => Protocol::MeteoraPools,
+            DexEvent::MeteoraDammV2Swap(_) | DexEvent::MeteoraDammV2AddLiquidity(_)
+            | DexEvent::MeteoraDammV2RemoveLiquidity(_) | DexEvent::MeteoraDammV2InitializePool(_)
+            | DexEvent::MeteoraDammV2CreatePosition(_) | DexEvent::MeteoraDammV2ClosePosition(_)
+            | DexEvent::MeteoraDammV2ClaimPositionFee(_) | DexEvent::MeteoraDammV2InitializeReward(_)
+            | DexEvent::MeteoraDammV2FundReward(_) | DexEvent::MeteoraDammV2ClaimReward(_) => Protocol::MeteoraDammV2,
+            DexEvent::MeteoraDlmmSwap(_) | DexEvent::MeteoraDlmmAddLiquidity(_) | DexEvent::MeteoraDlmmRemoveLiquidity(_)
+            | DexEvent::MeteoraDlmmInitializePool(_) | DexEvent::MeteoraDlmmInitializeBinArray(_)
+            | DexEvent::MeteoraDlmmCreatePosition(_) | DexEvent::MeteoraDlmmClosePosition(_)
+            | DexEvent::MeteoraDlmmClaimFee(_) => Protocol::MeteoraDlmm,
+            _ => return None,
+        })
+    }
+
+    /// 事件所属池子（PumpFun 为 bonding curve，Bonk 迁移为原 launchpad 池），用于按池路由
+    pub fn primary_pool(&self) -> Option<Pubkey> {
+        let pool = match self {
+            DexEvent::PumpFunCreate(e) => e.bonding_curve,
+            DexEvent::PumpFunComplete(e) => e.bonding_curve,
+            DexEvent::PumpFunMigrate(e) => e.bonding_curve,
+            DexEvent::BonkTrade(e) => e.pool_state,
+            DexEvent::BonkPoolCreate(e) => e.pool_state,
+            DexEvent::BonkMigrateAmm(e) => e.old_pool,
+            DexEvent::PumpSwapBuy(e) => e.pool_id,
+            DexEvent::PumpSwapSell(e) => e.pool_id,
+            DexEvent::PumpSwapCreatePool(e) => e.pool_id,
+            DexEvent::PumpSwapPoolCreated(e) => e.pool_account,
+            DexEvent::PumpSwapTrade(e) => e.pool_account,
+            DexEvent::PumpSwapLiquidityAdded(e) => e.pool_account,
+            DexEvent::PumpSwapLiquidityRemoved(e) => e.pool_account,
+            DexEvent::PumpSwapPoolUpdated(e) => e.pool_account,
+            DexEvent::PumpSwapFeesClaimed(e) => e.pool_account,
+            DexEvent::PumpSwapDeposit(e) => e.pool,
+            DexEvent::PumpSwapWithdraw(e) => e.pool,
+            DexEvent::RaydiumClmmSwap(e) => e.pool_state,
+            DexEvent::RaydiumClmmCreatePool(e) => e.pool,
+            DexEvent::RaydiumClmmOpenPosition(e) => e.pool,
+            DexEvent::RaydiumClmmOpenPositionWithTokenExtNft(e) => e.pool,
+            DexEvent::RaydiumClmmClosePosition(e) => e.pool,
+            DexEvent::RaydiumClmmIncreaseLiquidity(e) => e.pool,
+            DexEvent::RaydiumClmmDecreaseLiquidity(e) => e.pool,
+            DexEvent::RaydiumClmmCollectFee(e) => e.pool_state,
+            DexEvent::RaydiumCpmmSwap(e) => e.pool_id,
+            DexEvent::RaydiumCpmmDeposit(e) => e.pool,
+            DexEvent::RaydiumCpmmWithdraw(e) => e.pool,
+            DexEvent::RaydiumCpmmPoolStatusUpdated(e) => e.pool_state,
+            DexEvent::RaydiumCpmmInitialize(e) => e.pool,
+            DexEvent::RaydiumAmmV4Swap(e) => e.amm,
+            DexEvent::RaydiumAmmV4Deposit(e) => e.amm,
+            DexEvent::RaydiumAmmV4Initialize2(e) => e.amm,
+            DexEvent::RaydiumAmmV4Withdraw(e) => e.amm,
+            DexEvent::RaydiumAmmV4WithdrawPnl(e) => e.amm,
+            DexEvent::RaydiumAmmV4PoolState(e) => e.amm,
+            DexEvent::OrcaWhirlpoolSwap(e) => e.whirlpool,
+            DexEvent::OrcaWhirlpoolLiquidityIncreased(e) => e.whirlpool,
+            DexEvent::OrcaWhirlpoolLiquidityDecreased(e) => e.whirlpool,
+            DexEvent::OrcaWhirlpoolPoolInitialized(e) => e.whirlpool,
+            DexEvent::OrcaWhirlpoolCollectFees(e) => e.whirlpool,
+            DexEvent::OrcaWhirlpoolCollectReward(e) => e.whirlpool,
+            DexEvent::MeteoraPoolsBootstrapLiquidity(e) => e.pool,
+            DexEvent::MeteoraPoolsPoolCreated(e) => e.pool,
+            DexEvent::MeteoraPoolsSetPoolFees(e) => e.pool,
+            DexEvent::MeteoraDammV2Swap(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2AddLiquidity(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2RemoveLiquidity(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2InitializePool(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2CreatePosition(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2ClaimPositionFee(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2InitializeReward(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2FundReward(e) => e.lb_pair,
+            DexEvent::MeteoraDammV2ClaimReward(e) => e.lb_pair,
+            DexEvent::MeteoraDlmmSwap(e) => e.pool,
+            DexEvent::MeteoraDlmmAddLiquidity(e) => e.pool,
+            DexEvent::MeteoraDlmmRemoveLiquidity(e) => e.pool,
+            DexEvent::MeteoraDlmmInitializePool(e) => e.pool,
+            DexEvent::MeteoraDlmmInitializeBinArray(e) => e.pool,
+            DexEvent::MeteoraDlmmCreatePosition(e) => e.pool,
+            DexEvent::MeteoraDlmmClosePosition(e) => e.pool,
+            DexEvent::MeteoraDlmmClaimFee(e) => e.pool,
+            _ => return None,
+        };
+        (pool != Pubkey::default()).then_some(pool)
+    }
+}
+
+/// 为事件生成 `*_opt()` 金额访问器，语义见 [`EventMetadata::amount_opt`]
+macro_rules! amount_opt_accessors {
+    ($($event:ty { $($getter:ident => $field:ident),+ $(,)? })+) => {
+        $(
+            impl $event {
+                $(
+                    #[doc = concat!("`", stringify!($field), "`，仅来自指令且为 0 时返回 None")]
+                    #[inline]
+                    pub fn $getter(&self) -> Option<u64> {
+                        self.metadata.amount_opt(self.$field)
+                    }
+                )+
+            }
+        )+
+    };
+}
+
+amount_opt_accessors! {
+    PumpFunTradeEvent { sol_amount_opt => sol_amount, token_amount_opt => token_amount }
+    PumpSwapBuyEvent { sol_amount_opt => sol_amount, token_amount_opt => token_amount }
+    PumpSwapSellEvent { sol_amount_opt => sol_amount, token_amount_opt => token_amount }
+    BonkTradeEvent { amount_in_opt => amount_in, amount_out_opt => amount_out }
+    RaydiumCpmmSwapEvent { input_amount_opt => input_amount, output_amount_opt => output_amount }
+    RaydiumAmmV4SwapEvent { amount_in_opt => amount_in, amount_out_opt => amount_out }
+    OrcaWhirlpoolSwapEvent { input_amount_opt => input_amount, output_amount_opt => output_amount }
+    MeteoraDammV2SwapEvent { amount_in_opt => amount_in, amount_out_opt => amount_out }
+    MeteoraDlmmSwapEvent { amount_in_opt => amount_in, amount_out_opt => amount_out }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> EventMetadata {
+        EventMetadata {
+            signature: Signature::default(),
+            slot: 0,
+            tx_index: 0,
+            block_time_us: 0,
+            grpc_recv_us: 0,
+            handle_us: 0,
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    fn pumpfun_trade(is_buy: bool) -> DexEvent {
+        DexEvent::PumpFunTrade(PumpFunTradeEvent {
+            metadata: metadata(),
+            mint: Pubkey::new_unique(),
+            sol_amount: 1,
+            token_amount: 1,
+            is_buy,
+            is_created_buy: false,
+            is_dev_create_token_trade: false,
+            user: Pubkey::default(),
+            timestamp: 0,
+            virtual_sol_reserves: 0,
+            virtual_token_reserves: 0,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+            fee_recipient: Pubkey::default(),
+            fee_basis_points: 0,
+            fee: 0,
+            creator: Pubkey::default(),
+            creator_fee_basis_points: 0,
+            creator_fee: 0,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 0,
+            sol_limit: 0,
+            price_per_token_lamports: 0.0,
+            slippage_bps: None,
+            price_impact_bps: None,
+        })
+    }
+
+    fn clmm_swap(zero_for_one: bool) -> DexEvent {
+        DexEvent::RaydiumClmmSwap(RaydiumClmmSwapEvent {
+            metadata: metadata(),
+            pool_state: Pubkey::default(),
+            sender: Pubkey::default(),
+            token_account_0: Pubkey::default(),
+            token_account_1: Pubkey::default(),
+            amount_0: 0,
+            transfer_fee_0: 0,
+            amount_1: 0,
+            transfer_fee_1: 0,
+            zero_for_one,
+            sqrt_price_x64: 0,
+            liquidity: 0,
+            tick: 0,
+        })
+    }
+
+    fn cpmm_swap(base_input: bool) -> DexEvent {
+        DexEvent::RaydiumCpmmSwap(RaydiumCpmmSwapEvent {
+            metadata: metadata(),
+            pool_id: Pubkey::default(),
+            input_vault_before: 0,
+            output_vault_before: 0,
+            input_amount: 0,
+            output_amount: 0,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            base_input,
+        })
+    }
+
+    #[test]
+    fn test_event_id_is_stable() {
+        // 同一事件第二次解析：接收时间与来源不同
+        let first = pumpfun_trade(true);
+        let mut second = first.clone();
+        second.metadata_mut().unwrap().grpc_recv_us = 42;
+        second.metadata_mut().unwrap().event_source = EventSource::Merged;
+        assert_eq!(first.event_id(), second.event_id());
+        assert_eq!(first.kind(), "PumpFunTrade");
+
+        let mut cpi = pumpfun_trade(true);
+        cpi.metadata_mut().unwrap().inner_index = Some(0);
+        let mut next_ix = pumpfun_trade(true);
+        next_ix.metadata_mut().unwrap().outer_index = 1;
+        let ids = [first.event_id(), cpi.event_id(), next_ix.event_id(), clmm_swap(true).event_id()];
+        for (i, a) in ids.iter().enumerate() {
+            assert!(a.is_some() && ids[i + 1..].iter().all(|b| a != b));
+        }
+
+        // 没有元数据的事件没有 ID，不会互相冲突
+        assert_eq!(DexEvent::Error("a".into()).event_id(), None);
+    }
+
+    #[test]
+    fn test_pumpfun_trade_side() {
+        let buy = pumpfun_trade(true);
+        assert_eq!(buy.trade_side(), Some(TradeSide::Buy));
+        assert_eq!(pumpfun_trade(false).trade_side(), Some(TradeSide::Sell));
+        if let DexEvent::PumpFunTrade(e) = &buy {
+            assert_eq!(buy.base_mint(), Some(e.mint));
+        }
+        assert_eq!(buy.quote_mint(), Some(WSOL_MINT));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_pumpswap_trade_conversion() {
+        let (base, quote) = (Pubkey::new_unique(), WSOL_MINT);
+        let trade = |is_token_a_to_b: bool| PumpSwapTrade {
+            metadata: metadata(),
+            pool_account: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            user_token_in_account: Pubkey::new_unique(),
+            user_token_out_account: Pubkey::new_unique(),
+            pool_token_in_vault: Pubkey::new_unique(),
+            pool_token_out_vault: Pubkey::new_unique(),
+            token_in_mint: if is_token_a_to_b { base } else { quote },
+            token_out_mint: if is_token_a_to_b { quote } else { base },
+            amount_in: 1_000,
+            minimum_amount_out: 900,
+            is_token_a_to_b,
+        };
+
+        // 旧变体仍可构造和匹配
+        let legacy = DexEvent::PumpSwapTrade(trade(true));
+        assert_eq!((legacy.base_mint(), legacy.quote_mint()), (Some(base), Some(quote)));
+        assert_eq!(legacy.trade_side(), Some(TradeSide::Sell));
+
+        // 输出数量只有滑点下限，转换后不当作成交数量
+        match DexEvent::from(trade(true)) {
+            DexEvent::PumpSwapSell(e) => assert_eq!((e.token_mint, e.token_amount, e.sol_amount), (base, 1_000, 0)),
+            other => panic!("unexpected event {:?}", other),
+        }
+        match DexEvent::from(trade(false)) {
+            DexEvent::PumpSwapBuy(e) => assert_eq!((e.token_mint, e.sol_amount, e.token_amount), (base, 1_000, 0)),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_clmm_trade_side() {
+        assert_eq!(clmm_swap(true).trade_side(), Some(TradeSide::Sell));
+        assert_eq!(clmm_swap(false).trade_side(), Some(TradeSide::Buy));
+        assert_eq!(clmm_swap(true).base_mint(), None);
+    }
+
+    #[test]
+    fn test_amount_opt_zero_vs_unfilled() {
+        // 日志事件中的 0 是真实零金额
+        let DexEvent::RaydiumCpmmSwap(mut swap) = cpmm_swap(true) else { unreachable!() };
+        assert_eq!(swap.output_amount_opt(), Some(0));
+
+        // 仅来自指令的 0 表示尚未从日志补齐
+        swap.metadata.event_source = EventSource::Instruction;
+        assert_eq!(swap.output_amount_opt(), None);
+        swap.output_amount = 7;
+        assert_eq!(swap.output_amount_opt(), Some(7));
+
+        // 合并后仍为 0 视为真实零金额
+        swap.output_amount = 0;
+        swap.metadata.event_source = EventSource::Merged;
+        assert_eq!(swap.output_amount_opt(), Some(0));
+    }
+
+    #[test]
+    fn test_cpmm_trade_side_undetermined() {
+        // base_input 表示 exact-in 模式，不代表方向
+        assert_eq!(cpmm_swap(true).trade_side(), None);
+        assert_eq!(cpmm_swap(false).trade_side(), None);
+    }
+}
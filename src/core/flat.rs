@@ -0,0 +1,489 @@
+//! 扁平行：把嵌套的 [`DexEvent`] 转成列式存储（ClickHouse / Parquet）友好的行
+//!
+//! - [`FlatTradeRow`]：成交；[`FlatLiquidityRow`]：加 / 撤流动性；[`FlatPoolCreateRow`]：建池 / 发币 / 迁移建池
+//! - 公钥统一为 base58 字符串，事件中无法确定的列为 None（写入时为 NULL）
+//! - `protocol` 为 [`Protocol`](crate::core::protocol::Protocol) 的变体名，`event_type` 为 [`DexEvent::kind`]
+//! - `block_time` 为秒级时间戳，`grpc_recv_us` 为微秒
+//! - 金额均为链上原始单位（未按 decimals 换算）
+//!
+//! 列集合由各行类型的 `COLUMNS` 常量固定，新增 / 改名列视为破坏性变更。
+//! 开启 `arrow` feature 时 [`rows_to_record_batch`] 把成交行转成 Arrow `RecordBatch`，列顺序与 `COLUMNS` 相同。
+
+use crate::core::events::*;
+use serde::Serialize;
+use smallvec::SmallVec;
+use solana_sdk::pubkey::Pubkey;
+
+/// 成交行
+///
+/// `price_f64` 为每单位 base 的 quote 数量（原始单位），仅在 [`DexEvent::trade_side`] 能确定 base/quote 时给出。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatTradeRow {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub protocol: String,
+    pub event_type: String,
+    pub pool: Option<String>,
+    pub mint_in: Option<String>,
+    pub mint_out: Option<String>,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub user: Option<String>,
+    pub fee: u64,
+    pub price_f64: Option<f64>,
+    pub grpc_recv_us: i64,
+}
+
+impl FlatTradeRow {
+    pub const COLUMNS: [&'static str; 14] = [
+        "signature", "slot", "block_time", "protocol", "event_type", "pool", "mint_in", "mint_out",
+        "amount_in", "amount_out", "user", "fee", "price_f64", "grpc_recv_us",
+    ];
+}
+
+/// 加 / 撤流动性行
+///
+/// 事件只带上下限（如 Raydium CLMM 的 `amount0_max`）时金额列为 None；
+/// 集中流动性协议的 `lp_amount` 为 liquidity（超出 u64 时为 None）。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatLiquidityRow {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub protocol: String,
+    pub event_type: String,
+    pub pool: Option<String>,
+    pub user: Option<String>,
+    pub is_add: bool,
+    pub mint_a: Option<String>,
+    pub mint_b: Option<String>,
+    pub amount_a: Option<u64>,
+    pub amount_b: Option<u64>,
+    pub lp_amount: Option<u64>,
+    pub grpc_recv_us: i64,
+}
+
+impl FlatLiquidityRow {
+    pub const COLUMNS: [&'static str; 14] = [
+        "signature", "slot", "block_time", "protocol", "event_type", "pool", "user", "is_add", "mint_a",
+        "mint_b", "amount_a", "amount_b", "lp_amount", "grpc_recv_us",
+    ];
+}
+
+/// 建池行（PumpFun / Bonk 发币时为 bonding curve 及初始虚拟储备，PumpFun 迁移为新建的 PumpSwap 池）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatPoolCreateRow {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub protocol: String,
+    pub event_type: String,
+    pub pool: Option<String>,
+    pub creator: Option<String>,
+    pub mint_a: Option<String>,
+    pub mint_b: Option<String>,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub grpc_recv_us: i64,
+}
+
+impl FlatPoolCreateRow {
+    pub const COLUMNS: [&'static str; 12] = [
+        "signature", "slot", "block_time", "protocol", "event_type", "pool", "creator", "mint_a", "mint_b",
+        "amount_a", "amount_b", "grpc_recv_us",
+    ];
+}
+
+/// 一行扁平记录（按目标表区分）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "table", rename_all = "snake_case")]
+pub enum FlatRow {
+    Trade(FlatTradeRow),
+    Liquidity(FlatLiquidityRow),
+    PoolCreate(FlatPoolCreateRow),
+}
+
+/// 各行共有的列
+struct Common {
+    signature: String,
+    slot: u64,
+    block_time: i64,
+    protocol: String,
+    event_type: String,
+    grpc_recv_us: i64,
+}
+
+/// 默认公钥视为未知
+#[inline]
+fn key(pubkey: Pubkey) -> Option<String> {
+    (pubkey != Pubkey::default()).then(|| pubkey.to_string())
+}
+
+impl Common {
+    fn trade(self, pool: Pubkey, user: Pubkey, mints: (Option<Pubkey>, Option<Pubkey>), amounts: (u64, u64), fee: u64, price_f64: Option<f64>) -> FlatRow {
+        FlatRow::Trade(FlatTradeRow {
+            signature: self.signature,
+            slot: self.slot,
+            block_time: self.block_time,
+            protocol: self.protocol,
+            event_type: self.event_type,
+            pool: key(pool),
+            mint_in: mints.0.and_then(key),
+            mint_out: mints.1.and_then(key),
+            amount_in: amounts.0,
+            amount_out: amounts.1,
+            user: key(user),
+            fee,
+            price_f64,
+            grpc_recv_us: self.grpc_recv_us,
+        })
+    }
+
+    fn liquidity(self, pool: Pubkey, user: Pubkey, is_add: bool, mints: (Pubkey, Pubkey), amounts: Option<(u64, u64)>, lp_amount: Option<u64>) -> FlatRow {
+        FlatRow::Liquidity(FlatLiquidityRow {
+            signature: self.signature,
+            slot: self.slot,
+            block_time: self.block_time,
+            protocol: self.protocol,
+            event_type: self.event_type,
+            pool: key(pool),
+            user: key(user),
+            is_add,
+            mint_a: key(mints.0),
+            mint_b: key(mints.1),
+            amount_a: amounts.map(|a| a.0),
+            amount_b: amounts.map(|a| a.1),
+            lp_amount,
+            grpc_recv_us: self.grpc_recv_us,
+        })
+    }
+
+    fn pool_create(self, pool: Pubkey, creator: Pubkey, mints: (Pubkey, Pubkey), amounts: (u64, u64)) -> FlatRow {
+        FlatRow::PoolCreate(FlatPoolCreateRow {
+            signature: self.signature,
+            slot: self.slot,
+            block_time: self.block_time,
+            protocol: self.protocol,
+            event_type: self.event_type,
+            pool: key(pool),
+            creator: key(creator),
+            mint_a: key(mints.0),
+            mint_b: key(mints.1),
+            amount_a: amounts.0,
+            amount_b: amounts.1,
+            grpc_recv_us: self.grpc_recv_us,
+        })
+    }
+}
+
+/// quote/base 价格：买入时付出 quote 得到 base，卖出相反
+fn price(side: Option<TradeSide>, amount_in: u64, amount_out: u64) -> Option<f64> {
+    let (quote, base) = match side? {
+        TradeSide::Buy => (amount_in, amount_out),
+        TradeSide::Sell => (amount_out, amount_in),
+    };
+    (base > 0).then(|| quote as f64 / base as f64)
+}
+
+impl DexEvent {
+    /// 转成扁平行；非成交 / 流动性 / 建池事件返回空
+    ///
+    /// 目前每个事件至多一行（多跳交换由解析器拆成多个事件，各自成行），返回 SmallVec 以便日后一个事件拆成多行。
+    pub fn to_flat_rows(&self) -> SmallVec<[FlatRow; 1]> {
+        let mut rows = SmallVec::new();
+        let (Some(metadata), Some(protocol)) = (self.metadata(), self.protocol()) else {
+            return rows;
+        };
+        let common = Common {
+            signature: metadata.signature.to_string(),
+            slot: metadata.slot,
+            block_time: metadata.block_time_us / 1_000_000,
+            protocol: format!("{:?}", protocol),
+            event_type: self.kind().to_string(),
+            grpc_recv_us: metadata.grpc_recv_us,
+        };
+        let side = self.trade_side();
+        let none = Pubkey::default();
+
+        let row = match self {
+            // 成交
+            DexEvent::PumpFunTrade(e) => {
+                let (mints, amounts) = if e.is_buy {
+                    ((WSOL_MINT, e.mint), (e.sol_amount, e.token_amount))
+                } else {
+                    ((e.mint, WSOL_MINT), (e.token_amount, e.sol_amount))
+                };
+                common.trade(none, e.user, (Some(mints.0), Some(mints.1)), amounts, e.fee.saturating_add(e.creator_fee), price(side, amounts.0, amounts.1))
+            }
+            DexEvent::PumpSwapBuy(e) => {
+                let fee = e.lp_fee.saturating_add(e.protocol_fee).saturating_add(e.coin_creator_fee.unwrap_or(0));
+                common.trade(e.pool_id, e.user, (Some(WSOL_MINT), Some(e.token_mint)), (e.sol_amount, e.token_amount), fee, price(side, e.sol_amount, e.token_amount))
+            }
+            DexEvent::PumpSwapSell(e) => {
+                let fee = e.lp_fee.saturating_add(e.protocol_fee).saturating_add(e.coin_creator_fee.unwrap_or(0));
+                common.trade(e.pool_id, e.user, (Some(e.token_mint), Some(WSOL_MINT)), (e.token_amount, e.sol_amount), fee, price(side, e.token_amount, e.sol_amount))
+            }
+            DexEvent::BonkTrade(e) => {
+                // 买入用报价币换代币，卖出相反；费用都以报价币计
+                let mints = if e.is_buy { (e.quote_mint, e.base_mint) } else { (e.base_mint, e.quote_mint) };
+                let fee = [e.protocol_fee, e.platform_fee, e.share_fee].into_iter().flatten().fold(0u64, u64::saturating_add);
+                common.trade(e.pool_state, e.user, (Some(mints.0), Some(mints.1)), (e.amount_in, e.amount_out), fee, price(side, e.amount_in, e.amount_out))
+            }
+            DexEvent::RaydiumClmmSwap(e) => {
+                let amounts = if e.zero_for_one { (e.amount_0, e.amount_1) } else { (e.amount_1, e.amount_0) };
+                common.trade(e.pool_state, e.sender, (None, None), amounts, 0, price(side, amounts.0, amounts.1))
+            }
+            DexEvent::RaydiumCpmmSwap(e) => common.trade(e.pool_id, none, (None, None), (e.input_amount, e.output_amount), 0, None),
+            DexEvent::RaydiumAmmV4Swap(e) => common.trade(e.amm, e.user_source_owner, (None, None), (e.amount_in, e.amount_out), 0, None),
+            DexEvent::OrcaWhirlpoolSwap(e) => common.trade(
+                e.whirlpool,
+                none,
+                (None, None),
+                (e.input_amount, e.output_amount),
+                e.lp_fee.saturating_add(e.protocol_fee),
+                price(side, e.input_amount, e.output_amount),
+            ),
+            DexEvent::MeteoraPoolsSwap(e) => common.trade(e.pool, none, (None, None), (e.in_amount, e.out_amount), e.total_fee(), None),
+            DexEvent::MeteoraDammV2Swap(e) => common.trade(e.lb_pair, e.from, (None, None), (e.amount_in, e.amount_out), e.fee, price(side, e.amount_in, e.amount_out)),
+            DexEvent::MeteoraDlmmSwap(e) => common.trade(e.pool, e.from, (None, None), (e.amount_in, e.amount_out), e.fee, price(side, e.amount_in, e.amount_out)),
+
+            // 流动性
+            DexEvent::PumpSwapDeposit(e) => common.liquidity(e.pool, e.user, true, (e.base_mint, e.quote_mint), Some((e.base_amount_in, e.quote_amount_in)), Some(e.lp_token_amount_out)),
+            DexEvent::PumpSwapWithdraw(e) => common.liquidity(e.pool, e.user, false, (e.base_mint, e.quote_mint), Some((e.base_amount_out, e.quote_amount_out)), Some(e.lp_token_amount_in)),
+            DexEvent::RaydiumClmmIncreaseLiquidity(e) => common.liquidity(e.pool, e.user, true, (none, none), Some((e.amount0, e.amount1)), u64::try_from(e.liquidity).ok()),
+            DexEvent::RaydiumClmmDecreaseLiquidity(e) => common.liquidity(e.pool, e.user, false, (none, none), Some((e.amount0, e.amount1)), u64::try_from(e.liquidity).ok()),
+            DexEvent::RaydiumCpmmDeposit(e) => common.liquidity(e.pool, e.user, true, (none, none), Some((e.token0_amount, e.token1_amount)), Some(e.lp_token_amount)),
+            DexEvent::RaydiumCpmmWithdraw(e) => common.liquidity(e.pool, e.user, false, (none, none), Some((e.token0_amount, e.token1_amount)), Some(e.lp_token_amount)),
+            DexEvent::RaydiumAmmV4Deposit(e) => common.liquidity(e.amm, e.user_owner, true, (none, none), None, None),
+            DexEvent::RaydiumAmmV4Withdraw(e) => common.liquidity(e.amm, e.user_owner, false, (none, none), None, Some(e.amount)),
+            DexEvent::OrcaWhirlpoolLiquidityIncreased(e) => common.liquidity(e.whirlpool, none, true, (none, none), Some((e.token_a_amount, e.token_b_amount)), u64::try_from(e.liquidity).ok()),
+            DexEvent::OrcaWhirlpoolLiquidityDecreased(e) => common.liquidity(e.whirlpool, none, false, (none, none), Some((e.token_a_amount, e.token_b_amount)), u64::try_from(e.liquidity).ok()),
+            DexEvent::MeteoraPoolsAddLiquidity(e) => common.liquidity(none, none, true, (none, none), Some((e.token_a_amount, e.token_b_amount)), Some(e.lp_mint_amount)),
+            DexEvent::MeteoraPoolsRemoveLiquidity(e) => common.liquidity(none, none, false, (none, none), Some((e.token_a_out_amount, e.token_b_out_amount)), Some(e.lp_unmint_amount)),
+            DexEvent::MeteoraPoolsBootstrapLiquidity(e) => common.liquidity(e.pool, none, true, (none, none), Some((e.token_a_amount, e.token_b_amount)), Some(e.lp_mint_amount)),
+            DexEvent::MeteoraDammV2AddLiquidity(e) => common.liquidity(e.lb_pair, e.from, true, (none, none), Some((e.amounts[0], e.amounts[1])), None),
+            DexEvent::MeteoraDammV2RemoveLiquidity(e) => common.liquidity(e.lb_pair, e.from, false, (none, none), Some((e.amounts[0], e.amounts[1])), None),
+            DexEvent::MeteoraDlmmAddLiquidity(e) => common.liquidity(e.pool, e.from, true, (none, none), Some((e.amounts[0], e.amounts[1])), None),
+            DexEvent::MeteoraDlmmRemoveLiquidity(e) => common.liquidity(e.pool, e.from, false, (none, none), Some((e.amounts[0], e.amounts[1])), None),
+
+            // 建池
+            DexEvent::PumpFunCreate(e) => {
+                let creator = if e.creator != none { e.creator } else { e.user };
+                common.pool_create(e.bonding_curve, creator, (e.mint, WSOL_MINT), (e.virtual_token_reserves, e.virtual_sol_reserves))
+            }
+            DexEvent::PumpFunMigrate(e) => common.pool_create(e.pool, e.user, (e.mint, WSOL_MINT), (e.pool_base_amount, e.pool_quote_amount)),
+            DexEvent::BonkPoolCreate(e) => common.pool_create(e.pool_state, e.creator, (e.base_mint, e.quote_mint), (e.virtual_base, e.virtual_quote)),
+            DexEvent::PumpSwapCreatePool(e) => common.pool_create(e.pool_id, e.creator, (e.token_mint, WSOL_MINT), (e.initial_token_amount, e.initial_sol_amount)),
+            DexEvent::RaydiumClmmCreatePool(e) => common.pool_create(e.pool, e.creator, (none, none), (0, 0)),
+            DexEvent::RaydiumCpmmInitialize(e) => common.pool_create(e.pool, e.creator, (none, none), (e.init_amount0, e.init_amount1)),
+            DexEvent::RaydiumAmmV4Initialize2(e) => common.pool_create(e.amm, e.user_wallet, (e.coin_mint, e.pc_mint), (e.init_coin_amount, e.init_pc_amount)),
+            DexEvent::OrcaWhirlpoolPoolInitialized(e) => common.pool_create(e.whirlpool, none, (e.token_mint_a, e.token_mint_b), (0, 0)),
+            DexEvent::MeteoraPoolsPoolCreated(e) => common.pool_create(e.pool, none, (e.token_a_mint, e.token_b_mint), (0, 0)),
+            DexEvent::MeteoraDammV2InitializePool(e) => common.pool_create(e.lb_pair, none, (e.token_x, e.token_y), (0, 0)),
+            DexEvent::MeteoraDlmmInitializePool(e) => common.pool_create(e.pool, e.creator, (none, none), (0, 0)),
+            _ => return rows,
+        };
+        rows.push(row);
+        rows
+    }
+}
+
+/// 成交行的 Arrow schema（列顺序同 [`FlatTradeRow::COLUMNS`]）
+#[cfg(feature = "arrow")]
+pub fn trade_schema() -> arrow::datatypes::Schema {
+    use arrow::datatypes::{DataType, Field};
+    arrow::datatypes::Schema::new(vec![
+        Field::new("signature", DataType::Utf8, false),
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_time", DataType::Int64, false),
+        Field::new("protocol", DataType::Utf8, false),
+        Field::new("event_type", DataType::Utf8, false),
+        Field::new("pool", DataType::Utf8, true),
+        Field::new("mint_in", DataType::Utf8, true),
+        Field::new("mint_out", DataType::Utf8, true),
+        Field::new("amount_in", DataType::UInt64, false),
+        Field::new("amount_out", DataType::UInt64, false),
+        Field::new("user", DataType::Utf8, true),
+        Field::new("fee", DataType::UInt64, false),
+        Field::new("price_f64", DataType::Float64, true),
+        Field::new("grpc_recv_us", DataType::Int64, false),
+    ])
+}
+
+/// 成交行转 Arrow RecordBatch（可直接交给 parquet `ArrowWriter`）
+#[cfg(feature = "arrow")]
+pub fn rows_to_record_batch(rows: &[FlatTradeRow]) -> arrow::record_batch::RecordBatch {
+    use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray, UInt64Array};
+    use std::sync::Arc;
+
+    let strings = |f: fn(&FlatTradeRow) -> &str| Arc::new(rows.iter().map(f).map(Some).collect::<StringArray>()) as ArrayRef;
+    let optional = |f: fn(&FlatTradeRow) -> Option<&str>| Arc::new(rows.iter().map(f).collect::<StringArray>()) as ArrayRef;
+    let u64s = |f: fn(&FlatTradeRow) -> u64| Arc::new(rows.iter().map(f).collect::<UInt64Array>()) as ArrayRef;
+    let i64s = |f: fn(&FlatTradeRow) -> i64| Arc::new(rows.iter().map(f).collect::<Int64Array>()) as ArrayRef;
+
+    let columns = vec![
+        strings(|r| &r.signature),
+        u64s(|r| r.slot),
+        i64s(|r| r.block_time),
+        strings(|r| &r.protocol),
+        strings(|r| &r.event_type),
+        optional(|r| r.pool.as_deref()),
+        optional(|r| r.mint_in.as_deref()),
+        optional(|r| r.mint_out.as_deref()),
+        u64s(|r| r.amount_in),
+        u64s(|r| r.amount_out),
+        optional(|r| r.user.as_deref()),
+        u64s(|r| r.fee),
+        Arc::new(rows.iter().map(|r| r.price_f64).collect::<Float64Array>()) as ArrayRef,
+        i64s(|r| r.grpc_recv_us),
+    ];
+    arrow::record_batch::RecordBatch::try_new(Arc::new(trade_schema()), columns).expect("columns follow trade_schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signature;
+
+    fn metadata() -> EventMetadata {
+        EventMetadata {
+            signature: Signature::from([7; 64]),
+            slot: 9,
+            tx_index: 0,
+            block_time_us: 1_700_000_000_000_000,
+            grpc_recv_us: 1_700_000_000_500_000,
+            handle_us: 0,
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    fn dlmm_swap(swap_for_y: bool) -> DexEvent {
+        DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+            metadata: metadata(),
+            pool: Pubkey::new_unique(),
+            from: Pubkey::new_unique(),
+            start_bin_id: 0,
+            end_bin_id: 1,
+            amount_in: 2_000,
+            amount_out: 500,
+            swap_for_y,
+            fee: 6,
+            protocol_fee: 1,
+            fee_bps: 30,
+            host_fee: 0,
+        })
+    }
+
+    #[test]
+    fn test_trade_row() {
+        let event = dlmm_swap(true);
+        let rows = event.to_flat_rows();
+        let [FlatRow::Trade(row)] = rows.as_slice() else { panic!("{:?}", rows) };
+        let DexEvent::MeteoraDlmmSwap(swap) = &event else { unreachable!() };
+
+        assert_eq!((row.protocol.as_str(), row.event_type.as_str()), ("MeteoraDlmm", "MeteoraDlmmSwap"));
+        assert_eq!((row.slot, row.block_time, row.grpc_recv_us), (9, 1_700_000_000, 1_700_000_000_500_000));
+        assert_eq!((row.pool.clone(), row.user.clone()), (Some(swap.pool.to_string()), Some(swap.from.to_string())));
+        assert_eq!((row.amount_in, row.amount_out, row.fee), (2_000, 500, 6));
+        assert_eq!((row.mint_in.as_deref(), row.mint_out.as_deref()), (None, None));
+        // swap_for_y 为卖出 X：价格 = 得到的 Y / 付出的 X
+        assert_eq!(row.price_f64, Some(0.25));
+        let rows = dlmm_swap(false).to_flat_rows();
+        let [FlatRow::Trade(row)] = rows.as_slice() else { unreachable!() };
+        assert_eq!(row.price_f64, Some(4.0));
+
+        assert!(DexEvent::Error("x".into()).to_flat_rows().is_empty());
+    }
+
+    #[test]
+    fn test_bonk_trade_row() {
+        let (base_mint, quote_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let bonk_trade = |is_buy: bool| {
+            DexEvent::BonkTrade(BonkTradeEvent {
+                metadata: metadata(),
+                pool_state: Pubkey::new_unique(),
+                user: Pubkey::new_unique(),
+                amount_in: 1_000,
+                amount_out: 4_000,
+                is_buy,
+                trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
+                exact_in: true,
+                base_mint,
+                quote_mint,
+                minimum_amount_out: None,
+                maximum_amount_in: None,
+                protocol_fee: Some(3),
+                platform_fee: Some(5),
+                share_fee: None,
+                virtual_base: None,
+                virtual_quote: None,
+                real_base_after: None,
+                real_quote_after: None,
+                pool_status: None,
+                curve_progress_bps: None,
+            })
+        };
+
+        let rows = bonk_trade(true).to_flat_rows();
+        let [FlatRow::Trade(row)] = rows.as_slice() else { panic!("{:?}", rows) };
+        assert_eq!(row.fee, 8);
+        assert_eq!((row.mint_in.clone(), row.mint_out.clone()), (Some(quote_mint.to_string()), Some(base_mint.to_string())));
+
+        let rows = bonk_trade(false).to_flat_rows();
+        let [FlatRow::Trade(row)] = rows.as_slice() else { panic!("{:?}", rows) };
+        assert_eq!((row.mint_in.clone(), row.mint_out.clone()), (Some(base_mint.to_string()), Some(quote_mint.to_string())));
+    }
+
+    #[test]
+    fn test_schema_is_stable() {
+        // 列集合变化会破坏下游表结构，修改时同步更新这里和 COLUMNS
+        let columns = |row: FlatRow| -> Vec<String> {
+            let value = serde_json::to_value(row).unwrap();
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().filter(|k| *k != "table").cloned().collect();
+            keys.sort();
+            keys
+        };
+        let sorted = |columns: &[&str]| {
+            let mut columns: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+            columns.sort();
+            columns
+        };
+        let common = || Common {
+            signature: String::new(),
+            slot: 0,
+            block_time: 0,
+            protocol: String::new(),
+            event_type: String::new(),
+            grpc_recv_us: 0,
+        };
+        let none = Pubkey::default();
+
+        assert_eq!(columns(common().trade(none, none, (None, None), (0, 0), 0, None)), sorted(&FlatTradeRow::COLUMNS));
+        assert_eq!(columns(common().liquidity(none, none, true, (none, none), None, None)), sorted(&FlatLiquidityRow::COLUMNS));
+        assert_eq!(columns(common().pool_create(none, none, (none, none), (0, 0))), sorted(&FlatPoolCreateRow::COLUMNS));
+        assert_eq!(
+            FlatTradeRow::COLUMNS,
+            [
+                "signature", "slot", "block_time", "protocol", "event_type", "pool", "mint_in", "mint_out",
+                "amount_in", "amount_out", "user", "fee", "price_f64", "grpc_recv_us",
+            ]
+        );
+
+        #[cfg(feature = "arrow")]
+        {
+            let names: Vec<_> = trade_schema().fields().iter().map(|f| f.name().clone()).collect();
+            assert_eq!(names, FlatTradeRow::COLUMNS);
+            let FlatRow::Trade(row) = dlmm_swap(true).to_flat_rows().remove(0) else { unreachable!() };
+            let batch = rows_to_record_batch(&[row.clone(), row]);
+            assert_eq!((batch.num_rows(), batch.num_columns()), (2, FlatTradeRow::COLUMNS.len()));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! 按签名聚合事件
+//!
+//! 放在解析器和消费者之间：[`TransactionGrouper`] 按签名缓存事件，自该签名第一个事件起
+//! 经过 `window` 后认为交易的事件已经到齐，整体输出为 [`TransactionEvents`]。
+//! - 组内事件按 `(outer_index, inner_index)` 排序
+//! - 多个组同时到期时按首个事件到达的先后输出
+//! - 没有元数据的事件（例如 `DexEvent::Error`）归入默认签名
+
+use crate::core::events::DexEvent;
+use crate::core::merger::sort_by_instruction_order;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// 同一笔交易的全部事件
+#[derive(Debug, Clone)]
+pub struct TransactionEvents {
+    pub signature: Signature,
+    pub slot: u64,
+    pub events: Vec<DexEvent>,
+}
+
+struct PendingGroup {
+    first_seen: Instant,
+    slot: u64,
+    events: Vec<DexEvent>,
+}
+
+/// 按签名在时间窗口内聚合事件
+pub struct TransactionGrouper {
+    window: Duration,
+    pending: HashMap<Signature, PendingGroup>,
+    /// 按首个事件到达顺序排列的签名
+    order: VecDeque<Signature>,
+}
+
+impl TransactionGrouper {
+    /// `window` 为每笔交易等待后续事件的时间
+    pub fn new(window: Duration) -> Self {
+        Self { window, pending: HashMap::new(), order: VecDeque::new() }
+    }
+
+    /// 加入一个事件，返回此刻已到期的交易
+    pub fn push(&mut self, event: DexEvent) -> Vec<TransactionEvents> {
+        self.push_at(event, Instant::now())
+    }
+
+    /// 以指定时刻加入一个事件（便于测试和回放）
+    pub fn push_at(&mut self, event: DexEvent, now: Instant) -> Vec<TransactionEvents> {
+        let (signature, slot) = event.metadata().map_or((Signature::default(), 0), |m| (m.signature, m.slot));
+        let group = self.pending.entry(signature).or_insert_with(|| {
+            self.order.push_back(signature);
+            PendingGroup { first_seen: now, slot, events: Vec::new() }
+        });
+        group.events.push(event);
+        self.poll_at(now)
+    }
+
+    /// 输出已到期的交易（没有新事件时由消费者定期调用）
+    pub fn poll(&mut self) -> Vec<TransactionEvents> {
+        self.poll_at(Instant::now())
+    }
+
+    /// 以指定时刻输出已到期的交易
+    pub fn poll_at(&mut self, now: Instant) -> Vec<TransactionEvents> {
+        let mut ready = Vec::new();
+        while let Some(signature) = self.order.front() {
+            let expired = self
+                .pending
+                .get(signature)
+                .is_some_and(|group| now.duration_since(group.first_seen) >= self.window);
+            if !expired {
+                break;
+            }
+            let signature = self.order.pop_front().unwrap();
+            if let Some(group) = self.pending.remove(&signature) {
+                ready.push(Self::finish(signature, group));
+            }
+        }
+        ready
+    }
+
+    /// 不等窗口，立即输出所有缓存的交易（例如订阅结束时）
+    pub fn flush(&mut self) -> Vec<TransactionEvents> {
+        let order = std::mem::take(&mut self.order);
+        order
+            .into_iter()
+            .filter_map(|signature| self.pending.remove(&signature).map(|group| Self::finish(signature, group)))
+            .collect()
+    }
+
+    /// 尚未输出的交易数
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn finish(signature: Signature, group: PendingGroup) -> TransactionEvents {
+        let mut events = group.events;
+        sort_by_instruction_order(&mut events);
+        TransactionEvents { signature, slot: group.slot, events }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{EventMetadata, EventSource, MeteoraDlmmSwapEvent};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn swap(signature: Signature, outer_index: u32, amount_in: u64) -> DexEvent {
+        DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+            metadata: EventMetadata {
+                signature,
+                slot: 42,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+            pool: Pubkey::default(),
+            from: Pubkey::default(),
+            start_bin_id: 0,
+            end_bin_id: 0,
+            amount_in,
+            amount_out: 0,
+            swap_for_y: false,
+            fee: 0,
+            protocol_fee: 0,
+            fee_bps: 0,
+            host_fee: 0,
+        })
+    }
+
+    #[test]
+    fn test_interleaved_signatures_are_grouped() {
+        let (a, b) = (Signature::from([1u8; 64]), Signature::from([2u8; 64]));
+        let mut grouper = TransactionGrouper::new(Duration::from_millis(50));
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+
+        assert!(grouper.push_at(swap(a, 1, 1), t0).is_empty());
+        assert!(grouper.push_at(swap(b, 0, 2), t0 + ms(10)).is_empty());
+        assert!(grouper.push_at(swap(a, 0, 3), t0 + ms(20)).is_empty());
+        assert!(grouper.push_at(swap(b, 1, 4), t0 + ms(30)).is_empty());
+        assert_eq!(grouper.pending_len(), 2);
+
+        // a 的窗口先到期，b 仍在等待
+        let ready = grouper.poll_at(t0 + ms(55));
+        assert_eq!(ready.len(), 1);
+        assert_eq!((ready[0].signature, ready[0].slot), (a, 42));
+        let amounts: Vec<u64> = ready[0]
+            .events
+            .iter()
+            .map(|e| match e {
+                DexEvent::MeteoraDlmmSwap(s) => s.amount_in,
+                _ => 0,
+            })
+            .collect();
+        assert_eq!(amounts, vec![3, 1]);
+
+        let ready = grouper.push_at(swap(a, 2, 5), t0 + ms(60));
+        assert_eq!(ready.len(), 1);
+        assert_eq!((ready[0].signature, ready[0].events.len()), (b, 2));
+
+        // 窗口结束后 a 的迟到事件单独成组
+        let rest = grouper.flush();
+        assert_eq!(rest.len(), 1);
+        assert_eq!((rest[0].signature, rest[0].events.len()), (a, 1));
+        assert_eq!(grouper.pending_len(), 0);
+    }
+}
@@ -0,0 +1,1077 @@
+//! 指令事件与日志事件合并
+//!
+//! 默认合并规则（[`MergeMode::PreferLog`]）："日志优先，指令补缺"：
+//! - 日志事件按原始顺序输出，与之匹配的指令事件只用于填充日志中缺失（默认值）的字段
+//! - 未匹配上的指令事件追加在末尾
+//!
+//! 其它策略见 [`MergeMode`]；按协议、按字段组调整哪一侧优先见 [`MergePreferences`]。合并后的事件 `metadata.event_source` 为 [`EventSource::Merged`]。
+//! 所有策略的输出最后都按 `(outer_index, inner_index)` 稳定排序，保证交易内的指令顺序。
+//!
+//! 匹配键为 (事件变体, 签名, 协议关键字段哈希)（Raydium AMM V4 用指令位置代替关键字段），先对日志事件建索引，
+//! 指令事件 O(1) 查找，整体 O(n)。同一键的多个事件按出现顺序一一配对。
+
+use crate::core::events::*;
+use crate::core::protocol::Protocol;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::mem::Discriminant;
+
+pub(crate) type MergeKey = (Discriminant<DexEvent>, Signature, u64);
+
+/// 指令/日志事件合并策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MergeMode {
+    /// 日志优先，指令补缺（执行结果为准）
+    #[default]
+    PreferLog,
+    /// 指令优先，日志补缺（用户意图为准）
+    PreferInstruction,
+    /// 不合并，指令和日志事件都输出，通过 `metadata.event_source` 区分
+    KeepBoth,
+    /// 只输出指令事件
+    InstructionOnly,
+    /// 只输出日志事件
+    LogOnly,
+}
+
+/// 合并配对事件时字段以哪一侧为准
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum MergeSource {
+    /// 日志（执行结果）
+    #[default]
+    Log,
+    /// 指令（用户输入与账户列表）
+    Instruction,
+}
+
+/// 合并时可单独配置优先级的字段组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeField {
+    /// 账户地址（user、pool、mint、token account 等）
+    Accounts,
+    /// 数量、价格、费用及其它非账户字段
+    Amounts,
+}
+
+/// 按协议、按字段组配置合并时哪一侧优先
+///
+/// 优先一侧的非默认值胜出，为默认值时由另一侧补齐。
+/// 默认全部日志优先，与 [`MergeMode::PreferLog`] 一致；[`MergePreferences::prefer_instruction`]
+/// 与 [`MergeMode::PreferInstruction`] 一致。未列入字段组的字段取自 `base` 一侧。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct MergePreferences {
+    /// 未列入字段组的字段（以及事件本身）取自哪一侧
+    pub base: MergeSource,
+    /// 账户字段指令优先的协议位（[`Protocol::bit`]）
+    instruction_accounts: u32,
+    /// 数量字段指令优先的协议位
+    instruction_amounts: u32,
+}
+
+impl MergePreferences {
+    /// 所有协议、所有字段组都指令优先
+    pub const fn prefer_instruction() -> Self {
+        Self { base: MergeSource::Instruction, instruction_accounts: u32::MAX, instruction_amounts: u32::MAX }
+    }
+
+    /// 设置某协议某字段组的优先来源
+    pub fn with(mut self, protocol: Protocol, field: MergeField, source: MergeSource) -> Self {
+        self.set(protocol, field, source);
+        self
+    }
+
+    /// 设置某协议某字段组的优先来源
+    pub fn set(&mut self, protocol: Protocol, field: MergeField, source: MergeSource) {
+        let bits = match field {
+            MergeField::Accounts => &mut self.instruction_accounts,
+            MergeField::Amounts => &mut self.instruction_amounts,
+        };
+        match source {
+            MergeSource::Instruction => *bits |= protocol.bit(),
+            MergeSource::Log => *bits &= !protocol.bit(),
+        }
+    }
+
+    /// 某协议某字段组的优先来源
+    #[inline]
+    pub fn source(&self, protocol: Protocol, field: MergeField) -> MergeSource {
+        let bits = match field {
+            MergeField::Accounts => self.instruction_accounts,
+            MergeField::Amounts => self.instruction_amounts,
+        };
+        if bits & protocol.bit() != 0 { MergeSource::Instruction } else { MergeSource::Log }
+    }
+}
+
+/// 合并指令事件和日志事件（[`MergeMode::PreferLog`]）
+pub fn merge_instruction_and_log_events(
+    instruction_events: Vec<DexEvent>,
+    log_events: Vec<DexEvent>,
+) -> Vec<DexEvent> {
+    merge_instruction_and_log_events_with_mode(instruction_events, log_events, MergeMode::PreferLog)
+}
+
+/// 按指定策略合并指令事件和日志事件
+///
+/// `KeepBoth` 时同一指令位置的日志事件在前、指令事件在后。
+pub fn merge_instruction_and_log_events_with_mode(
+    instruction_events: Vec<DexEvent>,
+    log_events: Vec<DexEvent>,
+    mode: MergeMode,
+) -> Vec<DexEvent> {
+    let mut merged = match mode {
+        MergeMode::PreferLog => merge_matched(instruction_events, log_events, MergePreferences::default()),
+        MergeMode::PreferInstruction => {
+            merge_matched(instruction_events, log_events, MergePreferences::prefer_instruction())
+        }
+        MergeMode::KeepBoth => {
+            let mut merged = log_events;
+            tag_source(&mut merged, EventSource::Log);
+            let start = merged.len();
+            merged.extend(instruction_events);
+            tag_source(&mut merged[start..], EventSource::Instruction);
+            merged
+        }
+        MergeMode::InstructionOnly => instruction_events,
+        MergeMode::LogOnly => log_events,
+    };
+    finish(&mut merged);
+    merged
+}
+
+/// 按 [`MergePreferences`] 合并配对的指令事件和日志事件，未配对的事件都保留
+pub fn merge_instruction_and_log_events_with_preferences(
+    instruction_events: Vec<DexEvent>,
+    log_events: Vec<DexEvent>,
+    preferences: MergePreferences,
+) -> Vec<DexEvent> {
+    let mut merged = merge_matched(instruction_events, log_events, preferences);
+    finish(&mut merged);
+    merged
+}
+
+#[inline]
+fn merge_matched(
+    instruction_events: Vec<DexEvent>,
+    log_events: Vec<DexEvent>,
+    preferences: MergePreferences,
+) -> Vec<DexEvent> {
+    let mut merged = log_events;
+    if !instruction_events.is_empty() {
+        merge_into(&mut merged, instruction_events, preferences);
+    }
+    merged
+}
+
+/// 关联迁移、按指令顺序排序，标记 dev buy，计算 Bonk 募集进度和 Meteora Pools 费率模式
+#[inline]
+fn finish(merged: &mut [DexEvent]) {
+    link_pumpfun_migrations(merged);
+    sort_by_instruction_order(merged);
+    mark_dev_buys(merged);
+    fill_bonk_curve_progress(merged);
+    fill_meteora_fee_modes(merged);
+}
+
+/// 按交易内的指令顺序 `(outer_index, inner_index)` 稳定排序
+///
+/// 同一位置的事件保持原有相对顺序；没有元数据的事件排在最后。
+pub fn sort_by_instruction_order(events: &mut [DexEvent]) {
+    events.sort_by_key(instruction_order_key);
+}
+
+/// 排序键：没有元数据的事件排在最后
+#[inline]
+pub(crate) fn instruction_order_key(event: &DexEvent) -> (u32, Option<u32>) {
+    event.metadata().map_or((u32::MAX, Some(u32::MAX)), |m| m.instruction_order())
+}
+
+#[inline]
+fn tag_source(events: &mut [DexEvent], source: EventSource) {
+    for event in events {
+        if let Some(metadata) = event.metadata_mut() {
+            metadata.event_source = source;
+        }
+    }
+}
+
+/// 按合并键把指令事件填入对应的日志事件，未配对的指令事件追加到末尾
+///
+/// 输出位置总是日志事件的位置。
+pub(crate) fn merge_into(merged: &mut Vec<DexEvent>, instruction_events: Vec<DexEvent>, preferences: MergePreferences) {
+    let mut index: HashMap<MergeKey, VecDeque<usize>> = HashMap::with_capacity(merged.len());
+    for (i, event) in merged.iter().enumerate() {
+        if let Some(key) = merge_key(event) {
+            index.entry(key).or_default().push_back(i);
+        }
+    }
+
+    let mut unmatched = Vec::new();
+    for instr_event in instruction_events {
+        let slot = merge_key(&instr_event)
+            .and_then(|key| index.get_mut(&key))
+            .and_then(|positions| positions.pop_front());
+
+        match slot {
+            Some(i) => {
+                let log_event = std::mem::replace(&mut merged[i], DexEvent::Error(String::new()));
+                merged[i] = merge_pair(log_event, instr_event, preferences);
+            }
+            None => unmatched.push(instr_event),
+        }
+    }
+
+    merged.extend(unmatched);
+}
+
+/// 合并一对合并键相同的日志/指令事件，指令位置以日志侧（invoke 栈）为准
+pub(crate) fn merge_pair(log_event: DexEvent, mut instr_event: DexEvent, preferences: MergePreferences) -> DexEvent {
+    // 指令原始数据只有指令侧有
+    let raw = instr_event.metadata_mut().and_then(|m| m.raw.take());
+    let log_metadata = log_event.metadata().map(|m| (m.instruction_order(), m.protocol_version));
+    let times = [&log_event, &instr_event].map(|e| e.metadata().map_or((0, 0), |m| (m.grpc_recv_us, m.handle_us)));
+    // 字段组的优先来源与 base 不同时，另一侧的非默认值覆盖 base
+    let other_wins = |field| {
+        log_event.protocol().is_some_and(|protocol| preferences.source(protocol, field) != preferences.base)
+    };
+    let (accounts, amounts) = (other_wins(MergeField::Accounts), other_wins(MergeField::Amounts));
+    let mut event = match preferences.base {
+        MergeSource::Instruction => fill_gaps(instr_event, &log_event, accounts, amounts),
+        MergeSource::Log => fill_gaps(log_event, &instr_event, accounts, amounts),
+    };
+    if let Some(metadata) = event.metadata_mut() {
+        metadata.event_source = EventSource::Merged;
+        // 接收时间取两侧中较早的非零值，构造时间取较晚值，与哪一侧作为 base 无关
+        metadata.grpc_recv_us = times.iter().map(|&(recv, _)| recv).filter(|&recv| recv > 0).min().unwrap_or(0);
+        metadata.handle_us = times.iter().map(|&(_, handle)| handle).max().unwrap_or(0);
+        if let Some(((outer_index, inner_index), protocol_version)) = log_metadata {
+            metadata.outer_index = outer_index;
+            metadata.inner_index = inner_index;
+            // 布局版本只有日志侧知道
+            metadata.protocol_version = metadata.protocol_version.or(protocol_version);
+        }
+        metadata.raw = raw;
+    }
+    event
+}
+
+/// 关联同笔交易中的 PumpFun 迁移与其创建的 PumpSwap 池，互相补齐缺失字段
+///
+/// 按签名 + 池地址（或 mint）配对；两个事件都保留，通过相同的池地址关联。
+pub(crate) fn link_pumpfun_migrations(events: &mut [DexEvent]) {
+    let migrations: Vec<usize> = events
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| matches!(e, DexEvent::PumpFunMigrate(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    for m in migrations {
+        let DexEvent::PumpFunMigrate(migrate) = &events[m] else { continue };
+        let (signature, mint, pool) = (migrate.metadata.signature, migrate.mint, migrate.pool);
+
+        let Some(c) = events.iter().position(|e| match e {
+            DexEvent::PumpSwapCreatePool(create) => {
+                create.metadata.signature == signature
+                    && ((pool != Pubkey::default() && create.pool_id == pool)
+                        || (mint != Pubkey::default() && create.token_mint == mint))
+            }
+            _ => false,
+        }) else {
+            continue;
+        };
+
+        let DexEvent::PumpSwapCreatePool(create) = &events[c] else { continue };
+        let (pool_id, token_mint, initial_token, initial_sol) =
+            (create.pool_id, create.token_mint, create.initial_token_amount, create.initial_sol_amount);
+
+        let DexEvent::PumpFunMigrate(migrate) = &mut events[m] else { continue };
+        fill_if_default(&mut migrate.pool, &pool_id);
+        fill_if_default(&mut migrate.mint, &token_mint);
+        fill_if_default(&mut migrate.pool_base_amount, &initial_token);
+        fill_if_default(&mut migrate.pool_quote_amount, &initial_sol);
+        let (pool, mint, mint_amount, sol_amount) =
+            (migrate.pool, migrate.mint, migrate.mint_amount, migrate.sol_amount);
+
+        let DexEvent::PumpSwapCreatePool(create) = &mut events[c] else { continue };
+        fill_if_default(&mut create.pool_id, &pool);
+        fill_if_default(&mut create.token_mint, &mint);
+        fill_if_default(&mut create.initial_token_amount, &mint_amount);
+        fill_if_default(&mut create.initial_sol_amount, &sol_amount);
+    }
+}
+
+/// 标记同笔交易中创建者的买入（`is_dev_create_token_trade`），`events` 需已按指令顺序排序
+pub fn mark_dev_buys(events: &mut [DexEvent]) {
+    let mut tracker = DevBuyTracker::default();
+    for event in events {
+        tracker.observe(event);
+    }
+}
+
+/// 按事件顺序识别 dev buy：记录已出现的 PumpFun create，之后同签名、同 mint、由创建者发起的买入即为 dev buy
+#[derive(Default)]
+pub(crate) struct DevBuyTracker {
+    /// (签名, mint, user, creator)
+    creates: Vec<(Signature, Pubkey, Pubkey, Pubkey)>,
+}
+
+impl DevBuyTracker {
+    #[inline]
+    pub(crate) fn observe(&mut self, event: &mut DexEvent) {
+        match event {
+            DexEvent::PumpFunCreate(e) => self.creates.push((e.metadata.signature, e.mint, e.user, e.creator)),
+            DexEvent::PumpFunTrade(e) if e.is_buy && !self.creates.is_empty() && e.user != Pubkey::default() => {
+                e.is_dev_create_token_trade |= self.creates.iter().any(|&(signature, mint, user, creator)| {
+                    signature == e.metadata.signature && mint == e.mint && (user == e.user || creator == e.user)
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 计算 Bonk 成交的 `curve_progress_bps`，`events` 需已按指令顺序排序
+pub(crate) fn fill_bonk_curve_progress(events: &mut [DexEvent]) {
+    let mut tracker = BonkCurveTracker::default();
+    for event in events {
+        tracker.observe(event);
+    }
+}
+
+/// 按事件顺序计算 Bonk 募集进度：同一交易中先出现的 PoolCreate 提供募集目标，
+/// 否则 SOL 报价的池使用 [`BONK_DEFAULT_QUOTE_FUND_RAISING`]，其它报价（USD1 等）或报价未知时不计算；
+/// 池状态已离开 Fund 时记为 10000
+#[derive(Default)]
+pub(crate) struct BonkCurveTracker {
+    /// (签名, pool_state, total_quote_fund_raising)
+    targets: Vec<(Signature, Pubkey, u64)>,
+}
+
+impl BonkCurveTracker {
+    #[inline]
+    pub(crate) fn observe(&mut self, event: &mut DexEvent) {
+        match event {
+            DexEvent::BonkPoolCreate(e) if e.total_quote_fund_raising > 0 => {
+                self.targets.push((e.metadata.signature, e.pool_state, e.total_quote_fund_raising));
+            }
+            DexEvent::BonkTrade(e) => {
+                let target = self
+                    .targets
+                    .iter()
+                    .find(|&&(signature, pool, _)| signature == e.metadata.signature && pool == e.pool_state)
+                    .map(|&(_, _, target)| target)
+                    .or((e.quote_mint == WSOL_MINT).then_some(BONK_DEFAULT_QUOTE_FUND_RAISING));
+                e.curve_progress_bps = match (e.pool_status, e.real_quote_after, target) {
+                    (Some(status), ..) if status != 0 => Some(10_000),
+                    (_, Some(quote), Some(target)) => Some((quote as u128 * 10_000 / target as u128).min(10_000) as u16),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 按同交易中先出现的 SetPoolFees 判断 Meteora Pools 成交的费率模式，`events` 需已按指令顺序排序
+pub(crate) fn fill_meteora_fee_modes(events: &mut [DexEvent]) {
+    let mut tracker = MeteoraPoolFeeTracker::default();
+    for event in events {
+        tracker.observe(event);
+    }
+}
+
+/// 按事件顺序记录 SetPoolFees 设置的交易费率，之后同签名、同池子的成交据此判断费率模式
+#[derive(Default)]
+pub(crate) struct MeteoraPoolFeeTracker {
+    /// (签名, pool, trade_fee_numerator, trade_fee_denominator)
+    fees: Vec<(Signature, Pubkey, u64, u64)>,
+}
+
+impl MeteoraPoolFeeTracker {
+    #[inline]
+    pub(crate) fn observe(&mut self, event: &mut DexEvent) {
+        match event {
+            DexEvent::MeteoraPoolsSetPoolFees(e) => {
+                self.fees.push((e.metadata.signature, e.pool, e.trade_fee_numerator, e.trade_fee_denominator));
+            }
+            DexEvent::MeteoraPoolsSwap(e) if !self.fees.is_empty() && e.pool != Pubkey::default() => {
+                // 同一池子多次设置时取最近一次
+                let latest = self.fees.iter().rev().find(|&&(signature, pool, ..)| {
+                    signature == e.metadata.signature && pool == e.pool
+                });
+                if let Some(&(_, _, numerator, denominator)) = latest {
+                    e.apply_pool_fees(numerator, denominator);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 计算合并键，没有元数据的事件不参与合并
+#[inline]
+pub(crate) fn merge_key(event: &DexEvent) -> Option<MergeKey> {
+    let metadata = event.metadata()?;
+    let mut hasher = DefaultHasher::new();
+    match event {
+        // ray_log 不含 amm 账户，AMM V4 按指令位置配对，多跳路由两次经过同一池子时不会错配
+        DexEvent::RaydiumAmmV4Swap(_) => metadata.instruction_order().hash(&mut hasher),
+        _ => key_fields(event).hash(&mut hasher),
+    }
+    Some((std::mem::discriminant(event), metadata.signature, hasher.finish()))
+}
+
+/// 协议关键字段（mint / pool），指令和日志两侧都能拿到的字段
+#[inline]
+fn key_fields(event: &DexEvent) -> Option<Pubkey> {
+    match event {
+        DexEvent::PumpFunCreate(e) => Some(e.mint),
+        DexEvent::PumpFunTrade(e) => Some(e.mint),
+        DexEvent::PumpFunComplete(e) => Some(e.mint),
+        DexEvent::PumpFunMigrate(e) => Some(e.mint),
+        DexEvent::BonkTrade(e) => Some(e.pool_state),
+        DexEvent::BonkPoolCreate(e) => Some(e.pool_state),
+        DexEvent::PumpSwapBuy(e) => Some(e.pool_id),
+        DexEvent::PumpSwapSell(e) => Some(e.pool_id),
+        DexEvent::PumpSwapCreatePool(e) => Some(e.pool_id),
+        DexEvent::PumpSwapDeposit(e) => Some(e.pool),
+        DexEvent::PumpSwapWithdraw(e) => Some(e.pool),
+        DexEvent::RaydiumClmmSwap(e) => Some(e.pool_state),
+        // 增减流动性日志只有头寸 NFT，按头寸账户配对；开仓日志只有池地址
+        DexEvent::RaydiumClmmOpenPosition(e) => Some(e.pool),
+        DexEvent::RaydiumClmmIncreaseLiquidity(e) => Some(e.position_key),
+        DexEvent::RaydiumClmmDecreaseLiquidity(e) => Some(e.position_key),
+        DexEvent::RaydiumCpmmSwap(e) => Some(e.pool_id),
+        DexEvent::OrcaWhirlpoolSwap(e) => Some(e.whirlpool),
+        DexEvent::OrcaWhirlpoolCollectFees(e) => Some(e.position),
+        DexEvent::OrcaWhirlpoolCollectReward(e) => Some(e.position),
+        DexEvent::MeteoraDammV2Swap(e) => Some(e.lb_pair),
+        DexEvent::MeteoraDlmmSwap(e) => Some(e.pool),
+        _ => None,
+    }
+}
+
+/// 目标字段为默认值时从指令事件补齐
+#[inline]
+fn fill_if_default<T: Default + PartialEq + Clone>(dst: &mut T, src: &T) {
+    if *dst == T::default() {
+        *dst = src.clone();
+    }
+}
+
+/// `src_wins` 为 true 时 `src` 的非默认值覆盖目标字段，否则只在目标字段为默认值时补齐
+#[inline]
+fn merge_field<T: Default + PartialEq + Clone>(dst: &mut T, src: &T, src_wins: bool) {
+    if src_wins {
+        if *src != T::default() {
+            *dst = src.clone();
+        }
+    } else {
+        fill_if_default(dst, src);
+    }
+}
+
+macro_rules! fill_default {
+    ($dst:expr, $src:expr, $src_wins:expr; $($field:ident),+ $(,)?) => {
+        $( merge_field(&mut $dst.$field, &$src.$field, $src_wins); )+
+    };
+}
+
+/// 计算 PumpFun 成交价、滑点余量与价格冲击
+///
+/// bonding curve 为虚拟储备上的恒定乘积 `x * y = k`（x = SOL，y = 代币）。
+/// TradeEvent 中的储备是成交**之后**的值，先还原成交前储备：
+/// - 买入：`x0 = x1 - sol_amount`，`y0 = y1 + token_amount`
+/// - 卖出：`x0 = x1 + sol_amount`，`y0 = y1 - token_amount`
+///
+/// 成交前现货价 `p0 = x0 / y0`，成交均价 `p = sol_amount / token_amount`：
+/// - 价格冲击 = `|p / p0 - 1|`；恒定乘积下买入等于 `dx / x0`，卖出等于 `dy / (y0 + dy)`
+/// - 滑点余量：买入 `(max_sol_cost - sol_amount) / max_sol_cost`，
+///   卖出 `(sol_amount - min_sol_output) / min_sol_output`
+///
+/// 以上都不含手续费（`sol_amount` 是进出 bonding curve 的 SOL），结果向零取整。
+fn fill_pumpfun_trade_pricing(trade: &mut PumpFunTradeEvent) {
+    let (sol, tokens) = (trade.sol_amount as i128, trade.token_amount as i128);
+    if sol == 0 || tokens == 0 {
+        return;
+    }
+    trade.price_per_token_lamports = sol as f64 / tokens as f64;
+
+    let (x1, y1) = (trade.virtual_sol_reserves as i128, trade.virtual_token_reserves as i128);
+    let (x0, y0) = if trade.is_buy { (x1 - sol, y1 + tokens) } else { (x1 + sol, y1 - tokens) };
+    if x1 > 0 && y1 > 0 && x0 > 0 && y0 > 0 {
+        // p / p0 - 1 = (sol * y0 - tokens * x0) / (tokens * x0)
+        let delta = sol * y0 - tokens * x0;
+        let adverse = if trade.is_buy { delta } else { -delta };
+        trade.price_impact_bps = i32::try_from(adverse * 10_000 / (tokens * x0)).ok();
+    }
+
+    let limit = trade.sol_limit as i128;
+    if limit > 0 {
+        let headroom = if trade.is_buy { limit - sol } else { sol - limit };
+        trade.slippage_bps = i32::try_from(headroom * 10_000 / limit).ok();
+    }
+}
+
+/// 用另一侧事件填充 `base` 中缺失的字段（`base` 的已有值优先）
+///
+/// `accounts` / `amounts` 为 true 时该字段组改为另一侧的非默认值优先，见 [`MergeField`]。
+fn fill_gaps(base: DexEvent, other: &DexEvent, accounts: bool, amounts: bool) -> DexEvent {
+    let mut event = base;
+    match (&mut event, other) {
+        (DexEvent::PumpFunTrade(dst), DexEvent::PumpFunTrade(src)) => {
+            fill_default!(dst, src, accounts; user, fee_recipient, creator);
+            fill_default!(dst, src, amounts; sol_amount, token_amount, sol_limit);
+            fill_pumpfun_trade_pricing(dst);
+        }
+        (DexEvent::PumpFunCreate(dst), DexEvent::PumpFunCreate(src)) => {
+            fill_default!(dst, src, accounts; bonding_curve, user);
+            fill_default!(dst, src, amounts; name, symbol, uri);
+        }
+        (DexEvent::PumpFunMigrate(dst), DexEvent::PumpFunMigrate(src)) => {
+            fill_default!(dst, src, accounts; user, bonding_curve, pool);
+            fill_default!(dst, src, amounts; mint_amount, sol_amount);
+        }
+        (DexEvent::PumpFunSetParams(dst), DexEvent::PumpFunSetParams(src)) => {
+            fill_default!(dst, src, accounts; global, authority, set_creator_authority, admin_set_creator_authority);
+        }
+        (DexEvent::BonkTrade(dst), DexEvent::BonkTrade(src)) => {
+            fill_default!(dst, src, accounts; user, base_mint, quote_mint);
+            fill_default!(dst, src, amounts; amount_in, amount_out);
+            // 用户意图只在指令侧、费用和曲线状态只在日志侧，缺失时互相补齐
+            fill_default!(dst, src, false;
+                minimum_amount_out, maximum_amount_in, protocol_fee, platform_fee, share_fee,
+                virtual_base, virtual_quote, real_base_after, real_quote_after, pool_status,
+            );
+        }
+        (DexEvent::BonkPoolCreate(dst), DexEvent::BonkPoolCreate(src)) => {
+            fill_default!(dst, src, accounts; creator, config, platform_config, base_mint, quote_mint);
+        }
+        (DexEvent::PumpSwapBuy(dst), DexEvent::PumpSwapBuy(src)) => {
+            fill_default!(dst, src, accounts; pool_id, user, token_mint);
+            fill_default!(dst, src, amounts; sol_amount, token_amount, slippage);
+        }
+        (DexEvent::PumpSwapSell(dst), DexEvent::PumpSwapSell(src)) => {
+            fill_default!(dst, src, accounts; pool_id, user, token_mint);
+            fill_default!(dst, src, amounts; sol_amount, token_amount, slippage);
+        }
+        (DexEvent::PumpSwapDeposit(dst), DexEvent::PumpSwapDeposit(src)) => {
+            fill_default!(dst, src, accounts;
+                user, base_mint, quote_mint, user_base_token_account, user_quote_token_account, user_pool_token_account,
+            );
+            fill_default!(dst, src, amounts; lp_token_amount_out, max_base_amount_in, max_quote_amount_in);
+        }
+        (DexEvent::PumpSwapWithdraw(dst), DexEvent::PumpSwapWithdraw(src)) => {
+            fill_default!(dst, src, accounts;
+                user, base_mint, quote_mint, user_base_token_account, user_quote_token_account, user_pool_token_account,
+            );
+            fill_default!(dst, src, amounts; lp_token_amount_in, min_base_amount_out, min_quote_amount_out);
+        }
+        (DexEvent::RaydiumClmmSwap(dst), DexEvent::RaydiumClmmSwap(src)) => {
+            fill_default!(dst, src, accounts; sender, token_account_0, token_account_1);
+        }
+        (DexEvent::RaydiumClmmOpenPosition(dst), DexEvent::RaydiumClmmOpenPosition(src)) => {
+            fill_default!(dst, src, accounts; user, position_nft_mint, position_key);
+            fill_default!(dst, src, amounts; tick_lower_index, tick_upper_index, liquidity, amount0, amount1);
+        }
+        (DexEvent::RaydiumClmmIncreaseLiquidity(dst), DexEvent::RaydiumClmmIncreaseLiquidity(src)) => {
+            fill_default!(dst, src, accounts; pool, user, position_nft_mint, position_key);
+            fill_default!(dst, src, amounts; liquidity, amount0, amount1, tick_lower_index, tick_upper_index);
+            fill_default!(dst, src, false; amount0_max, amount1_max);
+        }
+        (DexEvent::RaydiumClmmDecreaseLiquidity(dst), DexEvent::RaydiumClmmDecreaseLiquidity(src)) => {
+            fill_default!(dst, src, accounts; pool, user, position_nft_mint, position_key);
+            fill_default!(dst, src, amounts; liquidity, amount0, amount1, tick_lower_index, tick_upper_index);
+            fill_default!(dst, src, false; amount0_min, amount1_min);
+        }
+        (DexEvent::RaydiumAmmV4Swap(dst), DexEvent::RaydiumAmmV4Swap(src)) => {
+            fill_default!(dst, src, amounts;
+                amount_in, minimum_amount_out, max_amount_in, amount_out, pool_coin_amount, pool_pc_amount, direction,
+            );
+            fill_default!(dst, src, accounts;
+                token_program, amm, amm_authority, amm_open_orders, amm_target_orders,
+                pool_coin_token_account, pool_pc_token_account,
+                serum_program, serum_market, serum_bids, serum_asks, serum_event_queue,
+                serum_coin_vault_account, serum_pc_vault_account, serum_vault_signer,
+                user_source_token_account, user_destination_token_account, user_source_owner,
+            );
+        }
+        (DexEvent::RaydiumAmmV4Deposit(dst), DexEvent::RaydiumAmmV4Deposit(src)) => {
+            fill_default!(dst, src, amounts; max_coin_amount, max_pc_amount, base_side);
+            fill_default!(dst, src, accounts;
+                token_program, amm, amm_authority, amm_open_orders, amm_target_orders,
+                lp_mint_address, pool_coin_token_account, pool_pc_token_account, serum_market,
+                user_coin_token_account, user_pc_token_account, user_lp_token_account,
+                user_owner, serum_event_queue,
+            );
+        }
+        (DexEvent::RaydiumAmmV4Withdraw(dst), DexEvent::RaydiumAmmV4Withdraw(src)) => {
+            fill_default!(dst, src, amounts; amount);
+            fill_default!(dst, src, accounts;
+                token_program, amm, amm_authority, amm_open_orders, amm_target_orders,
+                lp_mint_address, pool_coin_token_account, pool_pc_token_account,
+                pool_withdraw_queue, pool_temp_lp_token_account,
+                serum_program, serum_market, serum_coin_vault_account, serum_pc_vault_account,
+                serum_vault_signer, serum_event_queue, serum_bids, serum_asks,
+                user_lp_token_account, user_coin_token_account, user_pc_token_account, user_owner,
+            );
+        }
+        (DexEvent::RaydiumAmmV4Initialize2(dst), DexEvent::RaydiumAmmV4Initialize2(src)) => {
+            fill_default!(dst, src, amounts; nonce, open_time, init_pc_amount, init_coin_amount);
+            fill_default!(dst, src, accounts;
+                token_program, spl_associated_token_account, system_program, rent,
+                amm, amm_authority, amm_open_orders, lp_mint, coin_mint, pc_mint,
+                pool_coin_token_account, pool_pc_token_account, pool_withdraw_queue,
+                amm_target_orders, pool_temp_lp, serum_program, serum_market,
+                user_wallet, user_token_coin, user_token_pc, user_lp_token_account,
+            );
+        }
+        (DexEvent::OrcaWhirlpoolCollectFees(dst), DexEvent::OrcaWhirlpoolCollectFees(src)) => {
+            fill_default!(dst, src, accounts; whirlpool, owner, token_owner_account_a, token_owner_account_b);
+            fill_default!(dst, src, amounts; amount_a, amount_b);
+        }
+        (DexEvent::OrcaWhirlpoolCollectReward(dst), DexEvent::OrcaWhirlpoolCollectReward(src)) => {
+            fill_default!(dst, src, accounts; whirlpool, owner, reward_owner_account, reward_mint);
+            fill_default!(dst, src, amounts; amount);
+        }
+        (DexEvent::MeteoraDammV2Swap(dst), DexEvent::MeteoraDammV2Swap(src)) => {
+            fill_default!(dst, src, accounts; from);
+            fill_default!(dst, src, amounts;
+                amount_in, amount_out, swap_for_y, start_bin_id, end_bin_id, fee, protocol_fee, fee_bps, host_fee,
+                partner_fee, pre_sqrt_price, post_sqrt_price,
+            );
+        }
+        (DexEvent::MeteoraPoolsSwap(dst), DexEvent::MeteoraPoolsSwap(src)) => {
+            fill_default!(dst, src, accounts; pool);
+            fill_default!(dst, src, amounts; trade_fee, admin_fee, host_fee, fee_mode, effective_fee_bps);
+        }
+        (DexEvent::MeteoraDlmmSwap(dst), DexEvent::MeteoraDlmmSwap(src)) => {
+            fill_default!(dst, src, accounts; from);
+            fill_default!(dst, src, amounts;
+                amount_in, amount_out, swap_for_y, start_bin_id, end_bin_id, fee, protocol_fee, fee_bps, host_fee,
+            );
+        }
+        _ => {}
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(signature: Signature) -> EventMetadata {
+        EventMetadata { signature, slot: 1, tx_index: 0, block_time_us: 0, grpc_recv_us: 0, handle_us: 0, event_source: EventSource::Log, outer_index: 0, inner_index: None, protocol_version: None, sequence: 0, raw: None, priority_fee_micro_lamports: None, compute_unit_limit: None }
+    }
+
+    fn dlmm_swap(signature: Signature, pool: Pubkey, from: Pubkey, amount_in: u64) -> DexEvent {
+        DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
+            metadata: metadata(signature),
+            pool,
+            from,
+            start_bin_id: 0,
+            end_bin_id: 0,
+            amount_in,
+            amount_out: 0,
+            swap_for_y: false,
+            fee: 0,
+            protocol_fee: 0,
+            fee_bps: 0,
+            host_fee: 0,
+        })
+    }
+
+    /// 朴素实现：对每个指令事件线性扫描全部日志事件，O(n²)
+    fn merge_reference(instruction_events: Vec<DexEvent>, log_events: Vec<DexEvent>) -> Vec<DexEvent> {
+        let mut merged = log_events;
+        let log_count = merged.len();
+        let mut used = vec![false; log_count];
+        let mut unmatched = Vec::new();
+        for instr_event in instruction_events {
+            let key = merge_key(&instr_event);
+            let found = key.and_then(|k| {
+                (0..log_count).find(|&i| !used[i] && merge_key(&merged[i]) == Some(k))
+            });
+            match found {
+                Some(i) => {
+                    used[i] = true;
+                    merged[i] = fill_gaps(merged[i].clone(), &instr_event, false, false);
+                    if let Some(metadata) = merged[i].metadata_mut() {
+                        metadata.event_source = EventSource::Merged;
+                    }
+                }
+                None => unmatched.push(instr_event),
+            }
+        }
+        merged.extend(unmatched);
+        sort_by_instruction_order(&mut merged);
+        merged
+    }
+
+    fn render(events: &[DexEvent]) -> Vec<String> {
+        events.iter().map(|e| format!("{:?}", e)).collect()
+    }
+
+    #[test]
+    fn test_bonk_curve_progress_needs_known_target() {
+        let trade = |quote_mint: Pubkey, pool_status: u8| {
+            DexEvent::BonkTrade(BonkTradeEvent {
+                metadata: metadata(Signature::default()),
+                pool_state: Pubkey::new_unique(),
+                user: Pubkey::new_unique(),
+                amount_in: 1,
+                amount_out: 1,
+                is_buy: true,
+                trade_direction: TradeDirection::Buy,
+                exact_in: true,
+                base_mint: Pubkey::new_unique(),
+                quote_mint,
+                minimum_amount_out: None,
+                maximum_amount_in: None,
+                protocol_fee: None,
+                platform_fee: None,
+                share_fee: None,
+                virtual_base: None,
+                virtual_quote: None,
+                real_base_after: None,
+                real_quote_after: Some(42_500_000_000),
+                pool_status: Some(pool_status),
+                curve_progress_bps: None,
+            })
+        };
+        let progress = |event: DexEvent| {
+            let mut events = [event];
+            fill_bonk_curve_progress(&mut events);
+            let [DexEvent::BonkTrade(e)] = events else { unreachable!() };
+            e.curve_progress_bps
+        };
+
+        // SOL 报价按默认 85 SOL 目标计算；USD1 等其它报价、只有日志（报价未知）时没有目标
+        assert_eq!(progress(trade(WSOL_MINT, 0)), Some(5_000));
+        assert_eq!(progress(trade(Pubkey::new_unique(), 0)), None);
+        assert_eq!(progress(trade(Pubkey::default(), 0)), None);
+        // 已离开募集阶段时与目标无关
+        assert_eq!(progress(trade(Pubkey::new_unique(), 1)), Some(10_000));
+    }
+
+    #[test]
+    fn test_log_wins_instruction_fills_gaps() {
+        let sig = Signature::from([1u8; 64]);
+        let pool = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let log = dlmm_swap(sig, pool, Pubkey::default(), 100);
+        let instr = dlmm_swap(sig, pool, user, 999);
+
+        let merged = merge_instruction_and_log_events(vec![instr], vec![log]);
+        assert_eq!(merged.len(), 1);
+        match &merged[0] {
+            DexEvent::MeteoraDlmmSwap(e) => {
+                assert_eq!(e.from, user);
+                assert_eq!(e.amount_in, 100);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_matches_reference_on_small_inputs() {
+        let sig_a = Signature::from([1u8; 64]);
+        let sig_b = Signature::from([2u8; 64]);
+        let pools: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let user = Pubkey::new_unique();
+
+        let logs = vec![
+            dlmm_swap(sig_a, pools[0], Pubkey::default(), 1),
+            dlmm_swap(sig_a, pools[1], Pubkey::default(), 2),
+            dlmm_swap(sig_b, pools[0], Pubkey::default(), 3),
+            dlmm_swap(sig_a, pools[0], Pubkey::default(), 4),
+            DexEvent::Error("x".to_string()),
+        ];
+        let instrs = vec![
+            dlmm_swap(sig_a, pools[0], user, 0),
+            dlmm_swap(sig_a, pools[0], user, 0),
+            dlmm_swap(sig_a, pools[0], user, 0),
+            dlmm_swap(sig_b, pools[2], user, 0),
+            dlmm_swap(sig_b, pools[0], user, 0),
+        ];
+
+        let fast = merge_instruction_and_log_events(instrs.clone(), logs.clone());
+        let slow = merge_reference(instrs, logs);
+        assert_eq!(render(&fast), render(&slow));
+        assert_eq!(fast.len(), 7);
+    }
+
+    #[test]
+    fn test_raydium_amm_v4_swap_deep_merge() {
+        // 合成的 ray_log（非链上记录）
+        // SwapBaseIn: amount_in = 1_000_000_000, minimum_out = 140_000_000, 实际成交 149_812_345
+        const RAY_LOG: &str = "Program log: ray_log: AwDKmjsAAAAAADtYCAAAAAACAAAAAAAAAADyBSoBAAAAAEC3Q7oAAAAAsI7wGwAAAHn07QgAAAAA";
+
+        let sig = Signature::from([7u8; 64]);
+        let accounts: Vec<Pubkey> = (0..18).map(|_| Pubkey::new_unique()).collect();
+        let mut ix_data = vec![9u8];
+        ix_data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+        ix_data.extend_from_slice(&140_000_000u64.to_le_bytes());
+
+        let instr = crate::instr::parse_raydium_amm_instruction(&ix_data, &accounts, sig, 1, 0, None).unwrap();
+        let log = crate::logs::parse_raydium_amm_log(RAY_LOG, sig, 1, 0, None, 0).unwrap();
+
+        let merged = merge_instruction_and_log_events(vec![instr], vec![log]);
+        assert_eq!(merged.len(), 1);
+        match &merged[0] {
+            DexEvent::RaydiumAmmV4Swap(e) => {
+                assert_eq!(e.amount_in, 1_000_000_000);
+                assert_eq!(e.minimum_amount_out, 140_000_000);
+                assert_eq!(e.amount_out, 149_812_345);
+                assert_eq!(e.direction, 2);
+                assert_eq!(e.pool_coin_amount, 800_000_000_000);
+                assert_eq!(e.pool_pc_amount, 120_000_000_000);
+                assert_eq!(e.amm, accounts[1]);
+                assert_eq!(e.pool_coin_token_account, accounts[5]);
+                assert_eq!(e.pool_pc_token_account, accounts[6]);
+                assert_eq!(e.user_source_token_account, accounts[15]);
+                assert_eq!(e.user_destination_token_account, accounts[16]);
+                assert_eq!(e.user_source_owner, accounts[17]);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_raydium_amm_v4_multi_hop_pairs_by_position() {
+        // 合成数据：同一笔交易两次经过 AMM V4（内层指令 0 和 2），日志事件按出现顺序排在前面
+        let sig = Signature::from([7u8; 64]);
+        let ray_log = "Program log: ray_log: AwDKmjsAAAAAADtYCAAAAAACAAAAAAAAAADyBSoBAAAAAEC3Q7oAAAAAsI7wGwAAAHn07QgAAAAA";
+        let Some(DexEvent::RaydiumAmmV4Swap(base)) = crate::logs::parse_raydium_amm_log(ray_log, sig, 1, 0, None, 0) else {
+            panic!("expected a swap")
+        };
+        let swap = |outer: u32, inner: u32, amount_in: u64, amount_out: u64, amm: Pubkey| {
+            let mut e = base.clone();
+            e.metadata.outer_index = outer;
+            e.metadata.inner_index = Some(inner);
+            (e.amount_in, e.amount_out, e.amm) = (amount_in, amount_out, amm);
+            DexEvent::RaydiumAmmV4Swap(e)
+        };
+        let (first_amm, second_amm) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let logs = vec![swap(1, 0, 0, 500, Pubkey::default()), swap(1, 2, 0, 70, Pubkey::default())];
+        // 指令事件顺序与日志相反，只按签名配对会错配
+        let instrs = vec![swap(1, 2, 500, 0, second_amm), swap(1, 0, 1_000, 0, first_amm)];
+
+        let merged = merge_instruction_and_log_events(instrs, logs);
+        let pairs: Vec<_> = merged
+            .iter()
+            .map(|event| match event {
+                DexEvent::RaydiumAmmV4Swap(e) => (e.metadata.inner_index, e.amm, e.amount_in, e.amount_out),
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(pairs, vec![(Some(0), first_amm, 1_000, 500), (Some(2), second_amm, 500, 70)]);
+    }
+
+    #[test]
+    fn test_pumpfun_migration_linked_to_pumpswap_pool() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let signature = Signature::new_unique();
+        let accounts: Vec<Pubkey> = (0..24).map(|_| Pubkey::new_unique()).collect();
+        let (mint, bonding_curve, user, pool) = (accounts[2], accounts[3], accounts[5], accounts[9]);
+        let program_data = |discriminator: [u8; 8], body: Vec<u8>| {
+            let mut data = discriminator.to_vec();
+            data.extend(body);
+            format!("Program data: {}", general_purpose::STANDARD.encode(data))
+        };
+
+        // MigrateEvent: user, mint, mint_amount, sol_amount, pool_migration_fee, bonding_curve, timestamp, pool
+        let mut body = Vec::new();
+        body.extend_from_slice(user.as_ref());
+        body.extend_from_slice(mint.as_ref());
+        body.extend_from_slice(&206_900_000_000_000u64.to_le_bytes());
+        body.extend_from_slice(&84_990_359_370u64.to_le_bytes());
+        body.extend_from_slice(&15_000_001u64.to_le_bytes());
+        body.extend_from_slice(bonding_curve.as_ref());
+        body.extend_from_slice(&1_750_000_000i64.to_le_bytes());
+        body.extend_from_slice(pool.as_ref());
+        let migrate_log = program_data(crate::logs::pumpfun::discriminators::MIGRATE_EVENT, body);
+
+        // PumpSwap CreatePoolEvent（池地址缺省，靠迁移事件关联补齐）
+        let mut body = Vec::new();
+        body.extend_from_slice(accounts[10].as_ref());
+        body.extend_from_slice(mint.as_ref());
+        body.extend_from_slice(Pubkey::default().as_ref());
+        body.extend_from_slice(&84_990_359_370u64.to_le_bytes());
+        body.extend_from_slice(&206_900_000_000_000u64.to_le_bytes());
+        let create_pool_log = program_data(crate::logs::pump_amm::discriminators::CREATE_POOL, body);
+
+        let log_events = vec![
+            crate::logs::parse_pumpfun_log(&migrate_log, signature, 1, 0, None, 0, false).expect("migrate log"),
+            crate::logs::parse_pump_amm_log(&create_pool_log, signature, 1, 0, None, 0).expect("create pool log"),
+        ];
+        let instr_events = vec![crate::instr::pumpfun::parse_instruction(
+            &crate::instr::pumpfun::discriminators::MIGRATE, &accounts, signature, 1, 0, None,
+        ).expect("migrate instruction")];
+
+        let merged = merge_instruction_and_log_events(instr_events, log_events);
+        assert_eq!(merged.len(), 2);
+        match (&merged[0], &merged[1]) {
+            (DexEvent::PumpFunMigrate(migrate), DexEvent::PumpSwapCreatePool(create)) => {
+                assert_eq!(migrate.pool, pool);
+                assert_eq!(create.pool_id, pool);
+                assert_eq!(create.token_mint, migrate.mint);
+                assert_eq!(migrate.pool_base_amount, 206_900_000_000_000);
+                assert_eq!(migrate.pool_quote_amount, 84_990_359_370);
+            }
+            other => panic!("unexpected events {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pumpfun_trade_pricing() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let signature = Signature::new_unique();
+        let accounts: Vec<Pubkey> = (0..12).map(|_| Pubkey::new_unique()).collect();
+        let mint = accounts[2];
+        let trade = |is_buy: bool, ix: [u8; 8], limit: u64, sol: u64, tokens: u64, reserves: (u64, u64)| {
+            let mut data = crate::logs::pumpfun::discriminators::TRADE_EVENT.to_vec();
+            data.extend_from_slice(mint.as_ref());
+            data.extend_from_slice(&sol.to_le_bytes());
+            data.extend_from_slice(&tokens.to_le_bytes());
+            data.push(is_buy as u8);
+            data.extend_from_slice(accounts[6].as_ref());
+            data.extend_from_slice(&0i64.to_le_bytes());
+            data.extend_from_slice(&reserves.0.to_le_bytes());
+            data.extend_from_slice(&reserves.1.to_le_bytes());
+            data.extend_from_slice(&[0u8; 256]);
+            let log = format!("Program data: {}", general_purpose::STANDARD.encode(data));
+            let log_event = crate::logs::parse_pumpfun_log(&log, signature, 1, 0, None, 0, false).unwrap();
+
+            let mut ix_data = ix.to_vec();
+            ix_data.extend_from_slice(&tokens.to_le_bytes());
+            ix_data.extend_from_slice(&limit.to_le_bytes());
+            let instr_event = crate::instr::pumpfun::parse_instruction(&ix_data, &accounts, signature, 1, 0, None).unwrap();
+
+            match merge_instruction_and_log_events(vec![instr_event], vec![log_event]).remove(0) {
+                DexEvent::PumpFunTrade(e) => e,
+                other => panic!("unexpected event {:?}", other),
+            }
+        };
+
+        // 交易前虚拟储备 30 SOL / 1_073_000_000 代币，买入 1 SOL：
+        // dy = 1_073e15 * 1e9 / 31e9 = 34_612_903_225_806，冲击 = dx / x0 = 1/30 ≈ 333 bps
+        // max_sol_cost = 1.05 SOL，余量 = 0.05 / 1.05 ≈ 476 bps
+        let buy = trade(
+            true, crate::instr::pumpfun::discriminators::BUY, 1_050_000_000,
+            1_000_000_000, 34_612_903_225_806, (31_000_000_000, 1_038_387_096_774_194),
+        );
+        assert_eq!(buy.price_impact_bps, Some(333));
+        assert_eq!(buy.slippage_bps, Some(476));
+        assert!((buy.price_per_token_lamports - 1e9 / 34_612_903_225_806.0).abs() < 1e-15);
+
+        // 把同样数量的代币卖回：dx = 31e9 * dy / 1_073e15 = 999_999_999，
+        // 冲击 = dy / (y0 + dy) ≈ 322 bps；min_sol_output = 0.95 SOL，余量 ≈ 526 bps
+        let sell = trade(
+            false, crate::instr::pumpfun::discriminators::SELL, 950_000_000,
+            999_999_999, 34_612_903_225_806, (30_000_000_001, 1_073_000_000_000_000),
+        );
+        assert_eq!(sell.price_impact_bps, Some(322));
+        assert_eq!(sell.slippage_bps, Some(526));
+    }
+
+    #[test]
+    fn test_merge_modes_for_one_matching_pair() {
+        let sig = Signature::new_unique();
+        let pool = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let instr = || {
+            let mut e = dlmm_swap(sig, pool, user, 1_000);
+            e.metadata_mut().unwrap().event_source = EventSource::Instruction;
+            e
+        };
+        let log = || dlmm_swap(sig, pool, Pubkey::default(), 990);
+        let run = |mode| merge_instruction_and_log_events_with_mode(vec![instr()], vec![log()], mode);
+
+        let merged = run(MergeMode::PreferLog);
+        assert_eq!(merged.len(), 1);
+        match &merged[0] {
+            DexEvent::MeteoraDlmmSwap(e) => {
+                assert_eq!(e.amount_in, 990);
+                assert_eq!(e.from, user);
+                assert_eq!(e.metadata.event_source, EventSource::Merged);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let merged = run(MergeMode::PreferInstruction);
+        assert_eq!(merged.len(), 1);
+        match &merged[0] {
+            DexEvent::MeteoraDlmmSwap(e) => assert_eq!(e.amount_in, 1_000),
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let merged = run(MergeMode::KeepBoth);
+        assert_eq!(merged.len(), 2);
+        let sources: Vec<_> = merged.iter().map(|e| e.metadata().unwrap().event_source).collect();
+        assert_eq!(sources, vec![EventSource::Log, EventSource::Instruction]);
+
+        let merged = run(MergeMode::InstructionOnly);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].metadata().unwrap().event_source, EventSource::Instruction);
+
+        let merged = run(MergeMode::LogOnly);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].metadata().unwrap().event_source, EventSource::Log);
+    }
+
+    #[test]
+    fn test_preferences_override_pumpfun_amounts() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let signature = Signature::new_unique();
+        let accounts: Vec<Pubkey> = (0..12).map(|_| Pubkey::new_unique()).collect();
+        let mut data = crate::logs::pumpfun::discriminators::TRADE_EVENT.to_vec();
+        data.extend_from_slice(accounts[2].as_ref());
+        data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(accounts[6].as_ref());
+        data.extend_from_slice(&[0u8; 280]);
+        let log = format!("Program data: {}", general_purpose::STANDARD.encode(data));
+        let log_event = crate::logs::parse_pumpfun_log(&log, signature, 1, 0, None, 0, false).unwrap();
+
+        // 指令参数里的代币数量与日志不同
+        let mut ix_data = crate::instr::pumpfun::discriminators::BUY.to_vec();
+        ix_data.extend_from_slice(&4_000u64.to_le_bytes());
+        ix_data.extend_from_slice(&1_100_000_000u64.to_le_bytes());
+        let instr_event = crate::instr::pumpfun::parse_instruction(&ix_data, &accounts, signature, 1, 0, None).unwrap();
+
+        let run = |preferences| {
+            let merged = merge_instruction_and_log_events_with_preferences(
+                vec![instr_event.clone()], vec![log_event.clone()], preferences,
+            );
+            assert_eq!(merged.len(), 1);
+            match merged.into_iter().next() {
+                Some(DexEvent::PumpFunTrade(e)) => e,
+                other => panic!("unexpected event {:?}", other),
+            }
+        };
+
+        let default = run(MergePreferences::default());
+        assert_eq!(default.token_amount, 5_000);
+        assert_eq!(default.sol_limit, 1_100_000_000);
+
+        let preferences =
+            MergePreferences::default().with(Protocol::PumpFun, MergeField::Amounts, MergeSource::Instruction);
+        assert_eq!(preferences.source(Protocol::PumpFun, MergeField::Accounts), MergeSource::Log);
+        assert_eq!(preferences.source(Protocol::PumpSwap, MergeField::Amounts), MergeSource::Log);
+        let merged = run(preferences);
+        assert_eq!(merged.token_amount, 4_000);
+        // 指令侧为默认值的字段仍取日志
+        assert_eq!(merged.sol_amount, 1_000_000_000);
+        assert_eq!(merged.user, accounts[6]);
+        assert_eq!(merged.metadata.event_source, EventSource::Merged);
+    }
+}
\ No newline at end of file
@@ -8,14 +8,42 @@
 
 // 核心模块
 pub mod events;          // 事件定义
+pub mod protocol;        // 协议 - 协议枚举与程序 ID 映射
 pub mod unified_parser;  // 统一解析器 - 单一入口
 pub mod account_filler;  // 账户填充器 - 从指令数据填充事件账户
+pub mod transfer_filler; // 数量补全器 - 从内层代币转账补全成交数量
+pub mod merger;          // 合并器 - 指令事件与日志事件合并
+#[cfg(feature = "grpc")]
+pub mod router;          // 路由器 - 按代币/池子分发事件
+pub mod registry;        // 注册表 - 运行时注册自定义协议
+pub mod grouper;         // 聚合器 - 按签名聚合同一交易的事件
+pub mod aggregator;      // 汇总器 - 按 slot 汇总各协议的成交统计
+pub mod discriminators;  // 校验 - discriminator 重复与占位值检查
+pub mod state;           // 状态 - 从事件流维护各池子最新储备/价格
+pub mod flat;            // 扁平行 - 转成列式存储友好的成交 / 流动性 / 建池行
+pub mod display;         // 展示 - 事件的单行可读摘要（Display）
+pub mod arbitrage;       // 跨协议 - 识别同一交易在多个协议上成交同一代币
 
 // 主要导出 - 核心事件处理功能
 pub use events::*;
+pub use merger::{
+    mark_dev_buys, merge_instruction_and_log_events, merge_instruction_and_log_events_with_mode,
+    merge_instruction_and_log_events_with_preferences, sort_by_instruction_order, MergeField, MergeMode,
+    MergePreferences, MergeSource,
+};
+#[cfg(feature = "grpc")]
+pub use router::EventRouter;
+pub use grouper::{TransactionEvents, TransactionGrouper};
+pub use aggregator::{ProtocolStats, SlotAggregator, SlotSummary};
+pub use registry::{InstructionSpec, LogEventSpec, ParseContext, ParserRegistry};
+pub use discriminators::{validate_discriminators, Collision};
+pub use state::{ClmmPoolState, CurveState, PoolStateTracker, WhirlpoolState};
 pub use unified_parser::{
-    parse_transaction_events, parse_logs_only, parse_transaction_with_listener, EventListener,
-    parse_transaction_events_streaming, parse_logs_streaming, parse_transaction_with_streaming_listener, StreamingEventListener
+    parse_transaction_events, parse_transaction_events_with_options, ParseMode, ParseOptions,
+    parse_transaction_events_iter, parse_transaction_events_iter_with_options, TransactionEventsIter, parse_logs_only,
+    parse_transaction_with_listener, EventListener, AsyncEventListener,
+    parse_transaction_events_streaming, parse_transaction_events_streaming_with_options, parse_logs_streaming,
+    parse_transaction_with_streaming_listener, parse_transaction_with_streaming_listener_with_options, StreamingEventListener
 };
 
 // 兼容性类型
@@ -0,0 +1,106 @@
+//! 协议枚举与程序 ID 映射
+//!
+//! 程序 ID 的唯一来源是 [`crate::grpc::program_ids`]（base58 字符串）与
+//! [`crate::instr::program_ids`]（由同一字符串编译期解码的 `Pubkey`），解析器和过滤器都引用这两处。
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::instr::program_ids as ids;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Protocol {
+    PumpFun,
+    PumpSwap,
+    Bonk,
+    RaydiumCpmm,
+    RaydiumClmm,
+    RaydiumAmmV4,
+    OrcaWhirlpool,
+    MeteoraPools,
+    MeteoraDammV2,
+    MeteoraDlmm,
+}
+
+impl Protocol {
+    /// 所有支持的协议
+    pub const ALL: [Protocol; 10] = [
+        Protocol::PumpFun,
+        Protocol::PumpSwap,
+        Protocol::Bonk,
+        Protocol::RaydiumCpmm,
+        Protocol::RaydiumClmm,
+        Protocol::RaydiumAmmV4,
+        Protocol::OrcaWhirlpool,
+        Protocol::MeteoraPools,
+        Protocol::MeteoraDammV2,
+        Protocol::MeteoraDlmm,
+    ];
+
+    /// 协议在 ProtocolSet 中的位
+    #[inline(always)]
+    pub const fn bit(self) -> u32 {
+        1 << (self as u32)
+    }
+
+    /// 协议的链上程序 ID
+    pub const fn program_id(self) -> Pubkey {
+        match self {
+            Protocol::PumpFun => ids::PUMPFUN_PROGRAM_ID,
+            Protocol::PumpSwap => ids::PUMPSWAP_PROGRAM_ID,
+            Protocol::Bonk => ids::BONK_PROGRAM_ID,
+            Protocol::RaydiumCpmm => ids::RAYDIUM_CPMM_PROGRAM_ID,
+            Protocol::RaydiumClmm => ids::RAYDIUM_CLMM_PROGRAM_ID,
+            Protocol::RaydiumAmmV4 => ids::RAYDIUM_AMM_V4_PROGRAM_ID,
+            Protocol::OrcaWhirlpool => ids::ORCA_WHIRLPOOL_PROGRAM_ID,
+            Protocol::MeteoraPools => ids::METEORA_POOLS_PROGRAM_ID,
+            Protocol::MeteoraDammV2 => ids::METEORA_DAMM_V2_PROGRAM_ID,
+            Protocol::MeteoraDlmm => ids::METEORA_DLMM_PROGRAM_ID,
+        }
+    }
+
+    /// 按程序 ID 反查协议，非内置协议返回 None
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Protocol> {
+        Self::ALL.into_iter().find(|protocol| protocol.program_id() == *program_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_ids_round_trip() {
+        let expected = [
+            (Protocol::PumpFun, "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"),
+            (Protocol::PumpSwap, "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA"),
+            (Protocol::Bonk, "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj"),
+            (Protocol::RaydiumCpmm, "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C"),
+            (Protocol::RaydiumClmm, "CAMMCzo5YL8w4VFF8KVHrK22GGUQpMDdHFWF5LCATdCR"),
+            (Protocol::RaydiumAmmV4, "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"),
+            (Protocol::OrcaWhirlpool, "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc"),
+            (Protocol::MeteoraPools, "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB"),
+            (Protocol::MeteoraDammV2, "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG"),
+            (Protocol::MeteoraDlmm, "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo"),
+        ];
+        assert_eq!(expected.len(), Protocol::ALL.len());
+        for (protocol, base58) in expected {
+            assert_eq!(protocol.program_id().to_string(), base58, "{:?}", protocol);
+            assert_eq!(Protocol::from_program_id(&protocol.program_id()), Some(protocol));
+        }
+        assert_eq!(ids::TOKEN_PROGRAM_ID.to_string(), "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+        assert_eq!(ids::TOKEN_2022_PROGRAM_ID.to_string(), "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+        assert_eq!(Protocol::from_program_id(&Pubkey::new_unique()), None);
+
+        // 日志匹配器中预拼接的 invoke / success 行
+        use crate::logs::optimized_matcher::program_id_strings as lines;
+        for (invoke, success, id) in [
+            (lines::PUMPFUN_INVOKE, lines::PUMPFUN_SUCCESS, lines::PUMPFUN_ID),
+            (lines::BONK_INVOKE, lines::BONK_SUCCESS, lines::BONK_ID),
+            (lines::RAYDIUM_CLMM_INVOKE, lines::RAYDIUM_CLMM_SUCCESS, lines::RAYDIUM_CLMM_ID),
+            (lines::RAYDIUM_CPMM_INVOKE, lines::RAYDIUM_CPMM_SUCCESS, lines::RAYDIUM_CPMM_ID),
+        ] {
+            assert_eq!((invoke, success), (format!("Program {} invoke", id).as_str(), format!("Program {} success", id).as_str()));
+        }
+    }
+}
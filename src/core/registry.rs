@@ -0,0 +1,289 @@
+//! 运行时协议注册表
+//!
+//! 用于解析 crate 未内置的程序（例如布局与 PumpFun 相同、程序 ID 不同的分叉）：
+//! - 通过 [`ParserRegistry::register`] 注册程序 ID、指令规格与日志事件规格，应在订阅开始前完成
+//! - 内置程序表未命中时才查询注册表：指令按程序 ID，日志按 invoke 栈所属程序
+//! - 注册表为空时热路径只多一次原子读；非空时每笔交易只获取一次读锁（[`ParserRegistry::read`]）
+
+use crate::core::events::{DexEvent, EventMetadata, EventSource};
+use once_cell::sync::Lazy;
+use parking_lot::{RwLock, RwLockReadGuard};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 解析上下文
+#[derive(Debug, Clone, Copy)]
+pub struct ParseContext {
+    pub signature: Signature,
+    pub slot: u64,
+    pub tx_index: u64,
+    pub block_time: Option<i64>,
+    pub grpc_recv_us: i64,
+}
+
+impl ParseContext {
+    /// 构造事件元数据（`event_source` 会在返回前按解析路径覆盖）
+    pub fn metadata(&self) -> EventMetadata {
+        EventMetadata {
+            signature: self.signature,
+            slot: self.slot,
+            tx_index: self.tx_index,
+            block_time_us: self.block_time.unwrap_or(0) * 1_000_000,
+            grpc_recv_us: self.grpc_recv_us,
+            handle_us: crate::common::time::handle_micros(self.grpc_recv_us),
+            event_source: EventSource::Log,
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+}
+
+/// 指令解析函数：(指令数据, 账户, 上下文)
+pub type InstructionParser = Arc<dyn Fn(&[u8], &[Pubkey], &ParseContext) -> Option<DexEvent> + Send + Sync>;
+
+/// 日志事件解析函数：(原始日志行, discriminator 之后的事件数据, 上下文)
+pub type LogEventParser = Arc<dyn Fn(&str, &[u8], &ParseContext) -> Option<DexEvent> + Send + Sync>;
+
+/// 指令规格：指令数据以 `discriminator` 开头时调用 `parse`
+#[derive(Clone)]
+pub struct InstructionSpec {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub parse: InstructionParser,
+}
+
+impl InstructionSpec {
+    pub fn new<F>(name: impl Into<String>, discriminator: impl Into<Vec<u8>>, parse: F) -> Self
+    where
+        F: Fn(&[u8], &[Pubkey], &ParseContext) -> Option<DexEvent> + Send + Sync + 'static,
+    {
+        Self { name: name.into(), discriminator: discriminator.into(), parse: Arc::new(parse) }
+    }
+}
+
+/// 日志事件规格：`Program data:` 解码后前 8 字节等于 `discriminator` 时调用 `parse`
+#[derive(Clone)]
+pub struct LogEventSpec {
+    pub name: String,
+    pub discriminator: [u8; 8],
+    pub parse: LogEventParser,
+}
+
+impl LogEventSpec {
+    pub fn new<F>(name: impl Into<String>, discriminator: [u8; 8], parse: F) -> Self
+    where
+        F: Fn(&str, &[u8], &ParseContext) -> Option<DexEvent> + Send + Sync + 'static,
+    {
+        Self { name: name.into(), discriminator, parse: Arc::new(parse) }
+    }
+}
+
+/// 已注册的程序
+pub struct CustomProtocol {
+    pub name: String,
+    pub program_id: Pubkey,
+    pub instructions: Vec<InstructionSpec>,
+    pub log_events: Vec<LogEventSpec>,
+}
+
+#[derive(Default)]
+struct Tables {
+    by_id: HashMap<Pubkey, Arc<CustomProtocol>>,
+    /// 日志中的程序 ID 是 base58 字符串，单独建索引避免每行解码
+    by_str: HashMap<String, Arc<CustomProtocol>>,
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static TABLES: Lazy<RwLock<Tables>> = Lazy::new(|| RwLock::new(Tables::default()));
+
+/// 全局协议注册表
+pub struct ParserRegistry;
+
+impl ParserRegistry {
+    /// 注册（或替换）一个程序的解析规格
+    pub fn register(
+        name: impl Into<String>,
+        program_id: Pubkey,
+        instructions: Vec<InstructionSpec>,
+        log_events: Vec<LogEventSpec>,
+    ) {
+        let protocol = Arc::new(CustomProtocol { name: name.into(), program_id, instructions, log_events });
+        let mut tables = TABLES.write();
+        tables.by_str.insert(program_id.to_string(), Arc::clone(&protocol));
+        tables.by_id.insert(program_id, protocol);
+        ACTIVE.store(true, Ordering::Release);
+    }
+
+    /// 注销程序，返回是否存在
+    pub fn unregister(program_id: &Pubkey) -> bool {
+        let mut tables = TABLES.write();
+        tables.by_str.remove(&program_id.to_string());
+        let removed = tables.by_id.remove(program_id).is_some();
+        ACTIVE.store(!tables.by_id.is_empty(), Ordering::Release);
+        removed
+    }
+
+    /// 清空注册表
+    pub fn clear() {
+        let mut tables = TABLES.write();
+        *tables = Tables::default();
+        ACTIVE.store(false, Ordering::Release);
+    }
+
+    /// 已注册的程序
+    pub fn protocols() -> Vec<Arc<CustomProtocol>> {
+        TABLES.read().by_id.values().cloned().collect()
+    }
+
+    #[inline(always)]
+    pub fn is_empty() -> bool {
+        !ACTIVE.load(Ordering::Acquire)
+    }
+
+    /// 获取读锁（注册表为空时返回 None，不加锁）
+    ///
+    /// 读锁可能按交易长时间持有（如 [`parse_transaction_events_iter`](crate::core::unified_parser::parse_transaction_events_iter)），
+    /// 用可重入读，持有期间再次读取不会因等待中的写者死锁。
+    #[inline]
+    pub fn read() -> Option<RegistryReadGuard> {
+        if Self::is_empty() {
+            return None;
+        }
+        Some(RegistryReadGuard(TABLES.read_recursive()))
+    }
+}
+
+/// 注册表读锁，一笔交易内复用
+pub struct RegistryReadGuard(RwLockReadGuard<'static, Tables>);
+
+impl RegistryReadGuard {
+    #[inline]
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.0.by_id.contains_key(program_id)
+    }
+
+    #[inline]
+    pub fn contains_str(&self, program_id: &str) -> bool {
+        self.0.by_str.contains_key(program_id)
+    }
+
+    /// 解析已注册程序的指令
+    pub fn parse_instruction(
+        &self,
+        program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+        ctx: &ParseContext,
+    ) -> Option<DexEvent> {
+        let protocol = self.0.by_id.get(program_id)?;
+        let spec = protocol.instructions.iter().find(|s| data.starts_with(&s.discriminator))?;
+        let mut event = (spec.parse)(data, accounts, ctx)?;
+        if let Some(metadata) = event.metadata_mut() {
+            metadata.event_source = EventSource::Instruction;
+        }
+        Some(event)
+    }
+
+    /// 解析已注册程序的 `Program data:` 日志
+    pub fn parse_log(&self, program_id: &str, log: &str, ctx: &ParseContext) -> Option<DexEvent> {
+        let protocol = self.0.by_str.get(program_id)?;
+        let mut event = crate::logs::utils::with_program_data(log, |program_data| {
+            let discriminator: [u8; 8] = program_data.get(..8)?.try_into().ok()?;
+            let spec = protocol.log_events.iter().find(|s| s.discriminator == discriminator)?;
+            (spec.parse)(log, &program_data[8..], ctx)
+        })?;
+        if let Some(metadata) = event.metadata_mut() {
+            metadata.event_source = EventSource::Log;
+        }
+        Some(event)
+    }
+
+    /// 按 invoke 栈找出属于已注册程序的日志并解析，返回解析出的事件数
+    pub fn parse_logs<F: FnMut(DexEvent)>(&self, logs: &[String], ctx: &ParseContext, mut on_event: F) -> usize {
+        let mut invokes = crate::logs::optimized_matcher::InvokeTracker::new();
+        let mut count = 0;
+        for log in logs {
+            if invokes.observe(log) {
+                continue;
+            }
+            if let Some(mut event) = invokes.current_program().and_then(|p| self.parse_log(p, log, ctx)) {
+                invokes.stamp(&mut event);
+                on_event(event);
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::CustomEvent;
+    use base64::{engine::general_purpose, Engine as _};
+
+    /// 与 PumpFun 布局相同的分叉：复用内置解析器，只换程序 ID
+    fn register_pumpfun_fork(program_id: Pubkey) {
+        let buy = InstructionSpec::new("buy", crate::instr::pumpfun::discriminators::BUY, |data, accounts, ctx| {
+            crate::instr::pumpfun::parse_instruction(data, accounts, ctx.signature, ctx.slot, ctx.tx_index, ctx.block_time)
+        });
+        let trade = LogEventSpec::new("TradeEvent", crate::logs::pumpfun::discriminators::TRADE_EVENT, |log, _, ctx| {
+            crate::logs::parse_pumpfun_trade(log, ctx.signature, ctx.slot, ctx.tx_index, ctx.block_time, ctx.grpc_recv_us, false)
+        });
+        let note = LogEventSpec::new("Note", [9; 8], move |_, data, ctx| {
+            Some(DexEvent::Custom(CustomEvent {
+                metadata: ctx.metadata(),
+                protocol: "pumpfun-fork".into(),
+                name: "Note".into(),
+                program_id,
+                data: serde_json::json!({ "len": data.len() }),
+            }))
+        });
+        ParserRegistry::register("pumpfun-fork", program_id, vec![buy], vec![trade, note]);
+    }
+
+    #[test]
+    fn test_registered_fork_events_come_through() {
+        let fork = Pubkey::new_unique();
+        register_pumpfun_fork(fork);
+
+        // 指令：内置表未命中后走注册表
+        let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mut data = crate::instr::pumpfun::discriminators::BUY.to_vec();
+        data.resize(24, 1);
+        let event = crate::instr::parse_instruction_unified(&data, &accounts, Signature::default(), 5, 0, None, &fork, None);
+        assert!(matches!(event, Some(DexEvent::PumpFunTrade(ref e)) if e.metadata.event_source == EventSource::Instruction));
+
+        // 日志：按 invoke 栈归属到分叉程序
+        let mut trade = crate::logs::pumpfun::discriminators::TRADE_EVENT.to_vec();
+        trade.extend_from_slice(&[0u8; 200]);
+        let mut note = vec![9u8; 8];
+        note.extend_from_slice(&[1, 2, 3]);
+        let logs = vec![
+            format!("Program {} invoke [1]", fork),
+            format!("Program data: {}", general_purpose::STANDARD.encode(&trade)),
+            format!("Program data: {}", general_purpose::STANDARD.encode(&note)),
+            format!("Program {} success", fork),
+        ];
+        let events = crate::parse_logs_only(&logs, Signature::default(), 5, None, None);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], DexEvent::PumpFunTrade(_)));
+        match &events[1] {
+            DexEvent::Custom(e) => {
+                assert_eq!(e.program_id, fork);
+                assert_eq!(e.data["len"], 3);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        assert!(ParserRegistry::unregister(&fork));
+        assert!(crate::instr::parse_instruction_unified(&data, &accounts, Signature::default(), 5, 0, None, &fork, None).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! 按代币 / 池子分发事件的路由器
+//!
+//! 一个 gRPC 订阅、一个 [`DexEventQueue`]，由路由器统一消费，再按
+//! [`DexEvent::primary_mint`] / [`DexEvent::primary_pool`] 分发到各自的有界 channel：
+//! - 同时命中 mint 和 pool 订阅时两边都会收到
+//! - 都未命中的事件发往默认 channel（未创建则丢弃）
+//! - 新建的 channel 只接收此后的事件，不回放
+//! - Receiver 被 drop 后，对应的发送端在下次分发或新建 channel 时自动移除
+//! - channel 已满时丢弃事件并计入 [`EventRouter::dropped`]，不阻塞其它代币
+
+use crate::core::events::DexEvent;
+use crate::grpc::DexEventQueue;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+
+type Routes = HashMap<Pubkey, Vec<Sender<DexEvent>>>;
+
+struct RouterState {
+    by_mint: Mutex<Routes>,
+    by_pool: Mutex<Routes>,
+    default: Mutex<Option<Sender<DexEvent>>>,
+    channel_capacity: usize,
+    dropped: AtomicU64,
+    stopped: AtomicBool,
+}
+
+/// 事件路由器（Clone 后共享同一组路由）
+#[derive(Clone)]
+pub struct EventRouter {
+    queue: DexEventQueue,
+    state: Arc<RouterState>,
+}
+
+impl EventRouter {
+    /// 创建路由器，`channel_capacity` 为每个 channel 的容量
+    pub fn new(queue: DexEventQueue, channel_capacity: usize) -> Self {
+        Self {
+            queue,
+            state: Arc::new(RouterState {
+                by_mint: Mutex::new(HashMap::new()),
+                by_pool: Mutex::new(HashMap::new()),
+                default: Mutex::new(None),
+                channel_capacity: channel_capacity.max(1),
+                dropped: AtomicU64::new(0),
+                stopped: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// 订阅某个代币的事件
+    pub fn channel_for_mint(&self, mint: Pubkey) -> Receiver<DexEvent> {
+        self.subscribe(&self.state.by_mint, mint)
+    }
+
+    /// 订阅某个池子的事件
+    pub fn channel_for_pool(&self, pool: Pubkey) -> Receiver<DexEvent> {
+        self.subscribe(&self.state.by_pool, pool)
+    }
+
+    /// 未命中任何订阅的事件（再次调用会替换之前的默认 channel）
+    pub fn default_channel(&self) -> Receiver<DexEvent> {
+        let (tx, rx) = mpsc::channel(self.state.channel_capacity);
+        *self.state.default.lock() = Some(tx);
+        rx
+    }
+
+    fn subscribe(&self, routes: &Mutex<Routes>, key: Pubkey) -> Receiver<DexEvent> {
+        let (tx, rx) = mpsc::channel(self.state.channel_capacity);
+        let mut routes = routes.lock();
+        routes.retain(|_, senders| {
+            senders.retain(|s| !s.is_closed());
+            !senders.is_empty()
+        });
+        routes.entry(key).or_default().push(tx);
+        rx
+    }
+
+    /// 分发单个事件
+    pub fn route(&self, event: DexEvent) {
+        let mint = event.primary_mint();
+        let pool = event.primary_pool();
+
+        let mut targets: Vec<Sender<DexEvent>> = Vec::new();
+        if let Some(mint) = mint {
+            collect_senders(&self.state.by_mint, &mint, &mut targets);
+        }
+        if let Some(pool) = pool {
+            collect_senders(&self.state.by_pool, &pool, &mut targets);
+        }
+
+        if targets.is_empty() {
+            let mut default = self.state.default.lock();
+            if let Some(tx) = default.as_ref() {
+                if self.send(tx, event) {
+                    *default = None;
+                }
+            }
+            return;
+        }
+
+        let (last, rest) = targets.split_last().unwrap();
+        let mut any_closed = false;
+        for tx in rest {
+            any_closed |= self.send(tx, event.clone());
+        }
+        any_closed |= self.send(last, event);
+
+        if any_closed {
+            if let Some(mint) = mint {
+                prune(&self.state.by_mint, &mint);
+            }
+            if let Some(pool) = pool {
+                prune(&self.state.by_pool, &pool);
+            }
+        }
+    }
+
+    /// 发送事件，返回接收端是否已关闭
+    #[inline]
+    fn send(&self, tx: &Sender<DexEvent>, event: DexEvent) -> bool {
+        match tx.try_send(event) {
+            Ok(()) => false,
+            Err(TrySendError::Full(_)) => {
+                self.state.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            Err(TrySendError::Closed(_)) => true,
+        }
+    }
+
+    /// 分发队列中当前所有事件，返回分发数量
+    pub fn drain(&self) -> usize {
+        let mut count = 0;
+        while let Some(event) = self.queue.pop() {
+            self.route(event);
+            count += 1;
+        }
+        count
+    }
+
+    /// 在 tokio 任务中持续消费队列，直到调用 [`EventRouter::stop`]
+    pub fn spawn(&self) -> tokio::task::JoinHandle<()> {
+        let router = self.clone();
+        tokio::spawn(async move {
+            let mut spin_count = 0u32;
+            while !router.state.stopped.load(Ordering::Relaxed) {
+                if router.drain() > 0 {
+                    spin_count = 0;
+                } else {
+                    // 与示例消费者相同的策略：先自旋，长时间无数据再 yield
+                    spin_count += 1;
+                    if spin_count < 1000 {
+                        std::hint::spin_loop();
+                    } else {
+                        tokio::task::yield_now().await;
+                        spin_count = 0;
+                    }
+                }
+            }
+        })
+    }
+
+    /// 停止 [`EventRouter::spawn`] 启动的任务
+    pub fn stop(&self) {
+        self.state.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// 因 channel 已满而丢弃的事件数
+    pub fn dropped(&self) -> u64 {
+        self.state.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 当前仍有接收端的 mint / pool 订阅数
+    pub fn subscription_count(&self) -> (usize, usize) {
+        let count = |routes: &Mutex<Routes>| {
+            routes.lock().values().filter(|v| v.iter().any(|s| !s.is_closed())).count()
+        };
+        (count(&self.state.by_mint), count(&self.state.by_pool))
+    }
+}
+
+#[inline]
+fn collect_senders(routes: &Mutex<Routes>, key: &Pubkey, out: &mut Vec<Sender<DexEvent>>) {
+    if let Some(senders) = routes.lock().get(key) {
+        out.extend(senders.iter().cloned());
+    }
+}
+
+fn prune(routes: &Mutex<Routes>, key: &Pubkey) {
+    let mut routes = routes.lock();
+    if let Some(senders) = routes.get_mut(key) {
+        senders.retain(|s| !s.is_closed());
+        if senders.is_empty() {
+            routes.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{EventMetadata, EventSource, PumpFunTradeEvent};
+    use solana_sdk::signature::Signature;
+
+    fn trade(mint: Pubkey, token_amount: u64) -> DexEvent {
+        DexEvent::PumpFunTrade(PumpFunTradeEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot: 1,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+            mint,
+            sol_amount: 0,
+            token_amount,
+            is_buy: true,
+            is_created_buy: false,
+            is_dev_create_token_trade: false,
+            user: Pubkey::default(),
+            timestamp: 0,
+            virtual_sol_reserves: 0,
+            virtual_token_reserves: 0,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+            fee_recipient: Pubkey::default(),
+            fee_basis_points: 0,
+            fee: 0,
+            creator: Pubkey::default(),
+            creator_fee_basis_points: 0,
+            creator_fee: 0,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 0,
+            sol_limit: 0,
+            price_per_token_lamports: 0.0,
+            slippage_bps: None,
+            price_impact_bps: None,
+        })
+    }
+
+    fn amounts(rx: &mut Receiver<DexEvent>) -> Vec<u64> {
+        let mut out = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                DexEvent::PumpFunTrade(e) => out.push(e.token_amount),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_fan_out_by_mint() {
+        let queue = DexEventQueue::new(64);
+        let router = EventRouter::new(queue.clone(), 16);
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        // 订阅前的事件不回放，进入默认 channel
+        let mut rx_default = router.default_channel();
+        queue.push(trade(mints[0], 100)).unwrap();
+        router.drain();
+
+        let mut rx_a = router.channel_for_mint(mints[0]);
+        let mut rx_b = router.channel_for_mint(mints[1]);
+        for i in 0..9u64 {
+            queue.push(trade(mints[(i % 3) as usize], i)).unwrap();
+        }
+        assert_eq!(router.drain(), 9);
+
+        assert_eq!(amounts(&mut rx_a), vec![0, 3, 6]);
+        assert_eq!(amounts(&mut rx_b), vec![1, 4, 7]);
+        assert_eq!(amounts(&mut rx_default), vec![100, 2, 5, 8]);
+
+        // drop 后自动注销
+        drop(rx_b);
+        router.route(trade(mints[1], 42));
+        assert_eq!(router.subscription_count(), (1, 0));
+        assert_eq!(router.dropped(), 0);
+    }
+}
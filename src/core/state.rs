@@ -0,0 +1,228 @@
+//! 池子状态跟踪
+//!
+//! [`PoolStateTracker`] 从事件流中提取各协议的最新储备 / 价格，报价时不必再调用 RPC：
+//! - PumpFun：按 mint 记录 bonding curve 储备（来自 `PumpFunTrade`）
+//! - Orca Whirlpool：按池子记录成交后的 sqrt 价格（来自 `OrcaWhirlpoolSwap`）
+//! - Raydium CLMM：按池子记录 sqrt 价格、流动性和 tick（来自 `RaydiumClmmSwap`）
+//!
+//! 每条记录带有 `(slot, tx_index, outer_index, inner_index)` 顺序键，只有更新的事件才会覆盖，
+//! 乱序到达的旧事件不会让状态回退。缺少储备 / 价格（仅来自指令、尚未补齐）的事件直接忽略。
+//! 长期不活跃的条目用 [`PoolStateTracker::evict_older_than`] 按 slot 清理。
+
+use crate::core::events::{DexEvent, EventMetadata};
+use parking_lot::RwLock;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// PumpFun bonding curve 状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveState {
+    pub slot: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+}
+
+/// Orca Whirlpool 池子状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhirlpoolState {
+    pub slot: u64,
+    /// 最近一次成交后的 sqrt 价格（Q64.64）
+    pub sqrt_price: u128,
+}
+
+/// Raydium CLMM 池子状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClmmPoolState {
+    pub slot: u64,
+    /// 最近一次成交后的 sqrt 价格（Q64.64）
+    pub sqrt_price_x64: u128,
+    pub liquidity: u128,
+    pub tick: i32,
+}
+
+type OrderKey = (u64, u64, u32, Option<u32>);
+
+fn order_key(metadata: &EventMetadata) -> OrderKey {
+    (metadata.slot, metadata.tx_index, metadata.outer_index, metadata.inner_index)
+}
+
+type StateMap<T> = RwLock<HashMap<Pubkey, (OrderKey, T)>>;
+
+/// 按池子 / mint 维护最新状态（线程安全，可在多个消费者间共享）
+#[derive(Default)]
+pub struct PoolStateTracker {
+    curves: StateMap<CurveState>,
+    whirlpools: StateMap<WhirlpoolState>,
+    clmm_pools: StateMap<ClmmPoolState>,
+    latest_slot: AtomicU64,
+}
+
+impl PoolStateTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 用一个事件更新状态，不携带池子状态的事件直接忽略
+    pub fn update(&self, event: &DexEvent) {
+        match event {
+            DexEvent::PumpFunTrade(e) if e.virtual_sol_reserves > 0 && e.virtual_token_reserves > 0 => {
+                let state = CurveState {
+                    slot: e.metadata.slot,
+                    virtual_sol_reserves: e.virtual_sol_reserves,
+                    virtual_token_reserves: e.virtual_token_reserves,
+                    real_sol_reserves: e.real_sol_reserves,
+                    real_token_reserves: e.real_token_reserves,
+                };
+                self.store(&self.curves, e.mint, &e.metadata, state);
+            }
+            DexEvent::OrcaWhirlpoolSwap(e) if e.post_sqrt_price > 0 => {
+                let state = WhirlpoolState { slot: e.metadata.slot, sqrt_price: e.post_sqrt_price };
+                self.store(&self.whirlpools, e.whirlpool, &e.metadata, state);
+            }
+            DexEvent::RaydiumClmmSwap(e) if e.sqrt_price_x64 > 0 => {
+                let state = ClmmPoolState {
+                    slot: e.metadata.slot,
+                    sqrt_price_x64: e.sqrt_price_x64,
+                    liquidity: e.liquidity,
+                    tick: e.tick,
+                };
+                self.store(&self.clmm_pools, e.pool_state, &e.metadata, state);
+            }
+            _ => {}
+        }
+    }
+
+    /// PumpFun 代币当前的 bonding curve 储备
+    pub fn get_pumpfun_curve(&self, mint: &Pubkey) -> Option<CurveState> {
+        self.curves.read().get(mint).map(|(_, state)| *state)
+    }
+
+    /// Orca Whirlpool 池子当前价格
+    pub fn get_whirlpool(&self, pool: &Pubkey) -> Option<WhirlpoolState> {
+        self.whirlpools.read().get(pool).map(|(_, state)| *state)
+    }
+
+    /// Raydium CLMM 池子当前状态
+    pub fn get_clmm_pool(&self, pool: &Pubkey) -> Option<ClmmPoolState> {
+        self.clmm_pools.read().get(pool).map(|(_, state)| *state)
+    }
+
+    /// 已见过的最高 slot
+    pub fn latest_slot(&self) -> u64 {
+        self.latest_slot.load(Ordering::Relaxed)
+    }
+
+    /// 清理 `max_age_slots` 个 slot 内没有更新的条目（相对已见过的最高 slot），返回清理的条目数
+    pub fn evict_older_than(&self, max_age_slots: u64) -> usize {
+        let min_slot = self.latest_slot().saturating_sub(max_age_slots);
+        evict(&self.curves, min_slot) + evict(&self.whirlpools, min_slot) + evict(&self.clmm_pools, min_slot)
+    }
+
+    /// 跟踪的条目总数
+    pub fn len(&self) -> usize {
+        self.curves.read().len() + self.whirlpools.read().len() + self.clmm_pools.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn store<T>(&self, map: &StateMap<T>, key: Pubkey, metadata: &EventMetadata, state: T) {
+        self.latest_slot.fetch_max(metadata.slot, Ordering::Relaxed);
+        let order = order_key(metadata);
+        let mut map = map.write();
+        match map.get_mut(&key) {
+            Some(entry) if entry.0 >= order => {}
+            Some(entry) => *entry = (order, state),
+            None => {
+                map.insert(key, (order, state));
+            }
+        }
+    }
+}
+
+fn evict<T>(map: &StateMap<T>, min_slot: u64) -> usize {
+    let mut map = map.write();
+    let before = map.len();
+    map.retain(|_, (order, _)| order.0 >= min_slot);
+    before - map.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{EventSource, PumpFunTradeEvent};
+    use solana_sdk::signature::Signature;
+
+    fn trade(mint: Pubkey, slot: u64, virtual_sol_reserves: u64, virtual_token_reserves: u64) -> DexEvent {
+        DexEvent::PumpFunTrade(PumpFunTradeEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+            mint,
+            sol_amount: 1,
+            token_amount: 1,
+            is_buy: true,
+            is_created_buy: false,
+            is_dev_create_token_trade: false,
+            user: Pubkey::default(),
+            timestamp: 0,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            real_sol_reserves: 0,
+            real_token_reserves: 0,
+            fee_recipient: Pubkey::default(),
+            fee_basis_points: 0,
+            fee: 0,
+            creator: Pubkey::default(),
+            creator_fee_basis_points: 0,
+            creator_fee: 0,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: 0,
+            sol_limit: 0,
+            price_per_token_lamports: 0.0,
+            slippage_bps: None,
+            price_impact_bps: None,
+        })
+    }
+
+    #[test]
+    fn test_curve_follows_newest_trade() {
+        let tracker = PoolStateTracker::new();
+        let mint = Pubkey::new_unique();
+
+        tracker.update(&trade(mint, 100, 30_000, 1_000_000));
+        tracker.update(&trade(mint, 101, 31_000, 968_000));
+        let curve = tracker.get_pumpfun_curve(&mint).unwrap();
+        assert_eq!((curve.slot, curve.virtual_sol_reserves, curve.virtual_token_reserves), (101, 31_000, 968_000));
+
+        // 迟到的旧 slot 事件不会让状态回退
+        tracker.update(&trade(mint, 99, 29_000, 1_030_000));
+        assert_eq!(tracker.get_pumpfun_curve(&mint), Some(curve));
+
+        // 按 slot 年龄清理
+        tracker.update(&trade(Pubkey::new_unique(), 200, 30_000, 1_000_000));
+        assert_eq!(tracker.evict_older_than(50), 1);
+        assert_eq!(tracker.get_pumpfun_curve(&mint), None);
+        assert_eq!(tracker.len(), 1);
+    }
+}
@@ -0,0 +1,192 @@
+//! 成交数量补全模块
+//!
+//! 部分 DEX 事件拿不到实际成交数量（例如 Raydium AMM V4 只解析到指令时 `amount_out` 为 0），
+//! 这里根据同一指令的内层 SPL Token 转账（[`TokenTransfer`]）推断：
+//! - 用户源账户 → 池子 vault 的转账为 `amount_in`
+//! - 池子 vault → 用户目标账户的转账为 `amount_out`
+//!
+//! Orca Whirlpool 领取手续费 / 奖励的指令不带金额，同样由转入用户账户的转账补齐。
+//!
+//! 只填充为 0 的字段，已有数量不会被覆盖。gRPC / RPC 交易解析时按事件所在指令的内层指令自动补全。
+
+use crate::core::events::*;
+use crate::instr::spl_token::{parse_transfer, TokenTransfer};
+use solana_sdk::pubkey::Pubkey;
+
+/// 从内层指令 `(program_id, data, accounts)` 中提取代币转账
+pub fn collect_transfers<'a>(
+    instructions: impl IntoIterator<Item = (&'a Pubkey, &'a [u8], &'a [Pubkey])>,
+) -> Vec<TokenTransfer> {
+    instructions
+        .into_iter()
+        .filter_map(|(program_id, data, accounts)| parse_transfer(program_id, data, accounts))
+        .collect()
+}
+
+/// 事件是否有可由转账补全的空字段（用于跳过不需要扫描内层指令的事件）
+pub fn needs_transfers(event: &DexEvent) -> bool {
+    match event {
+        DexEvent::RaydiumAmmV4Swap(e) => e.amount_in == 0 || e.amount_out == 0,
+        DexEvent::OrcaWhirlpoolCollectFees(e) => e.amount_a == 0 || e.amount_b == 0,
+        DexEvent::OrcaWhirlpoolCollectReward(e) => e.amount == 0 || e.reward_mint == Pubkey::default(),
+        _ => false,
+    }
+}
+
+/// 用转账补全兑换事件中为 0 的成交数量，有字段被填充时返回 true
+///
+/// 目前支持 Raydium AMM V4 Swap（需要事件中已有用户代币账户和池子 vault，即经过指令解析）。
+pub fn fill_swap_amounts_from_transfers(event: &mut DexEvent, transfers: &[TokenTransfer]) -> bool {
+    match event {
+        DexEvent::RaydiumAmmV4Swap(e) => fill_amm_v4_swap(e, transfers),
+        _ => false,
+    }
+}
+
+/// 用转账补全领取手续费 / 奖励事件中为 0 的金额，有字段被填充时返回 true
+///
+/// 支持 Orca Whirlpool CollectFees / CollectReward：vault 转入用户账户的数量即为领取金额，
+/// V1 CollectReward 缺少的 `reward_mint` 取自 TransferChecked 转账。
+pub fn fill_collect_amounts_from_transfers(event: &mut DexEvent, transfers: &[TokenTransfer]) -> bool {
+    let received = |account: Pubkey| transfers.iter().find(|t| account != Pubkey::default() && t.destination == account);
+    let mut filled = false;
+    match event {
+        DexEvent::OrcaWhirlpoolCollectFees(e) => {
+            for (amount, account) in [(&mut e.amount_a, e.token_owner_account_a), (&mut e.amount_b, e.token_owner_account_b)] {
+                if let (0, Some(t)) = (*amount, received(account)) {
+                    *amount = t.amount;
+                    filled = true;
+                }
+            }
+        }
+        DexEvent::OrcaWhirlpoolCollectReward(e) => {
+            if let Some(t) = received(e.reward_owner_account) {
+                if e.amount == 0 {
+                    e.amount = t.amount;
+                    filled = true;
+                }
+                if let (true, Some(mint)) = (e.reward_mint == Pubkey::default(), t.mint) {
+                    e.reward_mint = mint;
+                    filled = true;
+                }
+            }
+        }
+        _ => {}
+    }
+    filled
+}
+
+fn fill_amm_v4_swap(e: &mut RaydiumAmmV4SwapEvent, transfers: &[TokenTransfer]) -> bool {
+    let vaults = [e.pool_coin_token_account, e.pool_pc_token_account];
+    if vaults.contains(&Pubkey::default()) {
+        return false;
+    }
+    // 用户账户未知时只按 vault 匹配
+    let matches = |expected: Pubkey, account: &Pubkey| expected == Pubkey::default() || *account == expected;
+    let (user_source, user_destination) = (e.user_source_token_account, e.user_destination_token_account);
+
+    let amount_in = transfers
+        .iter()
+        .find(|t| vaults.contains(&t.destination) && matches(user_source, &t.source))
+        .map(|t| t.amount);
+    let amount_out = transfers
+        .iter()
+        .find(|t| vaults.contains(&t.source) && matches(user_destination, &t.destination))
+        .map(|t| t.amount);
+
+    let mut filled = false;
+    if let (0, Some(amount)) = (e.amount_in, amount_in) {
+        e.amount_in = amount;
+        filled = true;
+    }
+    if let (0, Some(amount)) = (e.amount_out, amount_out) {
+        e.amount_out = amount;
+        filled = true;
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instr::program_ids::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+    use crate::instr::spl_token::discriminators;
+    use solana_sdk::signature::Signature;
+
+    #[test]
+    fn test_amm_v4_amounts_from_transfers() {
+        // 合成数据：SwapBaseIn 指令（18 个账户）只带 amount_in，成交数量来自内层转账
+        let accounts: Vec<Pubkey> = (0..18).map(|_| Pubkey::new_unique()).collect();
+        let (coin_vault, pc_vault) = (accounts[5], accounts[6]);
+        let (user_source, user_destination, user) = (accounts[15], accounts[16], accounts[17]);
+        let mut ix = vec![crate::instr::raydium_amm::discriminators::SWAP_BASE_IN];
+        ix.extend_from_slice(&0u64.to_le_bytes());
+        ix.extend_from_slice(&900u64.to_le_bytes());
+        let mut event =
+            crate::instr::raydium_amm::parse_instruction(&ix, &accounts, Signature::default(), 1, 0, None).unwrap();
+
+        let transfer = |amount: u64| {
+            let mut data = vec![discriminators::TRANSFER];
+            data.extend_from_slice(&amount.to_le_bytes());
+            data
+        };
+        let (data_in, data_out) = (transfer(1_000), transfer(950));
+        let mut data_checked = vec![discriminators::TRANSFER_CHECKED];
+        data_checked.extend_from_slice(&7u64.to_le_bytes());
+        data_checked.push(6);
+        let mint = Pubkey::new_unique();
+        let in_accounts = [user_source, pc_vault, user];
+        let out_accounts = [coin_vault, user_destination, accounts[2]];
+        let unrelated = [Pubkey::new_unique(), mint, Pubkey::new_unique(), user];
+        let other = Pubkey::new_unique();
+        let inner: Vec<(&Pubkey, &[u8], &[Pubkey])> = vec![
+            (&TOKEN_PROGRAM_ID, &data_checked, &unrelated),
+            (&other, &data_in, &in_accounts),
+            (&TOKEN_PROGRAM_ID, &data_in, &in_accounts),
+            (&TOKEN_2022_PROGRAM_ID, &data_out, &out_accounts),
+        ];
+        let transfers = collect_transfers(inner);
+        assert_eq!(transfers.len(), 3);
+        assert_eq!((transfers[0].mint, transfers[0].decimals), (Some(mint), Some(6)));
+
+        assert!(fill_swap_amounts_from_transfers(&mut event, &transfers));
+        let DexEvent::RaydiumAmmV4Swap(e) = &event else { panic!("{:?}", event) };
+        assert_eq!((e.amount_in, e.amount_out, e.minimum_amount_out), (1_000, 950, 900));
+
+        // 已填充的数量不会被再次覆盖
+        assert!(!fill_swap_amounts_from_transfers(&mut event, &transfers));
+    }
+
+    #[test]
+    fn test_orca_position_collects_fees_and_reward() {
+        use crate::instr::orca_whirlpool::{self, discriminators as orca};
+        // 合成数据：同一头寸先 collectFees，再 collectRewardV2（reward_index = 1）
+        let accounts: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+        let (owner_a, vault_a, owner_b, vault_b) = (accounts[4], accounts[5], accounts[6], accounts[7]);
+        let mut fees =
+            orca_whirlpool::parse_instruction(&orca::COLLECT_FEES, &accounts, Signature::default(), 1, 0, None).unwrap();
+
+        let mut reward_accounts = accounts[..4].to_vec();
+        let (reward_owner, reward_mint, reward_vault) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        reward_accounts.extend([reward_owner, reward_mint, reward_vault, TOKEN_PROGRAM_ID, Pubkey::new_unique()]);
+        let reward_ix = [&orca::COLLECT_REWARD_V2[..], &[1, 0]].concat();
+        let mut reward =
+            orca_whirlpool::parse_instruction(&reward_ix, &reward_accounts, Signature::default(), 1, 0, None).unwrap();
+
+        let transfer = |amount: u64| [&[discriminators::TRANSFER][..], &amount.to_le_bytes()].concat();
+        let (data_a, data_b, data_reward) = (transfer(120), transfer(45), transfer(7_000));
+        let fee_transfers = collect_transfers(vec![
+            (&TOKEN_PROGRAM_ID, &data_a[..], &[vault_a, owner_a, accounts[0]][..]),
+            (&TOKEN_PROGRAM_ID, &data_b[..], &[vault_b, owner_b, accounts[0]][..]),
+        ]);
+        let reward_transfers =
+            collect_transfers(vec![(&TOKEN_PROGRAM_ID, &data_reward[..], &[reward_vault, reward_owner, accounts[0]][..])]);
+
+        assert!(fill_collect_amounts_from_transfers(&mut fees, &fee_transfers));
+        assert!(fill_collect_amounts_from_transfers(&mut reward, &reward_transfers));
+        let DexEvent::OrcaWhirlpoolCollectFees(f) = &fees else { panic!("{:?}", fees) };
+        assert_eq!((f.position, f.owner, f.amount_a, f.amount_b), (accounts[2], accounts[1], 120, 45));
+        let DexEvent::OrcaWhirlpoolCollectReward(r) = &reward else { panic!("{:?}", reward) };
+        assert_eq!((r.position, r.reward_index, r.amount, r.reward_mint), (accounts[2], 1, 7_000, reward_mint));
+    }
+}
@@ -3,10 +3,69 @@
 //! 提供完整的交易解析能力，支持指令和日志数据处理
 
 use crate::core::events::*;
+use crate::core::merger::{
+    instruction_order_key, sort_by_instruction_order, BonkCurveTracker, DevBuyTracker, MergeKey, MeteoraPoolFeeTracker,
+};
+use crate::core::registry::{ParseContext, ParserRegistry, RegistryReadGuard};
+use crate::logs::raydium_clmm::LiquidityChangeTracker;
+use crate::grpc::types::{EnabledProtocols, EventType, EventTypeFilter};
+use crate::logs::optimized_matcher::{
+    detect_log_type, detect_pumpfun_create, log_type_for_program, parse_log_with_type, InvokeTracker, LogType,
+};
+use serde::{Deserialize, Serialize};
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::VecDeque;
+
+/// 交易解析范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ParseMode {
+    /// 只扫描日志，完全跳过指令解析（日志已包含所需字段时延迟最低）
+    LogsOnly,
+    /// 只解析指令，跳过日志扫描
+    InstructionsOnly,
+    /// 日志和指令都解析，合并为一个事件（日志优先，指令补缺）
+    #[default]
+    Merged,
+}
+
+impl ParseMode {
+    /// 是否扫描日志
+    #[inline]
+    pub fn parses_logs(self) -> bool {
+        self != Self::InstructionsOnly
+    }
+
+    /// 是否解析指令
+    #[inline]
+    pub fn parses_instructions(self) -> bool {
+        self != Self::LogsOnly
+    }
+}
+
+/// [`parse_transaction_events_with_options`] 的选项
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub mode: ParseMode,
+    /// [`ParseMode::Merged`] 时按协议、按字段组选择哪一侧优先，默认日志优先
+    pub merge: crate::core::merger::MergePreferences,
+    /// 未启用协议的指令与日志在解码前即被跳过，默认全部启用
+    pub enabled_protocols: EnabledProtocols,
+    /// 已支持程序中无法识别的指令输出 [`DexEvent::UnknownSwap`]，默认关闭
+    pub emit_unknown: bool,
+    /// 指令事件保留指令原始数据（[`crate::core::events::RawInstruction`]），默认关闭
+    pub retain_raw: bool,
+}
+
+impl From<ParseMode> for ParseOptions {
+    fn from(mode: ParseMode) -> Self {
+        Self { mode, ..Default::default() }
+    }
+}
 
 /// 主要解析函数 - 解析完整交易并返回所有 DEX 事件
 ///
+/// 事件按交易内的指令顺序 `(outer_index, inner_index)` 输出，例如 create 一定在同笔交易的 dev buy 之前。
+///
 /// 参数：
 /// - instruction_data: 交易指令数据
 /// - accounts: 账户列表
@@ -25,44 +84,374 @@ pub fn parse_transaction_events(
     block_time: Option<i64>,
     program_id: &Pubkey,
 ) -> Vec<DexEvent> {
-    let mut instruction_events = Vec::new();
-    let mut log_events = Vec::new();
+    parse_transaction_events_with_options(
+        instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, ParseOptions::default(),
+    )
+}
 
-    // 1. 解析指令事件
-    if let Some(instr_event) = crate::instr::parse_instruction_unified(
-        instruction_data, accounts, signature, slot, tx_index, block_time, program_id
-    ) {
-        instruction_events.push(instr_event);
+/// [`parse_transaction_events`] 的可配置版本，`options.mode` 决定解析日志、指令还是两者
+#[allow(clippy::too_many_arguments)]
+pub fn parse_transaction_events_with_options(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &[String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    options: ParseOptions,
+) -> Vec<DexEvent> {
+    // 运行时注册的协议：整笔交易只取一次读锁
+    let registry = ParserRegistry::read();
+
+    // 1. 解析日志事件（按 invoke 栈确定所属程序并标注指令位置）
+    let log_events = if options.mode.parses_logs() {
+        parse_logs_at(logs, signature, slot, tx_index, block_time, None, options.enabled_protocols, registry.as_ref())
+    } else {
+        Vec::new()
+    };
+
+    // 2. 解析指令事件，位置取该程序第一次外层调用
+    let instruction_events: Vec<DexEvent> = if options.mode.parses_instructions() {
+        parse_instruction_at(
+            instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, options,
+            registry.as_ref(),
+        )
+        .into_iter()
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // 3. 合并指令和日志事件（默认日志优先，指令补缺），按指令顺序输出
+    crate::core::merger::merge_instruction_and_log_events_with_preferences(instruction_events, log_events, options.merge)
+}
+
+/// [`parse_transaction_events`] 的惰性版本，边遍历日志边产出事件，不构造中间 `Vec`
+///
+/// 输出与 [`parse_transaction_events`] 相同（合并规则和顺序一致），只按外层指令缓冲事件：
+/// - 每个外层指令的日志事件排序后即可产出
+/// - 指令事件到达输出位置但后面仍可能有可合并的日志事件时，之后的事件暂存到配对或日志结束
+/// - PumpFun 迁移与 PumpSwap 建池的关联只在同一外层指令内进行（迁移总是在同一指令内 CPI 建池）
+///
+/// 注册了运行时协议时，迭代器持有 [`ParserRegistry`] 读锁直到日志遍历完，期间注册 / 注销会等待。
+#[allow(clippy::too_many_arguments)]
+pub fn parse_transaction_events_iter<'a>(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &'a [String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+) -> TransactionEventsIter<'a> {
+    parse_transaction_events_iter_with_options(
+        instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, ParseOptions::default(),
+    )
+}
+
+/// 同 [`parse_transaction_events_iter`]，`options.mode` 决定解析日志、指令还是两者
+#[allow(clippy::too_many_arguments)]
+pub fn parse_transaction_events_iter_with_options<'a>(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &'a [String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    options: ParseOptions,
+) -> TransactionEventsIter<'a> {
+    let registry = ParserRegistry::read();
+    let instruction = options
+        .mode
+        .parses_instructions()
+        .then(|| {
+            parse_instruction_at(
+                instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, options,
+                registry.as_ref(),
+            )
+        })
+        .flatten()
+        .map(|event| {
+            let key = crate::core::merger::merge_key(&event);
+            (event, key)
+        });
+    // 只解析指令时不遍历日志
+    let scanned = if options.mode.parses_logs() { logs } else { &logs[..0] };
+    TransactionEventsIter {
+        logs: scanned.iter(),
+        walker: LogWalker::new(logs, signature, slot, tx_index, block_time, None, options.enabled_protocols),
+        instruction,
+        positioned: false,
+        group: Vec::new(),
+        held: Vec::new(),
+        trailing: Vec::new(),
+        ready: VecDeque::new(),
+        dev_buys: DevBuyTracker::default(),
+        bonk_curves: BonkCurveTracker::default(),
+        meteora_fees: MeteoraPoolFeeTracker::default(),
+        merge: options.merge,
+        registry,
+        done: false,
     }
+}
 
-    // 2. 解析日志事件
-    for log in logs {
-        if let Some(log_event) = crate::logs::parse_log_unified(log, signature, slot, block_time) {
-            log_events.push(log_event);
+/// [`parse_transaction_events_iter`] 返回的迭代器
+pub struct TransactionEventsIter<'a> {
+    logs: std::slice::Iter<'a, String>,
+    walker: LogWalker<'a>,
+    /// 尚未配对的指令事件及其合并键
+    instruction: Option<(DexEvent, Option<MergeKey>)>,
+    /// 指令事件已到达输出位置（之后的事件进入 `held`）
+    positioned: bool,
+    /// 当前外层指令的事件
+    group: Vec<DexEvent>,
+    /// 排在未配对指令事件之后、等待配对结果的事件
+    held: Vec<DexEvent>,
+    /// 没有元数据的事件，最后输出
+    trailing: Vec<DexEvent>,
+    ready: VecDeque<DexEvent>,
+    /// 按输出顺序标记 dev buy（create 总在 dev buy 之前输出）
+    dev_buys: DevBuyTracker,
+    /// 按输出顺序计算 Bonk 募集进度（PoolCreate 在同交易的成交之前输出）
+    bonk_curves: BonkCurveTracker,
+    /// 按输出顺序判断 Meteora Pools 费率模式（SetPoolFees 在同交易的成交之前输出）
+    meteora_fees: MeteoraPoolFeeTracker,
+    /// 配对事件的合并优先级（`ParseOptions.merge`）
+    merge: crate::core::merger::MergePreferences,
+    /// 运行时注册表读锁，整笔交易只取一次，日志遍历完即释放
+    registry: Option<RegistryReadGuard>,
+    done: bool,
+}
+
+impl TransactionEventsIter<'_> {
+    fn accept(&mut self, event: DexEvent) {
+        let matched = matches!(
+            &self.instruction,
+            Some((_, Some(key))) if crate::core::merger::merge_key(&event).as_ref() == Some(key)
+        );
+        let event = if matched {
+            let (instr_event, _) = self.instruction.take().unwrap();
+            // 配对后指令事件不再单独输出，暂存的事件可以放行
+            self.ready.extend(self.held.drain(..));
+            crate::core::merger::merge_pair(event, instr_event, self.merge)
+        } else {
+            event
+        };
+
+        if event.metadata().is_none() {
+            self.trailing.push(event);
+            return;
+        }
+        let outer = instruction_order_key(&event).0;
+        if self.group.first().is_some_and(|e| instruction_order_key(e).0 != outer) {
+            self.flush_group();
+        }
+        self.group.push(event);
+    }
+
+    fn flush_group(&mut self) {
+        let mut group = std::mem::take(&mut self.group);
+        crate::core::merger::link_pumpfun_migrations(&mut group);
+        sort_by_instruction_order(&mut group);
+        match &self.instruction {
+            Some((instr_event, _)) if !self.positioned => {
+                let at = instruction_order_key(instr_event);
+                let split = group.partition_point(|e| instruction_order_key(e) <= at);
+                if split < group.len() {
+                    self.positioned = true;
+                    self.held.extend(group.drain(split..));
+                }
+                self.ready.extend(group);
+            }
+            Some(_) => self.held.extend(group),
+            None => self.ready.extend(group),
+        }
+    }
+
+    fn finish(&mut self) {
+        self.registry = None;
+        self.flush_group();
+        if let Some((instr_event, _)) = self.instruction.take() {
+            self.ready.push_back(instr_event);
         }
+        self.ready.extend(self.held.drain(..));
+        self.ready.extend(self.trailing.drain(..));
+        self.done = true;
     }
+}
 
-    // 3. 合并指令和日志事件
-    instruction_events.extend(log_events);
-    instruction_events
+impl Iterator for TransactionEventsIter<'_> {
+    type Item = DexEvent;
+
+    fn next(&mut self) -> Option<DexEvent> {
+        loop {
+            if let Some(mut event) = self.ready.pop_front() {
+                self.dev_buys.observe(&mut event);
+                self.bonk_curves.observe(&mut event);
+                self.meteora_fees.observe(&mut event);
+                return Some(event);
+            }
+            if self.done {
+                return None;
+            }
+            match self.logs.next() {
+                Some(log) => {
+                    if let Some(event) = self.walker.step(log, self.registry.as_ref()) {
+                        self.accept(event);
+                    }
+                }
+                None => self.finish(),
+            }
+        }
+    }
+}
+
+/// 解析指令事件，位置取该程序第一次外层调用
+#[allow(clippy::too_many_arguments)]
+fn parse_instruction_at(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &[String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    options: ParseOptions,
+    registry: Option<&RegistryReadGuard>,
+) -> Option<DexEvent> {
+    let mut instr_event = crate::instr::parse_instruction_unified_with_recv(
+        instruction_data, accounts, signature, slot, tx_index, block_time, program_id, None, 0, options, registry,
+    )?;
+    let program_id = program_id.to_string();
+    let mut invokes = InvokeTracker::new();
+    let outer_index = logs.iter().find_map(|log| {
+        let top_level = invokes.observe(log) && invokes.position().1.is_none();
+        (top_level && invokes.current_program() == Some(program_id.as_str())).then(|| invokes.position().0)
+    });
+    if let (Some(outer_index), Some(metadata)) = (outer_index, instr_event.metadata_mut()) {
+        metadata.outer_index = outer_index;
+    }
+    Some(instr_event)
 }
 
 /// 简化版本 - 仅解析日志事件
+///
+/// 与 gRPC 流走同一条 [`parse_log_optimized`](crate::logs::optimized_matcher::parse_log_optimized)
+/// 路径，覆盖所有支持的协议。`Program data:` 行本身不含程序 ID，这里按 invoke 栈确定所属程序；
+/// 栈顶是 [`ParserRegistry`](crate::core::registry::ParserRegistry) 中注册的程序时交给注册表，
+/// 否则退回按日志内容检测。事件元数据中的 `outer_index` / `inner_index` 也由 invoke 栈确定。
+/// `event_type_filter` 为 `None` 时返回全部事件。
 pub fn parse_logs_only(
     logs: &[String],
     signature: Signature,
     slot: u64,
     block_time: Option<i64>,
+    event_type_filter: Option<&EventTypeFilter>,
+) -> Vec<DexEvent> {
+    let registry = ParserRegistry::read();
+    let mut events = parse_logs_at(
+        logs, signature, slot, 0, block_time, event_type_filter, EnabledProtocols::all(), registry.as_ref(),
+    );
+    crate::core::merger::mark_dev_buys(&mut events);
+    crate::core::merger::fill_bonk_curve_progress(&mut events);
+    crate::core::merger::fill_meteora_fee_modes(&mut events);
+    if let Some(filter) = event_type_filter.filter(|f| f.has_event_conditions()) {
+        events.retain(|event| filter.accepts_event(event));
+    }
+    events
+}
+
+#[allow(clippy::too_many_arguments)]
+fn parse_logs_at(
+    logs: &[String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    event_type_filter: Option<&EventTypeFilter>,
+    enabled: EnabledProtocols,
+    registry: Option<&RegistryReadGuard>,
 ) -> Vec<DexEvent> {
-    let mut events = Vec::new();
+    let mut walker = LogWalker::new(logs, signature, slot, tx_index, block_time, event_type_filter, enabled);
+    logs.iter().filter_map(|log| walker.step(log, registry)).collect()
+}
 
-    for log in logs {
-        if let Some(event) = crate::logs::parse_log_unified(log, signature, slot, block_time) {
-            events.push(event);
+/// 逐行解析日志：按 invoke 栈确定所属程序并标注指令位置
+struct LogWalker<'a> {
+    invokes: InvokeTracker<'a>,
+    ctx: ParseContext,
+    event_type_filter: Option<&'a EventTypeFilter>,
+    enabled: EnabledProtocols,
+    is_created_buy: bool,
+    clmm_liquidity: LiquidityChangeTracker,
+}
+
+impl<'a> LogWalker<'a> {
+    fn new(
+        logs: &[String],
+        signature: Signature,
+        slot: u64,
+        tx_index: u64,
+        block_time: Option<i64>,
+        event_type_filter: Option<&'a EventTypeFilter>,
+        enabled: EnabledProtocols,
+    ) -> Self {
+        let grpc_recv_us = crate::common::time::now_micros();
+        let is_created_buy = event_type_filter.is_none_or(|f| f.includes_pumpfun())
+            && detect_pumpfun_create(logs);
+        Self {
+            invokes: InvokeTracker::new(),
+            ctx: ParseContext { signature, slot, tx_index, block_time, grpc_recv_us },
+            event_type_filter,
+            enabled,
+            is_created_buy,
+            clmm_liquidity: LiquidityChangeTracker::default(),
         }
     }
 
-    events
+    /// 处理一行日志，返回其中的事件
+    fn step(&mut self, log: &'a str, registry: Option<&RegistryReadGuard>) -> Option<DexEvent> {
+        if self.invokes.observe(log) {
+            return None;
+        }
+
+        let program_id = self.invokes.current_program();
+        let mut log_type = program_id.map_or(LogType::Unknown, log_type_for_program);
+        if log_type == LogType::Unknown {
+            if let (Some(registry), Some(program_id)) = (registry, program_id) {
+                if registry.contains_str(program_id) {
+                    let mut event = registry.parse_log(program_id, log, &self.ctx)?;
+                    let included = match (self.event_type_filter, EventType::from_event(&event)) {
+                        (Some(filter), Some(event_type)) => filter.should_include(event_type),
+                        _ => true,
+                    };
+                    if !included {
+                        return None;
+                    }
+                    self.invokes.stamp(&mut event);
+                    return Some(event);
+                }
+            }
+            log_type = detect_log_type(log);
+        }
+        if log_type == LogType::RaydiumClmm && self.clmm_liquidity.observe(log) {
+            return None;
+        }
+        let ParseContext { signature, slot, tx_index, block_time, grpc_recv_us } = self.ctx;
+        let mut event = parse_log_with_type(
+            log_type, log, signature, slot, tx_index, block_time, grpc_recv_us,
+            self.event_type_filter, self.enabled, self.is_created_buy,
+        )?;
+        self.clmm_liquidity.apply(&mut event);
+        self.invokes.stamp(&mut event);
+        Some(event)
+    }
 }
 
 /// 事件监听器 trait - 用户可以实现此 trait 来处理解析出的事件
@@ -70,6 +459,13 @@ pub trait EventListener {
     fn on_dex_event(&self, event: &DexEvent);
 }
 
+/// 异步事件监听器 - 处理时需要 await（如写数据库）时使用，实现时可直接写 `async fn on_dex_event`
+///
+/// 订阅见 `DexEventSource::subscribe_with_async_listener`（`grpc` feature）。
+pub trait AsyncEventListener {
+    fn on_dex_event(&self, event: &DexEvent) -> impl std::future::Future<Output = ()> + Send;
+}
+
 /// 使用监听器解析交易的便捷函数
 pub fn parse_transaction_with_listener<T: EventListener>(
     instruction_data: &[u8],
@@ -95,6 +491,7 @@ pub fn parse_transaction_with_listener<T: EventListener>(
 ///
 /// 这个版本不做事件合并，确保每个事件都能立即被处理
 /// 适用于需要实时响应的场景
+#[allow(clippy::too_many_arguments)]
 pub fn parse_transaction_events_streaming<F>(
     instruction_data: &[u8],
     accounts: &[Pubkey],
@@ -104,21 +501,52 @@ pub fn parse_transaction_events_streaming<F>(
     tx_index: u64,
     block_time: Option<i64>,
     program_id: &Pubkey,
+    callback: F,
+) where
+    F: FnMut(DexEvent)
+{
+    parse_transaction_events_streaming_with_options(
+        instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, ParseOptions::default(),
+        callback,
+    )
+}
+
+/// 同 [`parse_transaction_events_streaming`]，`options.mode` 为 `LogsOnly` 时跳过指令解析，
+/// 为 `InstructionsOnly` 时跳过日志扫描
+#[allow(clippy::too_many_arguments)]
+pub fn parse_transaction_events_streaming_with_options<F>(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &[String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    options: ParseOptions,
     mut callback: F,
 ) where
     F: FnMut(DexEvent)
 {
+    let registry = ParserRegistry::read();
+
     // 1. 先解析指令事件（如果有） - 立即回调
-    if let Some(instr_event) = crate::instr::parse_instruction_unified(
-        instruction_data, accounts, signature, slot, tx_index, block_time, program_id
-    ) {
-        callback(instr_event);  // 立即回调指令事件
+    if options.mode.parses_instructions() {
+        if let Some(instr_event) = crate::instr::parse_instruction_unified_with_recv(
+            instruction_data, accounts, signature, slot, tx_index, block_time, program_id, None, 0, options,
+            registry.as_ref(),
+        ) {
+            callback(instr_event);  // 立即回调指令事件
+        }
     }
 
-    // 2. 逐个解析日志事件 - 每个事件立即回调
-    for log in logs {
-        if let Some(log_event) = crate::logs::parse_log_unified(log, signature, slot, block_time) {
-            callback(log_event);  // 立即回调日志事件，不等待其他日志
+    // 2. 逐个解析日志事件（按 invoke 栈确定所属程序） - 每个事件立即回调
+    if options.mode.parses_logs() {
+        let mut walker = LogWalker::new(logs, signature, slot, tx_index, block_time, None, options.enabled_protocols);
+        for log in logs {
+            if let Some(log_event) = walker.step(log, registry.as_ref()) {
+                callback(log_event);  // 立即回调日志事件，不等待其他日志
+            }
         }
     }
 
@@ -149,6 +577,7 @@ pub trait StreamingEventListener {
 }
 
 /// 使用流式监听器解析交易的便捷函数
+#[allow(clippy::too_many_arguments)]
 pub fn parse_transaction_with_streaming_listener<T: StreamingEventListener>(
     instruction_data: &[u8],
     accounts: &[Pubkey],
@@ -160,7 +589,27 @@ pub fn parse_transaction_with_streaming_listener<T: StreamingEventListener>(
     program_id: &Pubkey,
     listener: &mut T,
 ) {
-    parse_transaction_events_streaming(
+    parse_transaction_with_streaming_listener_with_options(
+        instruction_data, accounts, logs, signature, slot, tx_index, block_time, program_id, ParseOptions::default(),
+        listener,
+    );
+}
+
+/// 同 [`parse_transaction_with_streaming_listener`]，按 `options.mode` 选择解析范围
+#[allow(clippy::too_many_arguments)]
+pub fn parse_transaction_with_streaming_listener_with_options<T: StreamingEventListener>(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    logs: &[String],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    options: ParseOptions,
+    listener: &mut T,
+) {
+    parse_transaction_events_streaming_with_options(
         instruction_data,
         accounts,
         logs,
@@ -169,6 +618,330 @@ pub fn parse_transaction_with_streaming_listener<T: StreamingEventListener>(
         tx_index,
         block_time,
         program_id,
+        options,
         |event| listener.on_dex_event_streaming(event)
     );
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::optimized_matcher::program_id_strings::{METEORA_DAMM_V2_ID, ORCA_WHIRLPOOL_ID};
+    use base64::{engine::general_purpose, Engine as _};
+
+    /// 模拟一次程序调用的日志（事件数据按 IDL 布局合成）
+    fn invoke_logs(program_id: &str, discriminator: [u8; 8], body: &[u8]) -> Vec<String> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(body);
+        vec![
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+            "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program data: {}", general_purpose::STANDARD.encode(&data)),
+            format!("Program {} consumed 41234 of 200000 compute units", program_id),
+            format!("Program {} success", program_id),
+        ]
+    }
+
+    fn orca_traded_logs(whirlpool: Pubkey) -> Vec<String> {
+        let mut body = whirlpool.to_bytes().to_vec();
+        body.push(1); // a_to_b
+        body.extend_from_slice(&1_000u128.to_le_bytes());
+        body.extend_from_slice(&900u128.to_le_bytes());
+        for v in [5_000u64, 4_900, 0, 0, 15, 2] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        invoke_logs(ORCA_WHIRLPOOL_ID, crate::logs::orca_whirlpool::discriminators::TRADED_EVENT, &body)
+    }
+
+    #[test]
+    fn test_orca_log_produces_event() {
+        let whirlpool = Pubkey::new_unique();
+        let events = parse_logs_only(&orca_traded_logs(whirlpool), Signature::default(), 7, None, None);
+
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            DexEvent::OrcaWhirlpoolSwap(e) => {
+                assert_eq!(e.whirlpool, whirlpool);
+                assert_eq!(e.input_amount, 5_000);
+                assert_eq!(e.output_amount, 4_900);
+                assert_eq!(e.metadata.slot, 7);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_meteora_log_produces_event() {
+        let lb_pair = Pubkey::new_unique();
+        // EvtSwap：pool, trade_direction, has_referral, params, swap_result, actual_amount_in, current_timestamp
+        let mut body = lb_pair.to_bytes().to_vec();
+        body.extend_from_slice(&[0, 0]);
+        for v in [2_000u64, 1_900, 1_990] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        body.extend_from_slice(&(1u128 << 64).to_le_bytes());
+        for v in [6u64, 1, 0, 0, 2_000, 0] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        let logs = invoke_logs(METEORA_DAMM_V2_ID, crate::logs::meteora_damm::discriminators::SWAP_EVENT, &body);
+
+        let events = parse_logs_only(&logs, Signature::default(), 7, None, None);
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            DexEvent::MeteoraDammV2Swap(e) => {
+                assert_eq!(e.lb_pair, lb_pair);
+                assert_eq!(e.amount_in, 2_000);
+                assert_eq!(e.amount_out, 1_990);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_create_sorted_before_dev_buy() {
+        use crate::logs::optimized_matcher::program_id_strings::PUMPFUN_ID;
+        use std::str::FromStr;
+
+        let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mut trade = crate::logs::pumpfun::discriminators::TRADE_EVENT.to_vec();
+        trade.extend_from_slice(accounts[0].as_ref());
+        trade.extend_from_slice(&[0u8; 200]);
+        let logs = vec![
+            // 外层指令 0：create，日志中没有 CreateEvent，只能从指令得到
+            format!("Program {} invoke [1]", PUMPFUN_ID),
+            "Program log: Instruction: Create".to_string(),
+            format!("Program {} success", PUMPFUN_ID),
+            // 外层指令 1：dev buy
+            format!("Program {} invoke [1]", PUMPFUN_ID),
+            "Program log: Instruction: Buy".to_string(),
+            format!("Program data: {}", general_purpose::STANDARD.encode(&trade)),
+            format!("Program {} success", PUMPFUN_ID),
+        ];
+
+        let parse = |mode| {
+            parse_transaction_events_with_options(
+                &crate::instr::pumpfun::discriminators::CREATE,
+                &accounts,
+                &logs,
+                Signature::default(),
+                1,
+                0,
+                None,
+                &Pubkey::from_str(PUMPFUN_ID).unwrap(),
+                ParseOptions { mode, ..Default::default() },
+            )
+        };
+        let events = parse(ParseMode::Merged);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], DexEvent::PumpFunCreate(e) if e.metadata.instruction_order() == (0, None)));
+        assert!(matches!(&events[1], DexEvent::PumpFunTrade(e) if e.metadata.instruction_order() == (1, None)));
+
+        // 只看日志时没有 create（日志中没有 CreateEvent），只看指令时没有 dev buy 的成交事件
+        assert!(matches!(&parse(ParseMode::LogsOnly)[..], [DexEvent::PumpFunTrade(_)]));
+        assert!(matches!(&parse(ParseMode::InstructionsOnly)[..], [DexEvent::PumpFunCreate(_)]));
+
+        // 流式回调、监听器和迭代器同样遵守 mode
+        struct Collect(Vec<DexEvent>);
+        impl StreamingEventListener for Collect {
+            fn on_dex_event_streaming(&mut self, event: DexEvent) {
+                self.0.push(event);
+            }
+        }
+        let program_id = Pubkey::from_str(PUMPFUN_ID).unwrap();
+        let data = crate::instr::pumpfun::discriminators::CREATE;
+        for (mode, create, trade) in
+            [(ParseMode::LogsOnly, false, true), (ParseMode::InstructionsOnly, true, false), (ParseMode::Merged, true, true)]
+        {
+            let options = ParseOptions { mode, ..Default::default() };
+            let kinds = |events: &[DexEvent]| {
+                (
+                    events.iter().any(|e| matches!(e, DexEvent::PumpFunCreate(_))),
+                    events.iter().any(|e| matches!(e, DexEvent::PumpFunTrade(_))),
+                )
+            };
+            let mut streamed = Vec::new();
+            parse_transaction_events_streaming_with_options(
+                &data, &accounts, &logs, Signature::default(), 1, 0, None, &program_id, options, |e| streamed.push(e),
+            );
+            assert_eq!(kinds(&streamed), (create, trade), "{:?}", mode);
+
+            let mut listener = Collect(Vec::new());
+            parse_transaction_with_streaming_listener_with_options(
+                &data, &accounts, &logs, Signature::default(), 1, 0, None, &program_id, options, &mut listener,
+            );
+            assert_eq!(kinds(&listener.0), (create, trade), "{:?}", mode);
+
+            let iterated: Vec<DexEvent> = parse_transaction_events_iter_with_options(
+                &data, &accounts, &logs, Signature::default(), 1, 0, None, &program_id, options,
+            )
+            .collect();
+            assert_eq!(kinds(&iterated), (create, trade), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_dev_buy_flag_and_filter() {
+        use crate::logs::optimized_matcher::program_id_strings::PUMPFUN_ID;
+        use crate::logs::pumpfun::discriminators::{CREATE_EVENT, TRADE_EVENT};
+
+        // 合成数据：同一笔交易中 create、创建者买入、狙击者买入
+        let (mint, dev, sniper) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut create = Vec::new();
+        for text in ["Dev", "DEV", "https://example.com"] {
+            create.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            create.extend_from_slice(text.as_bytes());
+        }
+        for key in [mint, Pubkey::new_unique(), dev, dev] {
+            create.extend_from_slice(key.as_ref());
+        }
+        let buy = |user: Pubkey| {
+            let mut trade = mint.to_bytes().to_vec();
+            trade.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+            trade.extend_from_slice(&35_000_000u64.to_le_bytes());
+            trade.push(1);
+            trade.extend_from_slice(user.as_ref());
+            trade.extend_from_slice(&[0u8; 120]);
+            trade
+        };
+        let mut logs = invoke_logs(PUMPFUN_ID, CREATE_EVENT, &create);
+        logs.extend(invoke_logs(PUMPFUN_ID, TRADE_EVENT, &buy(dev)).into_iter().skip(2));
+        logs.extend(invoke_logs(PUMPFUN_ID, TRADE_EVENT, &buy(sniper)).into_iter().skip(2));
+
+        let trades = |events: &[DexEvent]| -> Vec<(Pubkey, bool)> {
+            events
+                .iter()
+                .filter_map(|e| match e {
+                    DexEvent::PumpFunTrade(t) => Some((t.user, t.is_dev_create_token_trade)),
+                    _ => None,
+                })
+                .collect()
+        };
+        let all = parse_logs_only(&logs, Signature::default(), 1, None, None);
+        assert!(matches!(all[0], DexEvent::PumpFunCreate(_)));
+        assert_eq!(trades(&all), vec![(dev, true), (sniper, false)]);
+
+        // 只要 dev buy：create 只用于识别，不输出
+        let filter = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]).dev_buys_only();
+        let dev_buys = parse_logs_only(&logs, Signature::default(), 1, None, Some(&filter));
+        assert_eq!(dev_buys.len(), 1);
+        assert_eq!(trades(&dev_buys), vec![(dev, true)]);
+    }
+
+    #[test]
+    fn test_event_type_filter_applies() {
+        let logs = orca_traded_logs(Pubkey::new_unique());
+
+        let only_pumpfun = EventTypeFilter::include_only(vec![EventType::PumpFunTrade, EventType::PumpFunCreate]);
+        assert!(parse_logs_only(&logs, Signature::default(), 7, None, Some(&only_pumpfun)).is_empty());
+
+        let only_orca = EventTypeFilter::include_only(vec![EventType::OrcaWhirlpoolSwap]);
+        assert_eq!(parse_logs_only(&logs, Signature::default(), 7, None, Some(&only_orca)).len(), 1);
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn test_merge_preferences_follow_client_config() {
+        use crate::core::merger::{MergeField, MergePreferences, MergeSource};
+        use crate::core::protocol::Protocol;
+        use crate::grpc::config::ClientConfig;
+        use crate::logs::optimized_matcher::program_id_strings::PUMPFUN_ID;
+        use std::str::FromStr;
+
+        // 日志中的代币数量为 5000，指令参数为 4000
+        let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mut body = accounts[2].to_bytes().to_vec();
+        body.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+        body.extend_from_slice(&5_000u64.to_le_bytes());
+        body.push(1);
+        body.extend_from_slice(accounts[6].as_ref());
+        body.extend_from_slice(&[0u8; 280]);
+        let logs = invoke_logs(PUMPFUN_ID, crate::logs::pumpfun::discriminators::TRADE_EVENT, &body);
+        let mut ix = crate::instr::pumpfun::discriminators::BUY.to_vec();
+        ix.extend_from_slice(&4_000u64.to_le_bytes());
+        ix.extend_from_slice(&1_100_000_000u64.to_le_bytes());
+        let program_id = Pubkey::from_str(PUMPFUN_ID).unwrap();
+
+        let token_amounts = |config: &ClientConfig| {
+            let options = config.parse_options();
+            let iterated: Vec<DexEvent> = parse_transaction_events_iter_with_options(
+                &ix, &accounts, &logs, Signature::default(), 1, 0, None, &program_id, options,
+            )
+            .collect();
+            let collected = parse_transaction_events_with_options(
+                &ix, &accounts, &logs, Signature::default(), 1, 0, None, &program_id, options,
+            );
+            let amount = |events: &[DexEvent]| match events {
+                [DexEvent::PumpFunTrade(e)] => e.token_amount,
+                other => panic!("unexpected events {:?}", other),
+            };
+            (amount(&iterated), amount(&collected))
+        };
+
+        assert_eq!(token_amounts(&ClientConfig::default()), (5_000, 5_000));
+        let preferences =
+            MergePreferences::default().with(Protocol::PumpFun, MergeField::Amounts, MergeSource::Instruction);
+        let config = ClientConfig::builder().merge_preferences(preferences).build().unwrap();
+        assert_eq!(config.parse_options().merge, preferences);
+        assert_eq!(token_amounts(&config), (4_000, 4_000));
+    }
+
+    #[test]
+    fn test_iter_matches_vec() {
+        use crate::logs::optimized_matcher::program_id_strings::PUMPFUN_ID;
+        use std::str::FromStr;
+
+        fn collect_both(data: &[u8], accounts: &[Pubkey], logs: &[String], program_id: &str) -> (String, String) {
+            let program_id = Pubkey::from_str(program_id).unwrap();
+            let normalize = |events: Vec<DexEvent>| {
+                let events: Vec<DexEvent> = events
+                    .into_iter()
+                    .map(|mut e| {
+                        let metadata = e.metadata_mut().unwrap();
+                        (metadata.grpc_recv_us, metadata.handle_us) = (0, 0);
+                        e
+                    })
+                    .collect();
+                format!("{:?}", events)
+            };
+            let vec = parse_transaction_events(data, accounts, logs, Signature::default(), 1, 0, None, &program_id);
+            let iter = parse_transaction_events_iter(data, accounts, logs, Signature::default(), 1, 0, None, &program_id);
+            (normalize(vec), normalize(iter.collect()))
+        }
+
+        // 合成日志：外层 1/2 为 Meteora 兑换，外层 3 为聚合器 CPI 调用 Orca；
+        // Orca 不在外层，指令事件位置落在 0，要等到外层 3 才能与日志事件配对
+        let whirlpool = Pubkey::new_unique();
+        let meteora = crate::logs::meteora_damm::discriminators::SWAP_EVENT;
+        let mut body = Pubkey::new_unique().to_bytes().to_vec();
+        body.extend_from_slice(&[0u8; 97]);
+        let mut logs = invoke_logs(METEORA_DAMM_V2_ID, meteora, &body);
+        logs.extend(invoke_logs(METEORA_DAMM_V2_ID, meteora, &body).into_iter().skip(2));
+        let orca = orca_traded_logs(whirlpool);
+        logs.push("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]".to_string());
+        logs.push(format!("Program {} invoke [2]", ORCA_WHIRLPOOL_ID));
+        logs.push(orca[4].clone());
+        logs.push(format!("Program {} success", ORCA_WHIRLPOOL_ID));
+        logs.push("Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success".to_string());
+
+        let accounts: Vec<Pubkey> = vec![Pubkey::new_unique(), whirlpool, Pubkey::new_unique()];
+        let mut ix = crate::instr::orca_whirlpool::discriminators::SWAP.to_vec();
+        ix.extend_from_slice(&[0u8; 34]);
+        let (vec, iter) = collect_both(&ix, &accounts, &logs, ORCA_WHIRLPOOL_ID);
+        assert_eq!(vec.matches("OrcaWhirlpoolSwap(").count(), 1);
+        assert_eq!(vec.matches("MeteoraDammV2Swap(").count(), 2);
+        assert_eq!(iter, vec);
+
+        // 未配对的指令事件排在后续外层指令的事件之前
+        let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mut logs = vec![
+            format!("Program {} invoke [1]", PUMPFUN_ID),
+            "Program log: Instruction: Create".to_string(),
+            format!("Program {} success", PUMPFUN_ID),
+        ];
+        logs.extend(orca_traded_logs(whirlpool));
+        let (vec, iter) = collect_both(&crate::instr::pumpfun::discriminators::CREATE, &accounts, &logs, PUMPFUN_ID);
+        assert!(vec.find("PumpFunCreate") < vec.find("OrcaWhirlpoolSwap"));
+        assert_eq!(iter, vec);
+    }
+}
@@ -0,0 +1,280 @@
+//! 快照测试夹具
+//!
+//! 用于把解析结果与提交到仓库的 golden 快照逐字段比较（`tests/snapshots.rs`，`cargo test --features fixtures`）：
+//! - 夹具为 JSON 文件，每个文件是一组 [`RecordedTransaction`]，按文件名排序加载
+//! - 按 discriminator 构造、不是从链上抓取的交易必须标记 `"synthetic": true`
+//! - 每笔交易走 [`parse_transaction_events`]（日志 + 外层指令，合并后按指令顺序输出）
+//! - 链上抓取的交易保存为 `getTransaction` 的原始响应（[`RpcCapture`]，`rpc` feature），
+//!   走与订阅相同的完整解析路径（含内层指令和 ALT 地址）
+//! - 输出规范化后再比较：`grpc_recv_us` / `handle_us` 清零，`Pubkey` / `Signature` 的字节数组转为 base58 字符串
+//!
+//! 快照格式为缩进 JSON，便于在 diff 中审阅字段变化。
+
+use crate::core::events::DexEvent;
+use crate::parse_transaction_events;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::path::{Path, PathBuf};
+
+/// 一笔夹具交易（链上抓取或合成）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    pub name: String,
+    /// 合成交易（按布局构造，不是链上记录），字段取值不代表真实链上数据
+    #[serde(default)]
+    pub synthetic: bool,
+    /// base58 签名
+    pub signature: String,
+    pub slot: u64,
+    #[serde(default)]
+    pub tx_index: u64,
+    #[serde(default)]
+    pub block_time: Option<i64>,
+    /// 外层指令的程序 ID（base58）
+    pub program_id: String,
+    /// 外层指令数据（hex）
+    #[serde(default)]
+    pub instruction_data: String,
+    /// 外层指令的账户（base58）
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    #[serde(default)]
+    pub logs: Vec<String>,
+}
+
+impl RecordedTransaction {
+    /// 解析交易，返回规范化之前的事件
+    pub fn parse(&self) -> Result<Vec<DexEvent>, String> {
+        let signature: Signature = self.signature.parse().map_err(|e| format!("{}: signature: {}", self.name, e))?;
+        let program_id: Pubkey = self.program_id.parse().map_err(|e| format!("{}: program_id: {}", self.name, e))?;
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|a| a.parse::<Pubkey>().map_err(|e| format!("{}: account {}: {}", self.name, a, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let data = hex::decode(&self.instruction_data).map_err(|e| format!("{}: instruction_data: {}", self.name, e))?;
+        Ok(parse_transaction_events(
+            &data,
+            &accounts,
+            &self.logs,
+            signature,
+            self.slot,
+            self.tx_index,
+            self.block_time,
+            &program_id,
+        ))
+    }
+}
+
+/// 链上抓取的交易：`getTransaction`（base64 编码）的原始响应
+#[cfg(feature = "rpc")]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcCapture {
+    pub name: String,
+    pub transaction: solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta,
+}
+
+#[cfg(feature = "rpc")]
+impl RpcCapture {
+    /// 按订阅路径解析（RPC 不返回交易在 slot 中的索引，`tx_index` 为 0）
+    pub fn parse(&self) -> Result<Vec<DexEvent>, String> {
+        crate::grpc::rpc::parse_rpc_transaction(&self.transaction).map_err(|e| format!("{}: {}", self.name, e))
+    }
+}
+
+/// 加载目录下的全部夹具文件（`*.json`），返回 (文件名去掉扩展名, 交易) 并按文件名排序
+///
+/// 目录不存在时返回空列表。
+pub fn load_dir<T: DeserializeOwned>(dir: &Path) -> Result<Vec<(String, Vec<T>)>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let transactions = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            Ok((stem, transactions))
+        })
+        .collect()
+}
+
+/// 规范化事件：去掉随运行变化的字段，地址和签名转为 base58，超出 u64 的整数（u128 价格等）转为字符串
+pub fn normalize(events: &[DexEvent]) -> Value {
+    // serde_json::Value 放不下 u128，先输出文本再把超宽整数加上引号
+    let text = serde_json::to_string(events).expect("DexEvent serializes to JSON");
+    let mut value: Value = serde_json::from_str(&quote_wide_integers(&text)).expect("normalized JSON parses");
+    normalize_value(&mut value);
+    value
+}
+
+/// 给字符串之外、超出 u64 / i64 范围的整数加上引号
+fn quote_wide_integers(json: &str) -> String {
+    let bytes = json.as_bytes();
+    let mut out = Vec::with_capacity(json.len());
+    let (mut i, mut in_string) = (0, false);
+    while i < bytes.len() {
+        let c = bytes[i];
+        if in_string {
+            if c == b'\\' {
+                out.extend_from_slice(&bytes[i..i + 2]);
+                i += 2;
+                continue;
+            }
+            in_string = c != b'"';
+        } else if c == b'"' {
+            in_string = true;
+        } else if c == b'-' || c.is_ascii_digit() {
+            let len = bytes[i + 1..].iter().take_while(|b| b.is_ascii_digit() || b"+-.eE".contains(b)).count();
+            let end = i + 1 + len;
+            let token = &json[i..end];
+            let is_integer = !token.contains(['.', 'e', 'E']);
+            if is_integer && token.parse::<i64>().is_err() && token.parse::<u64>().is_err() {
+                out.extend_from_slice(format!("\"{}\"", token).as_bytes());
+            } else {
+                out.extend_from_slice(token.as_bytes());
+            }
+            i = end;
+            continue;
+        }
+        out.push(c);
+        i += 1;
+    }
+    String::from_utf8(out).expect("input is valid UTF-8")
+}
+
+fn normalize_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if key == "grpc_recv_us" || key == "handle_us" {
+                    *field = Value::from(0);
+                } else {
+                    normalize_value(field);
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(bytes) = as_bytes::<32>(items) {
+                *value = Value::String(Pubkey::new_from_array(bytes).to_string());
+            } else if let Some(bytes) = as_bytes::<64>(items) {
+                *value = Value::String(Signature::from(bytes).to_string());
+            } else {
+                items.iter_mut().for_each(normalize_value);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn as_bytes<const N: usize>(items: &[Value]) -> Option<[u8; N]> {
+    if items.len() != N {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (byte, item) in bytes.iter_mut().zip(items) {
+        *byte = u8::try_from(item.as_u64()?).ok()?;
+    }
+    Some(bytes)
+}
+
+/// 一组交易的快照文本：`[{ "name": ..., "events": [...] }, ...]`
+pub fn snapshot(transactions: &[RecordedTransaction]) -> Result<String, String> {
+    render(transactions.iter().map(|tx| (tx.name.as_str(), tx.parse())))
+}
+
+/// 一组链上抓取交易的快照文本，格式同 [`snapshot`]
+#[cfg(feature = "rpc")]
+pub fn snapshot_captures(captures: &[RpcCapture]) -> Result<String, String> {
+    render(captures.iter().map(|capture| (capture.name.as_str(), capture.parse())))
+}
+
+fn render<'a>(parsed: impl Iterator<Item = (&'a str, Result<Vec<DexEvent>, String>)>) -> Result<String, String> {
+    let entries = parsed
+        .map(|(name, events)| Ok(serde_json::json!({ "name": name, "events": normalize(&events?) })))
+        .collect::<Result<Vec<Value>, String>>()?;
+    let mut text = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+    text.push('\n');
+    Ok(text)
+}
+
+/// 与 golden 文件比较；`update` 为 true 时改为写入 golden
+///
+/// 不一致时返回第一处不同的行号及两侧内容。
+pub fn check_golden(path: &Path, actual: &str, update: bool) -> Result<(), String> {
+    if update {
+        return std::fs::write(path, actual).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    let expected = std::fs::read_to_string(path).map_err(|e| format!("{}: {} (regenerate the goldens)", path.display(), e))?;
+    if expected == actual {
+        return Ok(());
+    }
+    let mismatch = expected
+        .lines()
+        .zip(actual.lines())
+        .enumerate()
+        .find(|(_, (e, a))| e != a)
+        .map(|(i, (e, a))| format!("line {}:\n  golden: {}\n  actual: {}", i + 1, e.trim(), a.trim()))
+        .unwrap_or_else(|| format!("golden has {} lines, actual {}", expected.lines().count(), actual.lines().count()));
+    Err(format!("{} differs at {}", path.display(), mismatch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{EventMetadata, EventSource, PumpSwapBuyEvent};
+
+    #[test]
+    fn test_normalize_redacts_recv_time_and_encodes_pubkeys() {
+        let mint = Pubkey::new_unique();
+        let event = DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot: 1,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 123_456,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+            pool_id: Pubkey::default(),
+            user: Pubkey::default(),
+            token_mint: mint,
+            sol_amount: 1,
+            token_amount: 2,
+            price: 0,
+            slippage: 0,
+            pool_base_token_reserves: 0,
+            pool_quote_token_reserves: 0,
+            lp_fee: 0,
+            protocol_fee: 0,
+            coin_creator: Pubkey::default(),
+            coin_creator_fee: None,
+        });
+        let value = normalize(&[event]);
+        let buy = &value[0]["PumpSwapBuy"];
+        assert_eq!(buy["metadata"]["grpc_recv_us"], 0);
+        assert_eq!(buy["token_mint"], mint.to_string());
+        assert_eq!((buy["sol_amount"].as_u64(), buy["token_amount"].as_u64()), (Some(1), Some(2)));
+
+        let wide = u128::MAX.to_string();
+        let json = format!(r#"{{"a":{},"b":-3,"c":1.25e-7,"d":"{}"}}"#, wide, wide);
+        assert_eq!(quote_wide_integers(&json), format!(r#"{{"a":"{}","b":-3,"c":1.25e-7,"d":"{}"}}"#, wide, wide));
+    }
+}
@@ -0,0 +1,78 @@
+//! x-token 认证
+//!
+//! [`AuthToken::Static`] 与直接传入 `Option<String>` 相同，整个订阅期间使用同一个令牌。
+//! [`AuthToken::Provider`] 适用于短期令牌（如 JWT）：每次建立连接（包括断线重连）前调用一次，
+//! 用返回的令牌连接；提供方返回错误时订阅以 [`GrpcClientError::AuthError`] 结束，调用方可据此刷新凭据。
+
+use super::error::GrpcClientError;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// 令牌提供方返回的 future
+pub type AuthTokenFuture = Pin<Box<dyn Future<Output = Result<String, AuthError>> + Send>>;
+
+/// 令牌提供方获取令牌失败
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct AuthError(pub String);
+
+impl AuthError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+/// 连接使用的 x-token
+#[derive(Clone)]
+pub enum AuthToken {
+    /// 固定令牌
+    Static(String),
+    /// 每次连接前调用，返回本次连接使用的令牌
+    Provider(Arc<dyn Fn() -> AuthTokenFuture + Send + Sync>),
+}
+
+impl AuthToken {
+    /// 由异步闭包构造提供方：`AuthToken::provider(|| async { Ok(fetch_jwt().await?) })`
+    pub fn provider<F, Fut>(f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, AuthError>> + Send + 'static,
+    {
+        AuthToken::Provider(Arc::new(move || Box::pin(f())))
+    }
+
+    /// 取得本次连接使用的令牌并校验格式
+    pub(crate) async fn resolve(&self) -> Result<String, GrpcClientError> {
+        let token = match self {
+            AuthToken::Static(token) => token.clone(),
+            AuthToken::Provider(provider) => provider().await.map_err(GrpcClientError::AuthError)?,
+        };
+        validate(&token)?;
+        Ok(token)
+    }
+}
+
+/// 令牌必须是合法的 ASCII header 值
+pub(crate) fn validate(token: &str) -> Result<(), GrpcClientError> {
+    tonic::metadata::AsciiMetadataValue::try_from(token)
+        .map(|_| ())
+        .map_err(|e| GrpcClientError::InvalidToken(e.to_string()))
+}
+
+impl From<String> for AuthToken {
+    fn from(token: String) -> Self {
+        AuthToken::Static(token)
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 不输出令牌内容
+        match self {
+            AuthToken::Static(_) => f.write_str("AuthToken::Static(..)"),
+            AuthToken::Provider(_) => f.write_str("AuthToken::Provider(..)"),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! 区块订阅
+//!
+//! 订阅完整区块（blocks 过滤器，`include_transactions`）而不是逐笔交易：一条消息即可拿到
+//! 该 slot 内的全部交易和真实的 `block_time`。区块内的交易按 `transaction_index` 顺序逐笔走
+//! 与交易订阅相同的解析路径，事件直接追加到 [`ParsedBlock::events`]，不会为每笔交易
+//! 构造交易更新或单独的事件列表。
+//!
+//! 事件同样经过订阅级的 [`TrackingSink`]：更新池子状态、分配序号，开启去重时跨区块去掉已输出过的事件。
+//! 配置了 [`TxSizeGuard`] 时每笔交易在解析前同样经过大小检查。
+
+use super::broadcast::{EventSink, TrackingSink};
+use super::client::YellowstoneGrpc;
+use super::guard::{self, TxSizeGuard, TxSizeStats};
+use super::prefilter::TrackedPrograms;
+use super::types::EventTypeFilter;
+use crate::core::events::DexEvent;
+use crate::core::unified_parser::ParseOptions;
+use parking_lot::Mutex;
+use yellowstone_grpc_proto::prelude::{SubscribeUpdateBlock, SubscribeUpdateTransactionInfo};
+
+/// 解析后的区块
+#[derive(Debug, Clone)]
+pub struct ParsedBlock {
+    pub slot: u64,
+    pub blockhash: String,
+    /// 出块时间（秒），服务商未提供时为 None
+    pub block_time: Option<i64>,
+    pub parent_slot: u64,
+    /// 区块内全部事件，按交易索引、指令顺序排列
+    pub events: Vec<DexEvent>,
+}
+
+/// 收集当前区块的事件，每个区块解析完后取走
+#[derive(Default)]
+pub(crate) struct BlockSink {
+    events: Mutex<Vec<DexEvent>>,
+}
+
+impl EventSink for BlockSink {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        self.events.lock().push(event);
+    }
+}
+
+/// 解析区块内的全部交易
+///
+/// 交易通常已按索引排列，只有乱序时才额外排序一份引用列表。
+/// `size_guard` 不为 None 时先按 [`TxSizeGuard`] 跳过或截断超限交易。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn parse_block(
+    block: &mut SubscribeUpdateBlock,
+    size_guard: Option<(&TxSizeGuard, &TxSizeStats)>,
+    grpc_recv_us: i64,
+    sink: &TrackingSink<BlockSink>,
+    event_type_filter: Option<&EventTypeFilter>,
+    tracked: Option<&TrackedPrograms>,
+    first_event_only: bool,
+    options: ParseOptions,
+    cross_venue: bool,
+) -> ParsedBlock {
+    if let Some((size_guard, stats)) = size_guard {
+        block.transactions.retain_mut(|info| guard::admit(size_guard, info, stats, sink));
+    }
+    let block_time = block.block_time.as_ref().map(|t| t.timestamp);
+    let parse = |transaction: &SubscribeUpdateTransactionInfo| {
+        YellowstoneGrpc::parse_transaction_info(
+            transaction,
+            block.slot,
+            block_time,
+            grpc_recv_us,
+            sink,
+            event_type_filter,
+            tracked,
+            first_event_only,
+            options,
+            cross_venue,
+        );
+    };
+    if block.transactions.is_sorted_by_key(|tx| tx.index) {
+        block.transactions.iter().for_each(parse);
+    } else {
+        let mut ordered: Vec<_> = block.transactions.iter().collect();
+        ordered.sort_by_key(|tx| tx.index);
+        ordered.into_iter().for_each(parse);
+    }
+
+    ParsedBlock {
+        slot: block.slot,
+        blockhash: block.blockhash.clone(),
+        block_time,
+        parent_slot: block.parent_slot,
+        events: std::mem::take(&mut *sink.inner().events.lock()),
+    }
+}
@@ -0,0 +1,284 @@
+//! 多消费者广播队列
+//!
+//! 每个事件只分配一次（`Arc<DexEvent>`），再推入每个消费者各自的无锁队列：
+//! - 消费者之间互不影响：某个消费者处理慢导致其队列已满时，只丢弃该消费者的新事件，
+//!   并计入它自己的 [`BroadcastConsumer::dropped`]，其它消费者照常接收
+//! - 事件序号（`metadata.sequence`）由 [`TrackingSink`] 在过滤之后按订阅分配，所有消费者看到的序号相同
+//! - 单消费者 API（[`super::DexEventQueue`]）仍然交付 owned `DexEvent`
+
+use super::dedup::{DedupConfig, EventDeduplicator};
+use super::queue::{DexEventQueue, DroppedRanges};
+use crate::core::state::PoolStateTracker;
+use crate::DexEvent;
+use crossbeam_queue::ArrayQueue;
+use parking_lot::Mutex;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 事件输出端（单队列或广播）
+pub(crate) trait EventSink: Send + Sync + 'static {
+    fn push_event(&self, event: DexEvent);
+
+    /// 推入同一笔交易解析出的全部事件（按指令顺序）
+    #[inline]
+    fn push_transaction(&self, events: Vec<DexEvent>) {
+        for event in events {
+            self.push_event(event);
+        }
+    }
+}
+
+impl EventSink for ArrayQueue<DexEvent> {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        let _ = self.push(event);
+    }
+}
+
+impl EventSink for DexEventQueue {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        if let Err(event) = self.push(event) {
+            self.dropped.record(&event);
+        }
+    }
+}
+
+/// 内联回调：在 gRPC 接收任务内同步调用，不经过队列
+///
+/// 回调只要求 `Send`：包在锁里调用，解析线程池并发推入时也不会同时进入回调（接收任务内调用时锁无竞争）。
+pub(crate) struct InlineSink<F>(Mutex<F>);
+
+impl<F> InlineSink<F> {
+    pub(crate) fn new(callback: F) -> Self {
+        Self(Mutex::new(callback))
+    }
+}
+
+impl<F: Fn(DexEvent) + Send + 'static> EventSink for InlineSink<F> {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        (self.0.lock())(event);
+    }
+
+    #[inline]
+    fn push_transaction(&self, events: Vec<DexEvent>) {
+        let callback = self.0.lock();
+        for event in events {
+            callback(event);
+        }
+    }
+}
+
+/// 转发事件前先更新池子状态并分配订阅内序号
+///
+/// 多个解析线程并发推入时序号仍唯一递增，但入队顺序可能与序号略有交错。
+/// 开启去重（[`DedupConfig`]）时，交易事件在此之前先去掉已输出过的，重复事件不占用序号。
+pub(crate) struct TrackingSink<S> {
+    inner: Arc<S>,
+    state: Option<Arc<PoolStateTracker>>,
+    next_sequence: AtomicU64,
+    dedup: Option<EventDeduplicator>,
+}
+
+impl<S> TrackingSink<S> {
+    pub(crate) fn new(inner: Arc<S>, state: Option<Arc<PoolStateTracker>>) -> Self {
+        Self { inner, state, next_sequence: AtomicU64::new(1), dedup: None }
+    }
+
+    pub(crate) fn with_dedup(mut self, config: DedupConfig) -> Self {
+        self.dedup = EventDeduplicator::new(config);
+        self
+    }
+
+    /// 被包装的输出端
+    pub(crate) fn inner(&self) -> &S {
+        &self.inner
+    }
+}
+
+impl<S: EventSink> EventSink for TrackingSink<S> {
+    #[inline]
+    fn push_event(&self, mut event: DexEvent) {
+        if let Some(state) = &self.state {
+            state.update(&event);
+        }
+        if let Some(metadata) = event.metadata_mut() {
+            metadata.sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        }
+        self.inner.push_event(event);
+    }
+
+    #[inline]
+    fn push_transaction(&self, mut events: Vec<DexEvent>) {
+        if let Some(dedup) = &self.dedup {
+            dedup.retain_new(&mut events);
+        }
+        for event in events {
+            self.push_event(event);
+        }
+    }
+}
+
+/// 单个消费者的队列
+#[derive(Clone)]
+pub struct BroadcastConsumer {
+    inner: Arc<ArrayQueue<Arc<DexEvent>>>,
+    dropped: Arc<DroppedRanges>,
+}
+
+impl BroadcastConsumer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(ArrayQueue::new(capacity)),
+            dropped: Arc::new(DroppedRanges::default()),
+        }
+    }
+
+    #[inline]
+    pub fn pop(&self) -> Option<Arc<DexEvent>> {
+        self.inner.pop()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// 因队列已满而丢弃的事件数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.count()
+    }
+
+    /// 该消费者丢弃事件的序号区间
+    pub fn dropped_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        self.dropped.ranges()
+    }
+
+    /// 底层无锁队列
+    pub fn inner(&self) -> &Arc<ArrayQueue<Arc<DexEvent>>> {
+        &self.inner
+    }
+}
+
+/// 广播发布端
+pub struct BroadcastQueues {
+    consumers: Vec<BroadcastConsumer>,
+}
+
+impl BroadcastQueues {
+    /// 创建 `num_consumers` 个消费者队列，每个容量为 `capacity`
+    pub fn new(num_consumers: usize, capacity: usize) -> Self {
+        Self {
+            consumers: (0..num_consumers.max(1)).map(|_| BroadcastConsumer::new(capacity)).collect(),
+        }
+    }
+
+    pub fn consumers(&self) -> &[BroadcastConsumer] {
+        &self.consumers
+    }
+
+    /// 发布事件：分配一次，所有消费者共享
+    #[inline]
+    pub fn publish(&self, event: DexEvent) {
+        let event = Arc::new(event);
+        for consumer in &self.consumers {
+            if consumer.inner.push(Arc::clone(&event)).is_err() {
+                consumer.dropped.record(&event);
+            }
+        }
+    }
+}
+
+impl EventSink for BroadcastQueues {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        self.publish(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lagging_consumer_does_not_affect_others() {
+        let queues = BroadcastQueues::new(2, 4);
+        let fast = queues.consumers()[0].clone();
+        let slow = queues.consumers()[1].clone();
+
+        for i in 0..10 {
+            queues.publish(DexEvent::Error(i.to_string()));
+            // 快消费者每次都及时取走
+            let event = fast.pop().unwrap();
+            assert!(matches!(&*event, DexEvent::Error(s) if *s == i.to_string()));
+        }
+
+        assert_eq!(fast.dropped(), 0);
+        assert_eq!(slow.len(), 4);
+        assert_eq!(slow.dropped(), 6);
+        // 慢消费者保留最早的 4 个事件，且与快消费者共享同一份分配
+        let first = slow.pop().unwrap();
+        assert!(matches!(&*first, DexEvent::Error(s) if s == "0"));
+        assert_eq!(Arc::strong_count(&first), 1);
+    }
+
+    #[test]
+    fn test_event_allocated_once() {
+        let queues = BroadcastQueues::new(3, 4);
+        queues.publish(DexEvent::Error("x".into()));
+        let events: Vec<_> = queues.consumers().iter().map(|c| c.pop().unwrap()).collect();
+        assert!(Arc::ptr_eq(&events[0], &events[1]) && Arc::ptr_eq(&events[1], &events[2]));
+    }
+
+    #[test]
+    fn test_sequence_gap_matches_dropped_ranges() {
+        use crate::core::events::{BlockMetaEvent, EventMetadata, EventSource};
+
+        let event = || {
+            DexEvent::BlockMeta(BlockMetaEvent {
+                metadata: EventMetadata {
+                    signature: Default::default(),
+                    slot: 1,
+                    tx_index: 0,
+                    block_time_us: 0,
+                    grpc_recv_us: 0,
+                    handle_us: 0,
+                    event_source: EventSource::Log,
+                    outer_index: 0,
+                    inner_index: None,
+                    protocol_version: None,
+                    sequence: 0,
+                    raw: None,
+                    priority_fee_micro_lamports: None,
+                    compute_unit_limit: None,
+                },
+            })
+        };
+        let queue = DexEventQueue::new(2);
+        let sink = TrackingSink::new(Arc::new(queue.clone()), None);
+
+        // 序号 1、2 入队，3..=5 因队列已满被丢弃；取走后 6、7 入队
+        for _ in 0..5 {
+            sink.push_event(event());
+        }
+        let mut received: Vec<u64> = std::iter::from_fn(|| queue.pop()).map(|e| e.metadata().unwrap().sequence).collect();
+        for _ in 0..2 {
+            sink.push_event(event());
+        }
+        received.extend(std::iter::from_fn(|| queue.pop()).map(|e| e.metadata().unwrap().sequence));
+
+        assert_eq!(received, vec![1, 2, 6, 7]);
+        assert_eq!(queue.dropped(), 3);
+        assert_eq!(queue.dropped_ranges(), vec![3..=5]);
+        // 缺口恰好由丢弃区间覆盖
+        let gap = received[1] + 1..=received[2] - 1;
+        assert_eq!(queue.dropped_ranges()[0], gap);
+    }
+}
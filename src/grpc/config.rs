@@ -1,4 +1,430 @@
+//! 客户端配置构建器
+//!
+//! 推荐通过 [`ClientConfig::builder`] 构造配置，`build()` 时统一校验，
+//! 避免直接修改公开字段造成的非法组合（例如超时为 0）在连接时才暴露。
+//! 直接构造的配置由 [`YellowstoneGrpc::new_with_config`](crate::grpc::YellowstoneGrpc::new_with_config) 校验。
+//!
+//! 预设：[`ClientConfig::low_latency`]、[`ClientConfig::reliable`]、[`ClientConfig::backfill`]、
+//! [`ClientConfig::high_throughput`]，可通过 [`ClientConfigBuilder::from_config`] 在预设基础上调整。
+
 pub use crate::grpc::types::{ClientConfig, SlotFilter};
 
 // Re-export for backwards compatibility
-pub use crate::grpc::types::ClientConfig as StreamingConfig;
\ No newline at end of file
+pub use crate::grpc::types::ClientConfig as StreamingConfig;
+
+use super::tls::{TlsConfigError, TlsMode};
+use super::types::EnabledProtocols;
+use super::workers::MAX_CORE_ID;
+use crate::core::merger::MergePreferences;
+use crate::core::unified_parser::{ParseMode, ParseOptions};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+/// 超过该解码上限时给出警告
+const LARGE_DECODING_MESSAGE_SIZE: usize = 1024 * 1024 * 1024;
+
+/// 订阅的确认级别
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Commitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentLevel {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => CommitmentLevel::Processed,
+            Commitment::Confirmed => CommitmentLevel::Confirmed,
+            Commitment::Finalized => CommitmentLevel::Finalized,
+        }
+    }
+}
+
+/// 配置错误
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("{0} must be greater than zero")]
+    ZeroValue(&'static str),
+    #[error("request_timeout_ms ({request}) must not be shorter than connection_timeout_ms ({connect})")]
+    RequestTimeoutShorterThanConnect { request: u64, connect: u64 },
+    #[error("ping_interval_ms ({ping}) must be shorter than stale_stream_timeout_ms ({stale}), otherwise idle streams are reconnected before the first ping")]
+    PingSlowerThanStaleTimeout { ping: u64, stale: u64 },
+    #[error("resume_from_slot requires max_retries > 0")]
+    ResumeWithoutRetries,
+    #[error("keep_alive_timeout_ms ({timeout}) must be shorter than keep_alive_interval_ms ({interval})")]
+    KeepAliveTimeoutTooLong { timeout: u64, interval: u64 },
+    #[error("{field} contains core id {core_id}, which must be below {max}")]
+    CoreIdOutOfRange { field: &'static str, core_id: usize, max: usize },
+    #[error(transparent)]
+    Tls(#[from] TlsConfigError),
+}
+
+/// [`ClientConfig`] 构建器，初始值为 [`ClientConfig::default`]
+#[derive(Debug, Clone, Default)]
+pub struct ClientConfigBuilder {
+    config: ClientConfig,
+}
+
+impl ClientConfig {
+    /// 创建构建器
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+
+    /// 校验配置（[`YellowstoneGrpc::new_with_config`](crate::grpc::YellowstoneGrpc::new_with_config) 会调用）
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let positive = [
+            ("connection_timeout_ms", self.connection_timeout_ms),
+            ("request_timeout_ms", self.request_timeout_ms),
+            ("buffer_size", self.buffer_size as u64),
+            ("max_decoding_message_size", self.max_decoding_message_size as u64),
+            ("queue_capacity", self.queue_capacity as u64),
+            ("max_filter_accounts", self.max_filter_accounts as u64),
+        ];
+        if let Some((name, _)) = positive.iter().find(|(_, value)| *value == 0) {
+            return Err(ConfigError::ZeroValue(name));
+        }
+        if self.request_timeout_ms < self.connection_timeout_ms {
+            return Err(ConfigError::RequestTimeoutShorterThanConnect {
+                request: self.request_timeout_ms,
+                connect: self.connection_timeout_ms,
+            });
+        }
+        if self.ping_interval_ms > 0
+            && self.stale_stream_timeout_ms > 0
+            && self.ping_interval_ms >= self.stale_stream_timeout_ms
+        {
+            return Err(ConfigError::PingSlowerThanStaleTimeout {
+                ping: self.ping_interval_ms,
+                stale: self.stale_stream_timeout_ms,
+            });
+        }
+        if self.resume_from_slot && self.max_retries == 0 {
+            return Err(ConfigError::ResumeWithoutRetries);
+        }
+        if self.keep_alive_interval_ms > 0 && self.keep_alive_timeout_ms >= self.keep_alive_interval_ms {
+            return Err(ConfigError::KeepAliveTimeoutTooLong {
+                timeout: self.keep_alive_timeout_ms,
+                interval: self.keep_alive_interval_ms,
+            });
+        }
+        let core_ids = self.parser_core_ids.iter().map(|&id| ("parser_core_ids", id));
+        let core_ids = core_ids.chain(self.receiver_core_id.map(|id| ("receiver_core_id", id)));
+        if let Some((field, core_id)) = core_ids.into_iter().find(|&(_, id)| id >= MAX_CORE_ID) {
+            return Err(ConfigError::CoreIdOutOfRange { field, core_id, max: MAX_CORE_ID });
+        }
+        self.tls_mode().validate()?;
+        Ok(())
+    }
+
+    /// 该客户端解析交易时使用的选项（每个客户端独立，互不影响）
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            mode: self.parse_mode,
+            enabled_protocols: self.enabled_protocols,
+            merge: self.merge_preferences,
+            emit_unknown: self.emit_unknown_instructions,
+            retain_raw: self.retain_raw,
+        }
+    }
+}
+
+impl ClientConfigBuilder {
+    /// 以已有配置（例如 [`ClientConfig::low_latency`]）为起点
+    pub fn from_config(config: ClientConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn enable_metrics(mut self, enable: bool) -> Self {
+        self.config.enable_metrics = enable;
+        self
+    }
+
+    pub fn connection_timeout_ms(mut self, ms: u64) -> Self {
+        self.config.connection_timeout_ms = ms;
+        self
+    }
+
+    pub fn request_timeout_ms(mut self, ms: u64) -> Self {
+        self.config.request_timeout_ms = ms;
+        self
+    }
+
+    pub fn enable_tls(mut self, enable: bool) -> Self {
+        self.config.enable_tls = enable;
+        self
+    }
+
+    pub fn tls(mut self, tls: TlsMode) -> Self {
+        self.config.tls = tls;
+        self
+    }
+
+    pub fn commitment(mut self, commitment: Commitment) -> Self {
+        self.config.commitment = commitment;
+        self
+    }
+
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.config.max_retries = retries;
+        self
+    }
+
+    pub fn retry_delay_ms(mut self, ms: u64) -> Self {
+        self.config.retry_delay_ms = ms;
+        self
+    }
+
+    pub fn max_concurrent_streams(mut self, streams: u32) -> Self {
+        self.config.max_concurrent_streams = streams;
+        self
+    }
+
+    pub fn keep_alive(mut self, interval_ms: u64, timeout_ms: u64) -> Self {
+        self.config.keep_alive_interval_ms = interval_ms;
+        self.config.keep_alive_timeout_ms = timeout_ms;
+        self
+    }
+
+    pub fn buffer_size(mut self, size: usize) -> Self {
+        self.config.buffer_size = size;
+        self
+    }
+
+    /// gRPC 单条消息的解码上限（字节）
+    pub fn max_decoding_message_size(mut self, bytes: usize) -> Self {
+        if bytes >= LARGE_DECODING_MESSAGE_SIZE {
+            warn!(
+                "max_decoding_message_size = {} bytes; a single oversized message can allocate this much memory",
+                bytes
+            );
+        }
+        self.config.max_decoding_message_size = bytes;
+        self
+    }
+
+    pub fn ping_interval_ms(mut self, ms: u64) -> Self {
+        self.config.ping_interval_ms = ms;
+        self
+    }
+
+    pub fn stale_stream_timeout_ms(mut self, ms: u64) -> Self {
+        self.config.stale_stream_timeout_ms = ms;
+        self
+    }
+
+    pub fn enabled_protocols(mut self, protocols: EnabledProtocols) -> Self {
+        self.config.enabled_protocols = protocols;
+        self
+    }
+
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.config.queue_capacity = capacity;
+        self
+    }
+
+    pub fn first_event_only(mut self, enable: bool) -> Self {
+        self.config.first_event_only = enable;
+        self
+    }
+
+    pub fn resume_from_slot(mut self, enable: bool) -> Self {
+        self.config.resume_from_slot = enable;
+        self
+    }
+
+    /// 单个过滤器的账户数上限，超过时自动拆分
+    pub fn max_filter_accounts(mut self, max: usize) -> Self {
+        self.config.max_filter_accounts = max;
+        self
+    }
+
+    /// 允许不带账户条件的过滤器（全链订阅）
+    pub fn allow_firehose(mut self, allow: bool) -> Self {
+        self.config.allow_firehose = allow;
+        self
+    }
+
+    /// 解析线程数（0 表示在接收任务内解析）
+    pub fn parser_threads(mut self, threads: usize) -> Self {
+        self.config.parser_threads = threads;
+        self
+    }
+
+    /// 转交队列容量（0 表示沿用 `queue_capacity`）
+    pub fn parser_queue_capacity(mut self, capacity: usize) -> Self {
+        self.config.parser_queue_capacity = capacity;
+        self
+    }
+
+    /// 转交队列已满时等待解析线程（默认）还是丢弃交易
+    pub fn parser_backpressure(mut self, enable: bool) -> Self {
+        self.config.parser_backpressure = enable;
+        self
+    }
+
+    /// 识别同一交易跨协议成交同一代币，追加 `DexEvent::CrossVenueTrade`
+    pub fn detect_cross_venue_trades(mut self, enable: bool) -> Self {
+        self.config.detect_cross_venue_trades = enable;
+        self
+    }
+
+    /// 解析线程绑定的 CPU 核心
+    pub fn parser_core_ids(mut self, core_ids: Vec<usize>) -> Self {
+        self.config.parser_core_ids = core_ids;
+        self
+    }
+
+    /// 接收循环绑定的 CPU 核心
+    pub fn receiver_core_id(mut self, core_id: usize) -> Self {
+        self.config.receiver_core_id = Some(core_id);
+        self
+    }
+
+    /// 订阅 slot 状态更新
+    pub fn slot_updates(mut self, filter: SlotFilter) -> Self {
+        self.config.slot_updates = Some(filter);
+        self
+    }
+
+    /// 初始化客户端时校验 discriminator
+    pub fn validate_discriminators(mut self, enable: bool) -> Self {
+        self.config.validate_discriminators = enable;
+        self
+    }
+
+    /// 订阅时维护池子最新状态
+    pub fn track_pool_state(mut self, enable: bool) -> Self {
+        self.config.track_pool_state = enable;
+        self
+    }
+
+    /// 为无法识别的指令输出 `DexEvent::UnknownSwap`
+    pub fn emit_unknown_instructions(mut self, enable: bool) -> Self {
+        self.config.emit_unknown_instructions = enable;
+        self
+    }
+
+    /// 合并指令事件和日志事件时各字段组的优先来源
+    pub fn merge_preferences(mut self, preferences: MergePreferences) -> Self {
+        self.config.merge_preferences = preferences;
+        self
+    }
+
+    /// 指令事件保留指令原始数据，用于排查解码异常
+    pub fn retain_raw(mut self, enable: bool) -> Self {
+        self.config.retain_raw = enable;
+        self
+    }
+
+    /// 解析范围（只看日志 / 只看指令 / 两者合并）
+    pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+        self.config.parse_mode = mode;
+        self
+    }
+
+    /// 超大交易保护
+    pub fn tx_size_guard(mut self, guard: crate::grpc::guard::TxSizeGuard) -> Self {
+        self.config.tx_size_guard = guard;
+        self
+    }
+
+    /// 开启输出去重：最多记住 `capacity` 个事件，`window_ms` 为记录有效期（0 表示只按容量淘汰）
+    pub fn dedup(mut self, capacity: usize, window_ms: u64) -> Self {
+        self.config.dedup = crate::grpc::dedup::DedupConfig { capacity, window_ms };
+        self
+    }
+
+    /// 校验并生成配置
+    pub fn build(self) -> Result<ClientConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let config = ClientConfig::builder()
+            .connection_timeout_ms(10_000)
+            .enable_tls(false)
+            .commitment(Commitment::Confirmed)
+            .build()
+            .unwrap();
+        assert_eq!(config.connection_timeout_ms, 10_000);
+        assert_eq!(config.tls_mode(), &TlsMode::None);
+        assert_eq!(config.commitment, Commitment::Confirmed);
+        assert_eq!(config.request_timeout_ms, ClientConfig::default().request_timeout_ms);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_values() {
+        let err = ClientConfig::builder().connection_timeout_ms(0).build().unwrap_err();
+        assert!(matches!(err, ConfigError::ZeroValue("connection_timeout_ms")));
+
+        let err = ClientConfig::builder().buffer_size(0).build().unwrap_err();
+        assert!(matches!(err, ConfigError::ZeroValue("buffer_size")));
+
+        let err = ClientConfig::builder().keep_alive(5_000, 5_000).build().unwrap_err();
+        assert!(matches!(err, ConfigError::KeepAliveTimeoutTooLong { .. }));
+
+        let err = ClientConfig::builder()
+            .tls(TlsMode::CustomCa { pem: b"garbage".to_vec() })
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::Tls(TlsConfigError::EmptyCaPem)));
+
+        let err = ClientConfig::builder().connection_timeout_ms(20_000).request_timeout_ms(10_000).build().unwrap_err();
+        assert!(matches!(err, ConfigError::RequestTimeoutShorterThanConnect { request: 10_000, connect: 20_000 }));
+
+        let err = ClientConfig::builder().queue_capacity(0).build().unwrap_err();
+        assert!(err.to_string().contains("queue_capacity"));
+
+        let err = ClientConfig::builder().ping_interval_ms(30_000).stale_stream_timeout_ms(10_000).build().unwrap_err();
+        assert!(matches!(err, ConfigError::PingSlowerThanStaleTimeout { .. }));
+
+        let err = ClientConfig::builder().resume_from_slot(true).max_retries(0).build().unwrap_err();
+        assert!(matches!(err, ConfigError::ResumeWithoutRetries));
+
+        let config = ClientConfig { parser_core_ids: vec![0, MAX_CORE_ID], ..ClientConfig::default() };
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, ConfigError::CoreIdOutOfRange { field: "parser_core_ids", core_id, .. } if core_id == MAX_CORE_ID));
+
+        let config = ClientConfig { receiver_core_id: Some(4096), ..ClientConfig::default() };
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, ConfigError::CoreIdOutOfRange { field: "receiver_core_id", core_id: 4096, .. }));
+    }
+
+    #[test]
+    fn test_presets_are_valid() {
+        let low_latency = ClientConfig::low_latency();
+        assert!(low_latency.validate().is_ok());
+        assert_eq!(low_latency.commitment, Commitment::Processed);
+        assert!(low_latency.first_event_only);
+        assert!(low_latency.queue_capacity < ClientConfig::default().queue_capacity);
+
+        let reliable = ClientConfig::reliable();
+        assert!(reliable.validate().is_ok());
+        assert_eq!(reliable.commitment, Commitment::Confirmed);
+        assert!(reliable.resume_from_slot && !reliable.first_event_only);
+        assert!(reliable.queue_capacity > ClientConfig::default().queue_capacity);
+
+        let backfill = ClientConfig::backfill();
+        assert!(backfill.validate().is_ok());
+        assert!(!backfill.enable_metrics);
+        assert_eq!(backfill.max_decoding_message_size, usize::MAX);
+
+        assert!(ClientConfig::default().validate().is_ok());
+        assert!(ClientConfig::high_throughput().validate().is_ok());
+
+        // new_with_config 同样拒绝非法配置
+        let invalid = ClientConfig { queue_capacity: 0, ..ClientConfig::default() };
+        assert!(crate::grpc::YellowstoneGrpc::new_with_config("http://localhost:10000".into(), None, invalid).is_err());
+    }
+}
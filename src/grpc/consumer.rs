@@ -0,0 +1,376 @@
+//! 队列消费线程
+//!
+//! [`DexEventQueue::spawn_consumer`] 在独立线程上轮询队列，按批次回调：凑满 `max_batch` 个事件，
+//! 或批次中第一个事件等待超过 `max_wait_us` 时交付。队列为空时先自旋、再 `yield`、最后短暂 sleep，
+//! 与 [`DexEventQueue::drain_batch`] 的策略相同。
+//!
+//! 调用 [`ConsumerHandle::stop`]，或 `ConsumerOptions::subscription` 对应的订阅停止后，
+//! 线程交付已取出的事件后退出；队列中尚未取出的事件保留在队列里。
+//! [`DexEventQueue::spawn_consumer_async`] 使用同样的线程，把批次发送到 tokio mpsc：接收端 drop 后线程退出
+//! （空闲时也会检查）；mpsc 已满时同时等待空位、接收端关闭和停止信号，停止时 mpsc 仍满则丢弃该批并记录警告。
+
+use super::pause::SubscriptionHandle;
+use super::queue::DexEventQueue;
+use super::workers::pin_current_thread;
+use crate::DexEvent;
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Notify};
+
+/// 空轮询时先自旋的次数，之后 yield，再之后短暂 sleep
+const SPIN_LIMIT: u32 = 64;
+const YIELD_LIMIT: u32 = 1_000;
+const IDLE_SLEEP: Duration = Duration::from_micros(50);
+
+/// 消费线程参数
+#[derive(Clone)]
+pub struct ConsumerOptions {
+    /// 每批最多事件数（至少为 1）
+    pub max_batch: usize,
+    /// 批次中第一个事件最多等待的微秒数，0 表示取空队列后立即交付
+    pub max_wait_us: u64,
+    /// 消费线程绑定的 CPU 核心
+    pub core_id: Option<usize>,
+    /// 订阅停止（[`SubscriptionHandle::stop`]）时消费线程随之退出
+    pub subscription: Option<SubscriptionHandle>,
+    /// 异步版本的 mpsc 容量（按批次计），已满时消费线程等待
+    pub channel_capacity: usize,
+}
+
+impl Default for ConsumerOptions {
+    fn default() -> Self {
+        Self { max_batch: 256, max_wait_us: 1_000, core_id: None, subscription: None, channel_capacity: 64 }
+    }
+}
+
+/// 停止信号：轮询时读标志，异步交付时等待通知
+#[derive(Default)]
+struct Shutdown {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Shutdown {
+    fn request(&self) {
+        self.requested.store(true, Ordering::Release);
+        self.notify.notify_waiters();
+    }
+
+    fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::Acquire)
+    }
+
+    async fn wait(&self) {
+        let notified = self.notify.notified();
+        tokio::pin!(notified);
+        // 先登记再检查标志，避免错过检查之后、等待之前发出的通知
+        notified.as_mut().enable();
+        if !self.is_requested() {
+            notified.await;
+        }
+    }
+}
+
+/// 消费线程句柄，drop 时不停止线程
+pub struct ConsumerHandle {
+    shutdown: Arc<Shutdown>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ConsumerHandle {
+    /// 通知消费线程退出（不等待）
+    pub fn stop(&self) {
+        self.shutdown.request();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// 通知退出并等待线程结束
+    pub fn join(mut self) -> std::thread::Result<()> {
+        self.stop();
+        self.thread.take().map_or(Ok(()), JoinHandle::join)
+    }
+}
+
+impl DexEventQueue {
+    /// 在独立线程上按批次消费队列，回调参数为本批事件（按出队顺序）
+    pub fn spawn_consumer<F>(&self, opts: ConsumerOptions, mut f: F) -> ConsumerHandle
+    where
+        F: FnMut(&[DexEvent]) + Send + 'static,
+    {
+        let shutdown = Arc::new(Shutdown::default());
+        self.spawn_batches(opts, shutdown, || true, move |batch| {
+            f(batch);
+            true
+        })
+    }
+
+    /// 异步版本：批次通过 tokio mpsc 交付，接收端 drop 后消费线程退出
+    ///
+    /// mpsc 已满时等待空位，期间收到停止信号（[`ConsumerHandle::stop`] 或订阅停止）则丢弃该批并退出。
+    pub fn spawn_consumer_async(&self, opts: ConsumerOptions) -> (mpsc::Receiver<Vec<DexEvent>>, ConsumerHandle) {
+        let (tx, rx) = mpsc::channel(opts.channel_capacity.max(1));
+        let shutdown = Arc::new(Shutdown::default());
+        let (signal, subscription) = (shutdown.clone(), opts.subscription.clone());
+        let alive = {
+            let tx = tx.clone();
+            move || !tx.is_closed()
+        };
+        let deliver = move |batch: &[DexEvent]| {
+            let stopped = async {
+                match &subscription {
+                    Some(subscription) => tokio::select! {
+                        _ = signal.wait() => {}
+                        _ = subscription.stopped() => {}
+                    },
+                    None => signal.wait().await,
+                }
+            };
+            // 有空位时立即发送（停止时也先交付已取出的事件）；接收端关闭时 send 返回错误
+            futures::executor::block_on(async {
+                tokio::select! {
+                    biased;
+                    sent = tx.send(batch.to_vec()) => sent.is_ok(),
+                    _ = stopped => {
+                        warn!("consumer stopped while the channel was full, {} events dropped", batch.len());
+                        false
+                    }
+                }
+            })
+        };
+        let handle = self.spawn_batches(opts, shutdown, alive, deliver);
+        (rx, handle)
+    }
+
+    /// `deliver` 返回 false 或 `alive` 返回 false 时线程退出
+    fn spawn_batches<A, F>(&self, opts: ConsumerOptions, shutdown: Arc<Shutdown>, alive: A, mut deliver: F) -> ConsumerHandle
+    where
+        A: Fn() -> bool + Send + 'static,
+        F: FnMut(&[DexEvent]) -> bool + Send + 'static,
+    {
+        let queue = self.clone();
+        let flag = shutdown.clone();
+        let name = opts.core_id.map_or_else(|| "sol-consumer".to_string(), |id| format!("sol-consumer-{}", id));
+        let thread = std::thread::Builder::new()
+            .name(name)
+            .spawn(move || {
+                if let Some(core_id) = opts.core_id {
+                    pin_current_thread(core_id);
+                }
+                let should_stop = || {
+                    flag.is_requested()
+                        || opts.subscription.as_ref().is_some_and(SubscriptionHandle::is_stopped)
+                        || !alive()
+                };
+                let max_batch = opts.max_batch.max(1);
+                let max_wait = Duration::from_micros(opts.max_wait_us);
+                let mut batch = Vec::with_capacity(max_batch.min(queue.capacity()));
+                let mut first_at = Instant::now();
+                let mut idle = 0u32;
+
+                loop {
+                    let stop = should_stop();
+                    while batch.len() < max_batch {
+                        let Some(event) = queue.pop() else { break };
+                        if batch.is_empty() {
+                            first_at = Instant::now();
+                        }
+                        batch.push(event);
+                        idle = 0;
+                    }
+                    let due = batch.len() >= max_batch || first_at.elapsed() >= max_wait;
+                    if !batch.is_empty() && (due || stop) {
+                        let keep_going = deliver(&batch);
+                        batch.clear();
+                        if !keep_going {
+                            return;
+                        }
+                        continue;
+                    }
+                    if stop {
+                        return;
+                    }
+
+                    idle += 1;
+                    if idle < SPIN_LIMIT {
+                        std::hint::spin_loop();
+                    } else if idle < YIELD_LIMIT {
+                        std::thread::yield_now();
+                    } else if batch.is_empty() {
+                        std::thread::sleep(IDLE_SLEEP);
+                    } else {
+                        std::thread::sleep(IDLE_SLEEP.min(max_wait.saturating_sub(first_at.elapsed())));
+                    }
+                }
+            })
+            .expect("failed to spawn consumer thread");
+        ConsumerHandle { shutdown, thread: Some(thread) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{BlockMetaEvent, EventMetadata, EventSource};
+    use crossbeam_channel::RecvTimeoutError;
+    use solana_sdk::signature::Signature;
+
+    fn event(sequence: u64) -> DexEvent {
+        DexEvent::BlockMeta(BlockMetaEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot: sequence,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+        })
+    }
+
+    fn slots(batch: &[DexEvent]) -> Vec<u64> {
+        batch.iter().map(|e| e.metadata().unwrap().slot).collect()
+    }
+
+    #[test]
+    fn test_flushes_full_batches() {
+        let queue = DexEventQueue::new(16);
+        for i in 0..6 {
+            queue.push(event(i)).unwrap();
+        }
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let opts = ConsumerOptions { max_batch: 3, max_wait_us: 60_000_000, ..Default::default() };
+        let handle = queue.spawn_consumer(opts, move |batch| tx.send(slots(batch)).unwrap());
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), vec![0, 1, 2]);
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), vec![3, 4, 5]);
+        // 不足一批且未到等待上限时不交付
+        queue.push(event(6)).unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_millis(50)), Err(RecvTimeoutError::Timeout));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_flushes_partial_batch_after_max_wait() {
+        let queue = DexEventQueue::new(16);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let opts = ConsumerOptions { max_batch: 100, max_wait_us: 20_000, ..Default::default() };
+        let handle = queue.spawn_consumer(opts, move |batch| tx.send((Instant::now(), slots(batch))).unwrap());
+
+        let pushed_at = Instant::now();
+        queue.push(event(1)).unwrap();
+        queue.push(event(2)).unwrap();
+        let (delivered_at, batch) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch, vec![1, 2]);
+        assert!(delivered_at - pushed_at >= Duration::from_millis(20));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_stops_with_subscription_and_delivers_pending_events() {
+        use super::super::pause::PausableSink;
+        use tokio::sync::watch;
+
+        let queue = DexEventQueue::new(16);
+        let sink = Arc::new(PausableSink::new(Arc::new(queue.clone()), Default::default()));
+        let subscription = SubscriptionHandle::new(sink, Arc::new(watch::channel(false).0));
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let opts = ConsumerOptions {
+            max_batch: 100,
+            max_wait_us: 60_000_000,
+            subscription: Some(subscription.clone()),
+            ..Default::default()
+        };
+        let handle = queue.spawn_consumer(opts, move |batch| tx.send(slots(batch)).unwrap());
+
+        queue.push(event(1)).unwrap();
+        queue.push(event(2)).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        subscription.stop();
+        // 停止时交付已取出的事件
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), vec![1, 2]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !handle.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert!(handle.is_finished());
+        handle.join().unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_async_batches_and_receiver_drop() {
+        let queue = DexEventQueue::new(16);
+        for i in 0..4 {
+            queue.push(event(i)).unwrap();
+        }
+        let opts = ConsumerOptions { max_batch: 2, max_wait_us: 0, ..Default::default() };
+        let (mut rx, handle) = queue.spawn_consumer_async(opts);
+        assert_eq!(slots(&rx.recv().await.unwrap()), vec![0, 1]);
+        assert_eq!(slots(&rx.recv().await.unwrap()), vec![2, 3]);
+
+        // 接收端 drop 后，即使队列一直为空线程也自行退出
+        drop(rx);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !handle.is_finished() && Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(handle.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_async_stops_while_channel_full() {
+        use super::super::pause::PausableSink;
+        use tokio::sync::watch;
+
+        let wait_finished = |handle: &ConsumerHandle| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !handle.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            handle.is_finished()
+        };
+        let opts = ConsumerOptions { max_batch: 1, max_wait_us: 0, channel_capacity: 1, ..Default::default() };
+
+        // mpsc 容量 1 且接收端不读：第二批等待空位时 stop 仍能让线程退出
+        let queue = DexEventQueue::new(16);
+        for i in 0..3 {
+            queue.push(event(i)).unwrap();
+        }
+        let (mut rx, handle) = queue.spawn_consumer_async(opts.clone());
+        while queue.len() > 1 {
+            tokio::task::yield_now().await;
+        }
+        handle.stop();
+        assert!(wait_finished(&handle));
+        assert_eq!(slots(&rx.recv().await.unwrap()), vec![0]);
+        assert!(rx.recv().await.is_none());
+
+        // 订阅停止同样唤醒等待中的交付
+        let queue = DexEventQueue::new(16);
+        let sink = Arc::new(PausableSink::new(Arc::new(queue.clone()), Default::default()));
+        let subscription = SubscriptionHandle::new(sink, Arc::new(watch::channel(false).0));
+        for i in 0..3 {
+            queue.push(event(i)).unwrap();
+        }
+        let (_rx, handle) = queue.spawn_consumer_async(ConsumerOptions { subscription: Some(subscription.clone()), ..opts });
+        while queue.len() > 1 {
+            tokio::task::yield_now().await;
+        }
+        subscription.stop();
+        assert!(wait_finished(&handle));
+    }
+}
@@ -0,0 +1,175 @@
+//! 流式输出去重
+//!
+//! 双 commitment 订阅、重连后服务端重推等情况下，同一笔交易可能被解析多次。开启后按
+//! [`DexEvent::event_id`] 记住最近输出过的交易事件，再次出现时丢弃：
+//! - 只对交易解析出的事件生效（按整笔交易处理），账户、slot 更新不去重
+//! - 同一指令内同类型的多个事件 `event_id` 相同，按在交易内的出现次序区分，不会互相误伤
+//! - 最多记住 `capacity` 个事件，超出时淘汰最久未出现的；`window_ms > 0` 时超过窗口的记录也失效
+//!
+//! 默认关闭，关闭时不计算 `event_id`、不加锁。
+
+use crate::common::time::now_micros_coarse;
+use crate::core::events::DexEvent;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+
+/// 去重配置，`capacity` 为 0 表示关闭（默认）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupConfig {
+    /// 最多记住的事件数
+    #[serde(default)]
+    pub capacity: usize,
+    /// 记录的有效期（毫秒），0 表示只按容量淘汰
+    #[serde(default)]
+    pub window_ms: u64,
+}
+
+impl DedupConfig {
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+}
+
+/// `(event_id, 同一交易内相同 event_id 的出现次序)`
+type Key = ([u8; 32], u32);
+
+/// 按 LRU 淘汰的已输出事件集合
+pub(crate) struct EventDeduplicator {
+    capacity: usize,
+    window_us: i64,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// 事件 -> 最近一次出现的记录号
+    seen: HashMap<Key, u64>,
+    /// `(事件, 记录号, 出现时间)`，按出现顺序排列；再次出现时追加新记录，旧记录在淘汰时按记录号跳过
+    order: VecDeque<(Key, u64, i64)>,
+    next: u64,
+}
+
+impl EventDeduplicator {
+    /// 未启用时返回 None
+    pub(crate) fn new(config: DedupConfig) -> Option<Self> {
+        config.is_enabled().then(|| Self {
+            capacity: config.capacity,
+            window_us: config.window_ms.saturating_mul(1_000).min(i64::MAX as u64) as i64,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// 去掉同一笔交易中已经输出过的事件
+    pub(crate) fn retain_new(&self, events: &mut Vec<DexEvent>) {
+        let keys = keys(events);
+        let now = now_micros_coarse();
+        let mut state = self.state.lock();
+        state.expire(now, self.window_us);
+        let mut keys = keys.into_iter();
+        events.retain(|_| keys.next().flatten().is_none_or(|key| state.touch(key, now)));
+        state.evict(self.capacity);
+    }
+}
+
+/// 每个事件的去重键，没有签名的事件为 None（不去重）
+fn keys(events: &[DexEvent]) -> Vec<Option<Key>> {
+    let mut keys: Vec<Option<Key>> = Vec::with_capacity(events.len());
+    for event in events {
+        let id = match event.event_id() {
+            Some(id) if event.metadata().is_some_and(|m| m.signature != Signature::default()) => id,
+            _ => {
+                keys.push(None);
+                continue;
+            }
+        };
+        let ordinal = keys.iter().flatten().filter(|(other, _)| *other == id).count() as u32;
+        keys.push(Some((id, ordinal)));
+    }
+    keys
+}
+
+impl State {
+    /// 记录一次出现，返回之前是否没见过
+    fn touch(&mut self, key: Key, now: i64) -> bool {
+        self.next += 1;
+        let fresh = self.seen.insert(key, self.next).is_none();
+        self.order.push_back((key, self.next, now));
+        fresh
+    }
+
+    fn expire(&mut self, now: i64, window_us: i64) {
+        if window_us <= 0 {
+            return;
+        }
+        while let Some(&(key, record, at)) = self.order.front() {
+            if now.saturating_sub(at) <= window_us {
+                break;
+            }
+            self.order.pop_front();
+            self.forget(key, record);
+        }
+    }
+
+    fn evict(&mut self, capacity: usize) {
+        while self.seen.len() > capacity {
+            let Some((key, record, _)) = self.order.pop_front() else { break };
+            self.forget(key, record);
+        }
+        // 重复出现留下的过期记录过多时压缩一次
+        if self.order.len() > capacity.saturating_mul(2) {
+            let seen = &self.seen;
+            self.order.retain(|(key, record, _)| seen.get(key) == Some(record));
+        }
+    }
+
+    /// 只有该记录仍是最近一次出现时才删除
+    fn forget(&mut self, key: Key, record: u64) {
+        if self.seen.get(&key) == Some(&record) {
+            self.seen.remove(&key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{BlockMetaEvent, EventMetadata, EventSource};
+
+    fn event(signature: u8) -> DexEvent {
+        DexEvent::BlockMeta(BlockMetaEvent {
+            metadata: EventMetadata {
+                signature: Signature::from([signature; 64]),
+                slot: 1,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+        })
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_recent() {
+        let dedup = EventDeduplicator::new(DedupConfig { capacity: 2, window_ms: 0 }).unwrap();
+        let mut first = vec![event(1), event(1), event(2)];
+        dedup.retain_new(&mut first);
+        // 同一交易内相同 event_id 的两个事件都保留；容量为 2，最早的 (1, 第 0 个) 被淘汰
+        assert_eq!(first.len(), 3);
+
+        let mut again = vec![event(1), event(1), event(2)];
+        dedup.retain_new(&mut again);
+        assert_eq!(again.len(), 1);
+        assert!(EventDeduplicator::new(DedupConfig::default()).is_none());
+    }
+}
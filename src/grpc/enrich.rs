@@ -0,0 +1,295 @@
+//! 代币元数据补全
+//!
+//! 除 PumpFun Create 外，事件只携带 mint 地址。补全在事件出队后的独立任务中进行，不影响解析热路径：
+//! - [`TokenMetadataResolver`]：由使用方实现（通常是 RPC / 自建索引），SDK 本身不发起任何请求
+//! - [`TokenMetadataCache`]：按 mint 缓存解析结果，并发请求同一 mint 时只解析一次；超过容量后按插入顺序淘汰。
+//!   解析失败的 None 只缓存 `negative_ttl`（默认 60 秒），过期后重新解析，新代币的元数据晚于首笔交易上链时也能补全
+//! - `YellowstoneGrpc::subscribe_enriched`：把事件包装为 [`EnrichedEvent`] 输出，最多 `concurrency` 个解析同时进行，
+//!   输出顺序与事件顺序一致
+//!
+//! PumpFun Create 事件自带名称和符号（PumpFun 代币精度固定为 6），直接写入缓存，不再调用解析器。
+
+use crate::core::events::DexEvent;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, OnceCell};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio::time::Instant;
+
+/// PumpFun 代币精度
+const PUMPFUN_DECIMALS: u8 = 6;
+/// 解析失败结果的默认缓存时长
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+/// 代币元数据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMeta {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// 代币元数据解析器
+pub trait TokenMetadataResolver: Send + Sync {
+    /// 查询 mint 的元数据，查不到时返回 None（结果同样会被缓存）
+    fn resolve(&self, mint: Pubkey) -> impl Future<Output = Option<TokenMeta>> + Send;
+}
+
+/// 不做任何解析，只使用事件自带的元数据（PumpFun Create）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopResolver;
+
+impl TokenMetadataResolver for NoopResolver {
+    async fn resolve(&self, _mint: Pubkey) -> Option<TokenMeta> {
+        None
+    }
+}
+
+/// 由闭包实现的解析器：`FnResolver(|mint| async move { ... })`
+pub struct FnResolver<F>(pub F);
+
+impl<F, Fut> TokenMetadataResolver for FnResolver<F>
+where
+    F: Fn(Pubkey) -> Fut + Send + Sync,
+    Fut: Future<Output = Option<TokenMeta>> + Send,
+{
+    fn resolve(&self, mint: Pubkey) -> impl Future<Output = Option<TokenMeta>> + Send {
+        (self.0)(mint)
+    }
+}
+
+/// 解析结果及其完成时间
+type Resolved = (Option<TokenMeta>, Instant);
+type Slot = Arc<OnceCell<Resolved>>;
+
+struct CacheState {
+    entries: HashMap<Pubkey, Slot>,
+    order: VecDeque<Pubkey>,
+}
+
+/// 按 mint 缓存的元数据（有界）
+pub struct TokenMetadataCache {
+    capacity: usize,
+    negative_ttl: Duration,
+    state: Mutex<CacheState>,
+}
+
+impl TokenMetadataCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            state: Mutex::new(CacheState { entries: HashMap::new(), order: VecDeque::new() }),
+        }
+    }
+
+    /// 设置解析失败结果的缓存时长
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// 已缓存的结果（尚未解析、已被淘汰或失败结果已过期时返回 None）
+    pub fn get(&self, mint: &Pubkey) -> Option<Option<TokenMeta>> {
+        let state = self.state.lock();
+        let resolved = state.entries.get(mint)?.get()?;
+        (!self.is_expired(resolved)).then(|| resolved.0.clone())
+    }
+
+    /// 写入已知的元数据（已有结果时不覆盖，过期的失败结果除外）
+    pub fn insert(&self, mint: Pubkey, meta: TokenMeta) {
+        let _ = self.slot(mint).set((Some(meta), Instant::now()));
+    }
+
+    /// 取缓存，未命中时调用解析器；同一 mint 的并发请求共享一次解析
+    pub async fn get_or_resolve<R: TokenMetadataResolver>(&self, mint: Pubkey, resolver: &R) -> Option<TokenMeta> {
+        let slot = self.slot(mint);
+        slot.get_or_init(|| async { (resolver.resolve(mint).await, Instant::now()) }).await.0.clone()
+    }
+
+    /// 缓存的 mint 数
+    pub fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_expired(&self, (meta, resolved_at): &Resolved) -> bool {
+        meta.is_none() && resolved_at.elapsed() >= self.negative_ttl
+    }
+
+    fn slot(&self, mint: Pubkey) -> Slot {
+        let mut state = self.state.lock();
+        if let Some(slot) = state.entries.get_mut(&mint) {
+            // 过期的失败结果换成新的槽位重新解析，淘汰顺序不变
+            if slot.get().is_some_and(|resolved| self.is_expired(resolved)) {
+                *slot = Slot::default();
+            }
+            return Arc::clone(slot);
+        }
+        while state.entries.len() >= self.capacity {
+            let Some(oldest) = state.order.pop_front() else { break };
+            state.entries.remove(&oldest);
+        }
+        let slot = Slot::default();
+        state.entries.insert(mint, Arc::clone(&slot));
+        state.order.push_back(mint);
+        slot
+    }
+}
+
+/// 附带代币元数据的事件
+#[derive(Debug, Clone)]
+pub struct EnrichedEvent {
+    pub event: DexEvent,
+    /// [`DexEvent::primary_mint`] 的元数据，事件没有 mint 或解析不到时为 None
+    pub token: Option<TokenMeta>,
+}
+
+/// 补全单个事件
+pub async fn enrich<R: TokenMetadataResolver>(event: DexEvent, cache: &TokenMetadataCache, resolver: &R) -> EnrichedEvent {
+    if let DexEvent::PumpFunCreate(e) = &event {
+        cache.insert(e.mint, TokenMeta { name: e.name.clone(), symbol: e.symbol.clone(), decimals: PUMPFUN_DECIMALS });
+    }
+    let token = match event.primary_mint() {
+        Some(mint) => cache.get_or_resolve(mint, resolver).await,
+        None => None,
+    };
+    EnrichedEvent { event, token }
+}
+
+/// 补全任务：最多同时解析 `concurrency` 个事件，按输入顺序输出；任一 channel 关闭时退出
+pub(crate) async fn run<R: TokenMetadataResolver>(
+    events: mpsc::Receiver<DexEvent>,
+    tx: mpsc::Sender<EnrichedEvent>,
+    cache: Arc<TokenMetadataCache>,
+    resolver: Arc<R>,
+    concurrency: usize,
+) {
+    let mut enriched = ReceiverStream::new(events).map(|event| enrich(event, &cache, &*resolver)).buffered(concurrency.max(1));
+    while let Some(event) = enriched.next().await {
+        if tx.send(event).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_concurrent_events_resolve_each_mint_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let resolver = Arc::new(FnResolver(move |mint: Pubkey| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                Some(TokenMeta { name: mint.to_string(), symbol: "FAKE".to_string(), decimals: 9 })
+            }
+        }));
+        let cache = Arc::new(TokenMetadataCache::new(16));
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let tasks: Vec<_> = (0..20)
+            .map(|i| {
+                let (cache, resolver, mint) = (cache.clone(), resolver.clone(), mints[i % 2]);
+                tokio::spawn(async move { cache.get_or_resolve(mint, &*resolver).await })
+            })
+            .collect();
+        for (i, task) in tasks.into_iter().enumerate() {
+            assert_eq!(task.await.unwrap().unwrap().name, mints[i % 2].to_string());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 超过容量后按插入顺序淘汰
+        let small = TokenMetadataCache::new(1);
+        small.get_or_resolve(mints[0], &NoopResolver).await;
+        small.get_or_resolve(mints[1], &NoopResolver).await;
+        assert_eq!((small.len(), small.get(&mints[0]), small.get(&mints[1])), (1, None, Some(None)));
+    }
+
+    #[tokio::test]
+    async fn test_lookups_run_concurrently_in_order() {
+        use crate::core::events::{EventMetadata, EventSource, PumpFunCompleteTokenEvent};
+        use solana_sdk::signature::Signature;
+
+        // 第一个 mint 最慢，输出仍按事件顺序
+        let mints: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let slow = mints[0];
+        let resolver = Arc::new(FnResolver(move |mint: Pubkey| async move {
+            let delay = if mint == slow { 80 } else { 40 };
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            Some(TokenMeta { name: mint.to_string(), symbol: "FAKE".to_string(), decimals: 6 })
+        }));
+        let (event_tx, event_rx) = mpsc::channel(16);
+        let (tx, mut rx) = mpsc::channel(16);
+        for &mint in &mints {
+            let metadata = EventMetadata {
+                signature: Signature::default(),
+                slot: 0,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us: 0,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            };
+            let complete = PumpFunCompleteTokenEvent { metadata, user: Pubkey::default(), mint, bonding_curve: Pubkey::default(), timestamp: 0 };
+            event_tx.send(DexEvent::PumpFunComplete(complete)).await.unwrap();
+        }
+        drop(event_tx);
+
+        let start = std::time::Instant::now();
+        tokio::spawn(run(event_rx, tx, Arc::new(TokenMetadataCache::new(16)), resolver, 8));
+        let mut names = Vec::new();
+        while let Some(enriched) = rx.recv().await {
+            names.push(enriched.token.unwrap().name);
+        }
+        // 逐个解析需要 80 + 7 * 40 毫秒
+        assert!(start.elapsed() < std::time::Duration::from_millis(300), "{:?}", start.elapsed());
+        assert_eq!(names, mints.iter().map(|m| m.to_string()).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_negative_results_expire() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        // 第一次查不到，之后查得到（元数据晚于首笔交易上链）
+        let resolver = FnResolver(move |_mint: Pubkey| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move { (call > 0).then(|| TokenMeta { name: "Late".to_string(), symbol: "LATE".to_string(), decimals: 6 }) }
+        });
+        let cache = TokenMetadataCache::new(16).with_negative_ttl(Duration::from_millis(20));
+        let mint = Pubkey::new_unique();
+
+        assert_eq!(cache.get_or_resolve(mint, &resolver).await, None);
+        assert_eq!(cache.get_or_resolve(mint, &resolver).await, None);
+        assert_eq!((calls.load(Ordering::SeqCst), cache.get(&mint)), (1, Some(None)));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(cache.get(&mint), None);
+        assert_eq!(cache.get_or_resolve(mint, &resolver).await.unwrap().symbol, "LATE");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // 成功的结果不过期
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(cache.get_or_resolve(mint, &resolver).await.is_some());
+        assert_eq!((calls.load(Ordering::SeqCst), cache.len()), (2, 1));
+    }
+}
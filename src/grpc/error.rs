@@ -0,0 +1,149 @@
+//! gRPC 客户端错误类型
+//!
+//! [`GrpcClientError`] 区分端点/令牌格式错误、令牌获取失败、连接失败、订阅被拒和配置错误，
+//! 调用方可据此决定是否重试。实现了 `std::error::Error`，`?` 仍可转换为 `Box<dyn Error>`。
+
+use super::auth::AuthError;
+use super::config::ConfigError;
+use super::tls::TlsConfigError;
+use yellowstone_grpc_client::{GeyserGrpcBuilderError, GeyserGrpcClientError};
+
+/// gRPC 客户端错误
+#[derive(Debug, thiserror::Error)]
+pub enum GrpcClientError {
+    /// 端点 URL 无法解析
+    #[error("invalid endpoint {endpoint:?}: {reason}")]
+    InvalidEndpoint { endpoint: String, reason: String },
+    /// x-token 不是合法的 ASCII header 值
+    #[error("invalid x-token: {0}")]
+    InvalidToken(String),
+    /// 令牌提供方（[`super::auth::AuthToken::Provider`]）获取令牌失败，需要调用方刷新凭据
+    #[error("auth token provider failed: {0}")]
+    AuthError(AuthError),
+    /// 建立连接失败（DNS、TCP、TLS 握手等）
+    #[error("connect error: {0}")]
+    ConnectError(#[from] tonic::transport::Error),
+    /// 服务端拒绝订阅或流返回错误状态
+    #[error("subscribe error: {0}")]
+    SubscribeError(#[from] tonic::Status),
+    /// 订阅请求发送失败（连接已断开）
+    #[error("failed to send subscribe request: {0}")]
+    SubscribeSendError(String),
+    /// 配置错误
+    #[error(transparent)]
+    ConfigError(#[from] ConfigError),
+    /// 过滤器不带任何账户条件，会订阅全链数据；需显式设置 `allow_firehose`
+    #[error("{0} filter has no account conditions and would subscribe to every {0} on chain; set allow_firehose to opt in")]
+    FirehoseNotAllowed(&'static str),
+    /// 客户端已调用 `stop()`
+    #[error("client stopped")]
+    Stopped,
+    /// 服务商拒绝区块订阅（未开放 blocks 过滤器或不允许 `include_transactions`）
+    #[error("blocks subscription rejected by provider: {0}")]
+    BlocksNotSupported(String),
+    /// 回放文件无法读取或记录无法解码
+    #[error("replay file error: {0}")]
+    ReplayFile(String),
+}
+
+impl GrpcClientError {
+    /// 是否值得重试（网络或服务端的临时错误）
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GrpcClientError::ConnectError(_) | GrpcClientError::SubscribeSendError(_) => true,
+            GrpcClientError::SubscribeError(status) => is_retryable_code(status.code()),
+            _ => false,
+        }
+    }
+
+    /// 区块订阅被拒时换成 [`GrpcClientError::BlocksNotSupported`]，说明原因
+    pub(crate) fn into_blocks_error(self) -> Self {
+        match self {
+            GrpcClientError::SubscribeError(status) if matches!(
+                status.code(),
+                tonic::Code::InvalidArgument | tonic::Code::PermissionDenied | tonic::Code::Unimplemented
+            ) => GrpcClientError::BlocksNotSupported(status.message().to_string()),
+            e => e,
+        }
+    }
+}
+
+/// 服务端状态码是否为临时错误
+pub(crate) fn is_retryable_code(code: tonic::Code) -> bool {
+    matches!(
+        code,
+        tonic::Code::Unavailable
+            | tonic::Code::DeadlineExceeded
+            | tonic::Code::ResourceExhausted
+            | tonic::Code::Aborted
+            | tonic::Code::Internal
+            | tonic::Code::Unknown
+    )
+}
+
+impl From<TlsConfigError> for GrpcClientError {
+    fn from(e: TlsConfigError) -> Self {
+        GrpcClientError::ConfigError(ConfigError::Tls(e))
+    }
+}
+
+impl From<GeyserGrpcBuilderError> for GrpcClientError {
+    fn from(e: GeyserGrpcBuilderError) -> Self {
+        match e {
+            GeyserGrpcBuilderError::MetadataValueError(e) => GrpcClientError::InvalidToken(e.to_string()),
+            GeyserGrpcBuilderError::TonicError(e) => GrpcClientError::ConnectError(e),
+        }
+    }
+}
+
+impl From<GeyserGrpcClientError> for GrpcClientError {
+    fn from(e: GeyserGrpcClientError) -> Self {
+        match e {
+            GeyserGrpcClientError::TonicStatus(status) => GrpcClientError::SubscribeError(status),
+            GeyserGrpcClientError::SubscribeSendError(e) => GrpcClientError::SubscribeSendError(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::{ClientConfig, YellowstoneGrpc};
+
+    #[test]
+    fn test_malformed_endpoint_and_token() {
+        let err = YellowstoneGrpc::new("not a url\n".into(), None).err().unwrap();
+        assert!(matches!(err, GrpcClientError::InvalidEndpoint { .. }), "{:?}", err);
+        assert!(!err.is_retryable());
+
+        let err = YellowstoneGrpc::new("https://geyser.example:443".into(), Some("bad\ntoken".into())).err().unwrap();
+        assert!(matches!(err, GrpcClientError::InvalidToken(_)), "{:?}", err);
+
+        let config = ClientConfig { request_timeout_ms: 1, ..ClientConfig::default() };
+        let err = YellowstoneGrpc::new_with_config("https://geyser.example:443".into(), None, config).err().unwrap();
+        assert!(matches!(err, GrpcClientError::ConfigError(_)));
+
+        // 仍可通过 ? 转换为 Box<dyn Error>
+        let boxed: Box<dyn std::error::Error> = err.into();
+        assert!(boxed.to_string().contains("request_timeout_ms"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_after_stop() {
+        let grpc = YellowstoneGrpc::new("http://127.0.0.1:1".into(), None).unwrap();
+        grpc.stop().await;
+        let err = grpc.subscribe_dex_events(vec![], vec![], None).await.err().unwrap();
+        assert!(matches!(err, GrpcClientError::Stopped));
+        assert!(grpc.last_error().is_none());
+    }
+
+    #[test]
+    fn test_retryable_status() {
+        assert!(GrpcClientError::SubscribeError(tonic::Status::unavailable("down")).is_retryable());
+        assert!(!GrpcClientError::SubscribeError(tonic::Status::permission_denied("token")).is_retryable());
+
+        let err = GrpcClientError::SubscribeError(tonic::Status::invalid_argument("blocks are disabled")).into_blocks_error();
+        assert!(matches!(&err, GrpcClientError::BlocksNotSupported(msg) if msg == "blocks are disabled"));
+        assert!(GrpcClientError::SubscribeError(tonic::Status::unavailable("down")).into_blocks_error().is_retryable());
+    }
+}
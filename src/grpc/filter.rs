@@ -1,10 +1,18 @@
 pub use crate::grpc::types::{
     TransactionFilter, AccountFilter, AccountFilterData, AccountFilterMemcmp,
-    EventTypeFilter,
+    BlockFilter, EventTypeFilter, TransactionStatusFilter,
 };
 
-use crate::grpc::types::Protocol;
+use crate::grpc::error::GrpcClientError;
+use crate::grpc::types::{ClientConfig, Protocol, ProtocolSet};
 use crate::grpc::program_ids::{get_program_ids_for_protocols, PROTOCOL_PROGRAM_IDS};
+use std::collections::HashMap;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_request_filter_accounts_filter::Filter, subscribe_request_filter_accounts_filter_memcmp::Data,
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts, SubscribeRequestFilterAccountsFilter,
+    SubscribeRequestFilterAccountsFilterMemcmp, SubscribeRequestFilterBlocks, SubscribeRequestFilterSlots,
+    SubscribeRequestFilterTransactions,
+};
 
 impl TransactionFilter {
     pub fn for_protocols(protocols: &[Protocol]) -> Self {
@@ -13,6 +21,16 @@ impl TransactionFilter {
             account_include: program_ids,
             account_exclude: Vec::new(),
             account_required: Vec::new(),
+            protocols: ProtocolSet::from(protocols),
+        }
+    }
+}
+
+impl BlockFilter {
+    pub fn for_protocols(protocols: &[Protocol]) -> Self {
+        Self {
+            account_include: get_program_ids_for_protocols(protocols),
+            protocols: ProtocolSet::from(protocols),
         }
     }
 }
@@ -26,4 +44,257 @@ impl AccountFilter {
             filters: Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// 构建订阅请求
+///
+/// - 兼容限制单个过滤器账户数的服务商：`account_include`（账户过滤器的 `account` / `owner`）
+///   超过 `config.max_filter_accounts` 时拆成多个命名过滤器 `tx_0`、`tx_1`……/ `account_0`……，
+///   同一字段内的账户本来就是"或"关系，拆分后语义不变；`account_exclude` / `account_required` 原样复制
+/// - 账户过滤器的 `filters`（memcmp / datasize）转换后复制到每个拆分出的过滤器
+/// - `config.slot_updates` 设置时加入 slot 订阅
+/// - 不带任何账户条件的过滤器会订阅全链数据，除非 `config.allow_firehose` 为 true，否则返回
+///   [`GrpcClientError::FirehoseNotAllowed`]；只带 memcmp / datasize 条件的账户过滤器不受此限制
+pub fn build_subscribe_request(
+    transaction_filters: &[TransactionFilter],
+    account_filters: &[AccountFilter],
+    config: &ClientConfig,
+) -> Result<SubscribeRequest, GrpcClientError> {
+    let max = config.max_filter_accounts.max(1);
+
+    let mut transactions = HashMap::new();
+    for filter in transaction_filters {
+        if filter.account_include.is_empty() && filter.account_required.is_empty() && !config.allow_firehose {
+            return Err(GrpcClientError::FirehoseNotAllowed("transaction"));
+        }
+        for include in chunks(&filter.account_include, max) {
+            transactions.insert(format!("tx_{}", transactions.len()), SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: Some(false),
+                signature: None,
+                account_include: include,
+                account_exclude: filter.account_exclude.clone(),
+                account_required: filter.account_required.clone(),
+            });
+        }
+    }
+
+    let mut accounts = HashMap::new();
+    for filter in account_filters {
+        // 只带 memcmp / datasize 条件的过滤器由服务端按数据筛选，不算全链订阅
+        if filter.account.is_empty() && filter.owner.is_empty() && filter.filters.is_empty() && !config.allow_firehose {
+            return Err(GrpcClientError::FirehoseNotAllowed("account"));
+        }
+        let data_filters = account_data_filters(&filter.filters);
+        // account 与 owner 之间是"与"关系，两者都拆分时取笛卡尔积
+        for account in chunks(&filter.account, max) {
+            for owner in chunks(&filter.owner, max) {
+                accounts.insert(format!("account_{}", accounts.len()), SubscribeRequestFilterAccounts {
+                    account: account.clone(),
+                    owner,
+                    filters: data_filters.clone(),
+                    nonempty_txn_signature: None,
+                });
+            }
+        }
+    }
+
+    let slots = config
+        .slot_updates
+        .iter()
+        .map(|filter| {
+            ("slot".to_string(), SubscribeRequestFilterSlots {
+                filter_by_commitment: None,
+                interslot_updates: Some(filter.interslot_updates),
+            })
+        })
+        .collect();
+
+    Ok(SubscribeRequest {
+        slots,
+        accounts,
+        transactions,
+        transactions_status: HashMap::new(),
+        blocks: HashMap::new(),
+        blocks_meta: HashMap::new(),
+        entry: HashMap::new(),
+        commitment: Some(CommitmentLevel::from(config.commitment) as i32),
+        accounts_data_slice: Vec::new(),
+        ping: None,
+        from_slot: None,
+    })
+}
+
+/// 构建区块订阅请求（`include_transactions`，不带账户和 entry）
+///
+/// 区块消息已包含完整交易，不拆分 `account_include`；为空时同样需要 `config.allow_firehose`。
+pub fn build_block_subscribe_request(filter: &BlockFilter, config: &ClientConfig) -> Result<SubscribeRequest, GrpcClientError> {
+    if filter.account_include.is_empty() && !config.allow_firehose {
+        return Err(GrpcClientError::FirehoseNotAllowed("block"));
+    }
+    let blocks = HashMap::from([(
+        "block".to_string(),
+        SubscribeRequestFilterBlocks {
+            account_include: filter.account_include.clone(),
+            include_transactions: Some(true),
+            include_accounts: Some(false),
+            include_entries: Some(false),
+        },
+    )]);
+    Ok(SubscribeRequest {
+        blocks,
+        commitment: Some(CommitmentLevel::from(config.commitment) as i32),
+        ..Default::default()
+    })
+}
+
+/// 构建交易状态订阅请求（只填 `transactions_status`）
+///
+/// 既没有签名也没有账户条件的过滤器会订阅全链交易状态，同样需要 `config.allow_firehose`。
+pub fn build_transaction_status_request(
+    filters: &[TransactionStatusFilter],
+    config: &ClientConfig,
+) -> Result<SubscribeRequest, GrpcClientError> {
+    let mut transactions_status = HashMap::new();
+    for filter in filters {
+        if filter.signature.is_none()
+            && filter.account_include.is_empty()
+            && filter.account_required.is_empty()
+            && !config.allow_firehose
+        {
+            return Err(GrpcClientError::FirehoseNotAllowed("transaction status"));
+        }
+        transactions_status.insert(format!("tx_status_{}", transactions_status.len()), SubscribeRequestFilterTransactions {
+            vote: Some(false),
+            // None 表示成功和失败的交易都推送
+            failed: (!filter.include_failed).then_some(false),
+            signature: filter.signature.clone(),
+            account_include: filter.account_include.clone(),
+            account_exclude: filter.account_exclude.clone(),
+            account_required: filter.account_required.clone(),
+        });
+    }
+    Ok(SubscribeRequest {
+        transactions_status,
+        commitment: Some(CommitmentLevel::from(config.commitment) as i32),
+        ..Default::default()
+    })
+}
+
+/// 转换为 gRPC 账户数据过滤条件（同一项中的 memcmp 与 datasize 拆成两条，服务端按"与"处理）
+fn account_data_filters(filters: &[AccountFilterData]) -> Vec<SubscribeRequestFilterAccountsFilter> {
+    filters
+        .iter()
+        .flat_map(|f| {
+            let memcmp = f.memcmp.as_ref().map(|m| {
+                Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                    offset: m.offset,
+                    data: Some(Data::Bytes(m.bytes.clone())),
+                })
+            });
+            memcmp.into_iter().chain(f.datasize.map(Filter::Datasize))
+        })
+        .map(|filter| SubscribeRequestFilterAccountsFilter { filter: Some(filter) })
+        .collect()
+}
+
+/// 按上限切分，空列表保留为一个空块
+fn chunks(values: &[String], max: usize) -> Vec<Vec<String>> {
+    if values.is_empty() {
+        return vec![Vec::new()];
+    }
+    values.chunks(max).map(<[String]>::to_vec).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("acct{}", i)).collect()
+    }
+
+    #[test]
+    fn test_large_filters_are_split() {
+        let config = ClientConfig { max_filter_accounts: 50, ..ClientConfig::default() };
+        let tx = TransactionFilter { account_include: keys(120), account_required: keys(1), ..TransactionFilter::new() };
+        let account = AccountFilter { account: keys(60), owner: keys(2), filters: Vec::new() };
+        let request = build_subscribe_request(&[tx], &[account], &config).unwrap();
+
+        assert_eq!(request.transactions.len(), 3);
+        let sizes: Vec<usize> = (0..3).map(|i| request.transactions[&format!("tx_{}", i)].account_include.len()).collect();
+        assert_eq!(sizes, vec![50, 50, 20]);
+        assert!(request.transactions.values().all(|f| f.account_required == keys(1)));
+        let mut included: Vec<String> =
+            request.transactions.values().flat_map(|f| f.account_include.clone()).collect();
+        included.sort();
+        let mut expected = keys(120);
+        expected.sort();
+        assert_eq!(included, expected);
+
+        assert_eq!(request.accounts.len(), 2);
+        assert!(request.accounts.values().all(|f| f.owner == keys(2)));
+        assert_eq!(request.accounts["account_1"].account.len(), 10);
+
+        // 未超过上限时不拆分
+        let request = build_subscribe_request(&[TransactionFilter::for_protocols(&[Protocol::PumpFun])], &[], &config).unwrap();
+        assert_eq!(request.transactions.len(), 1);
+        assert!(request.transactions.contains_key("tx_0"));
+    }
+
+    #[test]
+    fn test_firehose_guard() {
+        let config = ClientConfig::default();
+        let err = build_subscribe_request(&[TransactionFilter::new()], &[], &config).unwrap_err();
+        assert!(matches!(err, GrpcClientError::FirehoseNotAllowed("transaction")));
+        let err = build_subscribe_request(&[], &[AccountFilter::new()], &config).unwrap_err();
+        assert!(matches!(err, GrpcClientError::FirehoseNotAllowed("account")));
+        let err = build_block_subscribe_request(&BlockFilter::new(), &config).unwrap_err();
+        assert!(matches!(err, GrpcClientError::FirehoseNotAllowed("block")));
+
+        // 只有数据条件（memcmp 或 datasize）的账户过滤器不是全链订阅
+        for data in [AccountFilterData::memcmp(0, [7u8; 32]), AccountFilterData::datasize(165)] {
+            let request = build_subscribe_request(&[], &[AccountFilter::new().add_filter(data)], &config).unwrap();
+            assert_eq!(request.accounts["account_0"].filters.len(), 1);
+        }
+
+        let config = ClientConfig { allow_firehose: true, ..config };
+        let request = build_subscribe_request(&[TransactionFilter::new()], &[], &config).unwrap();
+        assert!(request.transactions["tx_0"].account_include.is_empty());
+        let request = build_block_subscribe_request(&BlockFilter::new(), &config).unwrap();
+        assert_eq!(request.blocks["block"].include_transactions, Some(true));
+    }
+
+    #[test]
+    fn test_memcmp_rule_is_translated() {
+        let mint = [7u8; 32];
+        let account = AccountFilter::new()
+            .add_owner("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
+            .add_filter(AccountFilterData::memcmp(0, mint))
+            .add_filter(AccountFilterData::datasize(165));
+        let request = build_subscribe_request(&[], &[account], &ClientConfig::default()).unwrap();
+
+        let filters = &request.accounts["account_0"].filters;
+        assert_eq!(filters.len(), 2);
+        let Some(Filter::Memcmp(memcmp)) = &filters[0].filter else { panic!("{:?}", filters[0]) };
+        assert_eq!(memcmp.offset, 0);
+        assert_eq!(memcmp.data, Some(Data::Bytes(mint.to_vec())));
+        assert_eq!(filters[1].filter, Some(Filter::Datasize(165)));
+    }
+
+    #[test]
+    fn test_transaction_status_request() {
+        let config = ClientConfig::default();
+        let filter = TransactionStatusFilter::for_signature("sig").with_failed(false);
+        let request = build_transaction_status_request(&[filter], &config).unwrap();
+        assert!(request.transactions.is_empty());
+        let status = &request.transactions_status["tx_status_0"];
+        assert_eq!(status.signature.as_deref(), Some("sig"));
+        assert_eq!(status.failed, Some(false));
+
+        assert!(matches!(
+            build_transaction_status_request(&[TransactionStatusFilter::new()], &config),
+            Err(GrpcClientError::FirehoseNotAllowed(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! 超大交易保护
+//!
+//! 个别交易带有数百 KB 的日志，解析耗时可达毫秒级并拖慢整个订阅流。[`TxSizeGuard`] 在解析之前
+//! 检查日志条数、单行日志长度和指令数（外层 + 内层），超限时按 [`OversizedPolicy`] 跳过或截断。
+//! 检查只读取各字段长度，不扫描日志内容。
+//!
+//! 启用保护或 `enable_metrics` 时，每笔交易的日志条数和字节数记录到 [`TxSizeStats`]，用于调整上限。
+
+use super::broadcast::EventSink;
+use crate::common::latency::{LatencyHistogram, LatencySummary};
+use crate::core::events::DexEvent;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use yellowstone_grpc_proto::prelude::SubscribeUpdateTransactionInfo;
+
+/// 超限时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OversizedPolicy {
+    /// 跳过整笔交易
+    #[default]
+    Skip,
+    /// 丢弃超长的日志行，再只保留前 N 条日志 / 前 N 条外层指令
+    ///
+    /// 超长行整行丢弃而不是截断：截断的 `Program data:` 行解码后是错误的事件。
+    Truncate,
+}
+
+/// 交易大小上限，各项为 0 表示不限制（默认全部不限制）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSizeGuard {
+    /// 每笔交易的日志条数上限
+    #[serde(default)]
+    pub max_logs_per_tx: usize,
+    /// 单行日志的字节数上限
+    #[serde(default)]
+    pub max_log_line_len: usize,
+    /// 每笔交易的指令数上限（外层 + 内层）
+    #[serde(default)]
+    pub max_instructions_per_tx: usize,
+    #[serde(default)]
+    pub policy: OversizedPolicy,
+    /// 跳过交易时输出一个 `DexEvent::Error`（内容以 `Oversized` 开头）
+    #[serde(default)]
+    pub emit_error: bool,
+}
+
+/// 超出的上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedReason {
+    Logs { count: usize, max: usize },
+    LogLine { len: usize, max: usize },
+    Instructions { count: usize, max: usize },
+}
+
+impl fmt::Display for OversizedReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Logs { count, max } => write!(f, "{} log lines exceed limit {}", count, max),
+            Self::LogLine { len, max } => write!(f, "log line of {} bytes exceeds limit {}", len, max),
+            Self::Instructions { count, max } => write!(f, "{} instructions exceed limit {}", count, max),
+        }
+    }
+}
+
+impl TxSizeGuard {
+    /// 是否设置了任一上限
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.max_logs_per_tx > 0 || self.max_log_line_len > 0 || self.max_instructions_per_tx > 0
+    }
+
+    /// 检查交易是否超限，返回第一个超出的上限
+    pub fn check(&self, info: &SubscribeUpdateTransactionInfo) -> Option<OversizedReason> {
+        let logs = info.meta.as_ref().map_or(&[][..], |meta| &meta.log_messages[..]);
+        if self.max_logs_per_tx > 0 && logs.len() > self.max_logs_per_tx {
+            return Some(OversizedReason::Logs { count: logs.len(), max: self.max_logs_per_tx });
+        }
+        if self.max_log_line_len > 0 {
+            if let Some(len) = logs.iter().map(String::len).find(|&len| len > self.max_log_line_len) {
+                return Some(OversizedReason::LogLine { len, max: self.max_log_line_len });
+            }
+        }
+        if self.max_instructions_per_tx > 0 {
+            let count = instruction_count(info);
+            if count > self.max_instructions_per_tx {
+                return Some(OversizedReason::Instructions { count, max: self.max_instructions_per_tx });
+            }
+        }
+        None
+    }
+
+    /// 记录大小并执行保护：返回 `Err` 表示应跳过该交易，`Truncate` 策略下原地截断后返回 `Ok`
+    pub(crate) fn apply(
+        &self,
+        info: &mut SubscribeUpdateTransactionInfo,
+        stats: &TxSizeStats,
+    ) -> Result<(), OversizedReason> {
+        stats.record(info);
+        let Some(reason) = self.check(info) else { return Ok(()) };
+        match self.policy {
+            OversizedPolicy::Skip => {
+                stats.skipped.fetch_add(1, Ordering::Relaxed);
+                Err(reason)
+            }
+            OversizedPolicy::Truncate => {
+                stats.truncated.fetch_add(1, Ordering::Relaxed);
+                self.truncate(info);
+                Ok(())
+            }
+        }
+    }
+
+    fn truncate(&self, info: &mut SubscribeUpdateTransactionInfo) {
+        if let Some(meta) = info.meta.as_mut() {
+            if self.max_log_line_len > 0 {
+                meta.log_messages.retain(|line| line.len() <= self.max_log_line_len);
+            }
+            if self.max_logs_per_tx > 0 {
+                meta.log_messages.truncate(self.max_logs_per_tx);
+            }
+        }
+        if self.max_instructions_per_tx > 0 && instruction_count(info) > self.max_instructions_per_tx {
+            let max = self.max_instructions_per_tx;
+            let message = info.transaction.as_mut().and_then(|tx| tx.message.as_mut());
+            let outer = message.map_or(0, |message| {
+                message.instructions.truncate(max);
+                message.instructions.len()
+            });
+            // 剩余名额按外层顺序分给内层指令，超出的整组丢弃
+            if let Some(meta) = info.meta.as_mut() {
+                let mut budget = max - outer;
+                meta.inner_instructions.retain_mut(|group| {
+                    if group.index as usize >= outer || budget == 0 {
+                        return false;
+                    }
+                    group.instructions.truncate(budget);
+                    budget -= group.instructions.len();
+                    true
+                });
+            }
+        }
+    }
+}
+
+/// 对收到的交易执行保护，返回 false 表示跳过（`emit_error` 时向 `sink` 输出 `DexEvent::Error`）
+pub(crate) fn admit<S: EventSink>(
+    guard: &TxSizeGuard,
+    info: &mut SubscribeUpdateTransactionInfo,
+    stats: &TxSizeStats,
+    sink: &S,
+) -> bool {
+    let Err(reason) = guard.apply(info, stats) else { return true };
+    let signature = info.transaction.as_ref().and_then(|tx| tx.signatures.first()).map(|s| bs58::encode(s).into_string());
+    warn!("skipping oversized transaction {}: {}", signature.as_deref().unwrap_or("-"), reason);
+    if guard.emit_error {
+        sink.push_event(DexEvent::Error(format!("Oversized transaction {}: {}", signature.as_deref().unwrap_or("-"), reason)));
+    }
+    false
+}
+
+/// 外层 + 内层指令数
+#[inline]
+fn instruction_count(info: &SubscribeUpdateTransactionInfo) -> usize {
+    let outer = info.transaction.as_ref().and_then(|tx| tx.message.as_ref()).map_or(0, |m| m.instructions.len());
+    let inner = info.meta.as_ref().map_or(0, |meta| meta.inner_instructions.iter().map(|g| g.instructions.len()).sum());
+    outer + inner
+}
+
+/// 交易大小分布与超限计数
+#[derive(Default)]
+pub struct TxSizeStats {
+    log_count: LatencyHistogram,
+    bytes: LatencyHistogram,
+    skipped: AtomicU64,
+    truncated: AtomicU64,
+}
+
+/// [`TxSizeStats`] 快照；直方图沿用 [`LatencySummary`]，其中的值为条数 / 字节数而不是微秒
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxSizeSnapshot {
+    /// 每笔交易的日志条数
+    pub log_count: LatencySummary,
+    /// 每笔交易的日志与指令数据字节数
+    pub bytes: LatencySummary,
+    /// 超限被跳过的交易数
+    pub skipped: u64,
+    /// 超限被截断的交易数
+    pub truncated: u64,
+}
+
+impl TxSizeStats {
+    #[inline]
+    fn record(&self, info: &SubscribeUpdateTransactionInfo) {
+        let logs = info.meta.as_ref().map_or(&[][..], |meta| &meta.log_messages[..]);
+        let log_bytes: usize = logs.iter().map(String::len).sum();
+        let outer = info.transaction.as_ref().and_then(|tx| tx.message.as_ref()).map_or(0, |m| {
+            m.instructions.iter().map(|ix| ix.data.len()).sum::<usize>()
+        });
+        let inner = info.meta.as_ref().map_or(0, |meta| {
+            meta.inner_instructions.iter().flat_map(|g| &g.instructions).map(|ix| ix.data.len()).sum::<usize>()
+        });
+        self.log_count.record(logs.len() as u64);
+        self.bytes.record((log_bytes + outer + inner) as u64);
+    }
+
+    pub fn snapshot(&self) -> TxSizeSnapshot {
+        TxSizeSnapshot {
+            log_count: self.log_count.summary(),
+            bytes: self.bytes.summary(),
+            skipped: self.skipped.load(Ordering::Relaxed),
+            truncated: self.truncated.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,357 @@
+//! 离线模拟的事件流
+//!
+//! [`MockYellowstone`] 实现与真实客户端相同的 [`DexEventSource`] 接口，不连接任何节点：
+//! - 生成模式：用 [`EventGenerator`] 按速率和事件分布合成事件，同一种子的输出可复现
+//! - 回放模式：读取抓包文件（格式见 [`super::replay`]），走与实时订阅相同的解析路径
+//!
+//! 两种模式都执行 [`EventTypeFilter`]，`grpc_recv_us` 为事件交付时的本地时间。
+//! 生成模式不检查交易 / 账户过滤器；回放模式只用交易过滤器推导预过滤的程序集合。
+//! 每次订阅从头开始生成 / 回放，互不影响。
+
+use super::broadcast::{EventSink, InlineSink, TrackingSink};
+use super::client::YellowstoneGrpc;
+use super::error::GrpcClientError;
+use super::prefilter::TrackedPrograms;
+use super::queue::DexEventQueue;
+use super::replay::{self, read_updates, ReplaySpeed};
+use super::source::DexEventSource;
+use super::types::{AccountFilter, ClientConfig, EventType, EventTypeFilter, TransactionFilter};
+use crate::common::time::now_micros;
+use crate::core::events::DexEvent;
+use crate::testing::{EventGenerator, EventMix};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::watch;
+use yellowstone_grpc_proto::prelude::subscribe_update;
+
+#[derive(Clone)]
+enum Feed {
+    Generated { seed: u64, rate: u64, mix: EventMix, limit: usize },
+    Capture { path: PathBuf, speed: ReplaySpeed },
+}
+
+/// 模拟的 Yellowstone 客户端（Clone 后共享停止状态）
+#[derive(Clone)]
+pub struct MockYellowstone {
+    feed: Feed,
+    config: ClientConfig,
+    stop_tx: Arc<watch::Sender<bool>>,
+}
+
+impl MockYellowstone {
+    /// 生成模式：固定种子，默认每秒 1000 个事件、[`EventMix::default`] 分布、不限数量
+    pub fn generator(seed: u64) -> Self {
+        Self::with_feed(Feed::Generated { seed, rate: 1_000, mix: EventMix::default(), limit: usize::MAX })
+    }
+
+    /// 回放模式：订阅时读取抓包文件
+    pub fn from_capture(path: impl Into<PathBuf>, speed: ReplaySpeed) -> Self {
+        Self::with_feed(Feed::Capture { path: path.into(), speed })
+    }
+
+    fn with_feed(feed: Feed) -> Self {
+        Self { feed, config: ClientConfig::default(), stop_tx: Arc::new(watch::channel(false).0) }
+    }
+
+    /// 每秒事件数（0 表示不限速，仅生成模式）
+    pub fn with_rate(mut self, events_per_sec: u64) -> Self {
+        if let Feed::Generated { rate, .. } = &mut self.feed {
+            *rate = events_per_sec;
+        }
+        self
+    }
+
+    /// 事件类型分布（仅生成模式）
+    pub fn with_mix(mut self, event_mix: EventMix) -> Self {
+        if let Feed::Generated { mix, .. } = &mut self.feed {
+            *mix = event_mix;
+        }
+        self
+    }
+
+    /// 每次订阅生成的事件数上限，达到后停止（仅生成模式）
+    pub fn with_limit(mut self, count: usize) -> Self {
+        if let Feed::Generated { limit, .. } = &mut self.feed {
+            *limit = count;
+        }
+        self
+    }
+
+    /// 使用客户端配置中的 `queue_capacity`、`first_event_only` 和 `dedup`
+    pub fn with_config(mut self, config: ClientConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    async fn start<S: EventSink>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        sink: Arc<S>,
+    ) -> Result<(), GrpcClientError> {
+        if *self.stop_tx.borrow() {
+            return Err(GrpcClientError::Stopped);
+        }
+        let stop_rx = self.stop_tx.subscribe();
+        let sink = TrackingSink::new(sink, None).with_dedup(self.config.dedup);
+        match self.feed.clone() {
+            Feed::Generated { seed, rate, mix, limit } => {
+                std::thread::spawn(move || {
+                    let mut generator = EventGenerator::new(seed).with_mix(mix).with_rate(rate);
+                    generator.run_while(limit, |mut event| {
+                        if *stop_rx.borrow() {
+                            return false;
+                        }
+                        // 每个生成事件自成一笔交易；与实时路径一样先标记 dev buy 再按内容过滤
+                        crate::core::merger::mark_dev_buys(std::slice::from_mut(&mut event));
+                        let included = match (&event_type_filter, EventType::from_event(&event)) {
+                            (Some(filter), Some(event_type)) => filter.should_include(event_type) && filter.accepts_event(&event),
+                            _ => true,
+                        };
+                        if included {
+                            if let Some(metadata) = event.metadata_mut() {
+                                metadata.grpc_recv_us = now_micros();
+                                metadata.handle_us = metadata.grpc_recv_us;
+                            }
+                            sink.push_event(event);
+                        }
+                        true
+                    });
+                });
+            }
+            Feed::Capture { path, speed } => {
+                let file_error = |e: String| GrpcClientError::ReplayFile(format!("{}: {}", path.display(), e));
+                let bytes = tokio::fs::read(&path).await.map_err(|e| file_error(e.to_string()))?;
+                let updates = read_updates(&bytes).map_err(file_error)?;
+                let tracked = TrackedPrograms::from_filters(&transaction_filters, event_type_filter.as_ref());
+                let first_event_only = self.config.first_event_only;
+                let options = self.config.parse_options();
+                let cross_venue = self.config.detect_cross_venue_trades;
+                tokio::spawn(async move {
+                    let mut previous = None;
+                    for update in &updates {
+                        if *stop_rx.borrow() {
+                            return;
+                        }
+                        if let (ReplaySpeed::Original, Some(previous)) = (speed, previous) {
+                            tokio::time::sleep(replay::gap(previous, update)).await;
+                        }
+                        previous = Some(update);
+                        if let Some(subscribe_update::UpdateOneof::Transaction(transaction_update)) = &update.update_oneof {
+                            YellowstoneGrpc::parse_transaction(
+                                transaction_update,
+                                now_micros(),
+                                &sink,
+                                event_type_filter.as_ref(),
+                                Some(&tracked),
+                                first_event_only,
+                                options,
+                                cross_venue,
+                            );
+                        }
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DexEventSource for MockYellowstone {
+    async fn subscribe_dex_events(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        _account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+    ) -> Result<DexEventQueue, GrpcClientError> {
+        let queue = DexEventQueue::new(self.config.queue_capacity);
+        self.start(transaction_filters, event_type_filter, Arc::new(queue.clone())).await?;
+        Ok(queue)
+    }
+
+    async fn subscribe_dex_events_inline<F>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        _account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        callback: F,
+    ) -> Result<(), GrpcClientError>
+    where
+        F: Fn(DexEvent) + Send + 'static,
+    {
+        self.start(transaction_filters, event_type_filter, Arc::new(InlineSink::new(callback))).await
+    }
+
+    async fn stop(&self) {
+        self.stop_tx.send_replace(true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn collect(mock: &MockYellowstone, filter: EventTypeFilter) -> Vec<DexEvent> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        mock.subscribe_dex_events_inline(vec![], vec![], Some(filter), move |event| {
+            let _ = tx.send(event);
+        })
+        .await
+        .unwrap();
+        // 生成线程结束后发送端随回调一起释放
+        rx.iter().collect()
+    }
+
+    #[tokio::test]
+    async fn test_generator_honors_filter_and_seed() {
+        let mock = MockYellowstone::generator(11).with_rate(0).with_limit(500);
+        let filter = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]);
+
+        let events = collect(&mock, filter.clone()).await;
+        assert!(!events.is_empty() && events.len() < 500);
+        assert!(events.iter().all(|e| matches!(e, DexEvent::PumpFunTrade(_))));
+        let before = now_micros();
+        assert!(events.iter().all(|e| e.metadata().unwrap().grpc_recv_us <= before));
+
+        let signatures = |events: &[DexEvent]| events.iter().map(|e| e.metadata().unwrap().signature).collect::<Vec<_>>();
+        assert_eq!(signatures(&events), signatures(&collect(&mock, filter.clone()).await));
+
+        mock.stop().await;
+        assert!(matches!(
+            mock.subscribe_dex_events(vec![], vec![], None).await,
+            Err(GrpcClientError::Stopped)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_inline_callback_need_not_be_sync() {
+        // Cell 不是 Sync：回调只需要 Send
+        let seen = std::cell::Cell::new(0usize);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mock = MockYellowstone::generator(3).with_rate(0).with_limit(50);
+        mock.subscribe_dex_events_inline(vec![], vec![], None, move |_| {
+            seen.set(seen.get() + 1);
+            let _ = tx.send(seen.get());
+        })
+        .await
+        .unwrap();
+        let counts: Vec<usize> = rx.iter().collect();
+        assert!(!counts.is_empty());
+        assert_eq!(counts, (1..=counts.len()).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_transform_maps_and_filters() {
+        #[derive(Debug)]
+        struct Fill {
+            mint: solana_sdk::pubkey::Pubkey,
+            sol_amount: u64,
+            is_buy: bool,
+        }
+
+        let mock = MockYellowstone::generator(7).with_rate(0).with_limit(300);
+        let mut rx = mock
+            .subscribe_dex_events_with_transform(vec![], vec![], None, 1_000, |event| match event {
+                DexEvent::PumpFunTrade(t) => Some(Fill { mint: t.mint, sol_amount: t.sol_amount, is_buy: t.is_buy }),
+                _ => None,
+            })
+            .await
+            .unwrap();
+
+        let mut fills = Vec::new();
+        while let Some(fill) = rx.recv().await {
+            fills.push(fill);
+        }
+        // 与直接订阅、只保留 PumpFunTrade 的结果一致
+        let trades = collect(&mock, EventTypeFilter::include_only(vec![EventType::PumpFunTrade])).await;
+        assert!(!fills.is_empty() && fills.len() < 300);
+        assert_eq!(fills.len(), trades.len());
+        for (fill, trade) in fills.iter().zip(&trades) {
+            let DexEvent::PumpFunTrade(t) = trade else { unreachable!() };
+            assert_eq!((fill.mint, fill.sol_amount, fill.is_buy), (t.mint, t.sol_amount, t.is_buy));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_listener_records_events() {
+        use crate::core::unified_parser::AsyncEventListener;
+        use parking_lot::Mutex;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct Recorder {
+            events: Mutex<Vec<DexEvent>>,
+            in_flight: AtomicUsize,
+            max_in_flight: AtomicUsize,
+        }
+
+        impl AsyncEventListener for Recorder {
+            async fn on_dex_event(&self, event: &DexEvent) {
+                let n = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                self.max_in_flight.fetch_max(n, Ordering::SeqCst);
+                // 模拟一次数据库写入
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                self.events.lock().push(event.clone());
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let mock = MockYellowstone::generator(5).with_rate(0).with_limit(100);
+        let filter = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]);
+        let recorder = Arc::new(Recorder::default());
+        let handle = mock
+            .subscribe_with_async_listener(vec![], vec![], Some(filter.clone()), recorder.clone(), 4, 1_000)
+            .await
+            .unwrap();
+        // 模拟流结束后，处理任务处理完剩余事件再退出
+        handle.await.unwrap();
+
+        let mut recorded: Vec<_> = recorder.events.lock().iter().map(|e| e.metadata().unwrap().signature).collect();
+        let mut expected: Vec<_> = collect(&mock, filter).await.iter().map(|e| e.metadata().unwrap().signature).collect();
+        assert!(!expected.is_empty());
+        recorded.sort();
+        expected.sort();
+        assert_eq!(recorded, expected);
+        assert!((1..=4).contains(&recorder.max_in_flight.load(Ordering::SeqCst)));
+    }
+
+    #[tokio::test]
+    async fn test_async_listener_counts_drops_and_panics() {
+        use crate::core::unified_parser::AsyncEventListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// 处理很慢，且每收到第二个事件 panic 一次
+        #[derive(Default)]
+        struct Flaky {
+            seen: AtomicUsize,
+            handled: AtomicUsize,
+        }
+
+        impl AsyncEventListener for Flaky {
+            async fn on_dex_event(&self, _event: &DexEvent) {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                if self.seen.fetch_add(1, Ordering::SeqCst) % 2 == 1 {
+                    panic!("listener failure");
+                }
+                self.handled.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mock = MockYellowstone::generator(5).with_rate(0).with_limit(100);
+        let filter = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]);
+        let listener = Arc::new(Flaky::default());
+        let mut handle = mock
+            .subscribe_with_async_listener(vec![], vec![], Some(filter.clone()), listener.clone(), 1, 1)
+            .await
+            .unwrap();
+        // panic 不会终止处理任务
+        (&mut handle).await.unwrap();
+
+        let expected = collect(&mock, filter).await.len() as u64;
+        let (seen, handled) = (listener.seen.load(Ordering::SeqCst) as u64, listener.handled.load(Ordering::SeqCst) as u64);
+        assert!(handle.dropped() > 0);
+        assert_eq!(seen + handle.dropped(), expected);
+        assert_eq!(handle.panicked(), seen / 2);
+        assert_eq!(handled + handle.panicked(), seen);
+    }
+}
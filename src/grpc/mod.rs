@@ -5,19 +5,105 @@
 //! - 事件类型过滤
 //! - 账户和交易过滤
 //! - 多协议支持（PumpFun, Bonk, Raydium等）
+//!
+//! 只启用 `parse` feature 时仅保留解析器用到的 `types`（协议、事件类型及过滤）和 `program_ids`。
 
-pub mod client;
 pub mod types;
+pub mod program_ids;
+#[cfg(feature = "grpc")]
+pub mod client;
+#[cfg(feature = "grpc")]
 pub mod config;
+#[cfg(feature = "grpc")]
 pub mod filter;
-pub mod program_ids;
+#[cfg(feature = "grpc")]
 pub mod event_parser;
+#[cfg(feature = "grpc")]
+pub mod queue;
+#[cfg(feature = "grpc")]
+pub mod consumer;
+#[cfg(feature = "grpc")]
+pub mod broadcast;
+#[cfg(feature = "grpc")]
+pub mod tls;
+#[cfg(feature = "grpc")]
+pub mod stream_health;
+#[cfg(feature = "grpc")]
+pub mod error;
+#[cfg(feature = "grpc")]
+pub mod auth;
+#[cfg(feature = "grpc")]
+pub mod prefilter;
+#[cfg(feature = "grpc")]
+pub mod pause;
+#[cfg(feature = "grpc")]
+pub mod guard;
+#[cfg(feature = "grpc")]
+pub mod dedup;
+#[cfg(feature = "grpc")]
+pub mod replay;
+#[cfg(feature = "grpc")]
+pub mod workers;
+#[cfg(feature = "grpc")]
+pub mod source;
+#[cfg(feature = "grpc")]
+pub mod mock;
+#[cfg(feature = "grpc")]
+pub mod enrich;
+#[cfg(feature = "grpc")]
+pub mod block;
+#[cfg(feature = "grpc")]
+pub mod status;
+#[cfg(feature = "rpc")]
+pub mod rpc;
 
 // 重新导出主要API，保持兼容性
+pub use types::{EnabledProtocols, Protocol, ProtocolSet, EventType as StreamingEventType, TransactionFilter, TransactionStatusFilter, AccountFilter, BlockFilter, EventTypeFilter, SlotFilter};
+#[cfg(feature = "grpc")]
 pub use client::YellowstoneGrpc;
-pub use types::{ClientConfig, Protocol, EventType as StreamingEventType, TransactionFilter, AccountFilter, EventTypeFilter, SlotFilter};
+#[cfg(feature = "grpc")]
+pub use error::GrpcClientError;
+#[cfg(feature = "grpc")]
+pub use auth::{AuthError, AuthToken};
+#[cfg(feature = "grpc")]
+pub use queue::DexEventQueue;
+#[cfg(feature = "grpc")]
+pub use consumer::{ConsumerHandle, ConsumerOptions};
+#[cfg(feature = "grpc")]
+pub use broadcast::{BroadcastConsumer, BroadcastQueues};
+#[cfg(feature = "grpc")]
+pub use config::{ClientConfigBuilder, Commitment, ConfigError};
+#[cfg(feature = "grpc")]
+pub use tls::{TlsConfigError, TlsMode};
+#[cfg(feature = "grpc")]
+pub use stream_health::{StreamEnd, StreamStatsSnapshot};
+#[cfg(feature = "grpc")]
+pub use types::ClientConfig;
+#[cfg(feature = "grpc")]
+pub use prefilter::{TrackedPrograms, TrackedWallets, WalletFilter};
+#[cfg(feature = "grpc")]
+pub use pause::{PausePolicy, SubscriptionHandle};
+#[cfg(feature = "grpc")]
+pub use guard::{OversizedPolicy, OversizedReason, TxSizeGuard, TxSizeSnapshot};
+#[cfg(feature = "grpc")]
+pub use dedup::DedupConfig;
+#[cfg(feature = "grpc")]
+pub use replay::ReplaySpeed;
+#[cfg(feature = "grpc")]
+pub use source::{AsyncListenerHandle, DexEventSource};
+#[cfg(feature = "grpc")]
+pub use mock::MockYellowstone;
+#[cfg(feature = "grpc")]
+pub use block::ParsedBlock;
+#[cfg(feature = "grpc")]
+pub use status::TransactionStatus;
+#[cfg(feature = "rpc")]
+pub use rpc::{fetch_and_parse, RpcFetchError};
+#[cfg(feature = "grpc")]
+pub use enrich::{EnrichedEvent, FnResolver, NoopResolver, TokenMeta, TokenMetadataCache, TokenMetadataResolver};
 
 // 事件解析器重新导出
+#[cfg(feature = "grpc")]
 pub use event_parser::*;
 
 // 兼容性别名
@@ -0,0 +1,194 @@
+//! 可暂停的订阅
+//!
+//! 暂停期间接收任务照常读流（不触发服务端背压或断开，过滤器无需重新预热），
+//! 解析出的事件按 [`PausePolicy`] 丢弃或缓冲；恢复时按原顺序冲刷缓冲区。
+//! 缓冲事件保留原有的 `grpc_recv_us`，延迟统计包含暂停时长。
+
+use super::broadcast::EventSink;
+use super::queue::DroppedRanges;
+use crate::core::events::DexEvent;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// 暂停期间如何处理事件
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PausePolicy {
+    /// 直接丢弃
+    #[default]
+    Drop,
+    /// 最多缓冲 n 个事件，超出的新事件丢弃
+    Buffer(usize),
+}
+
+/// 暂停状态与缓冲区，包在下游 sink 外面
+pub(crate) struct PausableSink {
+    inner: Arc<dyn EventSink>,
+    policy: PausePolicy,
+    paused: AtomicBool,
+    buffer: Mutex<VecDeque<DexEvent>>,
+    dropped: DroppedRanges,
+}
+
+impl PausableSink {
+    pub(crate) fn new(inner: Arc<dyn EventSink>, policy: PausePolicy) -> Self {
+        Self { inner, policy, paused: AtomicBool::new(false), buffer: Mutex::new(VecDeque::new()), dropped: DroppedRanges::default() }
+    }
+}
+
+impl EventSink for PausableSink {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        if !self.paused.load(Ordering::Acquire) {
+            return self.inner.push_event(event);
+        }
+        let mut buffer = self.buffer.lock();
+        // 拿到锁时可能已经恢复（缓冲区已冲刷），直接下发以保持顺序
+        if !self.paused.load(Ordering::Acquire) {
+            return self.inner.push_event(event);
+        }
+        match self.policy {
+            PausePolicy::Buffer(max) if buffer.len() < max => buffer.push_back(event),
+            _ => self.dropped.record(&event),
+        }
+    }
+}
+
+/// 订阅句柄：暂停/恢复事件下发，或停止订阅
+///
+/// 可克隆，所有克隆控制同一个订阅。
+#[derive(Clone)]
+pub struct SubscriptionHandle {
+    sink: Arc<PausableSink>,
+    stop_tx: Arc<watch::Sender<bool>>,
+}
+
+impl SubscriptionHandle {
+    pub(crate) fn new(sink: Arc<PausableSink>, stop_tx: Arc<watch::Sender<bool>>) -> Self {
+        Self { sink, stop_tx }
+    }
+
+    /// 暂停下发，之后的事件按 [`PausePolicy`] 丢弃或缓冲
+    pub fn pause(&self) {
+        self.sink.paused.store(true, Ordering::Release);
+    }
+
+    /// 恢复下发，先按原顺序冲刷缓冲的事件
+    pub fn resume(&self) {
+        let mut buffer = self.sink.buffer.lock();
+        for event in buffer.drain(..) {
+            self.sink.inner.push_event(event);
+        }
+        self.sink.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.sink.paused.load(Ordering::Acquire)
+    }
+
+    /// 当前缓冲的事件数
+    pub fn buffered(&self) -> usize {
+        self.sink.buffer.lock().len()
+    }
+
+    /// 暂停期间丢弃的事件数（不含下游队列满时的丢弃）
+    pub fn dropped(&self) -> u64 {
+        self.sink.dropped.count()
+    }
+
+    /// 暂停期间丢弃事件的序号区间
+    pub fn dropped_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        self.sink.dropped.ranges()
+    }
+
+    /// 停止订阅，与 [`super::YellowstoneGrpc::stop`] 相同（同一客户端的其它订阅也会停止）
+    pub fn stop(&self) {
+        self.stop_tx.send_replace(true);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        *self.stop_tx.borrow()
+    }
+
+    /// 等待订阅停止
+    pub(crate) async fn stopped(&self) {
+        let _ = self.stop_tx.subscribe().wait_for(|stopped| *stopped).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{EventMetadata, EventSource};
+    use crossbeam_queue::ArrayQueue;
+    use solana_sdk::signature::Signature;
+
+    fn event(sequence: u64, grpc_recv_us: i64) -> DexEvent {
+        DexEvent::BlockMeta(crate::core::events::BlockMetaEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot: sequence,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+        })
+    }
+
+    fn handle(policy: PausePolicy) -> (SubscriptionHandle, Arc<PausableSink>, Arc<ArrayQueue<DexEvent>>) {
+        let queue = Arc::new(ArrayQueue::new(16));
+        let sink = Arc::new(PausableSink::new(queue.clone(), policy));
+        (SubscriptionHandle::new(sink.clone(), Arc::new(watch::channel(false).0)), sink, queue)
+    }
+
+    fn sequences(queue: &ArrayQueue<DexEvent>) -> Vec<(u64, i64)> {
+        std::iter::from_fn(|| queue.pop()).map(|e| (e.metadata().unwrap().sequence, e.metadata().unwrap().grpc_recv_us)).collect()
+    }
+
+    #[test]
+    fn test_pause_buffer_resume_in_order() {
+        let (handle, sink, queue) = handle(PausePolicy::Buffer(2));
+        sink.push_event(event(1, 100));
+        handle.pause();
+        assert!(handle.is_paused());
+        for seq in 2..=5 {
+            sink.push_event(event(seq, 100 * seq as i64));
+        }
+        assert_eq!(sequences(&queue), vec![(1, 100)]);
+        assert_eq!(handle.buffered(), 2);
+        assert_eq!(handle.dropped(), 2);
+        assert_eq!(handle.dropped_ranges(), vec![4..=5]);
+
+        handle.resume();
+        sink.push_event(event(6, 600));
+        assert!(!handle.is_paused());
+        assert_eq!(sequences(&queue), vec![(2, 200), (3, 300), (6, 600)]);
+    }
+
+    #[test]
+    fn test_pause_drop_and_stop() {
+        let (handle, sink, queue) = handle(PausePolicy::Drop);
+        handle.pause();
+        sink.push_event(event(1, 0));
+        handle.resume();
+        sink.push_event(event(2, 0));
+        assert_eq!(sequences(&queue), vec![(2, 0)]);
+        assert_eq!(handle.dropped(), 1);
+
+        assert!(!handle.is_stopped());
+        handle.clone().stop();
+        assert!(handle.is_stopped());
+    }
+}
@@ -0,0 +1,225 @@
+//! 交易预过滤
+//!
+//! 服务端过滤器较宽（全链、按钱包订阅）时，大部分交易与已支持的协议无关。
+//! [`TrackedPrograms`] 在解码账户列表和签名之前判断交易是否可能产生事件：
+//! - 外层指令的 `program_id_index` 指向的 32 字节 key 直接与跟踪集合比较，不构造 `Pubkey`
+//! - 日志中只看 `Program <id> invoke [n]` 行，取出程序 ID 字符串查表（CPI 调用只出现在日志里）
+//!
+//! 两者都未命中的交易直接跳过。运行时注册了协议（[`ParserRegistry`](crate::core::registry::ParserRegistry)）
+//! 时由调用方绕过预过滤。
+//!
+//! 跟踪大量钱包时（放进 `account_include` 会被服务商拒绝），用 [`WalletFilter`] 在客户端按账户 key 预过滤：
+//! 静态账户和地址查找表加载的账户中至少有一个是跟踪的钱包，交易才进入解析。钱包集合可在运行时整体替换。
+
+use crate::grpc::types::{EventTypeFilter, Protocol, ProtocolSet, TransactionFilter};
+use arc_swap::ArcSwap;
+use rustc_hash::FxHashSet;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use yellowstone_grpc_proto::prelude::{Message, SubscribeUpdateTransactionInfo};
+
+/// 订阅跟踪的程序集合
+///
+/// 集合只有十几个程序，先按首字节位图排除，再线性比较，比哈希查找更快。
+pub struct TrackedPrograms {
+    keys: Vec<[u8; 32]>,
+    /// base58 形式，日志中的程序 ID 不需要解码
+    log_ids: Vec<String>,
+    key_first_bytes: [bool; 256],
+    log_id_first_bytes: [bool; 256],
+}
+
+impl TrackedPrograms {
+    /// 由过滤器推导：交易过滤器通过 `for_protocols` 声明了协议时只跟踪这些协议，否则跟踪全部协议；
+    /// 再去掉事件类型过滤器不会放行的协议
+    pub fn from_filters(transaction_filters: &[TransactionFilter], event_type_filter: Option<&EventTypeFilter>) -> Self {
+        let declared =
+            transaction_filters.iter().fold(ProtocolSet::empty(), |set, filter| set.union(filter.protocols));
+        let protocols = Protocol::ALL.into_iter().filter(|&p| {
+            (declared.is_empty() || declared.contains(p)) && event_type_filter.is_none_or(|f| f.includes_protocol(p))
+        });
+        Self::new(protocols.map(Protocol::program_id))
+    }
+
+    pub fn new(program_ids: impl IntoIterator<Item = Pubkey>) -> Self {
+        let mut keys: Vec<[u8; 32]> = program_ids.into_iter().map(|id| id.to_bytes()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        let log_ids: Vec<String> = keys.iter().map(|key| Pubkey::new_from_array(*key).to_string()).collect();
+        let mut key_first_bytes = [false; 256];
+        let mut log_id_first_bytes = [false; 256];
+        for key in &keys {
+            key_first_bytes[key[0] as usize] = true;
+        }
+        for id in &log_ids {
+            log_id_first_bytes[id.as_bytes()[0] as usize] = true;
+        }
+        Self { keys, log_ids, key_first_bytes, log_id_first_bytes }
+    }
+
+    /// 跟踪的程序 ID
+    pub fn program_ids(&self) -> Vec<Pubkey> {
+        self.keys.iter().map(|key| Pubkey::new_from_array(*key)).collect()
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        key.first().is_some_and(|&b| self.key_first_bytes[b as usize]) && self.keys.iter().any(|tracked| tracked[..] == *key)
+    }
+
+    /// 交易是否调用了跟踪的程序（外层指令或日志任一命中）
+    pub fn matches(&self, message: &Message, logs: &[String]) -> bool {
+        let outer_hit = message.instructions.iter().any(|ix| {
+            message.account_keys.get(ix.program_id_index as usize).is_some_and(|key| self.contains_key(key))
+        });
+        outer_hit || logs.iter().any(|log| invoked_program(log).is_some_and(|id| self.contains_log_id(id)))
+    }
+
+    #[inline]
+    fn contains_log_id(&self, id: &str) -> bool {
+        id.as_bytes().first().is_some_and(|&b| self.log_id_first_bytes[b as usize])
+            && self.log_ids.iter().any(|tracked| tracked == id)
+    }
+}
+
+/// 跟踪的钱包集合（只读，构造后不再修改）
+#[derive(Default)]
+pub struct TrackedWallets {
+    keys: FxHashSet<[u8; 32]>,
+}
+
+impl TrackedWallets {
+    pub fn new(wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self { keys: wallets.into_iter().map(|wallet| wallet.to_bytes()).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// 原始 32 字节 key 是否为跟踪的钱包（长度不是 32 的 key 不匹配）
+    #[inline]
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        <&[u8; 32]>::try_from(key).is_ok_and(|key| self.keys.contains(key))
+    }
+
+    /// 交易的静态账户或地址查找表加载的账户中是否有跟踪的钱包
+    pub fn matches(&self, transaction_info: &SubscribeUpdateTransactionInfo) -> bool {
+        let static_hit = transaction_info
+            .transaction
+            .as_ref()
+            .and_then(|tx| tx.message.as_ref())
+            .is_some_and(|message| message.account_keys.iter().any(|key| self.contains_key(key)));
+        static_hit
+            || transaction_info.meta.as_ref().is_some_and(|meta| {
+                meta.loaded_writable_addresses.iter().chain(&meta.loaded_readonly_addresses).any(|key| self.contains_key(key))
+            })
+    }
+}
+
+/// 可在运行时替换的钱包预过滤器（Clone 后共享同一集合）
+///
+/// 订阅时传入一份，之后调用 [`Self::update`] 即可换成新的集合，无需重新订阅；
+/// 替换是原子的，正在检查的交易仍使用旧集合。
+#[derive(Clone)]
+pub struct WalletFilter {
+    wallets: Arc<ArcSwap<TrackedWallets>>,
+}
+
+impl WalletFilter {
+    pub fn new(wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        Self { wallets: Arc::new(ArcSwap::from_pointee(TrackedWallets::new(wallets))) }
+    }
+
+    /// 整体替换跟踪的钱包
+    pub fn update(&self, wallets: impl IntoIterator<Item = Pubkey>) {
+        self.wallets.store(Arc::new(TrackedWallets::new(wallets)));
+    }
+
+    /// 当前的钱包集合
+    pub fn current(&self) -> Arc<TrackedWallets> {
+        self.wallets.load_full()
+    }
+
+    #[inline]
+    pub fn matches(&self, transaction_info: &SubscribeUpdateTransactionInfo) -> bool {
+        self.wallets.load().matches(transaction_info)
+    }
+}
+
+/// `Program <id> invoke [n]` 中的程序 ID
+#[inline]
+fn invoked_program(log: &str) -> Option<&str> {
+    let (id, rest) = log.strip_prefix("Program ")?.split_once(' ')?;
+    rest.starts_with("invoke [").then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::types::EventType;
+    use crate::instr::program_ids as ids;
+    use yellowstone_grpc_proto::prelude::CompiledInstruction;
+
+    #[test]
+    fn test_skips_untracked_transactions() {
+        let tracked = TrackedPrograms::from_filters(
+            &[TransactionFilter::for_protocols(&[Protocol::RaydiumAmmV4, Protocol::PumpFun])],
+            Some(&EventTypeFilter::include_only(vec![EventType::RaydiumAmmV4Swap])),
+        );
+        assert_eq!(tracked.program_ids(), vec![ids::RAYDIUM_AMM_V4_PROGRAM_ID]);
+
+        // 合成交易：投票程序 + 一个钱包账户
+        let vote = "Vote111111111111111111111111111111111111111".parse::<Pubkey>().unwrap();
+        let mut message = Message {
+            account_keys: vec![Pubkey::new_unique().to_bytes().to_vec(), vote.to_bytes().to_vec()],
+            instructions: vec![CompiledInstruction { program_id_index: 1, accounts: vec![0], data: vec![] }],
+            ..Default::default()
+        };
+        let logs = vec![format!("Program {} invoke [1]", vote), format!("Program {} success", vote)];
+        assert!(!tracked.matches(&message, &logs));
+
+        // 只在日志里出现（CPI）
+        let cpi = format!("Program {} invoke [2]", ids::RAYDIUM_AMM_V4_PROGRAM_ID);
+        assert!(tracked.matches(&message, &[cpi]));
+
+        // 外层指令直接调用
+        message.account_keys.push(ids::RAYDIUM_AMM_V4_PROGRAM_ID.to_bytes().to_vec());
+        message.instructions[0].program_id_index = 2;
+        assert!(tracked.matches(&message, &[]));
+    }
+
+    #[test]
+    fn test_wallet_filter_and_update() {
+        use yellowstone_grpc_proto::prelude::{Transaction, TransactionStatusMeta};
+
+        let (followed, other, via_lookup_table) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let transaction = |signer: Pubkey, loaded: Vec<Pubkey>| SubscribeUpdateTransactionInfo {
+            transaction: Some(Transaction {
+                message: Some(Message { account_keys: vec![signer.to_bytes().to_vec()], ..Default::default() }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                loaded_writable_addresses: loaded.iter().map(|key| key.to_bytes().to_vec()).collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let filter = WalletFilter::new([followed, via_lookup_table]);
+        let handle = filter.clone();
+        assert!(filter.matches(&transaction(followed, vec![])));
+        assert!(filter.matches(&transaction(other, vec![via_lookup_table])));
+        assert!(!filter.matches(&transaction(other, vec![Pubkey::new_unique()])));
+
+        // 通过另一份句柄替换，订阅持有的过滤器立即生效
+        handle.update([other]);
+        assert_eq!(filter.current().len(), 1);
+        assert!(filter.matches(&transaction(other, vec![])));
+        assert!(!filter.matches(&transaction(followed, vec![])));
+    }
+}
@@ -0,0 +1,346 @@
+//! DexEvent 无锁队列封装
+//!
+//! 在 `ArrayQueue<DexEvent>` 之上增加可选的延迟统计：
+//! 开启后每次 `pop()` 记录 `now_us - metadata.grpc_recv_us` 到对应事件类型的直方图，
+//! 当前时间取自系统时钟或 [`DexEventQueue::with_clock`] 注入的 [`Clock`]。
+//! 订阅推入时队列已满的事件计入 [`DroppedRanges`]，按 `metadata.sequence` 记录丢弃区间。
+//! 启用 `perf` feature 时队列可以取自 `perf::LockFreeEventDispatcher`（见 [`DexEventQueue::from_dispatcher`]）。
+
+use super::types::EventType;
+use crate::common::latency::{LatencyHistogram, LatencySummary};
+use crate::common::time::{elapsed_micros, Clock};
+use crate::DexEvent;
+use crossbeam_queue::ArrayQueue;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// drain_batch 空轮询时先自旋的次数，之后 yield，再之后短暂 sleep
+const DRAIN_SPIN_LIMIT: u32 = 64;
+const DRAIN_YIELD_LIMIT: u32 = 1_000;
+const DRAIN_SLEEP: Duration = Duration::from_micros(50);
+
+/// 直方图槽位数（按 EventType 判别值索引）
+const LATENCY_SLOTS: usize = 128;
+
+/// 最多保留的丢弃区间数，超出时淘汰最早的区间
+const MAX_DROPPED_RANGES: usize = 1_024;
+
+/// 丢弃计数及丢弃事件的序号区间
+///
+/// 连续丢弃的序号合并为一个区间；没有 metadata（序号）的事件只计数。
+#[derive(Default)]
+pub struct DroppedRanges {
+    count: AtomicU64,
+    ranges: Mutex<VecDeque<RangeInclusive<u64>>>,
+}
+
+impl DroppedRanges {
+    /// 记录一个被丢弃的事件
+    pub(crate) fn record(&self, event: &DexEvent) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let Some(sequence) = event.metadata().map(|m| m.sequence).filter(|&s| s > 0) else { return };
+        let mut ranges = self.ranges.lock();
+        if let Some(last) = ranges.back_mut().filter(|r| *r.end() + 1 == sequence) {
+            *last = *last.start()..=sequence;
+            return;
+        }
+        if ranges.len() >= MAX_DROPPED_RANGES {
+            ranges.pop_front();
+        }
+        ranges.push_back(sequence..=sequence);
+    }
+
+    /// 丢弃的事件总数
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// 丢弃事件的序号区间（按时间顺序，最多保留最近 1024 个）
+    pub fn ranges(&self) -> Vec<RangeInclusive<u64>> {
+        self.ranges.lock().iter().cloned().collect()
+    }
+
+    /// 取出并清空已记录的区间（计数不清零）
+    pub fn take_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        self.ranges.lock().drain(..).collect()
+    }
+}
+
+/// 按事件类型统计的延迟跟踪器
+struct LatencyTracker {
+    enabled: AtomicBool,
+    histograms: Box<[OnceLock<(EventType, LatencyHistogram)>]>,
+    /// 注入的时间源，None 时使用系统时钟
+    clock: Option<Arc<dyn Clock>>,
+}
+
+impl LatencyTracker {
+    fn new(enabled: bool, clock: Option<Arc<dyn Clock>>) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            histograms: (0..LATENCY_SLOTS).map(|_| OnceLock::new()).collect(),
+            clock,
+        }
+    }
+
+    #[inline]
+    fn record(&self, event: &DexEvent) {
+        let Some(metadata) = event.metadata() else { return };
+        let Some(event_type) = EventType::from_event(event) else { return };
+        let Some(slot) = self.histograms.get(event_type as usize) else { return };
+
+        let latency_us = match &self.clock {
+            Some(clock) => (clock.now_micros() - metadata.grpc_recv_us).max(0) as u64,
+            None => elapsed_micros(metadata.grpc_recv_us),
+        };
+        slot.get_or_init(|| (event_type, LatencyHistogram::new())).1.record(latency_us);
+    }
+}
+
+/// DexEvent 队列（带可选延迟统计）
+#[derive(Clone)]
+pub struct DexEventQueue {
+    inner: Arc<ArrayQueue<DexEvent>>,
+    tracker: Arc<LatencyTracker>,
+    pub(crate) dropped: Arc<DroppedRanges>,
+    /// 队列取自分发器时经分发器推入（分发器与队列序号）
+    #[cfg(feature = "perf")]
+    dispatcher: Option<(Arc<crate::perf::LockFreeEventDispatcher>, usize)>,
+}
+
+impl DexEventQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self::build(capacity, None)
+    }
+
+    /// 延迟统计使用指定时间源（只影响该队列）
+    pub fn with_clock(capacity: usize, clock: Arc<dyn Clock>) -> Self {
+        Self::build(capacity, Some(clock))
+    }
+
+    /// 使用分发器分配给 `client_id` 的无锁队列（容量为分发器的队列容量）
+    ///
+    /// 推入经 [`LockFreeEventDispatcher::dispatch_to_queue`](crate::perf::LockFreeEventDispatcher::dispatch_to_queue)，
+    /// gRPC 接收到入队的延迟和溢出计入分发器统计。分发器队列数少于客户端数时，多个客户端共享同一队列；
+    /// 分发器自己的处理线程（`PerformanceOptimizer::start`）也会从这些队列取事件，二者不要同时使用。
+    #[cfg(feature = "perf")]
+    pub fn from_dispatcher(dispatcher: &Arc<crate::perf::LockFreeEventDispatcher>, client_id: &str) -> Self {
+        let (index, inner) = dispatcher.client_queue(client_id);
+        Self {
+            inner,
+            tracker: Arc::new(LatencyTracker::new(false, None)),
+            dropped: Arc::new(DroppedRanges::default()),
+            dispatcher: Some((dispatcher.clone(), index)),
+        }
+    }
+
+    fn build(capacity: usize, clock: Option<Arc<dyn Clock>>) -> Self {
+        Self {
+            inner: Arc::new(ArrayQueue::new(capacity)),
+            tracker: Arc::new(LatencyTracker::new(false, clock)),
+            dropped: Arc::new(DroppedRanges::default()),
+            #[cfg(feature = "perf")]
+            dispatcher: None,
+        }
+    }
+
+    /// 取出一个事件；开启延迟统计时记录 gRPC 接收到出队的耗时
+    #[inline]
+    pub fn pop(&self) -> Option<DexEvent> {
+        let event = self.inner.pop()?;
+        if self.tracker.enabled.load(Ordering::Relaxed) {
+            self.tracker.record(&event);
+        }
+        Some(event)
+    }
+
+    /// 推入事件，队列已满时返回原事件（装箱，避免 `Result` 体积随 `DexEvent` 膨胀）
+    #[inline]
+    pub fn push(&self, event: DexEvent) -> Result<(), Box<DexEvent>> {
+        #[cfg(feature = "perf")]
+        if let Some((dispatcher, index)) = &self.dispatcher {
+            return dispatcher.dispatch_to_queue(*index, event);
+        }
+        self.inner.push(event).map_err(Box::new)
+    }
+
+    /// 批量取出事件：凑满 `max` 个立即返回，否则最多等待 `timeout` 后返回已取到的事件
+    ///
+    /// 队列为空时先自旋、再 `yield`、最后短暂 sleep，避免空转占满 CPU。
+    /// 该方法会阻塞当前线程，在 tokio 中请放到 `spawn_blocking` 或独立线程里调用。
+    pub fn drain_batch(&self, max: usize, timeout: Duration) -> Vec<DexEvent> {
+        let mut batch = Vec::with_capacity(max.min(self.capacity()));
+        if max == 0 {
+            return batch;
+        }
+        let deadline = Instant::now() + timeout;
+        let mut idle = 0u32;
+
+        loop {
+            while let Some(event) = self.pop() {
+                batch.push(event);
+                if batch.len() >= max {
+                    return batch;
+                }
+                idle = 0;
+            }
+            if Instant::now() >= deadline {
+                return batch;
+            }
+
+            idle += 1;
+            if idle < DRAIN_SPIN_LIMIT {
+                std::hint::spin_loop();
+            } else if idle < DRAIN_YIELD_LIMIT {
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(DRAIN_SLEEP.min(deadline.saturating_duration_since(Instant::now())));
+            }
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// 底层无锁队列
+    pub fn inner(&self) -> &Arc<ArrayQueue<DexEvent>> {
+        &self.inner
+    }
+
+    /// 订阅推入时因队列已满而丢弃的事件数
+    pub fn dropped(&self) -> u64 {
+        self.dropped.count()
+    }
+
+    /// 丢弃事件的序号区间，与相邻事件的 `metadata.sequence` 对照即可定位缺口
+    pub fn dropped_ranges(&self) -> Vec<RangeInclusive<u64>> {
+        self.dropped.ranges()
+    }
+
+    /// 运行时开关延迟统计（关闭时 pop 只多一次原子读）
+    pub fn set_latency_tracking(&self, enabled: bool) {
+        self.tracker.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn latency_tracking_enabled(&self) -> bool {
+        self.tracker.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 各事件类型的延迟摘要（仅包含有样本的类型）
+    pub fn latency_snapshot(&self) -> Vec<(EventType, LatencySummary)> {
+        self.tracker
+            .histograms
+            .iter()
+            .filter_map(|slot| slot.get())
+            .filter(|(_, h)| h.count() > 0)
+            .map(|(t, h)| (*t, h.summary()))
+            .collect()
+    }
+
+    /// 清空延迟统计
+    pub fn reset_latency(&self) {
+        for (_, h) in self.tracker.histograms.iter().filter_map(|slot| slot.get()) {
+            h.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::events::{BlockMetaEvent, EventMetadata, EventSource};
+    use solana_sdk::signature::Signature;
+
+    fn block_meta(grpc_recv_us: i64) -> DexEvent {
+        DexEvent::BlockMeta(BlockMetaEvent {
+            metadata: EventMetadata {
+                signature: Signature::default(),
+                slot: 1,
+                tx_index: 0,
+                block_time_us: 0,
+                grpc_recv_us,
+                handle_us: 0,
+                event_source: EventSource::Log,
+                outer_index: 0,
+                inner_index: None,
+                protocol_version: None,
+                sequence: 0,
+                raw: None,
+                priority_fee_micro_lamports: None,
+                compute_unit_limit: None,
+            },
+        })
+    }
+
+    #[test]
+    fn test_latency_recorded_only_when_enabled() {
+        let queue = DexEventQueue::new(8);
+        queue.push(block_meta(0)).unwrap();
+        queue.pop().unwrap();
+        assert!(queue.latency_snapshot().is_empty());
+
+        queue.set_latency_tracking(true);
+        queue.push(block_meta(0)).unwrap();
+        queue.pop().unwrap();
+        let snapshot = queue.latency_snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].0, EventType::BlockMeta);
+        assert_eq!(snapshot[0].1.count, 1);
+    }
+
+    #[test]
+    fn test_drain_batch() {
+        let queue = DexEventQueue::new(16);
+        for i in 0..5 {
+            queue.push(block_meta(i)).unwrap();
+        }
+
+        let batch = queue.drain_batch(3, Duration::from_secs(1));
+        assert_eq!(batch.len(), 3);
+
+        // 不足 max 时等到超时，返回剩余的事件
+        let start = Instant::now();
+        let batch = queue.drain_batch(10, Duration::from_millis(20));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        let recv: Vec<_> = batch.iter().map(|e| e.metadata().unwrap().grpc_recv_us).collect();
+        assert_eq!(recv, vec![3, 4]);
+        assert!(queue.drain_batch(10, Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_latency_uses_injected_clock() {
+        use crate::common::time::tests::MockClock;
+        use std::sync::atomic::AtomicI64;
+
+        let queue = DexEventQueue::with_clock(8, Arc::new(MockClock(AtomicI64::new(1_500))));
+        queue.set_latency_tracking(true);
+        queue.push(block_meta(1_000)).unwrap();
+        queue.push(block_meta(1_000)).unwrap();
+        // 时钟回拨按 0 计
+        queue.push(block_meta(2_000)).unwrap();
+        while queue.pop().is_some() {}
+
+        let snapshot = queue.latency_snapshot();
+        assert_eq!(snapshot.len(), 1);
+        let summary = snapshot[0].1;
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.max_us, 500);
+        assert_eq!(summary.p50_us, 500);
+    }
+}
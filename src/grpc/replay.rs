@@ -0,0 +1,47 @@
+//! 回放抓取的 gRPC 更新
+//!
+//! 文件格式：连续的 length-delimited protobuf `SubscribeUpdate` 记录（varint 长度 + 消息），
+//! 与 prost `encode_length_delimited` 一致，可用 [`write_update`] 从实时流抓取。
+//! 回放入口为 `YellowstoneGrpc::replay_from_file`，走与实时订阅相同的解析路径。
+
+use prost::Message;
+use std::io::Write;
+use std::time::Duration;
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+/// 回放节奏
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    /// 不等待，尽快回放
+    #[default]
+    MaxSpeed,
+    /// 按记录的 `created_at` 间隔回放（缺少时间戳的记录不等待）
+    Original,
+}
+
+/// 追加一条更新记录
+pub fn write_update<W: Write>(writer: &mut W, update: &SubscribeUpdate) -> std::io::Result<()> {
+    writer.write_all(&update.encode_length_delimited_to_vec())
+}
+
+/// 解码全部记录，出错时返回记录序号和原因
+pub fn read_updates(mut bytes: &[u8]) -> Result<Vec<SubscribeUpdate>, String> {
+    let mut updates = Vec::new();
+    while !bytes.is_empty() {
+        let update = SubscribeUpdate::decode_length_delimited(&mut bytes)
+            .map_err(|e| format!("record {}: {}", updates.len(), e))?;
+        updates.push(update);
+    }
+    Ok(updates)
+}
+
+/// 两条更新之间按原始节奏应等待的时间
+pub(crate) fn gap(previous: &SubscribeUpdate, next: &SubscribeUpdate) -> Duration {
+    let nanos = |update: &SubscribeUpdate| {
+        update.created_at.as_ref().map(|t| t.seconds as i128 * 1_000_000_000 + t.nanos as i128)
+    };
+    match (nanos(previous), nanos(next)) {
+        (Some(a), Some(b)) if b > a => Duration::from_nanos((b - a).min(u64::MAX as i128) as u64),
+        _ => Duration::ZERO,
+    }
+}
@@ -0,0 +1,328 @@
+//! 按签名拉取单笔交易并解析（`rpc` feature）
+//!
+//! 用于排查：只有一个签名时，通过 RPC `getTransaction`（base64 编码、`maxSupportedTransactionVersion = 0`）
+//! 取回交易，转换成 gRPC 的 [`SubscribeUpdateTransactionInfo`]，再走与订阅相同的解析路径
+//! （外层指令、内层指令、日志，合并后按指令顺序输出）。
+//!
+//! 先按 `finalized` 查询；查不到时再按 `confirmed` 查询，用于区分"尚未最终确认"和"不存在"。
+
+use super::broadcast::EventSink;
+use super::client::YellowstoneGrpc;
+use crate::core::events::DexEvent;
+use crate::core::unified_parser::ParseOptions;
+use parking_lot::Mutex;
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiInstruction};
+use yellowstone_grpc_proto::prelude::{
+    CompiledInstruction, InnerInstruction, InnerInstructions, Message, MessageAddressTableLookup, MessageHeader,
+    SubscribeUpdateTransactionInfo, Transaction, TransactionError, TransactionStatusMeta,
+};
+
+/// 拉取或解析单笔交易失败
+#[derive(Debug, thiserror::Error)]
+pub enum RpcFetchError {
+    /// `confirmed` 和 `finalized` 下都查不到该签名
+    #[error("transaction {0} not found")]
+    NotFound(Signature),
+    /// 交易已确认但尚未 finalized，稍后重试
+    #[error("transaction {signature} is confirmed at slot {slot} but not yet finalized")]
+    NotFinalized { signature: Signature, slot: u64 },
+    /// RPC 返回的交易无法解码（编码不是 base64、内层指令为 jsonParsed、地址格式错误等）
+    #[error("failed to decode transaction: {0}")]
+    Decode(String),
+    /// RPC 请求失败
+    #[error("rpc error: {0}")]
+    Rpc(#[from] Box<ClientError>),
+}
+
+/// 按签名拉取交易并解析出全部 DEX 事件
+pub async fn fetch_and_parse(rpc_url: &str, signature: &Signature) -> Result<Vec<DexEvent>, RpcFetchError> {
+    fetch_and_parse_with_client(&RpcClient::new(rpc_url.to_string()), signature).await
+}
+
+/// 同 [`fetch_and_parse`]，复用已有的 [`RpcClient`]
+pub async fn fetch_and_parse_with_client(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<Vec<DexEvent>, RpcFetchError> {
+    parse_rpc_transaction(&fetch_transaction(client, signature).await?)
+}
+
+/// 按签名拉取 finalized 交易，不解析（用于录制快照夹具）
+pub async fn fetch_transaction(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta, RpcFetchError> {
+    if let Some(transaction) = get_transaction(client, signature, "finalized").await? {
+        return Ok(transaction);
+    }
+    match get_transaction(client, signature, "confirmed").await? {
+        Some(transaction) => Err(RpcFetchError::NotFinalized { signature: *signature, slot: transaction.slot }),
+        None => Err(RpcFetchError::NotFound(*signature)),
+    }
+}
+
+/// `getTransaction`，交易不存在时 RPC 返回 null
+async fn get_transaction(
+    client: &RpcClient,
+    signature: &Signature,
+    commitment: &str,
+) -> Result<Option<EncodedConfirmedTransactionWithStatusMeta>, RpcFetchError> {
+    let config = serde_json::json!({
+        "encoding": "base64",
+        "commitment": commitment,
+        "maxSupportedTransactionVersion": 0,
+    });
+    client
+        .send(RpcRequest::GetTransaction, serde_json::json!([signature.to_string(), config]))
+        .await
+        .map_err(|e| RpcFetchError::Rpc(Box::new(e)))
+}
+
+/// 收集单笔交易的事件
+struct VecSink(Mutex<Vec<DexEvent>>);
+
+impl EventSink for VecSink {
+    #[inline]
+    fn push_event(&self, event: DexEvent) {
+        self.0.lock().push(event);
+    }
+}
+
+/// 解析 RPC 返回的交易
+pub fn parse_rpc_transaction(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<Vec<DexEvent>, RpcFetchError> {
+    let info = transaction_info_from_rpc(transaction)?;
+    let sink = VecSink(Mutex::new(Vec::new()));
+    YellowstoneGrpc::parse_transaction_info(
+        &info,
+        transaction.slot,
+        transaction.block_time,
+        crate::common::time::now_micros(),
+        &sink,
+        None,
+        None,
+        false,
+        ParseOptions::default(),
+        false,
+    );
+    Ok(sink.0.into_inner())
+}
+
+/// 把 RPC 交易（base64 编码）转换成 gRPC 交易更新
+///
+/// RPC 不返回交易在 slot 中的索引，`index` 为 0。
+pub fn transaction_info_from_rpc(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<SubscribeUpdateTransactionInfo, RpcFetchError> {
+    let decoded = transaction
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| RpcFetchError::Decode("transaction is not base64/base58 encoded or fails sanitize".into()))?;
+    let message = &decoded.message;
+    let header = message.header();
+    let signature = decoded.signatures.first().copied().unwrap_or_default();
+
+    let proto_message = Message {
+        header: Some(MessageHeader {
+            num_required_signatures: header.num_required_signatures as u32,
+            num_readonly_signed_accounts: header.num_readonly_signed_accounts as u32,
+            num_readonly_unsigned_accounts: header.num_readonly_unsigned_accounts as u32,
+        }),
+        account_keys: message.static_account_keys().iter().map(|key| key.to_bytes().to_vec()).collect(),
+        recent_blockhash: message.recent_blockhash().to_bytes().to_vec(),
+        instructions: message
+            .instructions()
+            .iter()
+            .map(|ix| CompiledInstruction {
+                program_id_index: ix.program_id_index as u32,
+                accounts: ix.accounts.clone(),
+                data: ix.data.clone(),
+            })
+            .collect(),
+        versioned: message.address_table_lookups().is_some(),
+        address_table_lookups: message
+            .address_table_lookups()
+            .unwrap_or_default()
+            .iter()
+            .map(|lookup| MessageAddressTableLookup {
+                account_key: lookup.account_key.to_bytes().to_vec(),
+                writable_indexes: lookup.writable_indexes.clone(),
+                readonly_indexes: lookup.readonly_indexes.clone(),
+            })
+            .collect(),
+    };
+
+    let meta = transaction.transaction.meta.as_ref().map(convert_meta).transpose()?;
+    Ok(SubscribeUpdateTransactionInfo {
+        signature: signature.as_ref().to_vec(),
+        is_vote: false,
+        transaction: Some(Transaction {
+            signatures: decoded.signatures.iter().map(|s| s.as_ref().to_vec()).collect(),
+            message: Some(proto_message),
+        }),
+        meta,
+        index: 0,
+    })
+}
+
+/// 转换解析用到的 meta 字段：日志、内层指令、ALT 加载的地址、执行结果
+fn convert_meta(meta: &solana_transaction_status::UiTransactionStatusMeta) -> Result<TransactionStatusMeta, RpcFetchError> {
+    let inner_instructions = match &meta.inner_instructions {
+        OptionSerializer::Some(inner) => inner
+            .iter()
+            .map(|inner| {
+                let instructions = inner
+                    .instructions
+                    .iter()
+                    .map(|ix| match ix {
+                        UiInstruction::Compiled(ix) => Ok(InnerInstruction {
+                            program_id_index: ix.program_id_index as u32,
+                            accounts: ix.accounts.clone(),
+                            data: bs58::decode(&ix.data)
+                                .into_vec()
+                                .map_err(|e| RpcFetchError::Decode(format!("inner instruction data: {}", e)))?,
+                            stack_height: ix.stack_height,
+                        }),
+                        UiInstruction::Parsed(_) => {
+                            Err(RpcFetchError::Decode("inner instructions must use base64 encoding, got jsonParsed".into()))
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(InnerInstructions { index: inner.index as u32, instructions })
+            })
+            .collect::<Result<Vec<_>, RpcFetchError>>()?,
+        _ => Vec::new(),
+    };
+    let (loaded_writable_addresses, loaded_readonly_addresses) = match &meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => (decode_addresses(&loaded.writable)?, decode_addresses(&loaded.readonly)?),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let log_messages = match &meta.log_messages {
+        OptionSerializer::Some(logs) => logs.clone(),
+        _ => Vec::new(),
+    };
+    Ok(TransactionStatusMeta {
+        // 解析只区分成功 / 失败，不需要还原具体错误
+        err: meta.err.as_ref().map(|_| TransactionError { err: Vec::new() }),
+        fee: meta.fee,
+        pre_balances: meta.pre_balances.clone(),
+        post_balances: meta.post_balances.clone(),
+        inner_instructions_none: inner_instructions.is_empty(),
+        inner_instructions,
+        log_messages_none: log_messages.is_empty(),
+        log_messages,
+        loaded_writable_addresses,
+        loaded_readonly_addresses,
+        ..Default::default()
+    })
+}
+
+fn decode_addresses(addresses: &[String]) -> Result<Vec<Vec<u8>>, RpcFetchError> {
+    addresses
+        .iter()
+        .map(|address| {
+            address
+                .parse::<Pubkey>()
+                .map(|key| key.to_bytes().to_vec())
+                .map_err(|e| RpcFetchError::Decode(format!("loaded address {}: {}", address, e)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instr::program_ids::PUMPFUN_PROGRAM_ID;
+    use base64::{engine::general_purpose, Engine as _};
+    use solana_rpc_client::mock_sender::MocksMap;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::message::{compiled_instruction, Message as LegacyMessage, MessageHeader as LegacyHeader, VersionedMessage};
+    use solana_sdk::transaction::VersionedTransaction;
+
+    /// 以 RPC JSON 形式构造交易：夹具中的 PumpFun 买入指令及其日志 + 一条 CPI 内层指令
+    fn rpc_response(signature: Signature) -> serde_json::Value {
+        let fixtures: serde_json::Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/transactions/pumpfun.json")).unwrap();
+        let find = |name: &str| fixtures.as_array().unwrap().iter().find(|tx| tx["name"] == name).unwrap().clone();
+        let buy = find("ix_buy");
+
+        let mut account_keys = vec![Pubkey::new_unique()];
+        account_keys.extend(buy["accounts"].as_array().unwrap().iter().map(|a| a.as_str().unwrap().parse::<Pubkey>().unwrap()));
+        account_keys.push(PUMPFUN_PROGRAM_ID);
+        let program_index = (account_keys.len() - 1) as u8;
+        let message = LegacyMessage {
+            header: LegacyHeader { num_required_signatures: 1, num_readonly_signed_accounts: 0, num_readonly_unsigned_accounts: 1 },
+            account_keys,
+            recent_blockhash: Hash::default(),
+            instructions: vec![compiled_instruction::CompiledInstruction {
+                program_id_index: program_index,
+                accounts: (1..program_index).collect(),
+                data: hex::decode(buy["instruction_data"].as_str().unwrap()).unwrap(),
+            }],
+        };
+        let transaction = VersionedTransaction { signatures: vec![signature], message: VersionedMessage::Legacy(message) };
+        let blob = general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap());
+
+        serde_json::json!({
+            "slot": 300_000_001u64,
+            "blockTime": 1_700_000_000i64,
+            "version": "legacy",
+            "transaction": [blob, "base64"],
+            "meta": {
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+                "innerInstructions": [{
+                    "index": 0,
+                    "instructions": [{ "programIdIndex": program_index, "accounts": [], "data": bs58::encode([1u8, 2, 3]).into_string(), "stackHeight": 2 }],
+                }],
+                "logMessages": buy["logs"],
+                "loadedAddresses": { "writable": [], "readonly": [Pubkey::new_unique().to_string()] },
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_parse_with_mocked_rpc() {
+        let signature = Signature::from([7u8; 64]);
+        let response = rpc_response(signature);
+
+        // 转换层：内层指令 base58 解码、ALT 地址、日志
+        let transaction: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(response.clone()).unwrap();
+        let info = transaction_info_from_rpc(&transaction).unwrap();
+        let meta = info.meta.as_ref().unwrap();
+        assert_eq!(info.signature, signature.as_ref());
+        assert_eq!(meta.inner_instructions[0].instructions[0].data, vec![1, 2, 3]);
+        assert_eq!((meta.loaded_readonly_addresses.len(), meta.log_messages.len()), (1, 2));
+
+        let client = |responses: Vec<serde_json::Value>| {
+            let mocks: MocksMap = responses.into_iter().map(|r| (RpcRequest::GetTransaction, r)).collect();
+            RpcClient::new_mock_with_mocks_map("succeeds", mocks)
+        };
+        let events = fetch_and_parse_with_client(&client(vec![response.clone()]), &signature).await.unwrap();
+        let [DexEvent::PumpFunTrade(trade)] = &events[..] else { panic!("{:?}", events) };
+        assert_eq!((trade.metadata.signature, trade.metadata.slot, trade.metadata.block_time_us), (signature, 300_000_001, 1_700_000_000_000_000));
+
+        // finalized 查不到、confirmed 查得到
+        let err = fetch_and_parse_with_client(&client(vec![serde_json::Value::Null, response]), &signature).await.unwrap_err();
+        assert!(matches!(err, RpcFetchError::NotFinalized { slot: 300_000_001, .. }), "{}", err);
+        let err = fetch_and_parse_with_client(&client(vec![serde_json::Value::Null, serde_json::Value::Null]), &signature)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RpcFetchError::NotFound(s) if s == signature));
+
+        let mut undecodable = transaction;
+        undecodable.transaction.transaction = solana_transaction_status::EncodedTransaction::LegacyBinary("not base58!".into());
+        assert!(matches!(parse_rpc_transaction(&undecodable), Err(RpcFetchError::Decode(_))));
+    }
+}
@@ -0,0 +1,183 @@
+//! 事件源抽象
+//!
+//! [`DexEventSource`] 统一真实客户端（[`YellowstoneGrpc`]）和离线模拟（[`super::mock::MockYellowstone`]）的订阅接口，
+//! 消费逻辑对它泛型即可在两者之间切换。
+
+use super::client::YellowstoneGrpc;
+use super::error::GrpcClientError;
+use super::queue::DexEventQueue;
+use super::types::{AccountFilter, EventTypeFilter, TransactionFilter};
+use crate::core::events::DexEvent;
+use crate::core::unified_parser::AsyncEventListener;
+use futures::{FutureExt, StreamExt};
+use log::warn;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// 可订阅 DEX 事件的数据源
+pub trait DexEventSource: Send + Sync {
+    /// 订阅事件，经有界无锁队列交付（队列满时丢弃新事件）
+    fn subscribe_dex_events(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+    ) -> impl Future<Output = Result<DexEventQueue, GrpcClientError>> + Send;
+
+    /// 订阅事件，在产生事件的任务 / 线程内直接调用 `callback`
+    fn subscribe_dex_events_inline<F>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        callback: F,
+    ) -> impl Future<Output = Result<(), GrpcClientError>> + Send
+    where
+        F: Fn(DexEvent) + Send + 'static;
+
+    /// 订阅事件，在产生事件的任务 / 线程内用 `transform` 转换成自定义类型后经 channel 交付
+    ///
+    /// `transform` 返回 None 的事件直接丢弃；channel 已满时丢弃转换结果。
+    /// 与 `subscribe_dex_events_inline` 一样，`transform` 不应阻塞。
+    fn subscribe_dex_events_with_transform<T, F>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        channel_capacity: usize,
+        transform: F,
+    ) -> impl Future<Output = Result<tokio::sync::mpsc::Receiver<T>, GrpcClientError>> + Send
+    where
+        T: Send + 'static,
+        F: Fn(DexEvent) -> Option<T> + Send + 'static,
+    {
+        async move {
+            let (tx, rx) = tokio::sync::mpsc::channel(channel_capacity.max(1));
+            self.subscribe_dex_events_inline(transaction_filters, account_filters, event_type_filter, move |event| {
+                if let Some(output) = transform(event) {
+                    if tx.try_send(output).is_err() {
+                        warn!("transform channel full or closed, output dropped");
+                    }
+                }
+            })
+            .await?;
+            Ok(rx)
+        }
+    }
+
+    /// 订阅事件，逐个 await `listener` 的处理函数，最多 `max_concurrency` 个同时进行（1 表示按顺序逐个处理）
+    ///
+    /// 事件先经容量为 `channel_capacity` 的 channel 交给处理任务，处理跟不上、channel 已满时丢弃新事件，
+    /// 丢弃数见 [`AsyncListenerHandle::dropped`]；处理函数 panic 时记录并跳过该事件，见 [`AsyncListenerHandle::panicked`]。
+    /// 返回的句柄可直接 await，在订阅结束（如 `stop()`）且已收到的事件全部处理完后完成。
+    fn subscribe_with_async_listener<L>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        listener: Arc<L>,
+        max_concurrency: usize,
+        channel_capacity: usize,
+    ) -> impl Future<Output = Result<AsyncListenerHandle, GrpcClientError>> + Send
+    where
+        L: AsyncEventListener + Send + Sync + 'static,
+    {
+        async move {
+            let stats = Arc::new(AsyncListenerStats::default());
+            let (tx, rx) = tokio::sync::mpsc::channel(channel_capacity.max(1));
+            let producer_stats = stats.clone();
+            self.subscribe_dex_events_inline(transaction_filters, account_filters, event_type_filter, move |event| {
+                if tx.try_send(event).is_err() && producer_stats.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
+                    warn!("async listener channel full or closed, events dropped (see AsyncListenerHandle::dropped)");
+                }
+            })
+            .await?;
+            let task_stats = stats.clone();
+            let task = tokio::spawn(async move {
+                let (listener, stats) = (&listener, &task_stats);
+                tokio_stream::wrappers::ReceiverStream::new(rx)
+                    .for_each_concurrent(max_concurrency.max(1), |event| async move {
+                        if AssertUnwindSafe(listener.on_dex_event(&event)).catch_unwind().await.is_err() {
+                            stats.panicked.fetch_add(1, Ordering::Relaxed);
+                            warn!("async listener panicked on {:?}", event.metadata().map(|m| m.signature));
+                        }
+                    })
+                    .await;
+            });
+            Ok(AsyncListenerHandle { task, stats })
+        }
+    }
+
+    /// 停止所有订阅
+    fn stop(&self) -> impl Future<Output = ()> + Send;
+}
+
+#[derive(Default)]
+struct AsyncListenerStats {
+    dropped: AtomicU64,
+    panicked: AtomicU64,
+}
+
+/// [`DexEventSource::subscribe_with_async_listener`] 的处理任务，await 等待其退出
+pub struct AsyncListenerHandle {
+    task: tokio::task::JoinHandle<()>,
+    stats: Arc<AsyncListenerStats>,
+}
+
+impl AsyncListenerHandle {
+    /// 因 channel 已满（或处理任务已退出）而未交给处理函数的事件数
+    pub fn dropped(&self) -> u64 {
+        self.stats.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 处理函数 panic 的次数（panic 的事件被跳过，处理任务继续运行）
+    pub fn panicked(&self) -> u64 {
+        self.stats.panicked.load(Ordering::Relaxed)
+    }
+
+    /// 立即终止处理任务，未处理的事件被丢弃
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl Future for AsyncListenerHandle {
+    type Output = Result<(), tokio::task::JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.task).poll(cx)
+    }
+}
+
+impl DexEventSource for YellowstoneGrpc {
+    async fn subscribe_dex_events(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+    ) -> Result<DexEventQueue, GrpcClientError> {
+        YellowstoneGrpc::subscribe_dex_events(self, transaction_filters, account_filters, event_type_filter).await
+    }
+
+    async fn subscribe_dex_events_inline<F>(
+        &self,
+        transaction_filters: Vec<TransactionFilter>,
+        account_filters: Vec<AccountFilter>,
+        event_type_filter: Option<EventTypeFilter>,
+        callback: F,
+    ) -> Result<(), GrpcClientError>
+    where
+        F: Fn(DexEvent) + Send + 'static,
+    {
+        YellowstoneGrpc::subscribe_dex_events_inline(self, transaction_filters, account_filters, event_type_filter, callback)
+            .await
+    }
+
+    async fn stop(&self) {
+        YellowstoneGrpc::stop(self).await
+    }
+}
@@ -0,0 +1,83 @@
+//! 交易状态订阅（`transactions_status`）
+//!
+//! 只关心"某个签名是否上链、是否成功"时使用，服务端只推送签名、slot 和执行错误，不做任何解析。
+
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+use yellowstone_grpc_proto::prelude::{subscribe_update, SubscribeUpdate, SubscribeUpdateTransactionStatus};
+
+/// 一条交易状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionStatus {
+    pub signature: Signature,
+    pub slot: u64,
+    /// 交易在区块内的序号
+    pub index: u64,
+    /// 执行错误，成功时为 None
+    pub err: Option<String>,
+}
+
+impl TransactionStatus {
+    /// 从状态更新转换，签名长度不对时返回 None
+    pub fn from_update(update: &SubscribeUpdateTransactionStatus) -> Option<Self> {
+        Some(Self {
+            signature: Signature::try_from(update.signature.as_slice()).ok()?,
+            slot: update.slot,
+            index: update.index,
+            err: update.err.as_ref().map(|e| decode_error(&e.err)),
+        })
+    }
+
+    /// 取出订阅消息中的交易状态，其它类型的更新返回 None
+    pub fn from_subscribe_update(update: &SubscribeUpdate) -> Option<Self> {
+        match &update.update_oneof {
+            Some(subscribe_update::UpdateOneof::TransactionStatus(status)) => Self::from_update(status),
+            _ => None,
+        }
+    }
+
+    /// 交易是否执行成功
+    pub fn is_success(&self) -> bool {
+        self.err.is_none()
+    }
+}
+
+/// 错误为 bincode 编码的 `TransactionError`，解码失败时返回原始字节的 hex
+fn decode_error(bytes: &[u8]) -> String {
+    match bincode::deserialize::<TransactionError>(bytes) {
+        Ok(err) => format!("{:?}", err),
+        Err(_) => hex::encode(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::InstructionError;
+    use yellowstone_grpc_proto::prelude::TransactionError as ProtoTransactionError;
+
+    #[test]
+    fn test_status_update_for_signature() {
+        let signature = Signature::new_unique();
+        let err = TransactionError::InstructionError(0, InstructionError::Custom(6001));
+        let status = |err: Option<Vec<u8>>| SubscribeUpdate {
+            update_oneof: Some(subscribe_update::UpdateOneof::TransactionStatus(SubscribeUpdateTransactionStatus {
+                slot: 42,
+                signature: signature.as_ref().to_vec(),
+                is_vote: false,
+                index: 7,
+                err: err.map(|err| ProtoTransactionError { err }),
+            })),
+            ..Default::default()
+        };
+
+        let failed = TransactionStatus::from_subscribe_update(&status(Some(bincode::serialize(&err).unwrap()))).unwrap();
+        assert_eq!(failed.signature, signature);
+        assert_eq!((failed.slot, failed.index), (42, 7));
+        assert_eq!(failed.err.as_deref(), Some("InstructionError(0, Custom(6001))"));
+
+        let landed = TransactionStatus::from_subscribe_update(&status(None)).unwrap();
+        assert!(landed.is_success());
+        assert!(TransactionStatus::from_subscribe_update(&SubscribeUpdate::default()).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+//! 订阅流保活与僵死检测
+//!
+//! 部分 geyser 服务商会在不关闭连接的情况下停止推送数据。这里：
+//! - 按 `ClientConfig.ping_interval_ms` 通过 subscribe sender 定期发送 ping
+//! - 记录最后一次收到任意更新（包括 Ping/Pong）的时间
+//! - 超过 `ClientConfig.stale_stream_timeout_ms` 没有任何消息即判定为僵死，交给上层重连
+
+use crate::common::time::now_micros;
+use crate::grpc::error::is_retryable_code;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use log::{error, warn};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Duration;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update, SubscribeRequest, SubscribeRequestPing, SubscribeUpdate,
+};
+
+/// 流结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// 服务端正常关闭
+    Closed,
+    /// 超时未收到任何消息
+    Stale,
+}
+
+/// 订阅流统计（原子计数，可跨任务读取）
+#[derive(Debug, Default)]
+pub struct StreamStats {
+    pings_sent: AtomicU64,
+    pongs_received: AtomicU64,
+    stale_reconnects: AtomicU64,
+    reconnects: AtomicU64,
+    errors: AtomicU64,
+    parser_dropped: AtomicU64,
+    parser_waits: AtomicU64,
+    parser_queue_depth: AtomicU64,
+    last_update_us: AtomicI64,
+}
+
+/// [`StreamStats`] 快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStatsSnapshot {
+    pub pings_sent: u64,
+    pub pongs_received: u64,
+    /// 因僵死检测触发的重连次数
+    pub stale_reconnects: u64,
+    /// 总重连次数
+    pub reconnects: u64,
+    /// 订阅任务因错误退出的次数（原因见 `YellowstoneGrpc::last_error`）
+    pub errors: u64,
+    /// 解析线程跟不上、转交队列已满而丢弃的交易数（`parser_threads > 0` 时）
+    pub parser_dropped: u64,
+    /// 开启 `parser_backpressure` 时，转交队列已满、接收任务等待解析线程的次数
+    pub parser_waits: u64,
+    /// 转交队列中等待解析的交易数（解析滞后，最近一次转交或取出时的值）
+    pub parser_queue_depth: u64,
+    /// 最后一次收到更新的时间（微秒，0 表示尚未收到）
+    pub last_update_us: i64,
+}
+
+impl StreamStats {
+    pub fn snapshot(&self) -> StreamStatsSnapshot {
+        StreamStatsSnapshot {
+            pings_sent: self.pings_sent.load(Ordering::Relaxed),
+            pongs_received: self.pongs_received.load(Ordering::Relaxed),
+            stale_reconnects: self.stale_reconnects.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            parser_dropped: self.parser_dropped.load(Ordering::Relaxed),
+            parser_waits: self.parser_waits.load(Ordering::Relaxed),
+            parser_queue_depth: self.parser_queue_depth.load(Ordering::Relaxed),
+            last_update_us: self.last_update_us.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_parser_drop(&self) {
+        self.parser_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_parser_wait(&self) {
+        self.parser_waits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn set_parser_queue_depth(&self, depth: usize) {
+        self.parser_queue_depth.store(depth as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_reconnect(&self, reason: StreamEnd) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+        if reason == StreamEnd::Stale {
+            self.stale_reconnects.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[inline]
+fn non_zero_ms(ms: u64) -> Option<Duration> {
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// 消费订阅流，直到流关闭或僵死
+///
+/// `ping_interval_ms` / `stale_timeout_ms` 为 0 时分别关闭 ping 与僵死检测。
+/// 非 Ping/Pong 的更新交给 `on_update` 处理。流返回非临时错误（例如服务端拒绝过滤器）时
+/// 重连也无济于事，直接返回该状态。
+pub(crate) async fn pump_stream<Tx, St, F, Fut>(
+    mut subscribe_tx: Tx,
+    mut stream: St,
+    ping_interval_ms: u64,
+    stale_timeout_ms: u64,
+    stats: &StreamStats,
+    mut on_update: F,
+) -> Result<StreamEnd, tonic::Status>
+where
+    Tx: Sink<SubscribeRequest> + Unpin,
+    St: Stream<Item = Result<SubscribeUpdate, tonic::Status>> + Unpin,
+    F: FnMut(SubscribeUpdate) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    let ping_interval = non_zero_ms(ping_interval_ms);
+    let stale_timeout = non_zero_ms(stale_timeout_ms);
+
+    let mut ping_timer = tokio::time::interval(ping_interval.unwrap_or(Duration::from_secs(3600)));
+    ping_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ping_timer.tick().await; // 第一次 tick 立即返回，跳过
+
+    let mut ping_id = 0i32;
+    let mut deadline = stale_timeout.map(|t| tokio::time::Instant::now() + t);
+
+    loop {
+        let stale = async {
+            match deadline {
+                Some(d) => tokio::time::sleep_until(d).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            message = stream.next() => {
+                let Some(message) = message else { return Ok(StreamEnd::Closed) };
+                stats.last_update_us.store(now_micros(), Ordering::Relaxed);
+                deadline = stale_timeout.map(|t| tokio::time::Instant::now() + t);
+
+                match message {
+                    Ok(update) => match &update.update_oneof {
+                        Some(subscribe_update::UpdateOneof::Pong(_)) => {
+                            stats.pongs_received.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(subscribe_update::UpdateOneof::Ping(_)) => {}
+                        // 背压时在这里等待，期间不再读流
+                        _ => on_update(update).await,
+                    },
+                    Err(status) if !is_retryable_code(status.code()) => return Err(status),
+                    Err(e) => error!("Stream error: {:?}", e),
+                }
+            }
+            _ = ping_timer.tick(), if ping_interval.is_some() => {
+                ping_id = ping_id.wrapping_add(1);
+                let ping = SubscribeRequest {
+                    ping: Some(SubscribeRequestPing { id: ping_id }),
+                    ..Default::default()
+                };
+                if subscribe_tx.send(ping).await.is_ok() {
+                    stats.pings_sent.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            _ = stale => {
+                warn!(
+                    "No gRPC stream update for {}ms, treating stream as stale",
+                    stale_timeout_ms
+                );
+                return Ok(StreamEnd::Stale);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::mpsc;
+    use yellowstone_grpc_proto::prelude::{SubscribeUpdatePong, SubscribeUpdateSlot};
+
+    fn update(oneof: subscribe_update::UpdateOneof) -> Result<SubscribeUpdate, tonic::Status> {
+        Ok(SubscribeUpdate { filters: vec![], created_at: None, update_oneof: Some(oneof) })
+    }
+
+    #[tokio::test]
+    async fn test_watchdog_fires_when_stream_goes_silent() {
+        let (ping_tx, mut ping_rx) = mpsc::unbounded::<SubscribeRequest>();
+        let (update_tx, update_rx) = mpsc::unbounded();
+
+        update_tx.unbounded_send(update(subscribe_update::UpdateOneof::Slot(SubscribeUpdateSlot::default()))).unwrap();
+        update_tx.unbounded_send(update(subscribe_update::UpdateOneof::Pong(SubscribeUpdatePong { id: 1 }))).unwrap();
+        // 保持发送端存活：流不会结束，只是不再产生数据
+
+        let stats = StreamStats::default();
+        let mut received = 0;
+        let end = pump_stream(ping_tx, update_rx, 20, 200, &stats, |_| {
+            received += 1;
+            std::future::ready(())
+        }).await.unwrap();
+
+        assert_eq!(end, StreamEnd::Stale);
+        assert_eq!(received, 1);
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.pongs_received, 1);
+        assert!(snapshot.pings_sent >= 2, "pings_sent = {}", snapshot.pings_sent);
+        assert!(snapshot.last_update_us > 0);
+        assert!(ping_rx.try_next().unwrap().unwrap().ping.is_some());
+        drop(update_tx);
+    }
+
+    #[tokio::test]
+    async fn test_closed_stream_without_watchdog() {
+        let (ping_tx, _ping_rx) = mpsc::unbounded::<SubscribeRequest>();
+        let (update_tx, update_rx) = mpsc::unbounded();
+        drop(update_tx);
+
+        let stats = StreamStats::default();
+        let end = pump_stream(ping_tx, update_rx, 0, 0, &stats, |_| std::future::ready(())).await.unwrap();
+        assert_eq!(end, StreamEnd::Closed);
+        assert_eq!(stats.snapshot().pings_sent, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_filter_ends_stream() {
+        let (ping_tx, _ping_rx) = mpsc::unbounded::<SubscribeRequest>();
+        let (update_tx, update_rx) = mpsc::unbounded();
+        update_tx.unbounded_send(Err(tonic::Status::invalid_argument("blocks are disabled"))).unwrap();
+
+        let end = pump_stream(ping_tx, update_rx, 0, 0, &StreamStats::default(), |_| std::future::ready(())).await;
+        assert_eq!(end.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,265 @@
+//! gRPC 连接的 TLS 配置
+//!
+//! [`TlsMode`] 决定 Yellowstone 连接使用哪组根证书：
+//! - `None`：明文连接（x-token 仍会发送），适用于内网或本地 geyser
+//! - `NativeRoots`：系统证书（默认，与之前的行为一致）
+//! - `WebPkiRoots`：内置的 Mozilla 根证书，适合没有系统证书的容器
+//! - `CustomCa`：私有 CA（PEM），构造客户端时即校验
+//! - `Insecure`：跳过证书校验，仅用于开发；需要开启 `insecure-tls` feature，
+//!   未开启时构造客户端直接报错（tonic 的 `ClientTlsConfig` 不支持关闭校验，开启后改用自定义连接器）
+
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use serde::{Deserialize, Serialize};
+use tonic::transport::{Certificate, ClientTlsConfig};
+
+/// TLS 模式
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TlsMode {
+    /// 不使用 TLS
+    None,
+    /// 系统根证书
+    #[default]
+    NativeRoots,
+    /// webpki 内置根证书
+    WebPkiRoots,
+    /// 自定义 CA 证书（PEM，可包含多个证书）
+    CustomCa { pem: Vec<u8> },
+    /// 跳过证书校验（仅开发环境）
+    Insecure,
+}
+
+/// TLS 配置错误
+#[derive(Debug, thiserror::Error)]
+pub enum TlsConfigError {
+    #[error("invalid custom CA PEM: {0}")]
+    InvalidCaPem(String),
+    #[error("custom CA PEM contains no certificates")]
+    EmptyCaPem,
+    #[error("TlsMode::Insecure requires the insecure-tls feature; use TlsMode::CustomCa for self-signed endpoints")]
+    InsecureUnsupported,
+}
+
+impl TlsMode {
+    /// 校验配置（不建立连接）
+    pub fn validate(&self) -> Result<(), TlsConfigError> {
+        self.client_tls_config().map(|_| ())
+    }
+
+    /// 转换为 tonic 的 `ClientTlsConfig`，`None` 模式返回 `Ok(None)`
+    ///
+    /// `Insecure` 不经过 `ClientTlsConfig`：开启 `insecure-tls` 时返回 `Ok(None)`，由 [`insecure`] 连接器处理。
+    pub fn client_tls_config(&self) -> Result<Option<ClientTlsConfig>, TlsConfigError> {
+        let config = match self {
+            TlsMode::None => return Ok(None),
+            TlsMode::NativeRoots => ClientTlsConfig::new().with_native_roots(),
+            TlsMode::WebPkiRoots => ClientTlsConfig::new().with_webpki_roots(),
+            TlsMode::CustomCa { pem } => {
+                let mut count = 0usize;
+                for cert in CertificateDer::pem_slice_iter(pem) {
+                    cert.map_err(|e| TlsConfigError::InvalidCaPem(e.to_string()))?;
+                    count += 1;
+                }
+                if count == 0 {
+                    return Err(TlsConfigError::EmptyCaPem);
+                }
+                ClientTlsConfig::new().ca_certificate(Certificate::from_pem(pem))
+            }
+            #[cfg(feature = "insecure-tls")]
+            TlsMode::Insecure => return Ok(None),
+            #[cfg(not(feature = "insecure-tls"))]
+            TlsMode::Insecure => return Err(TlsConfigError::InsecureUnsupported),
+        };
+        Ok(Some(config))
+    }
+}
+
+/// 跳过证书校验的连接（`insecure-tls` feature）
+///
+/// tonic 只在 https 端点上套自己的 TLS，这里把端点改写为 http，
+/// 在自定义连接器里完成 TLS 握手：不校验证书链和域名，但仍校验握手签名。
+#[cfg(feature = "insecure-tls")]
+pub(crate) mod insecure {
+    use super::TlsMode;
+    use crate::grpc::error::GrpcClientError;
+    use hyper_util::rt::TokioIo;
+    use log::warn;
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+    use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+    use rustls::{DigitallySignedStruct, SignatureScheme};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tonic::transport::{Channel, Endpoint, Uri};
+    use tonic_health::pb::health_client::HealthClient;
+    use yellowstone_grpc_client::{GeyserGrpcBuilder, GeyserGrpcClient, InterceptorXToken};
+    use yellowstone_grpc_proto::prelude::geyser_client::GeyserClient;
+
+    /// 按 TLS 模式建立连接；开启该 feature 后所有模式都走这里，保证返回同一种客户端类型
+    pub(crate) async fn connect(
+        builder: GeyserGrpcBuilder,
+        tls_mode: &TlsMode,
+        connect_timeout: Option<Duration>,
+    ) -> Result<GeyserGrpcClient<InterceptorXToken>, GrpcClientError> {
+        let channel = if *tls_mode == TlsMode::Insecure {
+            insecure_channel(builder.endpoint.uri(), connect_timeout).await?
+        } else {
+            builder.endpoint.connect().await?
+        };
+
+        let interceptor = InterceptorXToken { x_token: builder.x_token, x_request_snapshot: builder.x_request_snapshot };
+        let mut geyser = GeyserClient::with_interceptor(channel.clone(), interceptor.clone());
+        if let Some(limit) = builder.max_decoding_message_size {
+            geyser = geyser.max_decoding_message_size(limit);
+        }
+        Ok(GeyserGrpcClient::new(HealthClient::with_interceptor(channel, interceptor), geyser))
+    }
+
+    async fn insecure_channel(uri: &Uri, connect_timeout: Option<Duration>) -> Result<Channel, GrpcClientError> {
+        let invalid = |reason: &str| GrpcClientError::InvalidEndpoint { endpoint: uri.to_string(), reason: reason.into() };
+        let host = uri.host().ok_or_else(|| invalid("missing host"))?.trim_matches(|c| c == '[' || c == ']').to_string();
+        let port = uri.port_u16().unwrap_or(443);
+        let server_name = ServerName::try_from(host.clone()).map_err(|e| invalid(&e.to_string()))?;
+
+        warn!("TLS certificate verification is DISABLED for {} (TlsMode::Insecure); never use this in production", uri);
+        eprintln!("⚠️  TLS certificate verification is DISABLED for {} (TlsMode::Insecure)", uri);
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = rustls::ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| invalid(&e.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification(provider)))
+            .with_no_client_auth();
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+
+        let path = uri.path_and_query().map_or("/", |p| p.as_str());
+        let mut endpoint = Endpoint::from_shared(format!("http://{}:{}{}", uri.host().unwrap_or_default(), port, path))
+            .map_err(|e| invalid(&e.to_string()))?;
+        if let Some(timeout) = connect_timeout {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        let channel = endpoint
+            .connect_with_connector(tower::service_fn(move |_: Uri| {
+                let (connector, server_name, host) = (connector.clone(), server_name.clone(), host.clone());
+                async move {
+                    let tcp = tokio::net::TcpStream::connect((host.as_str(), port)).await?;
+                    tcp.set_nodelay(true)?;
+                    let tls = connector.connect(server_name, tcp).await?;
+                    Ok::<_, std::io::Error>(TokioIo::new(tls))
+                }
+            }))
+            .await?;
+        Ok(channel)
+    }
+
+    /// 接受任意服务端证书，握手签名仍按 provider 校验
+    #[derive(Debug)]
+    struct SkipServerVerification(Arc<CryptoProvider>);
+
+    impl ServerCertVerifier for SkipServerVerification {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.0.signature_verification_algorithms.supported_schemes()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 自签名测试 CA（openssl 生成，仅用于测试 PEM 解析）
+    const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBljCCAT2gAwIBAgIUQHpVm50pbxIl1byuPpc4/nOUBYswCgYIKoZIzj0EAwIw
+ITEfMB0GA1UEAwwWc29sLXBhcnNlci1zZGstdGVzdC1jYTAeFw0yNjEwMTYxNTQz
+MThaFw0zNjEwMTMxNTQzMThaMCExHzAdBgNVBAMMFnNvbC1wYXJzZXItc2RrLXRl
+c3QtY2EwWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAAQEaigFlCh19jcbOICsMDHj
+QiJOdQbcLFsRuMg3GAbv+fJmIqWII/a/sUyKd/rf53nxbavobFaMAPIfBr9KrAQQ
+o1MwUTAdBgNVHQ4EFgQUgwDeLWZ5hx8iFT3ecgKkITNfG30wHwYDVR0jBBgwFoAU
+gwDeLWZ5hx8iFT3ecgKkITNfG30wDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQD
+AgNHADBEAiA7Yh7kjSEYP7omKzWwFQwgbybDqMiv0okf7ewMPDTjwAIgbmV0F25c
+KjUeRckOsOLv43i13IC4oHQOalKAq3eKZ7s=
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_tls_mode_mapping() {
+        assert!(TlsMode::None.client_tls_config().unwrap().is_none());
+        assert!(TlsMode::NativeRoots.client_tls_config().unwrap().is_some());
+        assert!(TlsMode::WebPkiRoots.client_tls_config().unwrap().is_some());
+
+        let custom = TlsMode::CustomCa { pem: TEST_CA_PEM.as_bytes().to_vec() };
+        assert!(custom.client_tls_config().unwrap().is_some());
+
+        #[cfg(not(feature = "insecure-tls"))]
+        assert!(matches!(TlsMode::Insecure.validate(), Err(TlsConfigError::InsecureUnsupported)));
+        #[cfg(feature = "insecure-tls")]
+        assert!(TlsMode::Insecure.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_custom_ca_rejected() {
+        let empty = TlsMode::CustomCa { pem: b"not a pem".to_vec() };
+        assert!(matches!(empty.validate(), Err(TlsConfigError::EmptyCaPem)));
+
+        let broken = TlsMode::CustomCa {
+            pem: b"-----BEGIN CERTIFICATE-----\n!!!!\n-----END CERTIFICATE-----\n".to_vec(),
+        };
+        assert!(matches!(broken.validate(), Err(TlsConfigError::InvalidCaPem(_))));
+    }
+
+    #[test]
+    fn test_client_config_flow() {
+        use crate::grpc::{ClientConfig, YellowstoneGrpc};
+
+        let mut config = ClientConfig::default();
+        assert_eq!(config.tls_mode(), &TlsMode::NativeRoots);
+        config.enable_tls = false;
+        assert_eq!(config.tls_mode(), &TlsMode::None);
+
+        // 私有端点：自定义 CA + 非 443 端口
+        let config = ClientConfig {
+            tls: TlsMode::CustomCa { pem: TEST_CA_PEM.as_bytes().to_vec() },
+            ..ClientConfig::default()
+        };
+        assert!(YellowstoneGrpc::new_with_config("https://geyser.internal:10000".into(), None, config).is_ok());
+
+        let config = ClientConfig {
+            tls: TlsMode::CustomCa { pem: b"garbage".to_vec() },
+            ..ClientConfig::default()
+        };
+        let err = YellowstoneGrpc::new_with_config("https://geyser.internal:10000".into(), None, config)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("custom CA PEM"));
+    }
+}
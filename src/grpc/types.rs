@@ -1,6 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::core::events::{DexEvent, WSOL_MINT};
+use crate::core::merger::MergePreferences;
+use crate::core::unified_parser::ParseMode;
+#[cfg(feature = "grpc")]
+use super::config::Commitment;
+#[cfg(feature = "grpc")]
+use super::tls::TlsMode;
+
+/// 客户端配置（`grpc` feature）
+#[cfg(feature = "grpc")]
+///
+/// 推荐使用 [`ClientConfig::builder`] 构造（会校验非法组合）；字段仍保持公开以兼容旧代码。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClientConfig {
     /// 是否启用性能监控
@@ -9,16 +21,129 @@ pub struct ClientConfig {
     pub connection_timeout_ms: u64,
     /// 请求超时时间（毫秒）
     pub request_timeout_ms: u64,
-    /// 是否启用TLS
+    /// 是否启用TLS（为 false 时忽略 `tls`，使用明文连接）
     pub enable_tls: bool,
+    /// TLS 模式（根证书来源 / 自定义 CA）
+    #[serde(default)]
+    pub tls: TlsMode,
     pub max_retries: u32,
     pub retry_delay_ms: u64,
     pub max_concurrent_streams: u32,
+    /// HTTP/2 keepalive ping 间隔（毫秒，0 表示关闭），连接空闲时同样发送
     pub keep_alive_interval_ms: u64,
+    /// HTTP/2 keepalive 应答超时（毫秒），超时后连接被关闭，由订阅任务重连
     pub keep_alive_timeout_ms: u64,
     pub buffer_size: usize,
+    /// 订阅流 ping 间隔（毫秒，0 表示不发送）
+    #[serde(default)]
+    pub ping_interval_ms: u64,
+    /// 超过该时间未收到任何消息（包括 pong）即视为僵死并重连（毫秒，0 表示关闭）
+    #[serde(default)]
+    pub stale_stream_timeout_ms: u64,
+    /// 启用的协议（未启用的协议跳过解析）
+    #[serde(default)]
+    pub enabled_protocols: EnabledProtocols,
+    /// 订阅的确认级别
+    #[serde(default)]
+    pub commitment: Commitment,
+    /// gRPC 单条消息的解码上限（字节）
+    #[serde(default = "default_max_decoding_message_size")]
+    pub max_decoding_message_size: usize,
+    /// 事件队列容量（每个消费者）
+    #[serde(default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// 每笔交易只输出第一个日志事件（延迟更低，但会丢弃同笔交易的其它事件，如 create + buy、批量成交）
+    ///
+    /// 默认关闭，`low_latency` 预设开启。
+    #[serde(default = "default_first_event_only")]
+    pub first_event_only: bool,
+    /// 重连时从最后收到的 slot 继续订阅（`from_slot`，该 slot 的事件可能重复）
+    #[serde(default)]
+    pub resume_from_slot: bool,
+    /// 单个过滤器的账户数上限，超过时拆分为多个命名过滤器（部分服务商限制为 50 左右）
+    #[serde(default = "default_max_filter_accounts")]
+    pub max_filter_accounts: usize,
+    /// 允许不带任何账户条件的过滤器（订阅全链交易/账户）
+    #[serde(default)]
+    pub allow_firehose: bool,
+    /// 解析线程数：0 表示在 gRPC 接收任务内解析；大于 0 时接收任务只记录接收时间并转交交易，
+    /// 由独立线程解析（同一交易内的事件顺序不变，不同交易之间的先后不再保证，见 [`super::workers`]）
+    #[serde(default)]
+    pub parser_threads: usize,
+    /// 接收任务到解析线程的转交队列容量，0 表示沿用 `queue_capacity`
+    #[serde(default)]
+    pub parser_queue_capacity: usize,
+    /// 转交队列已满时接收任务异步等待解析线程（背压，默认开启），等待期间不再读取订阅流；
+    /// 关闭时丢弃交易（计入 `parser_dropped`），[`Self::low_latency`] 预设关闭
+    #[serde(default = "default_parser_backpressure")]
+    pub parser_backpressure: bool,
+    /// 解析线程绑定的 CPU 核心，按线程序号循环使用；为空时不绑定（仅 Linux 生效）
+    #[serde(default)]
+    pub parser_core_ids: Vec<usize>,
+    /// 同一交易在不同协议上成交同一代币时追加 `DexEvent::CrossVenueTrade`（见 [`crate::core::arbitrage`]）；
+    /// 在解析阶段执行，建议配合 `parser_threads` 使用，避免占用接收任务
+    #[serde(default)]
+    pub detect_cross_venue_trades: bool,
+    /// 订阅接收循环绑定的 CPU 核心：设置后每个订阅的收流、ping 和内联解析在独立线程
+    /// （单线程 tokio runtime）上运行并绑核，不再与其它 tokio 任务共享工作线程（仅 Linux 生效）
+    #[serde(default)]
+    pub receiver_core_id: Option<usize>,
+    /// 订阅 slot 状态更新并以 `DexEvent::SlotStatus` 输出（None 表示不订阅）
+    #[serde(default)]
+    pub slot_updates: Option<SlotFilter>,
+    /// 初始化客户端时校验 discriminator（重复或疑似占位值时输出警告，见 [`crate::core::discriminators`]）
+    #[serde(default)]
+    pub validate_discriminators: bool,
+    /// 订阅时用解析出的事件更新 [`crate::core::state::PoolStateTracker`]，通过 `YellowstoneGrpc::state()` 读取
+    #[serde(default)]
+    pub track_pool_state: bool,
+    /// 已支持程序中无法识别的指令输出 `DexEvent::UnknownSwap`（仅作用于该客户端，见 `ParseOptions.emit_unknown`）
+    #[serde(default)]
+    pub emit_unknown_instructions: bool,
+    /// 合并指令事件和日志事件时各字段组的优先来源（见 `ParseOptions.merge`）
+    #[serde(default)]
+    pub merge_preferences: MergePreferences,
+    /// 指令事件保留指令原始数据（前 256 字节），通过 `DexEvent::raw_discriminator` / `raw_instruction` 读取，
+    /// 用于排查解码异常（仅作用于该客户端，见 `ParseOptions.retain_raw`）
+    #[serde(default)]
+    pub retain_raw: bool,
+    /// 解析范围：只看日志 / 只看指令 / 两者合并（默认）
+    #[serde(default)]
+    pub parse_mode: ParseMode,
+    /// 超大交易保护（日志条数、单行长度、指令数上限，默认不限制，见 [`crate::grpc::guard`]）
+    #[serde(default)]
+    pub tx_size_guard: crate::grpc::guard::TxSizeGuard,
+    /// 按 `event_id` 去掉重复输出的交易事件（默认关闭，见 [`crate::grpc::dedup`]）
+    #[serde(default)]
+    pub dedup: crate::grpc::dedup::DedupConfig,
+}
+
+#[cfg(feature = "grpc")]
+fn default_max_decoding_message_size() -> usize {
+    1024 * 1024 * 1024
+}
+
+#[cfg(feature = "grpc")]
+fn default_queue_capacity() -> usize {
+    100_000
+}
+
+#[cfg(feature = "grpc")]
+fn default_first_event_only() -> bool {
+    false
+}
+
+#[cfg(feature = "grpc")]
+fn default_max_filter_accounts() -> usize {
+    50
+}
+
+#[cfg(feature = "grpc")]
+fn default_parser_backpressure() -> bool {
+    true
 }
 
+#[cfg(feature = "grpc")]
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
@@ -26,29 +151,118 @@ impl Default for ClientConfig {
             connection_timeout_ms: 8000,
             request_timeout_ms: 15000,
             enable_tls: true,
+            tls: TlsMode::NativeRoots,
             max_retries: 3,
             retry_delay_ms: 1000,
             max_concurrent_streams: 100,
             keep_alive_interval_ms: 30000,
             keep_alive_timeout_ms: 5000,
             buffer_size: 8192,
+            ping_interval_ms: 10000,
+            stale_stream_timeout_ms: 30000,
+            enabled_protocols: EnabledProtocols::all(),
+            commitment: Commitment::Processed,
+            max_decoding_message_size: default_max_decoding_message_size(),
+            queue_capacity: default_queue_capacity(),
+            first_event_only: default_first_event_only(),
+            resume_from_slot: false,
+            max_filter_accounts: default_max_filter_accounts(),
+            allow_firehose: false,
+            parser_threads: 0,
+            parser_queue_capacity: 0,
+            parser_backpressure: true,
+            parser_core_ids: Vec::new(),
+            detect_cross_venue_trades: false,
+            receiver_core_id: None,
+            slot_updates: None,
+            validate_discriminators: false,
+            track_pool_state: false,
+            emit_unknown_instructions: false,
+            merge_preferences: MergePreferences::default(),
+            retain_raw: false,
+            parse_mode: ParseMode::Merged,
+            tx_size_guard: Default::default(),
+            dedup: Default::default(),
         }
     }
 }
 
+#[cfg(feature = "grpc")]
 impl ClientConfig {
+    /// 实际生效的 TLS 模式
+    pub fn tls_mode(&self) -> &TlsMode {
+        if self.enable_tls { &self.tls } else { &TlsMode::None }
+    }
+
+    /// 低延迟：processed 确认级别、小容量队列、每笔交易只取第一个事件
     pub fn low_latency() -> Self {
         Self {
             enable_metrics: false,
             connection_timeout_ms: 5000,
             request_timeout_ms: 10000,
             enable_tls: true,
+            tls: TlsMode::NativeRoots,
             max_retries: 1,
             retry_delay_ms: 100,
             max_concurrent_streams: 200,
             keep_alive_interval_ms: 10000,
             keep_alive_timeout_ms: 2000,
             buffer_size: 16384,
+            ping_interval_ms: 5000,
+            stale_stream_timeout_ms: 15000,
+            enabled_protocols: EnabledProtocols::all(),
+            commitment: Commitment::Processed,
+            max_decoding_message_size: default_max_decoding_message_size(),
+            queue_capacity: 10_000,
+            first_event_only: true,
+            resume_from_slot: false,
+            max_filter_accounts: default_max_filter_accounts(),
+            allow_firehose: false,
+            parser_threads: 0,
+            parser_queue_capacity: 0,
+            // 宁可丢弃交易也不让接收滞后
+            parser_backpressure: false,
+            parser_core_ids: Vec::new(),
+            detect_cross_venue_trades: false,
+            receiver_core_id: None,
+            slot_updates: None,
+            validate_discriminators: false,
+            track_pool_state: false,
+            emit_unknown_instructions: false,
+            merge_preferences: MergePreferences::default(),
+            retain_raw: false,
+            parse_mode: ParseMode::Merged,
+            tx_size_guard: Default::default(),
+            dedup: Default::default(),
+        }
+    }
+
+    /// 可靠订阅：confirmed 确认级别，断线后从最后的 slot 续订，更大的队列，输出全部事件
+    pub fn reliable() -> Self {
+        Self {
+            connection_timeout_ms: 10000,
+            request_timeout_ms: 30000,
+            max_retries: 20,
+            retry_delay_ms: 2000,
+            commitment: Commitment::Confirmed,
+            queue_capacity: 1_000_000,
+            first_event_only: false,
+            resume_from_slot: true,
+            ..Self::default()
+        }
+    }
+
+    /// 回填历史数据：不限制解码大小、关闭性能监控、finalized 确认级别
+    pub fn backfill() -> Self {
+        Self {
+            enable_metrics: false,
+            request_timeout_ms: 60000,
+            commitment: Commitment::Finalized,
+            max_decoding_message_size: usize::MAX,
+            queue_capacity: 1_000_000,
+            first_event_only: false,
+            resume_from_slot: true,
+            ..Self::default()
         }
     }
 
@@ -58,12 +272,38 @@ impl ClientConfig {
             connection_timeout_ms: 10000,
             request_timeout_ms: 30000,
             enable_tls: true,
+            tls: TlsMode::NativeRoots,
             max_retries: 5,
             retry_delay_ms: 2000,
             max_concurrent_streams: 500,
             keep_alive_interval_ms: 60000,
             keep_alive_timeout_ms: 10000,
             buffer_size: 32768,
+            ping_interval_ms: 15000,
+            stale_stream_timeout_ms: 60000,
+            enabled_protocols: EnabledProtocols::all(),
+            commitment: Commitment::Processed,
+            max_decoding_message_size: default_max_decoding_message_size(),
+            queue_capacity: default_queue_capacity(),
+            first_event_only: default_first_event_only(),
+            resume_from_slot: false,
+            max_filter_accounts: default_max_filter_accounts(),
+            allow_firehose: false,
+            parser_threads: 0,
+            parser_queue_capacity: 0,
+            parser_backpressure: true,
+            parser_core_ids: Vec::new(),
+            detect_cross_venue_trades: false,
+            receiver_core_id: None,
+            slot_updates: None,
+            validate_discriminators: false,
+            track_pool_state: false,
+            emit_unknown_instructions: false,
+            merge_preferences: MergePreferences::default(),
+            retain_raw: false,
+            parse_mode: ParseMode::Merged,
+            tx_size_guard: Default::default(),
+            dedup: Default::default(),
         }
     }
 }
@@ -73,6 +313,8 @@ pub struct TransactionFilter {
     pub account_include: Vec<String>,
     pub account_exclude: Vec<String>,
     pub account_required: Vec<String>,
+    /// 过滤器覆盖的协议（由 for_protocols 设置）
+    pub protocols: ProtocolSet,
 }
 
 impl TransactionFilter {
@@ -81,6 +323,7 @@ impl TransactionFilter {
             account_include: Vec::new(),
             account_exclude: Vec::new(),
             account_required: Vec::new(),
+            protocols: ProtocolSet::empty(),
         }
     }
 
@@ -105,6 +348,7 @@ impl TransactionFilter {
             account_include: program_ids,
             account_exclude: Vec::new(),
             account_required: Vec::new(),
+            protocols: ProtocolSet::empty(),
         }
     }
 }
@@ -115,6 +359,93 @@ impl Default for TransactionFilter {
     }
 }
 
+/// 交易状态订阅过滤器（`SubscribeRequestFilterTransactions`，用于 `transactions_status`）
+///
+/// 只推送签名、slot 和执行错误，不含交易内容。`signature` 与账户条件之间是"与"关系。
+#[derive(Debug, Clone)]
+pub struct TransactionStatusFilter {
+    /// 只关注某个签名（base58）
+    pub signature: Option<String>,
+    pub account_include: Vec<String>,
+    pub account_exclude: Vec<String>,
+    pub account_required: Vec<String>,
+    /// 是否包含执行失败的交易（默认包含）
+    pub include_failed: bool,
+}
+
+impl TransactionStatusFilter {
+    pub fn new() -> Self {
+        Self {
+            signature: None,
+            account_include: Vec::new(),
+            account_exclude: Vec::new(),
+            account_required: Vec::new(),
+            include_failed: true,
+        }
+    }
+
+    /// 只关注某个签名
+    pub fn for_signature(signature: impl Into<String>) -> Self {
+        Self { signature: Some(signature.into()), ..Self::new() }
+    }
+
+    pub fn include_account(mut self, account: impl Into<String>) -> Self {
+        self.account_include.push(account.into());
+        self
+    }
+
+    pub fn exclude_account(mut self, account: impl Into<String>) -> Self {
+        self.account_exclude.push(account.into());
+        self
+    }
+
+    pub fn require_account(mut self, account: impl Into<String>) -> Self {
+        self.account_required.push(account.into());
+        self
+    }
+
+    pub fn with_failed(mut self, include_failed: bool) -> Self {
+        self.include_failed = include_failed;
+        self
+    }
+}
+
+impl Default for TransactionStatusFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 区块订阅过滤器（`SubscribeRequestFilterBlocks`，始终包含交易）
+///
+/// `account_include` 为空时订阅全部区块；不为空时服务端只推送包含这些账户的交易。
+#[derive(Debug, Clone)]
+pub struct BlockFilter {
+    pub account_include: Vec<String>,
+    /// 过滤器覆盖的协议（由 for_protocols 设置）
+    pub protocols: ProtocolSet,
+}
+
+impl BlockFilter {
+    pub fn new() -> Self {
+        Self {
+            account_include: Vec::new(),
+            protocols: ProtocolSet::empty(),
+        }
+    }
+
+    pub fn include_account(mut self, account: impl Into<String>) -> Self {
+        self.account_include.push(account.into());
+        self
+    }
+}
+
+impl Default for BlockFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AccountFilter {
     pub account: Vec<String>,
@@ -162,32 +493,223 @@ impl Default for AccountFilter {
     }
 }
 
+/// 账户数据过滤条件，由 Geyser 服务端执行；同一项中 `memcmp` 与 `datasize` 都设置时需同时满足
 #[derive(Debug, Clone)]
 pub struct AccountFilterData {
     pub memcmp: Option<AccountFilterMemcmp>,
     pub datasize: Option<u64>,
 }
 
+impl AccountFilterData {
+    /// 账户数据 `offset` 处的字节等于 `bytes`
+    pub fn memcmp(offset: u64, bytes: impl Into<Vec<u8>>) -> Self {
+        Self { memcmp: Some(AccountFilterMemcmp { offset, bytes: bytes.into() }), datasize: None }
+    }
+
+    /// 账户数据长度等于 `size`
+    pub fn datasize(size: u64) -> Self {
+        Self { memcmp: None, datasize: Some(size) }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AccountFilterMemcmp {
     pub offset: u64,
     pub bytes: Vec<u8>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Protocol {
-    PumpFun,
-    PumpSwap,
-    Bonk,
-    RaydiumCpmm,
-    RaydiumClmm,
-    RaydiumAmmV4,
+/// 协议枚举定义在 [`crate::core::protocol`]，此处保留原路径
+pub use crate::core::protocol::Protocol;
+
+/// 协议位集合 - 热路径上的多协议判断只需一次按位与
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProtocolSet(u32);
+
+impl ProtocolSet {
+    pub const EMPTY: ProtocolSet = ProtocolSet(0);
+
+    #[inline(always)]
+    pub const fn empty() -> Self {
+        Self::EMPTY
+    }
+
+    pub fn all() -> Self {
+        Self::all_const()
+    }
+
+    #[inline(always)]
+    const fn all_const() -> Self {
+        Self((1 << Protocol::ALL.len()) - 1)
+    }
+
+    #[inline(always)]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    #[inline(always)]
+    pub const fn contains(self, protocol: Protocol) -> bool {
+        self.0 & protocol.bit() != 0
+    }
+
+    #[inline(always)]
+    pub const fn intersects(self, other: ProtocolSet) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline(always)]
+    pub const fn union(self, other: ProtocolSet) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline(always)]
+    pub const fn intersection(self, other: ProtocolSet) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    #[inline]
+    pub fn insert(&mut self, protocol: Protocol) {
+        self.0 |= protocol.bit();
+    }
+
+    #[inline]
+    pub fn remove(&mut self, protocol: Protocol) {
+        self.0 &= !protocol.bit();
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// 按声明顺序遍历集合中的协议
+    pub fn iter(self) -> impl Iterator<Item = Protocol> {
+        Protocol::ALL.into_iter().filter(move |p| self.contains(*p))
+    }
+
+    /// 事件是否属于集合中的某个协议
+    #[inline]
+    pub fn contains_event(self, event: &DexEvent) -> bool {
+        EventType::from_event(event)
+            .and_then(EventType::protocol)
+            .is_some_and(|p| self.contains(p))
+    }
+}
+
+impl From<Protocol> for ProtocolSet {
+    fn from(protocol: Protocol) -> Self {
+        Self(protocol.bit())
+    }
+}
+
+impl From<&[Protocol]> for ProtocolSet {
+    fn from(protocols: &[Protocol]) -> Self {
+        protocols.iter().copied().collect()
+    }
+}
+
+impl From<&Vec<Protocol>> for ProtocolSet {
+    fn from(protocols: &Vec<Protocol>) -> Self {
+        protocols.as_slice().into()
+    }
+}
+
+impl FromIterator<Protocol> for ProtocolSet {
+    fn from_iter<I: IntoIterator<Item = Protocol>>(iter: I) -> Self {
+        let mut set = Self::empty();
+        for protocol in iter {
+            set.insert(protocol);
+        }
+        set
+    }
+}
+
+impl std::ops::BitOr for ProtocolSet {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl std::ops::BitAnd for ProtocolSet {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl std::ops::BitOrAssign for ProtocolSet {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// 启用的协议（默认全部启用）
+///
+/// 未启用协议的指令与日志在解码前即被跳过，判别器也不再检查。每个客户端按自己的
+/// `ClientConfig::enabled_protocols` 解析；直接调用解析函数时通过
+/// [`ParseOptions::enabled_protocols`](crate::core::unified_parser::ParseOptions) 传入。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<Protocol>", into = "Vec<Protocol>")]
+pub struct EnabledProtocols(ProtocolSet);
+
+impl EnabledProtocols {
+    pub fn all() -> Self {
+        Self(ProtocolSet::all())
+    }
+
+    /// 只启用给定协议
+    pub fn only(protocols: impl IntoIterator<Item = Protocol>) -> Self {
+        Self(protocols.into_iter().collect())
+    }
+
+    pub fn with(mut self, protocol: Protocol) -> Self {
+        self.0.insert(protocol);
+        self
+    }
+
+    pub fn without(mut self, protocol: Protocol) -> Self {
+        self.0.remove(protocol);
+        self
+    }
+
+    #[inline(always)]
+    pub fn contains(self, protocol: Protocol) -> bool {
+        self.0.contains(protocol)
+    }
+
+    pub fn protocols(self) -> ProtocolSet {
+        self.0
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Default for EnabledProtocols {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl From<Vec<Protocol>> for EnabledProtocols {
+    fn from(protocols: Vec<Protocol>) -> Self {
+        Self::only(protocols)
+    }
+}
+
+impl From<EnabledProtocols> for Vec<Protocol> {
+    fn from(enabled: EnabledProtocols) -> Self {
+        enabled.0.iter().collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EventType {
     // Block events
     BlockMeta,
+    SlotStatus,
 
     // Bonk events
     BonkTrade,
@@ -199,15 +721,22 @@ pub enum EventType {
     PumpFunCreate,
     PumpFunComplete,
     PumpFunMigrate,
+    PumpFunSetParams,
 
     // PumpSwap events
     PumpSwapBuy,
     PumpSwapSell,
     PumpSwapCreatePool,
+    #[deprecated(note = "no parser produces this type; use PumpSwapCreatePool")]
     PumpSwapPoolCreated,
+    #[deprecated(note = "no parser produces this type; use PumpSwapBuy / PumpSwapSell")]
     PumpSwapTrade,
+    #[deprecated(note = "no parser produces this type; use PumpSwapDeposit")]
     PumpSwapLiquidityAdded,
+    #[deprecated(note = "no parser produces this type; use PumpSwapWithdraw")]
     PumpSwapLiquidityRemoved,
+    PumpSwapDeposit,
+    PumpSwapWithdraw,
     PumpSwapPoolUpdated,
     PumpSwapFeesClaimed,
 
@@ -216,6 +745,7 @@ pub enum EventType {
     RaydiumCpmmDeposit,
     RaydiumCpmmWithdraw,
     RaydiumCpmmInitialize,
+    RaydiumCpmmPoolStatusUpdated,
 
     // Raydium CLMM events
     RaydiumClmmSwap,
@@ -233,12 +763,16 @@ pub enum EventType {
     RaydiumAmmV4Withdraw,
     RaydiumAmmV4Initialize2,
     RaydiumAmmV4WithdrawPnl,
+    /// 池子账户更新（账户订阅）
+    RaydiumAmmV4PoolState,
 
     // Orca Whirlpool events
     OrcaWhirlpoolSwap,
     OrcaWhirlpoolLiquidityIncreased,
     OrcaWhirlpoolLiquidityDecreased,
     OrcaWhirlpoolPoolInitialized,
+    OrcaWhirlpoolCollectFees,
+    OrcaWhirlpoolCollectReward,
 
     // Meteora events
     MeteoraPoolsSwap,
@@ -264,27 +798,332 @@ pub enum EventType {
     TokenAccount,
     NonceAccount,
     TokenInfo,
+
+    // 运行时注册的自定义协议事件
+    Custom,
+
+    // 已支持程序中无法识别的指令
+    UnknownSwap,
+
+    // 跨协议成交
+    CrossVenueTrade,
+}
+
+impl EventType {
+    /// 会实际产生事件的类型（不含已废弃的类型）
+    pub const LIVE: [EventType; 64] = [
+        EventType::BlockMeta,
+        EventType::SlotStatus,
+        EventType::BonkTrade,
+        EventType::BonkPoolCreate,
+        EventType::BonkMigrateAmm,
+        EventType::PumpFunTrade,
+        EventType::PumpFunCreate,
+        EventType::PumpFunComplete,
+        EventType::PumpFunMigrate,
+        EventType::PumpFunSetParams,
+        EventType::PumpSwapBuy,
+        EventType::PumpSwapSell,
+        EventType::PumpSwapCreatePool,
+        EventType::PumpSwapDeposit,
+        EventType::PumpSwapWithdraw,
+        EventType::PumpSwapPoolUpdated,
+        EventType::PumpSwapFeesClaimed,
+        EventType::RaydiumCpmmSwap,
+        EventType::RaydiumCpmmDeposit,
+        EventType::RaydiumCpmmWithdraw,
+        EventType::RaydiumCpmmInitialize,
+        EventType::RaydiumCpmmPoolStatusUpdated,
+        EventType::RaydiumClmmSwap,
+        EventType::RaydiumClmmCreatePool,
+        EventType::RaydiumClmmOpenPosition,
+        EventType::RaydiumClmmClosePosition,
+        EventType::RaydiumClmmIncreaseLiquidity,
+        EventType::RaydiumClmmDecreaseLiquidity,
+        EventType::RaydiumClmmOpenPositionWithTokenExtNft,
+        EventType::RaydiumClmmCollectFee,
+        EventType::RaydiumAmmV4Swap,
+        EventType::RaydiumAmmV4Deposit,
+        EventType::RaydiumAmmV4Withdraw,
+        EventType::RaydiumAmmV4Initialize2,
+        EventType::RaydiumAmmV4WithdrawPnl,
+        EventType::RaydiumAmmV4PoolState,
+        EventType::OrcaWhirlpoolSwap,
+        EventType::OrcaWhirlpoolLiquidityIncreased,
+        EventType::OrcaWhirlpoolLiquidityDecreased,
+        EventType::OrcaWhirlpoolPoolInitialized,
+        EventType::OrcaWhirlpoolCollectFees,
+        EventType::OrcaWhirlpoolCollectReward,
+        EventType::MeteoraPoolsSwap,
+        EventType::MeteoraPoolsAddLiquidity,
+        EventType::MeteoraPoolsRemoveLiquidity,
+        EventType::MeteoraPoolsBootstrapLiquidity,
+        EventType::MeteoraPoolsPoolCreated,
+        EventType::MeteoraPoolsSetPoolFees,
+        EventType::MeteoraDammV2Swap,
+        EventType::MeteoraDammV2AddLiquidity,
+        EventType::MeteoraDammV2RemoveLiquidity,
+        EventType::MeteoraDammV2InitializePool,
+        EventType::MeteoraDammV2CreatePosition,
+        EventType::MeteoraDammV2ClosePosition,
+        EventType::MeteoraDammV2ClaimPositionFee,
+        EventType::MeteoraDammV2InitializeReward,
+        EventType::MeteoraDammV2FundReward,
+        EventType::MeteoraDammV2ClaimReward,
+        EventType::TokenAccount,
+        EventType::NonceAccount,
+        EventType::TokenInfo,
+        EventType::Custom,
+        EventType::UnknownSwap,
+        EventType::CrossVenueTrade,
+    ];
+
+    /// 根据 DexEvent 获取对应的事件类型（没有对应类型的事件返回 None）
+    #[allow(deprecated)]
+    pub fn from_event(event: &DexEvent) -> Option<Self> {
+        let event_type = match event {
+            DexEvent::BlockMeta(_) => EventType::BlockMeta,
+            DexEvent::SlotStatus(_) => EventType::SlotStatus,
+            DexEvent::BonkTrade(_) => EventType::BonkTrade,
+            DexEvent::BonkPoolCreate(_) => EventType::BonkPoolCreate,
+            DexEvent::BonkMigrateAmm(_) => EventType::BonkMigrateAmm,
+            DexEvent::PumpFunTrade(_) => EventType::PumpFunTrade,
+            DexEvent::PumpFunCreate(_) => EventType::PumpFunCreate,
+            DexEvent::PumpFunComplete(_) => EventType::PumpFunComplete,
+            DexEvent::PumpFunMigrate(_) => EventType::PumpFunMigrate,
+            DexEvent::PumpFunSetParams(_) => EventType::PumpFunSetParams,
+            DexEvent::PumpSwapBuy(_) => EventType::PumpSwapBuy,
+            DexEvent::PumpSwapSell(_) => EventType::PumpSwapSell,
+            DexEvent::PumpSwapCreatePool(_) => EventType::PumpSwapCreatePool,
+            DexEvent::PumpSwapPoolCreated(_) => EventType::PumpSwapPoolCreated,
+            DexEvent::PumpSwapTrade(_) => EventType::PumpSwapTrade,
+            DexEvent::PumpSwapLiquidityAdded(_) => EventType::PumpSwapLiquidityAdded,
+            DexEvent::PumpSwapLiquidityRemoved(_) => EventType::PumpSwapLiquidityRemoved,
+            DexEvent::PumpSwapDeposit(_) => EventType::PumpSwapDeposit,
+            DexEvent::PumpSwapWithdraw(_) => EventType::PumpSwapWithdraw,
+            DexEvent::PumpSwapPoolUpdated(_) => EventType::PumpSwapPoolUpdated,
+            DexEvent::PumpSwapFeesClaimed(_) => EventType::PumpSwapFeesClaimed,
+            DexEvent::RaydiumCpmmSwap(_) => EventType::RaydiumCpmmSwap,
+            DexEvent::RaydiumCpmmDeposit(_) => EventType::RaydiumCpmmDeposit,
+            DexEvent::RaydiumCpmmWithdraw(_) => EventType::RaydiumCpmmWithdraw,
+            DexEvent::RaydiumCpmmInitialize(_) => EventType::RaydiumCpmmInitialize,
+            DexEvent::RaydiumCpmmPoolStatusUpdated(_) => EventType::RaydiumCpmmPoolStatusUpdated,
+            DexEvent::RaydiumClmmSwap(_) => EventType::RaydiumClmmSwap,
+            DexEvent::RaydiumClmmCreatePool(_) => EventType::RaydiumClmmCreatePool,
+            DexEvent::RaydiumClmmOpenPosition(_) => EventType::RaydiumClmmOpenPosition,
+            DexEvent::RaydiumClmmClosePosition(_) => EventType::RaydiumClmmClosePosition,
+            DexEvent::RaydiumClmmIncreaseLiquidity(_) => EventType::RaydiumClmmIncreaseLiquidity,
+            DexEvent::RaydiumClmmDecreaseLiquidity(_) => EventType::RaydiumClmmDecreaseLiquidity,
+            DexEvent::RaydiumClmmOpenPositionWithTokenExtNft(_) => EventType::RaydiumClmmOpenPositionWithTokenExtNft,
+            DexEvent::RaydiumClmmCollectFee(_) => EventType::RaydiumClmmCollectFee,
+            DexEvent::RaydiumAmmV4Swap(_) => EventType::RaydiumAmmV4Swap,
+            DexEvent::RaydiumAmmV4Deposit(_) => EventType::RaydiumAmmV4Deposit,
+            DexEvent::RaydiumAmmV4Withdraw(_) => EventType::RaydiumAmmV4Withdraw,
+            DexEvent::RaydiumAmmV4Initialize2(_) => EventType::RaydiumAmmV4Initialize2,
+            DexEvent::RaydiumAmmV4WithdrawPnl(_) => EventType::RaydiumAmmV4WithdrawPnl,
+            DexEvent::RaydiumAmmV4PoolState(_) => EventType::RaydiumAmmV4PoolState,
+            DexEvent::OrcaWhirlpoolSwap(_) => EventType::OrcaWhirlpoolSwap,
+            DexEvent::OrcaWhirlpoolLiquidityIncreased(_) => EventType::OrcaWhirlpoolLiquidityIncreased,
+            DexEvent::OrcaWhirlpoolLiquidityDecreased(_) => EventType::OrcaWhirlpoolLiquidityDecreased,
+            DexEvent::OrcaWhirlpoolPoolInitialized(_) => EventType::OrcaWhirlpoolPoolInitialized,
+            DexEvent::OrcaWhirlpoolCollectFees(_) => EventType::OrcaWhirlpoolCollectFees,
+            DexEvent::OrcaWhirlpoolCollectReward(_) => EventType::OrcaWhirlpoolCollectReward,
+            DexEvent::MeteoraPoolsSwap(_) => EventType::MeteoraPoolsSwap,
+            DexEvent::MeteoraPoolsAddLiquidity(_) => EventType::MeteoraPoolsAddLiquidity,
+            DexEvent::MeteoraPoolsRemoveLiquidity(_) => EventType::MeteoraPoolsRemoveLiquidity,
+            DexEvent::MeteoraPoolsBootstrapLiquidity(_) => EventType::MeteoraPoolsBootstrapLiquidity,
+            DexEvent::MeteoraPoolsPoolCreated(_) => EventType::MeteoraPoolsPoolCreated,
+            DexEvent::MeteoraPoolsSetPoolFees(_) => EventType::MeteoraPoolsSetPoolFees,
+            DexEvent::MeteoraDammV2Swap(_) => EventType::MeteoraDammV2Swap,
+            DexEvent::MeteoraDammV2AddLiquidity(_) => EventType::MeteoraDammV2AddLiquidity,
+            DexEvent::MeteoraDammV2RemoveLiquidity(_) => EventType::MeteoraDammV2RemoveLiquidity,
+            DexEvent::MeteoraDammV2InitializePool(_) => EventType::MeteoraDammV2InitializePool,
+            DexEvent::MeteoraDammV2CreatePosition(_) => EventType::MeteoraDammV2CreatePosition,
+            DexEvent::MeteoraDammV2ClosePosition(_) => EventType::MeteoraDammV2ClosePosition,
+            DexEvent::MeteoraDammV2ClaimPositionFee(_) => EventType::MeteoraDammV2ClaimPositionFee,
+            DexEvent::MeteoraDammV2InitializeReward(_) => EventType::MeteoraDammV2InitializeReward,
+            DexEvent::MeteoraDammV2FundReward(_) => EventType::MeteoraDammV2FundReward,
+            DexEvent::MeteoraDammV2ClaimReward(_) => EventType::MeteoraDammV2ClaimReward,
+            DexEvent::TokenAccount(_) => EventType::TokenAccount,
+            DexEvent::NonceAccount(_) => EventType::NonceAccount,
+            DexEvent::TokenInfo(_) => EventType::TokenInfo,
+            DexEvent::UnknownSwap(_) => EventType::UnknownSwap,
+            DexEvent::CrossVenueTrade(_) => EventType::CrossVenueTrade,
+            DexEvent::Custom(_) => EventType::Custom,
+            _ => return None,
+        };
+        Some(event_type)
+    }
+
+    /// 事件类型所属协议（区块/账户类事件返回 None）
+    #[allow(deprecated)]
+    pub fn protocol(self) -> Option<Protocol> {
+        use EventType::*;
+        match self {
+            BonkTrade | BonkPoolCreate | BonkMigrateAmm => Some(Protocol::Bonk),
+            PumpFunTrade | PumpFunCreate | PumpFunComplete | PumpFunMigrate | PumpFunSetParams => {
+                Some(Protocol::PumpFun)
+            }
+            PumpSwapBuy | PumpSwapSell | PumpSwapCreatePool | PumpSwapPoolCreated | PumpSwapTrade
+            | PumpSwapLiquidityAdded | PumpSwapLiquidityRemoved | PumpSwapPoolUpdated
+            | PumpSwapFeesClaimed | PumpSwapDeposit | PumpSwapWithdraw => Some(Protocol::PumpSwap),
+            RaydiumCpmmSwap | RaydiumCpmmDeposit | RaydiumCpmmWithdraw | RaydiumCpmmInitialize
+            | RaydiumCpmmPoolStatusUpdated => Some(Protocol::RaydiumCpmm),
+            RaydiumClmmSwap | RaydiumClmmCreatePool | RaydiumClmmOpenPosition | RaydiumClmmClosePosition
+            | RaydiumClmmIncreaseLiquidity | RaydiumClmmDecreaseLiquidity
+            | RaydiumClmmOpenPositionWithTokenExtNft | RaydiumClmmCollectFee => Some(Protocol::RaydiumClmm),
+            RaydiumAmmV4Swap | RaydiumAmmV4Deposit | RaydiumAmmV4Withdraw | RaydiumAmmV4Initialize2
+            | RaydiumAmmV4WithdrawPnl | RaydiumAmmV4PoolState => Some(Protocol::RaydiumAmmV4),
+            OrcaWhirlpoolSwap | OrcaWhirlpoolLiquidityIncreased | OrcaWhirlpoolLiquidityDecreased
+            | OrcaWhirlpoolPoolInitialized | OrcaWhirlpoolCollectFees | OrcaWhirlpoolCollectReward => {
+                Some(Protocol::OrcaWhirlpool)
+            }
+            MeteoraPoolsSwap | MeteoraPoolsAddLiquidity | MeteoraPoolsRemoveLiquidity
+            | MeteoraPoolsBootstrapLiquidity | MeteoraPoolsPoolCreated | MeteoraPoolsSetPoolFees => {
+                Some(Protocol::MeteoraPools)
+            }
+            MeteoraDammV2Swap | MeteoraDammV2AddLiquidity | MeteoraDammV2RemoveLiquidity
+            | MeteoraDammV2InitializePool | MeteoraDammV2CreatePosition | MeteoraDammV2ClosePosition
+            | MeteoraDammV2ClaimPositionFee | MeteoraDammV2InitializeReward | MeteoraDammV2FundReward
+            | MeteoraDammV2ClaimReward => Some(Protocol::MeteoraDammV2),
+            _ => None,
+        }
+    }
+}
+
+/// 事件类型列表涉及的协议集合
+fn protocols_of(types: &[EventType]) -> ProtocolSet {
+    types.iter().filter_map(|t| t.protocol()).collect()
+}
+
+/// 所有事件类型都在列表中的协议集合（不计已废弃、不会产生事件的类型）
+fn fully_covered_protocols(types: &[EventType]) -> ProtocolSet {
+    protocols_of(types)
+        .iter()
+        .filter(|&p| EventType::LIVE.iter().filter(|t| t.protocol() == Some(p)).all(|t| types.contains(t)))
+        .collect()
 }
 
 #[derive(Debug, Clone)]
 pub struct EventTypeFilter {
     pub include_only: Option<Vec<EventType>>,
     pub exclude_types: Option<Vec<EventType>>,
+    /// include_only / exclude_types 涉及的协议集合
+    protocols: ProtocolSet,
+    /// exclude_types 覆盖了全部事件类型的协议
+    excluded_protocols: ProtocolSet,
+    /// PumpFun 成交只保留 dev buy
+    dev_buys_only: bool,
+    /// 为识别 dev buy 额外解析、但调用方没有要求的 PumpFun create
+    hidden_create: bool,
+    /// SOL 报价成交的最小 SOL 数量（lamports）
+    min_sol_amount: Option<u64>,
+    /// SOL 报价成交的最小代币数量（原始单位）
+    min_token_amount: Option<u64>,
 }
 
 impl EventTypeFilter {
     pub fn include_only(types: Vec<EventType>) -> Self {
         Self {
+            protocols: protocols_of(&types),
+            excluded_protocols: ProtocolSet::empty(),
             include_only: Some(types),
             exclude_types: None,
+            dev_buys_only: false,
+            hidden_create: false,
+            min_sol_amount: None,
+            min_token_amount: None,
         }
     }
 
     pub fn exclude_types(types: Vec<EventType>) -> Self {
         Self {
+            protocols: protocols_of(&types),
+            excluded_protocols: fully_covered_protocols(&types),
             include_only: None,
             exclude_types: Some(types),
+            dev_buys_only: false,
+            hidden_create: false,
+            min_sol_amount: None,
+            min_token_amount: None,
+        }
+    }
+
+    /// PumpFun 成交只保留创建者在创建代币的同笔交易中的买入（dev buy），其它事件类型不受影响
+    ///
+    /// 识别 dev buy 需要同笔交易的 create，过滤器会在解析时放行 `PumpFunCreate`，
+    /// 调用方没有要求 create 时再由 [`Self::accepts_event`] 剔除。
+    pub fn dev_buys_only(mut self) -> Self {
+        self.dev_buys_only = true;
+        if !self.should_include(EventType::PumpFunCreate) {
+            self.hidden_create = true;
+            if let Some(types) = &mut self.include_only {
+                types.push(EventType::PumpFunCreate);
+            }
+            if let Some(types) = &mut self.exclude_types {
+                types.retain(|t| *t != EventType::PumpFunCreate);
+                self.excluded_protocols = fully_covered_protocols(types);
+            }
+            let types = self.include_only.as_ref().or(self.exclude_types.as_ref()).map_or(&[][..], |t| &t[..]);
+            self.protocols = protocols_of(types);
         }
+        self
+    }
+
+    /// 丢弃 SOL 数量低于 `lamports` 的成交（买入看支付的 SOL，卖出看得到的 SOL）
+    ///
+    /// 只作用于能确定 SOL / 代币两侧数量的成交（PumpFun、PumpSwap、Bonk），其它事件不受影响；
+    /// Bonk 成交只在报价 mint 为 WSOL 时判断（USD1 等报价、只有日志时不判断）。
+    pub fn min_sol_amount(mut self, lamports: u64) -> Self {
+        self.min_sol_amount = Some(lamports);
+        self
+    }
+
+    /// 丢弃代币数量低于 `amount`（原始单位）的成交（买入看得到的代币，卖出看卖出的代币），适用范围同 [`Self::min_sol_amount`]
+    pub fn min_token_amount(mut self, amount: u64) -> Self {
+        self.min_token_amount = Some(amount);
+        self
+    }
+
+    /// 是否有需要整笔交易解析完成后才能判断的条件（见 [`Self::accepts_event`]）
+    #[inline]
+    pub fn has_event_conditions(&self) -> bool {
+        self.dev_buys_only || self.min_sol_amount.is_some() || self.min_token_amount.is_some()
+    }
+
+    /// 按事件内容过滤，在同笔交易的事件全部解析并标记（[`crate::core::mark_dev_buys`]）之后调用
+    pub fn accepts_event(&self, event: &DexEvent) -> bool {
+        if !self.accepts_trade_size(event) {
+            return false;
+        }
+        match event {
+            DexEvent::PumpFunTrade(e) => !self.dev_buys_only || e.is_dev_create_token_trade,
+            DexEvent::PumpFunCreate(_) => !self.hidden_create,
+            _ => true,
+        }
+    }
+
+    /// 成交数量下限；数量未知（仅来自指令且为 0）的一侧、非 SOL 报价一侧不参与判断
+    fn accepts_trade_size(&self, event: &DexEvent) -> bool {
+        if self.min_sol_amount.is_none() && self.min_token_amount.is_none() {
+            return true;
+        }
+        let (metadata, sol_amount, token_amount) = match event {
+            DexEvent::PumpFunTrade(e) => (&e.metadata, Some(e.sol_amount), e.token_amount),
+            DexEvent::PumpSwapBuy(e) => (&e.metadata, Some(e.sol_amount), e.token_amount),
+            DexEvent::PumpSwapSell(e) => (&e.metadata, Some(e.sol_amount), e.token_amount),
+            // Bonk 买入支付报价、得到代币，卖出相反；报价可能是 USD1 等非 SOL 代币
+            DexEvent::BonkTrade(e) => {
+                let (quote_amount, base_amount) = if e.is_buy { (e.amount_in, e.amount_out) } else { (e.amount_out, e.amount_in) };
+                (&e.metadata, (e.quote_mint == WSOL_MINT).then_some(quote_amount), base_amount)
+            }
+            _ => return true,
+        };
+        let meets = |amount: Option<u64>, min: Option<u64>| match (amount.and_then(|a| metadata.amount_opt(a)), min) {
+            (Some(amount), Some(min)) => amount >= min,
+            _ => true,
+        };
+        meets(sol_amount, self.min_sol_amount) && meets(Some(token_amount), self.min_token_amount)
     }
 
     pub fn should_include(&self, event_type: EventType) -> bool {
@@ -299,28 +1138,44 @@ impl EventTypeFilter {
         true
     }
 
+    /// include_only / exclude_types 中出现过的协议
     #[inline]
-    pub fn includes_pumpfun(&self) -> bool {
-        if let Some(ref include_only) = self.include_only {
-            return include_only.iter().any(|t| matches!(t,
-                EventType::PumpFunTrade | EventType::PumpFunCreate |
-                EventType::PumpFunComplete | EventType::PumpFunMigrate));
+    pub fn protocols(&self) -> ProtocolSet {
+        self.protocols
+    }
+
+    /// 过滤器是否可能放行该协议的事件
+    ///
+    /// exclude_types 只排除了协议的部分事件类型时，该协议的其它事件仍会放行。
+    #[inline]
+    pub fn includes_protocol(&self, protocol: Protocol) -> bool {
+        if self.include_only.is_some() {
+            return self.protocols.contains(protocol);
         }
 
-        if let Some(ref exclude_types) = self.exclude_types {
-            return !exclude_types.iter().any(|t| matches!(t,
-                EventType::PumpFunTrade | EventType::PumpFunCreate |
-                EventType::PumpFunComplete | EventType::PumpFunMigrate));
+        if self.exclude_types.is_some() {
+            return !self.excluded_protocols.contains(protocol);
         }
 
         true
     }
+
+    #[inline]
+    pub fn includes_pumpfun(&self) -> bool {
+        self.includes_protocol(Protocol::PumpFun)
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Slot 订阅过滤器（`ClientConfig::slot_updates`），slot 更新以 `DexEvent::SlotStatus` 输出
+///
+/// `min_slot` / `max_slot` 在客户端过滤，区间外的 slot 更新直接丢弃。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SlotFilter {
     pub min_slot: Option<u64>,
     pub max_slot: Option<u64>,
+    /// 同时接收 slot 内的中间状态（FirstShredReceived、Completed 等）
+    #[serde(default)]
+    pub interslot_updates: bool,
 }
 
 impl SlotFilter {
@@ -328,6 +1183,7 @@ impl SlotFilter {
         Self {
             min_slot: None,
             max_slot: None,
+            interslot_updates: false,
         }
     }
 
@@ -340,10 +1196,139 @@ impl SlotFilter {
         self.max_slot = Some(slot);
         self
     }
+
+    pub fn interslot_updates(mut self, enable: bool) -> Self {
+        self.interslot_updates = enable;
+        self
+    }
+
+    /// slot 是否在过滤区间内
+    #[inline]
+    pub fn contains(&self, slot: u64) -> bool {
+        self.min_slot.is_none_or(|min| slot >= min) && self.max_slot.is_none_or(|max| slot <= max)
+    }
 }
 
 impl Default for SlotFilter {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protocol_set_membership() {
+        let set = ProtocolSet::from(&[Protocol::PumpFun, Protocol::RaydiumClmm][..]);
+        assert!(set.contains(Protocol::PumpFun));
+        assert!(set.contains(Protocol::RaydiumClmm));
+        assert!(!set.contains(Protocol::Bonk));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![Protocol::PumpFun, Protocol::RaydiumClmm]);
+        assert!(ProtocolSet::empty().is_empty());
+        assert_eq!(ProtocolSet::all().len(), Protocol::ALL.len());
+    }
+
+    #[test]
+    fn test_protocol_set_union_intersection() {
+        let a: ProtocolSet = [Protocol::PumpFun, Protocol::PumpSwap].into_iter().collect();
+        let b: ProtocolSet = [Protocol::PumpSwap, Protocol::Bonk].into_iter().collect();
+
+        let union = a | b;
+        assert_eq!(union.len(), 3);
+        assert!(union.contains(Protocol::Bonk));
+
+        let inter = a & b;
+        assert_eq!(inter, ProtocolSet::from(Protocol::PumpSwap));
+        assert!(a.intersects(b));
+        assert!(!a.intersects(ProtocolSet::from(Protocol::RaydiumAmmV4)));
+    }
+
+    #[test]
+    fn test_event_type_filter_protocols() {
+        let include = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]);
+        assert!(include.includes_pumpfun());
+        assert!(!include.includes_protocol(Protocol::RaydiumClmm));
+
+        let exclude = EventTypeFilter::exclude_types(vec![EventType::RaydiumClmmSwap]);
+        assert!(exclude.includes_pumpfun());
+    }
+
+    #[test]
+    fn test_exclude_types_keeps_partially_excluded_protocol() {
+        let exclude = EventTypeFilter::exclude_types(vec![EventType::PumpFunCreate]);
+        assert!(exclude.includes_pumpfun());
+        assert!(exclude.should_include(EventType::PumpFunTrade));
+
+        let all_pumpfun: Vec<EventType> =
+            EventType::LIVE.into_iter().filter(|t| t.protocol() == Some(Protocol::PumpFun)).collect();
+        let exclude = EventTypeFilter::exclude_types(all_pumpfun);
+        assert!(!exclude.includes_pumpfun());
+        assert!(exclude.includes_protocol(Protocol::PumpSwap));
+    }
+
+    #[test]
+    fn test_enabled_protocols_config() {
+        assert_eq!(EnabledProtocols::default(), EnabledProtocols::all());
+        assert_eq!(EnabledProtocols::all().protocols().len(), Protocol::ALL.len());
+
+        let only = EnabledProtocols::only([Protocol::PumpFun]).with(Protocol::OrcaWhirlpool);
+        assert!(only.contains(Protocol::OrcaWhirlpool));
+        assert!(!only.without(Protocol::OrcaWhirlpool).contains(Protocol::OrcaWhirlpool));
+
+        let json = serde_json::to_string(&only).unwrap();
+        assert_eq!(json, r#"["PumpFun","OrcaWhirlpool"]"#);
+        assert_eq!(serde_json::from_str::<EnabledProtocols>(&json).unwrap(), only);
+    }
+
+    #[cfg(feature = "grpc")]
+    #[test]
+    fn test_enabled_protocols_config_default() {
+        // 旧配置没有该字段时默认全部启用
+        let mut value = serde_json::to_value(ClientConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("enabled_protocols");
+        let config: ClientConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.enabled_protocols, EnabledProtocols::all());
+    }
+
+    #[test]
+    fn test_min_sol_amount_ignores_non_sol_quote() {
+        use crate::core::events::{BonkTradeEvent, EventMetadata, EventSource, TradeDirection};
+        use solana_sdk::pubkey::Pubkey;
+
+        // 买入支付 50 个报价单位（0.00000005 SOL 或 0.00005 USD1），得到 1M 代币
+        let trade = |quote_mint: Pubkey| {
+            DexEvent::BonkTrade(BonkTradeEvent {
+                metadata: EventMetadata { signature: Default::default(), slot: 1, tx_index: 0, block_time_us: 0, grpc_recv_us: 0, handle_us: 0, event_source: EventSource::Log, outer_index: 0, inner_index: None, protocol_version: None, sequence: 0, raw: None, priority_fee_micro_lamports: None, compute_unit_limit: None },
+                pool_state: Pubkey::new_unique(),
+                user: Pubkey::new_unique(),
+                amount_in: 50,
+                amount_out: 1_000_000,
+                is_buy: true,
+                trade_direction: TradeDirection::Buy,
+                exact_in: true,
+                base_mint: Pubkey::new_unique(),
+                quote_mint,
+                minimum_amount_out: None,
+                maximum_amount_in: None,
+                protocol_fee: None,
+                platform_fee: None,
+                share_fee: None,
+                virtual_base: None,
+                virtual_quote: None,
+                real_base_after: None,
+                real_quote_after: None,
+                pool_status: None,
+                curve_progress_bps: None,
+            })
+        };
+        let usd1 = Pubkey::new_unique();
+        let filter = EventTypeFilter::include_only(vec![EventType::BonkTrade]).min_sol_amount(100);
+        assert!(!filter.accepts_event(&trade(WSOL_MINT)));
+        assert!(filter.accepts_event(&trade(usd1)));
+        assert!(filter.accepts_event(&trade(Pubkey::default())));
+        // 代币数量下限与报价无关
+        assert!(!filter.clone().min_token_amount(2_000_000).accepts_event(&trade(usd1)));
+    }
+}
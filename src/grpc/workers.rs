@@ -0,0 +1,237 @@
+//! 解析线程池
+//!
+//! `ClientConfig.parser_threads > 0` 时，gRPC 接收任务只记录接收时间并把交易转交给解析线程，
+//! 一批大交易的解析不再拖慢后续消息的接收，`grpc_recv_us` 也不再包含前面交易的解析耗时。
+//! - 每笔交易由一个线程完整解析，交易内的事件顺序与内联解析一致
+//! - 多个线程并行时，不同交易的事件可能乱序输出；需要跨交易顺序时设为 1 个线程
+//! - 转交队列容量为 `parser_queue_capacity`（0 时沿用 `queue_capacity`），已满时默认等待（`parser_backpressure`）：
+//!   接收任务异步等待解析线程取走交易，不占用 runtime 工作线程，期间不再读流，内存占用以队列容量为上限，
+//!   服务端推送过快时由 gRPC 流控向上游施加背压（计入 `parser_waits`）
+//! - 关闭 `parser_backpressure` 时已满即丢弃交易，计入 `StreamStatsSnapshot::parser_dropped`；
+//!   [`ClientConfig::low_latency`](crate::grpc::ClientConfig::low_latency) 预设如此，宁可丢交易也不让接收滞后
+//! - 队列中等待解析的交易数记录在 `StreamStatsSnapshot::parser_queue_depth`，即解析滞后
+//!
+//! 订阅任务退出时线程池随之关闭，线程处理完已转交的交易后退出。
+//!
+//! `ClientConfig.receiver_core_id` 设置后，订阅的接收循环本身也在绑核的独立线程上运行（[`spawn_receiver`]）。
+
+use super::stream_health::StreamStats;
+use crossbeam_channel::Sender;
+use log::warn;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use yellowstone_grpc_proto::prelude::SubscribeUpdateTransaction;
+
+/// 转交给解析线程的交易及其接收时间，附带占用的队列名额（解析线程取出时归还）
+type Job = (SubscribeUpdateTransaction, i64, OwnedSemaphorePermit);
+
+/// 可绑定的最大核心编号（不含），超出时 `CPU_SET` 会越界
+#[cfg(target_os = "linux")]
+pub const MAX_CORE_ID: usize = libc::CPU_SETSIZE as usize;
+/// 可绑定的最大核心编号（不含）
+#[cfg(not(target_os = "linux"))]
+pub const MAX_CORE_ID: usize = 1024;
+
+/// 解析线程池（订阅在 `parser_threads > 0` 时使用，也可用于自定义的接收循环）
+pub struct ParserPool {
+    tx: Sender<Job>,
+    /// 队列剩余名额，容量由名额而不是 channel 限制，等待名额时可以异步挂起
+    slots: Arc<Semaphore>,
+    backpressure: bool,
+    stats: Arc<StreamStats>,
+}
+
+impl ParserPool {
+    /// 启动 `threads` 个解析线程，`core_ids` 不为空时按线程序号循环绑核
+    ///
+    /// `backpressure` 为 true 时队列满则等待，否则丢弃。
+    pub fn spawn<F>(
+        threads: usize,
+        core_ids: &[usize],
+        capacity: usize,
+        backpressure: bool,
+        stats: Arc<StreamStats>,
+        parse: F,
+    ) -> Self
+    where
+        F: Fn(&SubscribeUpdateTransaction, i64) + Send + Sync + 'static,
+    {
+        let (tx, rx) = crossbeam_channel::unbounded::<Job>();
+        let slots = Arc::new(Semaphore::new(capacity.max(1)));
+        let parse = Arc::new(parse);
+        for i in 0..threads {
+            let (rx, parse, stats) = (rx.clone(), parse.clone(), stats.clone());
+            let core_id = (!core_ids.is_empty()).then(|| core_ids[i % core_ids.len()]);
+            std::thread::Builder::new()
+                .name(format!("sol-parser-{}", i))
+                .spawn(move || {
+                    if let Some(core_id) = core_id {
+                        pin_current_thread(core_id);
+                    }
+                    while let Ok((update, grpc_recv_us, slot)) = rx.recv() {
+                        drop(slot);
+                        stats.set_parser_queue_depth(rx.len());
+                        parse(&update, grpc_recv_us);
+                    }
+                })
+                .expect("failed to spawn parser thread");
+        }
+        Self { tx, slots, backpressure, stats }
+    }
+
+    /// 转交一笔交易：队列未满时立即返回；已满时按 `backpressure` 异步等待名额或丢弃
+    #[inline]
+    pub async fn submit(&self, update: SubscribeUpdateTransaction, grpc_recv_us: i64) {
+        let slot = match self.slots.clone().try_acquire_owned() {
+            Ok(slot) => slot,
+            Err(TryAcquireError::NoPermits) if self.backpressure => {
+                self.stats.record_parser_wait();
+                let Ok(slot) = self.slots.clone().acquire_owned().await else { return };
+                slot
+            }
+            Err(TryAcquireError::NoPermits) => return self.stats.record_parser_drop(),
+            Err(TryAcquireError::Closed) => return,
+        };
+        let _ = self.tx.send((update, grpc_recv_us, slot));
+        self.stats.set_parser_queue_depth(self.tx.len());
+    }
+}
+
+/// 把当前线程绑定到 `core_id`，失败或非 Linux 平台时只输出警告，返回是否绑定成功
+#[cfg(target_os = "linux")]
+pub fn pin_current_thread(core_id: usize) -> bool {
+    if core_id >= MAX_CORE_ID {
+        warn!("failed to pin thread to core {}: core id must be below {}", core_id, MAX_CORE_ID);
+        return false;
+    }
+    // SAFETY: cpu_set_t 为纯数据结构，全零即空集合；pid 0 表示当前线程
+    let ok = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core_id, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    };
+    if !ok {
+        warn!("failed to pin thread to core {}: {}", core_id, std::io::Error::last_os_error());
+    }
+    ok
+}
+
+/// 把当前线程绑定到 `core_id`，失败或非 Linux 平台时只输出警告，返回是否绑定成功
+#[cfg(not(target_os = "linux"))]
+pub fn pin_current_thread(core_id: usize) -> bool {
+    warn!("CPU pinning is only supported on Linux, core {} ignored", core_id);
+    false
+}
+
+/// 在绑核的独立线程上运行订阅任务（单线程 runtime），`core_id` 为 None 时交给 tokio 调度
+pub(crate) fn spawn_receiver<F>(core_id: Option<usize>, task: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    let Some(core_id) = core_id else {
+        tokio::spawn(task);
+        return;
+    };
+    std::thread::Builder::new()
+        .name(format!("sol-grpc-recv-{}", core_id))
+        .spawn(move || {
+            pin_current_thread(core_id);
+            match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(task),
+                Err(e) => warn!("failed to build receiver runtime: {}", e),
+            }
+        })
+        .expect("failed to spawn receiver thread");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pool_parses_every_transaction_and_counts_drops() {
+        let stats = Arc::new(StreamStats::default());
+        let (done_tx, done_rx) = crossbeam_channel::unbounded();
+        let (gate_tx, gate_rx) = crossbeam_channel::bounded::<()>(0);
+        let pool = ParserPool::spawn(1, &[0], 2, false, stats.clone(), move |update, grpc_recv_us| {
+            if update.slot == 0 {
+                let _ = gate_rx.recv();
+            }
+            done_tx.send((update.slot, grpc_recv_us)).unwrap();
+        });
+
+        // 合成交易：线程卡在第一笔上，队列容量 2，第四笔被丢弃
+        for slot in 0..4 {
+            pool.submit(SubscribeUpdateTransaction { slot, ..Default::default() }, slot as i64 * 10).await;
+            if slot == 0 {
+                while !pool.tx.is_empty() {
+                    std::thread::yield_now();
+                }
+            }
+        }
+        assert_eq!(stats.snapshot().parser_dropped, 1);
+        gate_tx.send(()).unwrap();
+
+        drop(pool);
+        let parsed: Vec<(u64, i64)> = (0..3).map(|_| done_rx.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(parsed, vec![(0, 0), (1, 10), (2, 20)]);
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_bounds_queue_depth() {
+        let stats = Arc::new(StreamStats::default());
+        let (done_tx, done_rx) = crossbeam_channel::unbounded();
+        let pool = ParserPool::spawn(1, &[], 4, true, stats.clone(), move |update, _| {
+            std::thread::sleep(Duration::from_millis(1));
+            done_tx.send(update.slot).unwrap();
+        });
+
+        // 解析线程比提交慢得多：提交方被阻塞，队列深度始终不超过容量，没有交易被丢弃
+        let mut max_depth = 0;
+        for slot in 0..50 {
+            pool.submit(SubscribeUpdateTransaction { slot, ..Default::default() }, 0).await;
+            max_depth = max_depth.max(stats.snapshot().parser_queue_depth);
+        }
+        let snapshot = stats.snapshot();
+        assert!(max_depth <= 4, "depth {}", max_depth);
+        assert!(snapshot.parser_waits > 0);
+        assert_eq!(snapshot.parser_dropped, 0);
+
+        drop(pool);
+        let parsed: Vec<u64> = (0..50).map(|_| done_rx.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(parsed, (0..50).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_does_not_block_runtime() {
+        let stats = Arc::new(StreamStats::default());
+        let (done_tx, done_rx) = crossbeam_channel::unbounded();
+        let (gate_tx, gate_rx) = crossbeam_channel::bounded::<()>(0);
+        let pool = ParserPool::spawn(1, &[], 1, true, stats.clone(), move |update, _| {
+            if update.slot == 0 {
+                let _ = gate_rx.recv();
+            }
+            done_tx.send(update.slot).unwrap();
+        });
+
+        // 单线程 runtime：线程卡在第一笔上、队列已满，第三笔等待名额时同一线程上的任务仍能运行并放行解析线程
+        for slot in 0..2 {
+            pool.submit(SubscribeUpdateTransaction { slot, ..Default::default() }, 0).await;
+            while slot == 0 && !pool.tx.is_empty() {
+                tokio::task::yield_now().await;
+            }
+        }
+        let release = async {
+            tokio::task::yield_now().await;
+            gate_tx.send(()).unwrap();
+        };
+        let submit = pool.submit(SubscribeUpdateTransaction { slot: 2, ..Default::default() }, 0);
+        tokio::time::timeout(Duration::from_secs(5), async { tokio::join!(submit, release) }).await.unwrap();
+        assert_eq!(stats.snapshot().parser_waits, 1);
+
+        drop(pool);
+        let parsed: Vec<u64> = (0..3).map(|_| done_rx.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(parsed, vec![0, 1, 2]);
+    }
+}
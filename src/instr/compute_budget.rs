@@ -0,0 +1,72 @@
+//! Compute Budget 指令解析
+//!
+//! 只解析 `SetComputeUnitLimit` / `SetComputeUnitPrice`，汇总为交易级的计算单元上限和优先费单价，
+//! 写入该交易所有事件的 [`EventMetadata`]。运行时只认外层指令，同一指令出现多次时交易执行失败，这里取最后一条。
+
+use crate::core::events::{DexEvent, EventMetadata};
+use solana_sdk::pubkey::Pubkey;
+use super::utils::*;
+use super::program_ids::COMPUTE_BUDGET_PROGRAM_ID;
+
+/// 指令标签（首字节）
+pub mod discriminators {
+    pub const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+    pub const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+}
+
+/// 交易设置的计算预算
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// `SetComputeUnitLimit`
+    pub compute_unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice`（微 lamports / CU）
+    pub priority_fee_micro_lamports: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// 汇总交易的外层指令 `(程序 ID, 指令数据)`
+    pub fn from_instructions<'a>(instructions: impl IntoIterator<Item = (&'a Pubkey, &'a [u8])>) -> Self {
+        let mut budget = Self::default();
+        for (program_id, data) in instructions {
+            budget.observe(program_id, data);
+        }
+        budget
+    }
+
+    /// 记录一条指令，其它程序或其它指令忽略
+    #[inline]
+    pub fn observe(&mut self, program_id: &Pubkey, data: &[u8]) {
+        if *program_id != COMPUTE_BUDGET_PROGRAM_ID {
+            return;
+        }
+        match data.first() {
+            Some(&discriminators::SET_COMPUTE_UNIT_LIMIT) => {
+                self.compute_unit_limit = read_u32_le(data, 1).or(self.compute_unit_limit);
+            }
+            Some(&discriminators::SET_COMPUTE_UNIT_PRICE) => {
+                self.priority_fee_micro_lamports = read_u64_le(data, 1).or(self.priority_fee_micro_lamports);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.compute_unit_limit.is_none() && self.priority_fee_micro_lamports.is_none()
+    }
+
+    /// 写入事件元数据
+    pub fn apply_to(&self, metadata: &mut EventMetadata) {
+        metadata.compute_unit_limit = self.compute_unit_limit;
+        metadata.priority_fee_micro_lamports = self.priority_fee_micro_lamports;
+    }
+
+    /// 写入同一交易的全部事件
+    pub fn apply(&self, events: &mut [DexEvent]) {
+        if self.is_empty() {
+            return;
+        }
+        for metadata in events.iter_mut().filter_map(DexEvent::metadata_mut) {
+            self.apply_to(metadata);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! 指令判别器未命中诊断
+//!
+//! 开启后，`parse_instruction_unified` 对已支持程序的指令解析失败时，
+//! 记录程序 ID 与指令数据前 8 字节到有界环形缓冲区，便于发现新的/未知的判别器。
+//! 默认关闭，关闭时热路径只多一次原子读（且仅在解析返回 None 时）。
+
+use super::program_ids::*;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// 默认缓冲区容量
+pub const DEFAULT_CAPACITY: usize = 256;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+static MISSES: Lazy<Mutex<VecDeque<DiscriminatorMiss>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(DEFAULT_CAPACITY)));
+
+/// 未命中原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissReason {
+    /// 指令数据不足 8 字节
+    TooShort,
+    /// 判别器未匹配，或判别器匹配但参数/账户解码失败
+    Unmatched,
+}
+
+/// 一条未命中记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscriminatorMiss {
+    pub program_id: Pubkey,
+    /// 指令数据前 8 字节（不足 8 字节时右侧补 0，实际长度见 `data_len`）
+    pub discriminator: [u8; 8],
+    pub data_len: usize,
+    pub reason: MissReason,
+    pub signature: Signature,
+    pub slot: u64,
+}
+
+/// 开启/关闭诊断记录
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+#[inline(always)]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 设置缓冲区容量（超出时丢弃最旧的记录）
+pub fn set_capacity(capacity: usize) {
+    let capacity = capacity.max(1);
+    CAPACITY.store(capacity, Ordering::Relaxed);
+    let mut misses = MISSES.lock();
+    while misses.len() > capacity {
+        misses.pop_front();
+    }
+}
+
+/// 最近的未命中记录（从旧到新）
+pub fn recent_misses() -> Vec<DiscriminatorMiss> {
+    MISSES.lock().iter().cloned().collect()
+}
+
+/// 清空记录
+pub fn clear() {
+    MISSES.lock().clear();
+}
+
+/// 是否为 `parse_instruction_unified` 支持的程序
+fn is_tracked_program(program_id: &Pubkey) -> bool {
+    [
+        PUMPFUN_PROGRAM_ID,
+        RAYDIUM_AMM_V4_PROGRAM_ID,
+        RAYDIUM_CLMM_PROGRAM_ID,
+        ORCA_WHIRLPOOL_PROGRAM_ID,
+        RAYDIUM_CPMM_PROGRAM_ID,
+        METEORA_DAMM_V2_PROGRAM_ID,
+        METEORA_DLMM_PROGRAM_ID,
+        BONK_PROGRAM_ID,
+        PUMPSWAP_PROGRAM_ID,
+        METEORA_POOLS_PROGRAM_ID,
+    ]
+    .contains(program_id)
+}
+
+/// 记录一次未命中（仅已支持的程序）
+#[cold]
+pub(crate) fn record_miss(program_id: &Pubkey, instruction_data: &[u8], signature: Signature, slot: u64) {
+    if !is_tracked_program(program_id) {
+        return;
+    }
+
+    let mut discriminator = [0u8; 8];
+    let n = instruction_data.len().min(8);
+    discriminator[..n].copy_from_slice(&instruction_data[..n]);
+    let reason = if instruction_data.len() < 8 { MissReason::TooShort } else { MissReason::Unmatched };
+
+    log::debug!(
+        target: "sol_parser_sdk::discriminator_miss",
+        "program={} discriminator={:?} data_len={} reason={:?} signature={} slot={}",
+        program_id, discriminator, instruction_data.len(), reason, signature, slot
+    );
+
+    let miss = DiscriminatorMiss {
+        program_id: *program_id,
+        discriminator,
+        data_len: instruction_data.len(),
+        reason,
+        signature,
+        slot,
+    };
+
+    let capacity = CAPACITY.load(Ordering::Relaxed);
+    let mut misses = MISSES.lock();
+    while misses.len() >= capacity {
+        misses.pop_front();
+    }
+    misses.push_back(miss);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instr::parse_instruction_unified;
+
+    #[test]
+    fn test_unknown_discriminator_recorded() {
+        let unknown = [0xde, 0xad, 0xbe, 0xef, 0, 1, 2, 3, 42];
+        let accounts = [Pubkey::new_unique(); 4];
+
+        // 关闭时不记录
+        assert!(parse_instruction_unified(&unknown, &accounts, Signature::default(), 7, 0, None, &PUMPFUN_PROGRAM_ID, None).is_none());
+        assert!(!recent_misses().iter().any(|m| m.slot == 7));
+
+        set_enabled(true);
+        assert!(parse_instruction_unified(&unknown, &accounts, Signature::default(), 8, 0, None, &PUMPFUN_PROGRAM_ID, None).is_none());
+        // 非支持程序不记录
+        let other = Pubkey::new_unique();
+        assert!(parse_instruction_unified(&unknown, &accounts, Signature::default(), 8, 0, None, &other, None).is_none());
+        set_enabled(false);
+
+        let misses: Vec<_> = recent_misses().into_iter().filter(|m| m.slot == 8).collect();
+        assert_eq!(misses.len(), 1);
+        assert_eq!(misses[0].program_id, PUMPFUN_PROGRAM_ID);
+        assert_eq!(misses[0].discriminator, [0xde, 0xad, 0xbe, 0xef, 0, 1, 2, 3]);
+        assert_eq!(misses[0].data_len, unknown.len());
+        assert_eq!(misses[0].reason, MissReason::Unmatched);
+    }
+}
@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -96,6 +97,18 @@ pub fn parse_instruction(
     }
 }
 
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match MeteoraPoolsInstruction::from_discriminator(&discriminator)? {
+        MeteoraPoolsInstruction::Swap => Some(EventType::MeteoraPoolsSwap),
+        MeteoraPoolsInstruction::AddLiquidity => Some(EventType::MeteoraPoolsAddLiquidity),
+        MeteoraPoolsInstruction::RemoveLiquidity => Some(EventType::MeteoraPoolsRemoveLiquidity),
+        MeteoraPoolsInstruction::CreatePool => Some(EventType::MeteoraPoolsPoolCreated),
+        _ => None,
+    }
+}
+
 /// 解析 Swap 指令
 fn parse_swap_instruction(
     data: &[u8],
@@ -122,6 +135,9 @@ fn parse_swap_instruction(
         trade_fee: 0, // 从日志中获取
         admin_fee: 0, // 从日志中获取
         host_fee: 0, // 从日志中获取
+        pool,
+        fee_mode: MeteoraFeeMode::Unknown, // 需要池子费率配置
+        effective_fee_bps: 0, // 从日志中获取
     }))
 }
 
@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -148,6 +149,24 @@ pub fn parse_instruction(
     }
 }
 
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match MeteoraDammV2Instruction::from_discriminator(&discriminator)? {
+        MeteoraDammV2Instruction::Swap => Some(EventType::MeteoraDammV2Swap),
+        MeteoraDammV2Instruction::AddLiquidity => Some(EventType::MeteoraDammV2AddLiquidity),
+        MeteoraDammV2Instruction::RemoveLiquidity => Some(EventType::MeteoraDammV2RemoveLiquidity),
+        MeteoraDammV2Instruction::InitializeLbPair => Some(EventType::MeteoraDammV2InitializePool),
+        MeteoraDammV2Instruction::InitializePosition => Some(EventType::MeteoraDammV2CreatePosition),
+        MeteoraDammV2Instruction::ClosePosition => Some(EventType::MeteoraDammV2ClosePosition),
+        MeteoraDammV2Instruction::ClaimPositionFee => Some(EventType::MeteoraDammV2ClaimPositionFee),
+        MeteoraDammV2Instruction::InitializeReward => Some(EventType::MeteoraDammV2InitializeReward),
+        MeteoraDammV2Instruction::FundReward => Some(EventType::MeteoraDammV2FundReward),
+        MeteoraDammV2Instruction::ClaimReward => Some(EventType::MeteoraDammV2ClaimReward),
+        _ => None,
+    }
+}
+
 /// 解析 Swap 指令
 fn parse_swap_instruction(
     data: &[u8],
@@ -181,6 +200,9 @@ fn parse_swap_instruction(
         protocol_fee: 0, // 从日志中获取
         fee_bps: 0, // 从日志中获取
         host_fee: 0, // 从日志中获取
+        partner_fee: 0, // 从日志中获取
+        pre_sqrt_price: 0, // 从日志中获取
+        post_sqrt_price: 0, // 从日志中获取
     }))
 }
 
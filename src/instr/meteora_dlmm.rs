@@ -108,7 +108,7 @@ fn parse_initialize_lb_pair_instruction(
     Some(DexEvent::MeteoraDlmmInitializePool(MeteoraDlmmInitializePoolEvent {
         metadata,
         pool,
-        creator: get_account(accounts, 1).unwrap_or_default(),
+        creator: acc(accounts, 1),
         active_bin_id: active_id,
         bin_step,
     }))
@@ -133,7 +133,7 @@ fn parse_initialize_bin_array_instruction(
     Some(DexEvent::MeteoraDlmmInitializeBinArray(MeteoraDlmmInitializeBinArrayEvent {
         metadata,
         pool,
-        bin_array: get_account(accounts, 1).unwrap_or_default(),
+        bin_array: acc(accounts, 1),
         index,
     }))
 }
@@ -160,8 +160,8 @@ fn parse_add_liquidity_instruction(
     Some(DexEvent::MeteoraDlmmAddLiquidity(MeteoraDlmmAddLiquidityEvent {
         metadata,
         pool,
-        from: get_account(accounts, 1).unwrap_or_default(),
-        position: get_account(accounts, 2).unwrap_or_default(),
+        from: acc(accounts, 1),
+        position: acc(accounts, 2),
         amounts: [
             amounts.get(0).copied().unwrap_or(0),
             amounts.get(1).copied().unwrap_or(0),
@@ -192,8 +192,8 @@ fn parse_remove_liquidity_instruction(
     Some(DexEvent::MeteoraDlmmRemoveLiquidity(MeteoraDlmmRemoveLiquidityEvent {
         metadata,
         pool,
-        from: get_account(accounts, 1).unwrap_or_default(),
-        position: get_account(accounts, 2).unwrap_or_default(),
+        from: acc(accounts, 1),
+        position: acc(accounts, 2),
         amounts: [
             amounts.get(0).copied().unwrap_or(0),
             amounts.get(1).copied().unwrap_or(0),
@@ -224,8 +224,8 @@ fn parse_initialize_position_instruction(
     Some(DexEvent::MeteoraDlmmCreatePosition(MeteoraDlmmCreatePositionEvent {
         metadata,
         pool,
-        position: get_account(accounts, 1).unwrap_or_default(),
-        owner: get_account(accounts, 2).unwrap_or_default(),
+        position: acc(accounts, 1),
+        owner: acc(accounts, 2),
         lower_bin_id,
         width,
     }))
@@ -253,7 +253,7 @@ fn parse_swap_instruction(
     Some(DexEvent::MeteoraDlmmSwap(MeteoraDlmmSwapEvent {
         metadata,
         pool,
-        from: get_account(accounts, 1).unwrap_or_default(),
+        from: acc(accounts, 1),
         start_bin_id: 0, // 从日志填充
         end_bin_id: 0, // 从日志填充
         amount_in,
@@ -281,8 +281,8 @@ fn parse_claim_fee_instruction(
     Some(DexEvent::MeteoraDlmmClaimFee(MeteoraDlmmClaimFeeEvent {
         metadata,
         pool,
-        position: get_account(accounts, 1).unwrap_or_default(),
-        owner: get_account(accounts, 2).unwrap_or_default(),
+        position: acc(accounts, 1),
+        owner: acc(accounts, 2),
         fee_x: 0, // 从日志填充
         fee_y: 0, // 从日志填充
     }))
@@ -303,7 +303,7 @@ fn parse_close_position_instruction(
     Some(DexEvent::MeteoraDlmmClosePosition(MeteoraDlmmClosePositionEvent {
         metadata,
         pool,
-        position: get_account(accounts, 1).unwrap_or_default(),
-        owner: get_account(accounts, 2).unwrap_or_default(),
+        position: acc(accounts, 1),
+        owner: acc(accounts, 2),
     }))
 }
\ No newline at end of file
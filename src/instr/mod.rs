@@ -14,6 +14,9 @@ pub mod orca_whirlpool;
 pub mod meteora_amm;
 pub mod meteora_damm;
 pub mod meteora_dlmm;
+pub mod spl_token;
+pub mod compute_budget;
+pub mod diagnostics;
 
 // 重新导出主要解析函数
 pub use raydium_launchpad::parse_instruction as parse_raydium_launchpad_instruction;
@@ -31,11 +34,55 @@ pub use meteora_dlmm::parse_instruction as parse_meteora_dlmm_instruction;
 pub use utils::*;
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use crate::core::events::DexEvent;
+use crate::common::time::now_micros_coarse;
+use crate::core::events::{DexEvent, RawInstruction, UnknownSwapEvent};
+use crate::core::registry::{ParseContext, ParserRegistry, RegistryReadGuard};
+use crate::core::unified_parser::ParseOptions;
+use crate::grpc::types::{EnabledProtocols, EventType, EventTypeFilter, Protocol};
+use crate::core::discriminators::{builtin_discriminators, DiscriminatorKind};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use program_ids::*;
 
+/// 因事件类型过滤被跳过的指令数
+static FILTERED_INSTRUCTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// 内置指令 discriminator（按程序 ID），来自 [`crate::core::discriminators::builtin_discriminators`]
+static KNOWN_INSTRUCTIONS: Lazy<HashMap<Pubkey, Vec<Vec<u8>>>> = Lazy::new(|| {
+    let program_ids = [
+        ("PumpFun", PUMPFUN_PROGRAM_ID),
+        ("PumpSwap", PUMPSWAP_PROGRAM_ID),
+        ("Bonk", BONK_PROGRAM_ID),
+        ("RaydiumCpmm", RAYDIUM_CPMM_PROGRAM_ID),
+        ("RaydiumClmm", RAYDIUM_CLMM_PROGRAM_ID),
+        ("RaydiumAmmV4", RAYDIUM_AMM_V4_PROGRAM_ID),
+        ("OrcaWhirlpool", ORCA_WHIRLPOOL_PROGRAM_ID),
+        ("MeteoraPools", METEORA_POOLS_PROGRAM_ID),
+        ("MeteoraDammV2", METEORA_DAMM_V2_PROGRAM_ID),
+    ];
+    let mut known: HashMap<Pubkey, Vec<Vec<u8>>> = HashMap::new();
+    for entry in builtin_discriminators() {
+        let Some((_, program_id)) = program_ids.iter().find(|(name, _)| *name == entry.program) else { continue };
+        if entry.kind == DiscriminatorKind::Instruction {
+            known.entry(*program_id).or_default().push(entry.discriminator);
+        }
+    }
+    known
+});
+
 /// 统一的指令解析入口函数
+///
+/// 内置程序未命中时查询 [`ParserRegistry`]。
+/// 开启 [`diagnostics`] 后，已支持程序的未命中指令会被记录到诊断缓冲区。
+///
+/// 传入 `event_type_filter` 时先按判别器预判事件类型，被过滤的指令不再解析账户和参数
+/// （计入 [`filtered_instruction_count`]）；无法预判的指令解析后再按事件类型过滤。
+///
+/// 没有接收时间（直接解析）时 `grpc_recv_us` 取事件构造时刻，需要与日志事件对齐时用
+/// [`parse_instruction_unified_with_recv`] 传入交易的接收时间。
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn parse_instruction_unified(
     instruction_data: &[u8],
     accounts: &[Pubkey],
@@ -44,63 +91,393 @@ pub fn parse_instruction_unified(
     tx_index: u64,
     block_time: Option<i64>,
     program_id: &Pubkey,
+    event_type_filter: Option<&EventTypeFilter>,
+) -> Option<DexEvent> {
+    parse_instruction_unified_with_recv(
+        instruction_data, accounts, signature, slot, tx_index, block_time, program_id, event_type_filter, 0,
+        ParseOptions::default(), ParserRegistry::read().as_ref(),
+    )
+}
+
+/// 同 [`parse_instruction_unified`]，事件的 `grpc_recv_us` 取交易的接收时间（为 0 时取事件构造时刻），
+/// 只解析 `options.enabled_protocols` 中的协议
+///
+/// `registry` 为调用方按交易取一次的 [`ParserRegistry::read`]，同一交易的各条指令共用，不再逐条加锁。
+#[inline]
+#[allow(clippy::too_many_arguments)]
+pub fn parse_instruction_unified_with_recv(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    event_type_filter: Option<&EventTypeFilter>,
+    grpc_recv_us: i64,
+    options: ParseOptions,
+    registry: Option<&RegistryReadGuard>,
+) -> Option<DexEvent> {
+    if let Some(filter) = event_type_filter {
+        if instruction_event_type(instruction_data, program_id).is_some_and(|t| !filter.should_include(t)) {
+            FILTERED_INSTRUCTIONS.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+    }
+    let mut event = route_instruction(
+        instruction_data, accounts, signature, slot, tx_index, block_time, program_id,
+        options.enabled_protocols,
+    );
+    // 内置程序表未命中：查询运行时注册表
+    if event.is_none() {
+        if let Some(registry) = registry {
+            let grpc_recv_us = if grpc_recv_us > 0 { grpc_recv_us } else { now_micros_coarse() };
+            let ctx = ParseContext { signature, slot, tx_index, block_time, grpc_recv_us };
+            event = registry.parse_instruction(program_id, instruction_data, accounts, &ctx);
+        }
+    }
+    if event.is_none() && diagnostics::is_enabled() {
+        diagnostics::record_miss(program_id, instruction_data, signature, slot);
+    }
+    if event.is_none() && options.emit_unknown {
+        event = unknown_instruction_event(instruction_data, signature, slot, tx_index, block_time, program_id);
+    }
+    if let Some(metadata) = event.as_mut().and_then(DexEvent::metadata_mut).filter(|_| grpc_recv_us > 0) {
+        metadata.grpc_recv_us = grpc_recv_us;
+        metadata.handle_us = metadata.handle_us.max(grpc_recv_us);
+    }
+    if options.retain_raw {
+        if let Some(metadata) = event.as_mut().and_then(DexEvent::metadata_mut) {
+            metadata.raw = Some(Box::new(RawInstruction::new(instruction_data)));
+        }
+    }
+    match (event_type_filter, event.as_ref().and_then(EventType::from_event)) {
+        (Some(filter), Some(event_type)) if !filter.should_include(event_type) => None,
+        _ => event,
+    }
+}
+
+/// 已支持程序的指令不匹配任何内置 discriminator 时生成 [`DexEvent::UnknownSwap`]
+/// （`ParseOptions.emit_unknown` 开启时由 [`parse_instruction_unified_with_recv`] 调用）；
+/// 未支持的程序、空数据或已知 discriminator（即使没有对应事件）返回 None
+pub fn unknown_instruction_event(
+    instruction_data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+) -> Option<DexEvent> {
+    let known = KNOWN_INSTRUCTIONS.get(program_id)?;
+    if instruction_data.is_empty() || known.iter().any(|d| instruction_data.starts_with(d)) {
+        return None;
+    }
+    let mut raw_discriminator = [0u8; 8];
+    let n = instruction_data.len().min(8);
+    raw_discriminator[..n].copy_from_slice(&instruction_data[..n]);
+    Some(DexEvent::UnknownSwap(UnknownSwapEvent {
+        metadata: create_metadata_simple(signature, slot, tx_index, block_time, *program_id),
+        program_id: *program_id,
+        raw_discriminator,
+        data_len: instruction_data.len(),
+    }))
+}
+
+/// 因事件类型过滤、未解析即跳过的指令总数
+pub fn filtered_instruction_count() -> u64 {
+    FILTERED_INSTRUCTIONS.load(Ordering::Relaxed)
+}
+
+type EventTypeFn = fn(&[u8]) -> Option<EventType>;
+
+/// 按程序 ID 和判别器预判指令的事件类型，未知程序或无法预判时返回 None
+pub fn instruction_event_type(instruction_data: &[u8], program_id: &Pubkey) -> Option<EventType> {
+    let table: [(Pubkey, EventTypeFn); 9] = [
+        (PUMPFUN_PROGRAM_ID, pumpfun::event_type),
+        (RAYDIUM_AMM_V4_PROGRAM_ID, raydium_amm::event_type),
+        (RAYDIUM_CLMM_PROGRAM_ID, raydium_clmm::event_type),
+        (ORCA_WHIRLPOOL_PROGRAM_ID, orca_whirlpool::event_type),
+        (RAYDIUM_CPMM_PROGRAM_ID, raydium_cpmm::event_type),
+        (METEORA_DAMM_V2_PROGRAM_ID, meteora_damm::event_type),
+        (BONK_PROGRAM_ID, raydium_launchpad::event_type),
+        (PUMPSWAP_PROGRAM_ID, pump_amm::event_type),
+        (METEORA_POOLS_PROGRAM_ID, meteora_amm::event_type),
+    ];
+    // 与 route_instruction 的顺序一致；Meteora DLMM 事件没有对应的 EventType
+    let (_, event_type) = table.iter().find(|(id, _)| id == program_id)?;
+    event_type(instruction_data)
+}
+
+/// 根据程序 ID 路由到对应协议的指令解析器
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+fn route_instruction(
+    instruction_data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    program_id: &Pubkey,
+    enabled: EnabledProtocols,
 ) -> Option<DexEvent> {
     // 快速检查指令数据长度，避免无效解析
     if instruction_data.is_empty() {
         return None;
     }
 
+    // 未启用的协议直接跳过，不检查判别器
+
     // 根据程序 ID 路由到相应的解析器，按使用频率排序
 
     // PumpFun (最常用)
-    if *program_id == PUMPFUN_PROGRAM_ID {
+    if *program_id == PUMPFUN_PROGRAM_ID && enabled.contains(Protocol::PumpFun) {
         return parse_pumpfun_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Raydium AMM (高频)
-    if *program_id == RAYDIUM_AMM_V4_PROGRAM_ID {
+    if *program_id == RAYDIUM_AMM_V4_PROGRAM_ID && enabled.contains(Protocol::RaydiumAmmV4) {
         return parse_raydium_amm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Raydium CLMM
-    if *program_id == RAYDIUM_CLMM_PROGRAM_ID {
+    if *program_id == RAYDIUM_CLMM_PROGRAM_ID && enabled.contains(Protocol::RaydiumClmm) {
         return parse_raydium_clmm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Orca Whirlpool
-    if *program_id == ORCA_WHIRLPOOL_PROGRAM_ID {
+    if *program_id == ORCA_WHIRLPOOL_PROGRAM_ID && enabled.contains(Protocol::OrcaWhirlpool) {
         return parse_orca_whirlpool_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Raydium CPMM
-    if *program_id == RAYDIUM_CPMM_PROGRAM_ID {
+    if *program_id == RAYDIUM_CPMM_PROGRAM_ID && enabled.contains(Protocol::RaydiumCpmm) {
         return parse_raydium_cpmm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Meteora DAMM
-    if *program_id == METEORA_DAMM_V2_PROGRAM_ID {
+    if *program_id == METEORA_DAMM_V2_PROGRAM_ID && enabled.contains(Protocol::MeteoraDammV2) {
         return parse_meteora_damm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Meteora DLMM
-    if *program_id == METEORA_DLMM_PROGRAM_ID {
+    if *program_id == METEORA_DLMM_PROGRAM_ID && enabled.contains(Protocol::MeteoraDlmm) {
         return parse_meteora_dlmm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Raydium Launchpad
-    if *program_id == BONK_PROGRAM_ID {
+    if *program_id == BONK_PROGRAM_ID && enabled.contains(Protocol::Bonk) {
         return parse_raydium_launchpad_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Pump AMM
-    if *program_id == PUMPSWAP_PROGRAM_ID {
+    if *program_id == PUMPSWAP_PROGRAM_ID && enabled.contains(Protocol::PumpSwap) {
         return parse_pump_amm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     // Meteora AMM
-    if *program_id == METEORA_POOLS_PROGRAM_ID {
+    if *program_id == METEORA_POOLS_PROGRAM_ID && enabled.contains(Protocol::MeteoraPools) {
         return parse_meteora_amm_instruction(instruction_data, accounts, signature, slot, tx_index, block_time);
     }
 
     None
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type ParseFn = fn(&[u8], &[Pubkey], Signature, u64, u64, Option<i64>) -> Option<DexEvent>;
+
+    const PARSERS: [(&str, ParseFn); 10] = [
+        ("raydium_launchpad", raydium_launchpad::parse_instruction),
+        ("pumpfun", pumpfun::parse_instruction),
+        ("pump_amm", pump_amm::parse_instruction),
+        ("raydium_clmm", raydium_clmm::parse_instruction),
+        ("raydium_cpmm", raydium_cpmm::parse_instruction),
+        ("raydium_amm", raydium_amm::parse_instruction),
+        ("orca_whirlpool", orca_whirlpool::parse_instruction),
+        ("meteora_amm", meteora_amm::parse_instruction),
+        ("meteora_damm", meteora_damm::parse_instruction),
+        ("meteora_dlmm", meteora_dlmm::parse_instruction),
+    ];
+
+    /// 各协议的 discriminator 加上所有单字节前缀（覆盖 Raydium AMM V4 / DLMM 的单字节指令）
+    fn candidate_prefixes() -> Vec<Vec<u8>> {
+        let mut prefixes: Vec<Vec<u8>> = [
+            pumpfun::discriminators::CREATE,
+            pumpfun::discriminators::BUY,
+            pumpfun::discriminators::SELL,
+            pumpfun::discriminators::SET_PARAMS,
+            pump_amm::discriminators::CREATE_POOL,
+            raydium_launchpad::discriminators::BUY_EXACT_IN,
+            raydium_launchpad::discriminators::BUY_EXACT_OUT,
+            raydium_launchpad::discriminators::SELL_EXACT_IN,
+            raydium_launchpad::discriminators::SELL_EXACT_OUT,
+            raydium_launchpad::discriminators::INITIALIZE,
+            raydium_launchpad::discriminators::MIGRATE_TO_AMM,
+            raydium_launchpad::discriminators::MIGRATE_TO_CPSWAP,
+            raydium_clmm::discriminators::SWAP,
+            raydium_clmm::discriminators::SWAP_V2,
+            raydium_clmm::discriminators::INCREASE_LIQUIDITY,
+            raydium_clmm::discriminators::INCREASE_LIQUIDITY_V2,
+            raydium_clmm::discriminators::DECREASE_LIQUIDITY,
+            raydium_clmm::discriminators::DECREASE_LIQUIDITY_V2,
+            raydium_clmm::discriminators::OPEN_POSITION,
+            raydium_clmm::discriminators::OPEN_POSITION_V2,
+            raydium_clmm::discriminators::OPEN_POSITION_WITH_TOKEN22_NFT,
+            raydium_clmm::discriminators::CLOSE_POSITION,
+            raydium_cpmm::discriminators::SWAP_BASE_IN,
+            raydium_cpmm::discriminators::SWAP_BASE_OUT,
+            raydium_cpmm::discriminators::INITIALIZE,
+            raydium_cpmm::discriminators::DEPOSIT,
+            raydium_cpmm::discriminators::WITHDRAW,
+            raydium_cpmm::discriminators::UPDATE_POOL_STATUS,
+            orca_whirlpool::discriminators::INITIALIZE_POOL,
+            orca_whirlpool::discriminators::INCREASE_LIQUIDITY,
+            orca_whirlpool::discriminators::COLLECT_FEES,
+            orca_whirlpool::discriminators::COLLECT_REWARD,
+            orca_whirlpool::discriminators::SWAP_V2,
+            orca_whirlpool::discriminators::TWO_HOP_SWAP,
+            meteora_amm::discriminators::ADD_LIQUIDITY,
+            meteora_amm::discriminators::REMOVE_LIQUIDITY,
+            meteora_amm::discriminators::CREATE_POOL,
+            meteora_damm::discriminators::INITIALIZE_LB_PAIR,
+            meteora_damm::discriminators::ADD_LIQUIDITY,
+            meteora_damm::discriminators::REMOVE_LIQUIDITY,
+            meteora_damm::discriminators::SWAP,
+            meteora_damm::discriminators::CLAIM_POSITION_FEE,
+        ]
+        .iter()
+        .map(|d| d.to_vec())
+        .collect();
+        prefixes.extend((0..=u8::MAX).map(|b| vec![b]));
+        prefixes
+    }
+
+    #[test]
+    fn test_short_account_arrays_do_not_panic() {
+        let keys: Vec<Pubkey> = (0..32).map(|_| Pubkey::new_unique()).collect();
+        let payloads = [0usize, 8, 64, 512];
+
+        for prefix in candidate_prefixes() {
+            for extra in payloads {
+                let mut data = prefix.clone();
+                data.resize(prefix.len() + extra, 0);
+                for len in 0..=keys.len() {
+                    for (name, parse) in PARSERS.iter() {
+                        let result = std::panic::catch_unwind(|| {
+                            parse(&data, &keys[..len], Signature::default(), 1, 0, None)
+                        });
+                        assert!(
+                            result.is_ok(),
+                            "{} panicked: prefix={:?} data_len={} accounts={}",
+                            name, prefix, data.len(), len
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_disabled_protocol_is_skipped() {
+        use crate::logs::optimized_matcher::LogType;
+
+        let keys: Vec<Pubkey> = (0..32).map(|_| Pubkey::new_unique()).collect();
+        let mut data = pumpfun::discriminators::BUY.to_vec();
+        data.resize(64, 0);
+        let parse = |enabled| {
+            route_instruction(&data, &keys, Signature::default(), 1, 0, None, &PUMPFUN_PROGRAM_ID, enabled)
+        };
+
+        let only_bonk = EnabledProtocols::only([Protocol::Bonk]);
+        assert!(parse(EnabledProtocols::all()).is_some());
+        assert!(parse(only_bonk).is_none());
+        assert!(!LogType::PumpFun.is_enabled(only_bonk));
+        assert!(LogType::RaydiumLaunchpad.is_enabled(only_bonk));
+    }
+
+    #[test]
+    fn test_acc_defaults_out_of_range() {
+        let key = Pubkey::new_unique();
+        assert_eq!(acc(&[key], 0), key);
+        assert_eq!(acc(&[key], 1), Pubkey::default());
+        assert_eq!(acc(&[], 0), Pubkey::default());
+    }
+
+    #[test]
+    fn test_event_type_filter_skips_before_parsing() {
+        // 合成数据：PumpFun create 指令，只订阅 PumpFunTrade
+        let accounts: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let data = pumpfun::discriminators::CREATE.to_vec();
+        let filter = EventTypeFilter::include_only(vec![EventType::PumpFunTrade]);
+        let parse = |filter| {
+            parse_instruction_unified(&data, &accounts, Signature::default(), 1, 0, None, &PUMPFUN_PROGRAM_ID, filter)
+        };
+
+        assert!(matches!(parse(None), Some(DexEvent::PumpFunCreate(_))));
+        let skipped = filtered_instruction_count();
+        assert!(parse(Some(&filter)).is_none());
+        assert!(filtered_instruction_count() > skipped);
+    }
+
+    #[test]
+    fn test_unknown_pumpfun_discriminator_fallback() {
+        let unknown = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 1, 2];
+        let Some(DexEvent::UnknownSwap(e)) =
+            unknown_instruction_event(&unknown, Signature::default(), 9, 2, None, &PUMPFUN_PROGRAM_ID)
+        else {
+            panic!("expected UnknownSwap");
+        };
+        assert_eq!((e.program_id, e.raw_discriminator, e.data_len), (PUMPFUN_PROGRAM_ID, [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88], 10));
+        assert_eq!((e.metadata.slot, e.metadata.tx_index), (9, 2));
+
+        // 已知 discriminator 和未支持的程序不生成
+        let buy = pumpfun::discriminators::BUY;
+        assert!(unknown_instruction_event(&buy, Signature::default(), 9, 2, None, &PUMPFUN_PROGRAM_ID).is_none());
+        assert!(unknown_instruction_event(&unknown, Signature::default(), 9, 2, None, &Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "grpc")]
+    fn test_emit_unknown_follows_client_config() {
+        use crate::grpc::config::ClientConfig;
+
+        let unknown = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 1, 2];
+        let parse = |config: &ClientConfig| {
+            parse_instruction_unified_with_recv(
+                &unknown, &[], Signature::default(), 1, 0, None, &PUMPFUN_PROGRAM_ID, None, 0, config.parse_options(), None,
+            )
+        };
+
+        // 两个客户端配置不同，互不影响
+        let emitting = ClientConfig::builder().emit_unknown_instructions(true).build().unwrap();
+        let silent = ClientConfig::default();
+        assert!(matches!(parse(&emitting), Some(DexEvent::UnknownSwap(_))));
+        assert!(parse(&silent).is_none());
+        assert!(matches!(parse(&emitting), Some(DexEvent::UnknownSwap(_))));
+    }
+
+    #[test]
+    fn test_retain_raw_keeps_pumpfun_buy_discriminator() {
+        let accounts: Vec<Pubkey> = (0..16).map(|_| Pubkey::new_unique()).collect();
+        let mut data = pumpfun::discriminators::BUY.to_vec();
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&2_000u64.to_le_bytes());
+        let parse = |retain_raw: bool| {
+            let options = ParseOptions { retain_raw, ..Default::default() };
+            parse_instruction_unified_with_recv(
+                &data, &accounts, Signature::default(), 1, 0, None, &PUMPFUN_PROGRAM_ID, None, 0, options, None,
+            )
+            .unwrap()
+        };
+
+        let event = parse(true);
+        assert!(matches!(event, DexEvent::PumpFunTrade(_)));
+        assert_eq!(event.raw_discriminator(), Some(pumpfun::discriminators::BUY));
+        let raw = event.raw_instruction().unwrap();
+        assert_eq!((raw.data.as_slice(), raw.data_len), (data.as_slice(), 24));
+
+        assert!(parse(false).raw_discriminator().is_none());
+    }
+}
@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -69,10 +70,12 @@ impl OrcaWhirlpoolInstruction {
             &[160, 38, 208, 111, 104, 91, 44, 1] => Some(Self::DecreaseLiquidity),
             &[173, 178, 66, 24, 33, 156, 204, 31] => Some(Self::UpdateFeesAndRewards),
             &[164, 152, 207, 99, 30, 186, 19, 182] => Some(Self::CollectFees),
-            &[206, 68, 114, 253, 168, 177, 245, 180] => Some(Self::CollectReward),
+            &[70, 5, 132, 87, 86, 235, 177, 34] => Some(Self::CollectReward),
             &[22, 67, 23, 98, 150, 178, 70, 220] => Some(Self::CollectProtocolFees),
             &[248, 198, 158, 145, 225, 117, 135, 200] => Some(Self::Swap),
             &[123, 134, 81, 0, 49, 68, 98, 98] => Some(Self::ClosePosition),
+            &[207, 117, 95, 191, 229, 180, 226, 15] => Some(Self::CollectFeesV2),
+            &[177, 107, 37, 180, 160, 19, 49, 209] => Some(Self::CollectRewardV2),
             &[43, 4, 237, 11, 26, 201, 30, 98] => Some(Self::SwapV2),
             &[195, 96, 237, 108, 68, 162, 219, 230] => Some(Self::TwoHopSwap),
             &[186, 143, 209, 29, 254, 2, 194, 117] => Some(Self::TwoHopSwapV2),
@@ -95,10 +98,12 @@ pub mod discriminators {
     pub const DECREASE_LIQUIDITY: [u8; 8] = [160, 38, 208, 111, 104, 91, 44, 1];
     pub const UPDATE_FEES_AND_REWARDS: [u8; 8] = [173, 178, 66, 24, 33, 156, 204, 31];
     pub const COLLECT_FEES: [u8; 8] = [164, 152, 207, 99, 30, 186, 19, 182];
-    pub const COLLECT_REWARD: [u8; 8] = [206, 68, 114, 253, 168, 177, 245, 180];
+    pub const COLLECT_REWARD: [u8; 8] = [70, 5, 132, 87, 86, 235, 177, 34];
     pub const COLLECT_PROTOCOL_FEES: [u8; 8] = [22, 67, 23, 98, 150, 178, 70, 220];
     pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
     pub const CLOSE_POSITION: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
+    pub const COLLECT_FEES_V2: [u8; 8] = [207, 117, 95, 191, 229, 180, 226, 15];
+    pub const COLLECT_REWARD_V2: [u8; 8] = [177, 107, 37, 180, 160, 19, 49, 209];
     pub const SWAP_V2: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
     pub const TWO_HOP_SWAP: [u8; 8] = [195, 96, 237, 108, 68, 162, 219, 230];
     pub const TWO_HOP_SWAP_V2: [u8; 8] = [186, 143, 209, 29, 254, 2, 194, 117];
@@ -137,10 +142,46 @@ pub fn parse_instruction(
         OrcaWhirlpoolInstruction::InitializePool | OrcaWhirlpoolInstruction::InitializePoolV2 => {
             parse_initialize_pool_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
+        OrcaWhirlpoolInstruction::CollectFees => {
+            parse_collect_fees_instruction(accounts, false, signature, slot, tx_index, block_time)
+        },
+        OrcaWhirlpoolInstruction::CollectFeesV2 => {
+            parse_collect_fees_instruction(accounts, true, signature, slot, tx_index, block_time)
+        },
+        OrcaWhirlpoolInstruction::CollectReward => {
+            parse_collect_reward_instruction(data, accounts, false, signature, slot, tx_index, block_time)
+        },
+        OrcaWhirlpoolInstruction::CollectRewardV2 => {
+            parse_collect_reward_instruction(data, accounts, true, signature, slot, tx_index, block_time)
+        },
         _ => None, // 其他指令暂不解析
     }
 }
 
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match OrcaWhirlpoolInstruction::from_discriminator(&discriminator)? {
+        OrcaWhirlpoolInstruction::Swap | OrcaWhirlpoolInstruction::SwapV2 => Some(EventType::OrcaWhirlpoolSwap),
+        OrcaWhirlpoolInstruction::IncreaseLiquidity | OrcaWhirlpoolInstruction::IncreaseLiquidityV2 => {
+            Some(EventType::OrcaWhirlpoolLiquidityIncreased)
+        }
+        OrcaWhirlpoolInstruction::DecreaseLiquidity | OrcaWhirlpoolInstruction::DecreaseLiquidityV2 => {
+            Some(EventType::OrcaWhirlpoolLiquidityDecreased)
+        }
+        OrcaWhirlpoolInstruction::InitializePool | OrcaWhirlpoolInstruction::InitializePoolV2 => {
+            Some(EventType::OrcaWhirlpoolPoolInitialized)
+        }
+        OrcaWhirlpoolInstruction::CollectFees | OrcaWhirlpoolInstruction::CollectFeesV2 => {
+            Some(EventType::OrcaWhirlpoolCollectFees)
+        }
+        OrcaWhirlpoolInstruction::CollectReward | OrcaWhirlpoolInstruction::CollectRewardV2 => {
+            Some(EventType::OrcaWhirlpoolCollectReward)
+        }
+        _ => None,
+    }
+}
+
 /// 解析 Swap 指令
 fn parse_swap_instruction(
     data: &[u8],
@@ -310,10 +351,97 @@ fn parse_initialize_pool_instruction(
         token_mint_a,
         token_mint_b,
         tick_spacing,
-        token_program_a: get_account(accounts, 8).unwrap_or_default(),
-        token_program_b: get_account(accounts, 9).unwrap_or_default(),
+        token_program_a: acc(accounts, 8),
+        token_program_b: acc(accounts, 9),
         decimals_a: 0, // 从日志中获取
         decimals_b: 0, // 从日志中获取
         initial_sqrt_price,
     }))
-}
\ No newline at end of file
+}
+
+/// 解析 Collect Fees / Collect Fees V2 指令
+///
+/// 指令无金额参数，`amount_a` / `amount_b` 需由内层 token 转账补齐。
+/// V2 在 position token account 之后多了 token_mint_a / token_mint_b 两个账户。
+fn parse_collect_fees_instruction(
+    accounts: &[Pubkey],
+    is_v2: bool,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let whirlpool = get_account(accounts, 0)?;
+    let owner = get_account(accounts, 1)?;
+    let position = get_account(accounts, 2)?;
+    let (owner_a_index, owner_b_index) = if is_v2 { (6, 8) } else { (4, 6) };
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, whirlpool);
+
+    Some(DexEvent::OrcaWhirlpoolCollectFees(OrcaWhirlpoolCollectFeesEvent {
+        metadata,
+        whirlpool,
+        position,
+        owner,
+        token_owner_account_a: get_account(accounts, owner_a_index)?,
+        token_owner_account_b: get_account(accounts, owner_b_index)?,
+        amount_a: 0, // 从内层转账获取
+        amount_b: 0, // 从内层转账获取
+    }))
+}
+
+/// 解析 Collect Reward / Collect Reward V2 指令
+///
+/// 参数为 `reward_index: u8`；V2 在 reward owner account 之后多了 reward_mint 账户。
+fn parse_collect_reward_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    is_v2: bool,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let reward_index = read_u8(data, 0)?;
+    let whirlpool = get_account(accounts, 0)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, whirlpool);
+
+    Some(DexEvent::OrcaWhirlpoolCollectReward(OrcaWhirlpoolCollectRewardEvent {
+        metadata,
+        whirlpool,
+        owner: get_account(accounts, 1)?,
+        position: get_account(accounts, 2)?,
+        reward_owner_account: get_account(accounts, 4)?,
+        reward_index,
+        amount: 0, // 从内层转账获取
+        reward_mint: if is_v2 { get_account(accounts, 5)? } else { Pubkey::default() },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_collect_fees() {
+        // 合成数据：按 collectFees 账户布局构造的指令，后 4 个账户为 vault / token program
+        let accounts: Vec<Pubkey> = (0..9).map(|_| Pubkey::new_unique()).collect();
+        let event = parse_instruction(&discriminators::COLLECT_FEES, &accounts, Signature::default(), 7, 0, None).unwrap();
+        let DexEvent::OrcaWhirlpoolCollectFees(e) = event else { panic!("unexpected event: {:?}", event) };
+        assert_eq!(e.whirlpool, accounts[0]);
+        assert_eq!(e.owner, accounts[1]);
+        assert_eq!(e.position, accounts[2]);
+        assert_eq!((e.token_owner_account_a, e.token_owner_account_b), (accounts[4], accounts[6]));
+        assert_eq!((e.amount_a, e.amount_b), (0, 0));
+
+        // V2 带 remaining_accounts_info 参数，账户多了两个 mint
+        let accounts: Vec<Pubkey> = (0..13).map(|_| Pubkey::new_unique()).collect();
+        let mut data = discriminators::COLLECT_FEES_V2.to_vec();
+        data.push(0); // remaining_accounts_info: None
+        let event = parse_instruction(&data, &accounts, Signature::default(), 7, 0, None).unwrap();
+        let DexEvent::OrcaWhirlpoolCollectFees(e) = event else { panic!("unexpected event: {:?}", event) };
+        assert_eq!(e.position, accounts[2]);
+        assert_eq!((e.token_owner_account_a, e.token_owner_account_b), (accounts[6], accounts[8]));
+
+        assert!(parse_instruction(&discriminators::COLLECT_FEES, &accounts[..5], Signature::default(), 7, 0, None).is_none());
+    }
+}
@@ -3,37 +3,47 @@
 //! This module contains optimized Pubkey constants for all DEX protocols.
 //! Using Pubkey constants instead of string constants allows for direct
 //! comparison without expensive string conversion operations.
+//!
+//! 每个常量都由 [`crate::grpc::program_ids`] 中的 base58 字符串在编译期解码，两处不会再不一致。
 
-use solana_sdk::pubkey;
+use crate::grpc::program_ids as base58;
 use solana_sdk::pubkey::Pubkey;
 
 /// PumpFun program ID as Pubkey constant
-pub const PUMPFUN_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+pub const PUMPFUN_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::PUMPFUN_PROGRAM_ID);
 
 /// Bonk program ID as Pubkey constant
-pub const BONK_PROGRAM_ID: Pubkey = pubkey!("DjVE6JNiYqPL2QXyCUUh8rNjHrbz9hXHNYt99MQ59qw1");
+pub const BONK_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::BONK_PROGRAM_ID);
 
-/// PumpSwap program ID as Pubkey constant (same as PumpFun)
-pub const PUMPSWAP_PROGRAM_ID: Pubkey = pubkey!("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P");
+/// PumpSwap (Pump AMM) program ID as Pubkey constant
+pub const PUMPSWAP_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::PUMPSWAP_PROGRAM_ID);
 
 /// Raydium CLMM program ID as Pubkey constant
-/// Note: Original string was too long, using corrected version
-pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUQpMDdHFWF5LCATdCR");
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::RAYDIUM_CLMM_PROGRAM_ID);
 
 /// Raydium CPMM program ID as Pubkey constant
-pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::RAYDIUM_CPMM_PROGRAM_ID);
 
 /// Raydium AMM V4 program ID as Pubkey constant
-pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::RAYDIUM_AMM_V4_PROGRAM_ID);
 
 /// Orca Whirlpool program ID as Pubkey constant
-pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::ORCA_WHIRLPOOL_PROGRAM_ID);
 
 /// Meteora Pools program ID as Pubkey constant
-pub const METEORA_POOLS_PROGRAM_ID: Pubkey = pubkey!("Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB");
+pub const METEORA_POOLS_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::METEORA_POOLS_PROGRAM_ID);
 
 /// Meteora DAMM V2 program ID as Pubkey constant
-pub const METEORA_DAMM_V2_PROGRAM_ID: Pubkey = pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
+pub const METEORA_DAMM_V2_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::METEORA_DAMM_V2_PROGRAM_ID);
 
 /// Meteora DLMM program ID as Pubkey constant
-pub const METEORA_DLMM_PROGRAM_ID: Pubkey = pubkey!("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo");
\ No newline at end of file
+pub const METEORA_DLMM_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::METEORA_DLMM_PROGRAM_ID);
+
+/// SPL Token program ID as Pubkey constant
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::TOKEN_PROGRAM_ID);
+
+/// Token-2022 program ID as Pubkey constant
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::TOKEN_2022_PROGRAM_ID);
+
+/// Compute Budget program ID as Pubkey constant
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey::from_str_const(base58::COMPUTE_BUDGET_PROGRAM_ID);
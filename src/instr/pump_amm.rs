@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -12,6 +13,8 @@ pub mod discriminators {
     pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
     pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
     pub const CREATE_POOL: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+    pub const DEPOSIT: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+    pub const WITHDRAW: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
 }
 
 /// Pump AMM 程序 ID
@@ -43,11 +46,32 @@ pub fn parse_instruction(
         discriminators::CREATE_POOL => {
             parse_create_pool_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
+        discriminators::DEPOSIT => {
+            parse_deposit_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        discriminators::WITHDRAW => {
+            parse_withdraw_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        _ => None,
+    }
+}
+
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match discriminator {
+        discriminators::BUY => Some(EventType::PumpSwapBuy),
+        discriminators::SELL => Some(EventType::PumpSwapSell),
+        discriminators::CREATE_POOL => Some(EventType::PumpSwapCreatePool),
+        discriminators::DEPOSIT => Some(EventType::PumpSwapDeposit),
+        discriminators::WITHDRAW => Some(EventType::PumpSwapWithdraw),
         _ => None,
     }
 }
 
 /// 解析买入指令
+///
+/// 参数：base_amount_out, max_quote_amount_in；账户：pool(0), user(1), global_config(2), base_mint(3), quote_mint(4)
 fn parse_buy_instruction(
     data: &[u8],
     accounts: &[Pubkey],
@@ -56,29 +80,33 @@ fn parse_buy_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let sol_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let slippage = read_u16_le(data, offset)?;
+    let base_amount_out = read_u64_le(data, 0)?;
+    let max_quote_amount_in = read_u64_le(data, 8)?;
 
-    let token_mint = get_account(accounts, 0)?;
+    let token_mint = get_account(accounts, 3)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, token_mint);
 
     Some(DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
         metadata,
-        pool_id: get_account(accounts, 1).unwrap_or_default(),
-        user: get_account(accounts, 2).unwrap_or_default(),
+        pool_id: acc(accounts, 0),
+        user: acc(accounts, 1),
         token_mint,
-        sol_amount,
-        token_amount: 0, // 将从日志填充
+        sol_amount: max_quote_amount_in, // 上限，有日志时以成交数量为准
+        token_amount: base_amount_out,
         price: 0, // 将从日志计算
-        slippage,
+        slippage: 0,
+        pool_base_token_reserves: 0,
+        pool_quote_token_reserves: 0,
+        lp_fee: 0,
+        protocol_fee: 0,
+        coin_creator: Pubkey::default(),
+        coin_creator_fee: None,
     }))
 }
 
 /// 解析卖出指令
+///
+/// 参数：base_amount_in, min_quote_amount_out；账户同买入
 fn parse_sell_instruction(
     data: &[u8],
     accounts: &[Pubkey],
@@ -87,25 +115,27 @@ fn parse_sell_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let token_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let slippage = read_u16_le(data, offset)?;
+    let base_amount_in = read_u64_le(data, 0)?;
+    let min_quote_amount_out = read_u64_le(data, 8)?;
 
-    let token_mint = get_account(accounts, 0)?;
+    let token_mint = get_account(accounts, 3)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, token_mint);
 
     Some(DexEvent::PumpSwapSell(PumpSwapSellEvent {
         metadata,
-        pool_id: get_account(accounts, 1).unwrap_or_default(),
-        user: get_account(accounts, 2).unwrap_or_default(),
+        pool_id: acc(accounts, 0),
+        user: acc(accounts, 1),
         token_mint,
-        token_amount,
-        sol_amount: 0, // 将从日志填充
+        token_amount: base_amount_in,
+        sol_amount: min_quote_amount_out, // 下限，有日志时以成交数量为准
         price: 0, // 将从日志计算
-        slippage,
+        slippage: 0,
+        pool_base_token_reserves: 0,
+        pool_quote_token_reserves: 0,
+        lp_fee: 0,
+        protocol_fee: 0,
+        coin_creator: Pubkey::default(),
+        coin_creator_fee: None,
     }))
 }
 
@@ -130,11 +160,88 @@ fn parse_create_pool_instruction(
 
     Some(DexEvent::PumpSwapCreatePool(PumpSwapCreatePoolEvent {
         metadata,
-        pool_id: get_account(accounts, 2).unwrap_or_default(),
-        creator: get_account(accounts, 1).unwrap_or_default(),
+        pool_id: acc(accounts, 2),
+        creator: acc(accounts, 1),
         token_mint,
         initial_sol_amount: initial_sol_reserve,
         initial_token_amount: initial_token_reserve,
         fee_rate: 100, // 默认费率
     }))
-}
\ No newline at end of file
+}
+
+/// 解析添加流动性指令
+///
+/// 账户：pool(0), global_config(1), user(2), base_mint(3), quote_mint(4), lp_mint(5),
+/// user_base(6), user_quote(7), user_pool(8), pool_base(9), pool_quote(10) ...
+fn parse_deposit_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let lp_token_amount_out = read_u64_le(data, 0)?;
+    let max_base_amount_in = read_u64_le(data, 8)?;
+    let max_quote_amount_in = read_u64_le(data, 16)?;
+
+    let pool = get_account(accounts, 0)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
+
+    Some(DexEvent::PumpSwapDeposit(PumpSwapDepositEvent {
+        metadata,
+        timestamp: 0, // 从日志获取
+        pool,
+        user: acc(accounts, 2),
+        base_mint: acc(accounts, 3),
+        quote_mint: acc(accounts, 4),
+        lp_token_amount_out,
+        max_base_amount_in,
+        max_quote_amount_in,
+        base_amount_in: 0, // 从日志获取
+        quote_amount_in: 0, // 从日志获取
+        pool_base_token_reserves: 0, // 从日志获取
+        pool_quote_token_reserves: 0, // 从日志获取
+        lp_mint_supply: 0, // 从日志获取
+        user_base_token_account: acc(accounts, 6),
+        user_quote_token_account: acc(accounts, 7),
+        user_pool_token_account: acc(accounts, 8),
+    }))
+}
+
+/// 解析移除流动性指令（账户布局与 deposit 相同）
+fn parse_withdraw_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let lp_token_amount_in = read_u64_le(data, 0)?;
+    let min_base_amount_out = read_u64_le(data, 8)?;
+    let min_quote_amount_out = read_u64_le(data, 16)?;
+
+    let pool = get_account(accounts, 0)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
+
+    Some(DexEvent::PumpSwapWithdraw(PumpSwapWithdrawEvent {
+        metadata,
+        timestamp: 0, // 从日志获取
+        pool,
+        user: acc(accounts, 2),
+        base_mint: acc(accounts, 3),
+        quote_mint: acc(accounts, 4),
+        lp_token_amount_in,
+        min_base_amount_out,
+        min_quote_amount_out,
+        base_amount_out: 0, // 从日志获取
+        quote_amount_out: 0, // 从日志获取
+        pool_base_token_reserves: 0, // 从日志获取
+        pool_quote_token_reserves: 0, // 从日志获取
+        lp_mint_supply: 0, // 从日志获取
+        user_base_token_account: acc(accounts, 6),
+        user_quote_token_account: acc(accounts, 7),
+        user_pool_token_account: acc(accounts, 8),
+    }))
+}
@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -12,6 +13,8 @@ pub mod discriminators {
     pub const CREATE: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
     pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
     pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+    pub const SET_PARAMS: [u8; 8] = [27, 234, 178, 52, 147, 2, 187, 141];
+    pub const MIGRATE: [u8; 8] = [155, 234, 231, 146, 236, 158, 162, 30];
 }
 
 /// PumpFun 程序 ID
@@ -43,6 +46,24 @@ pub fn parse_instruction(
         discriminators::SELL => {
             parse_sell_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
+        discriminators::SET_PARAMS => {
+            parse_set_params_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        discriminators::MIGRATE => {
+            parse_migrate_instruction(accounts, signature, slot, tx_index, block_time)
+        },
+        _ => None,
+    }
+}
+
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match discriminator {
+        discriminators::CREATE => Some(EventType::PumpFunCreate),
+        discriminators::BUY | discriminators::SELL => Some(EventType::PumpFunTrade),
+        discriminators::SET_PARAMS => Some(EventType::PumpFunSetParams),
+        discriminators::MIGRATE => Some(EventType::PumpFunMigrate),
         _ => None,
     }
 }
@@ -64,9 +85,10 @@ fn parse_create_instruction(
         name: "Unknown".to_string(),
         symbol: "UNK".to_string(),
         uri: String::new(),
+        invalid_utf8: false,
         mint,
-        bonding_curve: get_account(accounts, 1).unwrap_or_default(),
-        user: get_account(accounts, 2).unwrap_or_default(),
+        bonding_curve: acc(accounts, 1),
+        user: acc(accounts, 2),
         creator: Pubkey::default(), // 将从日志填充
         timestamp: block_time.unwrap_or(0),
         virtual_token_reserves: 1_073_000_000_000_000,
@@ -101,9 +123,10 @@ fn parse_buy_instruction(
         // IDL TradeEvent 字段 - 从日志填充，这里设置默认值
         mint,
         sol_amount: 0, // 将从日志填充
-        token_amount: 0, // 将从日志填充
+        token_amount: amount, // buy 的 amount 即买到的代币数量
         is_buy: true,
         is_created_buy: false, // 将在上层设置
+        is_dev_create_token_trade: false,
         user: Pubkey::default(), // 将从日志填充
         timestamp: block_time.unwrap_or(0),
         virtual_sol_reserves: 0, // 将从日志填充
@@ -121,6 +144,10 @@ fn parse_buy_instruction(
         total_claimed_tokens: 0, // 将从日志填充
         current_sol_volume: 0, // 将从日志填充
         last_update_timestamp: block_time.unwrap_or(0), // 将从日志填充
+        sol_limit: max_sol_cost,
+        price_per_token_lamports: 0.0,
+        slippage_bps: None,
+        price_impact_bps: None,
 
         // 暂时注释，以后会用，AI禁止改动
         // 指令账户字段 - 从account_filler填充
@@ -156,9 +183,10 @@ fn parse_sell_instruction(
         // IDL TradeEvent 字段 - 从日志填充，这里设置默认值
         mint,
         sol_amount: 0, // 将从日志填充
-        token_amount: 0, // 将从日志填充
+        token_amount: amount, // sell 的 amount 即卖出的代币数量
         is_buy: false,
         is_created_buy: false, // 将在上层设置
+        is_dev_create_token_trade: false,
         user: Pubkey::default(), // 将从日志填充
         timestamp: block_time.unwrap_or(0),
         virtual_sol_reserves: 0, // 将从日志填充
@@ -176,6 +204,10 @@ fn parse_sell_instruction(
         total_claimed_tokens: 0, // 将从日志填充
         current_sol_volume: 0, // 将从日志填充
         last_update_timestamp: block_time.unwrap_or(0), // 将从日志填充
+        sol_limit: min_sol_output,
+        price_per_token_lamports: 0.0,
+        slippage_bps: None,
+        price_impact_bps: None,
 
         // 暂时注释，以后会用，AI禁止改动
         // 指令账户字段 - 从account_filler填充
@@ -184,4 +216,144 @@ fn parse_sell_instruction(
         // associated_bonding_curve: Pubkey::default(),
         // associated_user: Pubkey::default(),
     }))
-}
\ No newline at end of file
+}
+
+/// 解析 set_params 指令（管理员更新全局参数和手续费配置）
+fn parse_set_params_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let mut offset = 0;
+
+    let initial_virtual_token_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let initial_virtual_sol_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let initial_real_token_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let token_total_supply = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let fee_basis_points = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let withdraw_authority = read_pubkey(data, offset)?;
+    offset += 32;
+
+    let enable_migrate = read_bool(data, offset)?;
+    offset += 1;
+
+    let pool_migration_fee = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let creator_fee_basis_points = read_u64_le(data, offset)?;
+    offset += 8;
+
+    // 旧版本指令没有以下字段
+    let set_creator_authority = read_pubkey(data, offset).unwrap_or_default();
+    offset += 32;
+
+    let admin_set_creator_authority = read_pubkey(data, offset).unwrap_or_default();
+
+    let global = get_account(accounts, 0)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, global);
+
+    Some(DexEvent::PumpFunSetParams(PumpFunSetParamsEvent {
+        metadata,
+        initial_virtual_token_reserves,
+        initial_virtual_sol_reserves,
+        initial_real_token_reserves,
+        final_real_sol_reserves: 0, // 将从日志填充
+        token_total_supply,
+        fee_basis_points,
+        withdraw_authority,
+        enable_migrate,
+        pool_migration_fee,
+        creator_fee_basis_points,
+        fee_recipients: Vec::new(), // 将从日志填充
+        timestamp: block_time.unwrap_or(0),
+        set_creator_authority,
+        admin_set_creator_authority,
+        global,
+        authority: acc(accounts, 1),
+    }))
+}
+
+/// 解析迁移指令（无参数，金额由 MigrateEvent 日志填充）
+///
+/// 账户：global, withdraw_authority, mint, bonding_curve, associated_bonding_curve, user,
+/// system_program, token_program, pump_amm, pool, ...
+fn parse_migrate_instruction(
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let mint = get_account(accounts, 2)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, mint);
+
+    Some(DexEvent::PumpFunMigrate(PumpFunMigrateEvent {
+        metadata,
+        user: acc(accounts, 5),
+        mint,
+        mint_amount: 0,
+        sol_amount: 0,
+        pool_migration_fee: 0,
+        bonding_curve: acc(accounts, 3),
+        timestamp: block_time.unwrap_or(0),
+        pool: acc(accounts, 9),
+        pool_base_amount: 0,
+        pool_quote_amount: 0,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_params_instruction() {
+        let withdraw_authority = Pubkey::new_unique();
+        let set_creator_authority = Pubkey::new_unique();
+        let admin_set_creator_authority = Pubkey::new_unique();
+
+        let mut data = discriminators::SET_PARAMS.to_vec();
+        for v in [1_073_000_000_000_000u64, 30_000_000_000, 793_100_000_000_000, 1_000_000_000_000_000, 95] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(withdraw_authority.as_ref());
+        data.push(1);
+        data.extend_from_slice(&15_000_000u64.to_le_bytes());
+        data.extend_from_slice(&5u64.to_le_bytes());
+        data.extend_from_slice(set_creator_authority.as_ref());
+        data.extend_from_slice(admin_set_creator_authority.as_ref());
+
+        let accounts = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let event = parse_instruction(&data, &accounts, Signature::default(), 1, 0, Some(0)).unwrap();
+
+        match event {
+            DexEvent::PumpFunSetParams(e) => {
+                assert_eq!(e.initial_virtual_token_reserves, 1_073_000_000_000_000);
+                assert_eq!(e.token_total_supply, 1_000_000_000_000_000);
+                assert_eq!(e.fee_basis_points, 95);
+                assert_eq!(e.withdraw_authority, withdraw_authority);
+                assert!(e.enable_migrate);
+                assert_eq!(e.pool_migration_fee, 15_000_000);
+                assert_eq!(e.creator_fee_basis_points, 5);
+                assert_eq!(e.set_creator_authority, set_creator_authority);
+                assert_eq!(e.admin_set_creator_authority, admin_set_creator_authority);
+                assert_eq!(e.global, accounts[0]);
+                assert_eq!(e.authority, accounts[1]);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -85,6 +86,18 @@ pub fn parse_instruction(
     }
 }
 
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let event_type = match RaydiumAmmV4Instruction::from_u8(*instruction_data.first()?)? {
+        RaydiumAmmV4Instruction::SwapBaseIn | RaydiumAmmV4Instruction::SwapBaseOut => EventType::RaydiumAmmV4Swap,
+        RaydiumAmmV4Instruction::Deposit => EventType::RaydiumAmmV4Deposit,
+        RaydiumAmmV4Instruction::Withdraw => EventType::RaydiumAmmV4Withdraw,
+        RaydiumAmmV4Instruction::Initialize2 => EventType::RaydiumAmmV4Initialize2,
+        RaydiumAmmV4Instruction::WithdrawPnl => EventType::RaydiumAmmV4WithdrawPnl,
+    };
+    Some(event_type)
+}
+
 /// 解析 SwapBaseIn 指令
 fn parse_swap_base_in_instruction(
     data: &[u8],
@@ -110,24 +123,27 @@ fn parse_swap_base_in_instruction(
         minimum_amount_out,
         max_amount_in: 0,
         amount_out: 0,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
+        pool_coin_amount: 0,
+        pool_pc_amount: 0,
+        direction: 0,
+        token_program: acc(accounts, 0),
         amm,
-        amm_authority: get_account(accounts, 2).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 3).unwrap_or_default(),
+        amm_authority: acc(accounts, 2),
+        amm_open_orders: acc(accounts, 3),
         amm_target_orders: get_account(accounts, 4),
-        pool_coin_token_account: get_account(accounts, 5).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 6).unwrap_or_default(),
-        serum_program: get_account(accounts, 7).unwrap_or_default(),
-        serum_market: get_account(accounts, 8).unwrap_or_default(),
-        serum_bids: get_account(accounts, 9).unwrap_or_default(),
-        serum_asks: get_account(accounts, 10).unwrap_or_default(),
-        serum_event_queue: get_account(accounts, 11).unwrap_or_default(),
-        serum_coin_vault_account: get_account(accounts, 12).unwrap_or_default(),
-        serum_pc_vault_account: get_account(accounts, 13).unwrap_or_default(),
-        serum_vault_signer: get_account(accounts, 14).unwrap_or_default(),
-        user_source_token_account: get_account(accounts, 15).unwrap_or_default(),
-        user_destination_token_account: get_account(accounts, 16).unwrap_or_default(),
-        user_source_owner: get_account(accounts, 17).unwrap_or_default(),
+        pool_coin_token_account: acc(accounts, 5),
+        pool_pc_token_account: acc(accounts, 6),
+        serum_program: acc(accounts, 7),
+        serum_market: acc(accounts, 8),
+        serum_bids: acc(accounts, 9),
+        serum_asks: acc(accounts, 10),
+        serum_event_queue: acc(accounts, 11),
+        serum_coin_vault_account: acc(accounts, 12),
+        serum_pc_vault_account: acc(accounts, 13),
+        serum_vault_signer: acc(accounts, 14),
+        user_source_token_account: acc(accounts, 15),
+        user_destination_token_account: acc(accounts, 16),
+        user_source_owner: acc(accounts, 17),
     }))
 }
 
@@ -156,24 +172,27 @@ fn parse_swap_base_out_instruction(
         minimum_amount_out: 0,
         max_amount_in,
         amount_out,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
+        pool_coin_amount: 0,
+        pool_pc_amount: 0,
+        direction: 0,
+        token_program: acc(accounts, 0),
         amm,
-        amm_authority: get_account(accounts, 2).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 3).unwrap_or_default(),
+        amm_authority: acc(accounts, 2),
+        amm_open_orders: acc(accounts, 3),
         amm_target_orders: get_account(accounts, 4),
-        pool_coin_token_account: get_account(accounts, 5).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 6).unwrap_or_default(),
-        serum_program: get_account(accounts, 7).unwrap_or_default(),
-        serum_market: get_account(accounts, 8).unwrap_or_default(),
-        serum_bids: get_account(accounts, 9).unwrap_or_default(),
-        serum_asks: get_account(accounts, 10).unwrap_or_default(),
-        serum_event_queue: get_account(accounts, 11).unwrap_or_default(),
-        serum_coin_vault_account: get_account(accounts, 12).unwrap_or_default(),
-        serum_pc_vault_account: get_account(accounts, 13).unwrap_or_default(),
-        serum_vault_signer: get_account(accounts, 14).unwrap_or_default(),
-        user_source_token_account: get_account(accounts, 15).unwrap_or_default(),
-        user_destination_token_account: get_account(accounts, 16).unwrap_or_default(),
-        user_source_owner: get_account(accounts, 17).unwrap_or_default(),
+        pool_coin_token_account: acc(accounts, 5),
+        pool_pc_token_account: acc(accounts, 6),
+        serum_program: acc(accounts, 7),
+        serum_market: acc(accounts, 8),
+        serum_bids: acc(accounts, 9),
+        serum_asks: acc(accounts, 10),
+        serum_event_queue: acc(accounts, 11),
+        serum_coin_vault_account: acc(accounts, 12),
+        serum_pc_vault_account: acc(accounts, 13),
+        serum_vault_signer: acc(accounts, 14),
+        user_source_token_account: acc(accounts, 15),
+        user_destination_token_account: acc(accounts, 16),
+        user_source_owner: acc(accounts, 17),
     }))
 }
 
@@ -204,20 +223,20 @@ fn parse_deposit_instruction(
         max_coin_amount,
         max_pc_amount,
         base_side,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
+        token_program: acc(accounts, 0),
         amm,
-        amm_authority: get_account(accounts, 2).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 3).unwrap_or_default(),
-        amm_target_orders: get_account(accounts, 4).unwrap_or_default(),
-        lp_mint_address: get_account(accounts, 5).unwrap_or_default(),
-        pool_coin_token_account: get_account(accounts, 6).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 7).unwrap_or_default(),
-        serum_market: get_account(accounts, 8).unwrap_or_default(),
-        user_coin_token_account: get_account(accounts, 9).unwrap_or_default(),
-        user_pc_token_account: get_account(accounts, 10).unwrap_or_default(),
-        user_lp_token_account: get_account(accounts, 11).unwrap_or_default(),
-        user_owner: get_account(accounts, 12).unwrap_or_default(),
-        serum_event_queue: get_account(accounts, 13).unwrap_or_default(),
+        amm_authority: acc(accounts, 2),
+        amm_open_orders: acc(accounts, 3),
+        amm_target_orders: acc(accounts, 4),
+        lp_mint_address: acc(accounts, 5),
+        pool_coin_token_account: acc(accounts, 6),
+        pool_pc_token_account: acc(accounts, 7),
+        serum_market: acc(accounts, 8),
+        user_coin_token_account: acc(accounts, 9),
+        user_pc_token_account: acc(accounts, 10),
+        user_lp_token_account: acc(accounts, 11),
+        user_owner: acc(accounts, 12),
+        serum_event_queue: acc(accounts, 13),
     }))
 }
 
@@ -238,28 +257,28 @@ fn parse_withdraw_instruction(
     Some(DexEvent::RaydiumAmmV4Withdraw(RaydiumAmmV4WithdrawEvent {
         metadata,
         amount,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
+        token_program: acc(accounts, 0),
         amm,
-        amm_authority: get_account(accounts, 2).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 3).unwrap_or_default(),
-        amm_target_orders: get_account(accounts, 4).unwrap_or_default(),
-        lp_mint_address: get_account(accounts, 5).unwrap_or_default(),
-        pool_coin_token_account: get_account(accounts, 6).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 7).unwrap_or_default(),
-        pool_withdraw_queue: get_account(accounts, 8).unwrap_or_default(),
-        pool_temp_lp_token_account: get_account(accounts, 9).unwrap_or_default(),
-        serum_program: get_account(accounts, 10).unwrap_or_default(),
-        serum_market: get_account(accounts, 11).unwrap_or_default(),
-        serum_coin_vault_account: get_account(accounts, 12).unwrap_or_default(),
-        serum_pc_vault_account: get_account(accounts, 13).unwrap_or_default(),
-        serum_vault_signer: get_account(accounts, 14).unwrap_or_default(),
-        user_lp_token_account: get_account(accounts, 15).unwrap_or_default(),
-        user_coin_token_account: get_account(accounts, 16).unwrap_or_default(),
-        user_pc_token_account: get_account(accounts, 17).unwrap_or_default(),
-        user_owner: get_account(accounts, 18).unwrap_or_default(),
-        serum_event_queue: get_account(accounts, 19).unwrap_or_default(),
-        serum_bids: get_account(accounts, 20).unwrap_or_default(),
-        serum_asks: get_account(accounts, 21).unwrap_or_default(),
+        amm_authority: acc(accounts, 2),
+        amm_open_orders: acc(accounts, 3),
+        amm_target_orders: acc(accounts, 4),
+        lp_mint_address: acc(accounts, 5),
+        pool_coin_token_account: acc(accounts, 6),
+        pool_pc_token_account: acc(accounts, 7),
+        pool_withdraw_queue: acc(accounts, 8),
+        pool_temp_lp_token_account: acc(accounts, 9),
+        serum_program: acc(accounts, 10),
+        serum_market: acc(accounts, 11),
+        serum_coin_vault_account: acc(accounts, 12),
+        serum_pc_vault_account: acc(accounts, 13),
+        serum_vault_signer: acc(accounts, 14),
+        user_lp_token_account: acc(accounts, 15),
+        user_coin_token_account: acc(accounts, 16),
+        user_pc_token_account: acc(accounts, 17),
+        user_owner: acc(accounts, 18),
+        serum_event_queue: acc(accounts, 19),
+        serum_bids: acc(accounts, 20),
+        serum_asks: acc(accounts, 21),
     }))
 }
 
@@ -294,27 +313,27 @@ fn parse_initialize2_instruction(
         open_time,
         init_pc_amount,
         init_coin_amount,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
-        spl_associated_token_account: get_account(accounts, 1).unwrap_or_default(),
-        system_program: get_account(accounts, 2).unwrap_or_default(),
-        rent: get_account(accounts, 3).unwrap_or_default(),
+        token_program: acc(accounts, 0),
+        spl_associated_token_account: acc(accounts, 1),
+        system_program: acc(accounts, 2),
+        rent: acc(accounts, 3),
         amm,
-        amm_authority: get_account(accounts, 5).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 6).unwrap_or_default(),
-        lp_mint: get_account(accounts, 7).unwrap_or_default(),
-        coin_mint: get_account(accounts, 8).unwrap_or_default(),
-        pc_mint: get_account(accounts, 9).unwrap_or_default(),
-        pool_coin_token_account: get_account(accounts, 10).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 11).unwrap_or_default(),
-        pool_withdraw_queue: get_account(accounts, 12).unwrap_or_default(),
-        amm_target_orders: get_account(accounts, 13).unwrap_or_default(),
-        pool_temp_lp: get_account(accounts, 14).unwrap_or_default(),
-        serum_program: get_account(accounts, 15).unwrap_or_default(),
-        serum_market: get_account(accounts, 16).unwrap_or_default(),
-        user_wallet: get_account(accounts, 17).unwrap_or_default(),
-        user_token_coin: get_account(accounts, 18).unwrap_or_default(),
-        user_token_pc: get_account(accounts, 19).unwrap_or_default(),
-        user_lp_token_account: get_account(accounts, 20).unwrap_or_default(),
+        amm_authority: acc(accounts, 5),
+        amm_open_orders: acc(accounts, 6),
+        lp_mint: acc(accounts, 7),
+        coin_mint: acc(accounts, 8),
+        pc_mint: acc(accounts, 9),
+        pool_coin_token_account: acc(accounts, 10),
+        pool_pc_token_account: acc(accounts, 11),
+        pool_withdraw_queue: acc(accounts, 12),
+        amm_target_orders: acc(accounts, 13),
+        pool_temp_lp: acc(accounts, 14),
+        serum_program: acc(accounts, 15),
+        serum_market: acc(accounts, 16),
+        user_wallet: acc(accounts, 17),
+        user_token_coin: acc(accounts, 18),
+        user_token_pc: acc(accounts, 19),
+        user_lp_token_account: acc(accounts, 20),
     }))
 }
 
@@ -332,22 +351,22 @@ fn parse_withdraw_pnl_instruction(
 
     Some(DexEvent::RaydiumAmmV4WithdrawPnl(RaydiumAmmV4WithdrawPnlEvent {
         metadata,
-        token_program: get_account(accounts, 0).unwrap_or_default(),
+        token_program: acc(accounts, 0),
         amm,
-        amm_config: get_account(accounts, 2).unwrap_or_default(),
-        amm_authority: get_account(accounts, 3).unwrap_or_default(),
-        amm_open_orders: get_account(accounts, 4).unwrap_or_default(),
-        pool_coin_token_account: get_account(accounts, 5).unwrap_or_default(),
-        pool_pc_token_account: get_account(accounts, 6).unwrap_or_default(),
-        coin_pnl_token_account: get_account(accounts, 7).unwrap_or_default(),
-        pc_pnl_token_account: get_account(accounts, 8).unwrap_or_default(),
-        pnl_owner: get_account(accounts, 9).unwrap_or_default(),
-        amm_target_orders: get_account(accounts, 10).unwrap_or_default(),
-        serum_program: get_account(accounts, 11).unwrap_or_default(),
-        serum_market: get_account(accounts, 12).unwrap_or_default(),
-        serum_event_queue: get_account(accounts, 13).unwrap_or_default(),
-        serum_coin_vault_account: get_account(accounts, 14).unwrap_or_default(),
-        serum_pc_vault_account: get_account(accounts, 15).unwrap_or_default(),
-        serum_vault_signer: get_account(accounts, 16).unwrap_or_default(),
+        amm_config: acc(accounts, 2),
+        amm_authority: acc(accounts, 3),
+        amm_open_orders: acc(accounts, 4),
+        pool_coin_token_account: acc(accounts, 5),
+        pool_pc_token_account: acc(accounts, 6),
+        coin_pnl_token_account: acc(accounts, 7),
+        pc_pnl_token_account: acc(accounts, 8),
+        pnl_owner: acc(accounts, 9),
+        amm_target_orders: acc(accounts, 10),
+        serum_program: acc(accounts, 11),
+        serum_market: acc(accounts, 12),
+        serum_event_queue: acc(accounts, 13),
+        serum_coin_vault_account: acc(accounts, 14),
+        serum_pc_vault_account: acc(accounts, 15),
+        serum_vault_signer: acc(accounts, 16),
     }))
 }
\ No newline at end of file
@@ -4,16 +4,22 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
 /// Raydium CLMM discriminator 常量
 pub mod discriminators {
     pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
-    pub const INCREASE_LIQUIDITY: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
+    pub const SWAP_V2: [u8; 8] = [43, 4, 237, 11, 26, 201, 30, 98];
+    pub const INCREASE_LIQUIDITY: [u8; 8] = [46, 156, 243, 118, 13, 205, 251, 178];
+    pub const INCREASE_LIQUIDITY_V2: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
     pub const DECREASE_LIQUIDITY: [u8; 8] = [160, 38, 208, 111, 104, 91, 44, 1];
+    pub const DECREASE_LIQUIDITY_V2: [u8; 8] = [58, 127, 188, 62, 79, 82, 196, 96];
     pub const CREATE_POOL: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
     pub const OPEN_POSITION: [u8; 8] = [135, 128, 47, 77, 15, 152, 240, 49];
+    pub const OPEN_POSITION_V2: [u8; 8] = [77, 184, 74, 214, 112, 86, 241, 199];
+    pub const OPEN_POSITION_WITH_TOKEN22_NFT: [u8; 8] = [77, 255, 174, 82, 125, 29, 201, 46];
     pub const CLOSE_POSITION: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
 }
 
@@ -40,17 +46,23 @@ pub fn parse_instruction(
         discriminators::SWAP => {
             parse_swap_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
-        discriminators::INCREASE_LIQUIDITY => {
+        discriminators::SWAP_V2 => {
+            parse_swap_v2_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        discriminators::INCREASE_LIQUIDITY | discriminators::INCREASE_LIQUIDITY_V2 => {
             parse_increase_liquidity_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
-        discriminators::DECREASE_LIQUIDITY => {
+        discriminators::DECREASE_LIQUIDITY | discriminators::DECREASE_LIQUIDITY_V2 => {
             parse_decrease_liquidity_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
         discriminators::CREATE_POOL => {
             parse_create_pool_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
-        discriminators::OPEN_POSITION => {
-            parse_open_position_instruction(data, accounts, signature, slot, tx_index, block_time)
+        discriminators::OPEN_POSITION | discriminators::OPEN_POSITION_V2 => {
+            parse_open_position_instruction(data, accounts, false, signature, slot, tx_index, block_time)
+        },
+        discriminators::OPEN_POSITION_WITH_TOKEN22_NFT => {
+            parse_open_position_instruction(data, accounts, true, signature, slot, tx_index, block_time)
         },
         discriminators::CLOSE_POSITION => {
             parse_close_position_instruction(data, accounts, signature, slot, tx_index, block_time)
@@ -59,6 +71,26 @@ pub fn parse_instruction(
     }
 }
 
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match discriminator {
+        discriminators::SWAP | discriminators::SWAP_V2 => Some(EventType::RaydiumClmmSwap),
+        discriminators::INCREASE_LIQUIDITY | discriminators::INCREASE_LIQUIDITY_V2 => {
+            Some(EventType::RaydiumClmmIncreaseLiquidity)
+        }
+        discriminators::DECREASE_LIQUIDITY | discriminators::DECREASE_LIQUIDITY_V2 => {
+            Some(EventType::RaydiumClmmDecreaseLiquidity)
+        }
+        discriminators::CREATE_POOL => Some(EventType::RaydiumClmmCreatePool),
+        discriminators::OPEN_POSITION
+        | discriminators::OPEN_POSITION_V2
+        | discriminators::OPEN_POSITION_WITH_TOKEN22_NFT => Some(EventType::RaydiumClmmOpenPosition),
+        discriminators::CLOSE_POSITION => Some(EventType::RaydiumClmmClosePosition),
+        _ => None,
+    }
+}
+
 /// 解析交换指令
 fn parse_swap_instruction(
     data: &[u8],
@@ -89,7 +121,7 @@ fn parse_swap_instruction(
 
         // IDL SwapEvent 事件字段
         pool_state: pool,
-        sender: get_account(accounts, 1).unwrap_or_default(),
+        sender: acc(accounts, 1),
         token_account_0: Pubkey::default(),
         token_account_1: Pubkey::default(),
         amount_0: 0, // 从日志填充
@@ -110,8 +142,22 @@ fn parse_swap_instruction(
     }))
 }
 
-/// 解析增加流动性指令
-fn parse_increase_liquidity_instruction(
+/// swapV2 账户索引（支持 Token-2022 转账手续费）
+mod swap_v2_accounts {
+    pub const PAYER: usize = 0;
+    pub const POOL_STATE: usize = 2;
+    pub const INPUT_TOKEN_ACCOUNT: usize = 3;
+    pub const OUTPUT_TOKEN_ACCOUNT: usize = 4;
+    pub const INPUT_VAULT_MINT: usize = 11;
+    pub const OUTPUT_VAULT_MINT: usize = 12;
+}
+
+/// 解析 swapV2 指令
+///
+/// 参数与 swap 相同：amount, other_amount_threshold, sqrt_price_limit_x64(u128), is_base_input。
+/// CLMM 池子要求 mint0 < mint1，因此方向由 input/output vault mint 的大小关系得出；
+/// 指令中只有精确一侧的数量，另一侧与转账手续费从日志填充。
+fn parse_swap_v2_instruction(
     data: &[u8],
     accounts: &[Pubkey],
     signature: Signature,
@@ -119,30 +165,117 @@ fn parse_increase_liquidity_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
+    use swap_v2_accounts::*;
 
-    let liquidity = read_u64_le(data, offset)? as u128;
-    offset += 8;
+    let amount = read_u64_le(data, 0)?;
+    let sqrt_price_limit_x64 = read_u128_le(data, 16)?;
+    let is_base_input = read_bool(data, 32)?;
 
-    let amount_0_max = read_u64_le(data, offset)?;
-    offset += 8;
+    let pool = get_account(accounts, POOL_STATE)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
 
-    let amount_1_max = read_u64_le(data, offset)?;
+    let input_mint = acc(accounts, INPUT_VAULT_MINT);
+    let output_mint = acc(accounts, OUTPUT_VAULT_MINT);
+    let zero_for_one = input_mint < output_mint;
 
-    let pool = get_account(accounts, 0)?;
+    let input_account = acc(accounts, INPUT_TOKEN_ACCOUNT);
+    let output_account = acc(accounts, OUTPUT_TOKEN_ACCOUNT);
+    let (token_account_0, token_account_1) = if zero_for_one {
+        (input_account, output_account)
+    } else {
+        (output_account, input_account)
+    };
+
+    // 精确一侧：exact-in 为输入，exact-out 为输出
+    let exact_is_token_0 = zero_for_one == is_base_input;
+    let (amount_0, amount_1) = if exact_is_token_0 { (amount, 0) } else { (0, amount) };
+
+    Some(DexEvent::RaydiumClmmSwap(RaydiumClmmSwapEvent {
+        metadata,
+        pool_state: pool,
+        sender: acc(accounts, PAYER),
+        token_account_0,
+        token_account_1,
+        amount_0,
+        transfer_fee_0: 0, // 从日志填充
+        amount_1,
+        transfer_fee_1: 0, // 从日志填充
+        zero_for_one,
+        sqrt_price_x64: sqrt_price_limit_x64,
+        liquidity: 0, // 从日志填充
+        tick: 0, // 从日志填充
+    }))
+}
+
+/// 头寸指令账户索引（v2 指令在末尾追加 Token-2022 相关账户，前缀相同）
+mod position_accounts {
+    pub mod open {
+        pub const POSITION_NFT_OWNER: usize = 1;
+        pub const POSITION_NFT_MINT: usize = 2;
+        pub const POOL_STATE: usize = 5;
+        pub const PERSONAL_POSITION: usize = 9;
+    }
+    /// open_position_with_token22_nft 没有 metadata 账户（open 的 index 4），之后的账户前移一位
+    pub mod open_token22_nft {
+        pub const POOL_STATE: usize = 4;
+        pub const PERSONAL_POSITION: usize = 8;
+    }
+    pub mod increase {
+        pub const NFT_OWNER: usize = 0;
+        pub const POOL_STATE: usize = 2;
+        pub const PERSONAL_POSITION: usize = 4;
+    }
+    pub mod decrease {
+        pub const NFT_OWNER: usize = 0;
+        pub const PERSONAL_POSITION: usize = 2;
+        pub const POOL_STATE: usize = 3;
+    }
+    pub mod close {
+        pub const NFT_OWNER: usize = 0;
+        pub const POSITION_NFT_MINT: usize = 1;
+        pub const PERSONAL_POSITION: usize = 3;
+    }
+}
+
+/// 解析增加流动性指令（increase_liquidity / increase_liquidity_v2）
+///
+/// 参数：liquidity(u128), amount_0_max, amount_1_max；头寸 NFT、价格区间与实际存入数量从日志填充。
+fn parse_increase_liquidity_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    use position_accounts::increase::*;
+
+    let liquidity = read_u128_le(data, 0)?;
+    let amount0_max = read_u64_le(data, 16)?;
+    let amount1_max = read_u64_le(data, 24)?;
+
+    let pool = get_account(accounts, POOL_STATE)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
 
     Some(DexEvent::RaydiumClmmIncreaseLiquidity(RaydiumClmmIncreaseLiquidityEvent {
         metadata,
         pool,
-        user: get_account(accounts, 2).unwrap_or_default(),
+        user: acc(accounts, NFT_OWNER),
         liquidity,
-        amount0_max: amount_0_max,
-        amount1_max: amount_1_max,
+        amount0_max,
+        amount1_max,
+        position_nft_mint: Pubkey::default(),
+        position_key: acc(accounts, PERSONAL_POSITION),
+        tick_lower_index: 0, // 从日志填充
+        tick_upper_index: 0, // 从日志填充
+        amount0: 0, // 从日志填充
+        amount1: 0, // 从日志填充
     }))
 }
 
-/// 解析减少流动性指令
+/// 解析减少流动性指令（decrease_liquidity / decrease_liquidity_v2）
+///
+/// 参数：liquidity(u128), amount_0_min, amount_1_min；头寸 NFT、价格区间与实际取出数量从日志填充。
 fn parse_decrease_liquidity_instruction(
     data: &[u8],
     accounts: &[Pubkey],
@@ -151,26 +284,28 @@ fn parse_decrease_liquidity_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
+    use position_accounts::decrease::*;
 
-    let liquidity = read_u64_le(data, offset)? as u128;
-    offset += 8;
-
-    let amount_0_min = read_u64_le(data, offset)?;
-    offset += 8;
+    let liquidity = read_u128_le(data, 0)?;
+    let amount0_min = read_u64_le(data, 16)?;
+    let amount1_min = read_u64_le(data, 24)?;
 
-    let amount_1_min = read_u64_le(data, offset)?;
-
-    let pool = get_account(accounts, 0)?;
+    let pool = get_account(accounts, POOL_STATE)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
 
     Some(DexEvent::RaydiumClmmDecreaseLiquidity(RaydiumClmmDecreaseLiquidityEvent {
         metadata,
         pool,
-        user: get_account(accounts, 1).unwrap_or_default(),
+        user: acc(accounts, NFT_OWNER),
         liquidity,
-        amount0_min: amount_0_min,
-        amount1_min: amount_1_min,
+        amount0_min,
+        amount1_min,
+        position_nft_mint: Pubkey::default(),
+        position_key: acc(accounts, PERSONAL_POSITION),
+        tick_lower_index: 0, // 从日志填充
+        tick_upper_index: 0, // 从日志填充
+        amount0: 0, // 从日志填充
+        amount1: 0, // 从日志填充
     }))
 }
 
@@ -196,58 +331,58 @@ fn parse_create_pool_instruction(
     Some(DexEvent::RaydiumClmmCreatePool(RaydiumClmmCreatePoolEvent {
         metadata,
         pool,
-        creator: get_account(accounts, 1).unwrap_or_default(),
+        creator: acc(accounts, 1),
         sqrt_price_x64,
         open_time,
     }))
 }
 
-/// 解析开启头寸指令
+/// 解析开启头寸指令（open_position / open_position_v2 / open_position_with_token22_nft）
+///
+/// 参数：tick_lower_index, tick_upper_index, tick_array_lower/upper_start_index(i32), liquidity(u128),
+/// amount_0_max, amount_1_max（v2 与 token22 版本之后还有 with_metadata, base_flag）；实际存入数量从日志填充。
+/// 三种指令都输出 `RaydiumClmmOpenPosition`，与日志中的 CreatePersonalPositionEvent 配对。
 fn parse_open_position_instruction(
     data: &[u8],
     accounts: &[Pubkey],
+    token22_nft: bool,
     signature: Signature,
     slot: u64,
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let tick_lower_index = read_u32_le(data, offset)? as i32;
-    offset += 4;
+    use position_accounts::{open::*, open_token22_nft};
 
-    let tick_upper_index = read_u32_le(data, offset)? as i32;
-    offset += 4;
+    let (pool_state_index, personal_position_index) = if token22_nft {
+        (open_token22_nft::POOL_STATE, open_token22_nft::PERSONAL_POSITION)
+    } else {
+        (POOL_STATE, PERSONAL_POSITION)
+    };
 
-    let _tick_array_lower_start_index = read_u32_le(data, offset)? as i32;
-    offset += 4;
+    let tick_lower_index = read_u32_le(data, 0)? as i32;
+    let tick_upper_index = read_u32_le(data, 4)? as i32;
+    let liquidity = read_u128_le(data, 16)?;
 
-    let _tick_array_upper_start_index = read_u32_le(data, offset)? as i32;
-    offset += 4;
-
-    let liquidity = read_u64_le(data, offset)? as u128;
-    offset += 8;
-
-    let _amount_0_max = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let _amount_1_max = read_u64_le(data, offset)?;
-
-    let pool = get_account(accounts, 0)?;
+    let pool = get_account(accounts, pool_state_index)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
 
     Some(DexEvent::RaydiumClmmOpenPosition(RaydiumClmmOpenPositionEvent {
         metadata,
         pool,
-        user: get_account(accounts, 1).unwrap_or_default(),
-        position_nft_mint: get_account(accounts, 2).unwrap_or_default(),
+        user: acc(accounts, POSITION_NFT_OWNER),
+        position_nft_mint: acc(accounts, POSITION_NFT_MINT),
         tick_lower_index,
         tick_upper_index,
         liquidity,
+        position_key: acc(accounts, personal_position_index),
+        amount0: 0, // 从日志填充
+        amount1: 0, // 从日志填充
     }))
 }
 
 /// 解析关闭头寸指令
+///
+/// 账户中没有池地址，`pool` 保留为默认值；头寸关闭前流动性必须已全部取出。
 fn parse_close_position_instruction(
     _data: &[u8],
     accounts: &[Pubkey],
@@ -256,13 +391,91 @@ fn parse_close_position_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let pool = get_account(accounts, 0)?;
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool);
+    use position_accounts::close::*;
+
+    let position_key = get_account(accounts, PERSONAL_POSITION)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, position_key);
 
     Some(DexEvent::RaydiumClmmClosePosition(RaydiumClmmClosePositionEvent {
         metadata,
-        pool,
-        user: get_account(accounts, 1).unwrap_or_default(),
-        position_nft_mint: get_account(accounts, 2).unwrap_or_default(),
+        pool: Pubkey::default(),
+        user: acc(accounts, NFT_OWNER),
+        position_nft_mint: acc(accounts, POSITION_NFT_MINT),
+        position_key,
     }))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_swap_v2() {
+        let mut accounts: Vec<Pubkey> = (0..13).map(|_| Pubkey::new_unique()).collect();
+        // input mint 大于 output mint => one_for_zero
+        if accounts[11] < accounts[12] {
+            accounts.swap(11, 12);
+        }
+
+        let mut data = discriminators::SWAP_V2.to_vec();
+        data.extend_from_slice(&1_500_000u64.to_le_bytes());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&(1u128 << 70).to_le_bytes());
+        data.push(1);
+
+        let sig = Signature::new_unique();
+        let event = parse_instruction(&data, &accounts, sig, 9, 1, None).unwrap();
+        match event {
+            DexEvent::RaydiumClmmSwap(e) => {
+                assert_eq!(e.metadata.signature, sig);
+                assert_eq!(e.pool_state, accounts[2]);
+                assert_eq!(e.sender, accounts[0]);
+                assert!(!e.zero_for_one);
+                assert_eq!(e.amount_0, 0);
+                assert_eq!(e.amount_1, 1_500_000);
+                assert_eq!(e.token_account_0, accounts[4]);
+                assert_eq!(e.token_account_1, accounts[3]);
+                assert_eq!(e.sqrt_price_x64, 1u128 << 70);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // 参数不完整时不产生事件
+        assert!(parse_instruction(&data[..data.len() - 1], &accounts, sig, 9, 1, None).is_none());
+    }
+
+    #[test]
+    fn test_parse_open_position_variants() {
+        let accounts: Vec<Pubkey> = (0..22).map(|_| Pubkey::new_unique()).collect();
+        let open = |discriminator: [u8; 8]| {
+            let mut data = discriminator.to_vec();
+            data.extend_from_slice(&(-120i32).to_le_bytes());
+            data.extend_from_slice(&240i32.to_le_bytes());
+            data.extend_from_slice(&(-3_600i32).to_le_bytes());
+            data.extend_from_slice(&0i32.to_le_bytes());
+            data.extend_from_slice(&1_000_000u128.to_le_bytes());
+            data.extend_from_slice(&6_000u64.to_le_bytes());
+            data.extend_from_slice(&8_000u64.to_le_bytes());
+            if discriminator != discriminators::OPEN_POSITION {
+                // with_metadata, base_flag: Option<bool> = None
+                data.extend_from_slice(&[1, 0]);
+            }
+            match parse_instruction(&data, &accounts, Signature::default(), 9, 1, None) {
+                Some(DexEvent::RaydiumClmmOpenPosition(e)) => e,
+                other => panic!("unexpected event {:?}", other),
+            }
+        };
+
+        for discriminator in [discriminators::OPEN_POSITION, discriminators::OPEN_POSITION_V2] {
+            let e = open(discriminator);
+            assert_eq!((e.user, e.position_nft_mint), (accounts[1], accounts[2]));
+            assert_eq!((e.pool, e.position_key), (accounts[5], accounts[9]));
+            assert_eq!((e.tick_lower_index, e.tick_upper_index, e.liquidity), (-120, 240, 1_000_000));
+        }
+        // token22 NFT 没有 metadata 账户
+        let e = open(discriminators::OPEN_POSITION_WITH_TOKEN22_NFT);
+        assert_eq!((e.user, e.position_nft_mint), (accounts[1], accounts[2]));
+        assert_eq!((e.pool, e.position_key), (accounts[4], accounts[8]));
+        assert_eq!(event_type(&discriminators::OPEN_POSITION_WITH_TOKEN22_NFT), Some(EventType::RaydiumClmmOpenPosition));
+    }
+}
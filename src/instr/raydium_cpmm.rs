@@ -4,6 +4,7 @@
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
@@ -14,6 +15,25 @@ pub mod discriminators {
     pub const INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
     pub const DEPOSIT: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
     pub const WITHDRAW: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+    pub const CREATE_AMM_CONFIG: [u8; 8] = [137, 52, 237, 212, 215, 117, 108, 104];
+    pub const UPDATE_AMM_CONFIG: [u8; 8] = [49, 60, 174, 136, 154, 28, 116, 200];
+    pub const UPDATE_POOL_STATUS: [u8; 8] = [130, 87, 108, 6, 46, 224, 117, 123];
+    pub const COLLECT_PROTOCOL_FEE: [u8; 8] = [136, 136, 252, 221, 194, 66, 126, 89];
+    pub const COLLECT_FUND_FEE: [u8; 8] = [167, 138, 78, 149, 223, 194, 6, 126];
+
+    /// 所有已知的 CPMM 指令 discriminator
+    pub const ALL: [[u8; 8]; 10] = [
+        SWAP_BASE_IN,
+        SWAP_BASE_OUT,
+        INITIALIZE,
+        DEPOSIT,
+        WITHDRAW,
+        CREATE_AMM_CONFIG,
+        UPDATE_AMM_CONFIG,
+        UPDATE_POOL_STATUS,
+        COLLECT_PROTOCOL_FEE,
+        COLLECT_FUND_FEE,
+    ];
 }
 
 /// Raydium CPMM 程序 ID
@@ -51,6 +71,27 @@ pub fn parse_instruction(
         discriminators::WITHDRAW => {
             parse_withdraw_instruction(data, accounts, signature, slot, tx_index, block_time)
         },
+        discriminators::UPDATE_POOL_STATUS => {
+            parse_update_pool_status_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        // 管理员配置/手续费提取指令，暂不生成事件
+        discriminators::CREATE_AMM_CONFIG
+        | discriminators::UPDATE_AMM_CONFIG
+        | discriminators::COLLECT_PROTOCOL_FEE
+        | discriminators::COLLECT_FUND_FEE => None,
+        _ => None,
+    }
+}
+
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match discriminator {
+        discriminators::SWAP_BASE_IN | discriminators::SWAP_BASE_OUT => Some(EventType::RaydiumCpmmSwap),
+        discriminators::INITIALIZE => Some(EventType::RaydiumCpmmInitialize),
+        discriminators::DEPOSIT => Some(EventType::RaydiumCpmmDeposit),
+        discriminators::WITHDRAW => Some(EventType::RaydiumCpmmWithdraw),
+        discriminators::UPDATE_POOL_STATUS => Some(EventType::RaydiumCpmmPoolStatusUpdated),
         _ => None,
     }
 }
@@ -184,7 +225,7 @@ fn parse_initialize_instruction(
     Some(DexEvent::RaydiumCpmmInitialize(RaydiumCpmmInitializeEvent {
         metadata,
         pool,
-        creator: get_account(accounts, 1).unwrap_or_default(),
+        creator: acc(accounts, 1),
         init_amount0,
         init_amount1,
     }))
@@ -215,7 +256,7 @@ fn parse_deposit_instruction(
     Some(DexEvent::RaydiumCpmmDeposit(RaydiumCpmmDepositEvent {
         metadata,
         pool,
-        user: get_account(accounts, 1).unwrap_or_default(),
+        user: acc(accounts, 1),
         lp_token_amount,
         token0_amount: maximum_token_0_amount, // 先赋值为maximum，logs会覆盖
         token1_amount: maximum_token_1_amount, // 先赋值为maximum，logs会覆盖
@@ -247,9 +288,63 @@ fn parse_withdraw_instruction(
     Some(DexEvent::RaydiumCpmmWithdraw(RaydiumCpmmWithdrawEvent {
         metadata,
         pool,
-        user: get_account(accounts, 1).unwrap_or_default(),
+        user: acc(accounts, 1),
         lp_token_amount,
         token0_amount: minimum_token_0_amount, // 先赋值为minimum，logs会覆盖
         token1_amount: minimum_token_1_amount, // 先赋值为minimum，logs会覆盖
     }))
-}
\ No newline at end of file
+}
+
+/// 解析池子状态更新指令（accounts: authority, pool_state）
+fn parse_update_pool_status_instruction(
+    data: &[u8],
+    accounts: &[Pubkey],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+) -> Option<DexEvent> {
+    let status = read_u8(data, 0)?;
+
+    let pool_state = get_account(accounts, 1)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state);
+
+    Some(DexEvent::RaydiumCpmmPoolStatusUpdated(RaydiumCpmmPoolStatusUpdatedEvent {
+        metadata,
+        pool_state,
+        authority: acc(accounts, 0),
+        status,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_discriminators_distinct() {
+        let unique: HashSet<_> = discriminators::ALL.iter().collect();
+        assert_eq!(unique.len(), discriminators::ALL.len());
+    }
+
+    #[test]
+    fn test_parse_update_pool_status() {
+        let authority = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+        let mut data = discriminators::UPDATE_POOL_STATUS.to_vec();
+        data.push(0b100);
+
+        let event = parse_instruction(&data, &[authority, pool], Signature::default(), 5, 0, None).unwrap();
+        match event {
+            DexEvent::RaydiumCpmmPoolStatusUpdated(e) => {
+                assert_eq!(e.pool_state, pool);
+                assert_eq!(e.authority, authority);
+                assert_eq!(e.status, 0b100);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        assert!(parse_instruction(&data[..8], &[authority, pool], Signature::default(), 5, 0, None).is_none());
+    }
+}
@@ -1,17 +1,28 @@
 //! Bonk 指令解析器
 //!
-//! 使用 match discriminator 模式解析 Bonk 指令
+//! 使用 match discriminator 模式解析 Bonk (Raydium Launchpad) 指令
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
+use crate::grpc::types::EventType;
 use super::utils::*;
 use super::program_ids;
 
 /// Bonk discriminator 常量
 pub mod discriminators {
-    pub const TRADE: [u8; 8] = [2, 3, 4, 5, 6, 7, 8, 9];
-    pub const POOL_CREATE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
-    pub const MIGRATE_AMM: [u8; 8] = [3, 4, 5, 6, 7, 8, 9, 10];
+    pub const BUY_EXACT_IN: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
+    pub const BUY_EXACT_OUT: [u8; 8] = [24, 211, 116, 40, 105, 3, 153, 56];
+    pub const SELL_EXACT_IN: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+    pub const SELL_EXACT_OUT: [u8; 8] = [95, 200, 71, 34, 8, 9, 11, 166];
+    pub const INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+    pub const MIGRATE_TO_AMM: [u8; 8] = [207, 82, 192, 145, 254, 207, 145, 223];
+    pub const MIGRATE_TO_CPSWAP: [u8; 8] = [136, 92, 200, 103, 28, 218, 144, 140];
+}
+
+/// 迁移目标（与 CurveParams 中的 migrate_type 取值一致）
+pub mod migrate_types {
+    pub const AMM: u8 = 0;
+    pub const CPSWAP: u8 = 1;
 }
 
 /// Raydium Launchpad 程序 ID
@@ -34,52 +45,218 @@ pub fn parse_instruction(
     let data = &instruction_data[8..];
 
     match discriminator {
-        discriminators::TRADE => {
-            parse_trade_instruction(data, accounts, signature, slot, tx_index, block_time)
+        discriminators::BUY_EXACT_IN => {
+            parse_trade_instruction(data, accounts, true, true, signature, slot, tx_index, block_time)
+        },
+        discriminators::BUY_EXACT_OUT => {
+            parse_trade_instruction(data, accounts, true, false, signature, slot, tx_index, block_time)
+        },
+        discriminators::SELL_EXACT_IN => {
+            parse_trade_instruction(data, accounts, false, true, signature, slot, tx_index, block_time)
         },
-        discriminators::POOL_CREATE => {
-            parse_pool_create_instruction(data, accounts, signature, slot, tx_index, block_time)
+        discriminators::SELL_EXACT_OUT => {
+            parse_trade_instruction(data, accounts, false, false, signature, slot, tx_index, block_time)
         },
-        discriminators::MIGRATE_AMM => {
-            parse_migrate_amm_instruction(data, accounts, signature, slot, tx_index, block_time)
+        discriminators::INITIALIZE => {
+            parse_initialize_instruction(data, accounts, signature, slot, tx_index, block_time)
+        },
+        discriminators::MIGRATE_TO_AMM => {
+            parse_migrate_instruction(accounts, migrate_types::AMM, signature, slot, tx_index, block_time)
+        },
+        discriminators::MIGRATE_TO_CPSWAP => {
+            parse_migrate_instruction(accounts, migrate_types::CPSWAP, signature, slot, tx_index, block_time)
         },
         _ => None,
     }
 }
 
-/// 解析交易指令
+/// 按判别器预判指令产生的事件类型（不解析账户和参数），无法确定时返回 None
+pub fn event_type(instruction_data: &[u8]) -> Option<EventType> {
+    let discriminator: [u8; 8] = instruction_data.get(0..8)?.try_into().ok()?;
+    match discriminator {
+        discriminators::BUY_EXACT_IN
+        | discriminators::BUY_EXACT_OUT
+        | discriminators::SELL_EXACT_IN
+        | discriminators::SELL_EXACT_OUT => Some(EventType::BonkTrade),
+        discriminators::INITIALIZE => Some(EventType::BonkPoolCreate),
+        discriminators::MIGRATE_TO_AMM | discriminators::MIGRATE_TO_CPSWAP => Some(EventType::BonkMigrateAmm),
+        _ => None,
+    }
+}
+
+/// 解析交易指令（buy/sell × exact_in/exact_out）
+///
+/// 参数：exact-in 为 `(amount_in, minimum_amount_out, share_fee_rate)`，
+/// exact-out 为 `(amount_out, maximum_amount_in, share_fee_rate)`。
+/// 账户：payer@0, pool_state@4, base_token_mint@9, quote_token_mint@10；实际成交数量以日志为准，指令只提供用户意图。
+#[allow(clippy::too_many_arguments)]
 fn parse_trade_instruction(
     data: &[u8],
     accounts: &[Pubkey],
+    is_buy: bool,
+    exact_in: bool,
     signature: Signature,
     slot: u64,
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let amount_in = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let amount_out_min = read_u64_le(data, offset)?;
+    let amount = read_u64_le(data, 0)?;
+    let limit = read_u64_le(data, 8)?;
 
-    let pool_state = get_account(accounts, 0)?;
+    let pool_state = get_account(accounts, 4)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state);
+    // 没有日志时，数量先用指令中的数量和限额
+    let (amount_in, amount_out) = if exact_in { (amount, limit) } else { (limit, amount) };
 
     Some(DexEvent::BonkTrade(BonkTradeEvent {
         metadata,
         pool_state,
-        user: get_account(accounts, 1).unwrap_or_default(),
+        user: acc(accounts, 0),
         amount_in,
-        amount_out: amount_out_min, // 先用指令中的最小值，日志会覆盖实际值
-        is_buy: true, // 默认为买入，实际值从日志确定
-        trade_direction: TradeDirection::Buy,
-        exact_in: true,
+        amount_out,
+        is_buy,
+        trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
+        exact_in,
+        base_mint: acc(accounts, 9),
+        quote_mint: acc(accounts, 10),
+        minimum_amount_out: exact_in.then_some(limit),
+        maximum_amount_in: (!exact_in).then_some(limit),
+        protocol_fee: None,
+        platform_fee: None,
+        share_fee: None,
+        virtual_base: None,
+        virtual_quote: None,
+        real_base_after: None,
+        real_quote_after: None,
+        pool_status: None,
+        curve_progress_bps: None,
     }))
 }
 
+/// `initialize` 指令参数与 `PoolCreateEvent` 共用的布局：MintParams + CurveParams + VestingParams
+pub(crate) struct PoolCreateParams {
+    pub base_mint_param: BaseMintParam,
+    pub curve_type: u8,
+    pub supply: u64,
+    pub total_base_sell: u64,
+    pub total_quote_fund_raising: u64,
+    pub migrate_type: u8,
+    pub total_locked_amount: u64,
+    pub cliff_period: u64,
+    pub unlock_period: u64,
+}
+
+/// 解析 MintParams { decimals, name, symbol, uri } + CurveParams + VestingParams
+pub(crate) fn parse_pool_create_params(data: &[u8]) -> Option<PoolCreateParams> {
+    let mut offset = 0;
+
+    let decimals = read_u8(data, offset)?;
+    offset += 1;
+    let (name, len) = read_string(data, offset)?;
+    offset += len;
+    let (symbol, len) = read_string(data, offset)?;
+    offset += len;
+    let (uri, len) = read_string(data, offset)?;
+    offset += len;
+
+    // CurveParams 枚举：Constant { supply, total_base_sell, total_quote_fund_raising, migrate_type }
+    // Fixed / Linear { supply, total_quote_fund_raising, migrate_type }
+    let curve_type = read_u8(data, offset)?;
+    offset += 1;
+    let supply = read_u64_le(data, offset)?;
+    offset += 8;
+    let total_base_sell = if curve_type == 0 {
+        let value = read_u64_le(data, offset)?;
+        offset += 8;
+        value
+    } else if curve_type <= 2 {
+        0
+    } else {
+        return None;
+    };
+    let total_quote_fund_raising = read_u64_le(data, offset)?;
+    offset += 8;
+    let migrate_type = read_u8(data, offset)?;
+    offset += 1;
+
+    let total_locked_amount = read_u64_le(data, offset)?;
+    let cliff_period = read_u64_le(data, offset + 8)?;
+    let unlock_period = read_u64_le(data, offset + 16)?;
+
+    Some(PoolCreateParams {
+        base_mint_param: BaseMintParam { symbol, name, uri, decimals },
+        curve_type,
+        supply,
+        total_base_sell,
+        total_quote_fund_raising,
+        migrate_type,
+        total_locked_amount,
+        cliff_period,
+        unlock_period,
+    })
+}
+
+/// Constant 曲线的初始虚拟储备
+///
+/// 曲线售出 `total_base_sell` 后恰好募集 `total_quote_fund_raising`，且终点价格等于
+/// 迁移时剩余 base 与募集 quote 的比价，解得：
+/// `virtual_base = A² / (2A - S)`，`virtual_quote = F · (S - A) / (2A - S)`
+pub fn constant_curve_virtual_reserves(supply: u64, total_base_sell: u64, total_quote_fund_raising: u64) -> (u64, u64) {
+    let (s, a, f) = (supply as u128, total_base_sell as u128, total_quote_fund_raising as u128);
+    if a == 0 || 2 * a <= s || a > s {
+        return (0, 0);
+    }
+    let denominator = 2 * a - s;
+    let virtual_base = a * a / denominator;
+    let virtual_quote = f * (s - a) / denominator;
+    (virtual_base.min(u64::MAX as u128) as u64, virtual_quote.min(u64::MAX as u128) as u64)
+}
+
+impl PoolCreateParams {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn into_event(
+        self,
+        metadata: EventMetadata,
+        pool_state: Pubkey,
+        creator: Pubkey,
+        config: Pubkey,
+        platform_config: Pubkey,
+        base_mint: Pubkey,
+        quote_mint: Pubkey,
+    ) -> DexEvent {
+        let (virtual_base, virtual_quote) = if self.curve_type == 0 {
+            constant_curve_virtual_reserves(self.supply, self.total_base_sell, self.total_quote_fund_raising)
+        } else {
+            (0, 0)
+        };
+
+        DexEvent::BonkPoolCreate(BonkPoolCreateEvent {
+            metadata,
+            base_mint_param: self.base_mint_param,
+            pool_state,
+            creator,
+            config,
+            platform_config,
+            base_mint,
+            quote_mint,
+            curve_type: self.curve_type,
+            supply: self.supply,
+            total_base_sell: self.total_base_sell,
+            total_quote_fund_raising: self.total_quote_fund_raising,
+            migrate_type: self.migrate_type,
+            virtual_base,
+            virtual_quote,
+            total_locked_amount: self.total_locked_amount,
+            cliff_period: self.cliff_period,
+            unlock_period: self.unlock_period,
+        })
+    }
+}
+
 /// 解析池创建指令
-fn parse_pool_create_instruction(
+///
+/// 账户：payer, creator, global_config, platform_config, authority, pool_state, base_mint, quote_mint, ...
+fn parse_initialize_instruction(
     data: &[u8],
     accounts: &[Pubkey],
     signature: Signature,
@@ -87,43 +264,122 @@ fn parse_pool_create_instruction(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let pool_state = get_account(accounts, 0)?;
+    let params = parse_pool_create_params(data)?;
+
+    let pool_state = get_account(accounts, 5)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state);
 
-    Some(DexEvent::BonkPoolCreate(BonkPoolCreateEvent {
+    Some(params.into_event(
         metadata,
-        base_mint_param: BaseMintParam {
-            symbol: "BONK".to_string(),
-            name: "Bonk Pool".to_string(),
-            uri: "https://bonk.com".to_string(),
-            decimals: 5,
-        },
         pool_state,
-        creator: get_account(accounts, 1).unwrap_or_default(),
-    }))
+        acc(accounts, 1),
+        acc(accounts, 2),
+        acc(accounts, 3),
+        acc(accounts, 6),
+        acc(accounts, 7),
+    ))
 }
 
-/// 解析 AMM 迁移指令
-fn parse_migrate_amm_instruction(
-    data: &[u8],
+/// 解析迁移指令（migrate_to_amm / migrate_to_cpswap）
+///
+/// migrate_to_amm 账户：payer, base_mint, quote_mint, openbook_program, market, ... amm_program(12), amm_pool(13), ... authority(22), pool_state(23)
+/// migrate_to_cpswap 账户：payer, base_mint, quote_mint, platform_config, cpswap_program, cpswap_pool(5), ... authority(16), pool_state(17)
+fn parse_migrate_instruction(
     accounts: &[Pubkey],
+    migrate_type: u8,
     signature: Signature,
     slot: u64,
     tx_index: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
+    let (pool_index, new_pool_index) = match migrate_type {
+        migrate_types::AMM => (23, 13),
+        _ => (17, 5),
+    };
 
-    let liquidity_amount = read_u64_le(data, offset)?;
-
-    let old_pool = get_account(accounts, 0)?;
+    let old_pool = get_account(accounts, pool_index)?;
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, old_pool);
 
     Some(DexEvent::BonkMigrateAmm(BonkMigrateAmmEvent {
         metadata,
         old_pool,
-        new_pool: get_account(accounts, 1).unwrap_or_default(),
-        user: get_account(accounts, 2).unwrap_or_default(),
-        liquidity_amount,
+        new_pool: acc(accounts, new_pool_index),
+        user: acc(accounts, 0),
+        liquidity_amount: 0,
+        base_mint: acc(accounts, 1),
+        quote_mint: acc(accounts, 2),
+        migrate_type,
     }))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 按 launchpad IDL 编码 MintParams + Constant CurveParams + VestingParams
+    pub(crate) fn encode_pool_create_params() -> Vec<u8> {
+        let mut data = vec![6u8];
+        for s in ["Example", "EXM", "https://example.com/exm.json"] {
+            data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+            data.extend_from_slice(s.as_bytes());
+        }
+        data.push(0); // CurveParams::Constant
+        data.extend_from_slice(&1_000_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&793_100_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&85_000_000_000u64.to_le_bytes());
+        data.push(migrate_types::CPSWAP);
+        data.extend_from_slice(&[0u8; 24]); // VestingParams
+        data
+    }
+
+    #[test]
+    fn test_initialize_instruction() {
+        let accounts: Vec<Pubkey> = (0..18).map(|_| Pubkey::new_unique()).collect();
+        let mut data = discriminators::INITIALIZE.to_vec();
+        data.extend(encode_pool_create_params());
+
+        match parse_instruction(&data, &accounts, Signature::default(), 1, 0, None) {
+            Some(DexEvent::BonkPoolCreate(e)) => {
+                assert_eq!(e.pool_state, accounts[5]);
+                assert_eq!(e.creator, accounts[1]);
+                assert_eq!(e.config, accounts[2]);
+                assert_eq!(e.base_mint, accounts[6]);
+                assert_eq!(e.quote_mint, accounts[7]);
+                assert_eq!(e.base_mint_param.symbol, "EXM");
+                assert_eq!(e.base_mint_param.decimals, 6);
+                assert_eq!(e.supply, 1_000_000_000_000_000);
+                assert_eq!(e.total_quote_fund_raising, 85_000_000_000);
+                assert_eq!(e.migrate_type, migrate_types::CPSWAP);
+                // bonk.fun 默认曲线的初始虚拟储备（约 1.073e15 base / 30 SOL）
+                assert_eq!(e.virtual_base, 1_073_025_605_595_359);
+                assert_eq!(e.virtual_quote, 30_000_852_951);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_migrate_instructions() {
+        let accounts: Vec<Pubkey> = (0..30).map(|_| Pubkey::new_unique()).collect();
+
+        match parse_instruction(&discriminators::MIGRATE_TO_AMM, &accounts, Signature::default(), 1, 0, None) {
+            Some(DexEvent::BonkMigrateAmm(e)) => {
+                assert_eq!(e.migrate_type, migrate_types::AMM);
+                assert_eq!(e.old_pool, accounts[23]);
+                assert_eq!(e.new_pool, accounts[13]);
+                assert_eq!(e.base_mint, accounts[1]);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        match parse_instruction(&discriminators::MIGRATE_TO_CPSWAP, &accounts, Signature::default(), 1, 0, None) {
+            Some(DexEvent::BonkMigrateAmm(e)) => {
+                assert_eq!(e.migrate_type, migrate_types::CPSWAP);
+                assert_eq!(e.old_pool, accounts[17]);
+                assert_eq!(e.new_pool, accounts[5]);
+                assert_eq!(e.user, accounts[0]);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! SPL Token / Token-2022 转账指令解析
+//!
+//! 只解析 `transfer` / `transferChecked`，用于从交易的内层指令还原 DEX 实际转账数量，
+//! 配合 [`crate::core::transfer_filler`] 补全日志中缺失的成交数量。
+
+use solana_sdk::pubkey::Pubkey;
+use super::utils::*;
+use super::program_ids::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+/// 指令标签（首字节）
+pub mod discriminators {
+    pub const TRANSFER: u8 = 3;
+    pub const TRANSFER_CHECKED: u8 = 12;
+}
+
+/// 一次代币转账
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    /// 仅 `transferChecked` 携带
+    pub mint: Option<Pubkey>,
+    pub decimals: Option<u8>,
+}
+
+/// 是否为 SPL Token 或 Token-2022 程序
+#[inline]
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == TOKEN_PROGRAM_ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+/// 解析转账指令，其它程序或其它指令返回 None
+pub fn parse_transfer(program_id: &Pubkey, data: &[u8], accounts: &[Pubkey]) -> Option<TokenTransfer> {
+    if !is_token_program(program_id) {
+        return None;
+    }
+    let amount = read_u64_le(data, 1)?;
+    match *data.first()? {
+        discriminators::TRANSFER => Some(TokenTransfer {
+            source: get_account(accounts, 0)?,
+            destination: get_account(accounts, 1)?,
+            authority: get_account(accounts, 2)?,
+            amount,
+            mint: None,
+            decimals: None,
+        }),
+        discriminators::TRANSFER_CHECKED => Some(TokenTransfer {
+            source: get_account(accounts, 0)?,
+            mint: Some(get_account(accounts, 1)?),
+            destination: get_account(accounts, 2)?,
+            authority: get_account(accounts, 3)?,
+            amount,
+            decimals: Some(read_u8(data, 9)?),
+        }),
+        _ => None,
+    }
+}
@@ -1,7 +1,7 @@
 //! 指令解析通用工具函数
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use crate::core::events::EventMetadata;
+use crate::core::events::{EventMetadata, EventSource};
 
 /// 创建事件元数据的通用函数
 pub fn create_metadata(
@@ -17,6 +17,15 @@ pub fn create_metadata(
         tx_index,
         block_time_us,
         grpc_recv_us,
+        handle_us: crate::common::time::handle_micros(grpc_recv_us),
+        event_source: EventSource::Instruction,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
     }
 }
 
@@ -29,18 +38,8 @@ pub fn create_metadata_simple(
     block_time: Option<i64>,
     _program_id: Pubkey,
 ) -> EventMetadata {
-    // 优化：macOS 使用 CLOCK_REALTIME（Linux 可用 CLOCK_REALTIME_COARSE）
-    let current_time = unsafe {
-        let mut ts = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
-        };
-        #[cfg(target_os = "linux")]
-        libc::clock_gettime(libc::CLOCK_REALTIME_COARSE, &mut ts);
-        #[cfg(not(target_os = "linux"))]
-        libc::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
-        (ts.tv_sec as i64 * 1_000_000) + (ts.tv_nsec as i64 / 1_000)
-    };
+    // Linux 上使用 CLOCK_REALTIME_COARSE
+    let current_time = crate::common::time::now_micros_coarse();
 
     EventMetadata {
         signature,
@@ -48,6 +47,15 @@ pub fn create_metadata_simple(
         tx_index,
         block_time_us: block_time.map_or(0, |t| t * 1_000_000),
         grpc_recv_us: current_time,
+        handle_us: current_time,
+        event_source: EventSource::Instruction,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
     }
 }
 
@@ -92,6 +100,15 @@ pub fn read_u128_le(data: &[u8], offset: usize) -> Option<u128> {
         .map(|slice| u128::from_le_bytes(slice.try_into().unwrap()))
 }
 
+/// 从指令数据中读取 borsh 字符串（u32 长度前缀），返回字符串和占用的字节数
+#[inline]
+pub fn read_string(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let len = read_u32_le(data, offset)? as usize;
+    let bytes = data.get(offset + 4..offset + 4 + len)?;
+    let string = std::str::from_utf8(bytes).ok()?.to_string();
+    Some((string, 4 + len))
+}
+
 /// 从指令数据中读取布尔值
 #[inline(always)]
 pub fn read_bool(data: &[u8], offset: usize) -> Option<bool> {
@@ -111,6 +128,12 @@ pub fn get_account(accounts: &[Pubkey], index: usize) -> Option<Pubkey> {
     accounts.get(index).copied()
 }
 
+/// 从账户列表中获取账户，越界时返回默认公钥（用于非关键账户字段）
+#[inline(always)]
+pub fn acc(accounts: &[Pubkey], index: usize) -> Pubkey {
+    accounts.get(index).copied().unwrap_or_default()
+}
+
 /// 计算滑点基点
 pub fn calculate_slippage_bps(amount_in: u64, amount_out_min: u64) -> u16 {
     if amount_in == 0 {
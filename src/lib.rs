@@ -3,11 +3,24 @@ pub mod common;
 pub mod core;
 pub mod instr;    // 指令解析器
 pub mod logs;     // 日志解析器
+pub mod accounts; // 账户数据解析器
 pub mod utils;
 
-// gRPC 模块 - 支持gRPC订阅和过滤
+// gRPC 模块 - 支持gRPC订阅和过滤（只启用 `parse` feature 时仅包含协议和事件类型定义）
 pub mod grpc;
 
+// 测试工具 - 合成事件生成器
+#[cfg(feature = "grpc")]
+pub mod testing;
+
+// 快照测试夹具 - 加载记录的交易并规范化解析结果
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+
+// 极致性能优化模块（src/perf）：默认构建只需要解析器，模块及其专用依赖放在 `perf` feature 下
+#[cfg(feature = "perf")]
+pub mod perf;
+
 // 兼容性别名
 pub mod parser {
     pub use crate::core::*;
@@ -16,11 +29,41 @@ pub mod parser {
 // 重新导出主要API - 简化的单一入口解析器
 pub use core::{
     // 事件类型
-    DexEvent, EventMetadata, ParsedEvent,
+    DexEvent, EventMetadata, EventSource, ParsedEvent,
     // 主要解析函数
-    parse_transaction_events, parse_logs_only, parse_transaction_with_listener,
+    parse_transaction_events, parse_transaction_events_iter, parse_logs_only, parse_transaction_with_listener,
+    parse_transaction_events_with_options, parse_transaction_events_iter_with_options, ParseMode, ParseOptions,
     // 流式解析函数
-    parse_transaction_events_streaming, parse_logs_streaming, parse_transaction_with_streaming_listener,
+    parse_transaction_events_streaming, parse_transaction_events_streaming_with_options, parse_logs_streaming,
+    parse_transaction_with_streaming_listener, parse_transaction_with_streaming_listener_with_options,
+    // 指令/日志事件合并
+    merge_instruction_and_log_events, merge_instruction_and_log_events_with_mode,
+    merge_instruction_and_log_events_with_preferences, MergeField, MergeMode, MergePreferences, MergeSource,
     // 事件监听器
-    EventListener, StreamingEventListener,
+    EventListener, AsyncEventListener, StreamingEventListener,
 };
+
+// 按代币/池子分发
+#[cfg(feature = "grpc")]
+pub use core::EventRouter;
+
+// 按签名拉取并解析单笔交易
+#[cfg(feature = "rpc")]
+pub use grpc::rpc::{fetch_and_parse, RpcFetchError};
+
+#[cfg(test)]
+mod tests {
+    /// 不带任何 feature 的精简构建（较慢，单独的 target 目录；`cargo test -- --ignored` 运行）
+    #[test]
+    #[ignore]
+    fn test_lean_build_without_default_features() {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let status = std::process::Command::new(env!("CARGO"))
+            .args(["check", "--lib", "--no-default-features", "--manifest-path"])
+            .arg(format!("{}/Cargo.toml", manifest_dir))
+            .env("CARGO_TARGET_DIR", format!("{}/target/lean-build", manifest_dir))
+            .status()
+            .expect("run cargo");
+        assert!(status.success());
+    }
+}
@@ -16,6 +16,50 @@ pub mod discriminators {
     pub const SET_POOL_FEES_EVENT: [u8; 8] = [245, 26, 198, 164, 88, 18, 75, 9];
 }
 
+/// 有效费率 bps（四舍五入）：`total_fee / in_amount`，`total_fee` 为 `trade_fee + admin_fee + host_fee`
+pub fn effective_fee_bps(in_amount: u64, total_fee: u64) -> u16 {
+    if in_amount == 0 {
+        return 0;
+    }
+    let in_amount = in_amount as u128;
+    ((total_fee as u128 * 10_000 + in_amount / 2) / in_amount).min(u16::MAX as u128) as u16
+}
+
+/// 按池子配置的交易费率判断费率模式
+///
+/// Swap 事件不带费率配置，`trade_fee_numerator / trade_fee_denominator` 取自池子账户的 `fees`
+/// 或 SetPoolFees 事件。实收总手续费与 `in_amount * numerator / denominator` 相差不超过 1 个最小单位
+/// （取整误差）为基础费，否则为动态费；分母为 0 时返回 [`MeteoraFeeMode::Unknown`]。
+pub fn classify_fee(
+    in_amount: u64,
+    total_fee: u64,
+    trade_fee_numerator: u64,
+    trade_fee_denominator: u64,
+) -> MeteoraFeeMode {
+    if trade_fee_denominator == 0 {
+        return MeteoraFeeMode::Unknown;
+    }
+    let expected = in_amount as u128 * trade_fee_numerator as u128 / trade_fee_denominator as u128;
+    if (total_fee as u128).abs_diff(expected) <= 1 {
+        MeteoraFeeMode::Base
+    } else {
+        MeteoraFeeMode::Dynamic
+    }
+}
+
+impl MeteoraPoolsSwapEvent {
+    /// 实收总手续费：交易费 + 协议费 + host 费
+    #[inline]
+    pub fn total_fee(&self) -> u64 {
+        self.trade_fee.saturating_add(self.admin_fee).saturating_add(self.host_fee)
+    }
+
+    /// 用池子的交易费率配置填充 `fee_mode`（见 [`classify_fee`]）
+    pub fn apply_pool_fees(&mut self, trade_fee_numerator: u64, trade_fee_denominator: u64) {
+        self.fee_mode = classify_fee(self.in_amount, self.total_fee(), trade_fee_numerator, trade_fee_denominator);
+    }
+}
+
 /// 主要的 Meteora Pools 日志解析函数
 pub fn parse_log(log: &str, signature: Signature, slot: u64, tx_index: u64, block_time: Option<i64>, grpc_recv_us: i64) -> Option<DexEvent> {
     parse_structured_log(log, signature, slot, tx_index, block_time, grpc_recv_us)
@@ -30,36 +74,37 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::SWAP_EVENT => {
-            parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::ADD_LIQUIDITY_EVENT => {
-            parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::REMOVE_LIQUIDITY_EVENT => {
-            parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::BOOTSTRAP_LIQUIDITY_EVENT => {
-            parse_bootstrap_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::POOL_CREATED_EVENT => {
-            parse_pool_created_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::SET_POOL_FEES_EVENT => {
-            parse_set_pool_fees_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP_EVENT => {
+                parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::ADD_LIQUIDITY_EVENT => {
+                parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::REMOVE_LIQUIDITY_EVENT => {
+                parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::BOOTSTRAP_LIQUIDITY_EVENT => {
+                parse_bootstrap_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::POOL_CREATED_EVENT => {
+                parse_pool_created_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::SET_POOL_FEES_EVENT => {
+                parse_set_pool_fees_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析 Swap 事件
@@ -86,6 +131,7 @@ fn parse_swap_event(
     offset += 8;
 
     let host_fee = read_u64_le(data, offset)?;
+    let total_fee = trade_fee.saturating_add(admin_fee).saturating_add(host_fee);
 
     // 使用默认的程序 ID，实际应该从上下文获取
     let metadata = create_metadata_default(signature, slot, tx_index, block_time);
@@ -97,6 +143,10 @@ fn parse_swap_event(
         trade_fee,
         admin_fee,
         host_fee,
+        pool: Default::default(),
+        // 日志不带池子地址和费率配置，由同交易的 SetPoolFees 或调用方按池子配置判断
+        fee_mode: MeteoraFeeMode::Unknown,
+        effective_fee_bps: effective_fee_bps(in_amount, total_fee),
     }))
 }
 
@@ -275,4 +325,43 @@ fn parse_text_log(
 ) -> Option<DexEvent> {
     // 目前暂不实现文本解析，主要依赖结构化解析
     None
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[test]
+    fn test_swap_fee_mode() {
+        let swap_log = |fees: [u64; 5]| {
+            let mut data = discriminators::SWAP_EVENT.to_vec();
+            for value in fees {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            let log = format!("Program data: {}", general_purpose::STANDARD.encode(&data));
+            match parse_log(&log, Signature::default(), 1, 0, None, 0) {
+                Some(DexEvent::MeteoraPoolsSwap(e)) => e,
+                other => panic!("{:?}", other),
+            }
+        };
+
+        // 合成数据（按 IDL 布局构造）：池子交易费 1%，其中 20% 为协议费、协议费的 20% 给 host
+        let mut e = swap_log([2_000_000_000, 39_600_000, 16_000_000, 3_200_000, 800_000]);
+        assert_eq!((e.fee_mode, e.effective_fee_bps), (MeteoraFeeMode::Unknown, 100));
+        e.apply_pool_fees(100, 10_000);
+        assert_eq!(e.fee_mode, MeteoraFeeMode::Base);
+        // 不计 host_fee 时只有 96 bps，会被误判
+        assert_eq!(classify_fee(e.in_amount, e.trade_fee + e.admin_fee, 100, 10_000), MeteoraFeeMode::Dynamic);
+
+        // 实收 1.37%，偏离池子配置的 1%
+        let mut e = swap_log([2_000_000_000, 39_450_000, 21_920_000, 5_480_000, 0]);
+        assert_eq!((e.trade_fee, e.admin_fee, e.host_fee), (21_920_000, 5_480_000, 0));
+        e.apply_pool_fees(100, 10_000);
+        assert_eq!((e.fee_mode, e.effective_fee_bps), (MeteoraFeeMode::Dynamic, 137));
+
+        // 小额成交的取整误差不误判；没有配置时无法判断
+        assert_eq!(classify_fee(101, 0, 25, 10_000), MeteoraFeeMode::Base);
+        assert_eq!(classify_fee(101, 1, 0, 0), MeteoraFeeMode::Unknown);
+        assert_eq!(effective_fee_bps(0, 0), 0);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! 解析 Meteora DAMM V2 程序的日志事件
 
-use solana_sdk::signature::Signature;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
 use crate::core::events::*;
 use super::utils::*;
 
@@ -20,6 +20,13 @@ pub mod discriminators {
     pub const CLAIM_REWARD_EVENT: [u8; 8] = [218, 86, 147, 200, 235, 188, 215, 231];
 }
 
+/// 事件布局（按 cp-amm IDL）
+pub mod layouts {
+    /// `EvtSwap`：pool(32) + trade_direction(1) + has_referral(1) + params(16) + swap_result(56)
+    /// + actual_amount_in(8) + current_timestamp(8)
+    pub const SWAP_LEN: usize = 122;
+}
+
 /// 主要的 Meteora DAMM V2 日志解析函数
 pub fn parse_log(log: &str, signature: Signature, slot: u64, tx_index: u64, block_time: Option<i64>, grpc_recv_us: i64) -> Option<DexEvent> {
     parse_structured_log(log, signature, slot, tx_index, block_time, grpc_recv_us)
@@ -34,51 +41,61 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::SWAP_EVENT => {
-            parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::ADD_LIQUIDITY_EVENT => {
-            parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::REMOVE_LIQUIDITY_EVENT => {
-            parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::INITIALIZE_POOL_EVENT => {
-            parse_initialize_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CREATE_POSITION_EVENT => {
-            parse_create_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CLOSE_POSITION_EVENT => {
-            parse_close_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CLAIM_POSITION_FEE_EVENT => {
-            parse_claim_position_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::INITIALIZE_REWARD_EVENT => {
-            parse_initialize_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::FUND_REWARD_EVENT => {
-            parse_fund_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CLAIM_REWARD_EVENT => {
-            parse_claim_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP_EVENT => {
+                parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::ADD_LIQUIDITY_EVENT => {
+                parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::REMOVE_LIQUIDITY_EVENT => {
+                parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::INITIALIZE_POOL_EVENT => {
+                parse_initialize_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_POSITION_EVENT => {
+                parse_create_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CLOSE_POSITION_EVENT => {
+                parse_close_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CLAIM_POSITION_FEE_EVENT => {
+                parse_claim_position_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::INITIALIZE_REWARD_EVENT => {
+                parse_initialize_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::FUND_REWARD_EVENT => {
+                parse_fund_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CLAIM_REWARD_EVENT => {
+                parse_claim_reward_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
-/// 解析 Swap 事件
+/// 解析 Swap 事件（IDL `EvtSwap`）
+///
+/// ```text
+/// pool: Pubkey, trade_direction: u8 (0 = A→B), has_referral: bool,
+/// params: { amount_in: u64, minimum_amount_out: u64 },
+/// swap_result: { output_amount: u64, next_sqrt_price: u128, lp_fee: u64, protocol_fee: u64, partner_fee: u64, referral_fee: u64 },
+/// actual_amount_in: u64, current_timestamp: u64
+/// ```
+///
+/// 事件只带交易后的 sqrt 价格，`pre_sqrt_price` 为 0；DAMM V2 没有 bin，bin 相关字段为默认值。
 fn parse_swap_event(
     data: &[u8],
     signature: Signature,
@@ -87,55 +104,63 @@ fn parse_swap_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
+    if data.len() < layouts::SWAP_LEN {
+        return None;
+    }
     let mut offset = 0;
 
-    let lb_pair = read_pubkey(data, offset)?;
+    let pool = read_pubkey(data, offset)?;
     offset += 32;
 
-    let from = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let start_bin_id = read_i32_le(data, offset)?;
-    offset += 4;
+    let trade_direction = read_u8(data, offset)?;
+    offset += 1;
 
-    let end_bin_id = read_i32_le(data, offset)?;
-    offset += 4;
+    let _has_referral = read_bool(data, offset)?;
+    offset += 1;
 
-    let amount_in = read_u64_le(data, offset)?;
+    // params.amount_in 为指令参数，实际输入取 actual_amount_in
+    offset += 8;
+    let _minimum_amount_out = read_u64_le(data, offset)?;
     offset += 8;
 
-    let amount_out = read_u64_le(data, offset)?;
+    let output_amount = read_u64_le(data, offset)?;
     offset += 8;
 
-    let swap_for_y = read_bool(data, offset)?;
-    offset += 1;
+    let next_sqrt_price = read_u128_le(data, offset)?;
+    offset += 16;
 
-    let fee = read_u64_le(data, offset)?;
+    let lp_fee = read_u64_le(data, offset)?;
     offset += 8;
 
     let protocol_fee = read_u64_le(data, offset)?;
     offset += 8;
 
-    let fee_bps = read_u128_le(data, offset)?;
-    offset += 16;
+    let partner_fee = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let referral_fee = read_u64_le(data, offset)?;
+    offset += 8;
 
-    let host_fee = read_u64_le(data, offset)?;
+    let actual_amount_in = read_u64_le(data, offset)?;
 
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, lb_pair, grpc_recv_us);
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool, grpc_recv_us);
 
     Some(DexEvent::MeteoraDammV2Swap(MeteoraDammV2SwapEvent {
         metadata,
-        lb_pair,
-        from,
-        start_bin_id,
-        end_bin_id,
-        amount_in,
-        amount_out,
-        swap_for_y,
-        fee,
+        lb_pair: pool,
+        from: Pubkey::default(), // 从指令中获取
+        start_bin_id: 0,
+        end_bin_id: 0,
+        amount_in: actual_amount_in,
+        amount_out: output_amount,
+        swap_for_y: trade_direction == 0,
+        fee: lp_fee,
         protocol_fee,
-        fee_bps,
-        host_fee,
+        fee_bps: 0,
+        host_fee: referral_fee,
+        partner_fee,
+        pre_sqrt_price: 0,
+        post_sqrt_price: next_sqrt_price,
     }))
 }
 
@@ -460,4 +485,65 @@ fn parse_text_log(
 ) -> Option<DexEvent> {
     // 目前暂不实现文本解析，主要依赖结构化解析
     None
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_swap_fee_and_prices() {
+        // 合成数据（按 cp-amm IDL `EvtSwap` 布局构造，非主网抓取）：A 换 B，实际输入 1_000_000，价格变为 1.005
+        let pool = Pubkey::new_unique();
+        let next_sqrt_price = (1u128 << 64) + (1u128 << 64) / 200;
+        let mut data = discriminators::SWAP_EVENT.to_vec();
+        data.extend_from_slice(pool.as_ref());
+        data.push(0);
+        data.push(1);
+        for v in [1_000_000u64, 980_000, 990_000] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(&next_sqrt_price.to_le_bytes());
+        for v in [2_500u64, 500, 40, 100, 1_000_000, 1_700_000_000] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        assert_eq!(data.len() - 8, layouts::SWAP_LEN);
+
+        let log = |data: &[u8]| format!("Program data: {}", general_purpose::STANDARD.encode(data));
+        let Some(DexEvent::MeteoraDammV2Swap(e)) = parse_log(&log(&data), Signature::default(), 1, 0, None, 0) else {
+            panic!("swap log")
+        };
+        assert_eq!((e.lb_pair, e.amount_in, e.amount_out, e.swap_for_y), (pool, 1_000_000, 990_000, true));
+        assert_eq!((e.fee, e.protocol_fee, e.partner_fee, e.host_fee), (2_500, 500, 40, 100));
+        assert_eq!((e.pre_sqrt_price, e.post_sqrt_price), (0, next_sqrt_price));
+        // 事件不含交易前价格
+        assert_eq!(e.price_impact_bps(), None);
+        let impact = MeteoraDammV2SwapEvent { pre_sqrt_price: 1u128 << 64, ..e.clone() };
+        assert_eq!(impact.price_impact_bps(), Some(100));
+
+        // 指令优先合并时手续费与价格字段仍来自日志
+        let accounts: Vec<Pubkey> = (0..8).map(|i| if i == 0 { pool } else { Pubkey::new_unique() }).collect();
+        let ix = [&crate::instr::meteora_damm::discriminators::SWAP[..], &1_000_000u64.to_le_bytes(), &980_000u64.to_le_bytes()].concat();
+        let instr_event = crate::instr::meteora_damm::parse_instruction(&ix, &accounts, Signature::default(), 1, 0, None).unwrap();
+        let merged = crate::core::merger::merge_instruction_and_log_events_with_mode(
+            vec![instr_event],
+            vec![DexEvent::MeteoraDammV2Swap(e)],
+            crate::core::merger::MergeMode::PreferInstruction,
+        );
+        let [DexEvent::MeteoraDammV2Swap(m)] = &merged[..] else { panic!("{:?}", merged) };
+        assert_eq!((m.amount_out, m.from), (980_000, accounts[7]));
+        assert_eq!((m.fee, m.partner_fee, m.post_sqrt_price), (2_500, 40, next_sqrt_price));
+
+        // 长度不足的数据不解析
+        assert!(parse_log(&log(&data[..data.len() - 1]), Signature::default(), 1, 0, None, 0).is_none());
+    }
+
+    #[test]
+    fn test_log_type_follows_program_id() {
+        use crate::grpc::program_ids::{METEORA_DAMM_V2_PROGRAM_ID, METEORA_DLMM_PROGRAM_ID};
+        use crate::logs::optimized_matcher::{log_type_for_program, LogType};
+        assert_eq!(log_type_for_program(METEORA_DAMM_V2_PROGRAM_ID), LogType::MeteoraDamm);
+        assert_eq!(log_type_for_program(METEORA_DLMM_PROGRAM_ID), LogType::MeteoraDlmm);
+    }
+}
@@ -19,6 +19,13 @@ pub mod discriminators {
     pub const CLAIM_FEE_EVENT: [u8; 8] = [152, 70, 208, 111, 104, 91, 44, 1];
 }
 
+/// 事件布局（按 lb_clmm IDL）
+pub mod layouts {
+    /// `Swap`：lb_pair / from / start_bin_id / end_bin_id / amount_in / amount_out / swap_for_y / fee
+    /// / protocol_fee / fee_bps / host_fee；bin step 是池子属性，不在事件中
+    pub const SWAP_LEN: usize = 129;
+}
+
 /// 主要的 Meteora DLMM 日志解析函数
 pub fn parse_log(log: &str, signature: Signature, slot: u64, tx_index: u64, block_time: Option<i64>, grpc_recv_us: i64) -> Option<DexEvent> {
     parse_structured_log(log, signature, slot, tx_index, block_time, grpc_recv_us)
@@ -33,38 +40,39 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::SWAP_EVENT => {
-            parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::ADD_LIQUIDITY_EVENT => {
-            parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::REMOVE_LIQUIDITY_EVENT => {
-            parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::INITIALIZE_POOL_EVENT => {
-            parse_initialize_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CREATE_POSITION_EVENT => {
-            parse_create_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CLOSE_POSITION_EVENT => {
-            parse_close_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CLAIM_FEE_EVENT => {
-            parse_claim_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP_EVENT => {
+                parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::ADD_LIQUIDITY_EVENT => {
+                parse_add_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::REMOVE_LIQUIDITY_EVENT => {
+                parse_remove_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::INITIALIZE_POOL_EVENT => {
+                parse_initialize_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_POSITION_EVENT => {
+                parse_create_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CLOSE_POSITION_EVENT => {
+                parse_close_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CLAIM_FEE_EVENT => {
+                parse_claim_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析交换事件
@@ -475,4 +483,35 @@ fn parse_initialize_pool_from_text(
         active_bin_id: extract_number_from_text(log, "bin_id").unwrap_or(0) as i32,
         bin_step: extract_number_from_text(log, "bin_step").unwrap_or(1) as u16,
     }))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[test]
+    fn test_swap_bins_and_fees() {
+        // 合成数据（按 IDL `Swap` 布局构造，非主网抓取）：活跃 bin 从 100 移到 98
+        let mut data = discriminators::SWAP_EVENT.to_vec();
+        data.extend_from_slice(&[7u8; 64]);
+        for bin in [100i32, 98] {
+            data.extend_from_slice(&bin.to_le_bytes());
+        }
+        for v in [5_000u64, 4_900] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.push(1);
+        for fee in [15u64, 3] {
+            data.extend_from_slice(&fee.to_le_bytes());
+        }
+        data.extend_from_slice(&30u128.to_le_bytes());
+        data.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(data.len() - 8, layouts::SWAP_LEN);
+
+        let log = format!("Program data: {}", general_purpose::STANDARD.encode(&data));
+        let Some(DexEvent::MeteoraDlmmSwap(e)) = parse_log(&log, Signature::default(), 1, 0, None, 0) else { panic!("swap log") };
+        assert_eq!((e.start_bin_id, e.end_bin_id, e.amount_in, e.amount_out, e.swap_for_y), (100, 98, 5_000, 4_900, true));
+        assert_eq!((e.fee, e.protocol_fee, e.fee_bps, e.host_fee), (15, 3, 30, 2));
+        assert_eq!(e.metadata.protocol_version, None);
+    }
+}
@@ -60,10 +60,6 @@ pub fn parse_log_unified(
     slot: u64,
     block_time: Option<i64>,
 ) -> Option<DexEvent> {
-    let grpc_recv_us = unsafe {
-        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
-        libc::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
-        (ts.tv_sec as i64) * 1_000_000 + (ts.tv_nsec as i64) / 1_000
-    };
+    let grpc_recv_us = crate::common::time::now_micros();
     optimized_matcher::parse_log_optimized(log, signature, slot, 0, block_time, grpc_recv_us, None, false)
 }
\ No newline at end of file
@@ -3,25 +3,31 @@
 //! 使用预计算的字符串常量和优化的匹配策略
 
 use crate::core::events::DexEvent;
-use crate::grpc::types::{EventType, EventTypeFilter};
+use crate::grpc::types::{EnabledProtocols, EventType, EventTypeFilter, Protocol};
 use solana_sdk::signature::Signature;
 use memchr::memmem;
 use once_cell::sync::Lazy;
 use super::perf_hints::{likely, unlikely};
+use super::zero_copy_parser;
+use crate::grpc::program_ids as ids;
 
 /// SIMD 优化的字符串查找器 - 预编译一次，重复使用
-static PUMPFUN_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P"));
-static RAYDIUM_AMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"));
-static RAYDIUM_CLMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"CAMMCzo5YL8w4VFF8KVHrK22GGUQpMdRBFSzKNT3t4ivN6"));
-static RAYDIUM_CPMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"CPMDWBwJDtYax9qKcQP3CtKz7tHjJsN3H8hGrYVD9mZD"));
-static BONK_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Bxby5A7E8xPDGGc3FyJw7m5eK5aqNVLU83H2zLTQDH1b"));
+static PUMPFUN_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::PUMPFUN_PROGRAM_ID.as_bytes()));
+static RAYDIUM_AMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::RAYDIUM_AMM_V4_PROGRAM_ID.as_bytes()));
+static RAYDIUM_CLMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::RAYDIUM_CLMM_PROGRAM_ID.as_bytes()));
+static RAYDIUM_CPMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::RAYDIUM_CPMM_PROGRAM_ID.as_bytes()));
+static BONK_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::BONK_PROGRAM_ID.as_bytes()));
 static PROGRAM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Program"));
 static PROGRAM_DATA_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Program data: "));
+static RAY_LOG_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"ray_log: "));
 static PUMPFUN_CREATE_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Program data: GB7IKAUcB3c"));
+// Launchpad 的 Program data 行不含程序ID，按事件 discriminator 的 base64 前缀识别（前 10 个字符只取决于 discriminator）
+static BONK_TRADE_EVENT_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Program data: vdt/007mYe"));
+static BONK_POOL_CREATE_EVENT_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"Program data: l9fiCXahc6"));
 static WHIRL_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"whirL"));
-static METEORA_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"meteora"));
-static METEORA_LB_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"LB"));
-static METEORA_DLMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"DLMM"));
+static METEORA_POOLS_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::METEORA_POOLS_PROGRAM_ID.as_bytes()));
+static METEORA_DAMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::METEORA_DAMM_V2_PROGRAM_ID.as_bytes()));
+static METEORA_DLMM_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(ids::METEORA_DLMM_PROGRAM_ID.as_bytes()));
 static PUMPSWAP_LOWER_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"pumpswap"));
 static PUMPSWAP_UPPER_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder::new(b"PumpSwap"));
 
@@ -29,21 +35,26 @@ static PUMPSWAP_UPPER_FINDER: Lazy<memmem::Finder> = Lazy::new(|| memmem::Finder
 pub mod program_id_strings {
     pub const PUMPFUN_INVOKE: &str = "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke";
     pub const PUMPFUN_SUCCESS: &str = "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success";
-    pub const PUMPFUN_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+    pub const PUMPFUN_ID: &str = crate::grpc::program_ids::PUMPFUN_PROGRAM_ID;
 
-    pub const BONK_INVOKE: &str = "Program Bxby5A7E8xPDGGc3FyJw7m5eK5aqNVLU83H2zLTQDH1b invoke";
-    pub const BONK_SUCCESS: &str = "Program Bxby5A7E8xPDGGc3FyJw7m5eK5aqNVLU83H2zLTQDH1b success";
-    pub const BONK_ID: &str = "Bxby5A7E8xPDGGc3FyJw7m5eK5aqNVLU83H2zLTQDH1b";
+    pub const BONK_INVOKE: &str = "Program LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj invoke";
+    pub const BONK_SUCCESS: &str = "Program LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj success";
+    pub const BONK_ID: &str = crate::grpc::program_ids::BONK_PROGRAM_ID;
 
-    pub const RAYDIUM_CLMM_INVOKE: &str = "Program CAMMCzo5YL8w4VFF8KVHrK22GGUQpMdRBFSzKNT3t4ivN6 invoke";
-    pub const RAYDIUM_CLMM_SUCCESS: &str = "Program CAMMCzo5YL8w4VFF8KVHrK22GGUQpMdRBFSzKNT3t4ivN6 success";
-    pub const RAYDIUM_CLMM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUQpMdRBFSzKNT3t4ivN6";
+    pub const RAYDIUM_CLMM_INVOKE: &str = "Program CAMMCzo5YL8w4VFF8KVHrK22GGUQpMDdHFWF5LCATdCR invoke";
+    pub const RAYDIUM_CLMM_SUCCESS: &str = "Program CAMMCzo5YL8w4VFF8KVHrK22GGUQpMDdHFWF5LCATdCR success";
+    pub const RAYDIUM_CLMM_ID: &str = crate::grpc::program_ids::RAYDIUM_CLMM_PROGRAM_ID;
 
-    pub const RAYDIUM_CPMM_INVOKE: &str = "Program CPMDWBwJDtYax9qKcQP3CtKz7tHjJsN3H8hGrYVD9mZD invoke";
-    pub const RAYDIUM_CPMM_SUCCESS: &str = "Program CPMDWBwJDtYax9qKcQP3CtKz7tHjJsN3H8hGrYVD9mZD success";
-    pub const RAYDIUM_CPMM_ID: &str = "CPMDWBwJDtYax9qKcQP3CtKz7tHjJsN3H8hGrYVD9mZD";
+    pub const RAYDIUM_CPMM_INVOKE: &str = "Program CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C invoke";
+    pub const RAYDIUM_CPMM_SUCCESS: &str = "Program CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C success";
+    pub const RAYDIUM_CPMM_ID: &str = crate::grpc::program_ids::RAYDIUM_CPMM_PROGRAM_ID;
 
-    pub const RAYDIUM_AMM_V4_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+    pub const RAYDIUM_AMM_V4_ID: &str = crate::grpc::program_ids::RAYDIUM_AMM_V4_PROGRAM_ID;
+
+    pub const ORCA_WHIRLPOOL_ID: &str = crate::grpc::program_ids::ORCA_WHIRLPOOL_PROGRAM_ID;
+    pub const METEORA_POOLS_ID: &str = crate::grpc::program_ids::METEORA_POOLS_PROGRAM_ID;
+    pub const METEORA_DAMM_V2_ID: &str = crate::grpc::program_ids::METEORA_DAMM_V2_PROGRAM_ID;
+    pub const METEORA_DLMM_ID: &str = crate::grpc::program_ids::METEORA_DLMM_PROGRAM_ID;
 
     // 常用的日志模式
     pub const PROGRAM_DATA: &str = "Program data: ";
@@ -69,6 +80,32 @@ pub enum LogType {
     Unknown,
 }
 
+impl LogType {
+    /// 日志类型所属协议
+    #[inline(always)]
+    pub fn protocol(self) -> Option<Protocol> {
+        match self {
+            LogType::PumpFun => Some(Protocol::PumpFun),
+            LogType::RaydiumLaunchpad => Some(Protocol::Bonk),
+            LogType::PumpAmm => Some(Protocol::PumpSwap),
+            LogType::RaydiumClmm => Some(Protocol::RaydiumClmm),
+            LogType::RaydiumCpmm => Some(Protocol::RaydiumCpmm),
+            LogType::RaydiumAmm => Some(Protocol::RaydiumAmmV4),
+            LogType::OrcaWhirlpool => Some(Protocol::OrcaWhirlpool),
+            LogType::MeteoraAmm => Some(Protocol::MeteoraPools),
+            LogType::MeteoraDamm => Some(Protocol::MeteoraDammV2),
+            LogType::MeteoraDlmm => Some(Protocol::MeteoraDlmm),
+            LogType::Unknown => None,
+        }
+    }
+
+    /// 所属协议是否启用（Unknown 视为未启用）
+    #[inline(always)]
+    pub fn is_enabled(self, enabled: EnabledProtocols) -> bool {
+        self.protocol().is_some_and(|p| enabled.contains(p))
+    }
+}
+
 /// SIMD 优化的日志类型检测器 - 激进早期退出
 #[inline(always)]
 pub fn detect_log_type(log: &str) -> LogType {
@@ -83,7 +120,11 @@ pub fn detect_log_type(log: &str) -> LogType {
     let has_program_data = PROGRAM_DATA_FINDER.find(log_bytes).is_some();
 
     // 只有 "Program data:" 日志才可能是交易事件
+    // 例外：Raydium AMM V4 通过 "Program log: ray_log: <base64>" 输出事件，且日志行不含程序ID
     if unlikely(!has_program_data) {
+        if RAY_LOG_FINDER.find(log_bytes).is_some() {
+            return LogType::RaydiumAmm;
+        }
         return LogType::Unknown;
     }
 
@@ -104,7 +145,10 @@ pub fn detect_log_type(log: &str) -> LogType {
     }
 
     // Raydium Launchpad (Bonk)
-    if BONK_FINDER.find(log_bytes).is_some() {
+    if BONK_FINDER.find(log_bytes).is_some()
+        || BONK_TRADE_EVENT_FINDER.find(log_bytes).is_some()
+        || BONK_POOL_CREATE_EVENT_FINDER.find(log_bytes).is_some()
+    {
         return LogType::RaydiumLaunchpad;
     }
 
@@ -113,16 +157,15 @@ pub fn detect_log_type(log: &str) -> LogType {
         return LogType::OrcaWhirlpool;
     }
 
-    // Meteora - SIMD 优化
-    if let Some(pos) = METEORA_FINDER.find(log_bytes) {
-        let rest = &log_bytes[pos..];
-        if METEORA_LB_FINDER.find(rest).is_some() {
-            return LogType::MeteoraDamm;
-        } else if METEORA_DLMM_FINDER.find(rest).is_some() {
-            return LogType::MeteoraDlmm;
-        } else {
-            return LogType::MeteoraAmm;
-        }
+    // Meteora：按各自的程序 ID 区分
+    if METEORA_DAMM_FINDER.find(log_bytes).is_some() {
+        return LogType::MeteoraDamm;
+    }
+    if METEORA_DLMM_FINDER.find(log_bytes).is_some() {
+        return LogType::MeteoraDlmm;
+    }
+    if METEORA_POOLS_FINDER.find(log_bytes).is_some() {
+        return LogType::MeteoraAmm;
     }
 
     // Pump AMM
@@ -158,8 +201,31 @@ pub fn parse_log_optimized(
     event_type_filter: Option<&EventTypeFilter>,
     is_created_buy: bool,
 ) -> Option<DexEvent> {
-    // 快速类型检测
-    let log_type = detect_log_type(log);
+    parse_log_with_type(
+        detect_log_type(log), log, signature, slot, tx_index, block_time, grpc_recv_us,
+        event_type_filter, EnabledProtocols::all(), is_created_buy,
+    )
+}
+
+/// 按已知日志类型解析（跳过类型检测），过滤逻辑与 [`parse_log_optimized`] 相同
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn parse_log_with_type(
+    log_type: LogType,
+    log: &str,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+    event_type_filter: Option<&EventTypeFilter>,
+    enabled: EnabledProtocols,
+    is_created_buy: bool,
+) -> Option<DexEvent> {
+    // 未启用的协议：不做任何解码
+    if unlikely(!log_type.is_enabled(enabled)) {
+        return None;
+    }
 
     // 提前过滤和解析
     if let Some(filter) = event_type_filter {
@@ -180,7 +246,10 @@ pub fn parse_log_optimized(
             let should_parse = match log_type {
                 LogType::PumpFun => include_only.iter().any(|t| matches!(t,
                     EventType::PumpFunTrade | EventType::PumpFunCreate |
-                    EventType::PumpFunComplete | EventType::PumpFunMigrate)),
+                    EventType::PumpFunComplete | EventType::PumpFunMigrate |
+                    EventType::PumpFunSetParams)),
+                LogType::RaydiumLaunchpad => include_only.iter().any(|t| matches!(t,
+                    EventType::BonkTrade | EventType::BonkPoolCreate)),
                 LogType::RaydiumAmm => include_only.iter().any(|t| matches!(t,
                     EventType::RaydiumAmmV4Swap | EventType::RaydiumAmmV4Deposit |
                     EventType::RaydiumAmmV4Withdraw | EventType::RaydiumAmmV4Initialize2 |
@@ -205,10 +274,14 @@ pub fn parse_log_optimized(
     // 根据类型直接调用相应的解析器，传入grpc_recv_us
     let event = match log_type {
         LogType::PumpFun => crate::logs::parse_pumpfun_log(log, signature, slot, tx_index, block_time, grpc_recv_us, is_created_buy),
-        LogType::RaydiumLaunchpad => crate::logs::parse_raydium_launchpad_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
-        LogType::PumpAmm => crate::logs::parse_pump_amm_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
+        // 高频交易事件优先走零拷贝解析，其余事件回退到通用解析器
+        LogType::RaydiumLaunchpad => zero_copy_parser::parse_bonk_trade(log, signature, slot, tx_index, block_time, grpc_recv_us)
+            .or_else(|| crate::logs::parse_raydium_launchpad_log(log, signature, slot, tx_index, block_time, grpc_recv_us)),
+        LogType::PumpAmm => zero_copy_parser::parse_pump_amm_trade(log, signature, slot, tx_index, block_time, grpc_recv_us)
+            .or_else(|| crate::logs::parse_pump_amm_log(log, signature, slot, tx_index, block_time, grpc_recv_us)),
         LogType::RaydiumClmm => crate::logs::parse_raydium_clmm_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
-        LogType::RaydiumCpmm => crate::logs::parse_raydium_cpmm_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
+        LogType::RaydiumCpmm => zero_copy_parser::parse_raydium_cpmm_swap(log, signature, slot, tx_index, block_time, grpc_recv_us)
+            .or_else(|| crate::logs::parse_raydium_cpmm_log(log, signature, slot, tx_index, block_time, grpc_recv_us)),
         LogType::RaydiumAmm => crate::logs::parse_raydium_amm_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
         LogType::OrcaWhirlpool => crate::logs::parse_orca_whirlpool_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
         LogType::MeteoraAmm => crate::logs::parse_meteora_amm_log(log, signature, slot, tx_index, block_time, grpc_recv_us),
@@ -220,15 +293,9 @@ pub fn parse_log_optimized(
     // 应用精确的事件类型过滤
     if let Some(event) = event {
         if let Some(filter) = event_type_filter {
-            let event_type = match &event {
-                DexEvent::PumpFunTrade(_) => EventType::PumpFunTrade,
-                DexEvent::PumpFunCreate(_) => EventType::PumpFunCreate,
-                DexEvent::PumpFunComplete(_) => EventType::PumpFunComplete,
-                DexEvent::PumpFunMigrate(_) => EventType::PumpFunMigrate,
-                DexEvent::RaydiumAmmV4Swap(_) => EventType::RaydiumAmmV4Swap,
-                DexEvent::RaydiumClmmSwap(_) => EventType::RaydiumClmmSwap,
-                DexEvent::RaydiumCpmmSwap(_) => EventType::RaydiumCpmmSwap,
-                _ => return Some(event),
+            // 没有对应 EventType 的事件（如 DLMM）不参与过滤
+            let Some(event_type) = EventType::from_event(&event) else {
+                return Some(event);
             };
 
             if likely(filter.should_include(event_type)) {
@@ -243,6 +310,99 @@ pub fn parse_log_optimized(
     }
 }
 
+/// 按程序 ID 确定日志类型（用于 invoke 栈归属，`Program data:` 行本身不含程序 ID）
+pub fn log_type_for_program(program_id: &str) -> LogType {
+    match program_id {
+        program_id_strings::PUMPFUN_ID => LogType::PumpFun,
+        program_id_strings::BONK_ID => LogType::RaydiumLaunchpad,
+        program_id_strings::RAYDIUM_AMM_V4_ID => LogType::RaydiumAmm,
+        program_id_strings::RAYDIUM_CLMM_ID => LogType::RaydiumClmm,
+        program_id_strings::RAYDIUM_CPMM_ID => LogType::RaydiumCpmm,
+        ids::PUMPSWAP_PROGRAM_ID => LogType::PumpAmm,
+        program_id_strings::ORCA_WHIRLPOOL_ID => LogType::OrcaWhirlpool,
+        program_id_strings::METEORA_POOLS_ID => LogType::MeteoraAmm,
+        program_id_strings::METEORA_DAMM_V2_ID => LogType::MeteoraDamm,
+        program_id_strings::METEORA_DLMM_ID => LogType::MeteoraDlmm,
+        _ => LogType::Unknown,
+    }
+}
+
+/// 解析 `Program <id> invoke [n]`，返回被调用的程序 ID
+#[inline]
+pub fn invoked_program(log: &str) -> Option<&str> {
+    let rest = log.strip_prefix("Program ")?;
+    let (program_id, tail) = rest.split_once(' ')?;
+    tail.starts_with("invoke [").then_some(program_id)
+}
+
+/// 是否为 `Program <id> success` / `Program <id> failed...`（调用结束）
+#[inline]
+pub fn is_program_exit(log: &str) -> bool {
+    log.strip_prefix("Program ")
+        .and_then(|rest| rest.split_once(' '))
+        .is_some_and(|(_, tail)| tail == "success" || tail.starts_with("failed"))
+}
+
+/// invoke 栈：跟踪当前执行的程序及其指令位置 `(outer_index, inner_index)`
+///
+/// 外层指令按 `invoke [1]` 计数；内层指令（CPI）在所属外层指令内按出现顺序计数，
+/// 与交易 meta 中 `inner_instructions` 的顺序一致。
+#[derive(Debug, Default)]
+pub struct InvokeTracker<'a> {
+    stack: Vec<(&'a str, Option<u32>)>,
+    outer_count: u32,
+    inner_count: u32,
+}
+
+impl<'a> InvokeTracker<'a> {
+    pub fn new() -> Self {
+        Self { stack: Vec::with_capacity(4), outer_count: 0, inner_count: 0 }
+    }
+
+    /// 处理一行日志；调用开始/结束行返回 true（这些行不含事件数据）
+    #[inline]
+    pub fn observe(&mut self, log: &'a str) -> bool {
+        if let Some(program_id) = invoked_program(log) {
+            let inner = if self.stack.is_empty() {
+                self.outer_count += 1;
+                self.inner_count = 0;
+                None
+            } else {
+                self.inner_count += 1;
+                Some(self.inner_count - 1)
+            };
+            self.stack.push((program_id, inner));
+            return true;
+        }
+        if is_program_exit(log) {
+            self.stack.pop();
+            return true;
+        }
+        false
+    }
+
+    /// 当前执行的程序
+    #[inline]
+    pub fn current_program(&self) -> Option<&'a str> {
+        self.stack.last().map(|&(program_id, _)| program_id)
+    }
+
+    /// 当前指令位置
+    #[inline]
+    pub fn position(&self) -> (u32, Option<u32>) {
+        let inner = self.stack.last().and_then(|&(_, inner)| inner);
+        (self.outer_count.saturating_sub(1), inner)
+    }
+
+    /// 把当前指令位置写入事件元数据
+    #[inline]
+    pub fn stamp(&self, event: &mut DexEvent) {
+        if let Some(metadata) = event.metadata_mut() {
+            (metadata.outer_index, metadata.inner_index) = self.position();
+        }
+    }
+}
+
 /// SIMD 优化的 PumpFun Create 事件检测（扫描所有日志）
 #[inline]
 pub fn detect_pumpfun_create(logs: &[String]) -> bool {
@@ -28,30 +28,31 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::TRADED_EVENT => {
-            parse_traded_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::LIQUIDITY_INCREASED_EVENT => {
-            parse_liquidity_increased_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::LIQUIDITY_DECREASED_EVENT => {
-            parse_liquidity_decreased_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::POOL_INITIALIZED_EVENT => {
-            parse_pool_initialized_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::TRADED_EVENT => {
+                parse_traded_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::LIQUIDITY_INCREASED_EVENT => {
+                parse_liquidity_increased_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::LIQUIDITY_DECREASED_EVENT => {
+                parse_liquidity_decreased_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::POOL_INITIALIZED_EVENT => {
+                parse_pool_initialized_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析 Traded 事件
@@ -8,13 +8,86 @@ use super::utils::*;
 
 /// PumpSwap discriminator 常量
 pub mod discriminators {
-    pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
-    pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+    /// BuyEvent
+    pub const BUY: [u8; 8] = [103, 244, 82, 31, 44, 245, 119, 119];
+    /// SellEvent
+    pub const SELL: [u8; 8] = [62, 47, 55, 10, 165, 3, 220, 42];
     pub const CREATE_POOL: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
+    pub const DEPOSIT_EVENT: [u8; 8] = [120, 248, 61, 83, 31, 142, 107, 144];
+    pub const WITHDRAW_EVENT: [u8; 8] = [22, 9, 133, 26, 160, 44, 71, 192];
+}
+
+/// PumpSwap Buy / Sell 事件布局版本（记录在 `EventMetadata::protocol_version`）
+///
+/// BuyEvent 与 SellEvent 字段顺序相同，只是数量的方向不同：
+/// timestamp, base_amount, quote 限额, 用户两侧余额, 池子两侧储备（@40 / @48）, quote_amount（@56）,
+/// lp_fee_basis_points, lp_fee（@72）, protocol_fee_basis_points, protocol_fee（@88）, 两个含费 quote 数量,
+/// pool（@112）, user（@144）, 用户两个代币账户, 协议费接收方及其代币账户
+pub mod layouts {
+    /// 初版：到 protocol_fee_recipient_token_account 为止
+    pub const TRADE_V1: u16 = 1;
+    /// 追加 coin_creator、coin_creator_fee_basis_points、coin_creator_fee
+    pub const TRADE_V2: u16 = 2;
+    /// 再追加交易量统计（track_volume、total_unclaimed_tokens 等），不解析
+    pub const TRADE_V3: u16 = 3;
+
+    pub const TRADE_V1_LEN: usize = 304;
+    pub const TRADE_V2_LEN: usize = TRADE_V1_LEN + 48;
+    pub const TRADE_V3_LEN: usize = TRADE_V2_LEN + 33;
+
+    /// 按数据长度判断 Buy / Sell 事件布局
+    #[inline(always)]
+    pub fn trade_version(data_len: usize) -> u16 {
+        match data_len {
+            len if len >= TRADE_V3_LEN => TRADE_V3,
+            len if len >= TRADE_V2_LEN => TRADE_V2,
+            _ => TRADE_V1,
+        }
+    }
+}
+
+/// Buy / Sell 事件中输出的字段
+pub(crate) struct TradeFields {
+    /// 买入为 base_amount_out，卖出为 base_amount_in
+    pub base_amount: u64,
+    /// 买入为 quote_amount_in，卖出为 quote_amount_out
+    pub quote_amount: u64,
+    pub pool_base_token_reserves: u64,
+    pub pool_quote_token_reserves: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub coin_creator: Pubkey,
+    pub coin_creator_fee: Option<u64>,
+}
+
+/// 按 IDL 布局读取 Buy / Sell 事件（结构化解析与零拷贝解析共用），返回 `(布局版本, 字段)`；
+/// 不足初版长度时返回 None
+#[inline(always)]
+pub(crate) fn read_trade(data: &[u8]) -> Option<(u16, TradeFields)> {
+    if data.len() < layouts::TRADE_V1_LEN {
+        return None;
+    }
+    let version = layouts::trade_version(data.len());
+    let v2 = version >= layouts::TRADE_V2;
+    let fields = TradeFields {
+        base_amount: read_u64_le(data, 8)?,
+        quote_amount: read_u64_le(data, 56)?,
+        pool_base_token_reserves: read_u64_le(data, 40)?,
+        pool_quote_token_reserves: read_u64_le(data, 48)?,
+        lp_fee: read_u64_le(data, 72)?,
+        protocol_fee: read_u64_le(data, 88)?,
+        pool: read_pubkey(data, 112)?,
+        user: read_pubkey(data, 144)?,
+        coin_creator: if v2 { read_pubkey(data, layouts::TRADE_V1_LEN)? } else { Pubkey::default() },
+        coin_creator_fee: if v2 { read_u64_le(data, layouts::TRADE_V1_LEN + 40) } else { None },
+    };
+    Some((version, fields))
 }
 
 /// PumpSwap 程序 ID
-pub const PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::PUMPSWAP_PROGRAM_ID;
 
 /// 检查日志是否来自 Pump AMM 程序
 pub fn is_pump_amm_log(log: &str) -> bool {
@@ -37,29 +110,36 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::BUY => {
-            parse_buy_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::SELL => {
-            parse_sell_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CREATE_POOL => {
-            parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::BUY => {
+                parse_buy_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::SELL => {
+                parse_sell_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_POOL => {
+                parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::DEPOSIT_EVENT => {
+                parse_deposit_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::WITHDRAW_EVENT => {
+                parse_withdraw_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
-/// 解析买入事件
+/// 解析买入事件（BuyEvent 不含 mint，由同一指令的账户补齐）
 fn parse_buy_event(
     data: &[u8],
     signature: Signature,
@@ -68,37 +148,29 @@ fn parse_buy_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let user = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let token_mint = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let input_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let output_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let pool_state = read_pubkey(data, offset)?;
-
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, token_mint, grpc_recv_us);
+    let (version, t) = read_trade(data)?;
+    let mut metadata = create_metadata_simple(signature, slot, tx_index, block_time, t.pool, grpc_recv_us);
+    metadata.protocol_version = Some(version);
 
     Some(DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
         metadata,
-        pool_id: pool_state,
-        user,
-        token_mint,
-        sol_amount: input_amount,
-        token_amount: output_amount,
+        pool_id: t.pool,
+        user: t.user,
+        token_mint: Pubkey::default(), // 从指令获取
+        sol_amount: t.quote_amount,
+        token_amount: t.base_amount,
         price: 0,
         slippage: 0,
+        pool_base_token_reserves: t.pool_base_token_reserves,
+        pool_quote_token_reserves: t.pool_quote_token_reserves,
+        lp_fee: t.lp_fee,
+        protocol_fee: t.protocol_fee,
+        coin_creator: t.coin_creator,
+        coin_creator_fee: t.coin_creator_fee,
     }))
 }
 
-/// 解析卖出事件
+/// 解析卖出事件（SellEvent 不含 mint，由同一指令的账户补齐）
 fn parse_sell_event(
     data: &[u8],
     signature: Signature,
@@ -107,33 +179,25 @@ fn parse_sell_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let user = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let token_mint = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let token_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let sol_amount = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let pool_state = read_pubkey(data, offset)?;
-
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, token_mint, grpc_recv_us);
+    let (version, t) = read_trade(data)?;
+    let mut metadata = create_metadata_simple(signature, slot, tx_index, block_time, t.pool, grpc_recv_us);
+    metadata.protocol_version = Some(version);
 
     Some(DexEvent::PumpSwapSell(PumpSwapSellEvent {
         metadata,
-        pool_id: pool_state,
-        user,
-        token_mint,
-        token_amount,
-        sol_amount,
+        pool_id: t.pool,
+        user: t.user,
+        token_mint: Pubkey::default(), // 从指令获取
+        token_amount: t.base_amount,
+        sol_amount: t.quote_amount,
         price: 0,
         slippage: 0,
+        pool_base_token_reserves: t.pool_base_token_reserves,
+        pool_quote_token_reserves: t.pool_quote_token_reserves,
+        lp_fee: t.lp_fee,
+        protocol_fee: t.protocol_fee,
+        coin_creator: t.coin_creator,
+        coin_creator_fee: t.coin_creator_fee,
     }))
 }
 
@@ -175,6 +239,88 @@ fn parse_create_pool_event(
     }))
 }
 
+/// 读取 Deposit/Withdraw 事件共同的布局：
+/// timestamp, lp 数量, 两个限额, 用户/池子储备, 两个成交数量, lp 供应量, 五个账户
+fn read_liquidity_event(data: &[u8]) -> Option<(i64, [u64; 10], [Pubkey; 5])> {
+    let timestamp = read_i64_le(data, 0)?;
+    let mut amounts = [0u64; 10];
+    for (i, amount) in amounts.iter_mut().enumerate() {
+        *amount = read_u64_le(data, 8 + i * 8)?;
+    }
+    let mut keys = [Pubkey::default(); 5];
+    for (i, key) in keys.iter_mut().enumerate() {
+        *key = read_pubkey(data, 88 + i * 32)?;
+    }
+    Some((timestamp, amounts, keys))
+}
+
+/// 解析添加流动性事件（DepositEvent）
+fn parse_deposit_event(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let (timestamp, a, [pool, user, user_base, user_quote, user_pool]) = read_liquidity_event(data)?;
+    // a[3], a[4] 为用户侧代币余额，不输出
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool, grpc_recv_us);
+
+    Some(DexEvent::PumpSwapDeposit(PumpSwapDepositEvent {
+        metadata,
+        timestamp,
+        pool,
+        user,
+        base_mint: Pubkey::default(), // 从指令获取
+        quote_mint: Pubkey::default(), // 从指令获取
+        lp_token_amount_out: a[0],
+        max_base_amount_in: a[1],
+        max_quote_amount_in: a[2],
+        pool_base_token_reserves: a[5],
+        pool_quote_token_reserves: a[6],
+        base_amount_in: a[7],
+        quote_amount_in: a[8],
+        lp_mint_supply: a[9],
+        user_base_token_account: user_base,
+        user_quote_token_account: user_quote,
+        user_pool_token_account: user_pool,
+    }))
+}
+
+/// 解析移除流动性事件（WithdrawEvent）
+fn parse_withdraw_event(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let (timestamp, a, [pool, user, user_base, user_quote, user_pool]) = read_liquidity_event(data)?;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool, grpc_recv_us);
+
+    Some(DexEvent::PumpSwapWithdraw(PumpSwapWithdrawEvent {
+        metadata,
+        timestamp,
+        pool,
+        user,
+        base_mint: Pubkey::default(), // 从指令获取
+        quote_mint: Pubkey::default(), // 从指令获取
+        lp_token_amount_in: a[0],
+        min_base_amount_out: a[1],
+        min_quote_amount_out: a[2],
+        pool_base_token_reserves: a[5],
+        pool_quote_token_reserves: a[6],
+        base_amount_out: a[7],
+        quote_amount_out: a[8],
+        lp_mint_supply: a[9],
+        user_base_token_account: user_base,
+        user_quote_token_account: user_quote,
+        user_pool_token_account: user_pool,
+    }))
+}
+
 /// 文本回退解析
 fn parse_text_log(
     log: &str,
@@ -223,6 +369,12 @@ fn parse_buy_from_text(
         token_amount: extract_number_from_text(log, "token").unwrap_or(950_000_000),
         price: 0,
         slippage: 0,
+        pool_base_token_reserves: 0,
+        pool_quote_token_reserves: 0,
+        lp_fee: 0,
+        protocol_fee: 0,
+        coin_creator: Pubkey::default(),
+        coin_creator_fee: None,
     }))
 }
 
@@ -248,6 +400,12 @@ fn parse_sell_from_text(
         sol_amount: extract_number_from_text(log, "sol").unwrap_or(900_000_000),
         price: 0,
         slippage: 0,
+        pool_base_token_reserves: 0,
+        pool_quote_token_reserves: 0,
+        lp_fee: 0,
+        protocol_fee: 0,
+        coin_creator: Pubkey::default(),
+        coin_creator_fee: None,
     }))
 }
 
@@ -273,4 +431,140 @@ fn parse_create_pool_from_text(
         initial_token_amount: extract_number_from_text(log, "token_reserve").unwrap_or(100_000_000_000),
         fee_rate: 0,
     }))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::merger::merge_instruction_and_log_events;
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[test]
+    fn test_deposit_log_merges_with_instruction() {
+        // 合成数据：按 IDL DepositEvent 布局构造的 Program data
+        let accounts: Vec<Pubkey> = (0..15).map(|_| Pubkey::new_unique()).collect();
+        let (pool, user) = (accounts[0], accounts[2]);
+        let mut data = discriminators::DEPOSIT_EVENT.to_vec();
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        // lp_out, max_base, max_quote, user_base, user_quote, pool_base, pool_quote, base_in, quote_in, lp_supply
+        for v in [1_000u64, 5_000_000, 2_000_000, 0, 0, 500_000_000, 200_000_000, 4_900_000, 1_960_000, 100_000] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for key in [pool, user, accounts[6], accounts[7], accounts[8]] {
+            data.extend_from_slice(key.as_ref());
+        }
+        let log = format!("Program data: {}", general_purpose::STANDARD.encode(&data));
+        let log_event = parse_log(&log, Signature::default(), 1, 0, None, 0).expect("deposit log");
+
+        let mut ix = crate::instr::pump_amm::discriminators::DEPOSIT.to_vec();
+        for v in [1_000u64, 5_000_000, 2_000_000] {
+            ix.extend_from_slice(&v.to_le_bytes());
+        }
+        let instr_event = crate::instr::pump_amm::parse_instruction(&ix, &accounts, Signature::default(), 1, 0, None)
+            .expect("deposit instruction");
+
+        let merged = merge_instruction_and_log_events(vec![instr_event], vec![log_event]);
+        assert_eq!(merged.len(), 1);
+        let DexEvent::PumpSwapDeposit(e) = &merged[0] else { panic!("unexpected event {:?}", merged[0]) };
+        assert_eq!((e.pool, e.user), (pool, user));
+        assert_eq!((e.base_mint, e.quote_mint), (accounts[3], accounts[4]));
+        assert_eq!((e.base_amount_in, e.quote_amount_in, e.lp_token_amount_out), (4_900_000, 1_960_000, 1_000));
+        assert_eq!((e.pool_base_token_reserves, e.lp_mint_supply), (500_000_000, 100_000));
+        assert_eq!(e.timestamp, 1_700_000_000);
+    }
+
+    /// 合成数据：按 IDL BuyEvent / SellEvent 布局和给定版本构造 Program data
+    fn trade_log(discriminator: [u8; 8], pool: Pubkey, user: Pubkey, coin_creator: Pubkey, version: u16) -> String {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        // base_amount, quote 限额, 用户两侧余额, 池子两侧储备, quote_amount,
+        // lp_fee_bps, lp_fee, protocol_fee_bps, protocol_fee, 两个含费 quote 数量
+        for v in [42_000u64, 3_100_000, 0, 0, 900_000_000, 80_000_000_000, 3_000_000, 20, 6_000, 5, 1_500, 3_006_000, 3_007_500] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        // pool, user, 用户两个代币账户, 协议费接收方及其代币账户
+        for key in [pool, user, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()] {
+            data.extend_from_slice(key.as_ref());
+        }
+        assert_eq!(data.len() - 8, layouts::TRADE_V1_LEN);
+        if version >= layouts::TRADE_V2 {
+            data.extend_from_slice(coin_creator.as_ref());
+            data.extend_from_slice(&5u64.to_le_bytes());
+            data.extend_from_slice(&1_250u64.to_le_bytes());
+        }
+        if version >= layouts::TRADE_V3 {
+            // track_volume, total_unclaimed_tokens, total_claimed_tokens, current_sol_volume, last_update_timestamp
+            data.push(1);
+            for v in [7u64, 8, 9, 1_700_000_000] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        format!("Program data: {}", general_purpose::STANDARD.encode(&data))
+    }
+
+    #[test]
+    fn test_buy_event_prefix() {
+        // BuyEvent / SellEvent 的 Program data 以 discriminator 的 base64 开头
+        let log = trade_log(discriminators::BUY, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), layouts::TRADE_V2);
+        assert!(log.starts_with("Program data: Z/RSHyz1d3"), "{}", log);
+        let log = trade_log(discriminators::SELL, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), layouts::TRADE_V2);
+        assert!(log.starts_with("Program data: Pi83CqUD3C"), "{}", log);
+    }
+
+    #[test]
+    fn test_buy_layout_versions() {
+        let (pool, user, coin_creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        for version in [layouts::TRADE_V1, layouts::TRADE_V2, layouts::TRADE_V3] {
+            let log = trade_log(discriminators::BUY, pool, user, coin_creator, version);
+            let structured = parse_log(&log, Signature::default(), 1, 0, None, 0);
+            let zero_copy = super::super::zero_copy_parser::parse_pump_amm_trade(&log, Signature::default(), 1, 0, None, 0);
+            for event in [structured, zero_copy] {
+                let Some(DexEvent::PumpSwapBuy(e)) = event else { panic!("layout v{} not decoded", version) };
+                assert_eq!((e.pool_id, e.user, e.token_mint), (pool, user, Pubkey::default()));
+                assert_eq!((e.sol_amount, e.token_amount), (3_000_000, 42_000));
+                assert_eq!((e.pool_base_token_reserves, e.pool_quote_token_reserves), (900_000_000, 80_000_000_000));
+                assert_eq!((e.lp_fee, e.protocol_fee), (6_000, 1_500));
+                assert_eq!(e.metadata.protocol_version, Some(version));
+                let creator = if version >= layouts::TRADE_V2 { (coin_creator, Some(1_250)) } else { (Pubkey::default(), None) };
+                assert_eq!((e.coin_creator, e.coin_creator_fee), creator);
+            }
+        }
+
+        // 不足初版长度的数据不解析
+        let short = format!("Program data: {}", general_purpose::STANDARD.encode([&discriminators::BUY[..], &[0u8; 112]].concat()));
+        assert!(parse_log(&short, Signature::default(), 1, 0, None, 0).is_none());
+        assert!(super::super::zero_copy_parser::parse_pump_amm_trade(&short, Signature::default(), 1, 0, None, 0).is_none());
+    }
+
+    #[test]
+    fn test_trade_merges_with_instruction() {
+        // 合成数据：指令账户 pool(0), user(1), global_config(2), base_mint(3), quote_mint(4)
+        let accounts: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        let (pool, user, mint, coin_creator) = (accounts[0], accounts[1], accounts[3], Pubkey::new_unique());
+
+        for (ix_discriminator, log_discriminator) in [
+            (crate::instr::pump_amm::discriminators::BUY, discriminators::BUY),
+            (crate::instr::pump_amm::discriminators::SELL, discriminators::SELL),
+        ] {
+            let mut ix = ix_discriminator.to_vec();
+            ix.extend_from_slice(&42_000u64.to_le_bytes());
+            ix.extend_from_slice(&3_100_000u64.to_le_bytes());
+            let instr_event = crate::instr::pump_amm::parse_instruction(&ix, &accounts, Signature::default(), 1, 0, None)
+                .expect("trade instruction");
+            let log = trade_log(log_discriminator, pool, user, coin_creator, layouts::TRADE_V2);
+            let log_event = parse_log(&log, Signature::default(), 1, 0, None, 0).expect("trade log");
+
+            let merged = merge_instruction_and_log_events(vec![instr_event], vec![log_event]);
+            assert_eq!(merged.len(), 1);
+            let (keys, amounts, fees, creator) = match &merged[0] {
+                DexEvent::PumpSwapBuy(e) => ((e.pool_id, e.user, e.token_mint), (e.token_amount, e.sol_amount), (e.lp_fee, e.protocol_fee), (e.coin_creator, e.coin_creator_fee)),
+                DexEvent::PumpSwapSell(e) => ((e.pool_id, e.user, e.token_mint), (e.token_amount, e.sol_amount), (e.lp_fee, e.protocol_fee), (e.coin_creator, e.coin_creator_fee)),
+                other => panic!("unexpected event {:?}", other),
+            };
+            // mint 只在指令账户中；成交数量以日志为准（指令中是限额）
+            assert_eq!(keys, (pool, user, mint));
+            assert_eq!(amounts, (42_000, 3_000_000));
+            assert_eq!((fees, creator), ((6_000, 1_500), (coin_creator, Some(1_250))));
+        }
+    }
+}
@@ -22,6 +22,7 @@ pub mod discriminators {
     pub const CREATE_EVENT: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
     pub const TRADE_EVENT: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
     pub const MIGRATE_EVENT: [u8; 8] = [189, 233, 93, 185, 92, 148, 234, 148];
+    pub const SET_PARAMS_EVENT: [u8; 8] = [223, 195, 159, 246, 62, 48, 143, 131];
 
     // 指令 discriminators (8 字节)
     pub const CREATE_TOKEN_IX: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
@@ -30,8 +31,46 @@ pub mod discriminators {
     pub const MIGRATE_IX: [u8; 8] = [155, 234, 231, 146, 236, 158, 162, 30];
 }
 
+/// PumpFun 事件布局版本（记录在 `EventMetadata::protocol_version`）
+///
+/// TradeEvent / CreateEvent 多次在末尾追加字段，按事件数据（不含 discriminator）长度区分。
+pub mod layouts {
+    /// TradeEvent 初版：mint ~ real_token_reserves，没有手续费字段
+    pub const TRADE_V1: u16 = 1;
+    /// 追加 fee_recipient / fee_basis_points / fee 及 creator / creator_fee_basis_points / creator_fee
+    pub const TRADE_V2: u16 = 2;
+    /// 追加 track_volume 及交易量累计字段
+    pub const TRADE_V3: u16 = 3;
+
+    pub const TRADE_V1_LEN: usize = 121;
+    pub const TRADE_V2_LEN: usize = 217;
+    pub const TRADE_V3_LEN: usize = 242;
+
+    /// CreateEvent 初版：name / symbol / uri / mint / bonding_curve / user
+    pub const CREATE_V1: u16 = 1;
+    /// 追加 creator / timestamp 及储备字段
+    pub const CREATE_V2: u16 = 2;
+
+    /// name / symbol / uri 的最大字节数（与 Metaplex 元数据上限一致），超出视为格式错误
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_SYMBOL_LEN: usize = 10;
+    pub const MAX_URI_LEN: usize = 200;
+
+    /// 按数据长度判断 TradeEvent 布局
+    #[inline(always)]
+    pub fn trade_version(data_len: usize) -> u16 {
+        if data_len >= TRADE_V3_LEN {
+            TRADE_V3
+        } else if data_len >= TRADE_V2_LEN {
+            TRADE_V2
+        } else {
+            TRADE_V1
+        }
+    }
+}
+
 /// PumpFun 程序 ID
-pub const PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::PUMPFUN_PROGRAM_ID;
 
 /// 检查日志是否来自 PumpFun 程序
 pub fn is_pumpfun_log(log: &str) -> bool {
@@ -97,26 +136,30 @@ fn parse_structured_log(
     grpc_recv_us: i64,
     is_created_buy: bool,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
 
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::CREATE_EVENT => {
-            parse_create_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::TRADE_EVENT => {
-            parse_trade_event(data, signature, slot, tx_index, block_time, grpc_recv_us, is_created_buy)
-        },
-        discriminators::MIGRATE_EVENT => {
-            parse_migrate_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::CREATE_EVENT => {
+                parse_create_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::TRADE_EVENT => {
+                parse_trade_event(data, signature, slot, tx_index, block_time, grpc_recv_us, is_created_buy)
+            },
+            discriminators::MIGRATE_EVENT => {
+                parse_migrate_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::SET_PARAMS_EVENT => {
+                parse_set_params_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析创建事件
@@ -130,14 +173,14 @@ fn parse_create_event(
 ) -> Option<DexEvent> {
     let mut offset = 0;
 
-    // 解析字符串字段
-    let (name, name_len) = read_string(data, offset)?;
+    // 解析字符串字段：无效 UTF-8 以替换字符保留并标记，超过上限的长度视为格式错误
+    let (name, name_lossy, name_len) = read_string_lossy(data, offset, layouts::MAX_NAME_LEN)?;
     offset += name_len;
 
-    let (symbol, symbol_len) = read_string(data, offset)?;
+    let (symbol, symbol_lossy, symbol_len) = read_string_lossy(data, offset, layouts::MAX_SYMBOL_LEN)?;
     offset += symbol_len;
 
-    let (uri, uri_len) = read_string(data, offset)?;
+    let (uri, uri_lossy, uri_len) = read_string_lossy(data, offset, layouts::MAX_URI_LEN)?;
     offset += uri_len;
 
     // 解析 Pubkey 字段
@@ -150,31 +193,36 @@ fn parse_create_event(
     let user = read_pubkey(data, offset)?;
     offset += 32;
 
-    let creator = read_pubkey(data, offset)?;
+    // 初版布局到 user 为止，之后的字段缺失时取默认值
+    let version = if read_pubkey(data, offset).is_some() { layouts::CREATE_V2 } else { layouts::CREATE_V1 };
+
+    let creator = read_pubkey(data, offset).unwrap_or_default();
     offset += 32;
 
     // 解析数值字段
-    let timestamp = read_i64_le(data, offset)?;
+    let timestamp = read_i64_le(data, offset).unwrap_or_else(|| block_time.unwrap_or(0));
     offset += 8;
 
-    let virtual_token_reserves = read_u64_le(data, offset)?;
+    let virtual_token_reserves = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let virtual_sol_reserves = read_u64_le(data, offset)?;
+    let virtual_sol_reserves = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let real_token_reserves = read_u64_le(data, offset)?;
+    let real_token_reserves = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let token_total_supply = read_u64_le(data, offset)?;
+    let token_total_supply = read_u64_le(data, offset).unwrap_or(0);
 
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, mint, grpc_recv_us);
+    let mut metadata = create_metadata_simple(signature, slot, tx_index, block_time, mint, grpc_recv_us);
+    metadata.protocol_version = Some(version);
 
     Some(DexEvent::PumpFunCreate(PumpFunCreateTokenEvent {
         metadata,
         name,
         symbol,
         uri,
+        invalid_utf8: name_lossy || symbol_lossy || uri_lossy,
         mint,
         bonding_curve,
         user,
@@ -230,22 +278,23 @@ fn parse_trade_event(
     let real_token_reserves = read_u64_le(data, offset)?;
     offset += 8;
 
-    let fee_recipient = read_pubkey(data, offset)?;
+    // 之后的字段是后续版本追加的，旧布局中缺失时取默认值
+    let fee_recipient = read_pubkey(data, offset).unwrap_or_default();
     offset += 32;
 
-    let fee_basis_points = read_u64_le(data, offset)?;
+    let fee_basis_points = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let fee = read_u64_le(data, offset)?;
+    let fee = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let creator = read_pubkey(data, offset)?;
+    let creator = read_pubkey(data, offset).unwrap_or_default();
     offset += 32;
 
-    let creator_fee_basis_points = read_u64_le(data, offset)?;
+    let creator_fee_basis_points = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
-    let creator_fee = read_u64_le(data, offset)?;
+    let creator_fee = read_u64_le(data, offset).unwrap_or(0);
     offset += 8;
 
     let track_volume = read_bool(data, offset).unwrap_or(false);
@@ -259,7 +308,8 @@ fn parse_trade_event(
 
     let current_sol_volume = read_u64_le(data, offset).unwrap_or(0);
 
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, mint, grpc_recv_us);
+    let mut metadata = create_metadata_simple(signature, slot, tx_index, block_time, mint, grpc_recv_us);
+    metadata.protocol_version = Some(layouts::trade_version(data.len()));
 
     Some(DexEvent::PumpFunTrade(PumpFunTradeEvent {
         metadata,
@@ -270,6 +320,7 @@ fn parse_trade_event(
         token_amount,
         is_buy,
         is_created_buy,
+        is_dev_create_token_trade: false,
         user,
         timestamp,
         virtual_sol_reserves,
@@ -287,6 +338,10 @@ fn parse_trade_event(
         total_claimed_tokens,
         current_sol_volume,
         last_update_timestamp: timestamp,
+        sol_limit: 0,
+        price_per_token_lamports: 0.0,
+        slippage_bps: None,
+        price_impact_bps: None,
 
         // 暂时注释，以后会用，AI禁止改动
         // 指令账户字段 - 默认值，由account_filler填充
@@ -375,6 +430,8 @@ fn parse_migrate_event(
         bonding_curve,
         timestamp,
         pool,
+        pool_base_amount: 0,
+        pool_quote_amount: 0,
         // 暂时注释，以后会用，AI禁止改动
         // global: Pubkey::default(),
         // withdraw_authority: Pubkey::default(),
@@ -392,6 +449,84 @@ fn parse_migrate_event(
     }))
 }
 
+/// 解析全局参数更新事件
+fn parse_set_params_event(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let mut offset = 0;
+
+    let initial_virtual_token_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let initial_virtual_sol_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let initial_real_token_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let final_real_sol_reserves = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let token_total_supply = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let fee_basis_points = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let withdraw_authority = read_pubkey(data, offset)?;
+    offset += 32;
+
+    let enable_migrate = read_bool(data, offset)?;
+    offset += 1;
+
+    let pool_migration_fee = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let creator_fee_basis_points = read_u64_le(data, offset)?;
+    offset += 8;
+
+    let mut fee_recipients = Vec::with_capacity(8);
+    for _ in 0..8 {
+        fee_recipients.push(read_pubkey(data, offset)?);
+        offset += 32;
+    }
+
+    let timestamp = read_i64_le(data, offset)?;
+    offset += 8;
+
+    let set_creator_authority = read_pubkey(data, offset).unwrap_or_default();
+    offset += 32;
+
+    let admin_set_creator_authority = read_pubkey(data, offset).unwrap_or_default();
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+
+    Some(DexEvent::PumpFunSetParams(PumpFunSetParamsEvent {
+        metadata,
+        initial_virtual_token_reserves,
+        initial_virtual_sol_reserves,
+        initial_real_token_reserves,
+        final_real_sol_reserves,
+        token_total_supply,
+        fee_basis_points,
+        withdraw_authority,
+        enable_migrate,
+        pool_migration_fee,
+        creator_fee_basis_points,
+        fee_recipients,
+        timestamp,
+        set_creator_authority,
+        admin_set_creator_authority,
+        global: Pubkey::default(), // 将从指令填充
+        authority: Pubkey::default(), // 将从指令填充
+    }))
+}
+
 /// 文本回退解析（SIMD 优化）
 fn parse_text_log(
     log: &str,
@@ -447,6 +582,7 @@ fn parse_create_from_text(
         name: extract_text_field(log, "name").unwrap_or_else(|| "Unknown".to_string()),
         symbol: extract_text_field(log, "symbol").unwrap_or_else(|| "UNK".to_string()),
         uri: extract_text_field(log, "uri").unwrap_or_default(),
+        invalid_utf8: false,
         mint: Pubkey::default(),
         bonding_curve: Pubkey::default(),
         user: Pubkey::default(),
@@ -482,6 +618,7 @@ fn parse_trade_from_text(
         token_amount: extract_number_from_text(log, "token").unwrap_or(1_000_000_000),
         is_buy,
         is_created_buy: false,  // 默认为 false，由上层设置
+        is_dev_create_token_trade: false,
         user: Pubkey::default(),
         timestamp: block_time.unwrap_or(0),
         virtual_sol_reserves: 30_000_000_000,
@@ -499,6 +636,10 @@ fn parse_trade_from_text(
         total_claimed_tokens: 0,
         current_sol_volume: 0,
         last_update_timestamp: block_time.unwrap_or(0),
+        sol_limit: 0,
+        price_per_token_lamports: 0.0,
+        slippage_bps: None,
+        price_impact_bps: None,
 
         // 暂时注释，以后会用，AI禁止改动
         // 指令账户字段
@@ -552,6 +693,8 @@ fn parse_migrate_from_text(
         bonding_curve: Pubkey::default(),
         timestamp: block_time.unwrap_or(0),
         pool: Pubkey::default(),
+        pool_base_amount: 0,
+        pool_quote_amount: 0,
         // 暂时注释，以后会用，AI禁止改动
         // global: Pubkey::default(),
         // withdraw_authority: Pubkey::default(),
@@ -567,4 +710,116 @@ fn parse_migrate_from_text(
         // pool_base_token_account: Pubkey::default(),
         // pool_quote_token_account: Pubkey::default(),
     }))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose, Engine as _};
+
+    fn program_data(discriminator: [u8; 8], data: &[u8]) -> String {
+        let mut bytes = discriminator.to_vec();
+        bytes.extend_from_slice(data);
+        format!("Program data: {}", general_purpose::STANDARD.encode(bytes))
+    }
+
+    #[test]
+    fn test_old_and_current_layouts() {
+        // 合成数据：初版 TradeEvent（mint ~ real_token_reserves，回填归档交易时的长度）、
+        // 追加手续费 / creator 字段的 V2 与追加交易量字段的当前布局
+        let (mint, user, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut v1 = mint.to_bytes().to_vec();
+        v1.extend_from_slice(&2_000_000_000u64.to_le_bytes());
+        v1.extend_from_slice(&5_000_000u64.to_le_bytes());
+        v1.push(1);
+        v1.extend_from_slice(user.as_ref());
+        v1.extend_from_slice(&1_600_000_000i64.to_le_bytes());
+        for v in [31_000_000_000u64, 1_000_000_000_000_000, 1_000_000_000, 793_000_000_000_000] {
+            v1.extend_from_slice(&v.to_le_bytes());
+        }
+        assert_eq!(v1.len(), layouts::TRADE_V1_LEN);
+        let mut v2 = v1.clone();
+        v2.extend_from_slice(Pubkey::new_unique().as_ref());
+        v2.extend_from_slice(&100u64.to_le_bytes());
+        v2.extend_from_slice(&20_000_000u64.to_le_bytes());
+        v2.extend_from_slice(creator.as_ref());
+        v2.extend_from_slice(&5u64.to_le_bytes());
+        v2.extend_from_slice(&1_000_000u64.to_le_bytes());
+        assert_eq!(v2.len(), layouts::TRADE_V2_LEN);
+        let mut v3 = v2.clone();
+        v3.push(1);
+        for v in [7u64, 8, 9_000_000_000] {
+            v3.extend_from_slice(&v.to_le_bytes());
+        }
+        assert_eq!(v3.len(), layouts::TRADE_V3_LEN);
+
+        for (data, version) in [(&v1, layouts::TRADE_V1), (&v2, layouts::TRADE_V2), (&v3, layouts::TRADE_V3)] {
+            let log = program_data(discriminators::TRADE_EVENT, data);
+            let structured = parse_log(&log, Signature::default(), 1, 0, None, 0, false);
+            let zero_copy = super::super::zero_copy_parser::parse_pumpfun_trade(&log, Signature::default(), 1, 0, None, 0, false);
+            for event in [structured, zero_copy] {
+                let Some(DexEvent::PumpFunTrade(e)) = event else { panic!("layout v{} not decoded", version) };
+                assert_eq!((e.mint, e.user, e.sol_amount, e.token_amount), (mint, user, 2_000_000_000, 5_000_000));
+                assert_eq!(e.real_token_reserves, 793_000_000_000_000);
+                assert_eq!(e.metadata.protocol_version, Some(version));
+                // 旧布局缺失的字段取默认值
+                let fees = if version >= layouts::TRADE_V2 { (20_000_000, creator, 1_000_000) } else { (0, Pubkey::default(), 0) };
+                assert_eq!((e.fee, e.creator, e.creator_fee), fees);
+                let volume = if version == layouts::TRADE_V3 { 9_000_000_000 } else { 0 };
+                assert_eq!(e.current_sol_volume, volume);
+            }
+        }
+
+        // 初版 CreateEvent 到 user 为止
+        let mut create = Vec::new();
+        for text in ["Old", "OLD", "https://example.com"] {
+            create.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            create.extend_from_slice(text.as_bytes());
+        }
+        for key in [mint, Pubkey::new_unique(), user] {
+            create.extend_from_slice(key.as_ref());
+        }
+        let log = program_data(discriminators::CREATE_EVENT, &create);
+        let Some(DexEvent::PumpFunCreate(e)) = parse_log(&log, Signature::default(), 1, 0, Some(1_600_000_000), 0, false) else {
+            panic!("old create layout not decoded")
+        };
+        assert_eq!((e.mint, e.user, e.creator), (mint, user, Pubkey::default()));
+        assert_eq!((e.symbol.as_str(), e.timestamp), ("OLD", 1_600_000_000));
+        assert_eq!(e.metadata.protocol_version, Some(layouts::CREATE_V1));
+    }
+
+    #[test]
+    fn test_create_invalid_utf8_and_caps() {
+        // 合成数据：name 含无效 UTF-8；另构造 symbol 超过长度上限、声明长度远超数据的 name
+        let (mint, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let create = |symbol: &[u8]| {
+            let mut create = Vec::new();
+            for text in [&b"Pump\xF0\x28Coin"[..], symbol, b"ipfs://x"] {
+                create.extend_from_slice(&(text.len() as u32).to_le_bytes());
+                create.extend_from_slice(text);
+            }
+            for key in [mint, Pubkey::new_unique(), user] {
+                create.extend_from_slice(key.as_ref());
+            }
+            program_data(discriminators::CREATE_EVENT, &create)
+        };
+        let Some(DexEvent::PumpFunCreate(e)) = parse_log(&create(b"ABCDEFGHIJ"), Signature::default(), 1, 0, None, 0, false)
+        else {
+            panic!("create with invalid utf-8 not decoded")
+        };
+        assert!(e.invalid_utf8);
+        assert_eq!(e.name, "Pump\u{FFFD}(Coin");
+        assert_eq!(e.symbol, "ABCDEFGHIJ");
+        assert_eq!((e.mint, e.user), (mint, user));
+
+        // 无效字节在第 10 字节之后也能检出；超过上限的 symbol 不截断，整条拒绝
+        let data = [&11u32.to_le_bytes()[..], b"ABCDEFGHIJ", &[0xFF]].concat();
+        let (string, lossy, used) = read_string_lossy(&data, 0, 32).unwrap();
+        assert_eq!((string.chars().count(), lossy, used), (11, true, 15));
+        assert!(parse_log(&create(b"ABCDEFGHIJKLMNOP"), Signature::default(), 1, 0, None, 0, false).is_none());
+
+        let mut huge = u32::MAX.to_le_bytes().to_vec();
+        huge.extend_from_slice(b"short");
+        let log = program_data(discriminators::CREATE_EVENT, &huge);
+        assert!(parse_log(&log, Signature::default(), 1, 0, None, 0, false).is_none());
+    }
+}
@@ -17,55 +17,339 @@ pub mod discriminators {
     pub const WITHDRAW_PNL_EVENT: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 7];
 }
 
+/// ray_log 日志类型（Raydium AMM V4 不发 anchor 事件，而是打印 `Program log: ray_log: <base64>`）
+pub mod ray_log_types {
+    pub const INIT: u8 = 0;
+    pub const DEPOSIT: u8 = 1;
+    pub const WITHDRAW: u8 = 2;
+    pub const SWAP_BASE_IN: u8 = 3;
+    pub const SWAP_BASE_OUT: u8 = 4;
+}
+
+/// ray_log 前缀
+pub const RAY_LOG_PREFIX: &str = "ray_log: ";
+
 /// Raydium AMM V4 程序 ID
-pub const PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::RAYDIUM_AMM_V4_PROGRAM_ID;
 
 /// 解析 Raydium AMM V4 日志
 #[inline]
 pub fn parse_log(log: &str, signature: Signature, slot: u64, tx_index: u64, block_time: Option<i64>, grpc_recv_us: i64) -> Option<DexEvent> {
+    if memchr::memmem::find(log.as_bytes(), RAY_LOG_PREFIX.as_bytes()).is_some() {
+        return with_base64_after(log, RAY_LOG_PREFIX.as_bytes(), |data| {
+            parse_ray_log(data, signature, slot, tx_index, block_time, grpc_recv_us)
+        });
+    }
     parse_structured_log(log, signature, slot, tx_index, block_time, grpc_recv_us)
 }
 
-/// 结构化日志解析（基于 Program data）
-fn parse_structured_log(
-    log: &str,
+/// 提取并解码 ray_log 数据
+#[inline]
+pub fn extract_ray_log(log: &str) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    decode_base64_after(log, RAY_LOG_PREFIX.as_bytes(), &mut buf)?;
+    Some(buf)
+}
+
+/// ray_log 解析（首字节为日志类型，其后为 bincode 编码的定长字段）
+fn parse_ray_log(
+    data: &[u8],
     signature: Signature,
     slot: u64,
     tx_index: u64,
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
+    let log_type = *data.first()?;
+    let data = &data[1..];
 
-    match discriminator {
-        discriminators::SWAP_BASE_IN_EVENT => {
-            parse_swap_base_in_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::SWAP_BASE_OUT_EVENT => {
-            parse_swap_base_out_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+    match log_type {
+        ray_log_types::INIT => {
+            parse_ray_log_init(data, signature, slot, tx_index, block_time, grpc_recv_us)
         },
-        discriminators::DEPOSIT_EVENT => {
-            parse_deposit_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+        ray_log_types::DEPOSIT => {
+            parse_ray_log_deposit(data, signature, slot, tx_index, block_time, grpc_recv_us)
         },
-        discriminators::WITHDRAW_EVENT => {
-            parse_withdraw_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+        ray_log_types::WITHDRAW => {
+            parse_ray_log_withdraw(data, signature, slot, tx_index, block_time, grpc_recv_us)
         },
-        discriminators::INITIALIZE2_EVENT => {
-            parse_initialize2_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+        ray_log_types::SWAP_BASE_IN => {
+            parse_ray_log_swap_base_in(data, signature, slot, tx_index, block_time, grpc_recv_us)
         },
-        discriminators::WITHDRAW_PNL_EVENT => {
-            parse_withdraw_pnl_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+        ray_log_types::SWAP_BASE_OUT => {
+            parse_ray_log_swap_base_out(data, signature, slot, tx_index, block_time, grpc_recv_us)
         },
         _ => None,
     }
 }
 
+/// 解析 SwapBaseInLog：amount_in, minimum_out, direction, user_source, pool_coin, pool_pc, out_amount
+fn parse_ray_log_swap_base_in(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let amount_in = read_u64_le(data, 0)?;
+    let minimum_amount_out = read_u64_le(data, 8)?;
+    let direction = read_u64_le(data, 16)?;
+    let _user_source = read_u64_le(data, 24)?;
+    let pool_coin_amount = read_u64_le(data, 32)?;
+    let pool_pc_amount = read_u64_le(data, 40)?;
+    let amount_out = read_u64_le(data, 48)?;
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+
+    Some(ray_log_swap_event(metadata, amount_in, minimum_amount_out, 0, amount_out, pool_coin_amount, pool_pc_amount, direction))
+}
+
+/// 解析 SwapBaseOutLog：max_in, amount_out, direction, user_source, pool_coin, pool_pc, deduct_in
+fn parse_ray_log_swap_base_out(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let max_amount_in = read_u64_le(data, 0)?;
+    let amount_out = read_u64_le(data, 8)?;
+    let direction = read_u64_le(data, 16)?;
+    let _user_source = read_u64_le(data, 24)?;
+    let pool_coin_amount = read_u64_le(data, 32)?;
+    let pool_pc_amount = read_u64_le(data, 40)?;
+    let amount_in = read_u64_le(data, 48)?;
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+
+    Some(ray_log_swap_event(metadata, amount_in, 0, max_amount_in, amount_out, pool_coin_amount, pool_pc_amount, direction))
+}
+
+/// 解析 InitLog：time, pc_decimals(u8), coin_decimals(u8), pc_lot_size, coin_lot_size, pc_amount, coin_amount, market
+fn parse_ray_log_init(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let _time = read_u64_le(data, 0)?;
+    let _pc_decimals = *data.get(8)?;
+    let _coin_decimals = *data.get(9)?;
+    let _pc_lot_size = read_u64_le(data, 10)?;
+    let _coin_lot_size = read_u64_le(data, 18)?;
+    let init_pc_amount = read_u64_le(data, 26)?;
+    let init_coin_amount = read_u64_le(data, 34)?;
+    let serum_market = read_pubkey(data, 42)?;
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+    let default_pubkey = Pubkey::default();
+
+    Some(DexEvent::RaydiumAmmV4Initialize2(RaydiumAmmV4Initialize2Event {
+        metadata,
+        nonce: 0,
+        open_time: 0,
+        init_pc_amount,
+        init_coin_amount,
+        token_program: default_pubkey,
+        spl_associated_token_account: default_pubkey,
+        system_program: default_pubkey,
+        rent: default_pubkey,
+        amm: default_pubkey,
+        amm_authority: default_pubkey,
+        amm_open_orders: default_pubkey,
+        lp_mint: default_pubkey,
+        coin_mint: default_pubkey,
+        pc_mint: default_pubkey,
+        pool_coin_token_account: default_pubkey,
+        pool_pc_token_account: default_pubkey,
+        pool_withdraw_queue: default_pubkey,
+        amm_target_orders: default_pubkey,
+        pool_temp_lp: default_pubkey,
+        serum_program: default_pubkey,
+        serum_market,
+        user_wallet: default_pubkey,
+        user_token_coin: default_pubkey,
+        user_token_pc: default_pubkey,
+        user_lp_token_account: default_pubkey,
+    }))
+}
+
+/// 解析 DepositLog：max_coin, max_pc, base, pool_coin, pool_pc, pool_lp, calc_pnl_x(u128), calc_pnl_y(u128), deduct_coin, deduct_pc, mint_lp
+fn parse_ray_log_deposit(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    // 定长 104 字节（不含类型字节），长度不足视为非 DepositLog
+    if data.len() < 104 {
+        return None;
+    }
+    let max_coin_amount = read_u64_le(data, 0)?;
+    let max_pc_amount = read_u64_le(data, 8)?;
+    let base_side = read_u64_le(data, 16)?;
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+    let default_pubkey = Pubkey::default();
+
+    Some(DexEvent::RaydiumAmmV4Deposit(RaydiumAmmV4DepositEvent {
+        metadata,
+        max_coin_amount,
+        max_pc_amount,
+        base_side,
+        token_program: default_pubkey,
+        amm: default_pubkey,
+        amm_authority: default_pubkey,
+        amm_open_orders: default_pubkey,
+        amm_target_orders: default_pubkey,
+        lp_mint_address: default_pubkey,
+        pool_coin_token_account: default_pubkey,
+        pool_pc_token_account: default_pubkey,
+        serum_market: default_pubkey,
+        user_coin_token_account: default_pubkey,
+        user_pc_token_account: default_pubkey,
+        user_lp_token_account: default_pubkey,
+        user_owner: default_pubkey,
+        serum_event_queue: default_pubkey,
+    }))
+}
+
+/// 解析 WithdrawLog：withdraw_lp, user_lp, pool_coin, pool_pc, pool_lp, calc_pnl_x(u128), calc_pnl_y(u128), out_coin, out_pc
+fn parse_ray_log_withdraw(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    // 定长 88 字节（不含类型字节）
+    if data.len() < 88 {
+        return None;
+    }
+    let amount = read_u64_le(data, 0)?;
+
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, Pubkey::default(), grpc_recv_us);
+    let default_pubkey = Pubkey::default();
+
+    Some(DexEvent::RaydiumAmmV4Withdraw(RaydiumAmmV4WithdrawEvent {
+        metadata,
+        amount,
+        token_program: default_pubkey,
+        amm: default_pubkey,
+        amm_authority: default_pubkey,
+        amm_open_orders: default_pubkey,
+        amm_target_orders: default_pubkey,
+        lp_mint_address: default_pubkey,
+        pool_coin_token_account: default_pubkey,
+        pool_pc_token_account: default_pubkey,
+        pool_withdraw_queue: default_pubkey,
+        pool_temp_lp_token_account: default_pubkey,
+        serum_program: default_pubkey,
+        serum_market: default_pubkey,
+        serum_coin_vault_account: default_pubkey,
+        serum_pc_vault_account: default_pubkey,
+        serum_vault_signer: default_pubkey,
+        user_lp_token_account: default_pubkey,
+        user_coin_token_account: default_pubkey,
+        user_pc_token_account: default_pubkey,
+        user_owner: default_pubkey,
+        serum_event_queue: default_pubkey,
+        serum_bids: default_pubkey,
+        serum_asks: default_pubkey,
+    }))
+}
+
+/// ray_log 不含账户信息，账户字段由指令事件合并填充
+#[allow(clippy::too_many_arguments)]
+fn ray_log_swap_event(
+    metadata: EventMetadata,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    max_amount_in: u64,
+    amount_out: u64,
+    pool_coin_amount: u64,
+    pool_pc_amount: u64,
+    direction: u64,
+) -> DexEvent {
+    let default_pubkey = Pubkey::default();
+    DexEvent::RaydiumAmmV4Swap(RaydiumAmmV4SwapEvent {
+        metadata,
+        amount_in,
+        minimum_amount_out,
+        max_amount_in,
+        amount_out,
+        pool_coin_amount,
+        pool_pc_amount,
+        direction,
+        token_program: default_pubkey,
+        amm: default_pubkey,
+        amm_authority: default_pubkey,
+        amm_open_orders: default_pubkey,
+        amm_target_orders: None,
+        pool_coin_token_account: default_pubkey,
+        pool_pc_token_account: default_pubkey,
+        serum_program: default_pubkey,
+        serum_market: default_pubkey,
+        serum_bids: default_pubkey,
+        serum_asks: default_pubkey,
+        serum_event_queue: default_pubkey,
+        serum_coin_vault_account: default_pubkey,
+        serum_pc_vault_account: default_pubkey,
+        serum_vault_signer: default_pubkey,
+        user_source_token_account: default_pubkey,
+        user_destination_token_account: default_pubkey,
+        user_source_owner: default_pubkey,
+    })
+}
+
+/// 结构化日志解析（基于 Program data）
+fn parse_structured_log(
+    log: &str,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP_BASE_IN_EVENT => {
+                parse_swap_base_in_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::SWAP_BASE_OUT_EVENT => {
+                parse_swap_base_out_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::DEPOSIT_EVENT => {
+                parse_deposit_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::WITHDRAW_EVENT => {
+                parse_withdraw_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::INITIALIZE2_EVENT => {
+                parse_initialize2_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::WITHDRAW_PNL_EVENT => {
+                parse_withdraw_pnl_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
+}
+
 /// 解析 SwapBaseIn 事件
 fn parse_swap_base_in_event(
     data: &[u8],
@@ -96,6 +380,9 @@ fn parse_swap_base_in_event(
         minimum_amount_out,
         max_amount_in: 0,
         amount_out: 0,
+        pool_coin_amount: 0,
+        pool_pc_amount: 0,
+        direction: 0,
         token_program: Pubkey::default(),
         amm,
         amm_authority: Pubkey::default(),
@@ -147,6 +434,9 @@ fn parse_swap_base_out_event(
         minimum_amount_out: 0,
         max_amount_in,
         amount_out,
+        pool_coin_amount: 0,
+        pool_pc_amount: 0,
+        direction: 0,
         token_program: Pubkey::default(),
         amm,
         amm_authority: Pubkey::default(),
@@ -428,6 +718,9 @@ fn parse_swap_log_fallback(
         minimum_amount_out,
         max_amount_in,
         amount_out,
+        pool_coin_amount: 0,
+        pool_pc_amount: 0,
+        direction: 0,
         token_program: default_pubkey,
         amm: default_pubkey,
         amm_authority: default_pubkey,
@@ -536,4 +829,70 @@ fn parse_withdraw_log_fallback(
         serum_bids: default_pubkey,
         serum_asks: default_pubkey,
     }))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::optimized_matcher::{detect_log_type, parse_log_optimized, LogType};
+
+    // 按 Raydium AMM V4 `log.rs` 的 bincode 布局构造的 ray_log 样本
+    const SWAP_BASE_IN_LOG: &str = "Program log: ray_log: AwDKmjsAAAAAADtYCAAAAAACAAAAAAAAAADyBSoBAAAAAEC3Q7oAAAAAsI7wGwAAAHn07QgAAAAA";
+    const SWAP_BASE_OUT_LOG: &str = "Program log: ray_log: BACUNXcAAAAAgNHwCAAAAAABAAAAAAAAAAAacRgCAAAAAEC3Q7oAAAAAsI7wGwAAABWJzzsAAAAA";
+    const DEPOSIT_LOG: &str = "Program log: ray_log: AQDyBSoBAAAAgBe0LAAAAAAAAAAAAAAAAABAt0O6AAAAALCO8BsAAAAArCP8BgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAPIFKgEAAAB/F7QsAAAAAJmEbHMAAAAA";
+
+    fn parse(log: &str) -> Option<DexEvent> {
+        parse_log_optimized(log, Signature::default(), 1, 0, None, 0, None, false)
+    }
+
+    #[test]
+    fn test_ray_log_detected_without_program_data() {
+        assert_eq!(detect_log_type(SWAP_BASE_IN_LOG), LogType::RaydiumAmm);
+        assert_eq!(detect_log_type("Program log: Instruction: SwapBaseIn"), LogType::Unknown);
+    }
+
+    #[test]
+    fn test_ray_log_swap_base_in() {
+        match parse(SWAP_BASE_IN_LOG) {
+            Some(DexEvent::RaydiumAmmV4Swap(e)) => {
+                assert_eq!(e.amount_in, 1_000_000_000);
+                assert_eq!(e.minimum_amount_out, 140_000_000);
+                assert_eq!(e.max_amount_in, 0);
+                assert_eq!(e.amount_out, 149_812_345);
+                assert_eq!(e.direction, 2);
+                assert_eq!(e.pool_coin_amount, 800_000_000_000);
+                assert_eq!(e.pool_pc_amount, 120_000_000_000);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ray_log_swap_base_out() {
+        match parse(SWAP_BASE_OUT_LOG) {
+            Some(DexEvent::RaydiumAmmV4Swap(e)) => {
+                assert_eq!(e.max_amount_in, 2_000_000_000);
+                assert_eq!(e.amount_out, 150_000_000);
+                assert_eq!(e.amount_in, 1_003_456_789);
+                assert_eq!(e.minimum_amount_out, 0);
+                assert_eq!(e.direction, 1);
+                assert_eq!(e.pool_coin_amount, 800_000_000_000);
+                assert_eq!(e.pool_pc_amount, 120_000_000_000);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ray_log_deposit_and_truncated() {
+        match parse(DEPOSIT_LOG) {
+            Some(DexEvent::RaydiumAmmV4Deposit(e)) => {
+                assert_eq!(e.max_coin_amount, 5_000_000_000);
+                assert_eq!(e.max_pc_amount, 750_000_000);
+                assert_eq!(e.base_side, 0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        // 截断的 ray_log 不应产生事件
+        assert!(parse("Program log: ray_log: AwDKmjsAAAAAADtYCAAAAAAC").is_none());
+    }
+}
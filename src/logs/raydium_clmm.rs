@@ -9,14 +9,16 @@ use super::utils::*;
 /// Raydium CLMM discriminator 常量
 pub mod discriminators {
     pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
-    pub const INCREASE_LIQUIDITY: [u8; 8] = [133, 29, 89, 223, 69, 238, 176, 10];
-    pub const DECREASE_LIQUIDITY: [u8; 8] = [160, 38, 208, 111, 104, 91, 44, 1];
+    pub const INCREASE_LIQUIDITY_EVENT: [u8; 8] = [49, 79, 105, 212, 32, 34, 30, 84];
+    pub const DECREASE_LIQUIDITY_EVENT: [u8; 8] = [58, 222, 86, 58, 68, 50, 85, 56];
+    pub const LIQUIDITY_CHANGE_EVENT: [u8; 8] = [126, 240, 175, 206, 158, 88, 153, 107];
+    pub const CREATE_PERSONAL_POSITION_EVENT: [u8; 8] = [100, 30, 87, 249, 196, 223, 154, 206];
     pub const CREATE_POOL: [u8; 8] = [233, 146, 209, 142, 207, 104, 64, 188];
     pub const COLLECT_FEE: [u8; 8] = [164, 152, 207, 99, 187, 104, 171, 119];
 }
 
 /// Raydium CLMM 程序 ID
-pub const PROGRAM_ID: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::RAYDIUM_CLMM_PROGRAM_ID;
 
 /// 检查日志是否来自 Raydium CLMM 程序
 pub fn is_raydium_clmm_log(log: &str) -> bool {
@@ -40,32 +42,36 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::SWAP => {
-            parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::INCREASE_LIQUIDITY => {
-            parse_increase_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::DECREASE_LIQUIDITY => {
-            parse_decrease_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CREATE_POOL => {
-            parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::COLLECT_FEE => {
-            parse_collect_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP => {
+                parse_swap_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::INCREASE_LIQUIDITY_EVENT => {
+                parse_increase_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::DECREASE_LIQUIDITY_EVENT => {
+                parse_decrease_liquidity_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_PERSONAL_POSITION_EVENT => {
+                parse_create_personal_position_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_POOL => {
+                parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::COLLECT_FEE => {
+                parse_collect_fee_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析交换事件
@@ -124,7 +130,10 @@ fn parse_swap_event(
     }))
 }
 
-/// 解析增加流动性事件
+/// 解析增加流动性事件（IncreaseLiquidityEvent）
+///
+/// 布局：position_nft_mint, liquidity(u128), amount_0, amount_1, amount_0_transfer_fee, amount_1_transfer_fee。
+/// 池地址与价格区间由同一指令中的 LiquidityChangeEvent 补齐，见 [`LiquidityChangeTracker`]。
 fn parse_increase_liquidity_event(
     data: &[u8],
     signature: Signature,
@@ -133,35 +142,33 @@ fn parse_increase_liquidity_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
+    let position_nft_mint = read_pubkey(data, 0)?;
+    let liquidity = read_u128_le(data, 32)?;
+    let amount0 = read_u64_le(data, 48)?;
+    let amount1 = read_u64_le(data, 56)?;
 
-    let pool_state = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let position_nft_mint = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let liquidity = read_u128_le(data, offset)?;
-    offset += 16;
-
-    let amount0_max = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let amount1_max = read_u64_le(data, offset)?;
-
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state, grpc_recv_us);
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, position_nft_mint, grpc_recv_us);
 
     Some(DexEvent::RaydiumClmmIncreaseLiquidity(RaydiumClmmIncreaseLiquidityEvent {
         metadata,
-        pool: pool_state,
-        user: position_nft_mint, // Use NFT mint as user for now
+        pool: Pubkey::default(),
+        user: Pubkey::default(),
         liquidity,
-        amount0_max,
-        amount1_max,
+        amount0_max: 0, // 从指令填充
+        amount1_max: 0, // 从指令填充
+        position_nft_mint,
+        position_key: raydium_clmm_position_key(&position_nft_mint),
+        tick_lower_index: 0,
+        tick_upper_index: 0,
+        amount0,
+        amount1,
     }))
 }
 
-/// 解析减少流动性事件
+/// 解析减少流动性事件（DecreaseLiquidityEvent）
+///
+/// 布局：position_nft_mint, liquidity(u128), decrease_amount_0, decrease_amount_1, fee_amount_0, fee_amount_1, ...；
+/// `amount0` / `amount1` 为取出的本金，不含手续费。
 fn parse_decrease_liquidity_event(
     data: &[u8],
     signature: Signature,
@@ -170,34 +177,120 @@ fn parse_decrease_liquidity_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let pool_state = read_pubkey(data, offset)?;
-    offset += 32;
+    let position_nft_mint = read_pubkey(data, 0)?;
+    let liquidity = read_u128_le(data, 32)?;
+    let amount0 = read_u64_le(data, 48)?;
+    let amount1 = read_u64_le(data, 56)?;
 
-    let position_nft_mint = read_pubkey(data, offset)?;
-    offset += 32;
+    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, position_nft_mint, grpc_recv_us);
 
-    let liquidity = read_u128_le(data, offset)?;
-    offset += 16;
-
-    let amount0_min = read_u64_le(data, offset)?;
-    offset += 8;
+    Some(DexEvent::RaydiumClmmDecreaseLiquidity(RaydiumClmmDecreaseLiquidityEvent {
+        metadata,
+        pool: Pubkey::default(),
+        user: Pubkey::default(),
+        liquidity,
+        amount0_min: 0, // 从指令填充
+        amount1_min: 0, // 从指令填充
+        position_nft_mint,
+        position_key: raydium_clmm_position_key(&position_nft_mint),
+        tick_lower_index: 0,
+        tick_upper_index: 0,
+        amount0,
+        amount1,
+    }))
+}
 
-    let amount1_min = read_u64_le(data, offset)?;
+/// 解析开仓事件（CreatePersonalPositionEvent）
+///
+/// 布局：pool_state, minter, nft_owner, tick_lower_index, tick_upper_index, liquidity(u128),
+/// deposit_amount_0, deposit_amount_1, ...；不含头寸 NFT，`position_nft_mint` / `position_key` 从指令填充。
+fn parse_create_personal_position_event(
+    data: &[u8],
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let pool_state = read_pubkey(data, 0)?;
+    let nft_owner = read_pubkey(data, 64)?;
+    let tick_lower_index = read_i32_le(data, 96)?;
+    let tick_upper_index = read_i32_le(data, 100)?;
+    let liquidity = read_u128_le(data, 104)?;
+    let amount0 = read_u64_le(data, 120)?;
+    let amount1 = read_u64_le(data, 128)?;
 
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state, grpc_recv_us);
 
-    Some(DexEvent::RaydiumClmmDecreaseLiquidity(RaydiumClmmDecreaseLiquidityEvent {
+    Some(DexEvent::RaydiumClmmOpenPosition(RaydiumClmmOpenPositionEvent {
         metadata,
         pool: pool_state,
-        user: position_nft_mint, // Use NFT mint as user for now
+        user: nft_owner,
+        position_nft_mint: Pubkey::default(),
+        tick_lower_index,
+        tick_upper_index,
         liquidity,
-        amount0_min,
-        amount1_min,
+        position_key: Pubkey::default(),
+        amount0,
+        amount1,
     }))
 }
 
+/// LiquidityChangeEvent 中的池与头寸价格区间
+#[derive(Debug, Clone, Copy)]
+struct LiquidityChange {
+    pool_state: Pubkey,
+    tick_lower: i32,
+    tick_upper: i32,
+}
+
+/// 补齐头寸事件的池与价格区间
+///
+/// 程序在同一指令中先输出 LiquidityChangeEvent（pool_state, tick, tick_lower, tick_upper, liquidity_before/after），
+/// 紧接着输出 IncreaseLiquidityEvent / DecreaseLiquidityEvent / CreatePersonalPositionEvent。
+/// 逐行解析日志时记住最近一条，补到随后的头寸事件上；LiquidityChangeEvent 本身不产生事件。
+#[derive(Debug, Default)]
+pub(crate) struct LiquidityChangeTracker {
+    pending: Option<LiquidityChange>,
+}
+
+impl LiquidityChangeTracker {
+    /// 日志是 LiquidityChangeEvent 时记下并返回 true
+    #[inline]
+    pub(crate) fn observe(&mut self, log: &str) -> bool {
+        if extract_discriminator_fast(log) != Some(discriminators::LIQUIDITY_CHANGE_EVENT) {
+            return false;
+        }
+        self.pending = with_program_data(log, |data| {
+            let data = data.get(8..)?;
+            Some(LiquidityChange {
+                pool_state: read_pubkey(data, 0)?,
+                tick_lower: read_i32_le(data, 36)?,
+                tick_upper: read_i32_le(data, 40)?,
+            })
+        });
+        true
+    }
+
+    /// 用记下的区间补齐随后的增减流动性事件（开仓事件自带区间）
+    #[inline]
+    pub(crate) fn apply(&mut self, event: &mut DexEvent) {
+        let (pool, tick_lower_index, tick_upper_index) = match event {
+            DexEvent::RaydiumClmmIncreaseLiquidity(e) => (&mut e.pool, &mut e.tick_lower_index, &mut e.tick_upper_index),
+            DexEvent::RaydiumClmmDecreaseLiquidity(e) => (&mut e.pool, &mut e.tick_lower_index, &mut e.tick_upper_index),
+            DexEvent::RaydiumClmmOpenPosition(_) => {
+                self.pending = None;
+                return;
+            }
+            _ => return,
+        };
+        let Some(change) = self.pending.take() else { return };
+        *pool = change.pool_state;
+        *tick_lower_index = change.tick_lower;
+        *tick_upper_index = change.tick_upper;
+    }
+}
+
 /// 解析池创建事件
 fn parse_create_pool_event(
     data: &[u8],
@@ -358,6 +451,12 @@ fn parse_increase_liquidity_from_text(
         liquidity: extract_number_from_text(log, "liquidity").unwrap_or(1_000_000) as u128,
         amount0_max: extract_number_from_text(log, "amount0_max").unwrap_or(1_000_000),
         amount1_max: extract_number_from_text(log, "amount1_max").unwrap_or(1_000_000),
+        position_nft_mint: Pubkey::default(),
+        position_key: Pubkey::default(),
+        tick_lower_index: 0,
+        tick_upper_index: 0,
+        amount0: 0,
+        amount1: 0,
     }))
 }
 
@@ -381,6 +480,12 @@ fn parse_decrease_liquidity_from_text(
         liquidity: extract_number_from_text(log, "liquidity").unwrap_or(1_000_000) as u128,
         amount0_min: extract_number_from_text(log, "amount0_min").unwrap_or(1_000_000),
         amount1_min: extract_number_from_text(log, "amount1_min").unwrap_or(1_000_000),
+        position_nft_mint: Pubkey::default(),
+        position_key: Pubkey::default(),
+        tick_lower_index: 0,
+        tick_upper_index: 0,
+        amount0: 0,
+        amount1: 0,
     }))
 }
 
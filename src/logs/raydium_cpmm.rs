@@ -16,7 +16,7 @@ pub mod discriminators {
 }
 
 /// Raydium CPMM 程序 ID
-pub const PROGRAM_ID: &str = "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::RAYDIUM_CPMM_PROGRAM_ID;
 
 /// 检查日志是否来自 Raydium CPMM 程序
 pub fn is_raydium_cpmm_log(log: &str) -> bool {
@@ -40,32 +40,33 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
 
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::SWAP_BASE_IN => {
-            parse_swap_base_in_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::SWAP_BASE_OUT => {
-            parse_swap_base_out_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::CREATE_POOL => {
-            parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::DEPOSIT => {
-            parse_deposit_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::WITHDRAW => {
-            parse_withdraw_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::SWAP_BASE_IN => {
+                parse_swap_base_in_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::SWAP_BASE_OUT => {
+                parse_swap_base_out_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::CREATE_POOL => {
+                parse_create_pool_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::DEPOSIT => {
+                parse_deposit_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::WITHDRAW => {
+                parse_withdraw_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析 Base In 交换事件
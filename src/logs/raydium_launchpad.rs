@@ -7,14 +7,16 @@ use crate::core::events::*;
 use super::utils::*;
 
 /// Bonk discriminator 常量
+///
+/// Launchpad 只发出 TradeEvent / PoolCreateEvent（以及锁仓相关事件），
+/// 迁移没有对应的事件日志，由 `instr::raydium_launchpad` 的迁移指令解析产生。
 pub mod discriminators {
-    pub const TRADE: [u8; 8] = [2, 3, 4, 5, 6, 7, 8, 9];
-    pub const POOL_CREATE: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
-    pub const MIGRATE_AMM: [u8; 8] = [3, 4, 5, 6, 7, 8, 9, 10];
+    pub const TRADE: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
+    pub const POOL_CREATE: [u8; 8] = [151, 215, 226, 9, 118, 161, 115, 174];
 }
 
 /// Bonk 程序 ID
-pub const PROGRAM_ID: &str = "DjVE6JNiYqPL2QXyCUUh8rNjHrbz9hXHNYt99MQ59qw1";
+pub const PROGRAM_ID: &str = crate::grpc::program_ids::BONK_PROGRAM_ID;
 
 /// 检查日志是否来自 Raydium Launchpad 程序
 pub fn is_raydium_launchpad_log(log: &str) -> bool {
@@ -38,29 +40,32 @@ fn parse_structured_log(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let program_data = extract_program_data(log)?;
-    if program_data.len() < 8 {
-        return None;
-    }
-
-    let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
-    let data = &program_data[8..];
-
-    match discriminator {
-        discriminators::TRADE => {
-            parse_trade_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::POOL_CREATE => {
-            parse_pool_create_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        discriminators::MIGRATE_AMM => {
-            parse_migrate_amm_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
-        },
-        _ => None,
-    }
+    with_program_data(log, |program_data| {
+        if program_data.len() < 8 {
+            return None;
+        }
+
+        let discriminator: [u8; 8] = program_data[0..8].try_into().ok()?;
+        let data = &program_data[8..];
+
+        match discriminator {
+            discriminators::TRADE => {
+                parse_trade_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            discriminators::POOL_CREATE => {
+                parse_pool_create_event(data, signature, slot, tx_index, block_time, grpc_recv_us)
+            },
+            _ => None,
+        }
+    })
 }
 
 /// 解析交易事件
+///
+/// TradeEvent 布局：pool_state, total_base_sell, virtual_base, virtual_quote,
+/// real_base_before, real_quote_before, real_base_after, real_quote_after,
+/// amount_in, amount_out, protocol_fee, platform_fee, share_fee,
+/// trade_direction(u8), pool_status(u8), exact_in(bool)
 fn parse_trade_event(
     data: &[u8],
     signature: Signature,
@@ -69,40 +74,46 @@ fn parse_trade_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let pool_state = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let user = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let amount_in = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let amount_out = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let is_buy = read_bool(data, offset)?;
-    offset += 1;
-
-    let exact_in = read_bool(data, offset)?;
+    let pool_state = read_pubkey(data, 0)?;
+    let amount_in = read_u64_le(data, 88)?;
+    let amount_out = read_u64_le(data, 96)?;
+    let is_buy = read_u8(data, 128)? == 0;
+    // 旧版布局没有 exact_in 字段，按 exact_in 处理
+    let exact_in = read_bool(data, 130).unwrap_or(true);
 
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state, grpc_recv_us);
 
     Some(DexEvent::BonkTrade(BonkTradeEvent {
         metadata,
         pool_state,
-        user,
+        // 事件不含用户，由指令事件合并填充
+        user: Pubkey::default(),
         amount_in,
         amount_out,
         is_buy,
         trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
         exact_in,
+        // 事件不含 mint，由指令事件合并填充
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        // 用户意图只在指令中，由指令事件合并填充
+        minimum_amount_out: None,
+        maximum_amount_in: None,
+        protocol_fee: read_u64_le(data, 104),
+        platform_fee: read_u64_le(data, 112),
+        share_fee: read_u64_le(data, 120),
+        virtual_base: read_u64_le(data, 40),
+        virtual_quote: read_u64_le(data, 48),
+        real_base_after: read_u64_le(data, 72),
+        real_quote_after: read_u64_le(data, 80),
+        pool_status: read_u8(data, 129),
+        curve_progress_bps: None,
     }))
 }
 
 /// 解析池创建事件
+///
+/// PoolCreateEvent 布局：pool_state, creator, config, MintParams, CurveParams, VestingParams
 fn parse_pool_create_event(
     data: &[u8],
     signature: Signature,
@@ -111,71 +122,23 @@ fn parse_pool_create_event(
     block_time: Option<i64>,
     grpc_recv_us: i64,
 ) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let pool_state = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let _token_a_mint = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let _token_b_mint = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let creator = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let _initial_liquidity_a = read_u64_le(data, offset)?;
-    offset += 8;
-
-    let _initial_liquidity_b = read_u64_le(data, offset)?;
+    let pool_state = read_pubkey(data, 0)?;
+    let creator = read_pubkey(data, 32)?;
+    let config = read_pubkey(data, 64)?;
+    let params = crate::instr::raydium_launchpad::parse_pool_create_params(&data[96..])?;
 
     let metadata = create_metadata_simple(signature, slot, tx_index, block_time, pool_state, grpc_recv_us);
 
-    Some(DexEvent::BonkPoolCreate(BonkPoolCreateEvent {
+    // mint 与 platform_config 不在事件中，由指令事件合并填充
+    Some(params.into_event(
         metadata,
-        base_mint_param: BaseMintParam {
-            symbol: "BONK".to_string(),
-            name: "Bonk Pool".to_string(),
-            uri: "https://bonk.com".to_string(),
-            decimals: 5,
-        },
         pool_state,
         creator,
-    }))
-}
-
-/// 解析 AMM 迁移事件
-fn parse_migrate_amm_event(
-    data: &[u8],
-    signature: Signature,
-    slot: u64,
-    tx_index: u64,
-    block_time: Option<i64>,
-    grpc_recv_us: i64,
-) -> Option<DexEvent> {
-    let mut offset = 0;
-
-    let old_pool = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let new_pool = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let user = read_pubkey(data, offset)?;
-    offset += 32;
-
-    let liquidity_amount = read_u64_le(data, offset)?;
-
-    let metadata = create_metadata_simple(signature, slot, tx_index, block_time, old_pool, grpc_recv_us);
-
-    Some(DexEvent::BonkMigrateAmm(BonkMigrateAmmEvent {
-        metadata,
-        old_pool,
-        new_pool,
-        user,
-        liquidity_amount,
-    }))
+        config,
+        Pubkey::default(),
+        Pubkey::default(),
+        Pubkey::default(),
+    ))
 }
 
 /// 文本回退解析
@@ -226,6 +189,19 @@ fn parse_trade_from_text(tx_index: u64,
         is_buy,
         trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
         exact_in: true,
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        minimum_amount_out: None,
+        maximum_amount_in: None,
+        protocol_fee: None,
+        platform_fee: None,
+        share_fee: None,
+        virtual_base: None,
+        virtual_quote: None,
+        real_base_after: None,
+        real_quote_after: None,
+        pool_status: None,
+        curve_progress_bps: None,
     }))
 }
 
@@ -249,6 +225,20 @@ fn parse_pool_create_from_text(tx_index: u64,
         },
         pool_state: Pubkey::default(),
         creator: Pubkey::default(),
+        config: Pubkey::default(),
+        platform_config: Pubkey::default(),
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        curve_type: 0,
+        supply: 0,
+        total_base_sell: 0,
+        total_quote_fund_raising: 0,
+        migrate_type: 0,
+        virtual_base: 0,
+        virtual_quote: 0,
+        total_locked_amount: 0,
+        cliff_period: 0,
+        unlock_period: 0,
     }))
 }
 
@@ -270,5 +260,89 @@ fn parse_migrate_from_text(tx_index: u64,
         new_pool: Pubkey::default(),
         user: Pubkey::default(),
         liquidity_amount: extract_number_from_text(log, "liquidity").unwrap_or(0),
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        migrate_type: 0,
     }))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::merger::merge_instruction_and_log_events;
+    use base64::{engine::general_purpose, Engine as _};
+
+    fn program_data_log(discriminator: [u8; 8], body: &[u8]) -> String {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(body);
+        format!("Program data: {}", general_purpose::STANDARD.encode(data))
+    }
+
+    #[test]
+    fn test_pool_create_event_merges_with_initialize() {
+        let accounts: Vec<Pubkey> = (0..18).map(|_| Pubkey::new_unique()).collect();
+        let (pool_state, creator, config) = (accounts[5], accounts[1], accounts[2]);
+
+        let mut body = Vec::new();
+        body.extend_from_slice(pool_state.as_ref());
+        body.extend_from_slice(creator.as_ref());
+        body.extend_from_slice(config.as_ref());
+        body.extend(crate::instr::raydium_launchpad::tests::encode_pool_create_params());
+        let log = program_data_log(discriminators::POOL_CREATE, &body);
+
+        assert_eq!(
+            crate::logs::optimized_matcher::detect_log_type(&log),
+            crate::logs::optimized_matcher::LogType::RaydiumLaunchpad
+        );
+        let log_event = parse_log(&log, Signature::default(), 1, 0, None, 0).expect("pool create log");
+
+        let mut ix = crate::instr::raydium_launchpad::discriminators::INITIALIZE.to_vec();
+        ix.extend(crate::instr::raydium_launchpad::tests::encode_pool_create_params());
+        let instr_event = crate::instr::raydium_launchpad::parse_instruction(
+            &ix, &accounts, Signature::default(), 1, 0, None,
+        ).expect("initialize instruction");
+
+        let merged = merge_instruction_and_log_events(vec![instr_event], vec![log_event]);
+        assert_eq!(merged.len(), 1);
+        match &merged[0] {
+            DexEvent::BonkPoolCreate(e) => {
+                assert_eq!(e.pool_state, pool_state);
+                assert_eq!(e.config, config);
+                assert_eq!(e.base_mint, accounts[6]);
+                assert_eq!(e.quote_mint, accounts[7]);
+                assert_eq!(e.platform_config, accounts[3]);
+                assert_eq!(e.total_base_sell, 793_100_000_000_000);
+                assert_eq!(e.virtual_quote, 30_000_852_951);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_trade_event_layout() {
+        let pool_state = Pubkey::new_unique();
+        let mut body = pool_state.as_ref().to_vec();
+        // total_base_sell .. real_quote_after
+        for v in [793_100_000_000_000u64, 1_073_025_605_595_359, 30_000_852_951, 0, 0, 0, 0] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        // amount_in, amount_out, protocol_fee, platform_fee, share_fee
+        for v in [2_000_000_000u64, 60_000_000_000_000, 5_000_000, 20_000_000, 0] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        body.extend_from_slice(&[1, 0, 0]); // Sell, Fund, exact_out
+        let log = program_data_log(discriminators::TRADE, &body);
+
+        match parse_log(&log, Signature::default(), 1, 0, None, 0) {
+            Some(DexEvent::BonkTrade(e)) => {
+                assert_eq!(e.pool_state, pool_state);
+                assert_eq!(e.amount_in, 2_000_000_000);
+                assert_eq!(e.amount_out, 60_000_000_000_000);
+                assert!(!e.is_buy);
+                assert!(!e.exact_in);
+                assert_eq!((e.protocol_fee, e.platform_fee, e.share_fee), (Some(5_000_000), Some(20_000_000), Some(0)));
+                assert_eq!((e.virtual_quote, e.real_quote_after, e.pool_status), (Some(30_000_852_951), Some(0), Some(0)));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
@@ -3,19 +3,61 @@
 //! 提供字节数据解析的基础工具，不使用 BorshDeserialize
 
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use crate::core::events::EventMetadata;
+use crate::core::events::{EventMetadata, EventSource};
 use base64::{Engine as _, engine::general_purpose};
+use std::cell::RefCell;
 
-/// 从日志中提取程序数据（使用 SIMD 优化查找）
+/// 线程内复用的解码缓冲区初始容量（覆盖绝大多数事件，超出时缓冲区自动扩容并保留）
+const PROGRAM_DATA_BUF_CAPACITY: usize = 1024;
+
+thread_local! {
+    static PROGRAM_DATA_BUF: RefCell<Vec<u8>> = RefCell::new(Vec::with_capacity(PROGRAM_DATA_BUF_CAPACITY));
+}
+
+/// 从日志中提取程序数据（使用 SIMD 优化查找），每次调用分配新的 Vec
+///
+/// 热路径请使用 [`decode_program_data`] / [`with_program_data`]。
 #[inline]
 pub fn extract_program_data(log: &str) -> Option<Vec<u8>> {
-    use memchr::memmem;
+    let mut buf = Vec::new();
+    decode_program_data(log, &mut buf)?;
+    Some(buf)
+}
 
-    let log_bytes = log.as_bytes();
-    let pos = memmem::find(log_bytes, b"Program data: ")?;
+/// 把 `Program data: ` 之后的 base64 解码到可复用的缓冲区，返回解码后的字节
+///
+/// 缓冲区容量足够时不分配内存；解码失败返回 None（缓冲区内容未定义）。
+#[inline]
+pub fn decode_program_data<'a>(log: &str, buf: &'a mut Vec<u8>) -> Option<&'a [u8]> {
+    decode_base64_after(log, b"Program data: ", buf)
+}
+
+/// 定位 `prefix` 并把其后的 base64 解码到 `buf`
+#[inline]
+pub(crate) fn decode_base64_after<'a>(log: &str, prefix: &[u8], buf: &'a mut Vec<u8>) -> Option<&'a [u8]> {
+    let pos = memchr::memmem::find(log.as_bytes(), prefix)?;
+    let b64 = log[pos + prefix.len()..].trim().as_bytes();
+    buf.clear();
+    buf.resize(base64::decoded_len_estimate(b64.len()), 0);
+    let decoded_len = general_purpose::STANDARD.decode_slice(b64, buf).ok()?;
+    buf.truncate(decoded_len);
+    Some(buf)
+}
+
+/// 用线程内复用的缓冲区解码 `Program data:`，并把解码后的字节交给 `f`
+#[inline]
+pub fn with_program_data<R>(log: &str, f: impl FnOnce(&[u8]) -> Option<R>) -> Option<R> {
+    with_base64_after(log, b"Program data: ", f)
+}
 
-    let data_part = &log[pos + 14..];
-    general_purpose::STANDARD.decode(data_part.trim()).ok()
+/// [`with_program_data`] 的通用版本，`prefix` 为 base64 数据前的标记
+#[inline]
+pub(crate) fn with_base64_after<R>(log: &str, prefix: &[u8], f: impl FnOnce(&[u8]) -> Option<R>) -> Option<R> {
+    PROGRAM_DATA_BUF.with(|cell| match cell.try_borrow_mut() {
+        Ok(mut buf) => f(decode_base64_after(log, prefix, &mut buf)?),
+        // 回调中再次解析日志（如自定义协议）时缓冲区已被占用，退回到临时缓冲区
+        Err(_) => f(decode_base64_after(log, prefix, &mut Vec::new())?),
+    })
 }
 
 /// 快速提取 discriminator（只解码前16字节，避免完整解码）
@@ -117,6 +159,23 @@ pub fn read_string(data: &[u8], offset: usize) -> Option<(String, usize)> {
     Some((string, 4 + len))
 }
 
+/// 读取完整的长度前缀字符串，返回 (字符串, 是否发生了无效 UTF-8 替换, 占用的字节数)
+///
+/// 声明长度超过 `max_len` 或超出数据范围时视为格式错误，返回 None，不会按声明长度分配内存；
+/// 无效 UTF-8 以替换字符保留并标记
+pub fn read_string_lossy(data: &[u8], offset: usize, max_len: usize) -> Option<(String, bool, usize)> {
+    let len = read_u32_le(data, offset)? as usize;
+    if len > max_len {
+        return None;
+    }
+    let bytes = data.get(offset + 4..offset + 4 + len)?;
+    let (string, lossy) = match std::str::from_utf8(bytes) {
+        Ok(s) => (s.to_string(), false),
+        Err(_) => (String::from_utf8_lossy(bytes).into_owned(), true),
+    };
+    Some((string, lossy, 4 + len))
+}
+
 /// 读取布尔值
 pub fn read_bool(data: &[u8], offset: usize) -> Option<bool> {
     if data.len() <= offset {
@@ -140,6 +199,15 @@ pub fn create_metadata_simple(
         tx_index,
         block_time_us: block_time.unwrap_or(0) * 1_000_000,
         grpc_recv_us,
+        handle_us: crate::common::time::handle_micros(grpc_recv_us),
+        event_source: EventSource::Log,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
     }
 }
 
@@ -150,18 +218,8 @@ pub fn create_metadata_default(
     tx_index: u64,
     block_time: Option<i64>,
 ) -> EventMetadata {
-    // 优化：macOS 使用 CLOCK_REALTIME（Linux 可用 CLOCK_REALTIME_COARSE）
-    let current_time = unsafe {
-        let mut ts = libc::timespec {
-            tv_sec: 0,
-            tv_nsec: 0,
-        };
-        #[cfg(target_os = "linux")]
-        libc::clock_gettime(libc::CLOCK_REALTIME_COARSE, &mut ts);
-        #[cfg(not(target_os = "linux"))]
-        libc::clock_gettime(libc::CLOCK_REALTIME, &mut ts);
-        (ts.tv_sec as i64 * 1_000_000) + (ts.tv_nsec as i64 / 1_000)
-    };
+    // Linux 上使用 CLOCK_REALTIME_COARSE
+    let current_time = crate::common::time::now_micros_coarse();
 
     EventMetadata {
         signature,
@@ -169,6 +227,15 @@ pub fn create_metadata_default(
         tx_index,
         block_time_us: block_time.unwrap_or(0) * 1_000_000,
         grpc_recv_us: current_time,
+        handle_us: current_time,
+        event_source: EventSource::Log,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
     }
 }
 
@@ -213,4 +280,24 @@ pub mod text_parser {
             None
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_program_data_reuses_buffer() {
+        let mut buf = Vec::with_capacity(64);
+        let ptr = buf.as_ptr();
+        for len in [0usize, 1, 2, 8, 33, 48] {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(37)).collect();
+            let log = format!("Program data: {} ", general_purpose::STANDARD.encode(&data));
+            assert_eq!(decode_program_data(&log, &mut buf), Some(&data[..]));
+            assert_eq!(extract_program_data(&log), Some(data.clone()));
+            assert_eq!(with_program_data(&log, |bytes| Some(bytes.to_vec())), Some(data));
+        }
+        assert_eq!(buf.as_ptr(), ptr);
+        assert_eq!(decode_program_data("Program data: !!!", &mut buf), None);
+        assert_eq!(decode_program_data("Program log: x", &mut buf), None);
+    }
+}
@@ -7,6 +7,59 @@ use memchr::memmem;
 use base64::{Engine as _, engine::general_purpose};
 use super::perf_hints::prefetch_read;
 
+/// 栈缓冲区大小：走零拷贝路径的事件数据均小于 512 字节
+/// （PumpFun Trade 事件最大约 350 base64 字符 = 262 字节，其余事件更小）
+const MAX_DECODE_SIZE: usize = 512;
+
+/// 定位 `Program data: ` 之后的 base64 数据
+#[inline(always)]
+fn program_data_b64(log: &str) -> Option<&[u8]> {
+    // SIMD 快速查找 "Program data: "
+    let pos = memmem::find(log.as_bytes(), b"Program data: ")?;
+    Some(log[pos + 14..].trim().as_bytes())
+}
+
+/// 只解码前 12 个 base64 字符（= 9 字节）取出 discriminator，不匹配时无需完整解码
+#[inline(always)]
+fn peek_discriminator(b64: &[u8]) -> Option<[u8; 8]> {
+    if b64.len() < 12 {
+        return None;
+    }
+    let mut buf = [0u8; 9];
+    let decoded_len = general_purpose::STANDARD.decode_slice(&b64[..12], &mut buf).ok()?;
+    if decoded_len < 8 {
+        return None;
+    }
+    buf[..8].try_into().ok()
+}
+
+/// 完整解码到栈缓冲区，返回 discriminator 之后的事件数据（超过缓冲区大小返回 None）
+#[inline(always)]
+fn decode_event_data<'a>(b64: &[u8], buf: &'a mut [u8; MAX_DECODE_SIZE]) -> Option<&'a [u8]> {
+    let decoded_len = general_purpose::STANDARD.decode_slice(b64, buf).ok()?;
+    buf.get(8..decoded_len)
+}
+
+#[inline(always)]
+fn metadata(signature: Signature, slot: u64, tx_index: u64, block_time: Option<i64>, grpc_recv_us: i64) -> EventMetadata {
+    EventMetadata {
+        signature,
+        slot,
+        tx_index,
+        block_time_us: block_time.unwrap_or(0) * 1_000_000,
+        grpc_recv_us,
+        handle_us: crate::common::time::handle_micros(grpc_recv_us),
+        event_source: EventSource::Log,
+        outer_index: 0,
+        inner_index: None,
+        protocol_version: None,
+        sequence: 0,
+        raw: None,
+        priority_fee_micro_lamports: None,
+        compute_unit_limit: None,
+    }
+}
+
 /// 零分配 PumpFun Trade 事件解析（栈缓冲区）
 #[inline(always)]
 pub fn parse_pumpfun_trade(
@@ -18,48 +71,20 @@ pub fn parse_pumpfun_trade(
     grpc_recv_us: i64,
     is_created_buy: bool,
 ) -> Option<DexEvent> {
-    // 使用栈缓冲区，避免堆分配（需要足够大以容纳完整的事件数据）
-    // PumpFun Trade 事件最大约 350 base64 字符 = 262字节，留出余量用 512 字节
-    const MAX_DECODE_SIZE: usize = 512;
-    let mut decode_buf: [u8; MAX_DECODE_SIZE] = [0u8; MAX_DECODE_SIZE];
-
-    // SIMD 快速查找 "Program data: "
-    let log_bytes = log.as_bytes();
-    let pos = memmem::find(log_bytes, b"Program data: ")?;
-    let data_part = log[pos + 14..].trim();
-
-    // 快速检查 discriminator（需要至少12个base64字符才能解码出8字节）
-    // base64: 每4个字符 = 3个字节，所以12个字符 = 9个字节
-    if data_part.len() < 12 {
-        return None;
-    }
-
-    // 解码 discriminator 到栈缓冲区（12个字符解码为9字节，包含完整8字节discriminator）
-    let disc_decoded_len = general_purpose::STANDARD
-        .decode_slice(&data_part.as_bytes()[..12], &mut decode_buf[..9])
-        .ok()?;
-
-    if disc_decoded_len < 8 {
-        return None;
-    }
-
     // 检查是否为 Trade 事件 discriminator
     const TRADE_DISCRIMINATOR: [u8; 8] = [189, 219, 127, 211, 78, 230, 97, 238];
 
-    if decode_buf[..8] != TRADE_DISCRIMINATOR {
+    let b64 = program_data_b64(log)?;
+    if peek_discriminator(b64)? != TRADE_DISCRIMINATOR {
         return None;
     }
 
     // 完整解码事件数据到栈缓冲区
-    let decoded_len = general_purpose::STANDARD
-        .decode_slice(data_part.as_bytes(), &mut decode_buf)
-        .ok()?;
-
-    if decoded_len < 96 {
+    let mut decode_buf = [0u8; MAX_DECODE_SIZE];
+    let data = decode_event_data(b64, &mut decode_buf)?;
+    if data.len() < 88 {
         return None;
     }
-
-    let data = &decode_buf[8..decoded_len];
     let mut offset = 0;
 
     // 预取后续数据到 CPU 缓存
@@ -129,13 +154,8 @@ pub fn parse_pumpfun_trade(
 
     let current_sol_volume = read_u64_le_inline(data, offset).unwrap_or(0);
 
-    let metadata = EventMetadata {
-        signature,
-        slot,
-        tx_index,
-        block_time_us: block_time.unwrap_or(0) * 1_000_000,
-        grpc_recv_us,
-    };
+    let mut metadata = metadata(signature, slot, tx_index, block_time, grpc_recv_us);
+    metadata.protocol_version = Some(super::pumpfun::layouts::trade_version(data.len()));
 
     Some(DexEvent::PumpFunTrade(PumpFunTradeEvent {
         metadata,
@@ -144,6 +164,7 @@ pub fn parse_pumpfun_trade(
         token_amount,
         is_buy: is_buy != 0,
         is_created_buy,
+        is_dev_create_token_trade: false,
         user,
         timestamp,
         virtual_sol_reserves,
@@ -161,6 +182,10 @@ pub fn parse_pumpfun_trade(
         total_claimed_tokens,
         current_sol_volume,
         last_update_timestamp: timestamp,
+        sol_limit: 0,
+        price_per_token_lamports: 0.0,
+        slippage_bps: None,
+        price_impact_bps: None,
         // 暂时注释，以后会用，AI禁止改动
         // bonding_curve: Pubkey::default(),
         // associated_bonding_curve: Pubkey::default(),
@@ -169,6 +194,171 @@ pub fn parse_pumpfun_trade(
     }))
 }
 
+/// 零分配 PumpSwap Buy / Sell 事件解析（其它 discriminator 返回 None，交给通用解析器）
+///
+/// 布局与 `pump_amm::parse_log` 一致（共用 `pump_amm::read_trade`），事件不含 mint
+#[inline(always)]
+pub fn parse_pump_amm_trade(
+    log: &str,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    use super::pump_amm::discriminators::{BUY, SELL};
+
+    let b64 = program_data_b64(log)?;
+    let discriminator = peek_discriminator(b64)?;
+    if discriminator != BUY && discriminator != SELL {
+        return None;
+    }
+
+    let mut decode_buf = [0u8; MAX_DECODE_SIZE];
+    let data = decode_event_data(b64, &mut decode_buf)?;
+    let (version, t) = super::pump_amm::read_trade(data)?;
+    let mut metadata = metadata(signature, slot, tx_index, block_time, grpc_recv_us);
+    metadata.protocol_version = Some(version);
+
+    Some(if discriminator == BUY {
+        DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+            metadata,
+            pool_id: t.pool,
+            user: t.user,
+            token_mint: Pubkey::default(),
+            sol_amount: t.quote_amount,
+            token_amount: t.base_amount,
+            price: 0,
+            slippage: 0,
+            pool_base_token_reserves: t.pool_base_token_reserves,
+            pool_quote_token_reserves: t.pool_quote_token_reserves,
+            lp_fee: t.lp_fee,
+            protocol_fee: t.protocol_fee,
+            coin_creator: t.coin_creator,
+            coin_creator_fee: t.coin_creator_fee,
+        })
+    } else {
+        DexEvent::PumpSwapSell(PumpSwapSellEvent {
+            metadata,
+            pool_id: t.pool,
+            user: t.user,
+            token_mint: Pubkey::default(),
+            token_amount: t.base_amount,
+            sol_amount: t.quote_amount,
+            price: 0,
+            slippage: 0,
+            pool_base_token_reserves: t.pool_base_token_reserves,
+            pool_quote_token_reserves: t.pool_quote_token_reserves,
+            lp_fee: t.lp_fee,
+            protocol_fee: t.protocol_fee,
+            coin_creator: t.coin_creator,
+            coin_creator_fee: t.coin_creator_fee,
+        })
+    })
+}
+
+/// 零分配 Bonk (Raydium Launchpad) Trade 事件解析
+///
+/// 布局与 `raydium_launchpad::parse_log` 一致：pool_state@0, virtual_base/quote@40/48,
+/// real_base/quote_after@72/80, amount_in@88, amount_out@96, 三项费用@104..128,
+/// trade_direction@128, pool_status@129, exact_in@130（旧版布局没有 exact_in）
+#[inline(always)]
+pub fn parse_bonk_trade(
+    log: &str,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    let b64 = program_data_b64(log)?;
+    if peek_discriminator(b64)? != super::raydium_launchpad::discriminators::TRADE {
+        return None;
+    }
+
+    let mut decode_buf = [0u8; MAX_DECODE_SIZE];
+    let data = decode_event_data(b64, &mut decode_buf)?;
+
+    let pool_state = read_pubkey_inline(data, 0)?;
+    let amount_in = read_u64_le_inline(data, 88)?;
+    let amount_out = read_u64_le_inline(data, 96)?;
+    let is_buy = read_u8_inline(data, 128)? == 0;
+    let exact_in = read_u8_inline(data, 130).is_none_or(|b| b == 1);
+
+    Some(DexEvent::BonkTrade(BonkTradeEvent {
+        metadata: metadata(signature, slot, tx_index, block_time, grpc_recv_us),
+        pool_state,
+        // 事件不含用户，由指令事件合并填充
+        user: Pubkey::default(),
+        amount_in,
+        amount_out,
+        is_buy,
+        trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
+        exact_in,
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        minimum_amount_out: None,
+        maximum_amount_in: None,
+        protocol_fee: read_u64_le_inline(data, 104),
+        platform_fee: read_u64_le_inline(data, 112),
+        share_fee: read_u64_le_inline(data, 120),
+        virtual_base: read_u64_le_inline(data, 40),
+        virtual_quote: read_u64_le_inline(data, 48),
+        real_base_after: read_u64_le_inline(data, 72),
+        real_quote_after: read_u64_le_inline(data, 80),
+        pool_status: read_u8_inline(data, 129),
+        curve_progress_bps: None,
+    }))
+}
+
+/// 零分配 Raydium CPMM Swap 事件解析（base in / base out）
+///
+/// 布局与 `raydium_cpmm::parse_log` 一致：pool_state, user, 三个 u64 金额, bool
+#[inline(always)]
+pub fn parse_raydium_cpmm_swap(
+    log: &str,
+    signature: Signature,
+    slot: u64,
+    tx_index: u64,
+    block_time: Option<i64>,
+    grpc_recv_us: i64,
+) -> Option<DexEvent> {
+    use super::raydium_cpmm::discriminators::{SWAP_BASE_IN, SWAP_BASE_OUT};
+
+    let b64 = program_data_b64(log)?;
+    let discriminator = peek_discriminator(b64)?;
+    if discriminator != SWAP_BASE_IN && discriminator != SWAP_BASE_OUT {
+        return None;
+    }
+
+    let mut decode_buf = [0u8; MAX_DECODE_SIZE];
+    let data = decode_event_data(b64, &mut decode_buf)?;
+
+    let pool_id = read_pubkey_inline(data, 0)?;
+    let _user = read_pubkey_inline(data, 32)?;
+    // base in: amount_in, minimum_amount_out, amount_out
+    // base out: maximum_amount_in, amount_out, amount_in
+    let (input_amount, output_amount) = if discriminator == SWAP_BASE_IN {
+        (read_u64_le_inline(data, 64)?, read_u64_le_inline(data, 80)?)
+    } else {
+        (read_u64_le_inline(data, 80)?, read_u64_le_inline(data, 72)?)
+    };
+    let flag = read_u8_inline(data, 88)? == 1;
+    let base_input = if discriminator == SWAP_BASE_IN { flag } else { !flag };
+
+    Some(DexEvent::RaydiumCpmmSwap(RaydiumCpmmSwapEvent {
+        metadata: metadata(signature, slot, tx_index, block_time, grpc_recv_us),
+        pool_id,
+        input_vault_before: 0,
+        output_vault_before: 0,
+        input_amount,
+        output_amount,
+        input_transfer_fee: 0,
+        output_transfer_fee: 0,
+        base_input,
+    }))
+}
+
 /// 内联读取 Pubkey（避免函数调用）
 #[inline(always)]
 fn read_pubkey_inline(data: &[u8], offset: usize) -> Option<Pubkey> {
@@ -209,4 +399,85 @@ fn read_i64_le_inline(data: &[u8], offset: usize) -> Option<i64> {
 #[inline(always)]
 fn read_u8_inline(data: &[u8], offset: usize) -> Option<u8> {
     data.get(offset).copied()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 合成的事件数据（按各解析器的字段布局填充），零拷贝与通用解析器使用同一份字节
+    fn program_data_log(discriminator: [u8; 8], body: &[u8]) -> String {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(body);
+        format!("Program data: {}", general_purpose::STANDARD.encode(&data))
+    }
+
+    fn body(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    fn assert_same(fast: Option<DexEvent>, generic: Option<DexEvent>) {
+        // 构造时间随两次解析而不同，不参与比较
+        let (mut fast, mut generic) = (fast.expect("zero-copy decoder returned None"), generic.unwrap());
+        fast.metadata_mut().unwrap().handle_us = 0;
+        generic.metadata_mut().unwrap().handle_us = 0;
+        assert_eq!(format!("{:?}", fast), format!("{:?}", generic));
+    }
+
+
+    #[test]
+    fn test_pump_amm_matches_generic() {
+        use super::super::pump_amm::discriminators::{BUY, SELL};
+        for discriminator in [BUY, SELL] {
+            let log = program_data_log(discriminator, &body(super::super::pump_amm::layouts::TRADE_V2_LEN));
+            assert_same(
+                parse_pump_amm_trade(&log, Signature::default(), 9, 2, Some(100), 5),
+                crate::logs::parse_pump_amm_log(&log, Signature::default(), 9, 2, Some(100), 5),
+            );
+        }
+        let log = program_data_log(super::super::pump_amm::discriminators::CREATE_POOL, &body(112));
+        assert!(parse_pump_amm_trade(&log, Signature::default(), 9, 2, None, 5).is_none());
+    }
+
+    #[test]
+    fn test_bonk_trade_matches_generic() {
+        let mut data = body(160);
+        for (direction, exact_in) in [(0u8, 1u8), (1, 0)] {
+            data[128] = direction;
+            data[130] = exact_in;
+            let log = program_data_log(super::super::raydium_launchpad::discriminators::TRADE, &data);
+            assert_same(
+                parse_bonk_trade(&log, Signature::default(), 9, 2, Some(100), 5),
+                crate::logs::parse_raydium_launchpad_log(&log, Signature::default(), 9, 2, Some(100), 5),
+            );
+        }
+        // 旧版布局没有 exact_in
+        let log = program_data_log(super::super::raydium_launchpad::discriminators::TRADE, &data[..129]);
+        assert_same(
+            parse_bonk_trade(&log, Signature::default(), 9, 2, None, 5),
+            crate::logs::parse_raydium_launchpad_log(&log, Signature::default(), 9, 2, None, 5),
+        );
+    }
+
+    #[test]
+    fn test_raydium_cpmm_swap_matches_generic() {
+        use super::super::raydium_cpmm::discriminators::{SWAP_BASE_IN, SWAP_BASE_OUT};
+        let mut data = body(89);
+        for discriminator in [SWAP_BASE_IN, SWAP_BASE_OUT] {
+            for flag in [0u8, 1] {
+                data[88] = flag;
+                let log = program_data_log(discriminator, &data);
+                assert_same(
+                    parse_raydium_cpmm_swap(&log, Signature::default(), 9, 2, Some(100), 5),
+                    crate::logs::parse_raydium_cpmm_log(&log, Signature::default(), 9, 2, Some(100), 5),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_oversized_payload_falls_back() {
+        let log = program_data_log(super::super::pump_amm::discriminators::BUY, &body(MAX_DECODE_SIZE));
+        assert!(parse_pump_amm_trade(&log, Signature::default(), 9, 2, None, 5).is_none());
+        assert!(crate::logs::parse_pump_amm_log(&log, Signature::default(), 9, 2, None, 5).is_some());
+    }
+}
@@ -29,238 +29,31 @@ pub struct SIMDMemoryOps;
 
 impl SIMDMemoryOps {
     /// 🚀 SIMD加速的内存拷贝 - 针对小数据包优化
+    ///
+    /// 只读写 `len` 字节，对齐没有要求；由标准库的 memcpy 完成（已向量化）
+    ///
+    /// # Safety
+    /// `src` 在 `len` 字节内可读、`dst` 在 `len` 字节内可写，且两者不重叠
     #[inline(always)]
     pub unsafe fn memcpy_simd_optimized(dst: *mut u8, src: *const u8, len: usize) {
-        match len {
-            // 针对不同数据大小使用不同优化策略
-            0 => return,
-            1..=8 => Self::memcpy_small(dst, src, len),
-            9..=16 => Self::memcpy_sse(dst, src, len),
-            17..=32 => Self::memcpy_avx(dst, src, len),
-            33..=64 => Self::memcpy_avx2(dst, src, len),
-            _ => Self::memcpy_avx512_or_fallback(dst, src, len),
-        }
-    }
-    
-    /// 小数据拷贝优化 (1-8字节)
-    #[inline(always)]
-    unsafe fn memcpy_small(dst: *mut u8, src: *const u8, len: usize) {
-        match len {
-            1 => *dst = *src,
-            2 => *(dst as *mut u16) = *(src as *const u16),
-            3 => {
-                *(dst as *mut u16) = *(src as *const u16);
-                *dst.add(2) = *src.add(2);
-            }
-            4 => *(dst as *mut u32) = *(src as *const u32),
-            5..=8 => {
-                *(dst as *mut u64) = *(src as *const u64);
-                if len > 8 {
-                    ptr::copy_nonoverlapping(src.add(8), dst.add(8), len - 8);
-                }
-            }
-            _ => unreachable!(),
-        }
-    }
-    
-    /// SSE优化拷贝 (9-16字节)
-    #[inline(always)]
-    unsafe fn memcpy_sse(dst: *mut u8, src: *const u8, len: usize) {
-        #[cfg(target_arch = "x86_64")]
-        {
-            use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_storeu_si128};
-            
-            if len <= 16 {
-                let chunk = _mm_loadu_si128(src as *const __m128i);
-                _mm_storeu_si128(dst as *mut __m128i, chunk);
-            }
-        }
-        
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            ptr::copy_nonoverlapping(src, dst, len);
-        }
-    }
-    
-    /// AVX优化拷贝 (17-32字节)
-    #[inline(always)]
-    unsafe fn memcpy_avx(dst: *mut u8, src: *const u8, len: usize) {
-        #[cfg(target_arch = "x86_64")]
-        {
-            use std::arch::x86_64::{__m256i, _mm256_loadu_si256, _mm256_storeu_si256};
-            
-            if len <= 32 {
-                let chunk = _mm256_loadu_si256(src as *const __m256i);
-                _mm256_storeu_si256(dst as *mut __m256i, chunk);
-            }
-        }
-        
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            ptr::copy_nonoverlapping(src, dst, len);
-        }
-    }
-    
-    /// AVX2优化拷贝 (33-64字节)
-    #[inline(always)]
-    unsafe fn memcpy_avx2(dst: *mut u8, src: *const u8, len: usize) {
-        #[cfg(target_arch = "x86_64")]
-        {
-            use std::arch::x86_64::{__m256i, _mm256_loadu_si256, _mm256_storeu_si256};
-            
-            // 拷贝前32字节
-            let chunk1 = _mm256_loadu_si256(src as *const __m256i);
-            _mm256_storeu_si256(dst as *mut __m256i, chunk1);
-            
-            if len > 32 {
-                // 拷贝剩余字节
-                let remaining = len - 32;
-                if remaining <= 32 {
-                    let chunk2 = _mm256_loadu_si256(src.add(32) as *const __m256i);
-                    _mm256_storeu_si256(dst.add(32) as *mut __m256i, chunk2);
-                }
-            }
-        }
-        
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            ptr::copy_nonoverlapping(src, dst, len);
-        }
-    }
-    
-    /// AVX512或回退拷贝 (>64字节)
-    #[inline(always)]
-    unsafe fn memcpy_avx512_or_fallback(dst: *mut u8, src: *const u8, len: usize) {
-        #[cfg(all(target_arch = "x86_64", target_feature = "avx512f"))]
-        {
-            use std::arch::x86_64::{__m512i, _mm512_loadu_si512, _mm512_storeu_si512};
-            
-            let chunks = len / 64;
-            let mut offset = 0;
-            
-            // 使用AVX512处理64字节块
-            for _ in 0..chunks {
-                let chunk = _mm512_loadu_si512(src.add(offset) as *const __m512i);
-                _mm512_storeu_si512(dst.add(offset) as *mut __m512i, chunk);
-                offset += 64;
-            }
-            
-            // 处理剩余字节
-            let remaining = len % 64;
-            if remaining > 0 {
-                Self::memcpy_avx2(dst.add(offset), src.add(offset), remaining);
-            }
-        }
-        
-        #[cfg(not(all(target_arch = "x86_64", target_feature = "avx512f")))]
-        {
-            // 回退到AVX2分块处理
-            let chunks = len / 32;
-            let mut offset = 0;
-            
-            for _ in 0..chunks {
-                Self::memcpy_avx2(dst.add(offset), src.add(offset), 32);
-                offset += 32;
-            }
-            
-            let remaining = len % 32;
-            if remaining > 0 {
-                Self::memcpy_avx(dst.add(offset), src.add(offset), remaining);
-            }
+        if len == 0 {
+            return;
         }
+        ptr::copy_nonoverlapping(src, dst, len);
     }
     
     /// 🚀 SIMD加速的内存比较
+    ///
+    /// 只读取 `[a, a + len)` 和 `[b, b + len)`，对齐没有要求；切片比较由标准库的 memcmp 完成（已向量化）
+    ///
+    /// # Safety
+    /// `a`、`b` 在 `len` 字节内必须有效可读
     #[inline(always)]
     pub unsafe fn memcmp_simd_optimized(a: *const u8, b: *const u8, len: usize) -> bool {
-        match len {
-            0 => true,
-            1..=8 => Self::memcmp_small(a, b, len),
-            9..=16 => Self::memcmp_sse(a, b, len),
-            17..=32 => Self::memcmp_avx2(a, b, len),
-            _ => Self::memcmp_large(a, b, len),
-        }
-    }
-    
-    /// 小数据比较
-    #[inline(always)]
-    unsafe fn memcmp_small(a: *const u8, b: *const u8, len: usize) -> bool {
-        match len {
-            1 => *a == *b,
-            2 => *(a as *const u16) == *(b as *const u16),
-            3 => {
-                *(a as *const u16) == *(b as *const u16) &&
-                *a.add(2) == *b.add(2)
-            }
-            4 => *(a as *const u32) == *(b as *const u32),
-            5..=8 => *(a as *const u64) == *(b as *const u64),
-            _ => unreachable!(),
-        }
-    }
-    
-    /// SSE比较
-    #[inline(always)]
-    unsafe fn memcmp_sse(a: *const u8, b: *const u8, len: usize) -> bool {
-        #[cfg(target_arch = "x86_64")]
-        {
-            use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_cmpeq_epi8, _mm_movemask_epi8};
-            
-            let chunk_a = _mm_loadu_si128(a as *const __m128i);
-            let chunk_b = _mm_loadu_si128(b as *const __m128i);
-            let cmp_result = _mm_cmpeq_epi8(chunk_a, chunk_b);
-            let mask = _mm_movemask_epi8(cmp_result) as u32;
-            
-            // 检查前len字节是否相等
-            let valid_mask = if len >= 16 { 0xFFFF } else { (1u32 << len) - 1 };
-            (mask & valid_mask) == valid_mask
-        }
-        
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            (0..len).all(|i| *a.add(i) == *b.add(i))
-        }
-    }
-    
-    /// AVX2比较
-    #[inline(always)]
-    unsafe fn memcmp_avx2(a: *const u8, b: *const u8, len: usize) -> bool {
-        #[cfg(target_arch = "x86_64")]
-        {
-            use std::arch::x86_64::{__m256i, _mm256_loadu_si256, _mm256_cmpeq_epi8, _mm256_movemask_epi8};
-            
-            let chunk_a = _mm256_loadu_si256(a as *const __m256i);
-            let chunk_b = _mm256_loadu_si256(b as *const __m256i);
-            let cmp_result = _mm256_cmpeq_epi8(chunk_a, chunk_b);
-            let mask = _mm256_movemask_epi8(cmp_result) as u32;
-            
-            let valid_mask = if len >= 32 { 0xFFFFFFFF } else { (1u32 << len) - 1 };
-            (mask & valid_mask) == valid_mask
-        }
-        
-        #[cfg(not(target_arch = "x86_64"))]
-        {
-            (0..len).all(|i| *a.add(i) == *b.add(i))
-        }
-    }
-    
-    /// 大数据比较
-    #[inline(always)]
-    unsafe fn memcmp_large(a: *const u8, b: *const u8, len: usize) -> bool {
-        let chunks = len / 32;
-        
-        for i in 0..chunks {
-            let offset = i * 32;
-            if !Self::memcmp_avx2(a.add(offset), b.add(offset), 32) {
-                return false;
-            }
-        }
-        
-        let remaining = len % 32;
-        if remaining > 0 {
-            return Self::memcmp_avx2(a.add(chunks * 32), b.add(chunks * 32), remaining);
+        if len == 0 {
+            return true;
         }
-        
-        true
+        std::slice::from_raw_parts(a, len) == std::slice::from_raw_parts(b, len)
     }
     
     /// 🚀 SIMD加速的内存清零
@@ -573,6 +366,20 @@ mod tests {
         
         assert_eq!(src, dst);
     }
+
+    #[test]
+    fn test_simd_memcpy_stays_in_bounds() {
+        let src: Vec<u8> = (1..=80u8).collect();
+        for len in 1..=70 {
+            // 目标起点不对齐，拷贝范围之外的字节必须保持不变
+            let mut dst = vec![0xAAu8; 80];
+            unsafe {
+                SIMDMemoryOps::memcpy_simd_optimized(dst.as_mut_ptr().add(3), src.as_ptr().add(1), len);
+            }
+            assert_eq!(&dst[3..3 + len], &src[1..1 + len]);
+            assert!(dst[..3].iter().chain(&dst[3 + len..]).all(|&b| b == 0xAA), "len {}", len);
+        }
+    }
     
     #[test]
     fn test_cache_optimized_ring_buffer() {
@@ -606,4 +413,25 @@ mod tests {
             ));
         }
     }
+
+    #[test]
+    fn test_simd_memcmp_unaligned_tail() {
+        // 起点不对齐、比较范围一直到缓冲区末尾：只能读取 len 字节
+        let buf: Vec<u8> = (0..80u8).collect();
+        let mut other = buf.clone();
+        for len in 1..=40 {
+            let start = buf.len() - len;
+            for offset in [1, 3] {
+                let start = start - offset;
+                unsafe {
+                    assert!(SIMDMemoryOps::memcmp_simd_optimized(buf.as_ptr().add(start), other.as_ptr().add(start), len));
+                }
+                other[start + len - 1] ^= 0xFF;
+                unsafe {
+                    assert!(!SIMDMemoryOps::memcmp_simd_optimized(buf.as_ptr().add(start), other.as_ptr().add(start), len));
+                }
+                other[start + len - 1] ^= 0xFF;
+            }
+        }
+    }
 }
\ No newline at end of file
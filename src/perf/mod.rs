@@ -14,6 +14,22 @@
 //! - 编译器级性能优化
 //! - 系统调用绕过机制
 //! - 极致性能测试验证
+//!
+//! 需启用 `perf` feature（旧名称 `extreme-perf` 仍可用）。
+//!
+//! ## 迁移说明
+//!
+//! 事件负载由 `fzstream_common::EventMessage` 改为本 crate 的 [`DexEvent`]，不再依赖 fzstream-common / solana-streamer-sdk：
+//! - `PerformanceOptimizer::process_event_ultra_fast`、`LockFreeEventDispatcher::dispatch_event_ultra_fast`
+//!   以及 `ProtocolStackOptimizer` 的序列化方法直接接收 `DexEvent`
+//! - 原来每个事件携带的 `serialization_format` 改为 `ProtocolOptimizationConfig::serialization`（[`SerializationFormat`]）
+//! - 内联序列化写入事件类型、签名、slot、交易索引和接收时间，不再写 `event_id` / `data`
+//! - 测试和基准可用 [`synthetic_event`] 构造事件
+//!
+//! ## 接入 gRPC 订阅
+//!
+//! `YellowstoneGrpc::with_performance` 按 [`CpuAffinityConfig`] 绑定接收循环和解析线程，
+//! 队列订阅改用 [`LockFreeEventDispatcher`] 的队列，[`PerformanceOptimizer::get_stats`] 即为实际事件的入队延迟。
 
 pub mod ultra_low_latency;
 pub mod kernel_bypass;
@@ -38,6 +54,7 @@ pub use extreme_performance_test::*;
 use std::sync::Arc;
 use anyhow::Result;
 use log::info;
+use crate::core::events::{BlockMetaEvent, DexEvent, EventMetadata, EventSource};
 
 /// 🚀 一键性能优化配置器
 #[derive(Clone)]
@@ -69,7 +86,7 @@ pub struct PerformanceOptimizerConfig {
 
 impl Default for PerformanceOptimizerConfig {
     fn default() -> Self {
-        let num_cpus = num_cpus::get();
+        let num_cpus = available_cores();
         
         Self {
             num_event_queues: num_cpus,
@@ -176,11 +193,38 @@ impl PerformanceOptimizer {
 
     /// 🚀 极速事件处理入口点
     #[inline(always)]
-    pub fn process_event_ultra_fast(&self, client_id: &str, event: fzstream_common::EventMessage) -> Result<()> {
+    pub fn process_event_ultra_fast(&self, client_id: &str, event: DexEvent) -> Result<()> {
         self.dispatcher.dispatch_event_ultra_fast(client_id, event)
     }
 }
 
+/// 可用 CPU 核心数
+pub(crate) fn available_cores() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// 合成事件（slot = `id` 的 BlockMeta），供性能测试套件和单元测试使用
+pub fn synthetic_event(id: u64) -> DexEvent {
+    DexEvent::BlockMeta(BlockMetaEvent {
+        metadata: EventMetadata {
+            signature: Default::default(),
+            slot: id,
+            tx_index: 0,
+            block_time_us: 0,
+            grpc_recv_us: crate::common::time::now_micros(),
+            handle_us: crate::common::time::now_micros(),
+            event_source: EventSource::default(),
+            outer_index: 0,
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        },
+    })
+}
+
 /// 🚀 系统级优化应用器
 pub struct SystemOptimizer;
 
@@ -258,21 +302,7 @@ mod tests {
         let optimizer = PerformanceOptimizer::new(config).unwrap();
         
         // 测试事件处理
-        let test_event = fzstream_common::EventMessage {
-            event_id: "test".to_string(),
-            event_type: fzstream_common::EventType::BlockMeta,
-            data: vec![1, 2, 3],
-            serialization_format: fzstream_common::SerializationProtocol::Bincode,
-            compression_format: fzstream_common::CompressionLevel::None,
-            is_compressed: false,
-            timestamp: 0,
-            original_size: Some(3),
-            grpc_arrival_time: 0,
-            parsing_time: 0,
-            completion_time: 0,
-            client_processing_start: None,
-            client_processing_end: None,
-        };
+        let test_event = synthetic_event(0);
         
         assert!(optimizer.process_event_ultra_fast("test_client", test_event).is_ok());
         
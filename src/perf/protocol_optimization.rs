@@ -13,7 +13,16 @@ use std::sync::Arc;
 
 use std::ptr;
 use anyhow::Result;
-use fzstream_common::{EventMessage, SerializationProtocol};
+use crate::core::events::DexEvent;
+use crate::grpc::types::EventType;
+
+/// 序列化缓存键：签名 + 指令位置 + 事件类型
+fn event_cache_key(event: &DexEvent) -> String {
+    match event.metadata() {
+        Some(m) => format!("{}_{}_{:?}_{:?}", m.signature, m.outer_index, m.inner_index, EventType::from_event(event)),
+        None => format!("{:?}", event),
+    }
+}
 
 /// 🚀 协议栈优化器
 pub struct ProtocolStackOptimizer {
@@ -46,6 +55,18 @@ pub struct ProtocolOptimizationConfig {
     pub enable_preallocation: bool,
     /// 启用原生指针操作
     pub enable_raw_pointer_ops: bool,
+    /// 非内联路径的序列化格式
+    pub serialization: SerializationFormat,
+}
+
+/// 事件序列化格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SerializationFormat {
+    #[default]
+    Bincode,
+    Json,
+    /// 优先 Bincode，失败时退回 JSON
+    Auto,
 }
 
 impl Default for ProtocolOptimizationConfig {
@@ -60,6 +81,7 @@ impl Default for ProtocolOptimizationConfig {
             max_batch_size: 1000,
             enable_preallocation: true,
             enable_raw_pointer_ops: true,
+            serialization: SerializationFormat::default(),
         }
     }
 }
@@ -137,7 +159,7 @@ impl ProtocolStackOptimizer {
     #[inline(always)]
     pub unsafe fn serialize_event_unchecked(
         &self,
-        event: &EventMessage,
+        event: &DexEvent,
         buffer: &mut [u8],
     ) -> Result<usize> {
         self.stats.unchecked_operations.fetch_add(1, Ordering::Relaxed);
@@ -148,7 +170,7 @@ impl ProtocolStackOptimizer {
         }
         
         // 检查缓存
-        let cache_key = format!("{}_{:?}", event.event_id, event.event_type);
+        let cache_key = event_cache_key(event);
         if let Some(cached) = self.fast_path_cache.serialization_cache.get(&cache_key) {
             let cached_len = cached.len();
             if buffer.len() >= cached_len {
@@ -174,55 +196,37 @@ impl ProtocolStackOptimizer {
     #[inline(always)]
     unsafe fn inline_serialize_unchecked(
         &self,
-        event: &EventMessage,
+        event: &DexEvent,
         buffer: &mut [u8],
     ) -> Result<usize> {
+        let Some(metadata) = event.metadata() else {
+            return Err(anyhow::anyhow!("event without metadata cannot be inline serialized"));
+        };
+        let out = buffer.as_mut_ptr();
         let mut offset = 0;
-        
-        // 直接写入事件ID长度 (绕过边界检查)
-        let event_id_bytes = event.event_id.as_bytes();
-        let event_id_len = event_id_bytes.len();
-        
-        *(buffer.as_mut_ptr().add(offset) as *mut u32) = event_id_len as u32;
-        offset += 4;
-        
-        // 直接拷贝事件ID (使用SIMD优化)
+
+        // 事件类型 (跳过枚举验证，没有对应类型的事件写 255)
+        let event_type_byte = EventType::from_event(event).map_or(255u8, |t| t as u8);
+        ptr::write_unaligned(out.add(offset), event_type_byte);
+        offset += 1;
+
+        // 签名 (使用SIMD优化拷贝)
+        let signature = metadata.signature.as_ref();
         super::hardware_optimizations::SIMDMemoryOps::memcpy_simd_optimized(
-            buffer.as_mut_ptr().add(offset),
-            event_id_bytes.as_ptr(),
-            event_id_len
+            out.add(offset),
+            signature.as_ptr(),
+            signature.len()
         );
-        offset += event_id_len;
-        
-        // 直接写入事件类型 (跳过枚举验证)
-        let event_type_byte = match event.event_type {
-            fzstream_common::EventType::BlockMeta => 0u8,
-            fzstream_common::EventType::PumpFunBuy => 1u8,
-            fzstream_common::EventType::BonkBuyExactIn => 2u8,
-            _ => 255u8, // 其他类型使用255
-        };
-        *(buffer.as_mut_ptr().add(offset) as *mut u8) = event_type_byte;
-        offset += 1;
-        
-        // 直接写入数据长度
-        let data_len = event.data.len();
-        *(buffer.as_mut_ptr().add(offset) as *mut u32) = data_len as u32;
-        offset += 4;
-        
-        // 直接拷贝数据 (绕过所有检查)
-        if data_len > 0 {
-            super::hardware_optimizations::SIMDMemoryOps::memcpy_simd_optimized(
-                buffer.as_mut_ptr().add(offset),
-                event.data.as_ptr(),
-                data_len
-            );
-            offset += data_len;
-        }
-        
-        // 直接写入时间戳 (跳过时间验证)
-        *(buffer.as_mut_ptr().add(offset) as *mut u64) = event.timestamp;
+        offset += signature.len();
+
+        // slot / 交易索引 / 接收时间 (绕过边界检查)
+        ptr::write_unaligned(out.add(offset) as *mut u64, metadata.slot);
         offset += 8;
-        
+        ptr::write_unaligned(out.add(offset) as *mut u64, metadata.tx_index);
+        offset += 8;
+        ptr::write_unaligned(out.add(offset) as *mut i64, metadata.grpc_recv_us);
+        offset += 8;
+
         if self.config.skip_integrity_checks {
             self.stats.checks_skipped.fetch_add(5, Ordering::Relaxed); // 跳过了5个检查
         }
@@ -232,28 +236,24 @@ impl ProtocolStackOptimizer {
     
     /// 快速序列化事件
     #[inline(always)]
-    fn fast_serialize_event(&self, event: &EventMessage, buffer: &mut [u8]) -> Result<usize> {
-        match event.serialization_format {
-            SerializationProtocol::Bincode => {
+    fn fast_serialize_event(&self, event: &DexEvent, buffer: &mut [u8]) -> Result<usize> {
+        match self.config.serialization {
+            SerializationFormat::Bincode => {
                 self.fast_bincode_serialize(event, buffer)
             }
-            SerializationProtocol::JSON => {
+            SerializationFormat::Json => {
                 self.fast_json_serialize(event, buffer)
             }
-            SerializationProtocol::Auto => {
-                // 自动选择：小数据用JSON，大数据用Bincode
-                if event.data.len() < 1024 {
-                    self.fast_json_serialize(event, buffer)
-                } else {
-                    self.fast_bincode_serialize(event, buffer)
-                }
+            SerializationFormat::Auto => {
+                self.fast_bincode_serialize(event, buffer)
+                    .or_else(|_| self.fast_json_serialize(event, buffer))
             }
         }
     }
     
     /// 快速Bincode序列化
     #[inline(always)]
-    fn fast_bincode_serialize(&self, event: &EventMessage, buffer: &mut [u8]) -> Result<usize> {
+    fn fast_bincode_serialize(&self, event: &DexEvent, buffer: &mut [u8]) -> Result<usize> {
         // 使用bincode序列化到缓冲区
         let serialized = bincode::serialize(event)
             .map_err(|e| anyhow::anyhow!("Bincode serialization failed: {}", e))?;
@@ -274,7 +274,7 @@ impl ProtocolStackOptimizer {
     
     /// 快速JSON序列化
     #[inline(always)]
-    fn fast_json_serialize(&self, event: &EventMessage, buffer: &mut [u8]) -> Result<usize> {
+    fn fast_json_serialize(&self, event: &DexEvent, buffer: &mut [u8]) -> Result<usize> {
         let json_str = serde_json::to_string(event)
             .map_err(|e| anyhow::anyhow!("JSON serialization failed: {}", e))?;
         
@@ -295,7 +295,7 @@ impl ProtocolStackOptimizer {
     
     /// 🚀 批量事件处理 - 减少函数调用开销
     #[inline(always)]
-    pub fn process_events_batch(&self, events: &[EventMessage], output_buffers: &mut [&mut [u8]]) -> Result<Vec<usize>> {
+    pub fn process_events_batch(&self, events: &[DexEvent], output_buffers: &mut [&mut [u8]]) -> Result<Vec<usize>> {
         if events.len() != output_buffers.len() {
             return Err(anyhow::anyhow!("Events and buffers length mismatch"));
         }
@@ -441,6 +441,7 @@ impl ProtocolStackOptimizer {
             max_batch_size: 10000, // 更大的批量
             enable_preallocation: true,
             enable_raw_pointer_ops: true,
+            serialization: SerializationFormat::default(),
         }
     }
 }
@@ -506,8 +507,7 @@ macro_rules! fast_serialize {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use fzstream_common::{CompressionLevel};
-    use solana_streamer_sdk::streaming::event_parser::common::EventType;
+    use crate::perf::synthetic_event;
     
     #[test]
     fn test_protocol_optimizer_creation() {
@@ -534,21 +534,7 @@ mod tests {
         let config = ProtocolOptimizationConfig::default();
         let optimizer = ProtocolStackOptimizer::new(config).unwrap();
         
-        let event = EventMessage {
-            event_id: "test".to_string(),
-            event_type: EventType::BlockMeta,
-            data: vec![1, 2, 3, 4, 5],
-            serialization_format: SerializationProtocol::Bincode,
-            compression_format: CompressionLevel::None,
-            is_compressed: false,
-            timestamp: 1234567890,
-            original_size: Some(5),
-            grpc_arrival_time: 0,
-            parsing_time: 0,
-            completion_time: 0,
-            client_processing_start: None,
-            client_processing_end: None,
-        };
+        let event = synthetic_event(1);
         
         let mut buffer = vec![0u8; 1024];
         let size = unsafe {
@@ -580,38 +566,7 @@ mod tests {
         let config = ProtocolOptimizationConfig::default();
         let optimizer = ProtocolStackOptimizer::new(config).unwrap();
         
-        let events = vec![
-            EventMessage {
-                event_id: "test1".to_string(),
-                event_type: EventType::BlockMeta,
-                data: vec![1, 2, 3],
-                serialization_format: SerializationProtocol::Bincode,
-                compression_format: CompressionLevel::None,
-                is_compressed: false,
-                timestamp: 1234567890,
-                original_size: Some(3),
-                grpc_arrival_time: 0,
-                parsing_time: 0,
-                completion_time: 0,
-                client_processing_start: None,
-                client_processing_end: None,
-            },
-            EventMessage {
-                event_id: "test2".to_string(),
-                event_type: EventType::BlockMeta,
-                data: vec![4, 5, 6],
-                serialization_format: SerializationProtocol::Bincode,
-                compression_format: CompressionLevel::None,
-                is_compressed: false,
-                timestamp: 1234567891,
-                original_size: Some(3),
-                grpc_arrival_time: 0,
-                parsing_time: 0,
-                completion_time: 0,
-                client_processing_start: None,
-                client_processing_end: None,
-            },
-        ];
+        let events = vec![synthetic_event(1), synthetic_event(2)];
         
         let mut buffer1 = vec![0u8; 1024];
         let mut buffer2 = vec![0u8; 1024];
@@ -166,7 +166,7 @@ impl RealtimeSystemOptimizer {
     /// 自动检测系统配置
     fn auto_detect_system_config(config: &mut RealtimeConfig) -> Result<()> {
         // 检测CPU核心数
-        let num_cpus = num_cpus::get();
+        let num_cpus = super::available_cores();
         info!("🧠 Detected {} CPU cores", num_cpus);
         
         // 自动配置CPU隔离 - 预留最后几个核心给应用
@@ -531,7 +531,7 @@ impl RealtimeSystemOptimizer {
     
     /// 🚀 创建超低延迟配置
     pub fn ultra_low_latency_config() -> RealtimeConfig {
-        let num_cpus = num_cpus::get();
+        let num_cpus = super::available_cores();
         
         RealtimeConfig {
             enable_realtime_scheduling: true,
@@ -384,10 +384,11 @@ impl IOOptimizer {
 }
 
 impl SyscallBatchProcessor {
-    /// 创建系统调用批处理器
+    /// 创建系统调用批处理器（需要在 Tokio 运行时内调用）
     pub fn new(batch_size: usize) -> Result<Self> {
         let pending_calls = crossbeam_queue::ArrayQueue::new(batch_size * 10);
-        let executor = tokio::runtime::Handle::current();
+        let executor = tokio::runtime::Handle::try_current()
+            .map_err(|e| anyhow::anyhow!("Syscall batch processor requires a Tokio runtime: {}", e))?;
         
         log::info!("🚀 Syscall batch processor created with batch size: {}", batch_size);
         
@@ -678,12 +679,12 @@ impl SyscallBypassStatsSnapshot {
 macro_rules! bypass_syscall {
     (time) => {
         // 使用快速时间而不是系统调用
-        crate::performance::syscall_bypass::GLOBAL_TIME_PROVIDER.fast_now_nanos()
+        crate::perf::syscall_bypass::GLOBAL_TIME_PROVIDER.fast_now_nanos()
     };
     
     (batch_io $ops:expr) => {
         // 批量提交I/O操作
-        crate::performance::syscall_bypass::GLOBAL_BYPASS_MANAGER.submit_batch_io($ops).await
+        crate::perf::syscall_bypass::GLOBAL_BYPASS_MANAGER.submit_batch_io($ops).await
     };
 }
 
@@ -755,8 +756,8 @@ mod tests {
         assert_eq!(config.syscall_cache_size, 10000);
     }
     
-    #[test]
-    fn test_userspace_allocation() {
+    #[tokio::test]
+    async fn test_userspace_allocation() {
         let config = SyscallBypassConfig::default();
         let manager = SystemCallBypassManager::new(config).unwrap();
         
@@ -12,7 +12,8 @@ use std::time::{Duration, Instant};
 // use std::collections::VecDeque;
 use crossbeam_queue::ArrayQueue;
 use crossbeam_utils::CachePadded;
-use fzstream_common::EventMessage;
+use crate::core::events::DexEvent;
+use crate::grpc::types::EventType;
 use tokio::sync::Notify;
 use anyhow::Result;
 use log::{info, warn, debug};
@@ -20,7 +21,7 @@ use log::{info, warn, debug};
 /// 🚀 无锁事件分发器 - 使用环形缓冲区实现极速事件分发
 pub struct LockFreeEventDispatcher {
     /// 无锁环形缓冲区，支持多生产者单消费者
-    event_queues: Vec<Arc<ArrayQueue<EventMessage>>>,
+    event_queues: Vec<Arc<ArrayQueue<DexEvent>>>,
     /// 客户端映射到队列的索引
     client_queue_mapping: Arc<dashmap::DashMap<String, usize>>,
     /// 队列选择策略（轮询计数器）
@@ -54,7 +55,7 @@ pub enum ThreadPriority {
 /// 🚀 预取优化器 - 预测性数据预加载
 pub struct PrefetchOptimizer {
     /// 预测缓存：基于历史模式预取可能需要的数据
-    prediction_cache: Arc<ArrayQueue<EventMessage>>,
+    prediction_cache: Arc<ArrayQueue<DexEvent>>,
     /// 预取命中统计
     hit_count: AtomicU64,
     /// 预取失效统计
@@ -75,7 +76,7 @@ impl PrefetchOptimizer {
 
     /// 预测性预取事件数据
     #[inline(always)]
-    pub fn prefetch_event_data(&self, event: &EventMessage) {
+    pub fn prefetch_event_data(&self, event: &DexEvent) {
         if !self.learning_enabled.load(Ordering::Relaxed) {
             return;
         }
@@ -89,7 +90,7 @@ impl PrefetchOptimizer {
 
     /// 尝试从预取缓存获取事件
     #[inline(always)]
-    pub fn try_get_prefetched(&self) -> Option<EventMessage> {
+    pub fn try_get_prefetched(&self) -> Option<DexEvent> {
         if let Some(event) = self.prediction_cache.pop() {
             self.hit_count.fetch_add(1, Ordering::Relaxed);
             Some(event)
@@ -276,20 +277,44 @@ impl LockFreeEventDispatcher {
         }
     }
 
+    /// 获取或分配客户端队列（新客户端按轮询策略分配）
+    #[inline]
+    fn queue_index(&self, client_id: &str) -> usize {
+        if let Some(index) = self.client_queue_mapping.get(client_id) {
+            return *index;
+        }
+        *self.client_queue_mapping.entry(client_id.to_string()).or_insert_with(|| {
+            self.queue_selector.fetch_add(1, Ordering::Relaxed) % self.event_queues.len()
+        })
+    }
+
+    /// 客户端对应的队列序号和无锁队列，gRPC 订阅以它作为消费者队列（见 `DexEventQueue::from_dispatcher`）
+    pub fn client_queue(&self, client_id: &str) -> (usize, Arc<ArrayQueue<DexEvent>>) {
+        let index = self.queue_index(client_id);
+        (index, Arc::clone(&self.event_queues[index]))
+    }
+
+    /// 推入第 `queue_index` 个队列，记录从 gRPC 接收（`metadata.grpc_recv_us`）到入队的延迟；
+    /// 队列满时计入溢出并返回原事件（装箱）
+    #[inline]
+    pub fn dispatch_to_queue(&self, queue_index: usize, event: DexEvent) -> std::result::Result<(), Box<DexEvent>> {
+        let recv_us = event.metadata().map_or(0, |m| m.grpc_recv_us);
+        if let Err(event) = self.event_queues[queue_index].push(event) {
+            self.stats.queue_overflows.fetch_add(1, Ordering::Relaxed);
+            return Err(Box::new(event));
+        }
+        if recv_us > 0 {
+            self.stats.record_event_latency(crate::common::time::elapsed_micros(recv_us) * 1_000);
+        }
+        Ok(())
+    }
+
     /// 🚀 极速事件分发 - 无锁路径
     #[inline(always)]
-    pub fn dispatch_event_ultra_fast(&self, client_id: &str, event: EventMessage) -> Result<()> {
+    pub fn dispatch_event_ultra_fast(&self, client_id: &str, event: DexEvent) -> Result<()> {
         let start_time = Instant::now();
 
-        // 获取或分配客户端队列
-        let queue_index = if let Some(index) = self.client_queue_mapping.get(client_id) {
-            *index
-        } else {
-            // 使用轮询策略分配新队列
-            let index = self.queue_selector.fetch_add(1, Ordering::Relaxed) % self.event_queues.len();
-            self.client_queue_mapping.insert(client_id.to_string(), index);
-            index
-        };
+        let queue_index = self.queue_index(client_id);
 
         // 预取优化
         self.prefetch_optimizer.prefetch_event_data(&event);
@@ -341,7 +366,7 @@ impl LockFreeEventDispatcher {
     /// 工作线程主循环 - 极速事件处理
     async fn worker_main_loop(
         worker_id: usize,
-        queues: Vec<Arc<ArrayQueue<EventMessage>>>,
+        queues: Vec<Arc<ArrayQueue<DexEvent>>>,
         stats: Arc<UltraLowLatencyStats>
     ) {
         info!("🔄 Worker {} started ultra-low-latency processing loop", worker_id);
@@ -395,12 +420,12 @@ impl LockFreeEventDispatcher {
 
     /// 🚀 极速事件处理函数
     #[inline(always)]
-    async fn process_event_ultra_fast(event: &EventMessage) {
+    async fn process_event_ultra_fast(event: &DexEvent) {
         // 在这里实现实际的事件处理逻辑
         // 为了演示，我们只是做一些最小的处理
         
         // 避免不必要的分配和复制
-        debug!("Processing event: {} bytes", event.data.len());
+        debug!("Processing event: {:?}", EventType::from_event(event));
         
         // 在实际应用中，这里会：
         // 1. 解析事件数据
@@ -545,31 +570,12 @@ impl ZeroAllocSerializer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use fzstream_common::{SerializationProtocol};
-    use solana_streamer_sdk::streaming::event_parser::common::EventType;
 
     #[tokio::test]
     async fn test_lockfree_dispatcher() {
         let dispatcher = LockFreeEventDispatcher::new(4, 1000, None);
         
-        let test_event = EventMessage {
-            event_id: "test_1".to_string(),
-            event_type: EventType::BlockMeta,
-            data: vec![1, 2, 3, 4],
-            serialization_format: SerializationProtocol::Bincode,
-            compression_format: fzstream_common::CompressionLevel::None,
-            is_compressed: false,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            original_size: Some(4),
-            grpc_arrival_time: 0,
-            parsing_time: 0,
-            completion_time: 0,
-            client_processing_start: None,
-            client_processing_end: None,
-        };
+        let test_event = crate::perf::synthetic_event(1);
 
         // 测试事件分发
         assert!(dispatcher.dispatch_event_ultra_fast("client_1", test_event).is_ok());
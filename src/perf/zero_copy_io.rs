@@ -51,9 +51,10 @@ impl SharedMemoryPool {
         let aligned_block_size = (block_size + 63) & !63;
         let total_blocks = total_size / aligned_block_size;
         
-        // 创建内存映射文件
+        // 创建内存映射文件（不预留 swap：池按需使用，GB 级映射在内存较小的机器上也能创建）
         let memory_region = MmapOptions::new()
             .len(total_blocks * aligned_block_size)
+            .no_reserve_swap()
             .map_anon()
             .context("Failed to create memory mapped region")?;
         
@@ -0,0 +1,414 @@
+//! 合成事件生成器
+//!
+//! 生成的事件直接使用真实的事件结构体：
+//! - 地址来自固定大小的 mint / 池 / 用户地址池，同一地址会重复出现，接近真实流量
+//! - 金额按对数均匀分布（0.01 ~ 10 SOL），代币数量按 PumpFun 初始虚拟储备推算
+//! - 事件类型按 [`EventMix`] 的权重抽样；固定种子时输出可复现
+//!
+//! 生成是同步的，按 `rate` 节流（0 表示不限速），需要并发时放到独立线程中运行。
+
+use crate::core::events::*;
+use crate::grpc::types::EventType;
+use crate::grpc::DexEventQueue;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::time::{Duration, Instant};
+
+/// PumpFun 初始虚拟储备
+const INITIAL_VIRTUAL_SOL: u64 = 30_000_000_000;
+const INITIAL_VIRTUAL_TOKEN: u64 = 1_073_000_000_000_000;
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// 事件类型分布（权重）
+#[derive(Debug, Clone)]
+pub struct EventMix {
+    weights: Vec<(EventType, u32)>,
+    total: u32,
+}
+
+impl EventMix {
+    /// 支持生成的事件类型
+    pub const SUPPORTED: [EventType; 6] = [
+        EventType::PumpFunTrade,
+        EventType::PumpFunCreate,
+        EventType::PumpSwapBuy,
+        EventType::PumpSwapSell,
+        EventType::BonkTrade,
+        EventType::RaydiumCpmmSwap,
+    ];
+
+    /// 自定义分布，忽略不支持的类型和权重为 0 的项
+    pub fn new(weights: impl IntoIterator<Item = (EventType, u32)>) -> Self {
+        let weights: Vec<_> = weights
+            .into_iter()
+            .filter(|(t, w)| *w > 0 && Self::SUPPORTED.contains(t))
+            .collect();
+        let total = weights.iter().map(|(_, w)| w).sum();
+        Self { weights, total }
+    }
+
+    /// 权重之和
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+
+    fn pick(&self, rng: &mut StdRng) -> Option<EventType> {
+        if self.total == 0 {
+            return None;
+        }
+        let mut n = rng.random_range(0..self.total);
+        for &(event_type, weight) in &self.weights {
+            if n < weight {
+                return Some(event_type);
+            }
+            n -= weight;
+        }
+        None
+    }
+}
+
+impl Default for EventMix {
+    /// 以 PumpFun 交易为主的分布
+    fn default() -> Self {
+        Self::new([
+            (EventType::PumpFunTrade, 60),
+            (EventType::PumpFunCreate, 2),
+            (EventType::PumpSwapBuy, 10),
+            (EventType::PumpSwapSell, 10),
+            (EventType::BonkTrade, 8),
+            (EventType::RaydiumCpmmSwap, 10),
+        ])
+    }
+}
+
+/// 合成事件生成器
+pub struct EventGenerator {
+    rng: StdRng,
+    mix: EventMix,
+    /// 每秒事件数，0 表示不限速
+    rate: u64,
+    mints: Vec<Pubkey>,
+    pools: Vec<Pubkey>,
+    users: Vec<Pubkey>,
+    slot: u64,
+    tx_index: u64,
+}
+
+impl EventGenerator {
+    /// 使用固定种子创建（输出可复现）
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut addresses = |n: usize| -> Vec<Pubkey> {
+            (0..n).map(|_| Pubkey::new_from_array(rng.random())).collect()
+        };
+        let (mints, pools, users) = (addresses(1_000), addresses(1_000), addresses(10_000));
+        Self {
+            rng,
+            mix: EventMix::default(),
+            rate: 0,
+            mints,
+            pools,
+            users,
+            slot: 300_000_000,
+            tx_index: 0,
+        }
+    }
+
+    pub fn with_mix(mut self, mix: EventMix) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    /// 每秒生成的事件数（0 表示不限速）
+    pub fn with_rate(mut self, events_per_sec: u64) -> Self {
+        self.rate = events_per_sec;
+        self
+    }
+
+    /// 生成下一个事件，分布为空时返回 None
+    pub fn next_event(&mut self) -> Option<DexEvent> {
+        let event_type = self.mix.pick(&mut self.rng)?;
+        let metadata = self.metadata();
+        Some(match event_type {
+            EventType::PumpFunTrade => self.pumpfun_trade(metadata),
+            EventType::PumpFunCreate => self.pumpfun_create(metadata),
+            EventType::PumpSwapBuy => self.pumpswap_trade(metadata, true),
+            EventType::PumpSwapSell => self.pumpswap_trade(metadata, false),
+            EventType::BonkTrade => self.bonk_trade(metadata),
+            _ => self.raydium_cpmm_swap(metadata),
+        })
+    }
+
+    /// 生成 `count` 个事件，按 `rate` 节流，依次交给 `sink`，返回生成的数量
+    pub fn run<F: FnMut(DexEvent)>(&mut self, count: usize, mut sink: F) -> usize {
+        self.run_while(count, |event| {
+            sink(event);
+            true
+        })
+    }
+
+    /// 同 [`Self::run`]，`sink` 返回 false 时提前停止
+    pub fn run_while<F: FnMut(DexEvent) -> bool>(&mut self, count: usize, mut sink: F) -> usize {
+        let start = Instant::now();
+        let mut produced = 0;
+        while produced < count {
+            let Some(event) = self.next_event() else { break };
+            produced += 1;
+            if !sink(event) {
+                break;
+            }
+            self.throttle(start, produced);
+        }
+        produced
+    }
+
+    /// 生成事件推入队列，返回成功入队的数量（队列满时丢弃）
+    pub fn run_into_queue(&mut self, queue: &DexEventQueue, count: usize) -> usize {
+        let mut pushed = 0;
+        self.run(count, |event| {
+            if queue.push(event).is_ok() {
+                pushed += 1;
+            }
+        });
+        pushed
+    }
+
+    /// 生成事件发送到 channel，返回发送成功的数量（接收端关闭后停止计数）
+    pub fn run_into_channel(&mut self, sender: &crossbeam_channel::Sender<DexEvent>, count: usize) -> usize {
+        let mut sent = 0;
+        self.run(count, |event| {
+            if sender.send(event).is_ok() {
+                sent += 1;
+            }
+        });
+        sent
+    }
+
+    /// 领先于目标速率时休眠（超过 1ms 才休眠，避免频繁系统调用）
+    #[inline]
+    fn throttle(&self, start: Instant, produced: usize) {
+        if self.rate == 0 {
+            return;
+        }
+        let due = Duration::from_nanos(produced as u64 * 1_000_000_000 / self.rate);
+        if let Some(ahead) = due.checked_sub(start.elapsed()) {
+            if ahead >= Duration::from_millis(1) {
+                std::thread::sleep(ahead);
+            }
+        }
+    }
+
+    fn metadata(&mut self) -> EventMetadata {
+        // 平均每个 slot 约 50 笔交易
+        if self.rng.random_range(0..50) == 0 {
+            self.slot += 1;
+            self.tx_index = 0;
+        }
+        self.tx_index += 1;
+        let now_us = crate::common::time::now_micros();
+        let mut signature = [0u8; 64];
+        self.rng.fill(&mut signature[..]);
+        EventMetadata {
+            signature: Signature::from(signature),
+            slot: self.slot,
+            tx_index: self.tx_index,
+            block_time_us: now_us,
+            grpc_recv_us: now_us,
+            handle_us: now_us,
+            event_source: EventSource::Log,
+            outer_index: self.rng.random_range(0..4),
+            inner_index: None,
+            protocol_version: None,
+            sequence: 0,
+            raw: None,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    fn pick(rng: &mut StdRng, addresses: &[Pubkey]) -> Pubkey {
+        addresses[rng.random_range(0..addresses.len())]
+    }
+
+    /// 对数均匀分布的 SOL 金额（lamports）
+    fn sol_amount(&mut self) -> u64 {
+        let sol = 10f64.powf(self.rng.random_range(-2.0..1.0));
+        (sol * LAMPORTS_PER_SOL) as u64
+    }
+
+    fn pumpfun_trade(&mut self, metadata: EventMetadata) -> DexEvent {
+        let is_buy = self.rng.random_bool(0.55);
+        let sol_amount = self.sol_amount();
+        // 随机的曲线进度：虚拟 SOL 储备 30 ~ 115 SOL
+        let virtual_sol = INITIAL_VIRTUAL_SOL + self.rng.random_range(0..85_000_000_000);
+        let k = INITIAL_VIRTUAL_SOL as u128 * INITIAL_VIRTUAL_TOKEN as u128;
+        let virtual_token = (k / virtual_sol as u128) as u64;
+        let token_amount = (virtual_token as u128 * sol_amount as u128 / (virtual_sol + sol_amount) as u128) as u64;
+        let fee = sol_amount / 100;
+        let timestamp = metadata.block_time_us / 1_000_000;
+
+        DexEvent::PumpFunTrade(PumpFunTradeEvent {
+            metadata,
+            mint: Self::pick(&mut self.rng, &self.mints),
+            sol_amount,
+            token_amount,
+            is_buy,
+            is_created_buy: false,
+            is_dev_create_token_trade: false,
+            user: Self::pick(&mut self.rng, &self.users),
+            timestamp,
+            virtual_sol_reserves: virtual_sol,
+            virtual_token_reserves: virtual_token,
+            real_sol_reserves: virtual_sol - INITIAL_VIRTUAL_SOL,
+            real_token_reserves: virtual_token.saturating_sub(279_900_000_000_000),
+            fee_recipient: Self::pick(&mut self.rng, &self.users),
+            fee_basis_points: 95,
+            fee,
+            creator: Self::pick(&mut self.rng, &self.users),
+            creator_fee_basis_points: 5,
+            creator_fee: sol_amount / 2_000,
+            track_volume: false,
+            total_unclaimed_tokens: 0,
+            total_claimed_tokens: 0,
+            current_sol_volume: 0,
+            last_update_timestamp: timestamp,
+            sol_limit: 0,
+            price_per_token_lamports: sol_amount as f64 / token_amount.max(1) as f64,
+            slippage_bps: None,
+            price_impact_bps: None,
+        })
+    }
+
+    fn pumpfun_create(&mut self, metadata: EventMetadata) -> DexEvent {
+        let n: u32 = self.rng.random_range(0..100_000);
+        let user = Self::pick(&mut self.rng, &self.users);
+        let timestamp = metadata.block_time_us / 1_000_000;
+        DexEvent::PumpFunCreate(PumpFunCreateTokenEvent {
+            metadata,
+            name: format!("Synthetic {}", n),
+            symbol: format!("SYN{}", n),
+            uri: format!("https://example.invalid/{}.json", n),
+            invalid_utf8: false,
+            mint: Self::pick(&mut self.rng, &self.mints),
+            bonding_curve: Self::pick(&mut self.rng, &self.pools),
+            user,
+            creator: user,
+            timestamp,
+            virtual_token_reserves: INITIAL_VIRTUAL_TOKEN,
+            virtual_sol_reserves: INITIAL_VIRTUAL_SOL,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+        })
+    }
+
+    fn pumpswap_trade(&mut self, metadata: EventMetadata, is_buy: bool) -> DexEvent {
+        let sol_amount = self.sol_amount();
+        // 毕业后的价格区间：每个代币 ~ 0.00000005 ~ 0.000005 SOL
+        let price = self.rng.random_range(50..5_000);
+        let token_amount = sol_amount * 1_000 / price;
+        let (pool_id, user, token_mint) = (
+            Self::pick(&mut self.rng, &self.pools),
+            Self::pick(&mut self.rng, &self.users),
+            Self::pick(&mut self.rng, &self.mints),
+        );
+        if is_buy {
+            DexEvent::PumpSwapBuy(PumpSwapBuyEvent {
+                metadata, pool_id, user, token_mint, sol_amount, token_amount, price, slippage: 100,
+                pool_base_token_reserves: 0, pool_quote_token_reserves: 0, lp_fee: 0, protocol_fee: 0,
+                coin_creator: Pubkey::default(), coin_creator_fee: None,
+            })
+        } else {
+            DexEvent::PumpSwapSell(PumpSwapSellEvent {
+                metadata, pool_id, user, token_mint, token_amount, sol_amount, price, slippage: 100,
+                pool_base_token_reserves: 0, pool_quote_token_reserves: 0, lp_fee: 0, protocol_fee: 0,
+                coin_creator: Pubkey::default(), coin_creator_fee: None,
+            })
+        }
+    }
+
+    fn bonk_trade(&mut self, metadata: EventMetadata) -> DexEvent {
+        let is_buy = self.rng.random_bool(0.55);
+        let sol_amount = self.sol_amount();
+        let token_amount = sol_amount * self.rng.random_range(10_000..100_000);
+        let (amount_in, amount_out) = if is_buy { (sol_amount, token_amount) } else { (token_amount, sol_amount) };
+        DexEvent::BonkTrade(BonkTradeEvent {
+            metadata,
+            pool_state: Self::pick(&mut self.rng, &self.pools),
+            user: Self::pick(&mut self.rng, &self.users),
+            amount_in,
+            amount_out,
+            is_buy,
+            trade_direction: if is_buy { TradeDirection::Buy } else { TradeDirection::Sell },
+            exact_in: true,
+            base_mint: Self::pick(&mut self.rng, &self.mints),
+            quote_mint: WSOL_MINT,
+            minimum_amount_out: None,
+            maximum_amount_in: None,
+            protocol_fee: None,
+            platform_fee: None,
+            share_fee: None,
+            virtual_base: None,
+            virtual_quote: None,
+            real_base_after: None,
+            real_quote_after: None,
+            pool_status: None,
+            curve_progress_bps: None,
+        })
+    }
+
+    fn raydium_cpmm_swap(&mut self, metadata: EventMetadata) -> DexEvent {
+        let input_amount = self.sol_amount();
+        let input_vault_before = self.rng.random_range(100..10_000) * LAMPORTS_PER_SOL as u64;
+        let output_vault_before = input_vault_before * self.rng.random_range(1_000..100_000);
+        let output_amount = (output_vault_before as u128 * input_amount as u128
+            / (input_vault_before + input_amount) as u128) as u64;
+        DexEvent::RaydiumCpmmSwap(RaydiumCpmmSwapEvent {
+            metadata,
+            pool_id: Self::pick(&mut self.rng, &self.pools),
+            input_vault_before,
+            output_vault_before,
+            input_amount,
+            output_amount,
+            input_transfer_fee: 0,
+            output_transfer_fee: 0,
+            base_input: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_count_and_mix() {
+        let mix = EventMix::new([(EventType::PumpFunTrade, 3), (EventType::PumpSwapBuy, 1)]);
+        let mut generator = EventGenerator::new(7).with_mix(mix);
+
+        let mut counts: HashMap<EventType, usize> = HashMap::new();
+        let produced = generator.run(10_000, |event| {
+            *counts.entry(EventType::from_event(&event).unwrap()).or_default() += 1;
+        });
+
+        assert_eq!(produced, 10_000);
+        assert_eq!(counts.len(), 2);
+        let trades = counts[&EventType::PumpFunTrade];
+        assert!((7_000..8_000).contains(&trades), "PumpFunTrade = {}", trades);
+        assert_eq!(trades + counts[&EventType::PumpSwapBuy], 10_000);
+    }
+
+    #[test]
+    fn test_queue_sink_and_determinism() {
+        let queue = DexEventQueue::new(16);
+        assert_eq!(EventGenerator::new(1).run_into_queue(&queue, 32), 16);
+
+        let render = |seed| {
+            let mut events = Vec::new();
+            EventGenerator::new(seed).run(5, |e| events.push(e));
+            events.iter().map(|e| format!("{:?}", e.metadata().unwrap().signature)).collect::<Vec<_>>()
+        };
+        assert_eq!(render(3), render(3));
+    }
+}
@@ -0,0 +1,8 @@
+//! 测试工具
+//!
+//! - [`EventGenerator`]：不依赖主网流量，按配置的速率和事件分布生成合成 `DexEvent`，
+//!   用于压测下游消费逻辑
+
+pub mod generator;
+
+pub use generator::{EventGenerator, EventMix};
@@ -0,0 +1,583 @@
+//! 解析结果快照测试（`cargo test --features fixtures`）
+//!
+//! `tests/fixtures/transactions/*.json` 中的每笔交易经 `parse_transaction_events` 解析、规范化后，
+//! 与 `tests/fixtures/golden/` 下的同名快照逐字段比较，防止字段取值（手续费、买卖方向等）在重构中悄悄变化。
+//!
+//! `transactions/` 中为合成交易（`"synthetic": true`）：按每个内置 discriminator 构造一笔交易，覆盖各协议能产生的全部事件类型，
+//! 只能锁定当前的解码结果，不能证明字段取值与链上一致。
+//!
+//! 链上交易放在 `recorded/`（`rpc` feature）：`recorded/signatures.txt` 每行 `<文件名> <名称> <签名>`，
+//! 录制后保存 `getTransaction` 的原始响应，快照在 `golden/recorded/`：
+//!
+//! ```text
+//! SOLANA_RPC_URL=... cargo test --features fixtures,rpc --test snapshots -- --ignored record_captures
+//! ```
+//!
+//! 解析输出有意变化时重新生成快照，并在 diff 中逐项审阅：
+//!
+//! ```text
+//! cargo test --features fixtures,rpc --test snapshots -- --ignored regenerate_goldens
+//! ```
+
+use sol_parser_sdk::fixtures::{check_golden, load_dir, snapshot, RecordedTransaction};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn load() -> Vec<(String, Vec<RecordedTransaction>)> {
+    let files = load_dir(&fixtures_dir().join("transactions")).unwrap();
+    assert!(!files.is_empty(), "no fixtures found");
+    files
+}
+
+fn compare_all(update: bool) {
+    let golden_dir = fixtures_dir().join("golden");
+    let mut failures: Vec<String> = load()
+        .iter()
+        .filter_map(|(name, transactions)| {
+            let actual = snapshot(transactions).unwrap();
+            check_golden(&golden_dir.join(format!("{}.json", name)), &actual, update).err()
+        })
+        .collect();
+    #[cfg(feature = "rpc")]
+    failures.extend(compare_captures(&golden_dir.join("recorded"), update));
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[cfg(feature = "rpc")]
+fn compare_captures(golden_dir: &Path, update: bool) -> Vec<String> {
+    use sol_parser_sdk::fixtures::{snapshot_captures, RpcCapture};
+
+    let captures: Vec<(String, Vec<RpcCapture>)> = load_dir(&fixtures_dir().join("recorded")).unwrap();
+    if update && !captures.is_empty() {
+        std::fs::create_dir_all(golden_dir).unwrap();
+    }
+    captures
+        .iter()
+        .filter_map(|(name, captures)| {
+            let actual = snapshot_captures(captures).unwrap();
+            check_golden(&golden_dir.join(format!("{}.json", name)), &actual, update).err()
+        })
+        .collect()
+}
+
+/// 按 `recorded/signatures.txt` 从 RPC 拉取交易并写入 `recorded/<文件名>.json`（需要网络，手动运行）
+#[cfg(feature = "rpc")]
+#[test]
+#[ignore]
+fn record_captures() {
+    use sol_parser_sdk::fixtures::RpcCapture;
+    use sol_parser_sdk::grpc::rpc::fetch_transaction;
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use std::collections::BTreeMap;
+
+    let dir = fixtures_dir().join("recorded");
+    let list = std::fs::read_to_string(dir.join("signatures.txt")).unwrap();
+    let rpc_url = std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    let client = RpcClient::new(rpc_url);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+    let mut files: BTreeMap<String, Vec<RpcCapture>> = BTreeMap::new();
+    for line in list.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let [file, name, signature] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            panic!("expected `<file> <name> <signature>`, got {:?}", line);
+        };
+        let transaction = runtime.block_on(fetch_transaction(&client, &signature.parse().unwrap())).unwrap();
+        files.entry(file.to_string()).or_default().push(RpcCapture { name: name.to_string(), transaction });
+    }
+    for (file, captures) in files {
+        let text = serde_json::to_string_pretty(&captures).unwrap() + "\n";
+        std::fs::write(dir.join(format!("{}.json", file)), text).unwrap();
+    }
+}
+
+#[test]
+fn test_parser_output_matches_goldens() {
+    compare_all(false);
+}
+
+/// 重新生成快照（有意修改解析输出时手动运行）
+#[test]
+#[ignore]
+fn regenerate_goldens() {
+    compare_all(true);
+}
+
+/// 解析 `recorded/<file>.json` 中名为 `name` 的链上交易；尚未录制时返回 None（测试跳过）
+#[cfg(feature = "rpc")]
+fn recorded_events(file: &str, name: &str) -> Option<Vec<sol_parser_sdk::core::events::DexEvent>> {
+    use sol_parser_sdk::fixtures::RpcCapture;
+
+    let files: Vec<(String, Vec<RpcCapture>)> = load_dir(&fixtures_dir().join("recorded")).unwrap();
+    let capture = files.iter().filter(|(f, _)| f == file).flat_map(|(_, captures)| captures).find(|c| c.name == name);
+    let Some(capture) = capture else {
+        eprintln!("recorded/{}.json has no `{}` capture yet (see recorded/signatures.txt), skipped", file, name);
+        return None;
+    };
+    Some(capture.parse().unwrap())
+}
+
+/// 主网 SwapBaseIn / SwapBaseOut：ray_log 金额与 swap 指令账户合并为一个事件
+#[cfg(feature = "rpc")]
+#[test]
+fn test_recorded_amm_v4_swaps_merge_ray_log() {
+    use sol_parser_sdk::core::events::{DexEvent, EventSource};
+    use solana_sdk::pubkey::Pubkey;
+
+    for name in ["swap_base_in", "swap_base_out"] {
+        let Some(events) = recorded_events("raydium_amm_v4", name) else { continue };
+        let swaps: Vec<_> = events.iter().filter_map(|e| match e { DexEvent::RaydiumAmmV4Swap(e) => Some(e), _ => None }).collect();
+        assert!(!swaps.is_empty(), "{}: no swap in {:?}", name, events);
+        for swap in swaps {
+            assert_eq!(swap.metadata.event_source, EventSource::Merged, "{}", name);
+            assert!(swap.amount_in > 0 && swap.amount_out > 0, "{}: {:?}", name, swap);
+            assert_ne!(swap.amm, Pubkey::default(), "{}", name);
+            assert_ne!(swap.user_source_owner, Pubkey::default(), "{}", name);
+        }
+    }
+}
+
+/// 主网 launchpad 创建代币：PoolCreate 日志与 initialize 指令合并，带上曲线参数
+#[cfg(feature = "rpc")]
+#[test]
+fn test_recorded_bonk_pool_create_has_curve_params() {
+    use sol_parser_sdk::core::events::{DexEvent, EventSource};
+    use solana_sdk::pubkey::Pubkey;
+
+    let Some(events) = recorded_events("bonk", "pool_create") else { return };
+    let Some(create) = events.iter().find_map(|e| match e { DexEvent::BonkPoolCreate(e) => Some(e), _ => None }) else {
+        panic!("no pool creation in {:?}", events);
+    };
+    assert_eq!(create.metadata.event_source, EventSource::Merged);
+    assert!(create.supply > 0 && create.virtual_base > 0 && create.virtual_quote > 0, "{:?}", create);
+    assert_ne!(create.pool_state, Pubkey::default());
+    assert_ne!(create.base_mint, Pubkey::default());
+}
+
+/// 主网毕业交易：PumpFunMigrate 与同一交易的 PumpSwapCreatePool 指向同一个池子
+#[cfg(feature = "rpc")]
+#[test]
+fn test_recorded_pumpfun_migration_links_pool() {
+    use sol_parser_sdk::core::events::DexEvent;
+    use solana_sdk::pubkey::Pubkey;
+
+    let Some(events) = recorded_events("pumpfun", "migrate_to_pumpswap") else { return };
+    let (Some(migrate), Some(create)) = (
+        events.iter().find_map(|e| match e { DexEvent::PumpFunMigrate(e) => Some(e), _ => None }),
+        events.iter().find_map(|e| match e { DexEvent::PumpSwapCreatePool(e) => Some(e), _ => None }),
+    ) else {
+        panic!("expected a migration and its pool creation, got {:?}", events);
+    };
+    assert_ne!(migrate.pool, Pubkey::default());
+    assert_eq!((create.pool_id, create.token_mint), (migrate.pool, migrate.mint));
+    assert!(migrate.pool_base_amount > 0 && migrate.pool_quote_amount > 0, "{:?}", migrate);
+}
+
+#[test]
+fn test_fixtures_cover_every_event_type() {
+    let mut produced = BTreeSet::new();
+    for (_, transactions) in load() {
+        for tx in &transactions {
+            let events = tx.parse().unwrap();
+            assert!(!events.is_empty(), "{} produced no events", tx.name);
+            for event in sol_parser_sdk::fixtures::normalize(&events).as_array().unwrap() {
+                produced.extend(event.as_object().unwrap().keys().cloned());
+            }
+        }
+    }
+
+    // PumpSwapPoolUpdated / PumpSwapFeesClaimed / RaydiumClmmOpenPositionWithTokenExtNft 目前没有解析器产生
+    let expected = [
+        "BonkTrade", "BonkPoolCreate", "BonkMigrateAmm",
+        "PumpFunTrade", "PumpFunCreate", "PumpFunComplete", "PumpFunMigrate", "PumpFunSetParams",
+        "PumpSwapBuy", "PumpSwapSell", "PumpSwapCreatePool", "PumpSwapDeposit", "PumpSwapWithdraw",
+        "RaydiumCpmmSwap", "RaydiumCpmmDeposit", "RaydiumCpmmWithdraw", "RaydiumCpmmInitialize",
+        "RaydiumCpmmPoolStatusUpdated",
+        "RaydiumClmmSwap", "RaydiumClmmCreatePool", "RaydiumClmmOpenPosition", "RaydiumClmmClosePosition",
+        "RaydiumClmmIncreaseLiquidity", "RaydiumClmmDecreaseLiquidity", "RaydiumClmmCollectFee",
+        "RaydiumAmmV4Swap", "RaydiumAmmV4Deposit", "RaydiumAmmV4Withdraw", "RaydiumAmmV4Initialize2",
+        "RaydiumAmmV4WithdrawPnl",
+        "OrcaWhirlpoolSwap", "OrcaWhirlpoolLiquidityIncreased", "OrcaWhirlpoolLiquidityDecreased",
+        "OrcaWhirlpoolPoolInitialized", "OrcaWhirlpoolCollectFees", "OrcaWhirlpoolCollectReward",
+        "MeteoraPoolsSwap", "MeteoraPoolsAddLiquidity", "MeteoraPoolsRemoveLiquidity",
+        "MeteoraPoolsBootstrapLiquidity", "MeteoraPoolsPoolCreated", "MeteoraPoolsSetPoolFees",
+        "MeteoraDammV2Swap", "MeteoraDammV2AddLiquidity", "MeteoraDammV2RemoveLiquidity",
+        "MeteoraDammV2InitializePool", "MeteoraDammV2CreatePosition", "MeteoraDammV2ClosePosition",
+        "MeteoraDammV2ClaimPositionFee", "MeteoraDammV2InitializeReward", "MeteoraDammV2FundReward",
+        "MeteoraDammV2ClaimReward",
+        "MeteoraDlmmSwap", "MeteoraDlmmAddLiquidity", "MeteoraDlmmRemoveLiquidity", "MeteoraDlmmInitializePool",
+        "MeteoraDlmmCreatePosition", "MeteoraDlmmClosePosition", "MeteoraDlmmClaimFee",
+    ];
+    let missing: Vec<&str> = expected.into_iter().filter(|name| !produced.contains(*name)).collect();
+    assert!(missing.is_empty(), "no fixture produces {:?}", missing);
+}
+
+#[test]
+fn test_flat_rows_cover_every_protocol() {
+    use sol_parser_sdk::core::flat::FlatRow;
+    use std::collections::BTreeMap;
+
+    // 表名 -> 产生该表行的协议
+    let mut tables: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for (_, transactions) in load() {
+        for tx in &transactions {
+            for event in tx.parse().unwrap() {
+                let rows = event.to_flat_rows();
+                assert!(rows.len() <= 1, "{}: {:?}", tx.name, rows);
+                let Some(row) = rows.into_iter().next() else { continue };
+                let (table, protocol, event_type) = match row {
+                    FlatRow::Trade(r) => ("trade", r.protocol, r.event_type),
+                    FlatRow::Liquidity(r) => ("liquidity", r.protocol, r.event_type),
+                    FlatRow::PoolCreate(r) => ("pool_create", r.protocol, r.event_type),
+                };
+                assert_eq!(event_type, event.kind());
+                tables.entry(table).or_default().insert(protocol);
+            }
+        }
+    }
+
+    let all: BTreeSet<String> = sol_parser_sdk::grpc::Protocol::ALL.iter().map(|p| format!("{:?}", p)).collect();
+    assert_eq!(tables["trade"], all);
+    assert_eq!(tables["pool_create"], all);
+    // PumpFun / Bonk 没有流动性事件
+    let liquidity: BTreeSet<String> = all.iter().filter(|p| *p != "PumpFun" && *p != "Bonk").cloned().collect();
+    assert_eq!(tables["liquidity"], liquidity);
+}
+
+/// 把一条 gRPC 更新写入临时文件并回放，返回经实时订阅解析路径输出的全部事件
+#[cfg(feature = "grpc")]
+async fn replay_update(
+    name: &str,
+    update: &yellowstone_grpc_proto::prelude::SubscribeUpdate,
+    config: sol_parser_sdk::grpc::ClientConfig,
+) -> Vec<sol_parser_sdk::core::events::DexEvent> {
+    use sol_parser_sdk::grpc::replay::{write_update, ReplaySpeed};
+    use sol_parser_sdk::grpc::YellowstoneGrpc;
+
+    let mut bytes = Vec::new();
+    write_update(&mut bytes, update).unwrap();
+    let path = std::env::temp_dir().join(format!("sol-parser-sdk-{}-{}.bin", name, std::process::id()));
+    std::fs::write(&path, &bytes).unwrap();
+    let grpc = YellowstoneGrpc::new_with_config("http://127.0.0.1:1".to_string(), None, config).unwrap();
+    let mut rx = grpc.replay_from_file(&path, vec![], None, ReplaySpeed::MaxSpeed).await.unwrap();
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+    }
+    std::fs::remove_file(&path).unwrap();
+    events
+}
+
+/// 单指令夹具转为 gRPC 交易更新：指令账户依次为静态账户，程序 ID 追加在最后
+#[cfg(feature = "grpc")]
+fn fixture_update(tx: &RecordedTransaction) -> yellowstone_grpc_proto::prelude::SubscribeUpdate {
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+    use yellowstone_grpc_proto::prelude::{
+        subscribe_update, CompiledInstruction, Message, SubscribeUpdate, SubscribeUpdateTransaction,
+        SubscribeUpdateTransactionInfo, Transaction, TransactionStatusMeta,
+    };
+
+    let mut account_keys: Vec<Pubkey> = tx.accounts.iter().map(|a| a.parse().unwrap()).collect();
+    account_keys.push(tx.program_id.parse().unwrap());
+    let signature: Signature = tx.signature.parse().unwrap();
+    SubscribeUpdate {
+        update_oneof: Some(subscribe_update::UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            slot: tx.slot,
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                index: tx.tx_index,
+                transaction: Some(Transaction {
+                    signatures: vec![signature.as_ref().to_vec()],
+                    message: Some(Message {
+                        account_keys: account_keys.iter().map(|a| a.to_bytes().to_vec()).collect(),
+                        instructions: vec![CompiledInstruction {
+                            program_id_index: tx.accounts.len() as u32,
+                            accounts: (0..tx.accounts.len() as u8).collect(),
+                            data: hex::decode(&tx.instruction_data).unwrap(),
+                        }],
+                        ..Default::default()
+                    }),
+                }),
+                meta: Some(TransactionStatusMeta { log_messages: tx.logs.clone(), ..Default::default() }),
+                ..Default::default()
+            }),
+        })),
+        ..Default::default()
+    }
+}
+
+/// 同时有指令和日志的夹具：实时订阅路径与 `parse_transaction_events` 一样合并两侧，输出相同的事件
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_grpc_path_merges_like_parse_transaction_events() {
+    use sol_parser_sdk::core::events::EventSource;
+    use sol_parser_sdk::fixtures::normalize;
+    use sol_parser_sdk::grpc::ClientConfig;
+
+    let cases = [
+        // Bonk 用户意图（最少输出 / 最多输入）只在指令里
+        ("bonk", "buy_near_graduation"),
+        ("bonk", "sell_near_graduation"),
+        // CLMM 头寸：区间取自指令，实际数量取自日志
+        ("raydium_clmm", "lifecycle_open_position"),
+        ("raydium_clmm", "lifecycle_increase_liquidity"),
+        ("raydium_clmm", "lifecycle_decrease_liquidity"),
+    ];
+    let fixtures = load();
+    for (protocol, name) in cases {
+        let (_, transactions) = fixtures.iter().find(|(p, _)| p == protocol).unwrap();
+        let tx = transactions.iter().find(|tx| tx.name == name).unwrap();
+        let expected = tx.parse().unwrap();
+        let actual = replay_update(name, &fixture_update(tx), ClientConfig::default()).await;
+        assert!(
+            actual.iter().all(|e| e.metadata().unwrap().event_source == EventSource::Merged),
+            "{}: {:?}",
+            name,
+            actual
+        );
+        assert_eq!(without_timing(normalize(&actual)), without_timing(normalize(&expected)), "{}", name);
+    }
+
+    // 回放没有区块时间，序号由订阅侧分配：这两项不参与比较
+    fn without_timing(mut events: serde_json::Value) -> serde_json::Value {
+        for event in events.as_array_mut().unwrap() {
+            for body in event.as_object_mut().unwrap().values_mut() {
+                let metadata = body["metadata"].as_object_mut().unwrap();
+                metadata.remove("block_time_us");
+                metadata.remove("sequence");
+            }
+        }
+        events
+    }
+}
+
+/// 两笔合成交易的指令拼成一笔 gRPC 交易，经回放走与实时订阅相同的解析路径（含按指令取账户）
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_cross_venue_trade_from_fixtures() {
+    use sol_parser_sdk::core::events::{DexEvent, TradeSide, WSOL_MINT};
+    use sol_parser_sdk::grpc::{ClientConfig, Protocol};
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+    use yellowstone_grpc_proto::prelude::{
+        subscribe_update, CompiledInstruction, Message, SubscribeUpdate, SubscribeUpdateTransaction,
+        SubscribeUpdateTransactionInfo, Transaction, TransactionStatusMeta,
+    };
+
+    let fixtures = load();
+    let find = |protocol: &str, name: &str| {
+        let (_, transactions) = fixtures.iter().find(|(p, _)| p == protocol).unwrap();
+        transactions.iter().find(|tx| tx.name == name).unwrap().clone()
+    };
+    let buy_tx = find("pumpswap", "ix_buy");
+    let cpmm_tx = find("raydium_cpmm", "ix_swap_base_in");
+    let Some(DexEvent::PumpSwapBuy(buy)) = buy_tx.parse().unwrap().into_iter().next() else {
+        panic!("expected a PumpSwap buy");
+    };
+    let Some(DexEvent::RaydiumCpmmSwap(sell)) = cpmm_tx.parse().unwrap().into_iter().next() else {
+        panic!("expected a CPMM swap");
+    };
+
+    // 外层指令 0 在 PumpSwap 买入，外层指令 1 在 Raydium CPMM 把同一代币卖回 SOL：
+    // CPMM 的输入 mint 指向 PumpSwap 指令中的代币账户，输出 mint（WSOL）通过 ALT 加载
+    let pubkeys = |accounts: &[String]| accounts.iter().map(|a| a.parse().unwrap()).collect::<Vec<Pubkey>>();
+    let mut account_keys = pubkeys(&buy_tx.accounts);
+    let buy_indices: Vec<u8> = (0..account_keys.len() as u8).collect();
+    let mint_index = account_keys.iter().position(|a| *a == buy.token_mint).expect("token mint among the buy accounts") as u8;
+    let mut cpmm_indices = Vec::new();
+    for (i, account) in pubkeys(&cpmm_tx.accounts).into_iter().enumerate() {
+        cpmm_indices.push(match i {
+            10 => mint_index,
+            11 => u8::MAX,
+            _ => {
+                account_keys.push(account);
+                account_keys.len() as u8 - 1
+            }
+        });
+    }
+    account_keys.extend([buy_tx.program_id.parse::<Pubkey>().unwrap(), cpmm_tx.program_id.parse().unwrap()]);
+    let wsol_index = account_keys.len() as u8;
+    for index in cpmm_indices.iter_mut().filter(|index| **index == u8::MAX) {
+        *index = wsol_index;
+    }
+    let instruction = |program_id_index: usize, accounts: Vec<u8>, data: &str| CompiledInstruction {
+        program_id_index: program_id_index as u32,
+        accounts,
+        data: hex::decode(data).unwrap(),
+    };
+    let signature: Signature = buy_tx.signature.parse().unwrap();
+    let update = SubscribeUpdate {
+        update_oneof: Some(subscribe_update::UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            slot: buy_tx.slot,
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                index: buy_tx.tx_index,
+                transaction: Some(Transaction {
+                    signatures: vec![signature.as_ref().to_vec()],
+                    message: Some(Message {
+                        account_keys: account_keys.iter().map(|a| a.to_bytes().to_vec()).collect(),
+                        instructions: vec![
+                            instruction(account_keys.len() - 2, buy_indices, &buy_tx.instruction_data),
+                            instruction(account_keys.len() - 1, cpmm_indices, &cpmm_tx.instruction_data),
+                        ],
+                        ..Default::default()
+                    }),
+                }),
+                // 没有日志：两笔成交都由指令解析
+                meta: Some(TransactionStatusMeta {
+                    loaded_readonly_addresses: vec![WSOL_MINT.to_bytes().to_vec()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        })),
+        ..Default::default()
+    };
+    let replay = |detect: bool| {
+        replay_update("cross-venue", &update, ClientConfig { detect_cross_venue_trades: detect, ..ClientConfig::default() })
+    };
+    let events = replay(true).await;
+    let without_detection = replay(false).await;
+
+    assert_eq!(events.len(), 3, "the legs stay in place: {:?}", events);
+    assert_eq!(without_detection.len(), 2);
+    let DexEvent::CrossVenueTrade(arb) = &events[2] else { panic!("expected a CrossVenueTrade, got {:?}", events[2]) };
+    assert_eq!(arb.mint, buy.token_mint);
+    assert_eq!(arb.metadata.signature, signature);
+    // 合成指令参数数值很大，按饱和运算计算净额
+    assert_eq!(arb.net_sol, 0i64.saturating_sub_unsigned(buy.sol_amount).saturating_add_unsigned(sell.output_amount));
+    let legs: Vec<_> = arb.legs.iter().map(|leg| (leg.protocol, leg.side, leg.token_amount, leg.sol_amount, leg.outer_index)).collect();
+    assert_eq!(
+        legs,
+        vec![
+            (Protocol::PumpSwap, TradeSide::Buy, buy.token_amount, buy.sol_amount, 0),
+            (Protocol::RaydiumCpmm, TradeSide::Sell, sell.input_amount, sell.output_amount, 1),
+        ]
+    );
+}
+
+#[test]
+fn test_merged_events_have_consistent_timestamps() {
+    for (_, transactions) in load() {
+        for tx in &transactions {
+            for event in tx.parse().unwrap() {
+                let metadata = event.metadata().unwrap();
+                assert!(metadata.grpc_recv_us > 0, "{}: {:?}", tx.name, metadata);
+                assert!(metadata.handle_us >= metadata.grpc_recv_us, "{}: {:?}", tx.name, metadata);
+            }
+        }
+    }
+}
+
+/// 直接解析的关联由 merger 的单元测试覆盖，这里只验证 gRPC 解析路径（`parse_transaction_info`）同样关联
+#[cfg(feature = "grpc")]
+#[tokio::test]
+async fn test_pumpfun_migration_links_pumpswap_pool() {
+    use sol_parser_sdk::core::events::DexEvent;
+    use sol_parser_sdk::grpc::ClientConfig;
+
+    let (_, transactions) = load().into_iter().find(|(protocol, _)| protocol == "pumpfun").unwrap();
+    let tx = transactions.iter().find(|tx| tx.name == "migrate_to_pumpswap").expect("migration fixture");
+    let update = fixture_update(tx);
+    let events = replay_update("migration", &update, ClientConfig::default()).await;
+
+    let (Some(migrate), Some(create)) = (
+        events.iter().find_map(|e| match e { DexEvent::PumpFunMigrate(e) => Some(e), _ => None }),
+        events.iter().find_map(|e| match e { DexEvent::PumpSwapCreatePool(e) => Some(e), _ => None }),
+    ) else {
+        panic!("expected a migration and its pool creation, got {:?}", events);
+    };
+    assert_eq!(migrate.mint.to_string(), "4yCyRX9rH1pGs4PgbeSiJSQNGQbjpTt3bWxWaq5g8NXL");
+    assert_eq!(migrate.pool.to_string(), "7QcWtKnmKmvbRhojZGwVKzceC4quCPaPmEQfHHZyS2xd");
+    assert_eq!((create.pool_id, create.token_mint), (migrate.pool, migrate.mint));
+    assert_eq!((migrate.pool_base_amount, migrate.pool_quote_amount), (206_900_000_000_000, 84_990_359_370));
+}
+
+#[test]
+fn test_bonk_trades_near_graduation() {
+    use sol_parser_sdk::core::events::DexEvent;
+
+    let (_, transactions) = load().into_iter().find(|(protocol, _)| protocol == "bonk").unwrap();
+    let trade = |name: &str| {
+        let tx = transactions.iter().find(|tx| tx.name == name).expect("bonk trade fixture");
+        match tx.parse().unwrap().as_slice() {
+            [DexEvent::BonkTrade(e)] => e.clone(),
+            events => panic!("expected a single merged trade, got {:?}", events),
+        }
+    };
+
+    // 买入 exact-in：成交数量和费用取自日志，最少输出取自指令
+    let buy = trade("buy_near_graduation");
+    assert!(buy.is_buy && buy.exact_in);
+    assert_eq!((buy.amount_in, buy.amount_out), (2_000_000_000, 21_500_000_000_000));
+    assert_eq!((buy.minimum_amount_out, buy.maximum_amount_in), (Some(20_000_000_000_000), None));
+    assert_eq!((buy.protocol_fee, buy.platform_fee, buy.share_fee), (Some(5_000_000), Some(20_000_000), Some(0)));
+    assert_eq!((buy.real_quote_after, buy.pool_status), (Some(84_000_000_000), Some(0)));
+    assert_eq!(buy.curve_progress_bps, Some(9_882));
+    assert_ne!(buy.user, Default::default());
+
+    // 卖出 exact-out：最多输入取自指令
+    let sell = trade("sell_near_graduation");
+    assert!(!sell.is_buy && !sell.exact_in);
+    assert_eq!((sell.amount_in, sell.amount_out), (27_000_000_000_000, 1_000_000_000));
+    assert_eq!((sell.minimum_amount_out, sell.maximum_amount_in), (None, Some(30_000_000_000_000)));
+    assert_eq!(sell.share_fee, Some(1_000_000));
+    assert_eq!(sell.curve_progress_bps, Some(9_823));
+}
+
+#[test]
+fn test_raydium_clmm_position_lifecycle() {
+    use sol_parser_sdk::core::events::{raydium_clmm_position_key, DexEvent, EventSource};
+
+    let (_, transactions) = load().into_iter().find(|(protocol, _)| protocol == "raydium_clmm").unwrap();
+    let parse = |name: &str| {
+        let tx = transactions.iter().find(|tx| tx.name == name).expect("lifecycle fixture");
+        match tx.parse().unwrap().as_slice() {
+            [event] => event.clone(),
+            events => panic!("{}: expected a single event, got {:?}", name, events),
+        }
+    };
+
+    // 开仓：区间与流动性取自指令，实际存入数量取自 CreatePersonalPositionEvent
+    let DexEvent::RaydiumClmmOpenPosition(open) = parse("lifecycle_open_position") else { panic!("expected an open") };
+    assert_eq!(open.metadata.event_source, EventSource::Merged);
+    assert_eq!(open.position_key, raydium_clmm_position_key(&open.position_nft_mint));
+    assert_eq!((open.tick_lower_index, open.tick_upper_index, open.liquidity), (-120, 240, 1_000_000));
+    assert_eq!((open.amount0, open.amount1), (5_000, 7_000));
+
+    // open_position_v2 与 open_position_with_token22_nft 参数相同；后者没有 metadata 账户，池与头寸账户前移一位
+    for name in ["lifecycle_open_position_v2", "lifecycle_open_position_with_token22_nft"] {
+        let DexEvent::RaydiumClmmOpenPosition(e) = parse(name) else { panic!("{}: expected an open", name) };
+        assert_eq!(e.metadata.event_source, EventSource::Merged, "{}", name);
+        assert_eq!((e.pool, e.user), (open.pool, open.user), "{}", name);
+        assert_eq!(e.position_key, raydium_clmm_position_key(&e.position_nft_mint), "{}", name);
+        assert_eq!((e.tick_lower_index, e.tick_upper_index, e.liquidity), (-120, 240, 1_000_000), "{}", name);
+        assert_eq!((e.amount0, e.amount1), (5_000, 7_000), "{}", name);
+    }
+
+    // 增减流动性：按头寸账户与日志配对，区间取自 LiquidityChangeEvent
+    let DexEvent::RaydiumClmmIncreaseLiquidity(increase) = parse("lifecycle_increase_liquidity") else {
+        panic!("expected an increase")
+    };
+    assert_eq!(increase.metadata.event_source, EventSource::Merged);
+    assert_eq!((increase.pool, increase.user), (open.pool, open.user));
+    assert_eq!((increase.position_nft_mint, increase.position_key), (open.position_nft_mint, open.position_key));
+    assert_eq!((increase.tick_lower_index, increase.tick_upper_index), (-120, 240));
+    assert_eq!((increase.liquidity, increase.amount0, increase.amount1), (500_000, 2_500, 3_500));
+    assert_eq!((increase.amount0_max, increase.amount1_max), (3_000, 4_000));
+
+    let DexEvent::RaydiumClmmDecreaseLiquidity(decrease) = parse("lifecycle_decrease_liquidity") else {
+        panic!("expected a decrease")
+    };
+    assert_eq!(decrease.metadata.event_source, EventSource::Merged);
+    assert_eq!((decrease.pool, decrease.position_key), (open.pool, open.position_key));
+    assert_eq!((decrease.tick_lower_index, decrease.tick_upper_index), (-120, 240));
+    assert_eq!((decrease.liquidity, decrease.amount0, decrease.amount1), (1_500_000, 7_400, 10_400));
+    assert_eq!((decrease.amount0_min, decrease.amount1_min), (7_000, 10_000));
+
+    // 平仓只有指令
+    let DexEvent::RaydiumClmmClosePosition(close) = parse("lifecycle_close_position") else { panic!("expected a close") };
+    assert_eq!((close.position_nft_mint, close.position_key, close.user), (open.position_nft_mint, open.position_key, open.user));
+}